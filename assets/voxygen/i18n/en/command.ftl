@@ -46,6 +46,7 @@ command-dismount-desc = Dismount if you are riding, or dismount anything riding
 command-dropall-desc = Drops all your items on the ground
 command-dummy-desc = Spawns a training dummy
 command-explosion-desc = Explodes the ground around you
+command-export_character-desc = Write a character to a file in the character_exports folder of the server, as JSON if the name ends with .json
 command-faction-desc = Send messages to your faction
 command-give_item-desc = Give yourself some items. For an example or to auto complete use Tab.
 command-gizmos-desc = Manage gizmo subscriptions.
@@ -58,6 +59,7 @@ command-group_kick-desc = Remove a player from a group
 command-group_leave-desc = Leave the current group
 command-group_promote-desc = Promote a player to group leader
 command-health-desc = Set your current health
+command-import_character-desc = Create a new character for a player from a file in the character_exports folder of the server
 command-into_npc-desc = Convert yourself to an NPC. Be careful!
 command-join_faction-desc = Join/leave the specified faction
 command-jump-desc = Offset your current position
@@ -237,6 +239,11 @@ command-lantern-adjusted-strength = You adjusted flame strength.
 command-lantern-adjusted-strength-color = You adjusted flame strength and color.
 command-explosion-power-too-high = Explosion power mustn't be more than { $power }
 command-explosion-power-too-low = Explosion power must be more than { $power }
+command-character_file-invalid = '{ $file }' is not a file name, characters can only be exported to and imported from the character_exports folder
+command-export_character-success = Exported { $alias } to { $file }
+command-export_character-failed = Failed to export character { $id }: { $error }
+command-import_character-success = Imported { $alias } as character { $id }
+command-import_character-failed = Failed to import { $file }: { $error }
 # Note: Do not translate "confirm" here
 command-disconnectall-confirm = Please run the command again with the second argument of "confirm" to confirm that
   you really want to disconnect all players from the server
@@ -9,6 +9,9 @@ dialogue-question-where-ship-going =
     .a1 = Where are we going?
     .a2 = Where is this ship going?
     .a3 = Where are you taking us?
+dialogue-question-caravan-cargo =
+    .a0 = What are you carrying?
+    .a1 = Where are you taking those goods?
 dialogue-question-self =
     .a0 = Who are you?
     .a1 = Tell me about yourself
@@ -46,6 +49,13 @@ dialogue-question-quest-escort-where = Where are we going?
 dialogue-question-quest-slay-where = Where is the { TAIL($body) }?
 dialogue-question-quest-slay-claim = The monster has been slain!
 
+dialogue-question-quest-deliver-hand_in = I've got a parcel for you.
+dialogue-question-quest-gather-hand_in = Here are the { $amount } { $item } you asked for.
+dialogue-question-quest-clear_site-where = Where is { $place }?
+dialogue-question-quest-clear_site-claim = { $place } has been cleared!
+dialogue-question-quest-bounty-where = Where was { $name } last seen?
+dialogue-question-quest-bounty-claim = { $name } won't trouble you anymore.
+
 dialogue-play_game = Let's play a game
 dialogue-game-what_game =
     .a0 = What game do you want to play?
@@ -42,6 +42,7 @@ gameinput-map = Map
 gameinput-inventory = Inventory
 gameinput-trade = Trade
 gameinput-social = Social
+gameinput-questlog = Quest Log
 gameinput-sit = Sit
 gameinput-crawl = Crawl
 gameinput-diary = Diary
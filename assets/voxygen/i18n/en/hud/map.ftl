@@ -29,6 +29,8 @@ hud-map-character = Character
 hud-map-character-label = { $name }'s last known location
 hud-map-creature-label = Last known location of { $body }
 hud-map-escort-label = Escort { $name } to { $place }.
+hud-map-deliver-label = Deliver a parcel to { $name } in { $place }.
+hud-map-clear_site-label = Clear { $place }.
 hud-map-difficulty_dungeon =
     Dungeon
 
@@ -9,3 +9,27 @@ hud-quest-decline = Decline
 hud-dialogue = Dialogue
 
 hud-dialogue-ack = Press [{ $key }] to acknowledge
+
+hud-quest-log-title = Quest Log
+hud-quest-log-empty = You haven't accepted any quests yet.
+hud-quest-track = Track
+hud-quest-untrack = Untrack
+hud-quest-abandon = Abandon
+hud-quest-status-accepted = Accepted
+hud-quest-status-underway = Underway
+hud-quest-status-achieved = Return for your reward
+hud-quest-status-completed = Completed
+hud-quest-status-failed = Failed
+hud-quest-giver = Given by { $name }
+hud-quest-site = In { $place }
+hud-quest-deadline = { $minutes ->
+    [one] 1 minute left
+   *[other] { $minutes } minutes left
+}
+hud-quest-unknown = someone
+hud-quest-objective-escort = Escort { $name } to { $place }.
+hud-quest-objective-slay = Slay { $body }.
+hud-quest-objective-deliver = Deliver { $amount } { $item } to { $name }.
+hud-quest-objective-gather = Gather { $amount } { $item }.
+hud-quest-objective-clear_site = Clear { $place } of its hostile creatures.
+hud-quest-objective-bounty = Hunt down { $name }.
@@ -220,6 +220,9 @@ object-v-coin = Coins
 object-collar = Collar
     .desc = Tames neutral wild animals within 5 blocks.
 
+object-parcel = Parcel
+    .desc = Tightly wrapped and addressed to someone in another town.
+
 object-training_dummy = Training Dummy
     .desc = His name is William. Fire at will.
 
@@ -57,6 +57,9 @@ name-custom-village-alchemist =
 name-custom-village-merchant =
     .fem = Merchant
     .masc = Merchant
+name-custom-village-caravan =
+    .fem = Caravaneer
+    .masc = Caravaneer
 ## Travelers
 name-custom-world-traveler0 =
     .fem = Greenhorn Traveler
@@ -55,3 +55,4 @@ noun-role-pirate = a pirate
 noun-role-cultist = a cultist
 noun-role-herbalist = a herbalist
 noun-role-captain = a captain
+noun-role-caravan = a caravaneer
@@ -316,6 +316,17 @@ npc-speech-cultist_low_health_fleeing =
 npc-speech-moving_on =
     .a0 = I've spent enough time here, onward to { $site }!
     .a1 = I hope I can find an airship traveling to { $site }.
+npc-speech-caravan-setting_off =
+    .a0 = The cart's loaded, off to { $site }!
+    .a1 = They'll pay good coin for this in { $site }.
+    .a2 = Time to take these goods to { $site }.
+npc-speech-caravan-arrived =
+    .a0 = Fresh goods from { $site }!
+    .a1 = Made it! Now to unload all this.
+    .a2 = Another safe delivery from { $site }.
+npc-speech-caravan-cargo =
+    .a0 = I'm hauling goods to { $site }, they fetch a better price there.
+    .a1 = Just some wares for the market in { $site }.
 npc-speech-migrating =
     .a0 = I'm no longer happy living here. Time to migrate to { $site }.
     .a1 = Time to move to { $site }, I've had it with this place.
@@ -502,6 +513,58 @@ npc-response-quest-slay-thanks =
     .a1 = You have my gratitude... and my money!
     .a2 = You've done us a huge favour, many thanks.
 
+npc-response-quest-deliver-ask =
+    .a0 = Could you bring this parcel to { $name } in { $dst }? They'll pay you { $coins } coins if it arrives within { $mins } minutes.
+    .a1 = I need a parcel delivered to { $name } in { $dst } within { $mins } minutes. { $coins } coins are waiting for you there!
+npc-response-quest-deliver-start =
+    .a0 = Here's the parcel. I've marked the destination on your map.
+    .a1 = Take good care of it! You'll find the way on your map.
+npc-response-quest-deliver-thanks =
+    .a0 = Ah, I've been waiting for this! Thank you.
+    .a1 = A parcel for me? How kind of you to bring it all this way.
+
+npc-response-quest-gather-ask =
+    .a0 = I'm running low on supplies. Could you bring me { $amount } { $item }? I'll pay { $coins } coins if you're back within { $mins } minutes.
+    .a1 = I need { $amount } { $item } within { $mins } minutes. There are plenty around here, and { $coins } coins in it for you!
+npc-response-quest-gather-start =
+    .a0 = Great! Come back to me once you've got them.
+    .a1 = Thank you! You'll find me here when you're done.
+npc-response-quest-gather-thanks =
+    .a0 = That's exactly what I needed, thanks!
+    .a1 = Perfect, that'll keep me going for a while.
+
+npc-response-quest-clear_site-ask =
+    .a0 = The creatures of { $site } keep raiding us. Drive them out and I'll pay you { $coins } coins!
+    .a1 = We won't be safe while { $site } stands. Clear it out for { $coins } coins?
+npc-response-quest-clear_site-start =
+    .a0 = I've marked the camp on your map. Come back once nothing is left of them.
+    .a1 = You'll find the camp on your map. Be careful, they won't go down without a fight!
+npc-response-quest-clear_site-where = I've marked { $place } on your map.
+npc-response-quest-clear_site-thanks =
+    .a0 = Finally, we can sleep at night again!
+    .a1 = You've done us all a great service.
+
+npc-response-quest-bounty-ask =
+    .murder = { $name } murdered one of our own! Bring them to justice and { $coins } coins are yours.
+    .theft = { $name } has been stealing from us! Teach them a lesson and I'll give you { $coins } coins.
+npc-response-quest-bounty-start =
+    .a0 = I've marked where they were last seen on your map.
+    .a1 = They were last seen at the place I've marked on your map. Don't let them get away!
+npc-response-quest-bounty-thanks =
+    .a0 = Justice has been served. Thank you.
+    .a1 = They had it coming. Thank you for your help.
+
+npc-item_resource =
+    .coin = coins
+    .parcel = parcels
+    .apple = apples
+    .carrot = carrots
+    .mushroom = mushrooms
+    .flax = wild flax
+    .stones = stones
+    .wood = logs of wood
+    .iron_ore = iron ore
+
 npc-response-like_you =
     .a0 = I like you!
     .a1 = You seem like a good friend.
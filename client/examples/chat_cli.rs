@@ -53,6 +53,7 @@ fn main() {
     let addr = ConnectionArgs::Tcp {
         prefer_ipv6: false,
         hostname: server_addr,
+        server_key: None,
     };
 
     // Create a client.
@@ -1,3 +1,4 @@
+use network::StaticPublicKey;
 use std::net::SocketAddr;
 use tokio::net::lookup_host;
 use tracing::trace;
@@ -14,6 +15,9 @@ pub enum ConnectionArgs {
     Tcp {
         hostname: String,
         prefer_ipv6: bool,
+        /// Encrypts the connection if set, the server has to authenticate with
+        /// this key. Plain TCP otherwise.
+        server_key: Option<StaticPublicKey>,
     },
     /// SRV lookup
     ///
@@ -25,6 +29,8 @@ pub enum ConnectionArgs {
         prefer_ipv6: bool,
         validate_tls: bool,
         use_quic: bool,
+        /// See [`ConnectionArgs::Tcp`]
+        server_key: Option<StaticPublicKey>,
     },
     Mpsc(u64),
 }
@@ -33,6 +39,16 @@ impl ConnectionArgs {
     const DEFAULT_PORT: u16 = 14004;
 }
 
+/// Encrypts the TCP connection if the key of the server is known
+pub(crate) fn tcp_connect_addr(
+    server_key: Option<StaticPublicKey>,
+) -> impl Fn(SocketAddr) -> network::ConnectAddr {
+    move |addr| match server_key {
+        Some(server_key) => network::ConnectAddr::TcpEncrypted(addr, server_key),
+        None => network::ConnectAddr::Tcp(addr),
+    }
+}
+
 /// Parse ip address or resolves hostname.
 /// Note: If you use an ipv6 address, the number after the last
 /// colon will be used as the port unless you use [] around the address.
@@ -63,6 +63,7 @@ pub fn make_client(
     let addr = ConnectionArgs::Tcp {
         prefer_ipv6: false,
         hostname: server.to_owned(),
+        server_key: None,
     };
     runtime
         .block_on(Client::new(
@@ -77,6 +78,7 @@ pub fn make_client(
             |_| {},
             Default::default(),
             ClientType::Game,
+            Default::default(),
         ))
         .ok()
 }
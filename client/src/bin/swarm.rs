@@ -113,6 +113,7 @@ fn run_client(
         let addr = ConnectionArgs::Tcp {
             prefer_ipv6: false,
             hostname: "localhost".into(),
+            server_key: None,
         };
         let runtime_clone = Arc::clone(&runtime);
         // NOTE: use a no-auth server
@@ -8,6 +8,7 @@ pub mod error;
 pub use crate::error::Error;
 pub use authc::AuthClientError;
 pub use common_net::msg::ServerInfo;
+pub use network::{NetworkConditions, StaticPublicKey};
 pub use specs::{
     Builder, DispatcherBuilder, Entity as EcsEntity, Join, LendJoin, ReadStorage, World, WorldExt,
 };
@@ -58,13 +59,13 @@ use common_base::{prof_span, span};
 use common_i18n::Content;
 use common_net::{
     msg::{
-        ChatTypeContext, ClientGeneral, ClientMsg, ClientRegister, DisconnectReason, InviteAnswer,
-        Notification, PingMsg, PlayerInfo, PlayerListUpdate, RegisterError, ServerGeneral,
-        ServerInit, ServerRegisterAnswer,
+        ChatTypeContext, ClientGeneral, ClientMsg, ClientRegister, DisconnectReason, EcsCompPacket,
+        InviteAnswer, Notification, PingMsg, PlayerInfo, PlayerListUpdate, RegisterError,
+        ServerGeneral, ServerInit, ServerRegisterAnswer,
         server::ServerDescription,
         world_msg::{EconomyInfo, PoiInfo, SiteId},
     },
-    sync::WorldSyncExt,
+    sync::{PhysicsBaseline, WorldSyncExt},
 };
 
 pub use common_net::msg::ClientType;
@@ -96,6 +97,10 @@ use vek::*;
 pub const MAX_SELECTABLE_VIEW_DISTANCE: u32 = 65;
 
 const PING_ROLLING_AVERAGE_SECS: usize = 10;
+/// How long we try to reconnect after the connection to the server broke, same
+/// as the default `session_resume_timeout` of the server. Only connections
+/// encrypted with a known server key can be resumed.
+const SESSION_RESUME_TIMEOUT: Duration = Duration::from_secs(15);
 
 /// Client frontend events.
 ///
@@ -302,6 +307,7 @@ pub struct Client {
     lod_last_requested: Option<Instant>,
     lod_pos_fallback: Option<Vec2<f32>>,
     force_update_counter: u64,
+    physics_baseline: PhysicsBaseline,
 
     role: Option<AdminRole>,
     max_group_size: u32,
@@ -315,6 +321,10 @@ pub struct Client {
     // The pending trade the client is involved in, and it's id
     pending_trade: Option<(TradeId, PendingTrade, Option<SitePrices>)>,
     waypoint: Option<String>,
+    /// Quests of the character, in the order they were accepted
+    quests: Vec<rtsim::QuestInfo>,
+    /// Quests whose objective is shown on the map
+    tracked_quests: HashSet<rtsim::QuestId>,
 
     network: Option<Network>,
     participant: Option<Participant>,
@@ -460,9 +470,12 @@ impl Client {
         add_foreign_systems: impl Fn(&mut DispatcherBuilder) + Send + 'static,
         #[cfg_attr(not(feature = "plugins"), expect(unused_variables))] config_dir: PathBuf,
         client_type: ClientType,
+        network_conditions: NetworkConditions,
     ) -> Result<Self, Error> {
         let _ = rustls::crypto::ring::default_provider().install_default(); // needs to be initialized before usage
         let network = Network::new(Pid::new(), &runtime);
+        network.set_resume_timeout(SESSION_RESUME_TIMEOUT);
+        network.set_network_conditions(network_conditions);
 
         init_stage_update(ClientInitStage::ConnectionEstablish);
 
@@ -472,6 +485,7 @@ impl Client {
                 prefer_ipv6,
                 validate_tls,
                 use_quic,
+                server_key,
             } => {
                 // Try to create a resolver backed by /etc/resolv.conf or the Windows Registry
                 // first. If that fails, create a resolver being hard-coded to
@@ -543,7 +557,7 @@ impl Client {
                                     &hostname,
                                     port,
                                     prefer_ipv6,
-                                    ConnectAddr::Tcp,
+                                    addr::tcp_connect_addr(server_key),
                                 )
                                 .await
                             },
@@ -569,7 +583,7 @@ impl Client {
                                 &hostname,
                                 None,
                                 prefer_ipv6,
-                                ConnectAddr::Tcp,
+                                addr::tcp_connect_addr(server_key),
                             )
                             .await
                             {
@@ -584,8 +598,16 @@ impl Client {
             ConnectionArgs::Tcp {
                 hostname,
                 prefer_ipv6,
+                server_key,
             } => {
-                addr::try_connect(&network, &hostname, None, prefer_ipv6, ConnectAddr::Tcp).await?
+                addr::try_connect(
+                    &network,
+                    &hostname,
+                    None,
+                    prefer_ipv6,
+                    addr::tcp_connect_addr(server_key),
+                )
+                .await?
             },
             ConnectionArgs::Quic {
                 hostname,
@@ -1064,6 +1086,7 @@ impl Client {
             lod_pos_fallback: None,
 
             force_update_counter: 0,
+            physics_baseline: PhysicsBaseline::default(),
 
             role,
             max_group_size,
@@ -1073,6 +1096,8 @@ impl Client {
             pending_invites: HashSet::new(),
             pending_trade: None,
             waypoint: None,
+            quests: Vec::new(),
+            tracked_quests: HashSet::new(),
 
             network: Some(network),
             participant: Some(participant),
@@ -1223,7 +1248,8 @@ impl Client {
                     | ClientGeneral::UpdateMapMarker(_)
                     | ClientGeneral::SpectatePosition(_)
                     | ClientGeneral::SpectateEntity(_)
-                    | ClientGeneral::SetBattleMode(_) => {
+                    | ClientGeneral::SetBattleMode(_)
+                    | ClientGeneral::AbandonQuest(_) => {
                         #[cfg(feature = "tracy")]
                         {
                             ingame = 1.0;
@@ -1243,6 +1269,7 @@ impl Client {
                     ClientGeneral::ChatMsg(_)
                     | ClientGeneral::Command(_, _)
                     | ClientGeneral::Terminate
+                    | ClientGeneral::AckPhysicsSync(_)
                     | ClientGeneral::RequestPlugins(_) => &mut self.general_stream,
                 };
                 #[cfg(feature = "tracy")]
@@ -1755,6 +1782,40 @@ impl Client {
             .values()
             .map(|s| &s.marker)
             .chain(self.extra_markers.iter())
+            .chain(
+                self.tracked_quests()
+                    .filter_map(|quest| quest.marker.as_ref()),
+            )
+    }
+
+    /// Quests of the character, in the order they were accepted
+    pub fn quests(&self) -> &[rtsim::QuestInfo] { &self.quests }
+
+    pub fn quest(&self, id: rtsim::QuestId) -> Option<&rtsim::QuestInfo> {
+        self.quests.iter().find(|quest| quest.id == id)
+    }
+
+    pub fn tracked_quests(&self) -> impl Iterator<Item = &rtsim::QuestInfo> {
+        self.quests
+            .iter()
+            .filter(|quest| self.tracked_quests.contains(&quest.id))
+    }
+
+    pub fn is_quest_tracked(&self, id: rtsim::QuestId) -> bool { self.tracked_quests.contains(&id) }
+
+    /// Show or hide the objective of a quest on the map. Quests are tracked
+    /// when they're accepted.
+    pub fn track_quest(&mut self, id: rtsim::QuestId, track: bool) {
+        if track && self.quest(id).is_some_and(|quest| quest.status.is_active()) {
+            self.tracked_quests.insert(id);
+        } else {
+            self.tracked_quests.remove(&id);
+        }
+    }
+
+    /// Give up on a quest, it fails once whoever gave it notices.
+    pub fn abandon_quest(&mut self, id: rtsim::QuestId) {
+        self.send_msg(ClientGeneral::AbandonQuest(id));
     }
 
     pub fn possible_starting_sites(&self) -> &[SiteId] { &self.possible_starting_sites }
@@ -2818,6 +2879,14 @@ impl Client {
                     .ecs_mut()
                     .apply_comp_sync_package(comp_sync_package);
             },
+            ServerGeneral::PhysicsSync(physics_sync_package) => {
+                let seq = physics_sync_package.seq();
+                let states = self.physics_baseline.decode(physics_sync_package);
+                self.state
+                    .ecs_mut()
+                    .apply_physics_states::<EcsCompPacket>(states);
+                self.send_msg(ClientGeneral::AckPhysicsSync(seq));
+            },
             ServerGeneral::CreateEntity(entity_package) => {
                 self.state.ecs_mut().apply_entity_package(entity_package);
             },
@@ -3010,6 +3079,31 @@ impl Client {
             ServerGeneral::Dialogue(sender, dialogue) => {
                 frontend_events.push(Event::Dialogue(sender, dialogue));
             },
+            ServerGeneral::QuestLog(quests) => {
+                self.tracked_quests = quests
+                    .iter()
+                    .filter(|quest| quest.status.is_active())
+                    .map(|quest| quest.id)
+                    .collect();
+                self.quests = quests;
+            },
+            ServerGeneral::QuestUpdate(quest) => {
+                if !quest.status.is_active() {
+                    self.tracked_quests.remove(&quest.id);
+                }
+                if let Some(old) = self.quests.iter_mut().find(|old| old.id == quest.id) {
+                    *old = quest;
+                } else {
+                    if quest.status.is_active() {
+                        self.tracked_quests.insert(quest.id);
+                    }
+                    self.quests.push(quest);
+                }
+            },
+            ServerGeneral::QuestRemoved(id) => {
+                self.tracked_quests.remove(&id);
+                self.quests.retain(|quest| quest.id != id);
+            },
             ServerGeneral::SetViewDistance(vd) => {
                 self.view_distance = Some(vd);
                 frontend_events.push(Event::SetViewDistance(vd));
@@ -3323,6 +3417,10 @@ impl Client {
         // Clear pending trade
         self.pending_trade = None;
 
+        // Quests belong to the character that left
+        self.quests.clear();
+        self.tracked_quests.clear();
+
         let client_uid = self.uid().expect("Client doesn't have a Uid!!!");
 
         // Clear ecs of all entities
@@ -3571,6 +3669,7 @@ mod tests {
             ConnectionArgs::Tcp {
                 hostname: "127.0.0.1:9000".to_owned(),
                 prefer_ipv6: false,
+                server_key: None,
             },
             runtime2,
             &mut None,
@@ -3582,6 +3681,7 @@ mod tests {
             |_| {},
             PathBuf::default(),
             ClientType::ChatOnly,
+            NetworkConditions::default(),
         ));
         let localisation = LocalizationHandle::load_expect("en");
 
@@ -19,6 +19,8 @@ pub use assets_manager::{
 
 mod fs;
 #[cfg(feature = "plugins")] mod plugin_cache;
+#[cfg(feature = "plugins")]
+pub use plugin_cache::PluginArchives;
 mod walk;
 pub use walk::{Walk, walk_tree};
 
@@ -38,6 +40,10 @@ lazy_static! {
 #[cfg(feature = "plugins")]
 pub fn register_tar(path: PathBuf) -> std::io::Result<()> { ASSETS.register_tar(path) }
 
+// unregister a plugin, e.g. after its file was removed
+#[cfg(feature = "plugins")]
+pub fn unregister_tar(path: &std::path::Path) { ASSETS.unregister_tar(path) }
+
 pub type AssetHandle<T> = &'static assets_manager::Handle<T>;
 pub type AssetReadGuard<T> = assets_manager::AssetReadGuard<'static, T>;
 pub type AssetDirHandle<T> = AssetHandle<assets_manager::RecursiveDirectory<T>>;
@@ -1,13 +1,21 @@
-use std::{path::PathBuf, sync::RwLock};
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+    sync::RwLock,
+};
 
 use super::{ASSETS_PATH, Concatenate, fs::FileSystem};
 use assets_manager::{
-    Asset, AssetCache, BoxedError, Storable,
+    Asset, AssetCache, BoxedError, FileAsset, SharedString, Storable,
     asset::DirLoadable,
     hot_reloading::EventSender,
-    source::{FileContent, Source, Tar},
+    source::{DirEntry, FileContent, OwnedDirEntry, Source, Tar},
 };
 
+/// Plugin archives are named `<name>.plugin.tar`, the asset id of such an
+/// archive ends with this suffix and has the `tar` extension
+const PLUGIN_ID_SUFFIX: &str = ".plugin";
+
 struct PluginEntry {
     path: PathBuf,
     cache: AssetCache,
@@ -28,6 +36,9 @@ struct SourceAndContents<'a>(AssetSource, FileContent<'a>);
 pub struct CombinedSource {
     fs: FileSystem,
     plugin_list: RwLock<Vec<PluginEntry>>,
+    /// Set once hot reloading is enabled, used to reload the assets of a
+    /// plugin when its archive is replaced or removed
+    events: RwLock<Option<EventSender>>,
 }
 
 impl CombinedSource {
@@ -35,8 +46,30 @@ impl CombinedSource {
         Ok(Self {
             fs: FileSystem::new()?,
             plugin_list: RwLock::new(Vec::new()),
+            events: RwLock::new(None),
         })
     }
+
+    /// Tell the hot reloader that all files in this plugin archive changed
+    fn plugin_changed(&self, plugin: &AssetCache) {
+        let events = self.events.read().unwrap();
+        let Some(events) = events.as_ref() else {
+            return;
+        };
+        let mut changed = Vec::new();
+        let mut dirs = vec![SharedString::default()];
+        while let Some(dir) = dirs.pop() {
+            let _ = plugin.source().read_dir(&dir, &mut |entry| match entry {
+                DirEntry::File(id, ext) => changed.push(OwnedDirEntry::File(id.into(), ext.into())),
+                DirEntry::Directory(id) => dirs.push(id.into()),
+            });
+        }
+        for entry in changed {
+            if let Err(e) = events.send(entry) {
+                tracing::warn!(?e, "Failed to reload plugin assets");
+            }
+        }
+    }
 }
 
 impl CombinedSource {
@@ -80,6 +113,13 @@ impl CombinedSource {
 
 impl Source for CombinedSource {
     fn read(&self, id: &str, ext: &str) -> std::io::Result<FileContent<'_>> {
+        // The filesystem would look for `<name>/plugin.tar` instead of the
+        // archive, see `PluginArchives`
+        if ext == PluginArchive::EXTENSION
+            && let Some(name) = id.strip_suffix(PLUGIN_ID_SUFFIX)
+        {
+            return self.fs.read(name, "plugin.tar");
+        }
         // We could shortcut on fs if we dont check for conflicts
         let mut entries = self.read_multiple(id, ext);
         if entries.is_empty() {
@@ -114,9 +154,44 @@ impl Source for CombinedSource {
                 .any(|plugin| plugin.cache.source().exists(entry))
     }
 
-    // TODO: Enable hot reloading for plugins
+    // Plugin archives in the assets are watched like other files, the assets
+    // in an archive are reloaded when it is registered again or removed.
     fn configure_hot_reloading(&self, events: EventSender) -> Result<(), BoxedError> {
-        self.fs.configure_hot_reloading(events)
+        self.fs.configure_hot_reloading(events.clone())?;
+        *self.events.write().unwrap() = Some(events);
+        Ok(())
+    }
+}
+
+/// A plugin archive in the assets, only loaded to be notified when it changes.
+/// The plugin manager reads the archive itself.
+struct PluginArchive;
+
+impl FileAsset for PluginArchive {
+    const EXTENSION: &'static str = "tar";
+
+    fn from_bytes(_: Cow<[u8]>) -> Result<Self, BoxedError> { Ok(Self) }
+}
+
+/// The ids of the plugin archives in an asset directory, e.g. `plugins`.
+///
+/// This asset depends on the directory and on each archive in it, so with hot
+/// reloading it is reloaded whenever an archive is added, changed or removed.
+pub struct PluginArchives(pub Vec<SharedString>);
+
+impl Asset for PluginArchives {
+    fn load(cache: &AssetCache, id: &SharedString) -> Result<Self, BoxedError> {
+        let archives = cache
+            .load_dir::<PluginArchive>(id)?
+            .read()
+            .ids()
+            .filter(|id| id.ends_with(PLUGIN_ID_SUFFIX))
+            .cloned()
+            .collect::<Vec<_>>();
+        for archive in &archives {
+            cache.load::<PluginArchive>(archive)?;
+        }
+        Ok(Self(archives))
     }
 }
 
@@ -188,19 +263,36 @@ impl CombinedCache {
 
     /// Add a tar archive (a plugin) to the system.
     /// All files in that tar file become potential assets.
+    ///
+    /// Registering the same path again replaces the previous archive, this is
+    /// used when reloading plugins.
     pub fn register_tar(&self, path: PathBuf) -> std::io::Result<()> {
         let tar_source = Tar::open(&path)?;
         let cache = AssetCache::with_source(tar_source);
-        self.0
-            .downcast_raw_source::<CombinedSource>()
-            .unwrap()
-            .plugin_list
-            .write()
-            .unwrap()
-            .push(PluginEntry { path, cache });
+        let source = self.0.downcast_raw_source::<CombinedSource>().unwrap();
+        let mut plugin_list = source.plugin_list.write().unwrap();
+        if let Some(entry) = plugin_list.iter_mut().find(|entry| entry.path == path) {
+            let old = std::mem::replace(&mut entry.cache, cache);
+            drop(plugin_list);
+            // Only assets which were loaded from the previous archive are cached
+            source.plugin_changed(&old);
+        } else {
+            plugin_list.push(PluginEntry { path, cache });
+        }
         Ok(())
     }
 
+    /// Remove a tar archive registered with [`Self::register_tar`]
+    pub fn unregister_tar(&self, path: &Path) {
+        let source = self.0.downcast_raw_source::<CombinedSource>().unwrap();
+        let mut plugin_list = source.plugin_list.write().unwrap();
+        if let Some(index) = plugin_list.iter().position(|entry| entry.path == path) {
+            let removed = plugin_list.remove(index);
+            drop(plugin_list);
+            source.plugin_changed(&removed.cache);
+        }
+    }
+
     pub fn no_record<T>(&self, f: impl FnOnce() -> T) -> T { self.0.no_record(f) }
 
     // Just forward these methods to the cache
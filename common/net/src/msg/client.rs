@@ -1,10 +1,11 @@
-use super::{PingMsg, world_msg::SiteId};
+use super::{MsgKind, PingMsg, world_msg::SiteId};
 use common::{
     ViewDistances,
     character::CharacterId,
     comp::{self, AdminRole, Skill},
     event::PluginHash,
     resources::BattleMode,
+    rtsim::QuestId,
     terrain::block::Block,
 };
 use serde::{Deserialize, Serialize};
@@ -70,7 +71,7 @@ pub struct ClientRegister {
 }
 
 /// Messages sent from the client to the server
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, strum::IntoStaticStr)]
 pub enum ClientGeneral {
     //Only in Character Screen
     RequestCharacterList,
@@ -109,6 +110,7 @@ pub enum ClientGeneral {
     RequestSiteInfo(SiteId),
     UpdateMapMarker(comp::MapMarkerChange),
     SetBattleMode(BattleMode),
+    AbandonQuest(QuestId),
 
     SpectatePosition(Vec3<f32>),
     SpectateEntity(Option<common::uid::Uid>),
@@ -124,6 +126,10 @@ pub enum ClientGeneral {
     ChatMsg(comp::Content),
     Command(String, Vec<String>),
     Terminate,
+    /// Acknowledges a [`ServerGeneral::PhysicsSync`] with its sequence number
+    ///
+    /// [`ServerGeneral::PhysicsSync`]: super::ServerGeneral::PhysicsSync
+    AckPhysicsSync(u32),
     RequestPlayerPhysics {
         server_authoritative: bool,
     },
@@ -173,7 +179,8 @@ impl ClientMsg {
                         | ClientGeneral::RequestPlayerPhysics { .. }
                         | ClientGeneral::RequestLossyTerrainCompression { .. }
                         | ClientGeneral::UpdateMapMarker(_)
-                        | ClientGeneral::SetBattleMode(_) => {
+                        | ClientGeneral::SetBattleMode(_)
+                        | ClientGeneral::AbandonQuest(_) => {
                             c_type == ClientType::Game && presence.is_some()
                         },
                         ClientGeneral::SpectatePosition(_) | ClientGeneral::SpectateEntity(_) => {
@@ -185,6 +192,7 @@ impl ClientMsg {
                         //Always possible
                         ClientGeneral::Command(_, _)
                         | ClientGeneral::Terminate
+                        | ClientGeneral::AckPhysicsSync(_)
                         // LodZoneRequest is required by the char select screen
                         | ClientGeneral::LodZoneRequest { .. } => true,
                         | ClientGeneral::RequestPlugins(_) => true,
@@ -195,6 +203,25 @@ impl ClientMsg {
     }
 }
 
+impl MsgKind for ClientMsg {
+    fn kind(&self) -> &'static str {
+        match self {
+            ClientMsg::Type(_) => "Type",
+            ClientMsg::Register(m) => m.kind(),
+            ClientMsg::General(m) => m.kind(),
+            ClientMsg::Ping(m) => m.kind(),
+        }
+    }
+}
+
+impl MsgKind for ClientRegister {
+    fn kind(&self) -> &'static str { "Register" }
+}
+
+impl MsgKind for ClientGeneral {
+    fn kind(&self) -> &'static str { self.into() }
+}
+
 /*
 end of 2nd level Enums
 */
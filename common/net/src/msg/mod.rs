@@ -21,8 +21,18 @@ pub use self::{
 };
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, strum::IntoStaticStr)]
 pub enum PingMsg {
     Ping,
     Pong,
 }
+
+/// Name of the message variant, used to break down the network traffic by
+/// message type
+pub trait MsgKind {
+    fn kind(&self) -> &'static str;
+}
+
+impl MsgKind for PingMsg {
+    fn kind(&self) -> &'static str { self.into() }
+}
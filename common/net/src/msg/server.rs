@@ -1,5 +1,5 @@
 use super::{
-    ClientType, CompressedData, EcsCompPacket, PingMsg, QuadPngEncoding, TriPngEncoding,
+    ClientType, CompressedData, EcsCompPacket, MsgKind, PingMsg, QuadPngEncoding, TriPngEncoding,
     WidePacking, WireChonk, world_msg::EconomyInfo,
 };
 use crate::sync;
@@ -142,7 +142,7 @@ impl SerializedTerrainChunk {
 }
 
 /// Messages sent from the server to the client
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, strum::IntoStaticStr)]
 pub enum ServerGeneral {
     //Character Screen related
     /// Result of loading character data
@@ -184,6 +184,12 @@ pub enum ServerGeneral {
     ExitInGameSuccess,
     InventoryUpdate(comp::Inventory, Vec<comp::InventoryUpdateEvent>),
     Dialogue(Uid, rtsim::Dialogue<true>),
+    /// All quests of the character, sent when it enters the game
+    QuestLog(Vec<rtsim::QuestInfo>),
+    /// A quest of the character was accepted or changed
+    QuestUpdate(rtsim::QuestInfo),
+    /// A quest of the character that ended a while ago was forgotten
+    QuestRemoved(rtsim::QuestId),
     /// NOTE: The client can infer that entity view distance will be at most the
     /// terrain view distance that we send here (and if lower it won't be
     /// modified). So we just need to send the terrain VD back to the client
@@ -211,6 +217,11 @@ pub enum ServerGeneral {
     TimeOfDay(TimeOfDay, Calendar, Time, TimeScale),
     EntitySync(sync::EntitySyncPackage),
     CompSync(sync::CompSyncPackage<EcsCompPacket>, u64),
+    /// Delta compressed physics of other entities, has to be acknowledged with
+    /// [`ClientGeneral::AckPhysicsSync`]
+    ///
+    /// [`ClientGeneral::AckPhysicsSync`]: super::ClientGeneral::AckPhysicsSync
+    PhysicsSync(sync::PhysicsSyncPackage),
     CreateEntity(sync::EntityPackage<EcsCompPacket>),
     DeleteEntity(Uid),
     Disconnect(DisconnectReason),
@@ -368,6 +379,9 @@ impl ServerMsg {
                         | ServerGeneral::InventoryUpdate(_, _)
                         | ServerGeneral::GroupInventoryUpdate(_, _)
                         | ServerGeneral::Dialogue(_, _)
+                        | ServerGeneral::QuestLog(_)
+                        | ServerGeneral::QuestUpdate(_)
+                        | ServerGeneral::QuestRemoved(_)
                         | ServerGeneral::TerrainChunkUpdate { .. }
                         | ServerGeneral::TerrainBlockUpdates(_)
                         | ServerGeneral::SetViewDistance(_)
@@ -392,6 +406,7 @@ impl ServerMsg {
                         | ServerGeneral::TimeOfDay(_, _, _, _)
                         | ServerGeneral::EntitySync(_)
                         | ServerGeneral::CompSync(_, _)
+                        | ServerGeneral::PhysicsSync(_)
                         | ServerGeneral::CreateEntity(_)
                         | ServerGeneral::DeleteEntity(_)
                         | ServerGeneral::Disconnect(_)
@@ -406,6 +421,22 @@ impl ServerMsg {
     }
 }
 
+impl MsgKind for ServerMsg {
+    fn kind(&self) -> &'static str {
+        match self {
+            ServerMsg::Info(_) => "Info",
+            ServerMsg::Init(_) => "Init",
+            ServerMsg::RegisterAnswer(_) => "RegisterAnswer",
+            ServerMsg::General(m) => m.kind(),
+            ServerMsg::Ping(m) => m.kind(),
+        }
+    }
+}
+
+impl MsgKind for ServerGeneral {
+    fn kind(&self) -> &'static str { self.into() }
+}
+
 impl From<comp::ChatMsg> for ServerGeneral {
     fn from(v: comp::ChatMsg) -> Self { ServerGeneral::ChatMsg(v) }
 }
@@ -0,0 +1,489 @@
+//! Delta compression of the physics components of other entities.
+//!
+//! Positions, velocities and orientations are quantized and sent relative to
+//! the last snapshot the client acknowledged, so crowds of entities that
+//! barely move only cost a few bytes each. Both ends keep a
+//! [`PhysicsBaseline`] and fold exactly the same snapshots into it: the server
+//! when the client acknowledges one, the client when the server starts to use
+//! it as baseline.
+//!
+//! This relies on the client acknowledging every snapshot it receives, in the
+//! order it received them, over an ordered stream. Then, once the server
+//! received the acknowledgement for snapshot `n`, it knows about every
+//! snapshot before `n` the client received. If an acknowledgement is ever
+//! missing or out of order anyway, the server can't tell which snapshots the
+//! client folded, so it starts over without a baseline.
+use common::{
+    comp::{Ori, Pos, Vel},
+    uid::Uid,
+};
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use tracing::error;
+use vek::{Quaternion, Vec3, Vec4};
+
+/// Steps per block (or block per second), about 1.6cm
+const POS_SCALE: f32 = 64.0;
+const VEL_SCALE: f32 = 64.0;
+const ORI_SCALE: f32 = i16::MAX as f32;
+/// Entities that weren't part of a snapshot for this many snapshots are
+/// dropped from the baseline, so it doesn't grow with every entity ever seen
+const BASELINE_TTL: u32 = 30 * 60;
+/// Unacknowledged snapshots the server keeps around, once exceeded it starts
+/// over without a baseline
+const MAX_PENDING_SNAPSHOTS: usize = 256;
+
+// How a component is encoded in a `PhysicsSyncPackage`, two bits per component
+const ABSENT: u8 = 0;
+const ABSOLUTE: u8 = 1;
+const DELTA: u8 = 2;
+const UNCHANGED: u8 = 3;
+
+/// Physics components of an entity, as sent in a [`PhysicsSyncPackage`]
+pub type PhysicsState = (Uid, Pos, Option<Vel>, Option<Ori>);
+
+/// Physics components quantized, so both ends compute exactly the same deltas
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct QuantizedPhysics {
+    pos: Vec3<i32>,
+    vel: Option<Vec3<i32>>,
+    ori: Option<Vec4<i16>>,
+}
+
+impl QuantizedPhysics {
+    fn new(pos: Pos, vel: Option<Vel>, ori: Option<Ori>) -> Self {
+        Self {
+            pos: pos.0.map(|x| (x * POS_SCALE).round() as i32),
+            vel: vel.map(|vel| vel.0.map(|x| (x * VEL_SCALE).round() as i32)),
+            ori: ori.map(|ori| {
+                let quat = ori.to_quat().into_vec4();
+                // `q` and `-q` are the same rotation, only send one of them
+                let quat = if quat.w < 0.0 { -quat } else { quat };
+                quat.map(|x| (x * ORI_SCALE).round() as i16)
+            }),
+        }
+    }
+
+    fn to_comps(self) -> (Pos, Option<Vel>, Option<Ori>) {
+        (
+            Pos(self.pos.map(|x| x as f32 / POS_SCALE)),
+            self.vel.map(|vel| Vel(vel.map(|x| x as f32 / VEL_SCALE))),
+            self.ori.map(|ori| {
+                Ori::new(Quaternion::from_vec4(ori.map(|x| x as f32 / ORI_SCALE)).normalized())
+            }),
+        )
+    }
+}
+
+/// Quantized physics components of entities, relative to the snapshot
+/// `baseline`. Stored column-wise, which compresses better than one struct
+/// per entity.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PhysicsSyncPackage {
+    seq: u32,
+    /// `0` if the server has no acknowledged snapshot to build upon
+    baseline: u32,
+    /// The server discarded all snapshots before this one
+    since: u32,
+    uids: Vec<Uid>,
+    /// How the position, velocity and orientation are encoded, two bits each
+    modes: Vec<u8>,
+    small: Vec<i16>,
+    large: Vec<i32>,
+}
+
+impl PhysicsSyncPackage {
+    /// Sequence number to acknowledge after this package was applied
+    pub fn seq(&self) -> u32 { self.seq }
+
+    pub fn is_empty(&self) -> bool { self.uids.is_empty() }
+
+    fn push(&mut self, uid: Uid, state: &QuantizedPhysics, baseline: Option<&QuantizedPhysics>) {
+        let pos = self.push_vec3(Some(state.pos), baseline.map(|b| b.pos));
+        let vel = self.push_vec3(state.vel, baseline.and_then(|b| b.vel));
+        let ori = match (state.ori, baseline.and_then(|b| b.ori)) {
+            (None, _) => ABSENT,
+            (Some(ori), Some(baseline)) if ori == baseline => UNCHANGED,
+            (Some(ori), _) => {
+                self.small.extend(ori.into_array());
+                ABSOLUTE
+            },
+        };
+        self.uids.push(uid);
+        self.modes.push(pos | (vel << 2) | (ori << 4));
+    }
+
+    fn push_vec3(&mut self, value: Option<Vec3<i32>>, baseline: Option<Vec3<i32>>) -> u8 {
+        let Some(value) = value else {
+            return ABSENT;
+        };
+        if let Some(baseline) = baseline {
+            if value == baseline {
+                return UNCHANGED;
+            }
+            let delta = value.map2(baseline, i32::wrapping_sub);
+            if let (Ok(x), Ok(y), Ok(z)) = (
+                i16::try_from(delta.x),
+                i16::try_from(delta.y),
+                i16::try_from(delta.z),
+            ) {
+                self.small.extend([x, y, z]);
+                return DELTA;
+            }
+        }
+        self.large.extend(value.into_array());
+        ABSOLUTE
+    }
+}
+
+/// Reads the values of a [`PhysicsSyncPackage`] in the order they were pushed
+struct Reader<'a> {
+    small: std::slice::Iter<'a, i16>,
+    large: std::slice::Iter<'a, i32>,
+}
+
+impl Reader<'_> {
+    /// Outer `None` if the package is malformed or the baseline is missing
+    fn vec3(&mut self, mode: u8, baseline: Option<Vec3<i32>>) -> Option<Option<Vec3<i32>>> {
+        Some(match mode {
+            ABSENT => None,
+            ABSOLUTE => Some(Vec3::new(
+                *self.large.next()?,
+                *self.large.next()?,
+                *self.large.next()?,
+            )),
+            DELTA => {
+                // Consume the values even without baseline, to stay aligned
+                let delta = Vec3::new(
+                    *self.small.next()?,
+                    *self.small.next()?,
+                    *self.small.next()?,
+                );
+                Some(baseline?.map2(delta, |b, d| b.wrapping_add(i32::from(d))))
+            },
+            _ => Some(baseline?),
+        })
+    }
+
+    fn vec4(&mut self, mode: u8, baseline: Option<Vec4<i16>>) -> Option<Option<Vec4<i16>>> {
+        Some(match mode {
+            ABSENT => None,
+            ABSOLUTE | DELTA => Some(Vec4::new(
+                *self.small.next()?,
+                *self.small.next()?,
+                *self.small.next()?,
+                *self.small.next()?,
+            )),
+            _ => Some(baseline?),
+        })
+    }
+}
+
+/// Snapshots one end of a connection knows the other end has, see the
+/// [module level documentation](self)
+#[derive(Debug, Default)]
+pub struct PhysicsBaseline {
+    /// Latest acknowledged state of each entity and the snapshot it's from
+    states: HashMap<Uid, (u32, QuantizedPhysics)>,
+    /// Snapshots that weren't folded into `states` yet
+    snapshots: VecDeque<(u32, Vec<(Uid, QuantizedPhysics)>)>,
+    /// Latest snapshot folded into `states`
+    baseline: u32,
+    since: u32,
+    /// Sequence number of the latest snapshot, only used by the server
+    seq: u32,
+}
+
+impl PhysicsBaseline {
+    /// Used by the server, forgets everything, so the next snapshot is sent
+    /// without a baseline and the client drops its baseline as well
+    fn reset(&mut self) {
+        self.states.clear();
+        self.snapshots.clear();
+        self.baseline = 0;
+        self.since = self.seq + 1;
+    }
+
+    fn fold(&mut self, seq: u32, snapshot: Vec<(Uid, QuantizedPhysics)>) {
+        for (uid, state) in snapshot {
+            self.states.insert(uid, (seq, state));
+        }
+        self.states
+            .retain(|_, (last, _)| seq.saturating_sub(*last) <= BASELINE_TTL);
+        self.baseline = seq;
+    }
+
+    /// Used by the server, encodes the next snapshot against the latest
+    /// acknowledged one
+    pub fn encode(&mut self, states: Vec<PhysicsState>) -> PhysicsSyncPackage {
+        if self.snapshots.len() >= MAX_PENDING_SNAPSHOTS {
+            // The client doesn't acknowledge anything, start over
+            self.reset();
+        }
+        self.seq += 1;
+        let mut package = PhysicsSyncPackage {
+            seq: self.seq,
+            baseline: self.baseline,
+            since: self.since,
+            uids: Vec::with_capacity(states.len()),
+            modes: Vec::with_capacity(states.len()),
+            small: Vec::new(),
+            large: Vec::new(),
+        };
+        let snapshot = states
+            .into_iter()
+            .map(|(uid, pos, vel, ori)| {
+                let state = QuantizedPhysics::new(pos, vel, ori);
+                package.push(uid, &state, self.states.get(&uid).map(|(_, s)| s));
+                (uid, state)
+            })
+            .collect();
+        self.snapshots.push_back((self.seq, snapshot));
+        package
+    }
+
+    /// Used by the server when the client acknowledged the snapshot `seq`.
+    /// Acknowledgements have to arrive in order and without gaps, see the
+    /// [module level documentation](self). Ones for snapshots from before the
+    /// last reset are ignored.
+    pub fn acknowledge(&mut self, seq: u32) {
+        if seq < self.since {
+            return;
+        }
+        match self.snapshots.front() {
+            Some((front, _)) if *front == seq => {
+                if let Some((front, snapshot)) = self.snapshots.pop_front() {
+                    self.fold(front, snapshot);
+                }
+            },
+            // Missing, out of order or duplicate, the client might have
+            // folded a snapshot that we didn't
+            _ => {
+                error!(
+                    ?seq,
+                    baseline = self.baseline,
+                    "Unexpected physics sync acknowledgement, starting over"
+                );
+                self.reset();
+            },
+        }
+    }
+
+    /// Used by the client, decodes a snapshot from the server. It has to be
+    /// acknowledged afterwards with the sequence number of the package.
+    pub fn decode(&mut self, package: PhysicsSyncPackage) -> Vec<PhysicsState> {
+        if package.since != self.since {
+            self.states.clear();
+            self.snapshots.retain(|(seq, _)| *seq >= package.since);
+            self.baseline = 0;
+            self.since = package.since;
+        }
+        while let Some((front, _)) = self.snapshots.front()
+            && *front <= package.baseline
+        {
+            if let Some((front, snapshot)) = self.snapshots.pop_front() {
+                self.fold(front, snapshot);
+            }
+        }
+        if self.baseline != package.baseline {
+            error!(
+                local = self.baseline,
+                remote = package.baseline,
+                "Physics baseline out of sync with the server"
+            );
+        }
+
+        let mut reader = Reader {
+            small: package.small.iter(),
+            large: package.large.iter(),
+        };
+        let mut snapshot = Vec::with_capacity(package.uids.len());
+        for (&uid, &modes) in package.uids.iter().zip(&package.modes) {
+            let baseline = self.states.get(&uid).map(|(_, s)| s);
+            let pos = reader.vec3(modes & 0b11, baseline.map(|b| b.pos));
+            let vel = reader.vec3((modes >> 2) & 0b11, baseline.and_then(|b| b.vel));
+            let ori = reader.vec4((modes >> 4) & 0b11, baseline.and_then(|b| b.ori));
+            if let (Some(Some(pos)), Some(vel), Some(ori)) = (pos, vel, ori) {
+                snapshot.push((uid, QuantizedPhysics { pos, vel, ori }));
+            } else {
+                error!(?uid, "Failed to decode the physics of an entity");
+            }
+        }
+
+        let states = snapshot
+            .iter()
+            .map(|(uid, state)| {
+                let (pos, vel, ori) = state.to_comps();
+                (*uid, pos, vel, ori)
+            })
+            .collect();
+        self.snapshots.push_back((package.seq, snapshot));
+        states
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::num::NonZeroU64;
+
+    fn uid(id: u64) -> Uid { Uid(NonZeroU64::new(id).unwrap()) }
+
+    fn state(id: u64, pos: Vec3<f32>) -> PhysicsState {
+        (
+            uid(id),
+            Pos(pos),
+            Some(Vel(Vec3::new(1.0, -2.0, 0.5))),
+            Some(Ori::default().yawed_left(0.3)),
+        )
+    }
+
+    fn assert_roundtrip(sent: &[PhysicsState], received: &[PhysicsState]) {
+        assert_eq!(sent.len(), received.len());
+        for ((uid, pos, vel, ori), (r_uid, r_pos, r_vel, r_ori)) in sent.iter().zip(received) {
+            assert_eq!(uid, r_uid);
+            assert!(pos.0.distance(r_pos.0) < 1.0 / POS_SCALE);
+            assert_eq!(vel.is_some(), r_vel.is_some());
+            if let (Some(vel), Some(r_vel)) = (vel, r_vel) {
+                assert!(vel.0.distance(r_vel.0) < 1.0 / VEL_SCALE);
+            }
+            assert_eq!(ori.is_some(), r_ori.is_some());
+            if let (Some(ori), Some(r_ori)) = (ori, r_ori) {
+                assert!(ori.look_vec().distance(r_ori.look_vec()) < 0.001);
+            }
+        }
+    }
+
+    /// Encodes `states` on the server, decodes them on the client and
+    /// acknowledges them if `ack` is set
+    fn sync(
+        server: &mut PhysicsBaseline,
+        client: &mut PhysicsBaseline,
+        states: Vec<PhysicsState>,
+        ack: bool,
+    ) -> PhysicsSyncPackage {
+        let package = server.encode(states.clone());
+        let received = client.decode(package.clone());
+        assert_roundtrip(&states, &received);
+        if ack {
+            server.acknowledge(package.seq());
+        }
+        package
+    }
+
+    #[test]
+    fn roundtrip() {
+        let (mut server, mut client) = (PhysicsBaseline::default(), PhysicsBaseline::default());
+        let states = vec![
+            state(1, Vec3::new(100.3, 2000.7, 50.1)),
+            state(2, Vec3::new(-5.0, 0.0, 1.0e6)),
+            (uid(3), Pos(Vec3::zero()), None, None),
+        ];
+        sync(&mut server, &mut client, states, true);
+    }
+
+    #[test]
+    fn delta_after_acknowledge() {
+        let (mut server, mut client) = (PhysicsBaseline::default(), PhysicsBaseline::default());
+        let states = vec![state(1, Vec3::new(100.0, 200.0, 50.0))];
+        let first = server.encode(states.clone());
+        assert_eq!(first.baseline, 0);
+        assert_eq!(first.large.len(), 6);
+
+        // Not acknowledged yet, so it's sent in full again
+        let second = server.encode(states.clone());
+        assert_eq!(second.baseline, 0);
+        assert_eq!(second.large.len(), 6);
+        client.decode(first.clone());
+        client.decode(second.clone());
+        server.acknowledge(first.seq());
+        server.acknowledge(second.seq());
+
+        // Unchanged
+        let third = sync(&mut server, &mut client, states, true);
+        assert_eq!(third.baseline, second.seq);
+        assert_eq!(third.modes, vec![
+            UNCHANGED | (UNCHANGED << 2) | (UNCHANGED << 4)
+        ]);
+        assert!(third.small.is_empty() && third.large.is_empty());
+
+        // Moved a bit
+        let fourth = sync(
+            &mut server,
+            &mut client,
+            vec![state(1, Vec3::new(101.0, 199.5, 50.0))],
+            true,
+        );
+        assert_eq!(fourth.baseline, third.seq);
+        assert_eq!(fourth.modes[0] & 0b11, DELTA);
+        assert!(fourth.large.is_empty());
+    }
+
+    #[test]
+    fn full_resync_without_acknowledgements() {
+        let (mut server, mut client) = (PhysicsBaseline::default(), PhysicsBaseline::default());
+        sync(&mut server, &mut client, vec![state(1, Vec3::one())], true);
+        for i in 0..MAX_PENDING_SNAPSHOTS {
+            sync(
+                &mut server,
+                &mut client,
+                vec![state(1, Vec3::one() * i as f32)],
+                false,
+            );
+        }
+        let resync = sync(&mut server, &mut client, vec![state(1, Vec3::zero())], true);
+        assert_eq!(resync.baseline, 0);
+        assert_eq!(resync.since, resync.seq);
+
+        // Acknowledgements from before the resync are ignored
+        server.acknowledge(resync.seq - 1);
+        let next = sync(&mut server, &mut client, vec![state(1, Vec3::zero())], true);
+        assert_eq!(next.baseline, resync.seq);
+        assert_eq!(next.since, resync.since);
+    }
+
+    #[test]
+    fn out_of_order_acknowledgements_start_over() {
+        let (mut server, mut client) = (PhysicsBaseline::default(), PhysicsBaseline::default());
+        // The client folds both snapshots once the server uses the second
+        // one as baseline, so the server can't just skip the first one
+        let first = server.encode(vec![state(1, Vec3::one()), state(2, Vec3::one())]);
+        let second = server.encode(vec![state(1, Vec3::zero())]);
+        client.decode(first.clone());
+        client.decode(second.clone());
+        server.acknowledge(second.seq());
+        server.acknowledge(first.seq());
+
+        let third = sync(
+            &mut server,
+            &mut client,
+            vec![state(1, Vec3::one()), state(2, Vec3::zero())],
+            true,
+        );
+        assert_eq!(third.baseline, 0);
+        assert_eq!(third.since, third.seq);
+        sync(
+            &mut server,
+            &mut client,
+            vec![state(1, Vec3::zero()), state(2, Vec3::one())],
+            true,
+        );
+    }
+
+    #[test]
+    fn missing_acknowledgement_starts_over() {
+        let (mut server, mut client) = (PhysicsBaseline::default(), PhysicsBaseline::default());
+        sync(&mut server, &mut client, vec![state(1, Vec3::one())], false);
+        sync(&mut server, &mut client, vec![state(2, Vec3::one())], true);
+        let third = sync(&mut server, &mut client, vec![state(2, Vec3::zero())], true);
+        assert_eq!(third.baseline, 0);
+        let fourth = sync(
+            &mut server,
+            &mut client,
+            vec![state(1, Vec3::zero()), state(2, Vec3::zero())],
+            true,
+        );
+        assert_eq!(fourth.baseline, third.seq);
+    }
+}
@@ -1,5 +1,6 @@
 // Note: Currently only one-way sync is supported until a usecase for two-way
 // sync arises
+mod delta;
 pub mod interpolation;
 mod net_sync;
 mod packet;
@@ -8,6 +9,7 @@ mod track;
 
 // Reexports
 pub use common::uid::{IdMaps, Uid};
+pub use delta::{PhysicsBaseline, PhysicsState, PhysicsSyncPackage};
 pub use net_sync::{NetSync, SyncFrom};
 pub use packet::{
     CompPacket, CompSyncPackage, EntityPackage, EntitySyncPackage, InterpolatableComponent,
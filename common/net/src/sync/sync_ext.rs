@@ -1,8 +1,10 @@
 use super::{
+    delta::PhysicsState,
     packet::{CompPacket, CompSyncPackage, CompUpdateKind, EntityPackage, EntitySyncPackage},
     track::UpdateTracker,
 };
 use common::{
+    comp::{Ori, Pos, Vel},
     resources::PlayerEntity,
     uid::{IdMaps, Uid},
 };
@@ -27,6 +29,10 @@ pub trait WorldSyncExt {
     ) -> specs::Entity;
     fn apply_entity_sync_package(&mut self, package: EntitySyncPackage, client_uid: Option<Uid>);
     fn apply_comp_sync_package<P: CompPacket>(&mut self, package: CompSyncPackage<P>);
+    fn apply_physics_states<P: CompPacket + From<Pos> + From<Vel> + From<Ori>>(
+        &mut self,
+        states: Vec<PhysicsState>,
+    );
 }
 
 impl WorldSyncExt for specs::World {
@@ -150,6 +156,39 @@ impl WorldSyncExt for specs::World {
             }
         });
     }
+
+    /// Applies the physics decoded from a
+    /// [`PhysicsSyncPackage`](super::PhysicsSyncPackage), components that
+    /// don't exist yet are inserted
+    fn apply_physics_states<P: CompPacket + From<Pos> + From<Vel> + From<Ori>>(
+        &mut self,
+        states: Vec<PhysicsState>,
+    ) {
+        for (uid, pos, vel, ori) in states {
+            if let Some(entity) = self.read_resource::<IdMaps>().uid_entity(uid) {
+                apply_physics_comp::<P, _>(self, entity, pos);
+                if let Some(vel) = vel {
+                    apply_physics_comp::<P, _>(self, entity, vel);
+                }
+                if let Some(ori) = ori {
+                    apply_physics_comp::<P, _>(self, entity, ori);
+                }
+            }
+        }
+    }
+}
+
+fn apply_physics_comp<P: CompPacket + From<C>, C: specs::Component>(
+    world: &specs::World,
+    entity: specs::Entity,
+    comp: C,
+) {
+    let exists = world.read_storage::<C>().contains(entity);
+    if exists {
+        P::from(comp).apply_modify(entity, world, false);
+    } else {
+        P::from(comp).apply_insert(entity, world, false);
+    }
 }
 
 // Private utilities
@@ -70,4 +70,13 @@ async fn main() {
     {
         println!("{:?}", last_info);
     }
+
+    match client.extended_info().await {
+        Ok((info, _)) => println!("{info:?}"),
+        Err(e) => error!(?e, "Failed to fetch extended info from server"),
+    }
+    match client.player_list(0).await {
+        Ok((list, _)) => println!("{list:?}"),
+        Err(e) => error!(?e, "Failed to fetch the player list from server"),
+    }
 }
@@ -9,8 +9,8 @@ use tokio::{net::UdpSocket, time::timeout};
 use tracing::trace;
 
 use crate::proto::{
-    MAX_RESPONSE_SIZE, QueryServerRequest, QueryServerResponse, RawQueryServerRequest,
-    RawQueryServerResponse, ServerInfo,
+    ExtendedServerInfo, MAX_RESPONSE_SIZE, PlayerList, QueryServerRequest, QueryServerResponse,
+    RawQueryServerRequest, RawQueryServerResponse, ServerInfo, VERSION,
 };
 
 // This must be at least 2 for the client to get a value for the `p` field.
@@ -23,11 +23,14 @@ pub enum QueryClientError {
     InvalidResponse,
     Timeout,
     ChallengeFailed,
+    /// The server is too old to understand the request
+    UnsupportedRequest,
+    /// The server chose not to answer the request
+    Disabled,
 }
 
 struct ClientInitData {
     p: u64,
-    #[expect(dead_code)]
     server_max_version: u16,
 }
 
@@ -45,7 +48,6 @@ impl QueryClient {
         self.send_query(QueryServerRequest::ServerInfo)
             .await
             .and_then(|(response, duration)| {
-                #[expect(irrefutable_let_patterns)]
                 if let QueryServerResponse::ServerInfo(info) = response {
                     Ok((info, duration))
                 } else {
@@ -54,6 +56,33 @@ impl QueryClient {
             })
     }
 
+    /// One page of the names of the online players, see
+    /// [`QueryServerRequest::PlayerList`]
+    pub async fn player_list(
+        &mut self,
+        page: u16,
+    ) -> Result<(PlayerList, Duration), QueryClientError> {
+        self.send_query(QueryServerRequest::PlayerList { page })
+            .await
+            .and_then(|(response, duration)| match response {
+                QueryServerResponse::PlayerList(list) => Ok((list, duration)),
+                QueryServerResponse::Disabled => Err(QueryClientError::Disabled),
+                _ => Err(QueryClientError::InvalidResponse),
+            })
+    }
+
+    pub async fn extended_info(
+        &mut self,
+    ) -> Result<(ExtendedServerInfo, Duration), QueryClientError> {
+        self.send_query(QueryServerRequest::ExtendedInfo)
+            .await
+            .and_then(|(response, duration)| match response {
+                QueryServerResponse::ExtendedInfo(info) => Ok((info, duration)),
+                QueryServerResponse::Disabled => Err(QueryClientError::Disabled),
+                _ => Err(QueryClientError::InvalidResponse),
+            })
+    }
+
     async fn send_query(
         &mut self,
         request: QueryServerRequest,
@@ -66,18 +95,24 @@ impl QueryClient {
         .await?;
 
         for _ in 0..MAX_REQUEST_RETRIES {
-            let request = if let Some(init) = &self.init {
-                // TODO: Use the maximum version supported by both the client and server once
-                // new protocol versions are added
-                RawQueryServerRequest { p: init.p, request }
-            } else {
-                // TODO: Use the legacy version here once new protocol versions are added
-                RawQueryServerRequest {
-                    p: 0,
-                    request: QueryServerRequest::Init,
+            let (request, version) = if let Some(init) = &self.init {
+                // Use the newest version supported by both the client and server
+                let version = init.server_max_version.min(VERSION);
+                if request.min_version() > version {
+                    return Err(QueryClientError::UnsupportedRequest);
                 }
+                (RawQueryServerRequest { p: init.p, request }, version)
+            } else {
+                // The first request always has to be done in the V0 protocol
+                (
+                    RawQueryServerRequest {
+                        p: 0,
+                        request: QueryServerRequest::Init,
+                    },
+                    0,
+                )
             };
-            let buf = request.serialize()?;
+            let buf = request.serialize(version)?;
             let query_sent = Instant::now();
             socket.send_to(&buf, self.addr).await?;
             let mut buf = vec![0; MAX_RESPONSE_SIZE];
@@ -1,7 +1,13 @@
 #![expect(non_local_definitions)] // necessary because of the Protocol derive macro
 use protocol::Protocol;
 
-pub(crate) const VERSION: u16 = 0;
+/// The newest protocol version, requests in all versions up to this one are
+/// accepted.
+///
+/// Version history:
+/// - 0: `Init` and `ServerInfo`
+/// - 1: `PlayerList` and `ExtendedInfo`
+pub(crate) const VERSION: u16 = 1;
 pub(crate) const VELOREN_HEADER: [u8; 7] = [b'v', b'e', b'l', b'o', b'r', b'e', b'n'];
 pub(crate) const MAX_REQUEST_CONTENT_SIZE: usize = 300;
 // NOTE: The actual maximum size must never exceed 1200 or we risk getting near
@@ -9,6 +15,18 @@ pub(crate) const MAX_REQUEST_CONTENT_SIZE: usize = 300;
 pub(crate) const MAX_REQUEST_SIZE: usize = MAX_REQUEST_CONTENT_SIZE + VELOREN_HEADER.len() + 2;
 pub(crate) const MAX_RESPONSE_SIZE: usize = 256;
 
+/// Number of names in each page of [`PlayerList`], chosen so that a page of
+/// names with the maximum length still fits into [`MAX_RESPONSE_SIZE`]
+pub const PLAYERS_PER_PAGE: u16 = 6;
+/// Maximum length of player names in bytes, longer names are truncated
+pub const MAX_PLAYER_NAME_LEN: usize = 32;
+/// Maximum length of [`ExtendedServerInfo::motd`] in bytes
+pub const MAX_MOTD_LEN: usize = 120;
+/// Maximum number of [`ExtendedServerInfo::tags`]
+pub const MAX_TAGS: usize = 4;
+/// Maximum length of each of the [`ExtendedServerInfo::tags`] in bytes
+pub const MAX_TAG_LEN: usize = 20;
+
 #[derive(Protocol, Debug, Clone, Copy)]
 pub(crate) struct RawQueryServerRequest {
     /// See comment on [`Init::p`]
@@ -28,11 +46,28 @@ pub enum QueryServerRequest {
     /// will still be dropped as the supplied `P` value is invalid).
     Init,
     ServerInfo,
+    /// Names of the online players, [`PLAYERS_PER_PAGE`] at a time. Servers
+    /// have to opt into answering this request. Since version 1.
+    PlayerList {
+        page: u16,
+    },
+    /// Since version 1.
+    ExtendedInfo,
     // New requests should be added at the end to prevent breakage.
     // NOTE: Any new (sub-)variants must be added to the `check_request_sizes` test at the end of
     // this file
 }
 
+impl QueryServerRequest {
+    /// The first protocol version this request can be sent in
+    pub fn min_version(&self) -> u16 {
+        match self {
+            Self::Init | Self::ServerInfo => 0,
+            Self::PlayerList { .. } | Self::ExtendedInfo => 1,
+        }
+    }
+}
+
 #[derive(Protocol, Debug, Clone, Copy)]
 pub(crate) struct Init {
     /// This is used as a challenge to prevent IP address spoofing by verifying
@@ -50,7 +85,7 @@ pub(crate) struct Init {
     pub max_supported_version: u16,
 }
 
-#[derive(Protocol, Debug, Clone, Copy)]
+#[derive(Protocol, Debug, Clone)]
 #[protocol(discriminant = "integer")]
 #[protocol(discriminator(u8))]
 pub(crate) enum RawQueryServerResponse {
@@ -58,12 +93,18 @@ pub(crate) enum RawQueryServerResponse {
     Init(Init),
 }
 
-#[derive(Protocol, Debug, Clone, Copy)]
+#[derive(Protocol, Debug, Clone)]
 #[protocol(discriminant = "integer")]
 #[protocol(discriminator(u8))]
 pub enum QueryServerResponse {
     ServerInfo(ServerInfo),
+    PlayerList(PlayerList),
+    ExtendedInfo(ExtendedServerInfo),
+    /// The server doesn't answer this kind of request
+    Disabled,
     // New responses should be added at the end to prevent breakage
+    // NOTE: Any new (sub-)variants must be added to the `check_response_sizes` test at the end
+    // of this file
 }
 
 #[derive(Protocol, Debug, Clone, Copy, PartialEq, Eq)]
@@ -75,6 +116,71 @@ pub struct ServerInfo {
     pub battlemode: ServerBattleMode,
 }
 
+#[derive(Protocol, Debug, Clone, PartialEq, Eq)]
+pub struct PlayerList {
+    pub page: u16,
+    /// Total number of online players, there are
+    /// `total.div_ceil(PLAYERS_PER_PAGE)` pages
+    pub total: u16,
+    pub names: Vec<String>,
+}
+
+#[derive(Protocol, Debug, Clone, PartialEq)]
+pub struct ExtendedServerInfo {
+    pub world_seed: u32,
+    /// Length of an in-game day in minutes
+    pub day_length: f64,
+    pub uptime_secs: u64,
+    /// Message of the day in the default language of the server
+    pub motd: String,
+    /// Free form tags describing the server, e.g. "roleplay"
+    pub tags: Vec<String>,
+}
+
+impl ExtendedServerInfo {
+    /// Truncates `motd` and `tags` so the info fits into a response
+    pub fn new(world_seed: u32, day_length: f64, motd: &str, tags: &[String]) -> Self {
+        Self {
+            world_seed,
+            day_length,
+            uptime_secs: 0,
+            motd: truncated(motd, MAX_MOTD_LEN),
+            tags: tags
+                .iter()
+                .take(MAX_TAGS)
+                .map(|tag| truncated(tag, MAX_TAG_LEN))
+                .collect(),
+        }
+    }
+}
+
+impl PlayerList {
+    /// The `page` of the player list, names have to be in a stable order for
+    /// pagination to work
+    pub fn page(names: &[String], page: u16) -> Self {
+        let start = page as usize * PLAYERS_PER_PAGE as usize;
+        Self {
+            page,
+            total: names.len().try_into().unwrap_or(u16::MAX),
+            names: names
+                .iter()
+                .skip(start)
+                .take(PLAYERS_PER_PAGE as usize)
+                .map(|name| truncated(name, MAX_PLAYER_NAME_LEN))
+                .collect(),
+        }
+    }
+}
+
+/// Cut `s` to at most `max_len` bytes, at a char boundary
+fn truncated(s: &str, max_len: usize) -> String {
+    let mut end = s.len().min(max_len);
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    s[..end].to_owned()
+}
+
 #[derive(Protocol, Debug, Clone, Copy, PartialEq, Eq)]
 #[protocol(discriminant = "integer")]
 #[protocol(discriminator(u8))]
@@ -87,13 +193,13 @@ pub enum ServerBattleMode {
 
 impl RawQueryServerRequest {
     #[cfg(any(feature = "client", test))]
-    pub fn serialize(&self) -> Result<Vec<u8>, protocol::Error> {
+    pub fn serialize(&self, version: u16) -> Result<Vec<u8>, protocol::Error> {
         use protocol::Parcel;
 
         let mut buf = Vec::with_capacity(MAX_REQUEST_SIZE);
 
-        // 2 extra bytes for version information, currently unused
-        buf.extend(VERSION.to_le_bytes());
+        // 2 extra bytes for version information
+        buf.extend(version.to_le_bytes());
         buf.extend({
             let request_data =
                 <RawQueryServerRequest as Parcel>::raw_bytes(self, &Default::default())?;
@@ -115,18 +221,75 @@ impl RawQueryServerRequest {
 
 #[cfg(test)]
 mod tests {
-    use super::{QueryServerRequest, RawQueryServerRequest};
+    use super::{
+        ExtendedServerInfo, MAX_MOTD_LEN, MAX_PLAYER_NAME_LEN, MAX_RESPONSE_SIZE, MAX_TAG_LEN,
+        MAX_TAGS, PLAYERS_PER_PAGE, PlayerList, QueryServerRequest, QueryServerResponse,
+        RawQueryServerRequest, RawQueryServerResponse, ServerBattleMode, ServerInfo, VERSION,
+    };
+    use protocol::Parcel;
 
     #[test]
     fn check_request_sizes() {
-        const ALL_REQUESTS: &[QueryServerRequest] =
-            &[QueryServerRequest::ServerInfo, QueryServerRequest::Init];
+        const ALL_REQUESTS: &[QueryServerRequest] = &[
+            QueryServerRequest::ServerInfo,
+            QueryServerRequest::Init,
+            QueryServerRequest::PlayerList { page: u16::MAX },
+            QueryServerRequest::ExtendedInfo,
+        ];
         for request in ALL_REQUESTS {
             let request = RawQueryServerRequest {
                 p: 0,
                 request: *request,
             };
-            request.serialize().unwrap(); // This will panic if the size is above MAX_REQUEST_SIZE
+            // This will panic if the size is above MAX_REQUEST_SIZE
+            request.serialize(VERSION).unwrap();
+        }
+    }
+
+    #[test]
+    fn check_response_sizes() {
+        let names = vec!["n".repeat(MAX_PLAYER_NAME_LEN + 10); PLAYERS_PER_PAGE as usize + 1];
+        let tags = vec!["t".repeat(MAX_TAG_LEN + 10); MAX_TAGS + 1];
+        let all_responses = [
+            QueryServerResponse::ServerInfo(ServerInfo {
+                git_hash: u32::MAX,
+                git_timestamp: i64::MAX,
+                players_count: u16::MAX,
+                player_cap: u16::MAX,
+                battlemode: ServerBattleMode::PerPlayer,
+            }),
+            QueryServerResponse::PlayerList(PlayerList::page(&names, 0)),
+            QueryServerResponse::ExtendedInfo(ExtendedServerInfo {
+                uptime_secs: u64::MAX,
+                ..ExtendedServerInfo::new(u32::MAX, f64::MAX, &"m".repeat(MAX_MOTD_LEN + 10), &tags)
+            }),
+            QueryServerResponse::Disabled,
+        ];
+        for response in all_responses {
+            let response = RawQueryServerResponse::Response(response);
+            let size = response.raw_bytes(&Default::default()).unwrap().len();
+            assert!(
+                size <= MAX_RESPONSE_SIZE,
+                "{response:?} is {size} bytes, more than {MAX_RESPONSE_SIZE}"
+            );
         }
     }
+
+    #[test]
+    fn player_list_pages() {
+        let names: Vec<_> = (0..PLAYERS_PER_PAGE + 2).map(|i| i.to_string()).collect();
+        let first = PlayerList::page(&names, 0);
+        assert_eq!(first.names.len(), PLAYERS_PER_PAGE as usize);
+        assert_eq!(first.total as usize, names.len());
+        let second = PlayerList::page(&names, 1);
+        assert_eq!(second.names, names[PLAYERS_PER_PAGE as usize..]);
+        assert!(PlayerList::page(&names, 2).names.is_empty());
+    }
+
+    #[test]
+    fn truncate_at_char_boundary() {
+        let info = ExtendedServerInfo::new(0, 1.0, &"ä".repeat(MAX_MOTD_LEN), &[]);
+        assert!(info.motd.len() <= MAX_MOTD_LEN);
+        assert!(info.motd.chars().all(|c| c == 'ä'));
+    }
 }
@@ -14,8 +14,9 @@ use tracing::{debug, error, trace};
 
 use crate::{
     proto::{
-        Init, MAX_REQUEST_SIZE, MAX_RESPONSE_SIZE, QueryServerRequest, QueryServerResponse,
-        RawQueryServerRequest, RawQueryServerResponse, ServerInfo, VELOREN_HEADER, VERSION,
+        ExtendedServerInfo, Init, MAX_REQUEST_SIZE, MAX_RESPONSE_SIZE, PlayerList,
+        QueryServerRequest, QueryServerResponse, RawQueryServerRequest, RawQueryServerResponse,
+        ServerInfo, VELOREN_HEADER, VERSION,
     },
     ratelimit::{RateLimiter, ReducedIpAddr},
 };
@@ -25,6 +26,10 @@ const SECRET_REGEN_INTERNVAL: Duration = Duration::from_secs(300);
 pub struct QueryServer {
     addr: SocketAddr,
     server_info: watch::Receiver<ServerInfo>,
+    extended_info: Option<watch::Receiver<ExtendedServerInfo>>,
+    /// Names of the online players, in a stable order
+    player_list: Option<watch::Receiver<Vec<String>>>,
+    started: Instant,
     settings: protocol::Settings,
     ratelimit: RateLimiter,
 }
@@ -37,6 +42,8 @@ pub struct Metrics {
     pub proccessing_errors: u32,
     pub info_requests: u32,
     pub init_requests: u32,
+    pub player_list_requests: u32,
+    pub extended_info_requests: u32,
     pub sent_responses: u32,
     pub failed_responses: u32,
     pub timed_out_responses: u32,
@@ -48,11 +55,31 @@ impl QueryServer {
         Self {
             addr,
             server_info,
+            extended_info: None,
+            player_list: None,
+            started: Instant::now(),
             ratelimit: RateLimiter::new(ratelimit),
             settings: Default::default(),
         }
     }
 
+    /// Answer [`QueryServerRequest::ExtendedInfo`] requests, the uptime is
+    /// filled in by the query server
+    pub fn with_extended_info(
+        mut self,
+        extended_info: watch::Receiver<ExtendedServerInfo>,
+    ) -> Self {
+        self.extended_info = Some(extended_info);
+        self
+    }
+
+    /// Answer [`QueryServerRequest::PlayerList`] requests, the names have to be
+    /// kept in a stable order for pagination
+    pub fn with_player_list(mut self, player_list: watch::Receiver<Vec<String>>) -> Self {
+        self.player_list = Some(player_list);
+        self
+    }
+
     /// This produces TRACE level logs for any packet received on the assigned
     /// port. To prevent potentially unfettered log spam, disable the TRACE
     /// level for this crate (when outside of debugging contexts).
@@ -93,16 +120,26 @@ impl QueryServer {
             };
 
             let raw_msg_buf = &buf[..len];
-            let msg_buf = if Self::validate_datagram(raw_msg_buf) {
-                // Require 2 extra bytes for version (currently unused)
-                &raw_msg_buf[2..(raw_msg_buf.len() - VELOREN_HEADER.len())]
+            let (version, msg_buf) = if let Some(version) = Self::validate_datagram(raw_msg_buf) {
+                // Require 2 extra bytes for version
+                (
+                    version,
+                    &raw_msg_buf[2..(raw_msg_buf.len() - VELOREN_HEADER.len())],
+                )
             } else {
                 new_metrics.dropped_packets += 1;
                 continue;
             };
 
-            self.process_datagram(msg_buf, remote_addr, secrets, &mut new_metrics, &socket)
-                .await;
+            self.process_datagram(
+                msg_buf,
+                version,
+                remote_addr,
+                secrets,
+                &mut new_metrics,
+                &socket,
+            )
+            .await;
 
             // Update metrics at the end of eath packet
             if let Ok(mut metrics) = metrics.lock() {
@@ -121,34 +158,38 @@ impl QueryServer {
         }
     }
 
-    // Header must be discarded after this validation passes
-    fn validate_datagram(data: &[u8]) -> bool {
+    /// Returns the protocol version of the datagram if it is valid. Header must
+    /// be discarded after this validation passes
+    fn validate_datagram(data: &[u8]) -> Option<u16> {
         let len = data.len();
-        // Require 2 extra bytes for version (currently unused)
+        // Require 2 extra bytes for version
         if len < MAX_RESPONSE_SIZE.max(VELOREN_HEADER.len() + 2) {
             trace!(?len, "Datagram too short");
-            false
+            None
         } else if len > MAX_REQUEST_SIZE {
             trace!(?len, "Datagram too large");
-            false
+            None
         } else if data[(len - VELOREN_HEADER.len())..] != VELOREN_HEADER {
             trace!(?len, "Datagram header invalid");
-            false
-        // TODO: Allow lower versions once proper versioning is added.
-        } else if u16::from_ne_bytes(data[..2].try_into().unwrap()) != VERSION {
-            trace!(
-                "Datagram has invalid version {:?}, current {VERSION:?}",
-                &data[..2]
-            );
-            false
+            None
         } else {
-            true
+            let version = u16::from_le_bytes(data[..2].try_into().unwrap());
+            if version > VERSION {
+                trace!(
+                    ?version,
+                    "Datagram has unsupported version, newest {VERSION:?}"
+                );
+                None
+            } else {
+                Some(version)
+            }
         }
     }
 
     async fn process_datagram(
         &mut self,
         datagram: &[u8],
+        version: u16,
         remote: SocketAddr,
         secrets: (u64, u64),
         metrics: &mut Metrics,
@@ -164,7 +205,17 @@ impl QueryServer {
             return;
         };
 
-        trace!(?request, "Received packet");
+        trace!(?request, ?version, "Received packet");
+
+        if request.min_version() > version {
+            trace!(
+                ?request,
+                ?version,
+                "Request is not part of this protocol version"
+            );
+            metrics.invalid_packets += 1;
+            return;
+        }
 
         #[expect(deprecated)]
         let real_p = {
@@ -228,6 +279,40 @@ impl QueryServer {
                 )
                 .await;
             },
+            QueryServerRequest::PlayerList { page } => {
+                metrics.player_list_requests += 1;
+                let response = match &self.player_list {
+                    Some(player_list) => QueryServerResponse::PlayerList(PlayerList::page(
+                        &player_list.borrow(),
+                        page,
+                    )),
+                    None => QueryServerResponse::Disabled,
+                };
+                Self::send_response(
+                    RawQueryServerResponse::Response(response),
+                    remote,
+                    socket,
+                    metrics,
+                )
+                .await;
+            },
+            QueryServerRequest::ExtendedInfo => {
+                metrics.extended_info_requests += 1;
+                let response = match &self.extended_info {
+                    Some(extended_info) => QueryServerResponse::ExtendedInfo(ExtendedServerInfo {
+                        uptime_secs: self.started.elapsed().as_secs(),
+                        ..extended_info.borrow().clone()
+                    }),
+                    None => QueryServerResponse::Disabled,
+                };
+                Self::send_response(
+                    RawQueryServerResponse::Response(response),
+                    remote,
+                    socket,
+                    metrics,
+                )
+                .await;
+            },
         }
     }
 
@@ -237,8 +322,9 @@ impl QueryServer {
         socket: &UdpSocket,
         metrics: &mut Metrics,
     ) {
-        // TODO: Once more versions are added, send the packet in the same version as
-        // the request here.
+        // NOTE: Responses don't carry a version, clients only receive response variants
+        // of the version they sent the request in since those are answers to requests
+        // of that version.
         match <RawQueryServerResponse as Parcel>::raw_bytes(&response, &Default::default()) {
             Ok(data) => {
                 if data.len() > MAX_RESPONSE_SIZE {
@@ -286,6 +372,8 @@ impl std::ops::AddAssign for Metrics {
             proccessing_errors,
             info_requests,
             init_requests,
+            player_list_requests,
+            extended_info_requests,
             sent_responses,
             failed_responses,
             timed_out_responses,
@@ -298,6 +386,8 @@ impl std::ops::AddAssign for Metrics {
         self.proccessing_errors += proccessing_errors;
         self.info_requests += info_requests;
         self.init_requests += init_requests;
+        self.player_list_requests += player_list_requests;
+        self.extended_info_requests += extended_info_requests;
         self.sent_responses += sent_responses;
         self.failed_responses += failed_responses;
         self.timed_out_responses += timed_out_responses;
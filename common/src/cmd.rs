@@ -390,6 +390,7 @@ pub enum ServerChatCommand {
     DropAll,
     Dummy,
     Explosion,
+    ExportCharacter,
     Faction,
     GiveItem,
     Gizmos,
@@ -402,6 +403,7 @@ pub enum ServerChatCommand {
     GroupLeave,
     GroupPromote,
     Health,
+    ImportCharacter,
     IntoNpc,
     JoinFaction,
     Jump,
@@ -664,6 +666,11 @@ impl ServerChatCommand {
                 Content::localized("command-explosion-desc"),
                 Some(Admin),
             ),
+            ServerChatCommand::ExportCharacter => cmd(
+                vec![Integer("character id", 0, Required), Any("file", Required)],
+                Content::localized("command-export_character-desc"),
+                Some(Admin),
+            ),
             ServerChatCommand::Faction => cmd(
                 vec![Message(Optional)],
                 Content::localized("command-faction-desc"),
@@ -743,6 +750,11 @@ impl ServerChatCommand {
                 Content::localized("command-health-desc"),
                 Some(Admin),
             ),
+            ServerChatCommand::ImportCharacter => cmd(
+                vec![Any("file", Required), Any("player uuid", Required)],
+                Content::localized("command-import_character-desc"),
+                Some(Admin),
+            ),
             ServerChatCommand::Respawn => cmd(
                 vec![],
                 Content::localized("command-respawn-desc"),
@@ -1188,6 +1200,7 @@ impl ServerChatCommand {
             ServerChatCommand::DropAll => "dropall",
             ServerChatCommand::Dummy => "dummy",
             ServerChatCommand::Explosion => "explosion",
+            ServerChatCommand::ExportCharacter => "export_character",
             ServerChatCommand::Faction => "faction",
             ServerChatCommand::GiveItem => "give_item",
             ServerChatCommand::Gizmos => "gizmos",
@@ -1200,6 +1213,7 @@ impl ServerChatCommand {
             ServerChatCommand::GroupLeave => "group_leave",
             ServerChatCommand::GroupPromote => "group_promote",
             ServerChatCommand::Health => "health",
+            ServerChatCommand::ImportCharacter => "import_character",
             ServerChatCommand::IntoNpc => "into_npc",
             ServerChatCommand::JoinFaction => "join_faction",
             ServerChatCommand::Jump => "jump",
@@ -1659,6 +1659,11 @@ impl PickupItem {
             .expect("PickupItem without at least one item is an invariant")
     }
 
+    /// All items in this stack, only the last one may not have a full amount
+    pub fn items(&self) -> &[Item] { &self.items }
+
+    pub fn should_merge(&self) -> bool { self.should_merge }
+
     pub fn created(&self) -> ProgramTime { self.created_at }
 
     pub fn next_merge_check(&self) -> ProgramTime { self.next_merge_check }
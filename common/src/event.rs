@@ -190,6 +190,7 @@ pub struct CommandEvent(pub EcsEntity, pub String, pub Vec<String>);
 pub struct CreateSpecialEntityEvent {
     pub pos: Vec3<f32>,
     pub entity: SpecialEntity,
+    pub anchor: Option<comp::Anchor>,
 }
 
 pub struct CreateShipEvent {
@@ -423,6 +424,11 @@ pub struct NpcInteractEvent(pub EcsEntity, pub EcsEntity);
 
 pub struct DialogueEvent(pub EcsEntity, pub EcsEntity, pub rtsim::Dialogue);
 
+pub struct AbandonQuestEvent {
+    pub entity: EcsEntity,
+    pub quest_id: rtsim::QuestId,
+}
+
 pub struct InviteResponseEvent(pub EcsEntity, pub InviteResponse);
 
 pub struct InitiateInviteEvent(pub EcsEntity, pub Uid, pub InviteKind);
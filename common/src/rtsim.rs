@@ -18,6 +18,7 @@ use crate::{
     character::CharacterId,
     comp::{agent::FlightMode, inventory::item::ItemDef},
     map::Marker,
+    resources::Time,
     util::Dir,
 };
 use common_i18n::Content;
@@ -364,6 +365,44 @@ impl From<Content> for Response {
     }
 }
 
+/// A quest, as it's shown to the character that accepted it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct QuestInfo {
+    pub id: QuestId,
+    pub status: QuestStatus,
+    /// What the character has to do
+    pub objective: Content,
+    /// Name of whoever pays the reward
+    pub giver: Option<String>,
+    /// Name of the site the quest leads to, if any
+    pub target_site: Option<String>,
+    /// Where the objective is, if it's known
+    pub marker: Option<Marker>,
+    pub reward: Option<(Arc<ItemDef>, u32)>,
+    /// The quest fails if it's not completed before this time
+    pub deadline: Option<Time>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuestStatus {
+    /// Accepted, but nothing has been done yet
+    Accepted,
+    Underway,
+    /// The objective was achieved, the reward still has to be claimed from the
+    /// giver
+    Achieved,
+    Completed,
+    /// Failed, timed out or abandoned
+    Failed,
+}
+
+impl QuestStatus {
+    /// Whether the quest still has to be completed
+    pub fn is_active(&self) -> bool {
+        matches!(self, Self::Accepted | Self::Underway | Self::Achieved)
+    }
+}
+
 // Represents a message passed back to rtsim from an agent's brain
 #[derive(Clone, Debug)]
 pub enum NpcInput {
@@ -437,6 +476,24 @@ pub enum TerrainResource {
     Ore, // Iron, copper, etc.
 }
 
+impl TerrainResource {
+    /// The item gathering this resource usually produces, if it's specific
+    /// enough to ask for. Like going from [`TerrainResource`] to
+    /// [`SpriteKind`](crate::terrain::SpriteKind), this picks one of many.
+    pub fn gathered_item(&self) -> Option<ItemResource> {
+        match self {
+            Self::Fruit => Some(ItemResource::Apple),
+            Self::Vegetable => Some(ItemResource::Carrot),
+            Self::Mushroom => Some(ItemResource::Mushroom),
+            Self::Plant => Some(ItemResource::Flax),
+            Self::Stone => Some(ItemResource::Stones),
+            Self::Wood => Some(ItemResource::Wood),
+            Self::Ore => Some(ItemResource::IronOre),
+            Self::Grass | Self::Flower | Self::Loot | Self::Gem => None,
+        }
+    }
+}
+
 /// Like [`TerrainResource`], but for tracking inventory items in rtsim for the
 /// sake of questing, trade, etc.
 ///
@@ -452,6 +509,23 @@ pub enum TerrainResource {
 pub enum ItemResource {
     #[serde(rename = "0")]
     Coin,
+    /// Handed to players that deliver it to another site
+    #[serde(rename = "1")]
+    Parcel,
+    #[serde(rename = "2")]
+    Apple,
+    #[serde(rename = "3")]
+    Carrot,
+    #[serde(rename = "4")]
+    Mushroom,
+    #[serde(rename = "5")]
+    Flax,
+    #[serde(rename = "6")]
+    Stones,
+    #[serde(rename = "7")]
+    Wood,
+    #[serde(rename = "8")]
+    IronOre,
 }
 
 impl ItemResource {
@@ -459,9 +533,18 @@ impl ItemResource {
     // TODO: Return (Arc<ItemDef>, f32) to allow for an exchange rate
     // TODO: Have this function take an `impl RngExt` so that it can be stochastic
     pub fn to_equivalent_item_def(&self) -> Arc<ItemDef> {
-        match self {
-            Self::Coin => Arc::<ItemDef>::load_cloned("common.items.utility.coins").unwrap(),
-        }
+        let specifier = match self {
+            Self::Coin => "common.items.utility.coins",
+            Self::Parcel => "common.items.utility.parcel",
+            Self::Apple => "common.items.food.apple",
+            Self::Carrot => "common.items.food.carrot",
+            Self::Mushroom => "common.items.food.mushroom",
+            Self::Flax => "common.items.flowers.wild_flax",
+            Self::Stones => "common.items.crafting_ing.stones",
+            Self::Wood => "common.items.log.wood",
+            Self::IronOre => "common.items.mineral.ore.iron",
+        };
+        Arc::<ItemDef>::load_cloned(specifier).unwrap()
     }
 }
 
@@ -508,6 +591,8 @@ pub enum Profession {
     Herbalist,
     #[serde(rename = "11")]
     Captain,
+    #[serde(rename = "12")]
+    Caravan,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
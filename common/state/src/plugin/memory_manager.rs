@@ -1,9 +1,10 @@
 use atomic_refcell::AtomicRefCell;
 use common::{
-    comp::{Health, Player},
+    comp::{Body, Buffs, Group, Health, Inventory, Player, Pos, Stats, group::GroupManager},
+    resources::Time,
     uid::{IdMaps, Uid},
 };
-use core::ptr::NonNull;
+use core::{ptr::NonNull, time::Duration};
 use specs::{
     Component, Entities, Entity, Read, ReadStorage, WriteStorage, storage::GenericReadStorage,
 };
@@ -14,7 +15,15 @@ pub struct EcsWorld<'a, 'b> {
     pub health: EcsComponentAccess<'a, 'b, Health>,
     pub uid: EcsComponentAccess<'a, 'b, Uid>,
     pub player: EcsComponentAccess<'a, 'b, Player>,
+    pub pos: EcsComponentAccess<'a, 'b, Pos>,
+    pub inventory: EcsComponentAccess<'a, 'b, Inventory>,
+    pub stats: EcsComponentAccess<'a, 'b, Stats>,
+    pub buffs: EcsComponentAccess<'a, 'b, Buffs>,
+    pub group: EcsComponentAccess<'a, 'b, Group>,
+    pub body: EcsComponentAccess<'a, 'b, Body>,
     pub id_maps: &'b Read<'a, IdMaps>,
+    pub group_manager: &'b Read<'a, GroupManager>,
+    pub time: &'b Read<'a, Time>,
 }
 
 pub enum EcsComponentAccess<'a, 'b, T: Component> {
@@ -51,6 +60,36 @@ impl<'a, T: Component> From<WriteStorage<'a, T>> for EcsComponentAccess<'a, '_,
     fn from(a: WriteStorage<'a, T>) -> Self { Self::WriteOwned(a) }
 }
 
+/// Limits the work a plugin may do in its `tick` export and timer callbacks
+/// during a single server tick.
+///
+/// Running out of fuel, or a single call running longer than the time budget,
+/// traps the plugin, which disables it until it is reloaded. Exceeding the
+/// time budget over several timers postpones the remaining timers to the next
+/// tick.
+#[derive(Clone, Copy, Debug)]
+pub struct TickBudget {
+    /// Wasmtime fuel, roughly the number of executed wasm instructions
+    pub fuel: u64,
+    /// Enforced through wasmtime epoch interruption, with a resolution of 10
+    /// milliseconds
+    pub time: Duration,
+}
+
+impl TickBudget {
+    /// Fuel given to calls outside of the tick, which are not limited
+    pub const UNLIMITED_FUEL: u64 = u64::MAX;
+}
+
+impl Default for TickBudget {
+    fn default() -> Self {
+        Self {
+            fuel: 50_000_000,
+            time: Duration::from_millis(10),
+        }
+    }
+}
+
 /// This structure wraps the ECS pointer to ensure safety
 pub struct EcsAccessManager {
     ecs_pointer: AtomicRefCell<Option<NonNull<EcsWorld<'static, 'static>>>>,
@@ -1,31 +1,120 @@
 pub mod errors;
 pub mod memory_manager;
 pub mod module;
+pub mod storage;
 
 use bincode::error::DecodeError;
-use common::{assets::ASSETS_PATH, event::PluginHash, uid::Uid};
+use common::{
+    assets::ASSETS_PATH,
+    comp::{Body, BuffData, BuffKind},
+    event::PluginHash,
+    uid::Uid,
+};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{HashMap, HashSet},
     fs,
     io::{Read, Write},
     path::{Path, PathBuf},
+    sync::Arc,
 };
 use tracing::{error, info};
 
 use self::{
     errors::{PluginError, PluginModuleError},
-    memory_manager::EcsWorld,
-    module::PluginModule,
+    memory_manager::{EcsWorld, TickBudget},
+    module::{ChatKind, PluginModule},
+    storage::PluginStorage,
 };
+use crate::BlockDiff;
 
 use sha2::Digest;
+use vek::Vec3;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PluginData {
     name: String,
     modules: HashSet<PathBuf>,
     dependencies: HashSet<String>,
+    /// Actions which modify the world, a plugin can only use those it lists
+    /// here
+    #[serde(default)]
+    permissions: HashSet<PluginPermission>,
+}
+
+/// Permissions a plugin has to request in its `plugin.toml` before it is
+/// allowed to use the corresponding world mutating action
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PluginPermission {
+    Teleport,
+    GiveItem,
+    ApplyBuff,
+    SpawnNpc,
+}
+
+/// Game events plugins can subscribe to, except for chat messages which are
+/// handled by [`PluginMgr::chat_event`] as they can be modified.
+///
+/// The server collects these while handling its own events and dispatches them
+/// to the plugins afterwards.
+pub enum PluginEvent {
+    Death {
+        entity: Uid,
+        killer: Option<Uid>,
+    },
+    HealthChange {
+        entity: Uid,
+        amount: f32,
+        by: Option<Uid>,
+    },
+    Trade {
+        parties: [Uid; 2],
+        /// Item asset ids and amounts each party gave away
+        offers: [Vec<(String, u32)>; 2],
+    },
+    BlockChange(Vec<BlockDiff>),
+    Logout {
+        player: Uid,
+    },
+}
+
+/// A world mutation requested by a plugin.
+///
+/// Plugins only get read access to the ECS while they run, so these are
+/// collected and applied by the server after the plugin call returned.
+/// Arguments are validated when the plugin requests the action.
+#[derive(Debug)]
+pub enum PluginAction {
+    SendMessage {
+        target: Uid,
+        text: String,
+    },
+    Teleport {
+        target: Uid,
+        position: Vec3<f32>,
+    },
+    GiveItem {
+        target: Uid,
+        item: String,
+        amount: u32,
+    },
+    ApplyBuff {
+        target: Uid,
+        kind: BuffKind,
+        data: BuffData,
+    },
+    SpawnNpc {
+        body: Body,
+        name: String,
+        position: Vec3<f32>,
+    },
+}
+
+fn is_plugin_archive(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|s| s.ends_with(".plugin.tar"))
 }
 
 fn compute_hash(data: &[u8]) -> PluginHash {
@@ -113,9 +202,11 @@ impl Plugin {
             .iter()
             .map(|path| {
                 let wasm_data = files.remove(path).ok_or(PluginError::NoSuchModule)?;
-                PluginModule::new(data.name.to_owned(), &wasm_data).map_err(|e| {
-                    PluginError::PluginModuleError(data.name.to_owned(), "<init>".to_owned(), e)
-                })
+                PluginModule::new(data.name.to_owned(), &wasm_data, &data.permissions).map_err(
+                    |e| {
+                        PluginError::PluginModuleError(data.name.to_owned(), "<init>".to_owned(), e)
+                    },
+                )
             })
             .collect::<Result<_, _>>()?;
 
@@ -162,6 +253,46 @@ impl Plugin {
         result
     }
 
+    pub fn game_event(&mut self, ecs: &EcsWorld, event: &PluginEvent) {
+        self.modules
+            .iter_mut()
+            .for_each(|module| module.game_event(ecs, event));
+    }
+
+    pub fn chat_event(
+        &mut self,
+        ecs: &EcsWorld,
+        sender: Uid,
+        kind: ChatKind,
+        message: String,
+    ) -> Result<String, Option<String>> {
+        self.modules
+            .iter_mut()
+            .try_fold(message, |message, module| {
+                module.chat_event(ecs, sender, kind, message)
+            })
+    }
+
+    pub fn tick(&mut self, ecs: &EcsWorld, dt: f64, budget: TickBudget) {
+        self.modules
+            .iter_mut()
+            .for_each(|module| module.tick(ecs, dt, budget));
+    }
+
+    pub fn set_storage(&mut self, storage: &Arc<dyn PluginStorage>) {
+        self.modules
+            .iter_mut()
+            .for_each(|module| module.set_storage(Arc::clone(storage)));
+    }
+
+    /// Take the world mutations requested by this plugin since the last call
+    pub fn take_actions(&mut self) -> Vec<PluginAction> {
+        self.modules
+            .iter_mut()
+            .flat_map(|module| module.take_actions())
+            .collect()
+    }
+
     /// get the path to the plugin file
     pub fn path(&self) -> &Path { self.path.as_path() }
 
@@ -197,9 +328,16 @@ impl Plugin {
 #[derive(Default)]
 pub struct PluginMgr {
     plugins: Vec<Plugin>,
+    /// The directory the plugins were loaded from
+    dir: Option<PathBuf>,
+    storage: Option<Arc<dyn PluginStorage>>,
+    tick_budget: TickBudget,
 }
 
 impl PluginMgr {
+    /// The asset directory plugins are loaded from by default
+    pub const ASSETS_ID: &'static str = "plugins";
+
     pub fn from_asset_or_default() -> Self {
         match Self::from_assets() {
             Ok(plugin_mgr) => plugin_mgr,
@@ -210,33 +348,46 @@ impl PluginMgr {
         }
     }
 
+    /// The directory plugins are loaded from by default
+    pub fn assets_dir() -> PathBuf { ASSETS_PATH.join(Self::ASSETS_ID) }
+
+    /// The directory the plugins were loaded from
+    pub fn dir(&self) -> PathBuf { self.dir.clone().unwrap_or_else(Self::assets_dir) }
+
     pub fn from_assets() -> Result<Self, PluginError> {
-        let mut assets_path = (*ASSETS_PATH).clone();
-        assets_path.push("plugins");
+        let assets_path = Self::assets_dir();
         info!("Searching {:?} for plugins...", assets_path);
         Self::from_dir(assets_path)
     }
 
     pub fn from_dir<P: AsRef<Path>>(path: P) -> Result<Self, PluginError> {
+        let plugins = Self::load_plugins(path.as_ref())?;
+        for plugin in &plugins {
+            if let Err(e) = common::assets::register_tar(plugin.path.clone()) {
+                error!("Plugin {:?} tar error {e:?}", plugin.path);
+            }
+        }
+
+        Ok(Self {
+            plugins,
+            dir: Some(path.as_ref().to_path_buf()),
+            storage: None,
+            tick_budget: TickBudget::default(),
+        })
+    }
+
+    /// Load the plugin archives in a directory without registering their
+    /// assets
+    fn load_plugins(path: &Path) -> Result<Vec<Plugin>, PluginError> {
         let plugins = fs::read_dir(path)
             .map_err(PluginError::Io)?
             .filter_map(|e| e.ok())
             .map(|entry| {
                 if entry.file_type().map(|ft| ft.is_file()).unwrap_or(false)
-                    && entry
-                        .path()
-                        .file_name()
-                        .and_then(|n| n.to_str())
-                        .map(|s| s.ends_with(".plugin.tar"))
-                        .unwrap_or(false)
+                    && is_plugin_archive(&entry.path())
                 {
                     info!("Loading plugin at {:?}", entry.path());
-                    Plugin::from_path(entry.path()).map(|plugin| {
-                        if let Err(e) = common::assets::register_tar(entry.path()) {
-                            error!("Plugin {:?} tar error {e:?}", entry.path());
-                        }
-                        Some(plugin)
-                    })
+                    Plugin::from_path(entry.path()).map(Some)
                 } else {
                     Ok(None)
                 }
@@ -254,16 +405,68 @@ impl PluginMgr {
                 plugin.modules.len()
             );
         }
+        Ok(plugins)
+    }
+
+    /// Load all plugins from the plugin directory again, replacing the current
+    /// ones, and call their `load` export which registers their commands and
+    /// event subscriptions again.
+    ///
+    /// The current plugins and their assets are kept if any plugin fails to
+    /// load, otherwise actions they requested but which weren't taken yet are
+    /// dropped. Clients get the new plugins when they connect again. Returns
+    /// the names of the loaded plugins.
+    pub fn reload(
+        &mut self,
+        ecs: &EcsWorld,
+        mode: common::resources::GameMode,
+    ) -> Result<Vec<String>, PluginError> {
+        let dir = self.dir();
+        info!("Reloading plugins from {:?}", dir);
+        let mut plugins = Self::load_plugins(&dir)?;
+        for plugin in &mut plugins {
+            if let Some(storage) = &self.storage {
+                plugin.set_storage(storage);
+            }
+            plugin.load_event(ecs, mode).map_err(|e| {
+                PluginError::PluginModuleError(plugin.data.name.to_owned(), "load".to_owned(), e)
+            })?;
+        }
 
-        Ok(Self { plugins })
+        // Every plugin loaded, swap the archives together with the modules
+        for plugin in &self.plugins {
+            if !plugins.iter().any(|new| new.path == plugin.path) {
+                common::assets::unregister_tar(&plugin.path);
+            }
+        }
+        for plugin in &plugins {
+            if let Err(e) = common::assets::register_tar(plugin.path.clone()) {
+                error!("Plugin {:?} tar error {e:?}", plugin.path);
+            }
+        }
+        *self = Self {
+            plugins,
+            dir: Some(dir),
+            storage: self.storage.take(),
+            tick_budget: self.tick_budget,
+        };
+
+        Ok(self
+            .plugins
+            .iter()
+            .map(|plugin| plugin.data.name.clone())
+            .collect())
     }
 
     /// Add a plugin received from the server
     pub fn load_server_plugin(&mut self, path: PathBuf) -> Result<PluginHash, PluginError> {
-        Plugin::from_path(path.clone()).map(|plugin| {
+        Plugin::from_path(path.clone()).map(|mut plugin| {
             if let Err(e) = common::assets::register_tar(path.clone()) {
                 error!("Plugin {:?} tar error {e:?}", path.as_path());
             }
+            if let Some(storage) = &self.storage {
+                plugin.set_storage(storage);
+            }
             let hash = plugin.hash;
             self.plugins.push(plugin);
             hash
@@ -322,6 +525,60 @@ impl PluginMgr {
         result
     }
 
+    /// Notify all plugins subscribed to this kind of event
+    pub fn game_event(&mut self, ecs: &EcsWorld, event: &PluginEvent) {
+        self.plugins
+            .iter_mut()
+            .for_each(|plugin| plugin.game_event(ecs, event));
+    }
+
+    /// Pass a player chat message through all plugins subscribed to chat.
+    ///
+    /// Returns the text to deliver, which plugins may have modified, or `Err`
+    /// with an optional reason for the sender if a plugin vetoed the message.
+    pub fn chat_event(
+        &mut self,
+        ecs: &EcsWorld,
+        sender: Uid,
+        kind: ChatKind,
+        message: String,
+    ) -> Result<String, Option<String>> {
+        self.plugins
+            .iter_mut()
+            .try_fold(message, |message, plugin| {
+                plugin.chat_event(ecs, sender, kind, message)
+            })
+    }
+
+    /// Run the periodic logic of all plugins, each plugin module gets its own
+    /// [`TickBudget`]
+    pub fn tick(&mut self, ecs: &EcsWorld, dt: f64) {
+        let budget = self.tick_budget;
+        self.plugins
+            .iter_mut()
+            .for_each(|plugin| plugin.tick(ecs, dt, budget));
+    }
+
+    pub fn set_tick_budget(&mut self, budget: TickBudget) { self.tick_budget = budget; }
+
+    /// Give all plugins access to persistent storage, only the server provides
+    /// one
+    pub fn set_storage(&mut self, storage: Arc<dyn PluginStorage>) {
+        self.plugins
+            .iter_mut()
+            .for_each(|plugin| plugin.set_storage(&storage));
+        self.storage = Some(storage);
+    }
+
+    /// Take the world mutations requested by all plugins since the last call,
+    /// these should be applied by the server
+    pub fn take_actions(&mut self) -> Vec<PluginAction> {
+        self.plugins
+            .iter_mut()
+            .flat_map(|plugin| plugin.take_actions())
+            .collect()
+    }
+
     pub fn create_body(&mut self, name: &str) -> Option<module::Body> {
         let mut result = None;
         self.plugins.iter_mut().for_each(|plugin| {
@@ -354,3 +611,108 @@ pub enum CommandResults {
     HostError(wasmtime::Error),
     PluginError(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{
+        comp::{Body, Buffs, Group, Health, Inventory, Player, Pos, Stats, group::GroupManager},
+        resources::{GameMode, Time},
+        uid::IdMaps,
+    };
+    use specs::{World, WorldExt};
+
+    /// Write a plugin archive without modules, or a broken one without a
+    /// `plugin.toml` if `name` is `None`
+    fn write_plugin(dir: &Path, file: &str, name: Option<&str>) {
+        let (path, data) = match name {
+            Some(name) => (
+                "plugin.toml",
+                format!("name = \"{name}\"\nmodules = []\ndependencies = []\n"),
+            ),
+            None => ("readme.txt", "not a plugin".to_owned()),
+        };
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        let mut builder = tar::Builder::new(Vec::new());
+        builder
+            .append_data(&mut header, path, data.as_bytes())
+            .unwrap();
+        fs::write(dir.join(file), builder.into_inner().unwrap()).unwrap();
+    }
+
+    fn reload(plugin_mgr: &mut PluginMgr) -> Result<Vec<String>, PluginError> {
+        let mut ecs = World::new();
+        ecs.register::<Health>();
+        ecs.register::<Uid>();
+        ecs.register::<Player>();
+        ecs.register::<Pos>();
+        ecs.register::<Inventory>();
+        ecs.register::<Stats>();
+        ecs.register::<Buffs>();
+        ecs.register::<Group>();
+        ecs.register::<Body>();
+        ecs.insert(IdMaps::default());
+        ecs.insert(GroupManager::default());
+        ecs.insert(Time::default());
+        let ecs_world = EcsWorld {
+            entities: &ecs.entities(),
+            health: ecs.read_component().into(),
+            uid: ecs.read_component().into(),
+            id_maps: &ecs.read_resource::<IdMaps>().into(),
+            player: ecs.read_component().into(),
+            pos: ecs.read_component().into(),
+            inventory: ecs.read_component().into(),
+            stats: ecs.read_component().into(),
+            buffs: ecs.read_component().into(),
+            group: ecs.read_component().into(),
+            body: ecs.read_component().into(),
+            group_manager: &ecs.read_resource::<GroupManager>().into(),
+            time: &ecs.read_resource::<Time>().into(),
+        };
+        plugin_mgr.reload(&ecs_world, GameMode::Server)
+    }
+
+    fn names(plugin_mgr: &PluginMgr) -> Vec<&str> {
+        let mut names = plugin_mgr
+            .plugins
+            .iter()
+            .map(|plugin| plugin.data.name.as_str())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn reload_swaps_plugins_only_if_all_load() {
+        let dir = std::env::temp_dir().join(format!("veloren-plugins-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        write_plugin(&dir, "a.plugin.tar", Some("a"));
+        // Not a plugin archive, ignored
+        write_plugin(&dir, "c.tar", None);
+        let mut plugin_mgr = PluginMgr::from_dir(&dir).unwrap();
+        assert_eq!(names(&plugin_mgr), ["a"]);
+
+        // A broken archive keeps the current plugins
+        write_plugin(&dir, "b.plugin.tar", None);
+        assert!(matches!(
+            reload(&mut plugin_mgr),
+            Err(PluginError::NoConfig)
+        ));
+        assert_eq!(names(&plugin_mgr), ["a"]);
+
+        write_plugin(&dir, "b.plugin.tar", Some("b"));
+        let mut reloaded = reload(&mut plugin_mgr).unwrap();
+        reloaded.sort();
+        assert_eq!(reloaded, ["a", "b"]);
+        assert_eq!(names(&plugin_mgr), ["a", "b"]);
+
+        fs::remove_file(dir.join("a.plugin.tar")).unwrap();
+        assert_eq!(reload(&mut plugin_mgr).unwrap(), ["b"]);
+        assert_eq!(plugin_mgr.dir(), dir);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
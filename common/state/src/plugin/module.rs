@@ -2,19 +2,28 @@ use std::{
     io,
     num::NonZeroU64,
     pin::Pin,
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, OnceLock},
     task::{Context, Poll},
+    time::{Duration, Instant},
 };
 
 use super::{
-    CommandResults,
+    CommandResults, PluginAction, PluginEvent, PluginPermission,
     errors::PluginModuleError,
-    memory_manager::{EcsAccessManager, EcsWorld},
+    memory_manager::{EcsAccessManager, EcsWorld, TickBudget},
+    storage::{PluginStorage, StorageOp},
+};
+use common::{
+    cmd::BUFF_PARSER,
+    comp::{self, BuffData, item::Item},
+    npc,
+    resources::{GameMode, Secs},
+    terrain::Block,
 };
 use hashbrown::{HashMap, HashSet};
 use tokio::io::AsyncWrite;
 use wasmtime::{
-    Config, Engine, Store,
+    Config, Engine, Store, Trap,
     component::{Component, HasSelf, Linker},
 };
 use wasmtime_wasi::{
@@ -67,12 +76,30 @@ pub struct Entity {
 pub use animation::Body;
 use exports::veloren::plugin::animation;
 pub use types_mod::veloren::plugin::types::{
-    self, CharacterState, Dependency, Skeleton, Transform,
+    self, CharacterState, ChatKind, Dependency, Skeleton, Transform,
 };
-use veloren::plugin::{actions, information};
+use veloren::plugin::{actions, information, storage};
 
 type StoreType = wasmtime::Store<WasiHostCtx>;
 
+/// Calls a `server-events` export, animation plugins don't have these and
+/// return `$default`
+macro_rules! call_server_event {
+    ($wrapper:expr, $default:expr, | $events:ident | $call:expr) => {
+        match $wrapper {
+            PluginWrapper::Full(pl) => {
+                let $events = pl.veloren_plugin_server_events();
+                $call
+            },
+            PluginWrapper::Animation(_) => $default,
+            PluginWrapper::Server(pl) => {
+                let $events = pl.veloren_plugin_server_events();
+                $call
+            },
+        }
+    };
+}
+
 /// This enum abstracts over the different types of plugins we defined
 enum PluginWrapper {
     Full(Plugin),
@@ -139,6 +166,78 @@ impl PluginWrapper {
         }
     }
 
+    fn game_event(&self, store: &mut StoreType, event: &PluginEvent) -> wasmtime::Result<()> {
+        match event {
+            PluginEvent::Death { entity, killer } => {
+                call_server_event!(self, Ok(()), |events| events.call_death(
+                    store,
+                    entity.0.get(),
+                    killer.map(|killer| killer.0.get())
+                ))
+            },
+            PluginEvent::HealthChange { entity, amount, by } => {
+                call_server_event!(self, Ok(()), |events| events.call_health_change(
+                    store,
+                    entity.0.get(),
+                    *amount,
+                    by.map(|by| by.0.get())
+                ))
+            },
+            PluginEvent::Trade { parties, offers } => {
+                let [first_offer, second_offer] = offers.each_ref().map(|offer| {
+                    offer
+                        .iter()
+                        .map(|(item_id, amount)| types::ItemStack {
+                            item_id: item_id.clone(),
+                            amount: *amount,
+                        })
+                        .collect::<Vec<_>>()
+                });
+                call_server_event!(self, Ok(()), |events| events.call_trade(
+                    store,
+                    (parties[0].0.get(), parties[1].0.get()),
+                    &first_offer,
+                    &second_offer
+                ))
+            },
+            PluginEvent::BlockChange(changes) => {
+                let changes = changes
+                    .iter()
+                    .map(|diff| types::BlockDiff {
+                        position: diff.wpos.into_tuple(),
+                        old: block_info(diff.old),
+                        new: block_info(diff.new),
+                    })
+                    .collect::<Vec<_>>();
+                call_server_event!(self, Ok(()), |events| events
+                    .call_block_change(store, &changes))
+            },
+            PluginEvent::Logout { player } => {
+                call_server_event!(self, Ok(()), |events| events
+                    .call_logout(store, player.0.get()))
+            },
+        }
+    }
+
+    fn chat_event(
+        &self,
+        store: &mut StoreType,
+        sender: types::Uid,
+        kind: ChatKind,
+        message: &str,
+    ) -> wasmtime::Result<types::ChatResult> {
+        call_server_event!(self, Ok(types::ChatResult::Allow), |events| events
+            .call_chat(store, sender, kind, message))
+    }
+
+    fn tick(&self, store: &mut StoreType, dt: f64) -> wasmtime::Result<()> {
+        call_server_event!(self, Ok(()), |events| events.call_tick(store, dt))
+    }
+
+    fn timer(&self, store: &mut StoreType, id: types::TimerId) -> wasmtime::Result<()> {
+        call_server_event!(self, Ok(()), |events| events.call_timer(store, id))
+    }
+
     fn create_body(&self, store: &mut StoreType, bodytype: i32) -> Option<animation::Body> {
         match self {
             PluginWrapper::Full(pl) => {
@@ -180,6 +279,52 @@ pub struct PluginModule {
     plugin: PluginWrapper,
     store: Mutex<wasmtime::Store<WasiHostCtx>>,
     name: String,
+    /// Set once the plugin trapped, after which it isn't called anymore
+    disabled: bool,
+}
+
+/// How often the epoch of the plugin engine is incremented, which is the
+/// resolution of the tick time budget. Fuel bounds the work of a tick more
+/// precisely, so this can be coarse.
+const EPOCH_PERIOD: Duration = Duration::from_millis(10);
+
+/// Epoch deadline of calls outside of the tick, which are not limited
+const UNLIMITED_EPOCHS: u64 = u64::MAX / 2;
+
+/// Shared by all plugins, so there's a single thread incrementing the epoch
+static ENGINE: OnceLock<Engine> = OnceLock::new();
+
+/// The engine of all plugins, created with the first one
+fn engine() -> Result<&'static Engine, PluginModuleError> {
+    if let Some(engine) = ENGINE.get() {
+        return Ok(engine);
+    }
+
+    // configure the wasm runtime
+    let mut config = Config::new();
+    config.wasm_component_model(true);
+    config.consume_fuel(true);
+    config.epoch_interruption(true);
+    let engine = Engine::new(&config).map_err(PluginModuleError::Wasmtime)?;
+
+    // Only the thread that stores its engine starts the epoch ticker
+    let mut created = false;
+    let engine = ENGINE.get_or_init(|| {
+        created = true;
+        engine
+    });
+    if created {
+        std::thread::Builder::new()
+            .name("plugin_epoch".into())
+            .spawn(move || {
+                loop {
+                    std::thread::sleep(EPOCH_PERIOD);
+                    engine.increment_epoch();
+                }
+            })
+            .map_err(|e| PluginModuleError::Wasmtime(e.into()))?;
+    }
+    Ok(engine)
 }
 
 struct WasiHostCtx {
@@ -188,6 +333,101 @@ struct WasiHostCtx {
     ecs: Arc<EcsAccessManager>,
     registered_commands: HashSet<String>,
     registered_bodies: HashMap<String, types::BodyIndex>,
+    subscriptions: Vec<types::EventKind>,
+    permissions: HashSet<PluginPermission>,
+    game_mode: Option<GameMode>,
+    pending_actions: Vec<PluginAction>,
+    /// Only the server provides persistent storage
+    storage: Option<Arc<dyn PluginStorage>>,
+    /// Namespace of this plugin in the storage
+    name: String,
+    timers: Vec<Timer>,
+    next_timer_id: types::TimerId,
+}
+
+struct Timer {
+    id: types::TimerId,
+    /// Game time in seconds
+    due: f64,
+    interval: Option<f64>,
+}
+
+impl WasiHostCtx {
+    /// World mutations are only possible where the server state is
+    /// authoritative and if the plugin requested the permission
+    fn check_permission(&self, permission: PluginPermission) -> Result<(), types::Error> {
+        if self.game_mode != Some(GameMode::Client) && self.permissions.contains(&permission) {
+            Ok(())
+        } else {
+            tracing::warn!(?permission, "Plugin action denied");
+            Err(types::Error::PermissionDenied)
+        }
+    }
+
+    /// Checks that an entity with this uid exists
+    fn existing_uid(&self, uid: types::Uid) -> Result<common::uid::Uid, types::Error> {
+        let uid = common::uid::Uid(NonZeroU64::new(uid).ok_or(types::Error::InvalidArgument)?);
+        self.ecs.with(|world| {
+            let world = world.ok_or(types::Error::EcsPointerNotAvailable)?;
+            world
+                .id_maps
+                .uid_entity(uid)
+                .map(|_| uid)
+                .ok_or(types::Error::EcsEntityNotFound)
+        })
+    }
+
+    /// Ids of the timers which expired at `now`, in the order they expired
+    fn due_timers(&self, now: f64) -> Vec<types::TimerId> {
+        let mut due = self
+            .timers
+            .iter()
+            .filter(|timer| timer.due <= now)
+            .map(|timer| (timer.due, timer.id))
+            .collect::<Vec<_>>();
+        due.sort_by(|a, b| a.0.total_cmp(&b.0));
+        due.into_iter().map(|(_, id)| id).collect()
+    }
+
+    /// Reschedules repeating timers and removes the others
+    fn timer_fired(&mut self, id: types::TimerId, now: f64) {
+        if let Some(index) = self.timers.iter().position(|timer| timer.id == id) {
+            match self.timers[index].interval {
+                Some(interval) => self.timers[index].due = now + interval,
+                None => {
+                    self.timers.swap_remove(index);
+                },
+            }
+        }
+    }
+
+    fn storage(&self) -> Result<&dyn PluginStorage, types::Error> {
+        self.storage
+            .as_deref()
+            .ok_or_else(|| types::Error::StorageError("Storage is not available".to_owned()))
+    }
+
+    /// Calls `f` with the ecs entity the resource refers to
+    fn with_entity<R>(
+        &mut self,
+        entity: &wasmtime::component::Resource<information::Entity>,
+        f: impl FnOnce(&EcsWorld, specs::Entity) -> Result<R, types::Error>,
+    ) -> Result<R, types::Error> {
+        let uid = self
+            .ctx()
+            .table
+            .get(entity)
+            .map_err(|_err| types::Error::RuntimeError)?
+            .uid;
+        self.ecs.with(|world| {
+            let world = world.ok_or(types::Error::EcsPointerNotAvailable)?;
+            let entity = world
+                .id_maps
+                .uid_entity(uid)
+                .ok_or(types::Error::EcsEntityNotFound)?;
+            f(world, entity)
+        })
+    }
 }
 
 impl WasiView for WasiHostCtx {
@@ -203,19 +443,166 @@ impl information::Host for WasiHostCtx {}
 
 impl types::Host for WasiHostCtx {}
 
+impl storage::Host for WasiHostCtx {
+    fn get(&mut self, key: String) -> Result<Option<Vec<u8>>, types::Error> {
+        self.storage()?
+            .get(&self.name, &key)
+            .map_err(types::Error::StorageError)
+    }
+
+    fn set(&mut self, key: String, value: Vec<u8>) -> Result<(), types::Error> {
+        self.commit(vec![storage::StorageOp::Set((key, value))])
+    }
+
+    fn remove(&mut self, key: String) -> Result<(), types::Error> {
+        self.commit(vec![storage::StorageOp::Remove(key)])
+    }
+
+    fn keys(&mut self, prefix: String) -> Result<Vec<String>, types::Error> {
+        self.storage()?
+            .keys(&self.name, &prefix)
+            .map_err(types::Error::StorageError)
+    }
+
+    fn commit(&mut self, ops: Vec<storage::StorageOp>) -> Result<(), types::Error> {
+        let ops = ops
+            .into_iter()
+            .map(|op| match op {
+                storage::StorageOp::Set((key, value)) => StorageOp::Set { key, value },
+                storage::StorageOp::Remove(key) => StorageOp::Remove { key },
+            })
+            .collect();
+        self.storage()?
+            .commit(&self.name, ops)
+            .map_err(types::Error::StorageError)
+    }
+}
+
 impl actions::Host for WasiHostCtx {
     fn register_command(&mut self, name: String) {
         tracing::info!("Plugin registers /{name}");
         self.registered_commands.insert(name);
     }
 
+    fn subscribe(&mut self, kind: types::EventKind) {
+        tracing::info!("Plugin subscribes to {kind:?} events");
+        if !self.subscriptions.contains(&kind) {
+            self.subscriptions.push(kind);
+        }
+    }
+
+    fn schedule_timer(&mut self, delay: f64, repeat: bool) -> Result<types::TimerId, types::Error> {
+        if !delay.is_finite() || delay < 0.0 || (repeat && delay == 0.0) {
+            return Err(types::Error::InvalidArgument);
+        }
+        let now = self.ecs.with(|world| {
+            world
+                .map(|world| world.time.0)
+                .ok_or(types::Error::EcsPointerNotAvailable)
+        })?;
+        self.next_timer_id += 1;
+        let id = self.next_timer_id;
+        self.timers.push(Timer {
+            id,
+            due: now + delay,
+            interval: repeat.then_some(delay),
+        });
+        Ok(id)
+    }
+
+    fn cancel_timer(&mut self, id: types::TimerId) { self.timers.retain(|timer| timer.id != id); }
+
     fn player_send_message(&mut self, uid: actions::Uid, text: String) {
         tracing::info!("Plugin sends message {text} to player {uid:?}");
+        if let Ok(target) = self.existing_uid(uid) {
+            self.pending_actions
+                .push(PluginAction::SendMessage { target, text });
+        }
     }
 
     fn register_animation(&mut self, name: String, id: types::BodyIndex) {
         let _ = self.registered_bodies.insert(name, id);
     }
+
+    fn teleport(&mut self, uid: actions::Uid, position: types::Vec3) -> Result<(), types::Error> {
+        self.check_permission(PluginPermission::Teleport)?;
+        let target = self.existing_uid(uid)?;
+        let position = vek::Vec3::from(position);
+        if !position.map(f32::is_finite).reduce_and() {
+            return Err(types::Error::InvalidArgument);
+        }
+        self.pending_actions
+            .push(PluginAction::Teleport { target, position });
+        Ok(())
+    }
+
+    fn give_item(
+        &mut self,
+        uid: actions::Uid,
+        item_id: String,
+        amount: u32,
+    ) -> Result<(), types::Error> {
+        self.check_permission(PluginPermission::GiveItem)?;
+        let target = self.existing_uid(uid)?;
+        if amount == 0 || Item::new_from_asset(&item_id).is_err() {
+            return Err(types::Error::InvalidArgument);
+        }
+        self.pending_actions.push(PluginAction::GiveItem {
+            target,
+            item: item_id,
+            amount,
+        });
+        Ok(())
+    }
+
+    fn apply_buff(
+        &mut self,
+        uid: actions::Uid,
+        kind: String,
+        strength: f32,
+        duration: Option<f64>,
+    ) -> Result<(), types::Error> {
+        self.check_permission(PluginPermission::ApplyBuff)?;
+        let target = self.existing_uid(uid)?;
+        let kind = BUFF_PARSER
+            .get(&kind)
+            .copied()
+            // buffs which need extra data can't be described by the plugin yet
+            .filter(|kind| kind.is_simple())
+            .ok_or(types::Error::InvalidArgument)?;
+        if !strength.is_finite() || duration.is_some_and(|d| !d.is_finite() || d < 0.0) {
+            return Err(types::Error::InvalidArgument);
+        }
+        self.pending_actions.push(PluginAction::ApplyBuff {
+            target,
+            kind,
+            data: BuffData::new(strength, duration.map(Secs)),
+        });
+        Ok(())
+    }
+
+    fn spawn_npc(
+        &mut self,
+        body: String,
+        position: types::Vec3,
+        name: Option<String>,
+    ) -> Result<(), types::Error> {
+        self.check_permission(PluginPermission::SpawnNpc)?;
+        let npc::NpcBody(kind, mut body) =
+            body.parse().map_err(|()| types::Error::InvalidArgument)?;
+        let position = vek::Vec3::from(position);
+        if !position.map(f32::is_finite).reduce_and() {
+            return Err(types::Error::InvalidArgument);
+        }
+        let body = body();
+        let name = name.unwrap_or_else(|| npc::get_npc_name(kind, npc::BodyType::from_body(body)));
+        self.pending_actions.push(PluginAction::SpawnNpc {
+            body,
+            name,
+            position,
+        });
+        Ok(())
+    }
 }
 
 impl information::HostEntity for WasiHostCtx {
@@ -235,21 +622,10 @@ impl information::HostEntity for WasiHostCtx {
         &mut self,
         self_: wasmtime::component::Resource<information::Entity>,
     ) -> Result<information::Health, types::Error> {
-        let uid = self
-            .ctx()
-            .table
-            .get(&self_)
-            .map_err(|_err| types::Error::RuntimeError)?
-            .uid;
-        self.ecs.with(|world| {
-            let world = world.ok_or(types::Error::EcsPointerNotAvailable)?;
-            let player = world
-                .id_maps
-                .uid_entity(uid)
-                .ok_or(types::Error::EcsEntityNotFound)?;
+        self.with_entity(&self_, |world, entity| {
             world
                 .health
-                .get(player)
+                .get(entity)
                 .map(|health| information::Health {
                     current: health.current(),
                     base_max: health.base_max(),
@@ -263,27 +639,139 @@ impl information::HostEntity for WasiHostCtx {
         &mut self,
         self_: wasmtime::component::Resource<information::Entity>,
     ) -> Result<String, types::Error> {
-        let uid = self
-            .ctx()
-            .table
-            .get(&self_)
-            .map_err(|_err| types::Error::RuntimeError)?
-            .uid;
-        self.ecs.with(|world| {
-            let world = world.ok_or(types::Error::EcsPointerNotAvailable)?;
-            let player = world
-                .id_maps
-                .uid_entity(uid)
-                .ok_or(types::Error::EcsEntityNotFound)?;
+        self.with_entity(&self_, |world, entity| {
             Ok(world
                 .player
-                .get(player)
+                .get(entity)
                 .ok_or(types::Error::EcsComponentNotFound)?
                 .alias
                 .to_owned())
         })
     }
 
+    fn position(
+        &mut self,
+        self_: wasmtime::component::Resource<information::Entity>,
+    ) -> Result<types::Vec3, types::Error> {
+        self.with_entity(&self_, |world, entity| {
+            world
+                .pos
+                .get(entity)
+                .map(|pos| pos.0.into_tuple())
+                .ok_or(types::Error::EcsComponentNotFound)
+        })
+    }
+
+    fn inventory(
+        &mut self,
+        self_: wasmtime::component::Resource<information::Entity>,
+    ) -> Result<Vec<information::ItemStack>, types::Error> {
+        self.with_entity(&self_, |world, entity| {
+            let inventory = world
+                .inventory
+                .get(entity)
+                .ok_or(types::Error::EcsComponentNotFound)?;
+            Ok(inventory
+                .slots()
+                .flatten()
+                .map(|item| information::ItemStack {
+                    item_id: item.persistence_item_id(),
+                    amount: item.amount(),
+                })
+                .collect())
+        })
+    }
+
+    fn stats(
+        &mut self,
+        self_: wasmtime::component::Resource<information::Entity>,
+    ) -> Result<information::EntityStats, types::Error> {
+        self.with_entity(&self_, |world, entity| {
+            world
+                .stats
+                .get(entity)
+                .map(|stats| information::EntityStats {
+                    damage_reduction: stats.damage_reduction.modifier(),
+                    poise_reduction: stats.poise_reduction.modifier(),
+                    max_health_modifier: stats.max_health_modifiers.mult_mod,
+                    move_speed_modifier: stats.move_speed_modifier,
+                    attack_speed_modifier: stats.attack_speed_modifier,
+                    attack_damage_modifier: stats.attack_damage_modifier,
+                })
+                .ok_or(types::Error::EcsComponentNotFound)
+        })
+    }
+
+    fn buffs(
+        &mut self,
+        self_: wasmtime::component::Resource<information::Entity>,
+    ) -> Result<Vec<information::BuffInfo>, types::Error> {
+        self.with_entity(&self_, |world, entity| {
+            let buffs = world
+                .buffs
+                .get(entity)
+                .ok_or(types::Error::EcsComponentNotFound)?;
+            let now = world.time.0;
+            Ok(buffs
+                .buffs
+                .values()
+                .filter_map(|buff| {
+                    let kind = BUFF_PARSER
+                        .iter()
+                        .find(|(_, kind)| **kind == buff.kind)?
+                        .0
+                        .clone();
+                    Some(information::BuffInfo {
+                        kind,
+                        strength: buff.data.strength,
+                        duration: buff.end_time.map(|end| (end.0 - now).max(0.0)),
+                    })
+                })
+                .collect())
+        })
+    }
+
+    fn group(
+        &mut self,
+        self_: wasmtime::component::Resource<information::Entity>,
+    ) -> Result<Option<information::GroupInfo>, types::Error> {
+        self.with_entity(&self_, |world, entity| {
+            let Some(info) = world
+                .group
+                .get(entity)
+                .and_then(|group| world.group_manager.group_info(*group))
+            else {
+                return Ok(None);
+            };
+            let leader = world
+                .uid
+                .get(info.leader)
+                .ok_or(types::Error::EcsComponentNotFound)?;
+            Ok(Some(information::GroupInfo {
+                name: info.name.clone(),
+                leader: leader.0.get(),
+                member_count: info.num_members,
+            }))
+        })
+    }
+
+    fn body(
+        &mut self,
+        self_: wasmtime::component::Resource<information::Entity>,
+    ) -> Result<information::BodyInfo, types::Error> {
+        self.with_entity(&self_, |world, entity| {
+            world
+                .body
+                .get(entity)
+                .map(|body| information::BodyInfo {
+                    kind: body_kind_name(body).to_owned(),
+                    height: body.height(),
+                    mass: body.mass().0,
+                })
+                .ok_or(types::Error::EcsComponentNotFound)
+        })
+    }
+
     fn drop(
         &mut self,
         rep: wasmtime::component::Resource<information::Entity>,
@@ -292,6 +780,47 @@ impl information::HostEntity for WasiHostCtx {
     }
 }
 
+fn block_info(block: Block) -> types::BlockInfo {
+    types::BlockInfo {
+        kind: format!("{:?}", block.kind()),
+        sprite: block.get_sprite().map(|sprite| format!("{sprite:?}")),
+    }
+}
+
+fn event_kind(event: &PluginEvent) -> types::EventKind {
+    match event {
+        PluginEvent::Death { .. } => types::EventKind::Death,
+        PluginEvent::HealthChange { .. } => types::EventKind::HealthChange,
+        PluginEvent::Trade { .. } => types::EventKind::Trade,
+        PluginEvent::BlockChange(_) => types::EventKind::BlockChange,
+        PluginEvent::Logout { .. } => types::EventKind::Logout,
+    }
+}
+
+fn body_kind_name(body: &comp::Body) -> &'static str {
+    match body {
+        comp::Body::Humanoid(_) => "humanoid",
+        comp::Body::QuadrupedSmall(_) => "quadruped-small",
+        comp::Body::QuadrupedMedium(_) => "quadruped-medium",
+        comp::Body::BirdMedium(_) => "bird-medium",
+        comp::Body::FishMedium(_) => "fish-medium",
+        comp::Body::Dragon(_) => "dragon",
+        comp::Body::BirdLarge(_) => "bird-large",
+        comp::Body::FishSmall(_) => "fish-small",
+        comp::Body::BipedLarge(_) => "biped-large",
+        comp::Body::BipedSmall(_) => "biped-small",
+        comp::Body::Object(_) => "object",
+        comp::Body::Golem(_) => "golem",
+        comp::Body::Theropod(_) => "theropod",
+        comp::Body::QuadrupedLow(_) => "quadruped-low",
+        comp::Body::Ship(_) => "ship",
+        comp::Body::Arthropod(_) => "arthropod",
+        comp::Body::Item(_) => "item",
+        comp::Body::Crustacean(_) => "crustacean",
+        comp::Body::Plugin(_) => "plugin",
+    }
+}
+
 struct InfoStream(String);
 
 impl AsyncWrite for InfoStream {
@@ -362,14 +891,14 @@ impl StdoutStream for LogStream {
 
 impl PluginModule {
     /// This function takes bytes from a WASM File and compile them
-    pub fn new(name: String, wasm_data: &[u8]) -> Result<Self, PluginModuleError> {
+    pub fn new(
+        name: String,
+        wasm_data: &[u8],
+        permissions: &std::collections::HashSet<PluginPermission>,
+    ) -> Result<Self, PluginModuleError> {
         let ecs = Arc::new(EcsAccessManager::default());
 
-        // configure the wasm runtime
-        let mut config = Config::new();
-        config.wasm_component_model(true);
-
-        let engine = Engine::new(&config).map_err(PluginModuleError::Wasmtime)?;
+        let engine = engine()?;
         // create a WASI environment (std implementing system calls)
         let wasi = WasiCtxBuilder::new()
             .stdout(LogStream(name.clone(), tracing::Level::INFO))
@@ -381,16 +910,29 @@ impl PluginModule {
             ecs: Arc::clone(&ecs),
             registered_commands: HashSet::new(),
             registered_bodies: HashMap::new(),
+            subscriptions: Vec::new(),
+            permissions: permissions.iter().copied().collect(),
+            game_mode: None,
+            pending_actions: Vec::new(),
+            storage: None,
+            name: name.clone(),
+            timers: Vec::new(),
+            next_timer_id: 0,
         };
         // the store contains all data of a wasm instance
-        let mut store = Store::new(&engine, host_ctx);
+        let mut store = Store::new(engine, host_ctx);
+        // only the tick is limited, see `TickBudget`
+        store
+            .set_fuel(TickBudget::UNLIMITED_FUEL)
+            .map_err(PluginModuleError::Wasmtime)?;
+        store.set_epoch_deadline(UNLIMITED_EPOCHS);
 
         // load wasm from binary
         let module =
-            Component::from_binary(&engine, wasm_data).map_err(PluginModuleError::Wasmtime)?;
+            Component::from_binary(engine, wasm_data).map_err(PluginModuleError::Wasmtime)?;
 
         // register WASI and Veloren methods with the runtime
-        let mut linker = Linker::new(&engine);
+        let mut linker = Linker::new(engine);
         wasmtime_wasi::p2::add_to_linker_sync(&mut linker).map_err(PluginModuleError::Wasmtime)?;
         Plugin::add_to_linker::<_, HasSelf<_>>(&mut linker, |x| x)
             .map_err(PluginModuleError::Wasmtime)?;
@@ -413,21 +955,36 @@ impl PluginModule {
             ecs,
             store: store.into(),
             name,
+            disabled: false,
         })
     }
 
     pub fn name(&self) -> &str { &self.name }
 
+    /// Stop calling into the plugin once it trapped, e.g. because it ran out
+    /// of its tick budget, as its state can't be trusted anymore
+    fn disable_on_trap(&mut self, err: &wasmtime::Error) {
+        if let Some(trap) = err.downcast_ref::<Trap>() {
+            tracing::error!(%trap, name = %self.name, "Plugin trapped, disabling it");
+            self.disabled = true;
+        }
+    }
+
     // Implementation of the commands called from veloren and provided in plugins
     pub fn load_event(
         &mut self,
         ecs: &EcsWorld,
         mode: common::resources::GameMode,
     ) -> Result<(), PluginModuleError> {
+        if self.disabled {
+            return Ok(());
+        }
+        self.store.get_mut().unwrap().data_mut().game_mode = Some(mode);
         self.ecs
             .execute_with(ecs, || {
                 self.plugin.load_event(self.store.get_mut().unwrap(), mode)
             })
+            .inspect_err(|err| self.disable_on_trap(err))
             .map_err(PluginModuleError::Wasmtime)
     }
 
@@ -438,27 +995,28 @@ impl PluginModule {
         args: &[String],
         player: common::uid::Uid,
     ) -> Result<Vec<String>, CommandResults> {
-        if !self
-            .store
-            .get_mut()
-            .unwrap()
-            .data()
-            .registered_commands
-            .contains(name)
+        if self.disabled
+            || !self
+                .store
+                .get_mut()
+                .unwrap()
+                .data()
+                .registered_commands
+                .contains(name)
         {
             return Err(CommandResults::UnknownCommand);
         }
-        self.ecs.execute_with(ecs, || {
-            match self.plugin.command_event(
-                self.store.get_mut().unwrap(),
-                name,
-                args,
-                player.0.into(),
-            ) {
-                Err(err) => Err(CommandResults::HostError(err)),
-                Ok(result) => result.map_err(CommandResults::PluginError),
-            }
-        })
+        let result = self.ecs.execute_with(ecs, || {
+            self.plugin
+                .command_event(self.store.get_mut().unwrap(), name, args, player.0.into())
+        });
+        match result {
+            Err(err) => {
+                self.disable_on_trap(&err);
+                Err(CommandResults::HostError(err))
+            },
+            Ok(result) => result.map_err(CommandResults::PluginError),
+        }
     }
 
     pub fn player_join_event(
@@ -467,22 +1025,139 @@ impl PluginModule {
         name: &str,
         uuid: common::uuid::Uuid,
     ) -> types::JoinResult {
-        self.ecs.execute_with(ecs, || {
-            match self.plugin.player_join_event(
+        if self.disabled {
+            return types::JoinResult::None;
+        }
+        let result = self.ecs.execute_with(ecs, || {
+            self.plugin
+                .player_join_event(self.store.get_mut().unwrap(), name, uuid.as_u64_pair())
+        });
+        match result {
+            Ok(value) => {
+                tracing::info!("JoinResult {value:?}");
+                value
+            },
+            Err(err) => {
+                tracing::error!("join_event: {err:?}");
+                self.disable_on_trap(&err);
+                types::JoinResult::None
+            },
+        }
+    }
+
+    fn is_subscribed(&mut self, kind: types::EventKind) -> bool {
+        !self.disabled
+            && self
+                .store
+                .get_mut()
+                .unwrap()
+                .data()
+                .subscriptions
+                .contains(&kind)
+    }
+
+    pub fn game_event(&mut self, ecs: &EcsWorld, event: &PluginEvent) {
+        if !self.is_subscribed(event_kind(event)) {
+            return;
+        }
+        if let Err(err) = self.ecs.execute_with(ecs, || {
+            self.plugin.game_event(self.store.get_mut().unwrap(), event)
+        }) {
+            tracing::error!(?err, name = %self.name, "Plugin failed to handle game event");
+            self.disable_on_trap(&err);
+        }
+    }
+
+    pub fn chat_event(
+        &mut self,
+        ecs: &EcsWorld,
+        sender: common::uid::Uid,
+        kind: ChatKind,
+        message: String,
+    ) -> Result<String, Option<String>> {
+        if !self.is_subscribed(types::EventKind::Chat) {
+            return Ok(message);
+        }
+        let result = self.ecs.execute_with(ecs, || {
+            self.plugin.chat_event(
                 self.store.get_mut().unwrap(),
-                name,
-                uuid.as_u64_pair(),
-            ) {
-                Ok(value) => {
-                    tracing::info!("JoinResult {value:?}");
-                    value
-                },
-                Err(err) => {
-                    tracing::error!("join_event: {err:?}");
-                    types::JoinResult::None
-                },
+                sender.0.get(),
+                kind,
+                &message,
+            )
+        });
+        match result {
+            Ok(types::ChatResult::Allow) => Ok(message),
+            Ok(types::ChatResult::Modify(text)) => Ok(text),
+            Ok(types::ChatResult::Veto(reason)) => Err(reason),
+            Err(err) => {
+                tracing::error!(?err, "chat_event");
+                self.disable_on_trap(&err);
+                Ok(message)
+            },
+        }
+    }
+
+    pub fn set_storage(&mut self, storage: Arc<dyn PluginStorage>) {
+        self.store.get_mut().unwrap().data_mut().storage = Some(storage);
+    }
+
+    /// Call the `tick` export if subscribed and fire expired timers, within
+    /// the limits of `budget`
+    pub fn tick(&mut self, ecs: &EcsWorld, dt: f64, budget: TickBudget) {
+        if self.disabled {
+            return;
+        }
+        let now = ecs.time.0;
+        let store = self.store.get_mut().unwrap();
+        let tick = store.data().subscriptions.contains(&types::EventKind::Tick);
+        let due = store.data().due_timers(now);
+        if !tick && due.is_empty() {
+            return;
+        }
+        if let Err(err) = store.set_fuel(budget.fuel) {
+            tracing::error!(?err, name = %self.name, "Failed to set plugin fuel");
+            return;
+        }
+        // A single call is interrupted once it runs past the time budget. The
+        // epoch may be incremented right after setting the deadline, so one more is
+        // needed to give it at least the whole budget.
+        let deadline = (budget.time.as_nanos() / EPOCH_PERIOD.as_nanos()) as u64 + 1;
+        let start = Instant::now();
+        let result = self.ecs.execute_with(ecs, || {
+            let store = self.store.get_mut().unwrap();
+            if tick {
+                store.set_epoch_deadline(deadline);
+                self.plugin.tick(store, dt)?;
             }
-        })
+            for id in due {
+                if start.elapsed() > budget.time {
+                    tracing::warn!(
+                        name = %self.name,
+                        "Plugin exceeded its tick time budget, postponing timers"
+                    );
+                    break;
+                }
+                store.data_mut().timer_fired(id, now);
+                store.set_epoch_deadline(deadline);
+                self.plugin.timer(store, id)?;
+            }
+            Ok::<_, wasmtime::Error>(())
+        });
+        let store = self.store.get_mut().unwrap();
+        store.set_epoch_deadline(UNLIMITED_EPOCHS);
+        if let Err(err) = store.set_fuel(TickBudget::UNLIMITED_FUEL) {
+            tracing::error!(?err, name = %self.name, "Failed to set plugin fuel");
+        }
+        if let Err(err) = result {
+            tracing::error!(?err, name = %self.name, "Plugin tick failed");
+            self.disable_on_trap(&err);
+        }
+    }
+
+    /// Take the world mutations this module requested since the last call
+    pub fn take_actions(&mut self) -> Vec<PluginAction> {
+        std::mem::take(&mut self.store.get_mut().unwrap().data_mut().pending_actions)
     }
 
     pub fn create_body(&mut self, bodytype: &str) -> Option<animation::Body> {
@@ -0,0 +1,27 @@
+//! Persistent key/value storage for plugins.
+//!
+//! The plugin runtime only knows about this interface, the server provides the
+//! actual backend (a table in its database) with [`PluginMgr::set_storage`].
+//! Every plugin gets its own namespace, named after the plugin.
+//!
+//! [`PluginMgr::set_storage`]: super::PluginMgr::set_storage
+
+/// A single write, see [`PluginStorage::commit`]
+#[derive(Clone, Debug)]
+pub enum StorageOp {
+    Set { key: String, value: Vec<u8> },
+    Remove { key: String },
+}
+
+pub trait PluginStorage: Send + Sync {
+    fn get(&self, namespace: &str, key: &str) -> Result<Option<Vec<u8>>, String>;
+
+    /// All keys of the namespace starting with `prefix`, in lexicographic order
+    fn keys(&self, namespace: &str, prefix: &str) -> Result<Vec<String>, String>;
+
+    /// Apply all writes in a single transaction, either all or none of them
+    /// take effect. Only returns `Ok` once the writes are stored, fails if the
+    /// backend couldn't store them or the namespace would grow beyond its
+    /// limits.
+    fn commit(&self, namespace: &str, ops: Vec<StorageOp>) -> Result<(), String>;
+}
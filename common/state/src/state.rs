@@ -377,6 +377,14 @@ impl State {
                 uid: ecs.read_component().into(),
                 id_maps: &ecs.read_resource::<IdMaps>().into(),
                 player: ecs.read_component().into(),
+                pos: ecs.read_component().into(),
+                inventory: ecs.read_component().into(),
+                stats: ecs.read_component().into(),
+                buffs: ecs.read_component().into(),
+                group: ecs.read_component().into(),
+                body: ecs.read_component().into(),
+                group_manager: &ecs.read_resource::<comp::group::GroupManager>().into(),
+                time: &ecs.read_resource::<Time>().into(),
             };
             if let Err(e) = plugin_mgr.load_event(&ecs_world, game_mode) {
                 tracing::debug!(?e, "Failed to run plugin init");
@@ -0,0 +1,394 @@
+//! Encryption for channels whose transport doesn't provide it, e.g. TCP.
+//!
+//! Both sides exchange ephemeral X25519 keys during the handshake. Like in the
+//! Noise `NK` pattern, the responder is authenticated by a static key the
+//! initializer knows beforehand, e.g. because it was published with the server
+//! address. ring has no static X25519 keys, so instead of mixing in a second
+//! Diffie-Hellman the responder signs the handshake transcript with its Ed25519
+//! [`StaticKey`]. The initializer aborts unless the signature matches the
+//! [`StaticPublicKey`] it pinned, so a man-in-the-middle can't run two
+//! separate key exchanges.
+//!
+//! Each direction derives its own ChaCha20-Poly1305 key from the shared secret
+//! and the hash of the handshake transcript, so tampering with any handshake
+//! frame results in different keys on both sides and the first encrypted frame
+//! fails to decrypt. Every record is authenticated, a modified, replayed or
+//! reordered record closes the channel.
+use crate::frame::InitFrame;
+use bytes::{Buf, BufMut, BytesMut};
+use ring::{aead, agreement, digest, hkdf, rand::SystemRandom, signature};
+
+pub(crate) const PUBLIC_KEY_SIZE: usize = 32;
+pub(crate) const SIGNATURE_SIZE: usize = 64;
+const KEY_SALT: &[u8] = b"veloren network encryption v2";
+const SIGNATURE_CONTEXT: &[u8] = b"veloren network key exchange v2";
+const INITIALIZER_INFO: &[u8] = b"initializer";
+const RESPONDER_INFO: &[u8] = b"responder";
+/// Bigger buffers are split into multiple records, so the receiver never
+/// needs to buffer more than this
+const MAX_RECORD_SIZE: usize = 1 << 16;
+const RECORD_HEADER_SIZE: usize = 4;
+const TAG_SIZE: usize = 16;
+
+/// Long-term Ed25519 key the responder authenticates itself with, e.g. a
+/// server. Connecting sides pin its [`StaticPublicKey`].
+pub struct StaticKey(signature::Ed25519KeyPair);
+
+/// The public half of a [`StaticKey`]. Displayed and parsed as hex, so it can
+/// be published and put into settings.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StaticPublicKey(pub [u8; PUBLIC_KEY_SIZE]);
+
+/// The string isn't a [`StaticPublicKey`] in hex
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidStaticPublicKey;
+
+/// Hash of all handshake frames so far, in the order they were sent
+#[derive(Clone)]
+pub(crate) struct Transcript(digest::Context);
+
+/// Local half of the key exchange
+pub(crate) struct KeyExchange {
+    private_key: agreement::EphemeralPrivateKey,
+    public_key: [u8; PUBLIC_KEY_SIZE],
+}
+
+/// Encrypts or decrypts one direction of a channel
+#[derive(Debug)]
+pub(crate) struct Cipher {
+    key: aead::LessSafeKey,
+    /// Each key is only used for one direction, so a counter never repeats
+    /// a nonce
+    counter: u64,
+}
+
+impl StaticKey {
+    /// Generates a new key, returns it together with its PKCS#8 document to
+    /// store it
+    pub fn generate() -> (Self, Vec<u8>) {
+        let pkcs8 = signature::Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+            .expect("system random number generator failed");
+        let key = Self::from_pkcs8(pkcs8.as_ref()).expect("generated key is valid");
+        (key, pkcs8.as_ref().to_vec())
+    }
+
+    /// Loads a key stored by [`generate`]
+    ///
+    /// [`generate`]: StaticKey::generate
+    pub fn from_pkcs8(pkcs8: &[u8]) -> Result<Self, ring::error::KeyRejected> {
+        signature::Ed25519KeyPair::from_pkcs8(pkcs8).map(Self)
+    }
+
+    pub fn public_key(&self) -> StaticPublicKey {
+        let mut public_key = [0u8; PUBLIC_KEY_SIZE];
+        public_key.copy_from_slice(signature::KeyPair::public_key(&self.0).as_ref());
+        StaticPublicKey(public_key)
+    }
+
+    pub(crate) fn sign(&self, transcript: &Transcript) -> [u8; SIGNATURE_SIZE] {
+        let mut signature = [0u8; SIGNATURE_SIZE];
+        signature.copy_from_slice(self.0.sign(&transcript.signed_message()).as_ref());
+        signature
+    }
+}
+
+impl core::fmt::Debug for StaticKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("StaticKey")
+            .field(&self.public_key())
+            .finish()
+    }
+}
+
+impl StaticPublicKey {
+    pub(crate) fn verify(&self, transcript: &Transcript, signature: &[u8; SIGNATURE_SIZE]) -> bool {
+        signature::UnparsedPublicKey::new(&signature::ED25519, &self.0)
+            .verify(&transcript.signed_message(), signature)
+            .is_ok()
+    }
+}
+
+impl core::fmt::Display for StaticPublicKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+impl core::fmt::Debug for StaticPublicKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "StaticPublicKey({self})")
+    }
+}
+
+impl core::str::FromStr for StaticPublicKey {
+    type Err = InvalidStaticPublicKey;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().as_bytes();
+        if s.len() != PUBLIC_KEY_SIZE * 2 {
+            return Err(InvalidStaticPublicKey);
+        }
+        let mut key = [0u8; PUBLIC_KEY_SIZE];
+        for (byte, hex) in key.iter_mut().zip(s.chunks(2)) {
+            let hex = core::str::from_utf8(hex).map_err(|_| InvalidStaticPublicKey)?;
+            *byte = u8::from_str_radix(hex, 16).map_err(|_| InvalidStaticPublicKey)?;
+        }
+        Ok(Self(key))
+    }
+}
+
+impl core::fmt::Display for InvalidStaticPublicKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "expected {} hex digits", PUBLIC_KEY_SIZE * 2)
+    }
+}
+
+impl std::error::Error for InvalidStaticPublicKey {}
+
+impl Transcript {
+    pub(crate) fn new() -> Self { Self(digest::Context::new(&digest::SHA256)) }
+
+    pub(crate) fn add_frame(&mut self, frame: &InitFrame) {
+        let mut bytes = BytesMut::new();
+        frame.clone().write_bytes(&mut bytes);
+        self.0.update(&bytes);
+    }
+
+    pub(crate) fn add_key(&mut self, key: &[u8; PUBLIC_KEY_SIZE]) { self.0.update(key); }
+
+    fn hash(&self) -> digest::Digest { self.0.clone().finish() }
+
+    fn signed_message(&self) -> Vec<u8> { [SIGNATURE_CONTEXT, self.hash().as_ref()].concat() }
+}
+
+impl KeyExchange {
+    pub(crate) fn new() -> Self {
+        let private_key =
+            agreement::EphemeralPrivateKey::generate(&agreement::X25519, &SystemRandom::new())
+                .expect("system random number generator failed");
+        let mut public_key = [0u8; PUBLIC_KEY_SIZE];
+        public_key.copy_from_slice(
+            private_key
+                .compute_public_key()
+                .expect("X25519 public key can always be computed")
+                .as_ref(),
+        );
+        Self {
+            private_key,
+            public_key,
+        }
+    }
+
+    pub(crate) fn public_key(&self) -> [u8; PUBLIC_KEY_SIZE] { self.public_key }
+
+    /// Returns the `(send, recv)` ciphers or `None` if the remote public key
+    /// is invalid. Both sides need the same `transcript`, which must contain
+    /// both public keys.
+    pub(crate) fn finish(
+        self,
+        remote_public_key: [u8; PUBLIC_KEY_SIZE],
+        transcript: &Transcript,
+        initializer: bool,
+    ) -> Option<(Cipher, Cipher)> {
+        let transcript = transcript.hash();
+        let remote_public_key =
+            agreement::UnparsedPublicKey::new(&agreement::X25519, remote_public_key);
+        agreement::agree_ephemeral(self.private_key, &remote_public_key, |shared_secret| {
+            let prk = hkdf::Salt::new(hkdf::HKDF_SHA256, KEY_SALT).extract(shared_secret);
+            let cipher = |direction: &[u8]| {
+                let info = [direction, transcript.as_ref()];
+                let okm = prk.expand(&info, &aead::CHACHA20_POLY1305).ok()?;
+                Some(Cipher {
+                    key: aead::LessSafeKey::new(aead::UnboundKey::from(okm)),
+                    counter: 0,
+                })
+            };
+            let from_initializer = cipher(INITIALIZER_INFO)?;
+            let from_responder = cipher(RESPONDER_INFO)?;
+            Some(if initializer {
+                (from_initializer, from_responder)
+            } else {
+                (from_responder, from_initializer)
+            })
+        })
+        .ok()
+        .flatten()
+    }
+}
+
+impl Cipher {
+    fn next_nonce(&mut self) -> aead::Nonce {
+        let mut nonce = [0u8; aead::NONCE_LEN];
+        nonce[4..].copy_from_slice(&self.counter.to_le_bytes());
+        self.counter += 1;
+        aead::Nonce::assume_unique_for_key(nonce)
+    }
+
+    /// Appends `data` as encrypted records to `out`
+    pub(crate) fn seal(&mut self, data: &[u8], out: &mut BytesMut) {
+        for chunk in data.chunks(MAX_RECORD_SIZE) {
+            let nonce = self.next_nonce();
+            let mut record = BytesMut::with_capacity(chunk.len() + TAG_SIZE);
+            record.extend_from_slice(chunk);
+            self.key
+                .seal_in_place_append_tag(nonce, aead::Aad::empty(), &mut record)
+                .expect("records are smaller than the ChaCha20-Poly1305 limit");
+            out.reserve(RECORD_HEADER_SIZE + record.len());
+            out.put_u32_le(record.len() as u32);
+            out.extend_from_slice(&record);
+        }
+    }
+
+    /// Appends the decrypted content of all complete records in `data` to
+    /// `out`, an incomplete record stays in `data`.
+    /// Err => a record was modified or isn't a record at all
+    pub(crate) fn open(&mut self, data: &mut BytesMut, out: &mut BytesMut) -> Result<(), ()> {
+        while data.len() >= RECORD_HEADER_SIZE {
+            let length = u32::from_le_bytes([data[0], data[1], data[2], data[3]]) as usize;
+            if !(TAG_SIZE..=MAX_RECORD_SIZE + TAG_SIZE).contains(&length) {
+                return Err(());
+            }
+            if data.len() < RECORD_HEADER_SIZE + length {
+                break;
+            }
+            data.advance(RECORD_HEADER_SIZE);
+            let mut record = data.split_to(length);
+            let nonce = self.next_nonce();
+            let plaintext = self
+                .key
+                .open_in_place(nonce, aead::Aad::empty(), &mut record)
+                .map_err(|_| ())?;
+            out.extend_from_slice(plaintext);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transcript(initializer_key: &[u8; 32], responder_key: &[u8; 32]) -> Transcript {
+        let mut transcript = Transcript::new();
+        transcript.add_key(initializer_key);
+        transcript.add_key(responder_key);
+        transcript
+    }
+
+    fn ciphers() -> ((Cipher, Cipher), (Cipher, Cipher)) {
+        let initializer = KeyExchange::new();
+        let responder = KeyExchange::new();
+        let transcript = transcript(&initializer.public_key(), &responder.public_key());
+        let initializer_key = initializer.public_key();
+        let responder_key = responder.public_key();
+        (
+            initializer
+                .finish(responder_key, &transcript, true)
+                .unwrap(),
+            responder
+                .finish(initializer_key, &transcript, false)
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn roundtrip() {
+        let ((mut s1, mut r1), (mut s2, mut r2)) = ciphers();
+        let mut buffer = BytesMut::new();
+        s1.seal(b"Hello World", &mut buffer);
+        s1.seal(&[7u8; 200_000], &mut buffer);
+        assert!(!buffer.windows(5).any(|w| w == b"Hello"));
+        let mut plaintext = BytesMut::new();
+        r2.open(&mut buffer, &mut plaintext).unwrap();
+        assert!(buffer.is_empty());
+        assert_eq!(&plaintext[..11], b"Hello World");
+        assert_eq!(plaintext.len(), 200_011);
+
+        s2.seal(b"back", &mut buffer);
+        let mut plaintext = BytesMut::new();
+        r1.open(&mut buffer, &mut plaintext).unwrap();
+        assert_eq!(&plaintext[..], b"back");
+    }
+
+    #[test]
+    fn partial_record() {
+        let ((mut s1, _), (_, mut r2)) = ciphers();
+        let mut buffer = BytesMut::new();
+        s1.seal(b"Hello World", &mut buffer);
+        let rest = buffer.split_off(10);
+        let mut plaintext = BytesMut::new();
+        r2.open(&mut buffer, &mut plaintext).unwrap();
+        assert!(plaintext.is_empty());
+        buffer.unsplit(rest);
+        r2.open(&mut buffer, &mut plaintext).unwrap();
+        assert_eq!(&plaintext[..], b"Hello World");
+    }
+
+    #[test]
+    fn tampered_record() {
+        let ((mut s1, _), (_, mut r2)) = ciphers();
+        let mut buffer = BytesMut::new();
+        s1.seal(b"Hello World", &mut buffer);
+        buffer[6] ^= 1;
+        assert_eq!(r2.open(&mut buffer, &mut BytesMut::new()), Err(()));
+    }
+
+    #[test]
+    fn different_keys() {
+        let ((mut s1, _), _) = ciphers();
+        let ((_, mut r1), _) = ciphers();
+        let mut buffer = BytesMut::new();
+        s1.seal(b"Hello World", &mut buffer);
+        assert_eq!(r1.open(&mut buffer, &mut BytesMut::new()), Err(()));
+    }
+
+    #[test]
+    fn different_transcripts() {
+        let initializer = KeyExchange::new();
+        let responder = KeyExchange::new();
+        let (initializer_key, responder_key) = (initializer.public_key(), responder.public_key());
+        let mut tampered = transcript(&initializer_key, &responder_key);
+        tampered.add_frame(&InitFrame::Raw(b"tampered".to_vec()));
+        let (mut s1, _) = initializer
+            .finish(
+                responder_key,
+                &transcript(&initializer_key, &responder_key),
+                true,
+            )
+            .unwrap();
+        let (_, mut r2) = responder.finish(initializer_key, &tampered, false).unwrap();
+        let mut buffer = BytesMut::new();
+        s1.seal(b"Hello World", &mut buffer);
+        assert_eq!(r2.open(&mut buffer, &mut BytesMut::new()), Err(()));
+    }
+
+    #[test]
+    fn static_key_signature() {
+        let (_, pkcs8) = StaticKey::generate();
+        let key = StaticKey::from_pkcs8(&pkcs8).unwrap();
+        let transcript = transcript(&[1; 32], &[2; 32]);
+        let signature = key.sign(&transcript);
+        assert!(key.public_key().verify(&transcript, &signature));
+
+        let (other, _) = StaticKey::generate();
+        assert!(!other.public_key().verify(&transcript, &signature));
+        let mut tampered = transcript.clone();
+        tampered.add_key(&[3; 32]);
+        assert!(!key.public_key().verify(&tampered, &signature));
+    }
+
+    #[test]
+    fn static_public_key_hex() {
+        let (key, _) = StaticKey::generate();
+        let public_key = key.public_key();
+        assert_eq!(public_key.to_string().parse(), Ok(public_key));
+        assert_eq!(
+            "abcd".parse::<StaticPublicKey>(),
+            Err(InvalidStaticPublicKey)
+        );
+        assert_eq!(
+            "zz".repeat(32).parse::<StaticPublicKey>(),
+            Err(InvalidStaticPublicKey)
+        );
+    }
+}
@@ -8,6 +8,10 @@ pub enum InitProtocolError<E: std::fmt::Debug + Send> {
     NotHandshake,
     /// expected Id, didn't get id
     NotId,
+    /// requested encryption, didn't get a valid key exchange
+    NotKeyExchange,
+    /// the key exchange wasn't signed by the expected static key
+    WrongStaticKey,
     WrongMagicNumber([u8; 7]),
     WrongVersion([u32; 3]),
 }
@@ -46,6 +50,13 @@ impl<E: std::fmt::Debug + Send> core::fmt::Display for InitProtocolError<E> {
             InitProtocolError::NotId => {
                 write!(f, "Remote send something which couldn't be parsed as an id")
             },
+            InitProtocolError::NotKeyExchange => write!(
+                f,
+                "Remote doesn't support encryption or send an invalid key exchange"
+            ),
+            InitProtocolError::WrongStaticKey => {
+                write!(f, "Remote didn't authenticate with the expected static key")
+            },
             InitProtocolError::WrongMagicNumber(r) => write!(
                 f,
                 "Magic Number doesn't match, remote side send '{:?}' instead of '{:?}'",
@@ -25,6 +25,12 @@ pub enum ProtocolEvent {
         data: Bytes,
         sid: Sid,
     },
+    /// Number of messages received on a stream so far, used to resume a
+    /// stream on another channel without losing or duplicating messages
+    Ack {
+        sid: Sid,
+        received: u64,
+    },
 }
 
 impl ProtocolEvent {
@@ -43,6 +49,10 @@ impl ProtocolEvent {
                 guaranteed_bandwidth: *guaranteed_bandwidth,
             },
             ProtocolEvent::CloseStream { sid } => OTFrame::CloseStream { sid: *sid },
+            ProtocolEvent::Ack { sid, received } => OTFrame::Ack {
+                sid: *sid,
+                received: *received,
+            },
             ProtocolEvent::Message { .. } => {
                 unimplemented!("Event::Message to OTFrame IS NOT supported")
             },
@@ -61,6 +71,17 @@ mod tests {
             ProtocolEvent::CloseStream { sid: Sid::new(42) }.to_frame(),
             OTFrame::CloseStream { sid: Sid::new(42) }
         );
+        assert_eq!(
+            ProtocolEvent::Ack {
+                sid: Sid::new(42),
+                received: 7
+            }
+            .to_frame(),
+            OTFrame::Ack {
+                sid: Sid::new(42),
+                received: 7
+            }
+        );
     }
 
     #[test]
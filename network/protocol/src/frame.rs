@@ -10,7 +10,10 @@ const FRAME_CLOSE_STREAM: u8 = 5;
 const FRAME_DATA_HEADER: u8 = 6;
 const FRAME_DATA: u8 = 7;
 const FRAME_RAW: u8 = 8;
-//const FRAME_RESERVED_2: u8 = 10;
+const FRAME_KEY_EXCHANGE: u8 = 9;
+const FRAME_KEY_EXCHANGE_RESPONSE: u8 = 10;
+const FRAME_RESUME: u8 = 11;
+const FRAME_ACK: u8 = 12;
 //const FRAME_RESERVED_3: u8 = 13;
 
 /// Used for Communication between Channel <----(TCP/UDP)----> Channel
@@ -24,6 +27,22 @@ pub enum InitFrame {
         pid: Pid,
         secret: u128,
     },
+    /// Ephemeral X25519 public key of the initializer
+    KeyExchange {
+        public_key: [u8; 32],
+    },
+    /// Ephemeral X25519 public key of the responder and the signature of the
+    /// handshake transcript with its static key, every frame afterwards is
+    /// encrypted
+    KeyExchangeResponse {
+        public_key: [u8; 32],
+        signature: [u8; 64],
+    },
+    /// Send instead of `Init` to add this channel to an existing session
+    Resume {
+        pid: Pid,
+        secret: u128,
+    },
     /// WARNING: sending RAW is only for debug purposes and will drop the
     /// connection
     Raw(Vec<u8>),
@@ -52,6 +71,11 @@ pub enum OTFrame {
         mid: Mid,
         data: Bytes,
     },
+    /// The sender received `received` messages of this stream so far
+    Ack {
+        sid: Sid,
+        received: u64,
+    },
 }
 
 /// Used for IN TCP Communication between Channel <--(TCP)-- Channel
@@ -77,14 +101,21 @@ pub enum ITFrame {
         mid: Mid,
         data: BytesMut,
     },
+    Ack {
+        sid: Sid,
+        received: u64,
+    },
 }
 
 impl InitFrame {
     // Size WITHOUT the 1rst indicating byte
     pub(crate) const HANDSHAKE_CNS: usize = 19;
     pub(crate) const INIT_CNS: usize = 32;
+    pub(crate) const KEY_EXCHANGE_CNS: usize = 32;
+    pub(crate) const KEY_EXCHANGE_RESPONSE_CNS: usize = 96;
     /// const part of the RAW frame, actual size is variable
     pub(crate) const RAW_CNS: usize = 2;
+    pub(crate) const RESUME_CNS: usize = 32;
 
     //provide an appropriate buffer size. > 1500
     pub(crate) fn write_bytes(self, bytes: &mut BytesMut) {
@@ -104,6 +135,23 @@ impl InitFrame {
                 pid.to_bytes(bytes);
                 bytes.put_u128_le(secret);
             },
+            InitFrame::KeyExchange { public_key } => {
+                bytes.put_u8(FRAME_KEY_EXCHANGE);
+                bytes.put_slice(&public_key);
+            },
+            InitFrame::KeyExchangeResponse {
+                public_key,
+                signature,
+            } => {
+                bytes.put_u8(FRAME_KEY_EXCHANGE_RESPONSE);
+                bytes.put_slice(&public_key);
+                bytes.put_slice(&signature);
+            },
+            InitFrame::Resume { pid, secret } => {
+                bytes.put_u8(FRAME_RESUME);
+                pid.to_bytes(bytes);
+                bytes.put_u128_le(secret);
+            },
             InitFrame::Raw(data) => {
                 bytes.put_u8(FRAME_RAW);
                 bytes.put_u16_le(data.len() as u16);
@@ -141,6 +189,39 @@ impl InitFrame {
                     secret: bytes.get_u128_le(),
                 }
             },
+            FRAME_KEY_EXCHANGE => {
+                if bytes.len() < Self::KEY_EXCHANGE_CNS + 1 {
+                    return None;
+                }
+                bytes.advance(1);
+                let mut public_key = [0u8; Self::KEY_EXCHANGE_CNS];
+                bytes.copy_to_slice(&mut public_key);
+                InitFrame::KeyExchange { public_key }
+            },
+            FRAME_KEY_EXCHANGE_RESPONSE => {
+                if bytes.len() < Self::KEY_EXCHANGE_RESPONSE_CNS + 1 {
+                    return None;
+                }
+                bytes.advance(1);
+                let mut public_key = [0u8; 32];
+                bytes.copy_to_slice(&mut public_key);
+                let mut signature = [0u8; 64];
+                bytes.copy_to_slice(&mut signature);
+                InitFrame::KeyExchangeResponse {
+                    public_key,
+                    signature,
+                }
+            },
+            FRAME_RESUME => {
+                if bytes.len() < Self::RESUME_CNS + 1 {
+                    return None;
+                }
+                bytes.advance(1);
+                InitFrame::Resume {
+                    pid: Pid::from_bytes(bytes),
+                    secret: bytes.get_u128_le(),
+                }
+            },
             FRAME_RAW => {
                 if bytes.len() < Self::RAW_CNS + 1 {
                     return None;
@@ -159,6 +240,7 @@ impl InitFrame {
     }
 }
 
+pub(crate) const TCP_ACK_CNS: usize = 16;
 pub(crate) const TCP_CLOSE_STREAM_CNS: usize = 8;
 /// const part of the DATA frame, actual size is variable
 pub(crate) const TCP_DATA_CNS: usize = 10;
@@ -201,6 +283,11 @@ impl OTFrame {
                 bytes.put_u16_le(data.len() as u16);
                 bytes.put_slice(&data);
             },
+            Self::Ack { sid, received } => {
+                bytes.put_u8(FRAME_ACK);
+                sid.to_bytes(bytes);
+                bytes.put_u64_le(received);
+            },
         }
     }
 }
@@ -224,6 +311,7 @@ impl ITFrame {
                 }
                 u16::from_le_bytes([bytes[8 + 1], bytes[9 + 1]]) as usize + TCP_DATA_CNS
             },
+            FRAME_ACK => TCP_ACK_CNS,
             _ => return Err(()),
         };
 
@@ -270,6 +358,14 @@ impl ITFrame {
                 let data = bytes.split_to(length as usize);
                 Self::Data { mid, data }
             },
+            FRAME_ACK => {
+                let mut bytes = bytes.split_to(size + 1);
+                bytes.advance(1);
+                Self::Ack {
+                    sid: Sid::from_bytes(&mut bytes),
+                    received: bytes.get_u64_le(),
+                }
+            },
             _ => unreachable!("Frame::to_frame should be handled before!"),
         };
         Ok(Some(frame))
@@ -297,6 +393,7 @@ impl PartialEq<ITFrame> for OTFrame {
                 matches!(other, ITFrame::DataHeader { mid, sid, length })
             },
             Self::Data { mid, data } => matches!(other, ITFrame::Data { mid, data }),
+            Self::Ack { sid, received } => matches!(other, ITFrame::Ack { sid, received }),
         }
     }
 }
@@ -316,6 +413,17 @@ mod tests {
                 pid: Pid::fake(0),
                 secret: 0u128,
             },
+            InitFrame::KeyExchange {
+                public_key: [42u8; 32],
+            },
+            InitFrame::KeyExchangeResponse {
+                public_key: [42u8; 32],
+                signature: [7u8; 64],
+            },
+            InitFrame::Resume {
+                pid: Pid::fake(1),
+                secret: 42u128,
+            },
             InitFrame::Raw(vec![1, 2, 3]),
         ]
     }
@@ -341,6 +449,10 @@ mod tests {
                 mid: 0,
                 data: Bytes::from(&[42u8; 16][..]),
             },
+            OTFrame::Ack {
+                sid: Sid::new(1337),
+                received: 2,
+            },
             OTFrame::CloseStream {
                 sid: Sid::new(1337),
             },
@@ -1,5 +1,6 @@
 use crate::{
     InitProtocol,
+    crypto::{Cipher, KeyExchange, StaticKey, StaticPublicKey, Transcript},
     error::{InitProtocolError, ProtocolError},
     frame::InitFrame,
     types::{
@@ -23,6 +24,23 @@ use tracing::{debug, error, info, trace};
 pub trait ReliableDrain {
     type CustomErr: std::fmt::Debug + Send;
     async fn send(&mut self, frame: InitFrame) -> Result<(), ProtocolError<Self::CustomErr>>;
+
+    /// The initializer asks the remote to encrypt the channel if this returns
+    /// the static key the remote must authenticate with, see the `crypto`
+    /// module
+    fn request_encryption(&self) -> Option<StaticPublicKey> { None }
+
+    /// The static key this side authenticates with when the remote asks for
+    /// encryption. Without one, such requests are rejected
+    fn static_key(&self) -> Option<&StaticKey> { None }
+
+    /// Every frame send after this call MUST be encrypted with `cipher`. Only
+    /// called if either [`request_encryption`] or [`static_key`] returned a
+    /// key
+    ///
+    /// [`request_encryption`]: ReliableDrain::request_encryption
+    /// [`static_key`]: ReliableDrain::static_key
+    fn enable_encryption(&mut self, _cipher: Cipher) {}
 }
 
 /// Implement this for auto Handshake with [`ReliableDrain`]. See
@@ -33,6 +51,10 @@ pub trait ReliableDrain {
 pub trait ReliableSink {
     type CustomErr: std::fmt::Debug + Send;
     async fn recv(&mut self) -> Result<InitFrame, ProtocolError<Self::CustomErr>>;
+
+    /// Every frame received after this call MUST be decrypted with `cipher`.
+    /// See [`ReliableDrain::enable_encryption`]
+    fn enable_encryption(&mut self, _cipher: Cipher) {}
 }
 
 #[async_trait]
@@ -47,9 +69,10 @@ where
     async fn initialize(
         &mut self,
         initializer: bool,
+        resume: bool,
         local_pid: Pid,
         local_secret: u128,
-    ) -> Result<(Pid, Sid, u128), InitProtocolError<E>> {
+    ) -> Result<(Pid, Sid, u128, bool), InitProtocolError<E>> {
         #[cfg(debug_assertions)]
         const WRONG_NUMBER: &str = "Handshake does not contain the magic number required by \
                                     veloren server.\nWe are not sure if you are a valid veloren \
@@ -64,16 +87,16 @@ where
         let drain = &mut self.0;
         let sink = &mut self.1;
 
+        let local_handshake = InitFrame::Handshake {
+            magic_number: VELOREN_MAGIC_NUMBER,
+            version: VELOREN_NETWORK_VERSION,
+        };
         if initializer {
-            drain
-                .send(InitFrame::Handshake {
-                    magic_number: VELOREN_MAGIC_NUMBER,
-                    version: VELOREN_NETWORK_VERSION,
-                })
-                .await?;
+            drain.send(local_handshake.clone()).await?;
         }
 
-        match sink.recv().await? {
+        let remote_handshake = sink.recv().await?;
+        match remote_handshake.clone() {
             InitFrame::Handshake {
                 magic_number,
                 version,
@@ -104,20 +127,8 @@ where
                     Err(InitProtocolError::WrongVersion(version))
                 } else {
                     trace!("Handshake Frame completed");
-                    if initializer {
-                        drain
-                            .send(InitFrame::Init {
-                                pid: local_pid,
-                                secret: local_secret,
-                            })
-                            .await?;
-                    } else {
-                        drain
-                            .send(InitFrame::Handshake {
-                                magic_number: VELOREN_MAGIC_NUMBER,
-                                version: VELOREN_NETWORK_VERSION,
-                            })
-                            .await?;
+                    if !initializer {
+                        drain.send(local_handshake.clone()).await?;
                     }
                     Ok(())
                 }
@@ -135,35 +146,115 @@ where
             },
         }?;
 
-        match sink.recv().await? {
-            InitFrame::Init { pid, secret } => {
-                debug!(?pid, "Participant send their ID");
-                let stream_id_offset = if initializer {
-                    STREAM_ID_OFFSET1
-                } else {
-                    drain
-                        .send(InitFrame::Init {
-                            pid: local_pid,
-                            secret: local_secret,
-                        })
-                        .await?;
-                    STREAM_ID_OFFSET2
-                };
-                info!(?pid, "This Handshake is now configured!");
-                Ok((pid, stream_id_offset, secret))
-            },
+        // Both sides hash the frames in the order they were sent, the
+        // initializer's first
+        let mut transcript = Transcript::new();
+        if initializer {
+            transcript.add_frame(&local_handshake);
+            transcript.add_frame(&remote_handshake);
+        } else {
+            transcript.add_frame(&remote_handshake);
+            transcript.add_frame(&local_handshake);
+        }
+
+        if initializer {
+            if let Some(remote_static_key) = drain.request_encryption() {
+                let key_exchange = KeyExchange::new();
+                drain
+                    .send(InitFrame::KeyExchange {
+                        public_key: key_exchange.public_key(),
+                    })
+                    .await?;
+                match sink.recv().await? {
+                    InitFrame::KeyExchangeResponse {
+                        public_key,
+                        signature,
+                    } => {
+                        transcript.add_key(&key_exchange.public_key());
+                        transcript.add_key(&remote_static_key.0);
+                        transcript.add_key(&public_key);
+                        if !remote_static_key.verify(&transcript, &signature) {
+                            error!(?remote_static_key, "Remote didn't sign the key exchange");
+                            return Err(InitProtocolError::WrongStaticKey);
+                        }
+                        let (send, recv) = key_exchange
+                            .finish(public_key, &transcript, true)
+                            .ok_or(InitProtocolError::NotKeyExchange)?;
+                        drain.enable_encryption(send);
+                        sink.enable_encryption(recv);
+                        debug!("Channel is now encrypted");
+                    },
+                    _ => {
+                        info!("Remote didn't accept encryption");
+                        return Err(InitProtocolError::NotKeyExchange);
+                    },
+                }
+            }
+            drain
+                .send(init_frame(resume, local_pid, local_secret))
+                .await?;
+        }
+
+        let mut frame = sink.recv().await?;
+        if !initializer
+            && let InitFrame::KeyExchange { public_key } = frame
+            && let Some(static_key) = drain.static_key()
+        {
+            let key_exchange = KeyExchange::new();
+            transcript.add_key(&public_key);
+            transcript.add_key(&static_key.public_key().0);
+            transcript.add_key(&key_exchange.public_key());
+            let signature = static_key.sign(&transcript);
+            drain
+                .send(InitFrame::KeyExchangeResponse {
+                    public_key: key_exchange.public_key(),
+                    signature,
+                })
+                .await?;
+            let (send, recv) = key_exchange
+                .finish(public_key, &transcript, false)
+                .ok_or(InitProtocolError::NotKeyExchange)?;
+            drain.enable_encryption(send);
+            sink.enable_encryption(recv);
+            debug!("Channel is now encrypted");
+            frame = sink.recv().await?;
+        }
+
+        let (pid, secret, remote_resume) = match frame {
+            InitFrame::Init { pid, secret } => (pid, secret, false),
+            InitFrame::Resume { pid, secret } => (pid, secret, true),
             InitFrame::Raw(bytes) => {
                 match std::str::from_utf8(bytes.as_slice()) {
                     Ok(string) => error!(?string, ERR_S),
                     _ => error!(?bytes, ERR_S),
                 }
-                Err(InitProtocolError::NotId)
+                return Err(InitProtocolError::NotId);
             },
             _ => {
                 info!("Handshake failed");
-                Err(InitProtocolError::NotId)
+                return Err(InitProtocolError::NotId);
             },
-        }
+        };
+
+        debug!(?pid, ?remote_resume, "Participant send their ID");
+        let stream_id_offset = if initializer {
+            STREAM_ID_OFFSET1
+        } else {
+            drain
+                .send(init_frame(resume, local_pid, local_secret))
+                .await?;
+            STREAM_ID_OFFSET2
+        };
+        info!(?pid, "This Handshake is now configured!");
+        Ok((pid, stream_id_offset, secret, remote_resume))
+    }
+}
+
+fn init_frame(resume: bool, pid: Pid, secret: u128) -> InitFrame {
+    if resume {
+        InitFrame::Resume { pid, secret }
+    } else {
+        InitFrame::Init { pid, secret }
     }
 }
 
@@ -175,7 +266,7 @@ mod tests {
     #[tokio::test]
     async fn handshake_drop_start() {
         let [mut p1, p2] = ac_bound(10, None);
-        let r1 = tokio::spawn(async move { p1.initialize(true, Pid::fake(2), 1337).await });
+        let r1 = tokio::spawn(async move { p1.initialize(true, false, Pid::fake(2), 1337).await });
         let r2 = tokio::spawn(async move {
             let _ = &p2;
             let _ = p2;
@@ -187,7 +278,7 @@ mod tests {
     #[tokio::test]
     async fn handshake_wrong_magic_number() {
         let [mut p1, mut p2] = ac_bound(10, None);
-        let r1 = tokio::spawn(async move { p1.initialize(true, Pid::fake(2), 1337).await });
+        let r1 = tokio::spawn(async move { p1.initialize(true, false, Pid::fake(2), 1337).await });
         let r2 = tokio::spawn(async move {
             let _ = p2.1.recv().await?;
             p2.0.send(InitFrame::Handshake {
@@ -209,7 +300,7 @@ mod tests {
     #[tokio::test]
     async fn handshake_wrong_version() {
         let [mut p1, mut p2] = ac_bound(10, None);
-        let r1 = tokio::spawn(async move { p1.initialize(true, Pid::fake(2), 1337).await });
+        let r1 = tokio::spawn(async move { p1.initialize(true, false, Pid::fake(2), 1337).await });
         let r2 = tokio::spawn(async move {
             let _ = p2.1.recv().await?;
             p2.0.send(InitFrame::Handshake {
@@ -229,7 +320,7 @@ mod tests {
     #[tokio::test]
     async fn handshake_unexpected_raw() {
         let [mut p1, mut p2] = ac_bound(10, None);
-        let r1 = tokio::spawn(async move { p1.initialize(true, Pid::fake(2), 1337).await });
+        let r1 = tokio::spawn(async move { p1.initialize(true, false, Pid::fake(2), 1337).await });
         let r2 = tokio::spawn(async move {
             let _ = p2.1.recv().await?;
             p2.0.send(InitFrame::Handshake {
@@ -0,0 +1,303 @@
+//! Simulates bad network conditions on top of another [`SendProtocol`], e.g.
+//! to reproduce rubber-banding reliably in tests.
+//!
+//! Only the sending side is impaired, both sides of a channel need to be
+//! impaired to slow down both directions. As this crate has no timers, delayed
+//! events are only passed on when [`flush`] is called, so call it regularly.
+//!
+//! [`flush`]: crate::SendProtocol::flush
+use crate::{
+    SendProtocol,
+    error::ProtocolError,
+    event::ProtocolEvent,
+    types::{Bandwidth, Promises, Sid},
+};
+use async_trait::async_trait;
+use hashbrown::HashSet;
+use rand::RngExt;
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// Network conditions to simulate, the default doesn't impair anything
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct NetworkConditions {
+    /// Added to every event
+    pub latency: Duration,
+    /// Up to this is randomly added to the `latency` of every event
+    pub jitter: Duration,
+    /// Chance between `0.0` and `1.0` that an event is lost. Messages of
+    /// streams without [`Promises::GUARANTEED_DELIVERY`] are dropped, all
+    /// other events are delayed by a retransmission, which also delays
+    /// everything sent after them.
+    pub loss: f32,
+    /// Maximum message data per second, `None` for unlimited
+    pub bandwidth: Option<Bandwidth>,
+}
+
+#[derive(Debug)]
+enum Delayed {
+    Send(ProtocolEvent),
+    Notify(ProtocolEvent),
+}
+
+/// Wraps a [`SendProtocol`] and holds back its events according to
+/// [`NetworkConditions`]. Events are never reordered, like on a TCP
+/// connection.
+#[derive(Debug)]
+pub struct ImpairedSendProtocol<P> {
+    inner: P,
+    conditions: NetworkConditions,
+    /// Events and when they are passed on to `inner`
+    queue: VecDeque<(Instant, Delayed)>,
+    /// Streams whose lost messages are dropped instead of sent again
+    unreliable: HashSet<Sid>,
+    /// Message data that may still be passed on, negative after a message
+    /// bigger than the remaining budget
+    budget: i64,
+}
+
+impl NetworkConditions {
+    /// Whether these conditions change anything at all
+    pub fn is_impaired(&self) -> bool { *self != Self::default() }
+}
+
+impl<P> ImpairedSendProtocol<P> {
+    /// Unused bandwidth is only saved up for this long, so a stalled channel
+    /// doesn't send a huge burst afterwards
+    const MAX_BURST: Duration = Duration::from_millis(100);
+    /// Lower bound of the TCP retransmission timeout on Linux
+    const MIN_RETRANSMISSION_TIMEOUT: Duration = Duration::from_millis(200);
+
+    pub fn new(inner: P, conditions: NetworkConditions) -> Self {
+        Self {
+            inner,
+            conditions,
+            queue: VecDeque::new(),
+            unreliable: HashSet::new(),
+            budget: 0,
+        }
+    }
+
+    /// The wrapped protocol
+    pub fn inner(&self) -> &P { &self.inner }
+
+    pub fn conditions(&self) -> NetworkConditions { self.conditions }
+
+    fn track(&mut self, event: &ProtocolEvent) {
+        match event {
+            ProtocolEvent::OpenStream { sid, promises, .. }
+                if !promises.contains(Promises::GUARANTEED_DELIVERY) =>
+            {
+                self.unreliable.insert(*sid);
+            },
+            ProtocolEvent::CloseStream { sid } => {
+                self.unreliable.remove(sid);
+            },
+            _ => (),
+        }
+    }
+
+    /// When an event sent now arrives at the remote, `None` if it's lost
+    fn due(&self, event: &ProtocolEvent) -> Option<Instant> {
+        let conditions = &self.conditions;
+        let mut rng = rand::rng();
+        let mut delay = conditions.latency;
+        if !conditions.jitter.is_zero() {
+            delay += conditions.jitter.mul_f32(rng.random::<f32>());
+        }
+        if conditions.loss > 0.0 && rng.random::<f32>() < conditions.loss {
+            if let ProtocolEvent::Message { sid, .. } = event
+                && self.unreliable.contains(sid)
+            {
+                return None;
+            }
+            delay += Self::MIN_RETRANSMISSION_TIMEOUT.max(conditions.latency * 2);
+        }
+        Some(self.not_before(Instant::now() + delay))
+    }
+
+    /// Later events have to wait for earlier ones
+    fn not_before(&self, due: Instant) -> Instant {
+        self.queue.back().map_or(due, |(last, _)| due.max(*last))
+    }
+}
+
+impl<P: SendProtocol + Send> ImpairedSendProtocol<P> {
+    async fn forward(&mut self, delayed: Delayed) -> Result<(), ProtocolError<P::CustomErr>> {
+        match delayed {
+            Delayed::Send(event) => self.inner.send(event).await,
+            Delayed::Notify(event) => {
+                self.inner.notify_from_recv(event);
+                Ok(())
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl<P: SendProtocol + Send> SendProtocol for ImpairedSendProtocol<P> {
+    type CustomErr = P::CustomErr;
+
+    fn notify_from_recv(&mut self, event: ProtocolEvent) {
+        self.track(&event);
+        let due = self.not_before(Instant::now());
+        self.queue.push_back((due, Delayed::Notify(event)));
+    }
+
+    async fn send(&mut self, event: ProtocolEvent) -> Result<(), ProtocolError<Self::CustomErr>> {
+        if let ProtocolEvent::Shutdown = event {
+            // The protocol is dropped right after, nothing would pass on the
+            // remaining events later
+            while let Some((_, delayed)) = self.queue.pop_front() {
+                self.forward(delayed).await?;
+            }
+            return self.inner.send(event).await;
+        }
+        self.track(&event);
+        if let Some(due) = self.due(&event) {
+            self.queue.push_back((due, Delayed::Send(event)));
+        }
+        Ok(())
+    }
+
+    async fn flush(
+        &mut self,
+        bandwidth: Bandwidth,
+        dt: Duration,
+    ) -> Result<Bandwidth, ProtocolError<Self::CustomErr>> {
+        if let Some(cap) = self.conditions.bandwidth {
+            let max_budget = (cap as f64 * Self::MAX_BURST.as_secs_f64()) as i64;
+            let refill = (cap as f64 * dt.as_secs_f64()) as i64;
+            self.budget = (self.budget + refill).min(max_budget);
+        }
+        let now = Instant::now();
+        while let Some((due, delayed)) = self.queue.front() {
+            if *due > now {
+                break;
+            }
+            if self.conditions.bandwidth.is_some()
+                && let Delayed::Send(ProtocolEvent::Message { data, .. }) = delayed
+            {
+                if self.budget <= 0 {
+                    break;
+                }
+                self.budget -= data.len() as i64;
+            }
+            if let Some((_, delayed)) = self.queue.pop_front() {
+                self.forward(delayed).await?;
+            }
+        }
+        self.inner.flush(bandwidth, dt).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RecvProtocol, mpsc::test_utils::*};
+    use bytes::Bytes;
+
+    const LATENCY: Duration = Duration::from_millis(50);
+
+    fn open(sid: u64, promises: Promises) -> ProtocolEvent {
+        ProtocolEvent::OpenStream {
+            sid: Sid::new(sid),
+            prio: 5,
+            promises,
+            guaranteed_bandwidth: 0,
+        }
+    }
+
+    fn message(sid: u64, data: &'static [u8]) -> ProtocolEvent {
+        ProtocolEvent::Message {
+            sid: Sid::new(sid),
+            data: Bytes::from_static(data),
+        }
+    }
+
+    #[tokio::test]
+    async fn latency_delays_events() {
+        let [p1, p2] = ac_bound(10, None);
+        let (s, _) = p1;
+        let (_, mut r) = p2;
+        let mut s = ImpairedSendProtocol::new(s, NetworkConditions {
+            latency: LATENCY,
+            ..Default::default()
+        });
+        let event = open(10, Promises::ORDERED);
+        s.send(event.clone()).await.unwrap();
+        s.send(message(10, b"Hello")).await.unwrap();
+        s.flush(1_000_000, Duration::from_millis(1)).await.unwrap();
+        assert_eq!(s.queue.len(), 2);
+        std::thread::sleep(LATENCY);
+        s.flush(1_000_000, LATENCY).await.unwrap();
+        assert_eq!(r.recv().await.unwrap(), event);
+        assert_eq!(r.recv().await.unwrap(), message(10, b"Hello"));
+    }
+
+    #[tokio::test]
+    async fn shutdown_sends_everything() {
+        let [p1, p2] = ac_bound(10, None);
+        let (s, _) = p1;
+        let (_, mut r) = p2;
+        let mut s = ImpairedSendProtocol::new(s, NetworkConditions {
+            latency: Duration::from_secs(60),
+            ..Default::default()
+        });
+        s.send(open(10, Promises::ORDERED)).await.unwrap();
+        s.send(ProtocolEvent::Shutdown).await.unwrap();
+        assert_eq!(r.recv().await.unwrap(), open(10, Promises::ORDERED));
+        assert_eq!(r.recv().await.unwrap(), ProtocolEvent::Shutdown);
+    }
+
+    #[tokio::test]
+    async fn loss_drops_only_unreliable_messages() {
+        let [p1, p2] = ac_bound(10, None);
+        let (s, _) = p1;
+        let (_, mut r) = p2;
+        let mut s = ImpairedSendProtocol::new(s, NetworkConditions {
+            loss: 1.0,
+            ..Default::default()
+        });
+        s.send(open(10, Promises::ORDERED)).await.unwrap();
+        s.send(open(12, Promises::GUARANTEED_DELIVERY))
+            .await
+            .unwrap();
+        s.send(message(10, b"lost")).await.unwrap();
+        s.send(message(12, b"resent")).await.unwrap();
+        std::thread::sleep(ImpairedSendProtocol::<()>::MIN_RETRANSMISSION_TIMEOUT);
+        s.flush(1_000_000, Duration::from_millis(1)).await.unwrap();
+        assert_eq!(r.recv().await.unwrap(), open(10, Promises::ORDERED));
+        assert_eq!(
+            r.recv().await.unwrap(),
+            open(12, Promises::GUARANTEED_DELIVERY)
+        );
+        assert_eq!(r.recv().await.unwrap(), message(12, b"resent"));
+    }
+
+    #[tokio::test]
+    async fn bandwidth_is_capped() {
+        let [p1, p2] = ac_bound(10, None);
+        let (s, _) = p1;
+        let (_, mut r) = p2;
+        let mut s = ImpairedSendProtocol::new(s, NetworkConditions {
+            bandwidth: Some(1000),
+            ..Default::default()
+        });
+        s.send(open(10, Promises::ORDERED)).await.unwrap();
+        s.send(message(10, b"first")).await.unwrap();
+        s.send(message(10, b"second")).await.unwrap();
+        // budget of 5 bytes
+        s.flush(1_000_000, Duration::from_millis(5)).await.unwrap();
+        assert_eq!(r.recv().await.unwrap(), open(10, Promises::ORDERED));
+        assert_eq!(r.recv().await.unwrap(), message(10, b"first"));
+        s.flush(1_000_000, Duration::ZERO).await.unwrap();
+        assert_eq!(s.queue.len(), 1);
+        s.flush(1_000_000, Duration::from_millis(10)).await.unwrap();
+        assert_eq!(r.recv().await.unwrap(), message(10, b"second"));
+    }
+}
@@ -14,8 +14,10 @@
 //!  - TCP
 //!  - MPSC
 //!  - QUIC
+//!  - UDP
 //!
-//! eventually a pure UDP implementation will follow
+//! [`ImpairedSendProtocol`] wraps any of them to simulate latency, loss and
+//! limited bandwidth.
 //!
 //! warning: don't mix protocol, using the TCP variant for actual UDP socket
 //! will result in dropped data  using UDP with a TCP socket will be a waste of
@@ -49,10 +51,12 @@
 //! [`RecvProtocol`]: crate::RecvProtocol
 //! [`InitProtocol`]: crate::InitProtocol
 
+mod crypto;
 mod error;
 mod event;
 mod frame;
 mod handshake;
+mod impair;
 mod message;
 mod metrics;
 mod mpsc;
@@ -60,10 +64,13 @@ mod prio;
 mod quic;
 mod tcp;
 mod types;
+mod udp;
 mod util;
 
+pub use crypto::{InvalidStaticPublicKey, StaticKey, StaticPublicKey};
 pub use error::{InitProtocolError, ProtocolError};
 pub use event::ProtocolEvent;
+pub use impair::{ImpairedSendProtocol, NetworkConditions};
 pub use metrics::ProtocolMetricCache;
 #[cfg(feature = "metrics")]
 pub use metrics::ProtocolMetrics;
@@ -71,6 +78,7 @@ pub use mpsc::{MpscMsg, MpscRecvProtocol, MpscSendProtocol};
 pub use quic::{QuicDataFormat, QuicDataFormatStream, QuicRecvProtocol, QuicSendProtocol};
 pub use tcp::{TcpRecvProtocol, TcpSendProtocol};
 pub use types::{Bandwidth, Cid, HIGHEST_PRIO, Pid, Prio, Promises, Sid, VELOREN_NETWORK_VERSION};
+pub use udp::{UdpRecvProtocol, UdpSendProtocol, is_udp_handshake, udp_init_index};
 
 ///use at own risk, might change any time, for internal benchmarks
 pub mod _internal {
@@ -87,12 +95,16 @@ use async_trait::async_trait;
 pub trait InitProtocol {
     type CustomErr: std::fmt::Debug + Send;
 
+    /// `resume` asks the remote to add this channel to the session it already
+    /// has with `local_pid`. Returns the remote [`Pid`], the offset for local
+    /// [`Sid`]s, the remote secret and whether the remote asked to resume.
     async fn initialize(
         &mut self,
         initializer: bool,
+        resume: bool,
         local_pid: Pid,
         secret: u128,
-    ) -> Result<(Pid, Sid, u128), InitProtocolError<Self::CustomErr>>;
+    ) -> Result<(Pid, Sid, u128, bool), InitProtocolError<Self::CustomErr>>;
 }
 
 /// Generic Network Send Protocol.
@@ -101,9 +113,9 @@ pub trait InitProtocol {
 /// [`ProtocolEvent`].
 ///
 /// A `Stream` MUST be bound to a specific Channel. You MUST NOT switch the
-/// channel to send a stream mid air. We will provide takeover options for
-/// Channel closure in the future to allow keeping a `Stream` over a broken
-/// Channel.
+/// channel to send a stream mid air. After a Channel broke, its `Streams` can
+/// be opened again on another Channel, [`ProtocolEvent::Ack`] tells which
+/// messages need to be sent again.
 ///
 /// [`ProtocolEvent`]: crate::ProtocolEvent
 #[async_trait]
@@ -260,10 +260,29 @@ mod tests {
     #[tokio::test]
     async fn handshake_all_good() {
         let [mut p1, mut p2] = ac_bound(10, None);
-        let r1 = tokio::spawn(async move { p1.initialize(true, Pid::fake(2), 1337).await });
-        let r2 = tokio::spawn(async move { p2.initialize(false, Pid::fake(3), 42).await });
+        let r1 = tokio::spawn(async move { p1.initialize(true, false, Pid::fake(2), 1337).await });
+        let r2 = tokio::spawn(async move { p2.initialize(false, false, Pid::fake(3), 42).await });
         let (r1, r2) = tokio::join!(r1, r2);
-        assert_eq!(r1.unwrap(), Ok((Pid::fake(3), STREAM_ID_OFFSET1, 42)));
-        assert_eq!(r2.unwrap(), Ok((Pid::fake(2), STREAM_ID_OFFSET2, 1337)));
+        assert_eq!(
+            r1.unwrap(),
+            Ok((Pid::fake(3), STREAM_ID_OFFSET1, 42, false))
+        );
+        assert_eq!(
+            r2.unwrap(),
+            Ok((Pid::fake(2), STREAM_ID_OFFSET2, 1337, false))
+        );
+    }
+
+    #[tokio::test]
+    async fn handshake_resume() {
+        let [mut p1, mut p2] = ac_bound(10, None);
+        let r1 = tokio::spawn(async move { p1.initialize(true, false, Pid::fake(2), 1337).await });
+        let r2 = tokio::spawn(async move { p2.initialize(false, true, Pid::fake(3), 42).await });
+        let (r1, r2) = tokio::join!(r1, r2);
+        assert_eq!(r1.unwrap(), Ok((Pid::fake(3), STREAM_ID_OFFSET1, 42, true)));
+        assert_eq!(
+            r2.unwrap(),
+            Ok((Pid::fake(2), STREAM_ID_OFFSET2, 1337, false))
+        );
     }
 }
@@ -94,7 +94,7 @@ where
     metrics: ProtocolMetricCache,
 }
 
-fn is_reliable(p: &Promises) -> bool {
+pub(crate) fn is_reliable(p: &Promises) -> bool {
     p.contains(Promises::ORDERED)
         || p.contains(Promises::CONSISTENCY)
         || p.contains(Promises::GUARANTEED_DELIVERY)
@@ -259,6 +259,12 @@ where
                     self.pending_shutdown = true;
                 }
             },
+            ProtocolEvent::Ack { .. } => {
+                event.to_frame().write_bytes(&mut self.main_buffer);
+                self.drain
+                    .send(QuicDataFormat::with_main(&mut self.main_buffer))
+                    .await?;
+            },
             ProtocolEvent::Message { data, sid } => {
                 self.metrics.smsg_ib(sid, data.len() as u64);
                 self.store.add(data, self.next_mid, sid);
@@ -378,6 +384,9 @@ where
                             //let _ = self.reliable_buffers.delete(sid); // if it was reliable
                             break 'outer Ok(ProtocolEvent::CloseStream { sid });
                         },
+                        ITFrame::Ack { sid, received } => {
+                            break 'outer Ok(ProtocolEvent::Ack { sid, received });
+                        },
                         _ => break 'outer Err(ProtocolError::Violated),
                     };
                 },
@@ -625,11 +634,17 @@ mod tests {
     #[tokio::test]
     async fn handshake_all_good() {
         let [mut p1, mut p2] = quic_bound(10, 0.5, None);
-        let r1 = tokio::spawn(async move { p1.initialize(true, Pid::fake(2), 1337).await });
-        let r2 = tokio::spawn(async move { p2.initialize(false, Pid::fake(3), 42).await });
+        let r1 = tokio::spawn(async move { p1.initialize(true, false, Pid::fake(2), 1337).await });
+        let r2 = tokio::spawn(async move { p2.initialize(false, false, Pid::fake(3), 42).await });
         let (r1, r2) = tokio::join!(r1, r2);
-        assert_eq!(r1.unwrap(), Ok((Pid::fake(3), STREAM_ID_OFFSET1, 42)));
-        assert_eq!(r2.unwrap(), Ok((Pid::fake(2), STREAM_ID_OFFSET2, 1337)));
+        assert_eq!(
+            r1.unwrap(),
+            Ok((Pid::fake(3), STREAM_ID_OFFSET1, 42, false))
+        );
+        assert_eq!(
+            r2.unwrap(),
+            Ok((Pid::fake(2), STREAM_ID_OFFSET2, 1337, false))
+        );
     }
 
     #[tokio::test]
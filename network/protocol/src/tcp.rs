@@ -1,5 +1,6 @@
 use crate::{
     RecvProtocol, SendProtocol, UnreliableDrain, UnreliableSink,
+    crypto::{Cipher, StaticKey, StaticPublicKey},
     error::ProtocolError,
     event::ProtocolEvent,
     frame::{ITFrame, InitFrame, OTFrame},
@@ -12,7 +13,10 @@ use crate::{
 use async_trait::async_trait;
 use bytes::BytesMut;
 use hashbrown::HashMap;
-use std::time::{Duration, Instant};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tracing::info;
 #[cfg(feature = "trace_pedantic")]
 use tracing::trace;
@@ -31,6 +35,9 @@ where
     closing_streams: Vec<Sid>,
     notify_closing_streams: Vec<Sid>,
     pending_shutdown: bool,
+    request_encryption: Option<StaticPublicKey>,
+    static_key: Option<Arc<StaticKey>>,
+    cipher: Option<Cipher>,
     drain: D,
     #[expect(dead_code)]
     last: Instant,
@@ -46,12 +53,27 @@ where
     S: UnreliableSink<DataFormat = BytesMut>,
 {
     buffer: BytesMut,
+    /// Records which aren't decrypted yet, only used if encrypted
+    encrypted_buffer: BytesMut,
+    cipher: Option<Cipher>,
     itmsg_allocator: BytesMut,
     incoming: HashMap<Mid, ITMessage>,
     sink: S,
     metrics: ProtocolMetricCache,
 }
 
+/// Encrypts `data` if the handshake enabled encryption
+fn seal(cipher: &mut Option<Cipher>, data: BytesMut) -> BytesMut {
+    match cipher {
+        Some(cipher) => {
+            let mut sealed = BytesMut::with_capacity(data.len() + 32);
+            cipher.seal(&data, &mut sealed);
+            sealed
+        },
+        None => data,
+    }
+}
+
 impl<D> TcpSendProtocol<D>
 where
     D: UnreliableDrain<DataFormat = BytesMut>,
@@ -64,14 +86,56 @@ where
             closing_streams: vec![],
             notify_closing_streams: vec![],
             pending_shutdown: false,
+            request_encryption: None,
+            static_key: None,
+            cipher: None,
             drain,
             last: Instant::now(),
             metrics,
         }
     }
 
+    /// Like [`new`], but asks the remote to encrypt the channel during the
+    /// handshake, which fails if the remote doesn't support it or doesn't
+    /// authenticate with `remote_key`
+    ///
+    /// [`new`]: TcpSendProtocol::new
+    pub fn new_encrypted(
+        drain: D,
+        metrics: ProtocolMetricCache,
+        remote_key: StaticPublicKey,
+    ) -> Self {
+        Self {
+            request_encryption: Some(remote_key),
+            ..Self::new(drain, metrics)
+        }
+    }
+
+    /// Like [`new`], but accepts when the remote asks to encrypt the channel
+    /// and authenticates with `static_key`
+    ///
+    /// [`new`]: TcpSendProtocol::new
+    pub fn new_with_static_key(
+        drain: D,
+        metrics: ProtocolMetricCache,
+        static_key: Arc<StaticKey>,
+    ) -> Self {
+        Self {
+            static_key: Some(static_key),
+            ..Self::new(drain, metrics)
+        }
+    }
+
+    /// Returns true if the handshake enabled encryption on this channel. The
+    /// responder of an encrypted channel is always authenticated by its
+    /// static key.
+    pub fn encrypted(&self) -> bool { self.cipher.is_some() }
+
     /// returns all promises that this Protocol can take care of
     /// If you open a Stream anyway, unsupported promises are ignored.
+    /// `ENCRYPTED` is only supported if the channel is [`encrypted`].
+    ///
+    /// [`encrypted`]: TcpSendProtocol::encrypted
     pub fn supported_promises() -> Promises {
         Promises::ORDERED
             | Promises::CONSISTENCY
@@ -87,12 +151,34 @@ where
     pub fn new(sink: S, metrics: ProtocolMetricCache) -> Self {
         Self {
             buffer: BytesMut::new(),
+            encrypted_buffer: BytesMut::new(),
+            cipher: None,
             itmsg_allocator: BytesMut::with_capacity(ALLOC_BLOCK),
             incoming: HashMap::new(),
             sink,
             metrics,
         }
     }
+
+    /// Appends a received chunk to the buffer, decrypted if the handshake
+    /// enabled encryption.
+    /// Err => the remote send a modified or invalid record
+    fn append(&mut self, chunk: BytesMut) -> Result<(), ()> {
+        match &mut self.cipher {
+            Some(cipher) => {
+                self.encrypted_buffer.extend_from_slice(&chunk);
+                cipher.open(&mut self.encrypted_buffer, &mut self.buffer)
+            },
+            None => {
+                if self.buffer.is_empty() {
+                    self.buffer = chunk;
+                } else {
+                    self.buffer.extend_from_slice(&chunk);
+                }
+                Ok(())
+            },
+        }
+    }
 }
 
 #[async_trait]
@@ -137,12 +223,16 @@ where
                 self.store
                     .open_stream(sid, prio, promises, guaranteed_bandwidth);
                 event.to_frame().write_bytes(&mut self.buffer);
-                self.drain.send(self.buffer.split()).await?;
+                self.drain
+                    .send(seal(&mut self.cipher, self.buffer.split()))
+                    .await?;
             },
             ProtocolEvent::CloseStream { sid } => {
                 if self.store.try_close_stream(sid) {
                     event.to_frame().write_bytes(&mut self.buffer);
-                    self.drain.send(self.buffer.split()).await?;
+                    self.drain
+                        .send(seal(&mut self.cipher, self.buffer.split()))
+                        .await?;
                 } else {
                     #[cfg(feature = "trace_pedantic")]
                     trace!(?sid, "hold back close stream");
@@ -152,13 +242,21 @@ where
             ProtocolEvent::Shutdown => {
                 if self.store.is_empty() {
                     event.to_frame().write_bytes(&mut self.buffer);
-                    self.drain.send(self.buffer.split()).await?;
+                    self.drain
+                        .send(seal(&mut self.cipher, self.buffer.split()))
+                        .await?;
                 } else {
                     #[cfg(feature = "trace_pedantic")]
                     trace!("hold back shutdown");
                     self.pending_shutdown = true;
                 }
             },
+            ProtocolEvent::Ack { .. } => {
+                event.to_frame().write_bytes(&mut self.buffer);
+                self.drain
+                    .send(seal(&mut self.cipher, self.buffer.split()))
+                    .await?;
+            },
             ProtocolEvent::Message { data, sid } => {
                 self.metrics.smsg_ib(sid, data.len() as u64);
                 self.store.add(data, self.next_mid, sid);
@@ -184,7 +282,9 @@ where
             }
             frame.write_bytes(&mut self.buffer);
         }
-        self.drain.send(self.buffer.split()).await?;
+        self.drain
+            .send(seal(&mut self.cipher, self.buffer.split()))
+            .await?;
         self.metrics
             .sdata_frames_b(data_frames, data_bandwidth as u64);
 
@@ -194,7 +294,9 @@ where
                 #[cfg(feature = "trace_pedantic")]
                 trace!(?sid, "close stream, as it's now empty");
                 OTFrame::CloseStream { sid }.write_bytes(&mut self.buffer);
-                self.drain.send(self.buffer.split()).await?;
+                self.drain
+                    .send(seal(&mut self.cipher, self.buffer.split()))
+                    .await?;
                 finished_streams.push(i);
             }
         }
@@ -218,7 +320,9 @@ where
             #[cfg(feature = "trace_pedantic")]
             trace!("shutdown, as it's now empty");
             OTFrame::Shutdown {}.write_bytes(&mut self.buffer);
-            self.drain.send(self.buffer.split()).await?;
+            self.drain
+                .send(seal(&mut self.cipher, self.buffer.split()))
+                .await?;
             self.pending_shutdown = false;
         }
         Ok(data_bandwidth as u64)
@@ -257,6 +361,9 @@ where
                             ITFrame::CloseStream { sid } => {
                                 break 'outer Ok(ProtocolEvent::CloseStream { sid });
                             },
+                            ITFrame::Ack { sid, received } => {
+                                break 'outer Ok(ProtocolEvent::Ack { sid, received });
+                            },
                             ITFrame::DataHeader { sid, mid, length } => {
                                 let m = ITMessage::new(sid, length, &mut self.itmsg_allocator);
                                 self.metrics.rmsg_ib(sid, length);
@@ -297,10 +404,9 @@ where
                 }
             }
             let chunk = self.sink.recv().await?;
-            if self.buffer.is_empty() {
-                self.buffer = chunk;
-            } else {
-                self.buffer.extend_from_slice(&chunk);
+            if self.append(chunk).is_err() {
+                info!("protocol violation by remote side: send invalid encrypted record");
+                return Err(ProtocolError::Violated);
             }
         }
     }
@@ -316,8 +422,14 @@ where
     async fn send(&mut self, frame: InitFrame) -> Result<(), ProtocolError<Self::CustomErr>> {
         let mut buffer = BytesMut::with_capacity(500);
         frame.write_bytes(&mut buffer);
-        self.drain.send(buffer).await
+        self.drain.send(seal(&mut self.cipher, buffer)).await
     }
+
+    fn request_encryption(&self) -> Option<StaticPublicKey> { self.request_encryption }
+
+    fn static_key(&self) -> Option<&StaticKey> { self.static_key.as_deref() }
+
+    fn enable_encryption(&mut self, cipher: Cipher) { self.cipher = Some(cipher); }
 }
 
 #[async_trait]
@@ -329,14 +441,24 @@ where
 
     async fn recv(&mut self) -> Result<InitFrame, ProtocolError<Self::CustomErr>> {
         while self.buffer.len() < 100 {
-            let chunk = self.sink.recv().await?;
-            self.buffer.extend_from_slice(&chunk);
             if let Some(frame) = InitFrame::read_frame(&mut self.buffer) {
                 return Ok(frame);
             }
+            let chunk = self.sink.recv().await?;
+            if self.append(chunk).is_err() {
+                // Violated is not allowed during the handshake
+                return Ok(InitFrame::Raw(b"invalid encrypted record".to_vec()));
+            }
         }
         Err(ProtocolError::Violated)
     }
+
+    fn enable_encryption(&mut self, cipher: Cipher) {
+        // The remote waits for our key exchange before it encrypts, so there
+        // shouldn't be anything left. If there is, it's encrypted already.
+        self.encrypted_buffer = self.buffer.split();
+        self.cipher = Some(cipher);
+    }
 }
 
 #[cfg(test)]
@@ -411,7 +533,8 @@ mod test_utils {
 mod tests {
     use crate::{
         InitProtocol, ProtocolEvent, RecvProtocol, SendProtocol,
-        error::ProtocolError,
+        crypto::StaticKey,
+        error::{InitProtocolError, ProtocolError},
         frame::OTFrame,
         metrics::{ProtocolMetricCache, ProtocolMetrics, RemoveReason},
         tcp::test_utils::*,
@@ -423,11 +546,83 @@ mod tests {
     #[tokio::test]
     async fn handshake_all_good() {
         let [mut p1, mut p2] = tcp_bound(10, None);
-        let r1 = tokio::spawn(async move { p1.initialize(true, Pid::fake(2), 1337).await });
-        let r2 = tokio::spawn(async move { p2.initialize(false, Pid::fake(3), 42).await });
+        let r1 = tokio::spawn(async move { p1.initialize(true, false, Pid::fake(2), 1337).await });
+        let r2 = tokio::spawn(async move { p2.initialize(false, false, Pid::fake(3), 42).await });
+        let (r1, r2) = tokio::join!(r1, r2);
+        assert_eq!(
+            r1.unwrap(),
+            Ok((Pid::fake(3), STREAM_ID_OFFSET1, 42, false))
+        );
+        assert_eq!(
+            r2.unwrap(),
+            Ok((Pid::fake(2), STREAM_ID_OFFSET2, 1337, false))
+        );
+    }
+
+    #[tokio::test]
+    async fn handshake_encrypted() {
+        let [mut p1, mut p2] = tcp_bound(10, None);
+        let (static_key, _) = StaticKey::generate();
+        p1.0.request_encryption = Some(static_key.public_key());
+        p2.0.static_key = Some(Arc::new(static_key));
+        let r1 = tokio::spawn(async move {
+            let r = p1.initialize(true, false, Pid::fake(2), 1337).await;
+            (r, p1)
+        });
+        let r2 = tokio::spawn(async move {
+            let r = p2.initialize(false, false, Pid::fake(3), 42).await;
+            (r, p2)
+        });
+        let (r1, r2) = tokio::join!(r1, r2);
+        let ((r1, mut p1), (r2, mut p2)) = (r1.unwrap(), r2.unwrap());
+        assert_eq!(r1, Ok((Pid::fake(3), STREAM_ID_OFFSET1, 42, false)));
+        assert_eq!(r2, Ok((Pid::fake(2), STREAM_ID_OFFSET2, 1337, false)));
+        assert!(p1.0.encrypted());
+        assert!(p2.0.encrypted());
+
+        let event = ProtocolEvent::OpenStream {
+            sid: Sid::new(10),
+            prio: 0u8,
+            promises: Promises::ORDERED | Promises::ENCRYPTED,
+            guaranteed_bandwidth: 0,
+        };
+        p1.0.send(event.clone()).await.unwrap();
+        assert_eq!(p2.1.recv().await.unwrap(), event);
+        let event = ProtocolEvent::Message {
+            sid: Sid::new(10),
+            data: Bytes::from(&[42u8; 5000][..]),
+        };
+        p1.0.send(event.clone()).await.unwrap();
+        p1.0.flush(1_000_000, Duration::from_secs(1)).await.unwrap();
+        assert_eq!(p2.1.recv().await.unwrap(), event);
+        p2.0.send(ProtocolEvent::Shutdown).await.unwrap();
+        assert_eq!(p1.1.recv().await.unwrap(), ProtocolEvent::Shutdown);
+    }
+
+    #[tokio::test]
+    async fn handshake_encrypted_wrong_static_key() {
+        let [mut p1, mut p2] = tcp_bound(10, None);
+        let (static_key, _) = StaticKey::generate();
+        let (pinned_key, _) = StaticKey::generate();
+        p1.0.request_encryption = Some(pinned_key.public_key());
+        p2.0.static_key = Some(Arc::new(static_key));
+        let r1 = tokio::spawn(async move { p1.initialize(true, false, Pid::fake(2), 1337).await });
+        let r2 = tokio::spawn(async move { p2.initialize(false, false, Pid::fake(3), 42).await });
+        let (r1, r2) = tokio::join!(r1, r2);
+        assert_eq!(r1.unwrap(), Err(InitProtocolError::WrongStaticKey));
+        assert_eq!(r2.unwrap(), Err(InitProtocolError::Custom(())));
+    }
+
+    #[tokio::test]
+    async fn handshake_encryption_unsupported() {
+        let [mut p1, mut p2] = tcp_bound(10, None);
+        let (static_key, _) = StaticKey::generate();
+        p1.0.request_encryption = Some(static_key.public_key());
+        let r1 = tokio::spawn(async move { p1.initialize(true, false, Pid::fake(2), 1337).await });
+        let r2 = tokio::spawn(async move { p2.initialize(false, false, Pid::fake(3), 42).await });
         let (r1, r2) = tokio::join!(r1, r2);
-        assert_eq!(r1.unwrap(), Ok((Pid::fake(3), STREAM_ID_OFFSET1, 42)));
-        assert_eq!(r2.unwrap(), Ok((Pid::fake(2), STREAM_ID_OFFSET2, 1337)));
+        assert_eq!(r1.unwrap(), Err(InitProtocolError::Custom(())));
+        assert_eq!(r2.unwrap(), Err(InitProtocolError::NotId));
     }
 
     #[tokio::test]
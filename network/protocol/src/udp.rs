@@ -1,37 +1,1332 @@
-// TODO: quick and dirty which activly waits for an ack!
-/*
-UDP protocol
-
-All Good Case:
-S --HEADER--> R
-S --DATA--> R
-S --DATA--> R
-S <--FINISHED-- R
-
-
-Delayed HEADER:
-S --HEADER-->
-S --DATA--> R // STORE IT
-  --HEADER--> R // apply left data and continue
-S --DATA--> R
-S <--FINISHED-- R
-
-
-NO HEADER:
-S --HEADER--> !
-S --DATA--> R // STORE IT
-S --DATA--> R // STORE IT
-S <--MISSING_HEADER-- R // SEND AFTER 10 ms after DATA1
-S --HEADER--> R
-S <--FINISHED-- R
-
-
-NO DATA:
-S --HEADER--> R
-S --DATA--> R
-S --DATA--> !
-S --STATUS--> R
-S <--MISSING_DATA -- R
-S --DATA--> R
-S <--FINISHED-- R
-*/
+//! UDP protocol
+//!
+//! UDP neither guarantees delivery nor order of datagrams, so this protocol
+//! adds both for streams that need it and skips it for the others. Every
+//! datagram starts with a packet kind:
+//!
+//!  - `INIT`: a frame of the handshake, with its index. The connecting side
+//!    starts the handshake and the transport resends its latest frame till the
+//!    listener answers. The listener only answers: each frame it receives again
+//!    is answered with its latest frame once. The first frame is padded to
+//!    [`HELLO_SIZE`], more than the listener ever answers with, so a spoofed
+//!    source address can't make a listener send more than it received.
+//!  - `RELIABLE`: a sequence number followed by a chunk of the same frame
+//!    stream the TCP protocol uses. The receiver reassembles the chunks in
+//!    order and acknowledges them, the sender resends them till they are.
+//!  - `UNRELIABLE`: a fragment of a message on a stream without `ORDERED`,
+//!    `CONSISTENCY` or `GUARANTEED_DELIVERY`. Lost fragments are never resent.
+//!    Once a message is complete, older incomplete messages of the stream are
+//!    dropped as well as older messages arriving late. Only a few incomplete
+//!    messages are kept per stream, the oldest ones are dropped first.
+//!  - `ACK`: the next sequence number the receiver expects and which of the
+//!    following 64 packets it already stored. Also sent as keep alive.
+//!
+//! ```text
+//! All Good Case:
+//! S --RELIABLE(0)--> R
+//! S --RELIABLE(1)--> R
+//! S <--ACK(2)-- R
+//!
+//! Lost Packet:
+//! S --RELIABLE(0)--> R
+//! S --RELIABLE(1)--> !
+//! S --RELIABLE(2)--> R // STORE IT
+//! S <--ACK(1, 2)-- R
+//! S --RELIABLE(1)--> R // after the retransmission timeout, apply stored data
+//! S <--ACK(3)-- R
+//! ```
+use crate::{
+    RecvProtocol, SendProtocol, UnreliableDrain, UnreliableSink,
+    error::ProtocolError,
+    event::ProtocolEvent,
+    frame::{ITFrame, InitFrame, OTFrame},
+    handshake::{ReliableDrain, ReliableSink},
+    message::{ALLOC_BLOCK, ITMessage, OTMessage},
+    metrics::{ProtocolMetricCache, RemoveReason},
+    prio::PrioManager,
+    quic::is_reliable,
+    types::{Bandwidth, Mid, Promises, Sid},
+};
+use async_trait::async_trait;
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use hashbrown::{HashMap, HashSet};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tracing::info;
+#[cfg(feature = "trace_pedantic")]
+use tracing::trace;
+
+const PACKET_INIT: u8 = 1;
+const PACKET_RELIABLE: u8 = 2;
+const PACKET_UNRELIABLE: u8 = 3;
+const PACKET_ACK: u8 = 4;
+
+/// Fits into the 1500 bytes MTU of ethernet after the IPv6 and UDP headers
+pub(crate) const MAX_PACKET_SIZE: usize = 1452;
+// Sizes WITH the packet kind
+const RELIABLE_HEADER_SIZE: usize = 9;
+const UNRELIABLE_HEADER_SIZE: usize = 29;
+const ACK_SIZE: usize = 17;
+const _: () = assert!(
+    UNRELIABLE_HEADER_SIZE + OTMessage::FRAME_DATA_SIZE as usize <= MAX_PACKET_SIZE,
+    "a data frame must fit into a single fragment"
+);
+
+/// Size of the first datagram of a handshake
+const HELLO_SIZE: usize = 256;
+/// Maximum of unacknowledged reliable packets
+const MAX_IN_FLIGHT: usize = 512;
+/// Packets received during the handshake which are kept for later
+const MAX_PENDING_PACKETS: usize = 1024;
+/// Incomplete messages kept per unreliable stream, the oldest ones are dropped
+/// first
+const MAX_INCOMPLETE_MESSAGES: usize = 8;
+/// Received bytes of incomplete messages kept per unreliable stream, enough
+/// for a single message of the maximum size
+const MAX_INCOMPLETE_BYTES: usize = ALLOC_BLOCK;
+const INITIAL_RTT: Duration = Duration::from_millis(100);
+const MIN_RTO: Duration = Duration::from_millis(50);
+const MAX_RTO: Duration = Duration::from_secs(1);
+/// Send an `ACK` if nothing else was sent for this long, so the remote knows
+/// we are still there
+const KEEP_ALIVE: Duration = Duration::from_secs(1);
+
+/// Returns true if `datagram` is the first packet a remote sends to open a
+/// new UDP channel. Listeners should ignore other datagrams from unknown
+/// addresses.
+pub fn is_udp_handshake(datagram: &[u8]) -> bool {
+    datagram.len() >= HELLO_SIZE && matches!(datagram, [PACKET_INIT, 0, ..])
+}
+
+/// Returns the index of the handshake frame in `datagram`, if it is one. Used
+/// by transports to resend lost handshake frames, see the [module level
+/// documentation](self).
+pub fn udp_init_index(datagram: &[u8]) -> Option<u8> {
+    match datagram {
+        [PACKET_INIT, index, ..] => Some(*index),
+        _ => None,
+    }
+}
+
+/// State shared between the send and recv half of one channel
+#[derive(Debug, Default)]
+struct SharedState {
+    /// The remote received all our reliable packets before this
+    remote_ack: u64,
+    /// Bit `n` is set if the remote received packet `remote_ack + 1 + n`
+    remote_selective: u64,
+    /// Next reliable packet expected from the remote
+    local_ack: u64,
+    /// Bit `n` is set if we stored packet `local_ack + 1 + n`
+    local_selective: u64,
+    /// `local_ack` changed or a duplicate arrived since the last `ACK`
+    ack_pending: bool,
+    /// Streams without reliability promises, opened by either side
+    unreliable_streams: HashSet<Sid>,
+}
+
+impl SharedState {
+    fn remote_received(&self, seq: u64) -> bool {
+        seq < self.remote_ack
+            || (seq > self.remote_ack
+                && seq - self.remote_ack <= 64
+                && self.remote_selective & (1 << (seq - self.remote_ack - 1)) != 0)
+    }
+}
+
+/// Reliable packet which wasn't acknowledged yet
+#[derive(Debug)]
+struct InFlight {
+    seq: u64,
+    packet: Bytes,
+    sent: Instant,
+    resent: bool,
+}
+
+/// Part of a message on an unreliable stream
+#[derive(Debug, PartialEq, Eq)]
+struct Fragment {
+    mid: Mid,
+    sid: Sid,
+    /// Length of the whole message
+    length: u64,
+    index: u32,
+    data: Bytes,
+}
+
+impl Fragment {
+    fn write_bytes(self, bytes: &mut BytesMut) {
+        bytes.reserve(UNRELIABLE_HEADER_SIZE + self.data.len());
+        bytes.put_u8(PACKET_UNRELIABLE);
+        bytes.put_u64_le(self.mid);
+        self.sid.to_bytes(bytes);
+        bytes.put_u64_le(self.length);
+        bytes.put_u32_le(self.index);
+        bytes.put_slice(&self.data);
+    }
+
+    /// `bytes` without the packet kind
+    fn read(mut bytes: BytesMut) -> Option<Self> {
+        if bytes.len() < UNRELIABLE_HEADER_SIZE - 1 {
+            return None;
+        }
+        Some(Self {
+            mid: bytes.get_u64_le(),
+            sid: Sid::from_bytes(&mut bytes),
+            length: bytes.get_u64_le(),
+            index: bytes.get_u32_le(),
+            data: bytes.freeze(),
+        })
+    }
+
+    fn count(length: u64) -> u64 { length.div_ceil(OTMessage::FRAME_DATA_SIZE) }
+}
+
+/// Unreliable message which is currently sent as fragments
+#[derive(Debug)]
+struct OutgoingFragments {
+    sid: Sid,
+    length: u64,
+    next_index: u32,
+}
+
+/// Unreliable message which is currently received as fragments. Only the
+/// fragments that arrived are stored, so a remote can't make us allocate whole
+/// messages by sending a single fragment of each.
+#[derive(Debug)]
+struct IncomingFragments {
+    sid: Sid,
+    length: u64,
+    parts: BTreeMap<u32, Bytes>,
+    received: usize,
+}
+
+/// UDP implementation of [`SendProtocol`]
+///
+/// [`SendProtocol`]: crate::SendProtocol
+#[derive(Debug)]
+pub struct UdpSendProtocol<D>
+where
+    D: UnreliableDrain<DataFormat = BytesMut>,
+{
+    /// Frames of reliable streams which aren't sent yet
+    reliable_buffer: BytesMut,
+    store: PrioManager,
+    unreliable_streams: HashSet<Sid>,
+    fragmenting: HashMap<Mid, OutgoingFragments>,
+    next_mid: Mid,
+    next_seq: u64,
+    in_flight: VecDeque<InFlight>,
+    srtt: Duration,
+    init_index: u8,
+    closing_streams: Vec<Sid>,
+    notify_closing_streams: Vec<Sid>,
+    pending_shutdown: bool,
+    last_send: Instant,
+    shared: Arc<Mutex<SharedState>>,
+    drain: D,
+    metrics: ProtocolMetricCache,
+}
+
+/// UDP implementation of [`RecvProtocol`]
+///
+/// [`RecvProtocol`]: crate::RecvProtocol
+#[derive(Debug)]
+pub struct UdpRecvProtocol<S>
+where
+    S: UnreliableSink<DataFormat = BytesMut>,
+{
+    /// In order data of reliable packets
+    reliable_buffer: BytesMut,
+    out_of_order: BTreeMap<u64, BytesMut>,
+    /// Packets which arrived before the handshake was done
+    pending: VecDeque<BytesMut>,
+    early_init_frames: Vec<(u8, BytesMut)>,
+    next_init_index: u8,
+    itmsg_allocator: BytesMut,
+    incoming: HashMap<Mid, ITMessage>,
+    fragments: HashMap<Mid, IncomingFragments>,
+    /// Newest message delivered on each unreliable stream
+    newest: HashMap<Sid, Mid>,
+    shared: Arc<Mutex<SharedState>>,
+    sink: S,
+    metrics: ProtocolMetricCache,
+}
+
+impl<D> UdpSendProtocol<D>
+where
+    D: UnreliableDrain<DataFormat = BytesMut>,
+{
+    pub fn new(drain: D, metrics: ProtocolMetricCache) -> Self {
+        Self {
+            reliable_buffer: BytesMut::new(),
+            store: PrioManager::new(metrics.clone()),
+            unreliable_streams: HashSet::new(),
+            fragmenting: HashMap::new(),
+            next_mid: 0u64,
+            next_seq: 0u64,
+            in_flight: VecDeque::new(),
+            srtt: INITIAL_RTT,
+            init_index: 0,
+            closing_streams: vec![],
+            notify_closing_streams: vec![],
+            pending_shutdown: false,
+            last_send: Instant::now(),
+            shared: Arc::new(Mutex::new(SharedState::default())),
+            drain,
+            metrics,
+        }
+    }
+
+    /// returns all promises that this Protocol can take care of
+    /// If you open a Stream anyway, unsupported promises are ignored.
+    /// Streams without any of `ORDERED`, `CONSISTENCY` and
+    /// `GUARANTEED_DELIVERY` are sent unreliable.
+    pub fn supported_promises() -> Promises {
+        Promises::ORDERED
+            | Promises::CONSISTENCY
+            | Promises::GUARANTEED_DELIVERY
+            | Promises::COMPRESSED
+    }
+
+    fn open_stream(
+        &mut self,
+        sid: Sid,
+        prio: u8,
+        promises: Promises,
+        guaranteed_bandwidth: Bandwidth,
+    ) {
+        self.store
+            .open_stream(sid, prio, promises, guaranteed_bandwidth);
+        if !is_reliable(&promises) {
+            self.unreliable_streams.insert(sid);
+            self.shared.lock().unwrap().unreliable_streams.insert(sid);
+        }
+    }
+
+    fn try_close_stream(&mut self, sid: Sid) -> bool {
+        let closed = self.store.try_close_stream(sid);
+        if closed && self.unreliable_streams.remove(&sid) {
+            self.shared.lock().unwrap().unreliable_streams.remove(&sid);
+        }
+        closed
+    }
+
+    /// Split the buffered reliable frames into packets, as far as the window
+    /// allows
+    async fn send_reliable(&mut self) -> Result<(), ProtocolError<D::CustomErr>> {
+        while !self.reliable_buffer.is_empty() && self.in_flight.len() < MAX_IN_FLIGHT {
+            let len = self
+                .reliable_buffer
+                .len()
+                .min(MAX_PACKET_SIZE - RELIABLE_HEADER_SIZE);
+            let mut packet = BytesMut::with_capacity(RELIABLE_HEADER_SIZE + len);
+            packet.put_u8(PACKET_RELIABLE);
+            packet.put_u64_le(self.next_seq);
+            packet.put_slice(&self.reliable_buffer.split_to(len));
+            let packet = packet.freeze();
+            self.drain.send(BytesMut::from(&packet[..])).await?;
+            self.in_flight.push_back(InFlight {
+                seq: self.next_seq,
+                packet,
+                sent: Instant::now(),
+                resent: false,
+            });
+            self.next_seq += 1;
+            self.last_send = Instant::now();
+        }
+        Ok(())
+    }
+
+    async fn send_unreliable(&mut self, frame: OTFrame) -> Result<(), ProtocolError<D::CustomErr>> {
+        match frame {
+            OTFrame::DataHeader { mid, sid, length } => {
+                self.fragmenting.insert(mid, OutgoingFragments {
+                    sid,
+                    length,
+                    next_index: 0,
+                });
+            },
+            OTFrame::Data { mid, data } => {
+                let Some(message) = self.fragmenting.get_mut(&mid) else {
+                    return Ok(());
+                };
+                let fragment = Fragment {
+                    mid,
+                    sid: message.sid,
+                    length: message.length,
+                    index: message.next_index,
+                    data,
+                };
+                message.next_index += 1;
+                if u64::from(message.next_index) >= Fragment::count(message.length) {
+                    self.fragmenting.remove(&mid);
+                }
+                let mut packet = BytesMut::new();
+                fragment.write_bytes(&mut packet);
+                self.drain.send(packet).await?;
+                self.last_send = Instant::now();
+            },
+            // PrioManager only returns data frames
+            frame => frame.write_bytes(&mut self.reliable_buffer),
+        }
+        Ok(())
+    }
+
+    /// Forget acknowledged packets and resend the ones which are probably lost
+    async fn resend_lost(&mut self) -> Result<(), ProtocolError<D::CustomErr>> {
+        {
+            let shared = self.shared.lock().unwrap();
+            let srtt = &mut self.srtt;
+            self.in_flight.retain(|packet| {
+                let received = shared.remote_received(packet.seq);
+                // Resent packets don't tell which of the copies was acknowledged
+                if received && !packet.resent {
+                    *srtt = (*srtt * 7 + packet.sent.elapsed()) / 8;
+                }
+                !received
+            });
+        }
+
+        let rto = (self.srtt * 2).clamp(MIN_RTO, MAX_RTO);
+        let now = Instant::now();
+        for packet in self.in_flight.iter_mut() {
+            if now.duration_since(packet.sent) >= rto {
+                #[cfg(feature = "trace_pedantic")]
+                trace!(seq = packet.seq, "resend reliable packet");
+                self.drain.send(BytesMut::from(&packet.packet[..])).await?;
+                packet.sent = now;
+                packet.resent = true;
+                self.last_send = now;
+            }
+        }
+        Ok(())
+    }
+
+    async fn send_ack(&mut self) -> Result<(), ProtocolError<D::CustomErr>> {
+        let ack = {
+            let mut shared = self.shared.lock().unwrap();
+            if !shared.ack_pending && self.last_send.elapsed() < KEEP_ALIVE {
+                return Ok(());
+            }
+            shared.ack_pending = false;
+            (shared.local_ack, shared.local_selective)
+        };
+        let mut packet = BytesMut::with_capacity(ACK_SIZE);
+        packet.put_u8(PACKET_ACK);
+        packet.put_u64_le(ack.0);
+        packet.put_u64_le(ack.1);
+        self.drain.send(packet).await?;
+        self.last_send = Instant::now();
+        Ok(())
+    }
+}
+
+impl<S> UdpRecvProtocol<S>
+where
+    S: UnreliableSink<DataFormat = BytesMut>,
+{
+    /// Both halves of a channel exchange acknowledgements, so the recv half
+    /// needs to be created from the send half
+    pub fn new<D>(sink: S, send: &UdpSendProtocol<D>, metrics: ProtocolMetricCache) -> Self
+    where
+        D: UnreliableDrain<DataFormat = BytesMut>,
+    {
+        Self {
+            reliable_buffer: BytesMut::new(),
+            out_of_order: BTreeMap::new(),
+            pending: VecDeque::new(),
+            early_init_frames: vec![],
+            next_init_index: 0,
+            itmsg_allocator: BytesMut::with_capacity(ALLOC_BLOCK),
+            incoming: HashMap::new(),
+            fragments: HashMap::new(),
+            newest: HashMap::new(),
+            shared: Arc::clone(&send.shared),
+            sink,
+            metrics,
+        }
+    }
+
+    fn handle_ack(&mut self, mut packet: BytesMut) -> Result<(), ProtocolError<S::CustomErr>> {
+        if packet.len() < ACK_SIZE - 1 {
+            return Err(ProtocolError::Violated);
+        }
+        let ack = packet.get_u64_le();
+        let selective = packet.get_u64_le();
+        let mut shared = self.shared.lock().unwrap();
+        // ACKs can be reordered too
+        if ack >= shared.remote_ack {
+            shared.remote_ack = ack;
+            shared.remote_selective = selective;
+        }
+        Ok(())
+    }
+
+    fn handle_reliable(&mut self, mut packet: BytesMut) -> Result<(), ProtocolError<S::CustomErr>> {
+        if packet.len() < RELIABLE_HEADER_SIZE - 1 {
+            return Err(ProtocolError::Violated);
+        }
+        let seq = packet.get_u64_le();
+        let mut shared = self.shared.lock().unwrap();
+        // Also acknowledge duplicates, the last ACK might got lost
+        shared.ack_pending = true;
+        if seq == shared.local_ack {
+            if self.reliable_buffer.is_empty() {
+                self.reliable_buffer = packet;
+            } else {
+                self.reliable_buffer.extend_from_slice(&packet);
+            }
+            shared.local_ack += 1;
+            while let Some(packet) = self.out_of_order.remove(&shared.local_ack) {
+                self.reliable_buffer.extend_from_slice(&packet);
+                shared.local_ack += 1;
+            }
+        } else if seq > shared.local_ack && seq - shared.local_ack <= MAX_IN_FLIGHT as u64 {
+            self.out_of_order.insert(seq, packet);
+        }
+        let base = shared.local_ack + 1;
+        shared.local_selective = self
+            .out_of_order
+            .range(base..base + 64)
+            .fold(0, |bits, (seq, _)| bits | (1 << (seq - base)));
+        Ok(())
+    }
+
+    fn handle_frame(
+        &mut self,
+        frame: ITFrame,
+    ) -> Result<Option<ProtocolEvent>, ProtocolError<S::CustomErr>> {
+        #[cfg(feature = "trace_pedantic")]
+        trace!(?frame, "recv");
+        Ok(match frame {
+            ITFrame::Shutdown => Some(ProtocolEvent::Shutdown),
+            ITFrame::OpenStream {
+                sid,
+                prio,
+                promises,
+                guaranteed_bandwidth,
+            } => {
+                if !is_reliable(&promises) {
+                    self.shared.lock().unwrap().unreliable_streams.insert(sid);
+                }
+                Some(ProtocolEvent::OpenStream {
+                    sid,
+                    prio: prio.min(crate::types::HIGHEST_PRIO),
+                    promises,
+                    guaranteed_bandwidth,
+                })
+            },
+            ITFrame::CloseStream { sid } => {
+                if self.newest.remove(&sid).is_some() {
+                    self.drop_fragments(|_, message| message.sid == sid);
+                }
+                Some(ProtocolEvent::CloseStream { sid })
+            },
+            ITFrame::Ack { sid, received } => Some(ProtocolEvent::Ack { sid, received }),
+            ITFrame::DataHeader { sid, mid, length } => {
+                let m = ITMessage::new(sid, length, &mut self.itmsg_allocator);
+                self.metrics.rmsg_ib(sid, length);
+                self.incoming.insert(mid, m);
+                None
+            },
+            ITFrame::Data { mid, data } => {
+                self.metrics.rdata_frames_b(data.len() as u64);
+                let m = match self.incoming.get_mut(&mid) {
+                    Some(m) => m,
+                    None => {
+                        info!(
+                            ?mid,
+                            "protocol violation by remote side: send Data before Header"
+                        );
+                        return Err(ProtocolError::Violated);
+                    },
+                };
+                m.data.extend_from_slice(&data);
+                if m.data.len() == m.length as usize {
+                    // finished, yay
+                    let m = self.incoming.remove(&mid).ok_or(ProtocolError::Violated)?;
+                    self.metrics
+                        .rmsg_ob(m.sid, RemoveReason::Finished, m.data.len() as u64);
+                    Some(ProtocolEvent::Message {
+                        sid: m.sid,
+                        data: m.data.freeze(),
+                    })
+                } else {
+                    None
+                }
+            },
+        })
+    }
+
+    fn handle_fragment(
+        &mut self,
+        packet: BytesMut,
+    ) -> Result<Option<ProtocolEvent>, ProtocolError<S::CustomErr>> {
+        let Fragment {
+            mid,
+            sid,
+            length,
+            index,
+            data,
+        } = Fragment::read(packet).ok_or(ProtocolError::Violated)?;
+        if !self
+            .shared
+            .lock()
+            .unwrap()
+            .unreliable_streams
+            .contains(&sid)
+        {
+            // The stream isn't open yet or was closed already
+            return Ok(None);
+        }
+        if self.newest.get(&sid).is_some_and(|&newest| mid <= newest) {
+            // A newer message was delivered already
+            return Ok(None);
+        }
+        let count = Fragment::count(length);
+        let offset = u64::from(index) * OTMessage::FRAME_DATA_SIZE;
+        if length == 0
+            || length > ALLOC_BLOCK as u64
+            || u64::from(index) >= count
+            || data.len() as u64 != (length - offset).min(OTMessage::FRAME_DATA_SIZE)
+        {
+            return Err(ProtocolError::Violated);
+        }
+
+        if !self.fragments.contains_key(&mid) {
+            let incomplete = self.fragments.iter().filter(|(_, m)| m.sid == sid);
+            if incomplete.clone().count() >= MAX_INCOMPLETE_MESSAGES {
+                // make room by dropping the oldest message, unless it's this one
+                let oldest = incomplete.map(|(&mid, _)| mid).min().unwrap_or(mid);
+                if oldest > mid {
+                    return Ok(None);
+                }
+                self.drop_fragments(|other, _| other == oldest);
+            }
+            self.metrics.rmsg_ib(sid, length);
+            self.fragments.insert(mid, IncomingFragments {
+                sid,
+                length,
+                parts: BTreeMap::new(),
+                received: 0,
+            });
+        }
+        let message = self
+            .fragments
+            .get_mut(&mid)
+            .ok_or(ProtocolError::Violated)?;
+        if message.sid != sid || message.length != length {
+            return Err(ProtocolError::Violated);
+        }
+        if message.parts.contains_key(&index) {
+            // duplicate
+            return Ok(None);
+        }
+        message.received += data.len();
+        self.metrics.rdata_frames_b(data.len() as u64);
+        message.parts.insert(index, data);
+        if (message.parts.len() as u64) < count {
+            self.limit_fragments(sid, mid);
+            return Ok(None);
+        }
+
+        let message = self.fragments.remove(&mid).ok_or(ProtocolError::Violated)?;
+        self.metrics
+            .rmsg_ob(sid, RemoveReason::Finished, message.length);
+        let mut data = BytesMut::with_capacity(message.length as usize);
+        for part in message.parts.into_values() {
+            data.extend_from_slice(&part);
+        }
+        self.newest.insert(sid, mid);
+        // incomplete older messages are stale now
+        self.drop_fragments(|other, message| message.sid == sid && other < mid);
+        Ok(Some(ProtocolEvent::Message {
+            sid,
+            data: data.freeze(),
+        }))
+    }
+
+    /// Drops the oldest incomplete messages of a stream other than `keep`, till
+    /// the stream is below [`MAX_INCOMPLETE_BYTES`] again
+    fn limit_fragments(&mut self, sid: Sid, keep: Mid) {
+        let mut received: usize = self
+            .fragments
+            .values()
+            .filter(|message| message.sid == sid)
+            .map(|message| message.received)
+            .sum();
+        while received > MAX_INCOMPLETE_BYTES {
+            let Some((oldest, bytes)) = self
+                .fragments
+                .iter()
+                .filter(|(mid, message)| message.sid == sid && **mid != keep)
+                .map(|(&mid, message)| (mid, message.received))
+                .min_by_key(|(mid, _)| *mid)
+            else {
+                break;
+            };
+            self.drop_fragments(|other, _| other == oldest);
+            received -= bytes;
+        }
+    }
+
+    fn drop_fragments(&mut self, drop: impl Fn(Mid, &IncomingFragments) -> bool) {
+        let metrics = &mut self.metrics;
+        self.fragments.retain(|&mid, message| {
+            if drop(mid, message) {
+                metrics.rmsg_ob(message.sid, RemoveReason::Dropped, message.length);
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
+
+#[async_trait]
+impl<D> SendProtocol for UdpSendProtocol<D>
+where
+    D: UnreliableDrain<DataFormat = BytesMut>,
+{
+    type CustomErr = D::CustomErr;
+
+    fn notify_from_recv(&mut self, event: ProtocolEvent) {
+        match event {
+            ProtocolEvent::OpenStream {
+                sid,
+                prio,
+                promises,
+                guaranteed_bandwidth,
+            } => {
+                self.open_stream(sid, prio, promises, guaranteed_bandwidth);
+            },
+            ProtocolEvent::CloseStream { sid } => {
+                if !self.try_close_stream(sid) {
+                    #[cfg(feature = "trace_pedantic")]
+                    trace!(?sid, "hold back notify close stream");
+                    self.notify_closing_streams.push(sid);
+                }
+            },
+            _ => {},
+        }
+    }
+
+    async fn send(&mut self, event: ProtocolEvent) -> Result<(), ProtocolError<Self::CustomErr>> {
+        #[cfg(feature = "trace_pedantic")]
+        trace!(?event, "send");
+        match event {
+            ProtocolEvent::OpenStream {
+                sid,
+                prio,
+                promises,
+                guaranteed_bandwidth,
+            } => {
+                self.open_stream(sid, prio, promises, guaranteed_bandwidth);
+                event.to_frame().write_bytes(&mut self.reliable_buffer);
+                self.send_reliable().await?;
+            },
+            ProtocolEvent::CloseStream { sid } => {
+                if self.try_close_stream(sid) {
+                    event.to_frame().write_bytes(&mut self.reliable_buffer);
+                    self.send_reliable().await?;
+                } else {
+                    #[cfg(feature = "trace_pedantic")]
+                    trace!(?sid, "hold back close stream");
+                    self.closing_streams.push(sid);
+                }
+            },
+            ProtocolEvent::Shutdown => {
+                if self.store.is_empty() {
+                    event.to_frame().write_bytes(&mut self.reliable_buffer);
+                    self.send_reliable().await?;
+                } else {
+                    #[cfg(feature = "trace_pedantic")]
+                    trace!("hold back shutdown");
+                    self.pending_shutdown = true;
+                }
+            },
+            ProtocolEvent::Ack { .. } => {
+                event.to_frame().write_bytes(&mut self.reliable_buffer);
+                self.send_reliable().await?;
+            },
+            ProtocolEvent::Message { data, sid } => {
+                self.metrics.smsg_ib(sid, data.len() as u64);
+                self.store.add(data, self.next_mid, sid);
+                self.next_mid += 1;
+            },
+        }
+        Ok(())
+    }
+
+    async fn flush(
+        &mut self,
+        bandwidth: Bandwidth,
+        dt: Duration,
+    ) -> Result</* actual */ Bandwidth, ProtocolError<Self::CustomErr>> {
+        self.resend_lost().await?;
+
+        let (frames, _) = self.store.grab(bandwidth, dt);
+        let mut data_frames = 0;
+        let mut data_bandwidth = 0;
+        for (sid, frame) in frames {
+            if let OTFrame::Data { mid: _, data } = &frame {
+                data_bandwidth += data.len();
+                data_frames += 1;
+            }
+            if self.unreliable_streams.contains(&sid) {
+                self.send_unreliable(frame).await?;
+            } else {
+                frame.write_bytes(&mut self.reliable_buffer);
+            }
+        }
+        self.metrics
+            .sdata_frames_b(data_frames, data_bandwidth as u64);
+
+        let mut finished_streams = vec![];
+        for (i, &sid) in self.closing_streams.clone().iter().enumerate() {
+            if self.try_close_stream(sid) {
+                #[cfg(feature = "trace_pedantic")]
+                trace!(?sid, "close stream, as it's now empty");
+                OTFrame::CloseStream { sid }.write_bytes(&mut self.reliable_buffer);
+                finished_streams.push(i);
+            }
+        }
+        for i in finished_streams.iter().rev() {
+            self.closing_streams.remove(*i);
+        }
+
+        let mut finished_streams = vec![];
+        for (i, &sid) in self.notify_closing_streams.clone().iter().enumerate() {
+            if self.try_close_stream(sid) {
+                #[cfg(feature = "trace_pedantic")]
+                trace!(?sid, "close stream, as it's now empty");
+                finished_streams.push(i);
+            }
+        }
+        for i in finished_streams.iter().rev() {
+            self.notify_closing_streams.remove(*i);
+        }
+
+        if self.pending_shutdown && self.store.is_empty() {
+            #[cfg(feature = "trace_pedantic")]
+            trace!("shutdown, as it's now empty");
+            OTFrame::Shutdown {}.write_bytes(&mut self.reliable_buffer);
+            self.pending_shutdown = false;
+        }
+
+        self.send_reliable().await?;
+        self.send_ack().await?;
+        Ok(data_bandwidth as u64)
+    }
+}
+
+#[async_trait]
+impl<S> RecvProtocol for UdpRecvProtocol<S>
+where
+    S: UnreliableSink<DataFormat = BytesMut>,
+{
+    type CustomErr = S::CustomErr;
+
+    async fn recv(&mut self) -> Result<ProtocolEvent, ProtocolError<Self::CustomErr>> {
+        loop {
+            while let Some(frame) = ITFrame::read_frame(&mut self.reliable_buffer)
+                .map_err(|()| ProtocolError::Violated)?
+            {
+                if let Some(event) = self.handle_frame(frame)? {
+                    return Ok(event);
+                }
+            }
+
+            let mut packet = match self.pending.pop_front() {
+                Some(packet) => packet,
+                None => self.sink.recv().await?,
+            };
+            if packet.is_empty() {
+                return Err(ProtocolError::Violated);
+            }
+            match packet.get_u8() {
+                // late copy of a handshake frame
+                PACKET_INIT => {},
+                PACKET_RELIABLE => self.handle_reliable(packet)?,
+                PACKET_UNRELIABLE => {
+                    if let Some(event) = self.handle_fragment(packet)? {
+                        return Ok(event);
+                    }
+                },
+                PACKET_ACK => self.handle_ack(packet)?,
+                _ => return Err(ProtocolError::Violated),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<D> ReliableDrain for UdpSendProtocol<D>
+where
+    D: UnreliableDrain<DataFormat = BytesMut>,
+{
+    type CustomErr = D::CustomErr;
+
+    async fn send(&mut self, frame: InitFrame) -> Result<(), ProtocolError<Self::CustomErr>> {
+        let mut buffer = BytesMut::with_capacity(500);
+        buffer.put_u8(PACKET_INIT);
+        buffer.put_u8(self.init_index);
+        frame.write_bytes(&mut buffer);
+        if self.init_index == 0 {
+            buffer.resize(buffer.len().max(HELLO_SIZE), 0);
+        }
+        self.init_index += 1;
+        self.drain.send(buffer).await?;
+        self.last_send = Instant::now();
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<S> ReliableSink for UdpRecvProtocol<S>
+where
+    S: UnreliableSink<DataFormat = BytesMut>,
+{
+    type CustomErr = S::CustomErr;
+
+    async fn recv(&mut self) -> Result<InitFrame, ProtocolError<Self::CustomErr>> {
+        let mut packet = loop {
+            if let Some(i) = self
+                .early_init_frames
+                .iter()
+                .position(|(index, _)| *index == self.next_init_index)
+            {
+                break self.early_init_frames.swap_remove(i).1;
+            }
+            let packet = self.sink.recv().await?;
+            let init_index = match packet[..] {
+                [PACKET_INIT, index, ..] => Some(index),
+                _ => None,
+            };
+            match init_index {
+                Some(index) if index == self.next_init_index => break packet,
+                Some(index) => {
+                    // copies of older frames are ignored, newer ones are kept
+                    if index > self.next_init_index
+                        && !self.early_init_frames.iter().any(|(i, _)| *i == index)
+                    {
+                        self.early_init_frames.push((index, packet));
+                    }
+                },
+                _ => {
+                    // The remote finished the handshake already and started sending
+                    if self.pending.len() < MAX_PENDING_PACKETS {
+                        self.pending.push_back(packet);
+                    }
+                },
+            }
+        };
+        self.next_init_index += 1;
+        packet.advance(2);
+        Ok(InitFrame::read_frame(&mut packet).unwrap_or_else(|| InitFrame::Raw(packet.to_vec())))
+    }
+}
+
+#[cfg(test)]
+mod test_utils {
+    //UDP protocol based on Channel
+    use super::*;
+    use crate::metrics::{ProtocolMetricCache, ProtocolMetrics};
+    use async_channel::*;
+
+    pub struct UdpDrain {
+        pub sender: Sender<BytesMut>,
+        pub drop_ratio: f32,
+    }
+
+    pub struct UdpSink {
+        pub receiver: Receiver<BytesMut>,
+    }
+
+    /// emulate Udp protocol on Channels, `drop_ratio` of all packets get lost
+    pub fn udp_bound(
+        cap: usize,
+        drop_ratio: f32,
+        metrics: Option<ProtocolMetricCache>,
+    ) -> [(UdpSendProtocol<UdpDrain>, UdpRecvProtocol<UdpSink>); 2] {
+        let (s1, r1) = bounded(cap);
+        let (s2, r2) = bounded(cap);
+        let m = metrics.unwrap_or_else(|| {
+            ProtocolMetricCache::new("udp", Arc::new(ProtocolMetrics::new().unwrap()))
+        });
+        let sp1 = UdpSendProtocol::new(
+            UdpDrain {
+                sender: s1,
+                drop_ratio,
+            },
+            m.clone(),
+        );
+        let rp1 = UdpRecvProtocol::new(UdpSink { receiver: r2 }, &sp1, m.clone());
+        let sp2 = UdpSendProtocol::new(
+            UdpDrain {
+                sender: s2,
+                drop_ratio,
+            },
+            m.clone(),
+        );
+        let rp2 = UdpRecvProtocol::new(UdpSink { receiver: r1 }, &sp2, m);
+        [(sp1, rp1), (sp2, rp2)]
+    }
+
+    #[async_trait]
+    impl UnreliableDrain for UdpDrain {
+        type CustomErr = ();
+        type DataFormat = BytesMut;
+
+        async fn send(
+            &mut self,
+            data: Self::DataFormat,
+        ) -> Result<(), ProtocolError<Self::CustomErr>> {
+            use rand::RngExt;
+            assert!(data.len() <= MAX_PACKET_SIZE);
+            if rand::rng().random::<f32>() < self.drop_ratio {
+                return Ok(());
+            }
+            self.sender
+                .send(data)
+                .await
+                .map_err(|_| ProtocolError::Custom(()))
+        }
+    }
+
+    #[async_trait]
+    impl UnreliableSink for UdpSink {
+        type CustomErr = ();
+        type DataFormat = BytesMut;
+
+        async fn recv(&mut self) -> Result<Self::DataFormat, ProtocolError<Self::CustomErr>> {
+            self.receiver
+                .recv()
+                .await
+                .map_err(|_| ProtocolError::Custom(()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Fragment;
+    use crate::{
+        InitProtocol, ProtocolEvent, RecvProtocol, SendProtocol,
+        frame::OTFrame,
+        metrics::{ProtocolMetricCache, ProtocolMetrics, RemoveReason},
+        types::{Pid, Promises, STREAM_ID_OFFSET1, STREAM_ID_OFFSET2, Sid},
+        udp::{PACKET_RELIABLE, test_utils::*},
+    };
+    use bytes::{BufMut, Bytes, BytesMut};
+    use std::{sync::Arc, time::Duration};
+
+    #[tokio::test]
+    async fn handshake_all_good() {
+        let [mut p1, mut p2] = udp_bound(10, 0.0, None);
+        let r1 = tokio::spawn(async move { p1.initialize(true, false, Pid::fake(2), 1337).await });
+        let r2 = tokio::spawn(async move { p2.initialize(false, false, Pid::fake(3), 42).await });
+        let (r1, r2) = tokio::join!(r1, r2);
+        assert_eq!(
+            r1.unwrap(),
+            Ok((Pid::fake(3), STREAM_ID_OFFSET1, 42, false))
+        );
+        assert_eq!(
+            r2.unwrap(),
+            Ok((Pid::fake(2), STREAM_ID_OFFSET2, 1337, false))
+        );
+    }
+
+    #[tokio::test]
+    async fn only_first_init_frame_is_padded() {
+        use crate::{frame::InitFrame, handshake::ReliableDrain};
+
+        let (s, r) = async_channel::bounded(10);
+        let m = ProtocolMetricCache::new("udp", Arc::new(ProtocolMetrics::new().unwrap()));
+        let mut send = super::UdpSendProtocol::new(
+            UdpDrain {
+                sender: s,
+                drop_ratio: 0.0,
+            },
+            m,
+        );
+        for _ in 0..2 {
+            ReliableDrain::send(&mut send, InitFrame::Init {
+                pid: Pid::fake(2),
+                secret: 1337,
+            })
+            .await
+            .unwrap();
+        }
+        let hello = r.recv().await.unwrap();
+        assert!(super::is_udp_handshake(&hello));
+        assert_eq!(hello.len(), super::HELLO_SIZE);
+        let next = r.recv().await.unwrap();
+        assert_eq!(super::udp_init_index(&next), Some(1));
+        assert!(next.len() < super::HELLO_SIZE);
+        // every frame is only sent once, the transport resends lost ones
+        assert!(r.is_empty());
+    }
+
+    #[tokio::test]
+    async fn send_short_msg() {
+        let [p1, p2] = udp_bound(10, 0.0, None);
+        let (mut s, mut r) = (p1.0, p2.1);
+        let event = ProtocolEvent::OpenStream {
+            sid: Sid::new(10),
+            prio: 3u8,
+            promises: Promises::ORDERED,
+            guaranteed_bandwidth: 1_000_000,
+        };
+        s.send(event.clone()).await.unwrap();
+        assert_eq!(r.recv().await.unwrap(), event);
+        let event = ProtocolEvent::Message {
+            sid: Sid::new(10),
+            data: Bytes::from(&[188u8; 600][..]),
+        };
+        s.send(event.clone()).await.unwrap();
+        s.flush(1_000_000, Duration::from_secs(1)).await.unwrap();
+        let e = r.recv().await.unwrap();
+        assert_eq!(event, e);
+    }
+
+    #[tokio::test]
+    async fn send_long_msg() {
+        let mut metrics =
+            ProtocolMetricCache::new("long_udp", Arc::new(ProtocolMetrics::new().unwrap()));
+        let sid = Sid::new(1);
+        let [p1, p2] = udp_bound(10000, 0.0, Some(metrics.clone()));
+        let (mut s, mut r) = (p1.0, p2.1);
+        let event = ProtocolEvent::OpenStream {
+            sid,
+            prio: 5u8,
+            promises: Promises::COMPRESSED | Promises::ORDERED,
+            guaranteed_bandwidth: 1_000_000,
+        };
+        s.send(event).await.unwrap();
+        let _ = r.recv().await.unwrap();
+        let event = ProtocolEvent::Message {
+            sid,
+            data: Bytes::from(&[99u8; 500_000][..]),
+        };
+        s.send(event.clone()).await.unwrap();
+        s.flush(1_000_000, Duration::from_secs(1)).await.unwrap();
+        let e = r.recv().await.unwrap();
+        assert_eq!(event, e);
+        metrics.assert_msg(sid, 1, RemoveReason::Finished);
+        metrics.assert_msg_bytes(sid, 500_000, RemoveReason::Finished);
+        metrics.assert_data_frames(358);
+        metrics.assert_data_frames_bytes(500_000);
+    }
+
+    #[tokio::test]
+    async fn msg_finishes_after_shutdown() {
+        let sid = Sid::new(1);
+        let [p1, p2] = udp_bound(10000, 0.0, None);
+        let (mut s, mut r) = (p1.0, p2.1);
+        let event = ProtocolEvent::OpenStream {
+            sid,
+            prio: 5u8,
+            promises: Promises::COMPRESSED | Promises::ORDERED,
+            guaranteed_bandwidth: 0,
+        };
+        s.send(event).await.unwrap();
+        let _ = r.recv().await.unwrap();
+        let event = ProtocolEvent::Message {
+            sid,
+            data: Bytes::from(&[99u8; 500_000][..]),
+        };
+        s.send(event).await.unwrap();
+        s.send(ProtocolEvent::Shutdown {}).await.unwrap();
+        s.send(ProtocolEvent::CloseStream { sid }).await.unwrap();
+        s.flush(1_000_000, Duration::from_secs(1)).await.unwrap();
+        let e = r.recv().await.unwrap();
+        assert!(matches!(e, ProtocolEvent::Message { .. }));
+        let e = r.recv().await.unwrap();
+        assert!(matches!(e, ProtocolEvent::CloseStream { .. }));
+        let e = r.recv().await.unwrap();
+        assert!(matches!(e, ProtocolEvent::Shutdown));
+    }
+
+    #[tokio::test]
+    async fn reliable_despite_packet_loss() {
+        const COUNT: usize = 100;
+        let sid = Sid::new(1);
+        let [p1, p2] = udp_bound(10000, 0.3, None);
+        let ((mut s1, mut r1), (mut s2, mut r2)) = (p1, p2);
+        let event = ProtocolEvent::OpenStream {
+            sid,
+            prio: 5u8,
+            promises: Promises::ORDERED | Promises::GUARANTEED_DELIVERY,
+            guaranteed_bandwidth: 0,
+        };
+        s1.send(event).await.unwrap();
+        for i in 0..COUNT {
+            let event = ProtocolEvent::Message {
+                sid,
+                data: Bytes::from(vec![i as u8; 2000]),
+            };
+            s1.send(event).await.unwrap();
+        }
+        // keep flushing both sides, so ACKs and resends happen
+        let sender = tokio::spawn(async move {
+            loop {
+                s1.flush(1_000_000_000, Duration::from_millis(5))
+                    .await
+                    .unwrap();
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        });
+        let acks = tokio::spawn(async move {
+            loop {
+                s2.flush(1_000_000_000, Duration::from_millis(5))
+                    .await
+                    .unwrap();
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        });
+        let ack_reader = tokio::spawn(async move { while r1.recv().await.is_ok() {} });
+
+        assert!(matches!(
+            r2.recv().await.unwrap(),
+            ProtocolEvent::OpenStream { .. }
+        ));
+        for i in 0..COUNT {
+            let e = r2.recv().await.unwrap();
+            assert_eq!(e, ProtocolEvent::Message {
+                sid,
+                data: Bytes::from(vec![i as u8; 2000]),
+            });
+        }
+        sender.abort();
+        acks.abort();
+        ack_reader.abort();
+    }
+
+    #[tokio::test]
+    async fn unreliable_drops_stale_messages() {
+        let sid = Sid::new(1);
+        let (s, r) = async_channel::bounded(10);
+        let m = ProtocolMetricCache::new("udp", Arc::new(ProtocolMetrics::new().unwrap()));
+        let [(send, _), _] = udp_bound(10, 0.0, Some(m.clone()));
+        let mut r = super::UdpRecvProtocol::new(UdpSink { receiver: r }, &send, m);
+
+        let mut bytes = BytesMut::new();
+        bytes.put_u8(PACKET_RELIABLE);
+        bytes.put_u64_le(0);
+        OTFrame::OpenStream {
+            sid,
+            prio: 5u8,
+            promises: Promises::empty(),
+            guaranteed_bandwidth: 0,
+        }
+        .write_bytes(&mut bytes);
+        s.send(bytes.split()).await.unwrap();
+        let e = r.recv().await.unwrap();
+        assert!(matches!(e, ProtocolEvent::OpenStream { .. }));
+
+        let fragment = |mid: u64, index: u32, length: usize, byte: u8| {
+            let mut bytes = BytesMut::new();
+            Fragment {
+                mid,
+                sid,
+                length: length as u64,
+                index,
+                data: Bytes::from(vec![byte; (length - index as usize * 1400).min(1400)]),
+            }
+            .write_bytes(&mut bytes);
+            bytes
+        };
+        // first half of message 1 arrives, then message 2 and the rest of
+        // message 1, then message 3
+        s.send(fragment(1, 0, 2000, 1)).await.unwrap();
+        s.send(fragment(2, 0, 10, 2)).await.unwrap();
+        s.send(fragment(1, 1, 2000, 1)).await.unwrap();
+        s.send(fragment(3, 0, 10, 3)).await.unwrap();
+
+        assert_eq!(r.recv().await.unwrap(), ProtocolEvent::Message {
+            sid,
+            data: Bytes::from(vec![2; 10]),
+        });
+        assert_eq!(r.recv().await.unwrap(), ProtocolEvent::Message {
+            sid,
+            data: Bytes::from(vec![3; 10]),
+        });
+    }
+
+    #[tokio::test]
+    async fn unreliable_incomplete_messages_are_limited() {
+        let sid = Sid::new(1);
+        let (s, r) = async_channel::bounded(20);
+        let m = ProtocolMetricCache::new("udp", Arc::new(ProtocolMetrics::new().unwrap()));
+        let [(send, _), _] = udp_bound(10, 0.0, Some(m.clone()));
+        let mut r = super::UdpRecvProtocol::new(UdpSink { receiver: r }, &send, m);
+
+        let mut bytes = BytesMut::new();
+        bytes.put_u8(PACKET_RELIABLE);
+        bytes.put_u64_le(0);
+        OTFrame::OpenStream {
+            sid,
+            prio: 5u8,
+            promises: Promises::empty(),
+            guaranteed_bandwidth: 0,
+        }
+        .write_bytes(&mut bytes);
+        s.send(bytes.split()).await.unwrap();
+        let e = r.recv().await.unwrap();
+        assert!(matches!(e, ProtocolEvent::OpenStream { .. }));
+
+        let fragment = |mid: u64, index: u32| {
+            let mut bytes = BytesMut::new();
+            Fragment {
+                mid,
+                sid,
+                length: 2000,
+                index,
+                data: Bytes::from(vec![mid as u8; (2000 - index as usize * 1400).min(1400)]),
+            }
+            .write_bytes(&mut bytes);
+            bytes
+        };
+        // the first halves of one message too many arrive, so the oldest one
+        // is dropped and its second half is ignored
+        for mid in 1..=super::MAX_INCOMPLETE_MESSAGES as u64 + 1 {
+            s.send(fragment(mid, 0)).await.unwrap();
+        }
+        s.send(fragment(1, 1)).await.unwrap();
+        s.send(fragment(2, 1)).await.unwrap();
+
+        assert_eq!(r.recv().await.unwrap(), ProtocolEvent::Message {
+            sid,
+            data: Bytes::from(vec![2; 2000]),
+        });
+    }
+
+    #[tokio::test]
+    async fn unreliable_fragments_arrive_reordered() {
+        let sid = Sid::new(1337);
+        let [mut p1, mut p2] = udp_bound(100, 0.0, None);
+        let event = ProtocolEvent::OpenStream {
+            sid,
+            prio: 3u8,
+            promises: Promises::empty(), /* on purpose! */
+            guaranteed_bandwidth: 1_000_000,
+        };
+        p1.0.send(event).await.unwrap();
+        let e = p2.1.recv().await.unwrap();
+        p2.0.notify_from_recv(e);
+        let event = ProtocolEvent::Message {
+            sid,
+            data: Bytes::from((0..5000).map(|i| i as u8).collect::<Vec<_>>()),
+        };
+        p2.0.send(event.clone()).await.unwrap();
+        p2.0.flush(1_000_000, Duration::from_secs(1)).await.unwrap();
+        // p1 opened the stream, it has to accept fragments from p2 on it
+        let e = p1.1.recv().await.unwrap();
+        assert_eq!(event, e);
+    }
+}
@@ -8,15 +8,18 @@ use bytes::Bytes;
 use hashbrown::HashMap;
 #[cfg(feature = "compression")]
 use lz_fear::raw::DecodeError;
-use network_protocol::{Bandwidth, InitProtocolError, Pid, Prio, Promises, Sid};
+use network_protocol::{
+    Bandwidth, InitProtocolError, NetworkConditions, Pid, Prio, Promises, Sid, StaticKey,
+    StaticPublicKey,
+};
 #[cfg(feature = "metrics")]
 use prometheus::Registry;
 use serde::{Serialize, de::DeserializeOwned};
 use std::{
     net::SocketAddr,
     sync::{
-        Arc,
-        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+        atomic::{AtomicBool, AtomicU64, Ordering},
     },
     time::Duration,
 };
@@ -33,6 +36,14 @@ type A2sDisconnect = Arc<Mutex<Option<mpsc::UnboundedSender<(Pid, S2bShutdownBpa
 #[derive(Clone, Debug)]
 pub enum ConnectAddr {
     Tcp(SocketAddr),
+    /// Tcp, but the channel is encrypted, so it supports
+    /// [`Promises::ENCRYPTED`]. Fails if the remote doesn't listen with
+    /// [`ListenAddr::TcpEncrypted`] or doesn't authenticate with the given
+    /// static key, which has to be known beforehand, e.g. published by the
+    /// server.
+    ///
+    /// [`Promises::ENCRYPTED`]: crate::Promises::ENCRYPTED
+    TcpEncrypted(SocketAddr, StaticPublicKey),
     Udp(SocketAddr),
     #[cfg(feature = "quic")]
     Quic(SocketAddr, quinn::ClientConfig, String),
@@ -45,6 +56,7 @@ impl ConnectAddr {
     pub fn socket_addr(&self) -> Option<SocketAddr> {
         match self {
             Self::Tcp(addr) => Some(*addr),
+            Self::TcpEncrypted(addr, _) => Some(*addr),
             Self::Udp(addr) => Some(*addr),
             Self::Mpsc(_) => None,
             #[cfg(feature = "quic")]
@@ -57,6 +69,10 @@ impl ConnectAddr {
 #[derive(Clone, Debug)]
 pub enum ListenAddr {
     Tcp(SocketAddr),
+    /// Tcp, but remotes connecting with [`ConnectAddr::TcpEncrypted`] can
+    /// encrypt the channel, authenticated by this static key. Remotes
+    /// connecting with [`ConnectAddr::Tcp`] are accepted as well.
+    TcpEncrypted(SocketAddr, Arc<StaticKey>),
     Udp(SocketAddr),
     #[cfg(feature = "quic")]
     Quic(SocketAddr, quinn::ServerConfig),
@@ -213,6 +229,9 @@ pub struct Network {
     connect_sender: mpsc::UnboundedSender<A2sConnect>,
     connected_receiver: mpsc::UnboundedReceiver<Participant>,
     shutdown_network_s: Option<oneshot::Sender<oneshot::Sender<()>>>,
+    /// in milliseconds
+    resume_timeout: Arc<AtomicU64>,
+    network_conditions: Arc<RwLock<NetworkConditions>>,
 }
 
 impl Network {
@@ -288,9 +307,13 @@ impl Network {
         let p = participant_id;
         let span = info_span!("network", ?p);
         span.in_scope(|| trace!("Starting Network"));
+        let resume_timeout = Arc::new(AtomicU64::new(0));
+        let network_conditions = Arc::new(RwLock::new(NetworkConditions::default()));
         let (scheduler, listen_sender, connect_sender, connected_receiver, shutdown_sender) =
             Scheduler::new(
                 participant_id,
+                Arc::clone(&resume_timeout),
+                Arc::clone(&network_conditions),
                 #[cfg(feature = "metrics")]
                 registry,
             );
@@ -318,9 +341,80 @@ impl Network {
             connect_sender,
             connected_receiver,
             shutdown_network_s: Some(shutdown_network_s),
+            resume_timeout,
+            network_conditions,
         }
     }
 
+    /// Keeps a [`Participant`] alive for `timeout` after all of its channels
+    /// failed, e.g. because of a short network outage, instead of closing it
+    /// right away. If we [`connect`]ed to the remote, we try to reconnect in
+    /// this time. Once a new channel is established, all [`Streams`] continue
+    /// where they stopped, messages of [`Streams`] with
+    /// [`Promises::ORDERED`] and [`Promises::GUARANTEED_DELIVERY`] are neither
+    /// lost nor duplicated.
+    ///
+    /// Both sides need to enable this. It only affects [`Participants`] that
+    /// connect afterwards, it's disabled by default. A [`Participant`] that is
+    /// disconnected by the remote is still closed right away.
+    ///
+    /// Only [`Participants`] connected via [`ConnectAddr::TcpEncrypted`] or
+    /// QUIC can be resumed, as the secret needed to resume them must neither
+    /// be sent in the clear nor to a remote that isn't authenticated.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tokio::runtime::Runtime;
+    /// use veloren_network::{Network, Pid};
+    ///
+    /// let runtime = Runtime::new().unwrap();
+    /// let network = Network::new(Pid::new(), &runtime);
+    /// network.set_resume_timeout(Duration::from_secs(15));
+    /// ```
+    ///
+    /// [`connect`]: Network::connect
+    /// [`Streams`]: crate::api::Stream
+    /// [`Participants`]: crate::api::Participant
+    /// [`Promises::ORDERED`]: crate::Promises::ORDERED
+    /// [`Promises::GUARANTEED_DELIVERY`]: crate::Promises::GUARANTEED_DELIVERY
+    pub fn set_resume_timeout(&self, timeout: Duration) {
+        self.resume_timeout
+            .store(timeout.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    /// Simulates a bad connection on all MPSC and TCP channels, e.g. to
+    /// reproduce rubber-banding in tests. Only data sent by this `Network`
+    /// is delayed, lost or throttled, impair both sides to affect both
+    /// directions.
+    ///
+    /// It only affects [`Participants`] that connect afterwards, so usually
+    /// you call it right after creating the `Network`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tokio::runtime::Runtime;
+    /// use veloren_network::{Network, NetworkConditions, Pid};
+    ///
+    /// let runtime = Runtime::new().unwrap();
+    /// let network = Network::new(Pid::new(), &runtime);
+    /// network.set_network_conditions(NetworkConditions {
+    ///     latency: Duration::from_millis(150),
+    ///     jitter: Duration::from_millis(50),
+    ///     loss: 0.01,
+    ///     bandwidth: Some(100_000),
+    /// });
+    /// ```
+    ///
+    /// [`Participants`]: crate::api::Participant
+    pub fn set_network_conditions(&self, conditions: NetworkConditions) {
+        *self
+            .network_conditions
+            .write()
+            .expect("network conditions lock poisoned") = conditions;
+    }
+
     /// starts listening on an [`ListenAddr`].
     /// When the method returns the `Network` is ready to listen for incoming
     /// connections OR has returned a [`NetworkError`] (e.g. port already used).
@@ -1115,16 +1209,22 @@ impl Stream {
     /// [`recv`]: Stream::recv
     #[inline]
     pub fn try_recv<M: DeserializeOwned>(&mut self) -> Result<Option<M>, StreamError> {
+        self.try_recv_raw()?.map(Message::deserialize).transpose()
+    }
+
+    /// Like [`try_recv`] but doesn't deserialize the [`Message`], see
+    /// [`recv_raw`].
+    ///
+    /// [`try_recv`]: Stream::try_recv
+    /// [`recv_raw`]: Stream::recv_raw
+    pub fn try_recv_raw(&mut self) -> Result<Option<Message>, StreamError> {
         match &mut self.b2a_msg_recv_r {
             Some(b2a_msg_recv_r) => match b2a_msg_recv_r.try_recv() {
-                Ok(data) => Ok(Some(
-                    Message {
-                        data,
-                        #[cfg(feature = "compression")]
-                        compressed: self.promises.contains(Promises::COMPRESSED),
-                    }
-                    .deserialize()?,
-                )),
+                Ok(data) => Ok(Some(Message {
+                    data,
+                    #[cfg(feature = "compression")]
+                    compressed: self.promises.contains(Promises::COMPRESSED),
+                })),
                 Err(async_channel::TryRecvError::Empty) => Ok(None),
                 Err(async_channel::TryRecvError::Closed) => {
                     self.b2a_msg_recv_r = None; //prevent panic
@@ -4,9 +4,10 @@ use bytes::BytesMut;
 use futures_util::FutureExt;
 use hashbrown::HashMap;
 use network_protocol::{
-    Bandwidth, Cid, InitProtocolError, MpscMsg, MpscRecvProtocol, MpscSendProtocol, Pid,
-    ProtocolError, ProtocolEvent, ProtocolMetricCache, ProtocolMetrics, Sid, TcpRecvProtocol,
-    TcpSendProtocol, UnreliableDrain, UnreliableSink,
+    Bandwidth, Cid, ImpairedSendProtocol, InitProtocolError, MpscMsg, MpscRecvProtocol,
+    MpscSendProtocol, NetworkConditions, Pid, ProtocolError, ProtocolEvent, ProtocolMetricCache,
+    ProtocolMetrics, Sid, StaticKey, StaticPublicKey, TcpRecvProtocol, TcpSendProtocol,
+    UdpRecvProtocol, UdpSendProtocol, UnreliableDrain, UnreliableSink, udp_init_index,
 };
 #[cfg(feature = "quic")]
 use network_protocol::{QuicDataFormat, QuicDataFormatStream, QuicRecvProtocol, QuicSendProtocol};
@@ -17,7 +18,7 @@ use std::{
         Arc,
         atomic::{AtomicU64, Ordering},
     },
-    time::Duration,
+    time::{Duration, Instant},
 };
 use tokio::{
     io::{AsyncReadExt, AsyncWriteExt},
@@ -31,6 +32,7 @@ use tracing::{error, info, trace, warn};
 #[derive(Debug)]
 pub(crate) enum Protocols {
     Tcp((TcpSendProtocol<TcpDrain>, TcpRecvProtocol<TcpSink>)),
+    Udp((UdpSendProtocol<UdpDrain>, UdpRecvProtocol<UdpSink>)),
     Mpsc((MpscSendProtocol<MpscDrain>, MpscRecvProtocol<MpscSink>)),
     #[cfg(feature = "quic")]
     Quic((QuicSendProtocol<QuicDrain>, QuicRecvProtocol<QuicSink>)),
@@ -39,14 +41,18 @@ pub(crate) enum Protocols {
 #[derive(Debug)]
 pub(crate) enum SendProtocols {
     Tcp(TcpSendProtocol<TcpDrain>),
+    Udp(UdpSendProtocol<UdpDrain>),
     Mpsc(MpscSendProtocol<MpscDrain>),
     #[cfg(feature = "quic")]
     Quic(QuicSendProtocol<QuicDrain>),
+    /// Simulates bad [`NetworkConditions`] on top of another protocol
+    Impaired(Box<ImpairedSendProtocol<SendProtocols>>),
 }
 
 #[derive(Debug)]
 pub(crate) enum RecvProtocols {
     Tcp(TcpRecvProtocol<TcpSink>),
+    Udp(UdpRecvProtocol<UdpSink>),
     Mpsc(MpscRecvProtocol<MpscSink>),
     #[cfg(feature = "quic")]
     Quic(QuicRecvProtocol<QuicSink>),
@@ -86,6 +92,7 @@ impl Protocols {
 
     pub(crate) async fn with_tcp_connect(
         addr: SocketAddr,
+        request_encryption: Option<StaticPublicKey>,
         metrics: ProtocolMetricCache,
     ) -> Result<Self, NetworkConnectError> {
         let stream = net::TcpStream::connect(addr)
@@ -99,11 +106,12 @@ impl Protocols {
             "Connecting Tcp to: {}",
             stream.peer_addr().map_err(NetworkConnectError::Io)?
         );
-        Ok(Self::new_tcp(stream, metrics))
+        Ok(Self::new_tcp(stream, request_encryption, None, metrics))
     }
 
     pub(crate) async fn with_tcp_listen(
         addr: SocketAddr,
+        static_key: Option<Arc<StaticKey>>,
         cids: Arc<AtomicU64>,
         metrics: Arc<ProtocolMetrics>,
         s2s_stop_listening_r: oneshot::Receiver<()>,
@@ -160,7 +168,7 @@ impl Protocols {
                 );
                 let metrics = ProtocolMetricCache::new(&cid.to_string(), Arc::clone(&metrics));
                 let _ = c2s_protocol_s.send((
-                    Self::new_tcp(stream, metrics.clone()),
+                    Self::new_tcp(stream, None, static_key.clone(), metrics.clone()),
                     ConnectAddr::Tcp(remote_addr),
                     cid,
                 ));
@@ -169,9 +177,25 @@ impl Protocols {
         Ok(())
     }
 
-    pub(crate) fn new_tcp(stream: net::TcpStream, metrics: ProtocolMetricCache) -> Self {
+    /// The listening side never requests encryption, but accepts it if it
+    /// has a `static_key` to authenticate with
+    pub(crate) fn new_tcp(
+        stream: net::TcpStream,
+        request_encryption: Option<StaticPublicKey>,
+        static_key: Option<Arc<StaticKey>>,
+        metrics: ProtocolMetricCache,
+    ) -> Self {
         let (r, w) = stream.into_split();
-        let sp = TcpSendProtocol::new(TcpDrain { half: w }, metrics.clone());
+        let drain = TcpDrain { half: w };
+        let sp = match (request_encryption, static_key) {
+            (Some(remote_key), _) => {
+                TcpSendProtocol::new_encrypted(drain, metrics.clone(), remote_key)
+            },
+            (None, Some(static_key)) => {
+                TcpSendProtocol::new_with_static_key(drain, metrics.clone(), static_key)
+            },
+            (None, None) => TcpSendProtocol::new(drain, metrics.clone()),
+        };
         let rp = TcpRecvProtocol::new(
             TcpSink {
                 half: r,
@@ -182,6 +206,119 @@ impl Protocols {
         Protocols::Tcp((sp, rp))
     }
 
+    pub(crate) async fn with_udp_connect(
+        addr: SocketAddr,
+        metrics: ProtocolMetricCache,
+    ) -> Result<Self, NetworkConnectError> {
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+        let bindsock = match addr {
+            SocketAddr::V4(_) => SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0),
+            SocketAddr::V6(_) => SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0),
+        };
+        let socket = net::UdpSocket::bind(bindsock)
+            .await
+            .map_err(NetworkConnectError::Io)?;
+        info!("Connecting Udp to: {}", &addr);
+        let socket = Arc::new(socket);
+        let init = SharedUdpInit::default();
+        Ok(Self::new_udp(
+            UdpDrain {
+                socket: Arc::clone(&socket),
+                remote: addr,
+                init: Arc::clone(&init),
+            },
+            UdpSink::Socket {
+                socket,
+                remote: addr,
+                init,
+            },
+            metrics,
+        ))
+    }
+
+    /// One socket is shared by all remotes, so incoming datagrams are
+    /// dispatched to the channel of their source address
+    pub(crate) async fn with_udp_listen(
+        addr: SocketAddr,
+        cids: Arc<AtomicU64>,
+        metrics: Arc<ProtocolMetrics>,
+        s2s_stop_listening_r: oneshot::Receiver<()>,
+        c2s_protocol_s: mpsc::UnboundedSender<C2sProtocol>,
+    ) -> io::Result<()> {
+        let socket = Arc::new(net::UdpSocket::bind(addr).await?);
+        trace!(?addr, "Udp Listener bound");
+        let mut end_receiver = s2s_stop_listening_r.fuse();
+        tokio::spawn(async move {
+            let mut remotes: HashMap<SocketAddr, mpsc::Sender<BytesMut>> = HashMap::new();
+            let mut buffer = [0u8; UDP_RECV_BUFFER_SIZE];
+            while let Some(next) = select! {
+                    next = socket.recv_from(&mut buffer).fuse() => Some(next),
+                    _ = &mut end_receiver => None,
+            } {
+                let (size, remote_addr) = match next {
+                    Ok(data) => data,
+                    Err(e) => {
+                        trace!(?e, "UdpSocket Error, ignoring datagram");
+                        continue;
+                    },
+                };
+                let datagram = BytesMut::from(&buffer[..size]);
+                let datagram = match remotes.get(&remote_addr) {
+                    Some(sender) => match sender.try_send(datagram) {
+                        // it's UDP, so the protocol handles dropped datagrams
+                        Ok(()) | Err(mpsc::error::TrySendError::Full(_)) => continue,
+                        // the remote might reconnect from the same address
+                        Err(mpsc::error::TrySendError::Closed(datagram)) => datagram,
+                    },
+                    None => datagram,
+                };
+                if !network_protocol::is_udp_handshake(&datagram) {
+                    continue;
+                }
+
+                let (sender, receiver) = mpsc::channel(UDP_CHANNEL_BOUND);
+                let _ = sender.try_send(datagram);
+                remotes.retain(|_, sender| !sender.is_closed());
+                remotes.insert(remote_addr, sender);
+
+                let cid = cids.fetch_add(1, Ordering::Relaxed);
+                info!(
+                    remote_addr = anonymize_addr(&remote_addr),
+                    ?cid,
+                    "Accepting Udp from"
+                );
+                let metrics = ProtocolMetricCache::new(&cid.to_string(), Arc::clone(&metrics));
+                let init = SharedUdpInit::default();
+                let _ = c2s_protocol_s.send((
+                    Self::new_udp(
+                        UdpDrain {
+                            socket: Arc::clone(&socket),
+                            remote: remote_addr,
+                            init: Arc::clone(&init),
+                        },
+                        UdpSink::Listener {
+                            receiver,
+                            socket: Arc::clone(&socket),
+                            remote: remote_addr,
+                            init,
+                        },
+                        metrics.clone(),
+                    ),
+                    ConnectAddr::Udp(remote_addr),
+                    cid,
+                ));
+            }
+        });
+        Ok(())
+    }
+
+    pub(crate) fn new_udp(drain: UdpDrain, sink: UdpSink, metrics: ProtocolMetricCache) -> Self {
+        let sp = UdpSendProtocol::new(drain, metrics.clone());
+        let rp = UdpRecvProtocol::new(sink, &sp, metrics);
+        Protocols::Udp((sp, rp))
+    }
+
     pub(crate) async fn with_mpsc_connect(
         addr: u64,
         metrics: ProtocolMetricCache,
@@ -398,11 +535,24 @@ impl Protocols {
     pub(crate) fn split(self) -> (SendProtocols, RecvProtocols) {
         match self {
             Protocols::Tcp((s, r)) => (SendProtocols::Tcp(s), RecvProtocols::Tcp(r)),
+            Protocols::Udp((s, r)) => (SendProtocols::Udp(s), RecvProtocols::Udp(r)),
             Protocols::Mpsc((s, r)) => (SendProtocols::Mpsc(s), RecvProtocols::Mpsc(r)),
             #[cfg(feature = "quic")]
             Protocols::Quic((s, r)) => (SendProtocols::Quic(s), RecvProtocols::Quic(r)),
         }
     }
+
+    /// Returns true if no one on the way can read what is sent over this
+    /// channel and the listening side is authenticated, i.e. it's an encrypted
+    /// TCP channel or QUIC, which uses TLS. Only valid after the handshake.
+    pub(crate) fn encrypted(&self) -> bool {
+        match self {
+            Protocols::Tcp((s, _)) => s.encrypted(),
+            Protocols::Udp(_) | Protocols::Mpsc(_) => false,
+            #[cfg(feature = "quic")]
+            Protocols::Quic(_) => true,
+        }
+    }
 }
 
 #[async_trait]
@@ -412,14 +562,39 @@ impl network_protocol::InitProtocol for Protocols {
     async fn initialize(
         &mut self,
         initializer: bool,
+        resume: bool,
         local_pid: Pid,
         secret: u128,
-    ) -> Result<(Pid, Sid, u128), InitProtocolError<Self::CustomErr>> {
+    ) -> Result<(Pid, Sid, u128, bool), InitProtocolError<Self::CustomErr>> {
         match self {
-            Protocols::Tcp(p) => p.initialize(initializer, local_pid, secret).await,
-            Protocols::Mpsc(p) => p.initialize(initializer, local_pid, secret).await,
+            Protocols::Tcp(p) => p.initialize(initializer, resume, local_pid, secret).await,
+            // A UDP listener only learns about a remote from its first datagram, so
+            // the connecting side starts the handshake
+            Protocols::Udp(p) => p.initialize(!initializer, resume, local_pid, secret).await,
+            Protocols::Mpsc(p) => p.initialize(initializer, resume, local_pid, secret).await,
             #[cfg(feature = "quic")]
-            Protocols::Quic(p) => p.initialize(initializer, local_pid, secret).await,
+            Protocols::Quic(p) => p.initialize(initializer, resume, local_pid, secret).await,
+        }
+    }
+}
+
+impl SendProtocols {
+    /// Only MPSC and TCP channels are impaired, UDP and QUIC already can drop
+    /// data on their own
+    pub(crate) fn with_conditions(self, conditions: NetworkConditions) -> Self {
+        match self {
+            SendProtocols::Tcp(_) | SendProtocols::Mpsc(_) if conditions.is_impaired() => {
+                SendProtocols::Impaired(Box::new(ImpairedSendProtocol::new(self, conditions)))
+            },
+            s => s,
+        }
+    }
+
+    /// The actual protocol below a simulation of bad network conditions
+    pub(crate) fn unimpaired(&self) -> &SendProtocols {
+        match self {
+            SendProtocols::Impaired(s) => s.inner().unimpaired(),
+            s => s,
         }
     }
 }
@@ -431,18 +606,22 @@ impl network_protocol::SendProtocol for SendProtocols {
     fn notify_from_recv(&mut self, event: ProtocolEvent) {
         match self {
             SendProtocols::Tcp(s) => s.notify_from_recv(event),
+            SendProtocols::Udp(s) => s.notify_from_recv(event),
             SendProtocols::Mpsc(s) => s.notify_from_recv(event),
             #[cfg(feature = "quic")]
             SendProtocols::Quic(s) => s.notify_from_recv(event),
+            SendProtocols::Impaired(s) => s.notify_from_recv(event),
         }
     }
 
     async fn send(&mut self, event: ProtocolEvent) -> Result<(), ProtocolError<Self::CustomErr>> {
         match self {
             SendProtocols::Tcp(s) => s.send(event).await,
+            SendProtocols::Udp(s) => s.send(event).await,
             SendProtocols::Mpsc(s) => s.send(event).await,
             #[cfg(feature = "quic")]
             SendProtocols::Quic(s) => s.send(event).await,
+            SendProtocols::Impaired(s) => s.send(event).await,
         }
     }
 
@@ -453,9 +632,11 @@ impl network_protocol::SendProtocol for SendProtocols {
     ) -> Result<Bandwidth, ProtocolError<Self::CustomErr>> {
         match self {
             SendProtocols::Tcp(s) => s.flush(bandwidth, dt).await,
+            SendProtocols::Udp(s) => s.flush(bandwidth, dt).await,
             SendProtocols::Mpsc(s) => s.flush(bandwidth, dt).await,
             #[cfg(feature = "quic")]
             SendProtocols::Quic(s) => s.flush(bandwidth, dt).await,
+            SendProtocols::Impaired(s) => s.flush(bandwidth, dt).await,
         }
     }
 }
@@ -467,6 +648,7 @@ impl network_protocol::RecvProtocol for RecvProtocols {
     async fn recv(&mut self) -> Result<ProtocolEvent, ProtocolError<Self::CustomErr>> {
         match self {
             RecvProtocols::Tcp(r) => r.recv().await,
+            RecvProtocols::Udp(r) => r.recv().await,
             RecvProtocols::Mpsc(r) => r.recv().await,
             #[cfg(feature = "quic")]
             RecvProtocols::Quic(r) => r.recv().await,
@@ -546,6 +728,173 @@ impl UnreliableSink for TcpSink {
     }
 }
 
+///////////////////////////////////////
+// UDP
+/// Enough for every datagram the udp protocol sends
+const UDP_RECV_BUFFER_SIZE: usize = 1500;
+/// Datagrams buffered per remote of a listener before new ones are dropped
+const UDP_CHANNEL_BOUND: usize = 1024;
+/// There is no connection to break, but the remote sends a keep-alive every
+/// second
+const UDP_TIMEOUT: Duration = Duration::from_secs(10);
+/// The connecting side resends its latest handshake frame if the listener
+/// didn't answer it for this long
+const UDP_INIT_RESEND: Duration = Duration::from_millis(250);
+
+/// Handshake frames of one UDP channel, so lost ones can be resent
+#[derive(Debug, Default)]
+pub struct UdpInit {
+    /// Index of the latest handshake frame we sent and the frame itself
+    sent: Option<(u8, BytesMut)>,
+    /// Highest index of a handshake frame the remote sent
+    received: Option<u8>,
+}
+
+type SharedUdpInit = Arc<std::sync::Mutex<UdpInit>>;
+
+impl UdpInit {
+    /// Returns true if the remote sent a frame with this index before
+    fn receive(&mut self, index: u8) -> bool {
+        let repeated = self.received.is_some_and(|received| index <= received);
+        self.received = Some(self.received.map_or(index, |received| received.max(index)));
+        repeated
+    }
+
+    /// Our latest frame, if the remote didn't answer it yet
+    fn unanswered(&self) -> Option<BytesMut> {
+        self.sent
+            .as_ref()
+            .filter(|(index, _)| self.received.is_none_or(|received| received < *index))
+            .map(|(_, frame)| frame.clone())
+    }
+}
+
+#[derive(Debug)]
+pub struct UdpDrain {
+    socket: Arc<net::UdpSocket>,
+    remote: SocketAddr,
+    init: SharedUdpInit,
+}
+
+#[derive(Debug)]
+pub enum UdpSink {
+    /// Own socket of a connecting channel, resends our handshake frames till
+    /// the listener answers them
+    Socket {
+        socket: Arc<net::UdpSocket>,
+        remote: SocketAddr,
+        init: SharedUdpInit,
+    },
+    /// Datagrams dispatched by the listener. Only answers handshake frames the
+    /// remote sent again, so a listener never sends more than it received.
+    Listener {
+        receiver: mpsc::Receiver<BytesMut>,
+        socket: Arc<net::UdpSocket>,
+        remote: SocketAddr,
+        init: SharedUdpInit,
+    },
+}
+
+fn udp_error(e: io::Error) -> ProtocolError<ProtocolsError> {
+    ProtocolError::Custom(ProtocolsError::Udp(e))
+}
+
+fn udp_timeout() -> ProtocolError<ProtocolsError> {
+    ProtocolError::Custom(ProtocolsError::Udp(io::Error::new(
+        io::ErrorKind::TimedOut,
+        "no datagram received from remote",
+    )))
+}
+
+#[async_trait]
+impl UnreliableDrain for UdpDrain {
+    type CustomErr = ProtocolsError;
+    type DataFormat = BytesMut;
+
+    async fn send(&mut self, data: Self::DataFormat) -> Result<(), ProtocolError<Self::CustomErr>> {
+        if let Some(index) = udp_init_index(&data) {
+            self.init.lock().unwrap().sent = Some((index, data.clone()));
+        }
+        self.socket
+            .send_to(&data, self.remote)
+            .await
+            .map(|_| ())
+            .map_err(udp_error)
+    }
+}
+
+#[async_trait]
+impl UnreliableSink for UdpSink {
+    type CustomErr = ProtocolsError;
+    type DataFormat = BytesMut;
+
+    async fn recv(&mut self) -> Result<Self::DataFormat, ProtocolError<Self::CustomErr>> {
+        match self {
+            UdpSink::Socket {
+                socket,
+                remote,
+                init,
+            } => {
+                let mut buffer = [0u8; UDP_RECV_BUFFER_SIZE];
+                let deadline = Instant::now() + UDP_TIMEOUT;
+                loop {
+                    let unanswered = init.lock().unwrap().unanswered();
+                    let wait = if unanswered.is_some() {
+                        UDP_INIT_RESEND
+                    } else {
+                        UDP_TIMEOUT
+                    };
+                    let wait = wait.min(deadline.saturating_duration_since(Instant::now()));
+                    match tokio::time::timeout(wait, socket.recv_from(&mut buffer)).await {
+                        Ok(result) => {
+                            let (size, addr) = result.map_err(udp_error)?;
+                            // anyone can send to our port
+                            if addr == *remote {
+                                let datagram = BytesMut::from(&buffer[..size]);
+                                if let Some(index) = udp_init_index(&datagram) {
+                                    init.lock().unwrap().receive(index);
+                                }
+                                break Ok(datagram);
+                            }
+                        },
+                        Err(_) if Instant::now() >= deadline => break Err(udp_timeout()),
+                        Err(_) => {
+                            if let Some(frame) = unanswered {
+                                socket.send_to(&frame, *remote).await.map_err(udp_error)?;
+                            }
+                        },
+                    }
+                }
+            },
+            UdpSink::Listener {
+                receiver,
+                socket,
+                remote,
+                init,
+            } => {
+                let datagram = tokio::time::timeout(UDP_TIMEOUT, receiver.recv())
+                    .await
+                    .map_err(|_| udp_timeout())?
+                    .ok_or(udp_error(io::Error::new(
+                        io::ErrorKind::BrokenPipe,
+                        "udp listener stopped",
+                    )))?;
+                // The remote didn't get our answer to this frame, answer it once more
+                let resend = udp_init_index(&datagram).and_then(|index| {
+                    let mut init = init.lock().unwrap();
+                    init.receive(index)
+                        .then(|| init.sent.as_ref().map(|(_, frame)| frame.clone()))
+                        .flatten()
+                });
+                if let Some(frame) = resend {
+                    socket.send_to(&frame, *remote).await.map_err(udp_error)?;
+                }
+                Ok(datagram)
+            },
+        }
+    }
+}
+
 ///////////////////////////////////////
 // MPSC
 #[derive(Debug)]
@@ -768,8 +1117,8 @@ mod tests {
         let client = TcpStream::connect("127.0.0.1:5000").await.unwrap();
         let (_listener, server) = r1.await.unwrap();
         let metrics = ProtocolMetricCache::new("0", Arc::new(ProtocolMetrics::new().unwrap()));
-        let client = Protocols::new_tcp(client, metrics.clone());
-        let server = Protocols::new_tcp(server, metrics);
+        let client = Protocols::new_tcp(client, None, None, metrics.clone());
+        let server = Protocols::new_tcp(server, None, None, metrics);
         let (mut s, _) = client.split();
         let (_, mut r) = server.split();
         let event = ProtocolEvent::OpenStream {
@@ -817,8 +1166,8 @@ mod tests {
         let client = TcpStream::connect("127.0.0.1:5001").await.unwrap();
         let (_listener, server) = r1.await.unwrap();
         let metrics = ProtocolMetricCache::new("0", Arc::new(ProtocolMetrics::new().unwrap()));
-        let client = Protocols::new_tcp(client, metrics.clone());
-        let server = Protocols::new_tcp(server, metrics);
+        let client = Protocols::new_tcp(client, None, None, metrics.clone());
+        let server = Protocols::new_tcp(server, None, None, metrics);
         let (s, _) = client.split();
         let (_, mut r) = server.split();
         let e = tokio::spawn(async move { r.recv().await });
@@ -131,6 +131,9 @@ impl Message {
         }
     }
 
+    /// Number of bytes that are sent, after compression
+    pub fn size(&self) -> usize { self.data.len() }
+
     #[cfg(debug_assertions)]
     pub(crate) fn verify(&self, params: StreamParams) {
         #[cfg(not(feature = "compression"))]
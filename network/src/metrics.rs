@@ -16,7 +16,7 @@ pub(crate) enum ProtocolInfo {
 impl From<ListenAddr> for ProtocolInfo {
     fn from(other: ListenAddr) -> ProtocolInfo {
         match other {
-            ListenAddr::Tcp(s) => ProtocolInfo::Tcp(s),
+            ListenAddr::Tcp(s) | ListenAddr::TcpEncrypted(s, _) => ProtocolInfo::Tcp(s),
             ListenAddr::Udp(s) => ProtocolInfo::Udp(s),
             #[cfg(feature = "quic")]
             ListenAddr::Quic(s, _) => ProtocolInfo::Quic(s),
@@ -247,6 +247,7 @@ impl NetworkMetrics {
 fn protocolconnect_name(protocol: &ConnectAddr) -> &str {
     match protocol {
         ConnectAddr::Tcp(_) => "tcp",
+        ConnectAddr::TcpEncrypted(_, _) => "tcp_encrypted",
         ConnectAddr::Udp(_) => "udp",
         ConnectAddr::Mpsc(_) => "mpsc",
         #[cfg(feature = "quic")]
@@ -258,6 +259,7 @@ fn protocolconnect_name(protocol: &ConnectAddr) -> &str {
 fn protocollisten_name(protocol: &ListenAddr) -> &str {
     match protocol {
         ListenAddr::Tcp(_) => "tcp",
+        ListenAddr::TcpEncrypted(_, _) => "tcp_encrypted",
         ListenAddr::Udp(_) => "udp",
         ListenAddr::Mpsc(_) => "mpsc",
         #[cfg(feature = "quic")]
@@ -6,15 +6,16 @@ use crate::{
 };
 use bytes::Bytes;
 use futures_util::{FutureExt, StreamExt};
-use hashbrown::HashMap;
+use hashbrown::{HashMap, HashSet};
 use network_protocol::{
-    _internal::SortedVec, Bandwidth, Cid, Pid, Prio, Promises, ProtocolEvent, RecvProtocol,
-    SendProtocol, Sid,
+    _internal::SortedVec, Bandwidth, Cid, NetworkConditions, Pid, Prio, Promises, ProtocolError,
+    ProtocolEvent, RecvProtocol, SendProtocol, Sid,
 };
 use std::{
+    collections::VecDeque,
     sync::{
         Arc,
-        atomic::{AtomicBool, AtomicI32, Ordering},
+        atomic::{AtomicBool, AtomicI32, AtomicU64, Ordering},
     },
     time::{Duration, Instant},
 };
@@ -30,6 +31,7 @@ pub(crate) type A2bStreamOpen = (Prio, Promises, Bandwidth, oneshot::Sender<Stre
 pub(crate) type S2bCreateChannel = (Cid, Sid, Protocols, ConnectAddr, oneshot::Sender<()>);
 pub(crate) type S2bShutdownBparticipant = (Duration, oneshot::Sender<Result<(), ParticipantError>>);
 pub(crate) type B2sPrioStatistic = (Pid, u64, u64);
+pub(crate) type B2sReconnect = (Pid, ConnectAddr);
 
 #[derive(Debug)]
 #[expect(dead_code)]
@@ -47,6 +49,52 @@ struct StreamInfo {
     promises: Promises,
     send_closed: Arc<AtomicBool>,
     b2a_msg_recv_s: Mutex<async_channel::Sender<Bytes>>,
+    /// Messages received on this stream so far
+    received: AtomicU64,
+}
+
+/// State of a stream only needed by `send_mgr`
+#[derive(Debug)]
+struct SendStreamInfo {
+    prio: Prio,
+    promises: Promises,
+    guaranteed_bandwidth: Bandwidth,
+    /// We opened the stream, so we open it again on another channel
+    opened_locally: bool,
+    /// `received` of the last [`ProtocolEvent::Ack`] we sent
+    acked_received: u64,
+    replay: Option<ReplayBuffer>,
+}
+
+/// Messages the remote didn't confirm yet, they are sent again when the stream
+/// continues on another channel
+#[derive(Debug, Default)]
+struct ReplayBuffer {
+    /// Number of messages the remote confirmed, `messages` starts after them
+    acked: u64,
+    messages: VecDeque<Bytes>,
+    bytes: usize,
+    /// The stream was opened again, new messages are held back until the
+    /// remote tells which messages it is missing
+    waiting_for_ack: bool,
+}
+
+/// Forwarded from `recv_mgr` to `send_mgr` in the order they were received
+#[derive(Debug)]
+enum ResumeEvent {
+    /// The remote opened a stream again that we know or already closed
+    Reopen {
+        cid: Cid,
+        sid: Sid,
+        prio: Prio,
+        promises: Promises,
+        guaranteed_bandwidth: Bandwidth,
+    },
+    Ack {
+        cid: Cid,
+        sid: Sid,
+        received: u64,
+    },
 }
 
 #[derive(Debug)]
@@ -73,17 +121,75 @@ pub struct BParticipant {
     offset_sid: Sid,
     channels: Arc<RwLock<HashMap<Cid, Mutex<ChannelInfo>>>>,
     streams: RwLock<HashMap<Sid, StreamInfo>>,
+    /// Streams we closed, the remote might open them again if it missed that
+    closed_streams: Mutex<HashSet<Sid>>,
     run_channels: Option<ControlChannels>,
     shutdown_barrier: AtomicI32,
     metrics: Arc<NetworkMetrics>,
     open_stream_channels: Arc<Mutex<Option<OpenStreamInfo>>>,
+    /// Only set if we connected to the remote, the listening side waits for
+    /// the remote to reconnect
+    reconnect_addr: Option<ConnectAddr>,
+    /// How long to wait for a new channel after all channels failed, zero
+    /// disables resuming
+    resume_timeout: Duration,
+    /// Simulated on all new MPSC and TCP channels
+    network_conditions: NetworkConditions,
+}
+
+impl SendStreamInfo {
+    fn new(
+        prio: Prio,
+        promises: Promises,
+        guaranteed_bandwidth: Bandwidth,
+        opened_locally: bool,
+        replay_enabled: bool,
+    ) -> Self {
+        let resumable = Self::resumable(promises);
+        Self {
+            prio,
+            promises,
+            guaranteed_bandwidth,
+            opened_locally,
+            acked_received: 0,
+            replay: (resumable && replay_enabled).then(ReplayBuffer::default),
+        }
+    }
+
+    /// Only streams that don't lose or reorder messages are continued
+    /// without a gap, all others just continue on the new channel
+    fn resumable(promises: Promises) -> bool {
+        promises.contains(Promises::ORDERED | Promises::GUARANTEED_DELIVERY)
+    }
+}
+
+impl ReplayBuffer {
+    fn push(&mut self, data: Bytes) {
+        self.bytes += data.len();
+        self.messages.push_back(data);
+    }
+
+    /// Drops all messages the remote confirmed to have received
+    fn ack(&mut self, received: u64) {
+        while self.acked < received
+            && let Some(data) = self.messages.pop_front()
+        {
+            self.acked += 1;
+            self.bytes -= data.len();
+        }
+    }
 }
 
 impl BParticipant {
+    const ACK_INTERVAL: Duration = Duration::from_millis(100);
     // We use integer instead of Barrier to not block mgr from freeing at the end
     const BARR_CHANNEL: i32 = 1;
     const BARR_RECV: i32 = 4;
     const BARR_SEND: i32 = 2;
+    /// Unconfirmed data per stream, if the remote falls further behind the
+    /// participant can no longer be resumed
+    const MAX_REPLAY_BYTES: usize = 4 * 1024 * 1024;
+    pub(crate) const RECONNECT_INTERVAL: Duration = Duration::from_secs(1);
     const TICK_TIME: Duration = Duration::from_millis(Self::TICK_TIME_MS);
     const TICK_TIME_MS: u64 = 5;
 
@@ -92,6 +198,9 @@ impl BParticipant {
         remote_pid: Pid,
         offset_sid: Sid,
         metrics: Arc<NetworkMetrics>,
+        reconnect_addr: Option<ConnectAddr>,
+        resume_timeout: Duration,
+        network_conditions: NetworkConditions,
     ) -> (
         Self,
         mpsc::UnboundedSender<A2bStreamOpen>,
@@ -125,12 +234,16 @@ impl BParticipant {
                 offset_sid,
                 channels: Arc::new(RwLock::new(HashMap::new())),
                 streams: RwLock::new(HashMap::new()),
+                closed_streams: Mutex::new(HashSet::new()),
                 shutdown_barrier: AtomicI32::new(
                     Self::BARR_CHANNEL + Self::BARR_SEND + Self::BARR_RECV,
                 ),
                 run_channels,
                 metrics,
                 open_stream_channels: Arc::new(Mutex::new(None)),
+                reconnect_addr,
+                resume_timeout,
+                network_conditions,
             },
             a2b_open_stream_s,
             b2a_stream_opened_r,
@@ -141,19 +254,27 @@ impl BParticipant {
         )
     }
 
-    pub async fn run(mut self, b2s_prio_statistic_s: mpsc::UnboundedSender<B2sPrioStatistic>) {
+    pub async fn run(
+        mut self,
+        b2s_prio_statistic_s: mpsc::UnboundedSender<B2sPrioStatistic>,
+        b2s_reconnect_s: mpsc::UnboundedSender<B2sReconnect>,
+    ) {
         let (b2b_add_send_protocol_s, b2b_add_send_protocol_r) =
             mpsc::unbounded_channel::<(Cid, SendProtocols)>();
         let (b2b_add_recv_protocol_s, b2b_add_recv_protocol_r) =
             mpsc::unbounded_channel::<(Cid, RecvProtocols)>();
         let (b2b_close_send_protocol_s, b2b_close_send_protocol_r) =
             async_channel::unbounded::<Cid>();
+        let (b2b_lost_send_protocol_s, b2b_lost_send_protocol_r) =
+            async_channel::unbounded::<Cid>();
         let (b2b_force_close_recv_protocol_s, b2b_force_close_recv_protocol_r) =
             async_channel::unbounded::<Cid>();
         let (b2b_notify_send_of_recv_open_s, b2b_notify_send_of_recv_open_r) =
             crossbeam_channel::unbounded::<(Cid, Sid, Prio, Promises, u64)>();
         let (b2b_notify_send_of_recv_close_s, b2b_notify_send_of_recv_close_r) =
             crossbeam_channel::unbounded::<(Cid, Sid)>();
+        let (b2b_notify_send_of_recv_resume_s, b2b_notify_send_of_recv_resume_r) =
+            crossbeam_channel::unbounded::<ResumeEvent>();
 
         let (a2b_close_stream_s, a2b_close_stream_r) = mpsc::unbounded_channel::<Sid>();
         let (a2b_msg_s, a2b_msg_r) = crossbeam_channel::unbounded::<(Sid, Bytes)>();
@@ -171,10 +292,14 @@ impl BParticipant {
                 a2b_msg_r,
                 b2b_add_send_protocol_r,
                 b2b_close_send_protocol_r,
+                b2b_lost_send_protocol_r,
+                b2b_force_close_recv_protocol_s.clone(),
                 b2b_notify_send_of_recv_open_r,
                 b2b_notify_send_of_recv_close_r,
+                b2b_notify_send_of_recv_resume_r,
                 run_channels.b2a_event_s.clone(),
                 b2s_prio_statistic_s,
+                b2s_reconnect_s,
                 run_channels.b2a_bandwidth_stats_s,
             )
             .instrument(tracing::info_span!("send")),
@@ -183,8 +308,10 @@ impl BParticipant {
                 b2b_add_recv_protocol_r,
                 b2b_force_close_recv_protocol_r,
                 b2b_close_send_protocol_s.clone(),
+                b2b_lost_send_protocol_s,
                 b2b_notify_send_of_recv_open_s,
                 b2b_notify_send_of_recv_close_s,
+                b2b_notify_send_of_recv_resume_s,
             )
             .instrument(tracing::info_span!("recv")),
             self.create_channel_mgr(
@@ -203,12 +330,31 @@ impl BParticipant {
 
     fn best_protocol(all: &SortedVec<Cid, SendProtocols>, promises: Promises) -> Option<Cid> {
         // check for mpsc
-        all.data.iter().find(|(_, p)| matches!(p, SendProtocols::Mpsc(_))).map(|(c, _)| *c).or_else(
+        all.data.iter().find(|(_, p)| matches!(p.unimpaired(), SendProtocols::Mpsc(_))).map(|(c, _)| *c).or_else(
             || if network_protocol::TcpSendProtocol::<crate::channel::TcpDrain>::supported_promises()
                 .contains(promises)
             {
                 // check for tcp
-                all.data.iter().find(|(_, p)| matches!(p, SendProtocols::Tcp(_))).map(|(c, _)| *c)
+                all.data.iter().find(|(_, p)| matches!(p.unimpaired(), SendProtocols::Tcp(_))).map(|(c, _)| *c)
+            } else {
+                None
+            }
+        ).or_else(
+            // check for encrypted tcp
+            || if network_protocol::TcpSendProtocol::<crate::channel::TcpDrain>::supported_promises()
+                .union(Promises::ENCRYPTED)
+                .contains(promises)
+            {
+                all.data.iter().find(|(_, p)| matches!(p.unimpaired(), SendProtocols::Tcp(s) if s.encrypted())).map(|(c, _)| *c)
+            } else {
+                None
+            }
+        ).or_else(
+            // check for udp
+            || if network_protocol::UdpSendProtocol::<crate::channel::UdpDrain>::supported_promises()
+                .contains(promises)
+            {
+                all.data.iter().find(|(_, p)| matches!(p.unimpaired(), SendProtocols::Udp(_))).map(|(c, _)| *c)
             } else {
                 None
             }
@@ -217,7 +363,7 @@ impl BParticipant {
             || if network_protocol::QuicSendProtocol::<crate::channel::QuicDrain>::supported_promises()
                 .contains(promises)
             {
-                all.data.iter().find(|(_, p)| matches!(p, SendProtocols::Quic(_))).map(|(c, _)| *c)
+                all.data.iter().find(|(_, p)| matches!(p.unimpaired(), SendProtocols::Quic(_))).map(|(c, _)| *c)
             } else {
                 None
             }
@@ -229,6 +375,36 @@ impl BParticipant {
         )
     }
 
+    /// Messages received on `sid` so far
+    async fn received(&self, sid: Sid) -> u64 {
+        self.streams
+            .read()
+            .await
+            .get(&sid)
+            .map_or(0, |si| si.received.load(Ordering::Relaxed))
+    }
+
+    /// Tells the remote which messages of a stream it needs to send again
+    /// after the stream was opened on `protocol`. With `wait` our own messages
+    /// are held back until the remote answered the same.
+    async fn send_ack(
+        &self,
+        sid: Sid,
+        info: &mut SendStreamInfo,
+        protocol: &mut SendProtocols,
+        wait: bool,
+    ) -> Result<(), ProtocolError<ProtocolsError>> {
+        if !SendStreamInfo::resumable(info.promises) {
+            return Ok(());
+        }
+        if wait && let Some(replay) = &mut info.replay {
+            replay.waiting_for_ack = true;
+        }
+        let received = self.received(sid).await;
+        info.acked_received = received;
+        protocol.send(ProtocolEvent::Ack { sid, received }).await
+    }
+
     //TODO: local stream_cid: HashMap<Sid, Cid> to know the respective protocol
     async fn send_mgr(
         &self,
@@ -237,6 +413,8 @@ impl BParticipant {
         a2b_msg_r: crossbeam_channel::Receiver<(Sid, Bytes)>,
         mut b2b_add_protocol_r: mpsc::UnboundedReceiver<(Cid, SendProtocols)>,
         b2b_close_send_protocol_r: async_channel::Receiver<Cid>,
+        b2b_lost_send_protocol_r: async_channel::Receiver<Cid>,
+        b2b_force_close_recv_protocol_s: async_channel::Sender<Cid>,
         b2b_notify_send_of_recv_open_r: crossbeam_channel::Receiver<(
             Cid,
             Sid,
@@ -245,36 +423,71 @@ impl BParticipant {
             Bandwidth,
         )>,
         b2b_notify_send_of_recv_close_r: crossbeam_channel::Receiver<(Cid, Sid)>,
+        b2b_notify_send_of_recv_resume_r: crossbeam_channel::Receiver<ResumeEvent>,
         b2a_event_s: mpsc::UnboundedSender<ParticipantEvent>,
         _b2s_prio_statistic_s: mpsc::UnboundedSender<B2sPrioStatistic>,
+        b2s_reconnect_s: mpsc::UnboundedSender<B2sReconnect>,
         b2a_bandwidth_stats_s: watch::Sender<f32>,
     ) {
         let mut sorted_send_protocols = SortedVec::<Cid, SendProtocols>::default();
         let mut sorted_stream_protocols = SortedVec::<Sid, Cid>::default();
+        let mut send_streams = HashMap::<Sid, SendStreamInfo>::new();
+        let mut deferred_closes = Vec::<Sid>::new();
         let mut interval = tokio::time::interval(Self::TICK_TIME);
         let mut last_instant = Instant::now();
+        let mut last_ack = Instant::now();
         let mut stream_ids = self.offset_sid;
         let mut part_bandwidth = 0.0f32;
+        let mut replay_enabled = !self.resume_timeout.is_zero();
+        // streams of failed channels need to be opened on another channel
+        let mut rebind = false;
+        let mut disconnected_since: Option<Instant> = None;
+        let mut last_reconnect: Option<Instant> = None;
+        // recv_mgr has no channel left, but only stops without channels when
+        // resuming is disabled
+        let mut close_recv = false;
         trace!("workaround, actively wait for first protocol");
         if let Some((c, p)) = b2b_add_protocol_r.recv().await {
             sorted_send_protocols.insert(c, p)
         }
         loop {
-            let (open, close, _, addp, remp) = select!(
-                Some(n) = a2b_open_stream_r.recv().fuse() => (Some(n), None, None, None, None),
-                Some(n) = a2b_close_stream_r.recv().fuse() => (None, Some(n), None, None, None),
-                _ = interval.tick() => (None, None, Some(()), None, None),
-                Some(n) = b2b_add_protocol_r.recv().fuse() => (None, None, None, Some(n), None),
-                Ok(n) = b2b_close_send_protocol_r.recv().fuse() => (None, None, None, None, Some(n)),
+            let (open, close, _, addp, remp, lost) = select!(
+                Some(n) = a2b_open_stream_r.recv().fuse() => (Some(n), None, None, None, None, None),
+                Some(n) = a2b_close_stream_r.recv().fuse() => (None, Some(n), None, None, None, None),
+                _ = interval.tick() => (None, None, Some(()), None, None, None),
+                Some(n) = b2b_add_protocol_r.recv().fuse() => (None, None, None, Some(n), None, None),
+                Ok(n) = b2b_close_send_protocol_r.recv().fuse() => (None, None, None, None, Some(n), None),
+                Ok(n) = b2b_lost_send_protocol_r.recv().fuse() => (None, None, None, None, None, Some(n)),
             );
+            // without resuming, a failed channel is closed like a removed one
+            let (remp, lost, lost_as_remp) = match lost {
+                Some(cid) if !replay_enabled => (Some(cid), None, true),
+                lost => (remp, lost, false),
+            };
 
             if let Some((cid, p)) = addp {
                 debug!(?cid, "add protocol");
                 sorted_send_protocols.insert(cid, p);
+                rebind = true;
+                if let Some(since) = disconnected_since.take() {
+                    info!(duration = ?since.elapsed(), "participant resumed");
+                }
             }
 
-            //verify that we have at LEAST 1 channel before continuing
-            if sorted_send_protocols.data.is_empty() {
+            if let Some(since) = disconnected_since {
+                if !replay_enabled || since.elapsed() > self.resume_timeout {
+                    info!("participant wasn't resumed in time");
+                    close_recv = true;
+                    break;
+                }
+                if let Some(addr) = &self.reconnect_addr
+                    && last_reconnect.is_none_or(|last| last.elapsed() >= Self::RECONNECT_INTERVAL)
+                {
+                    last_reconnect = Some(Instant::now());
+                    let _ = b2s_reconnect_s.send((self.remote_pid, addr.clone()));
+                }
+            } else if sorted_send_protocols.data.is_empty() {
+                //verify that we have at LEAST 1 channel before continuing
                 warn!("no channel");
                 tokio::time::sleep(Self::TICK_TIME * 1000).await; //TODO: failover
                 continue;
@@ -283,12 +496,47 @@ impl BParticipant {
             //let (cid, active) = sorted_send_protocols.data.iter_mut().next().unwrap();
             //used for error handling
             let mut cid = u64::MAX;
+            let mut give_up = false;
 
             let active_err = async {
+                if rebind && !sorted_send_protocols.data.is_empty() {
+                    for (sid, stream_cid) in sorted_stream_protocols.data.iter_mut() {
+                        let sid = *sid;
+                        if sorted_send_protocols.get(stream_cid).is_some() {
+                            continue;
+                        }
+                        // streams opened by the remote are opened again by the remote
+                        let Some(info) = send_streams
+                            .get_mut(&sid)
+                            .filter(|info| info.opened_locally)
+                        else {
+                            continue;
+                        };
+                        cid = Self::best_protocol(&sorted_send_protocols, info.promises).unwrap();
+                        debug!(?sid, ?cid, "open stream again");
+                        *stream_cid = cid;
+                        let p = sorted_send_protocols.get_mut(&cid).unwrap();
+                        p.send(ProtocolEvent::OpenStream {
+                            sid,
+                            prio: info.prio,
+                            promises: info.promises,
+                            guaranteed_bandwidth: info.guaranteed_bandwidth,
+                        })
+                        .await?;
+                        self.send_ack(sid, info, p, true).await?;
+                    }
+                    rebind = false;
+                }
+
                 if let Some((prio, promises, guaranteed_bandwidth, return_s)) = open {
                     let sid = stream_ids;
                     stream_ids += Sid::from(1);
-                    cid = Self::best_protocol(&sorted_send_protocols, promises).unwrap();
+                    cid = if sorted_send_protocols.data.is_empty() {
+                        // opened once the participant is resumed
+                        Cid::MAX
+                    } else {
+                        Self::best_protocol(&sorted_send_protocols, promises).unwrap()
+                    };
                     trace!(?sid, ?cid, "open stream");
 
                     let stream = self
@@ -303,67 +551,230 @@ impl BParticipant {
                     };
 
                     sorted_stream_protocols.insert(sid, cid);
+                    send_streams.insert(
+                        sid,
+                        SendStreamInfo::new(
+                            prio,
+                            promises,
+                            guaranteed_bandwidth,
+                            true,
+                            replay_enabled,
+                        ),
+                    );
                     return_s.send(stream).unwrap();
-                    sorted_send_protocols
-                        .get_mut(&cid)
-                        .unwrap()
-                        .send(event)
-                        .await?;
+                    if let Some(p) = sorted_send_protocols.get_mut(&cid) {
+                        p.send(event).await?;
+                    }
                 }
 
                 // process recv content first
-                for (cid, sid, prio, promises, guaranteed_bandwidth) in
+                for (c, sid, prio, promises, guaranteed_bandwidth) in
                     b2b_notify_send_of_recv_open_r.try_iter()
                 {
-                    match sorted_send_protocols.get_mut(&cid) {
+                    match sorted_send_protocols.get_mut(&c) {
                         Some(p) => {
-                            sorted_stream_protocols.insert(sid, cid);
+                            cid = c;
+                            sorted_stream_protocols.insert(sid, c);
                             p.notify_from_recv(ProtocolEvent::OpenStream {
                                 sid,
                                 prio,
                                 promises,
                                 guaranteed_bandwidth,
                             });
+                            let info = send_streams.entry(sid).or_insert_with(|| {
+                                SendStreamInfo::new(
+                                    prio,
+                                    promises,
+                                    guaranteed_bandwidth,
+                                    false,
+                                    replay_enabled,
+                                )
+                            });
+                            // the remote waits for it, if it opened the stream during an outage
+                            self.send_ack(sid, info, p, false).await?;
                         },
-                        None => warn!(?cid, "couldn't notify create protocol, doesn't exist"),
+                        None => warn!(cid = ?c, "couldn't notify create protocol, doesn't exist"),
                     };
                 }
 
+                for event in b2b_notify_send_of_recv_resume_r.try_iter() {
+                    match event {
+                        ResumeEvent::Reopen {
+                            cid: c,
+                            sid,
+                            prio,
+                            promises,
+                            guaranteed_bandwidth,
+                        } => {
+                            let Some(p) = sorted_send_protocols.get_mut(&c) else {
+                                warn!(cid = ?c, "couldn't notify reopen protocol, doesn't exist");
+                                continue;
+                            };
+                            cid = c;
+                            p.notify_from_recv(ProtocolEvent::OpenStream {
+                                sid,
+                                prio,
+                                promises,
+                                guaranteed_bandwidth,
+                            });
+                            let Some(info) = send_streams.get_mut(&sid) else {
+                                debug!(?sid, "remote opened a closed stream again, close it again");
+                                p.send(ProtocolEvent::CloseStream { sid }).await?;
+                                continue;
+                            };
+                            debug!(?sid, ?cid, "remote opened stream again");
+                            match sorted_stream_protocols.get_mut(&sid) {
+                                Some(stream_cid) => *stream_cid = c,
+                                None => sorted_stream_protocols.insert(sid, c),
+                            }
+                            self.send_ack(sid, info, p, true).await?;
+                        },
+                        ResumeEvent::Ack {
+                            cid: c,
+                            sid,
+                            received,
+                        } => {
+                            // acks from a previous channel of the stream are outdated
+                            if sorted_stream_protocols.get(&sid) != Some(&c) {
+                                continue;
+                            }
+                            let Some(replay) = send_streams
+                                .get_mut(&sid)
+                                .and_then(|info| info.replay.as_mut())
+                            else {
+                                continue;
+                            };
+                            replay.ack(received);
+                            if !replay.waiting_for_ack {
+                                continue;
+                            }
+                            replay.waiting_for_ack = false;
+                            if let Some(p) = sorted_send_protocols.get_mut(&c) {
+                                cid = c;
+                                trace!(?sid, cnt = replay.messages.len(), "send messages again");
+                                for data in &replay.messages {
+                                    let event = ProtocolEvent::Message {
+                                        data: data.clone(),
+                                        sid,
+                                    };
+                                    p.send(event).await?;
+                                }
+                            }
+                        },
+                    }
+                }
+
                 // get all messages and assign it to a channel
+                let mut replay_overflow = false;
                 for (sid, buffer) in a2b_msg_r.try_iter() {
-                    cid = *sorted_stream_protocols.get(&sid).unwrap();
-                    let event = ProtocolEvent::Message { data: buffer, sid };
-                    sorted_send_protocols
-                        .get_mut(&cid)
-                        .unwrap()
-                        .send(event)
-                        .await?;
+                    let c = *sorted_stream_protocols.get(&sid).unwrap();
+                    if let Some(replay) = send_streams
+                        .get_mut(&sid)
+                        .and_then(|info| info.replay.as_mut())
+                    {
+                        replay.push(buffer.clone());
+                        replay_overflow |= replay.bytes > Self::MAX_REPLAY_BYTES;
+                        if replay.waiting_for_ack {
+                            continue;
+                        }
+                    }
+                    // without a channel, the message is only kept for replay
+                    if let Some(p) = sorted_send_protocols.get_mut(&c) {
+                        cid = c;
+                        let event = ProtocolEvent::Message { data: buffer, sid };
+                        p.send(event).await?;
+                    }
+                }
+                if replay_overflow {
+                    warn!("remote doesn't confirm messages, participant can no longer be resumed");
+                    replay_enabled = false;
+                    // held back messages can't be delivered anymore
+                    give_up = disconnected_since.is_some()
+                        || send_streams.iter().any(|(sid, info)| {
+                            info.replay.as_ref().is_some_and(|replay| {
+                                replay.waiting_for_ack
+                                    || sorted_stream_protocols
+                                        .get(sid)
+                                        .is_none_or(|c| sorted_send_protocols.get(c).is_none())
+                            })
+                        });
+                    for info in send_streams.values_mut() {
+                        info.replay = None;
+                    }
                 }
 
                 // process recv content afterwards
-                for (cid, sid) in b2b_notify_send_of_recv_close_r.try_iter() {
-                    match sorted_send_protocols.get_mut(&cid) {
+                for (c, sid) in b2b_notify_send_of_recv_close_r.try_iter() {
+                    match sorted_send_protocols.get_mut(&c) {
                         Some(p) => {
                             let _ = sorted_stream_protocols.delete(&sid);
+                            send_streams.remove(&sid);
                             p.notify_from_recv(ProtocolEvent::CloseStream { sid });
                         },
-                        None => warn!(?cid, "couldn't notify close protocol, doesn't exist"),
+                        None => warn!(cid = ?c, "couldn't notify close protocol, doesn't exist"),
                     };
                 }
 
-                if let Some(sid) = close {
-                    trace!(?stream_ids, "delete stream");
+                // streams of a failed channel are closed after they were resumed
+                deferred_closes.extend(close);
+                let mut closed = Vec::new();
+                for sid in std::mem::take(&mut deferred_closes) {
+                    let stream_cid = sorted_stream_protocols.get(&sid).copied();
+                    let resuming = stream_cid
+                        .is_some_and(|c| sorted_send_protocols.get(&c).is_none())
+                        || send_streams
+                            .get(&sid)
+                            .and_then(|info| info.replay.as_ref())
+                            .is_some_and(|replay| replay.waiting_for_ack);
+                    if resuming {
+                        deferred_closes.push(sid);
+                        continue;
+                    }
+                    trace!(?sid, "delete stream");
                     self.delete_stream(sid).await;
+                    send_streams.remove(&sid);
+                    if !self.resume_timeout.is_zero() {
+                        self.closed_streams.lock().await.insert(sid);
+                    }
+                    if let Some(c) = sorted_stream_protocols.delete(&sid) {
+                        closed.push((sid, c));
+                    }
+                }
+                for (sid, c) in closed {
                     // Fire&Forget the protocol will take care to verify that this Frame is delayed
                     // till the last msg was received!
-                    if let Some(c) = sorted_stream_protocols.delete(&sid) {
-                        cid = c;
-                        let event = ProtocolEvent::CloseStream { sid };
-                        sorted_send_protocols
-                            .get_mut(&c)
-                            .unwrap()
-                            .send(event)
-                            .await?;
+                    cid = c;
+                    let event = ProtocolEvent::CloseStream { sid };
+                    sorted_send_protocols
+                        .get_mut(&c)
+                        .unwrap()
+                        .send(event)
+                        .await?;
+                }
+
+                // tell the remote which messages it doesn't need to keep anymore
+                if last_ack.elapsed() >= Self::ACK_INTERVAL {
+                    last_ack = Instant::now();
+                    let acks: Vec<_> = {
+                        let streams = self.streams.read().await;
+                        send_streams
+                            .iter()
+                            .filter(|(_, info)| SendStreamInfo::resumable(info.promises))
+                            .filter_map(|(sid, info)| {
+                                let received = streams.get(sid)?.received.load(Ordering::Relaxed);
+                                (received != info.acked_received).then_some((*sid, received))
+                            })
+                            .collect()
+                    };
+                    for (sid, received) in acks {
+                        if let Some(&c) = sorted_stream_protocols.get(&sid)
+                            && let Some(p) = sorted_send_protocols.get_mut(&c)
+                            && let Some(info) = send_streams.get_mut(&sid)
+                        {
+                            cid = c;
+                            info.acked_received = received;
+                            p.send(ProtocolEvent::Ack { sid, received }).await?;
+                        }
                     }
                 }
 
@@ -380,16 +791,32 @@ impl BParticipant {
                 self.metrics
                     .participant_bandwidth(&self.remote_pid_string, part_bandwidth);
                 let _ = b2a_bandwidth_stats_s.send(part_bandwidth);
-                let r: Result<(), network_protocol::ProtocolError<ProtocolsError>> = Ok(());
+                let r: Result<(), ProtocolError<ProtocolsError>> = Ok(());
                 r
             }
             .await;
+            if give_up {
+                close_recv = true;
+                break;
+            }
+
+            let mut failed: Vec<Cid> = lost.into_iter().collect();
             if let Err(e) = active_err {
                 info!(?cid, ?e, "protocol failed, shutting down channel");
                 // remote recv will now fail, which will trigger remote send which will trigger
                 // recv
-                trace!("TODO: for now decide to FAIL this participant and not wait for a failover");
-                sorted_send_protocols.delete(&cid).unwrap();
+                if !replay_enabled {
+                    trace!(
+                        "TODO: for now decide to FAIL this participant and not wait for a failover"
+                    );
+                }
+                failed.push(cid);
+            }
+            for cid in failed {
+                if sorted_send_protocols.delete(&cid).is_none() {
+                    trace!(?cid, "protocol already failed");
+                    continue;
+                }
                 if let Some(info) = self.channels.write().await.get(&cid)
                     && let Err(e) = b2a_event_s.send(ParticipantEvent::ChannelDeleted(
                         info.lock().await.remote_con_addr.clone(),
@@ -398,10 +825,19 @@ impl BParticipant {
                     debug!(?e, "Participant was dropped during channel disconnect");
                 };
                 self.metrics.channels_disconnected(&self.remote_pid_string);
-                if sorted_send_protocols.data.is_empty() {
-                    break;
+                rebind = true;
+                if sorted_send_protocols.data.is_empty()
+                    && disconnected_since.is_none()
+                    && replay_enabled
+                {
+                    info!("lost all channels, waiting for the participant to be resumed");
+                    disconnected_since = Some(Instant::now());
+                    last_reconnect = None;
                 }
             }
+            if sorted_send_protocols.data.is_empty() && disconnected_since.is_none() {
+                break;
+            }
 
             if let Some(cid) = remp {
                 debug!(?cid, "remove protocol");
@@ -423,12 +859,18 @@ impl BParticipant {
                     None => trace!("tried to remove protocol twice"),
                 };
                 if sorted_send_protocols.data.is_empty() {
+                    close_recv = lost_as_remp && !self.resume_timeout.is_zero();
                     break;
                 }
             }
         }
         trace!("stop sending in api!");
         self.open_stream_channels.lock().await.take();
+        if close_recv || disconnected_since.is_some() {
+            for cid in self.channels.read().await.keys() {
+                let _ = b2b_force_close_recv_protocol_s.send(*cid).await;
+            }
+        }
         trace!("Stop send_mgr");
         self.shutdown_barrier
             .fetch_sub(Self::BARR_SEND, Ordering::SeqCst);
@@ -440,6 +882,7 @@ impl BParticipant {
         mut b2b_add_protocol_r: mpsc::UnboundedReceiver<(Cid, RecvProtocols)>,
         b2b_force_close_recv_protocol_r: async_channel::Receiver<Cid>,
         b2b_close_send_protocol_s: async_channel::Sender<Cid>,
+        b2b_lost_send_protocol_s: async_channel::Sender<Cid>,
         b2b_notify_send_of_recv_open_r: crossbeam_channel::Sender<(
             Cid,
             Sid,
@@ -448,6 +891,7 @@ impl BParticipant {
             Bandwidth,
         )>,
         b2b_notify_send_of_recv_close_s: crossbeam_channel::Sender<(Cid, Sid)>,
+        b2b_notify_send_of_recv_resume_s: crossbeam_channel::Sender<ResumeEvent>,
     ) {
         let mut recv_protocols: HashMap<Cid, JoinHandle<()>> = HashMap::new();
         // we should be able to directly await futures imo
@@ -505,20 +949,34 @@ impl BParticipant {
                         promises,
                         guaranteed_bandwidth,
                     }) => {
-                        trace!(?sid, "open stream");
-                        let _ = b2b_notify_send_of_recv_open_r.send((
-                            cid,
-                            sid,
-                            prio,
-                            promises,
-                            guaranteed_bandwidth,
-                        ));
-                        // waiting for receiving is not necessary, because the send_mgr will first
-                        // process this before process messages!
-                        let stream = self
-                            .create_stream(sid, prio, promises, guaranteed_bandwidth)
-                            .await;
-                        b2a_stream_opened_s.send(stream).unwrap();
+                        // the remote opens streams again after their channel failed
+                        let known = self.streams.read().await.contains_key(&sid)
+                            || self.closed_streams.lock().await.contains(&sid);
+                        if known {
+                            trace!(?sid, "reopen stream");
+                            let _ = b2b_notify_send_of_recv_resume_s.send(ResumeEvent::Reopen {
+                                cid,
+                                sid,
+                                prio,
+                                promises,
+                                guaranteed_bandwidth,
+                            });
+                        } else {
+                            trace!(?sid, "open stream");
+                            let _ = b2b_notify_send_of_recv_open_r.send((
+                                cid,
+                                sid,
+                                prio,
+                                promises,
+                                guaranteed_bandwidth,
+                            ));
+                            // waiting for receiving is not necessary, because the send_mgr will
+                            // first process this before process messages!
+                            let stream = self
+                                .create_stream(sid, prio, promises, guaranteed_bandwidth)
+                                .await;
+                            b2a_stream_opened_s.send(stream).unwrap();
+                        }
                         retrigger(cid, p, &mut recv_protocols);
                     },
                     Ok(ProtocolEvent::CloseStream { sid }) => {
@@ -531,12 +989,21 @@ impl BParticipant {
                         let lock = self.streams.read().await;
                         match lock.get(&sid) {
                             Some(stream) => {
+                                stream.received.fetch_add(1, Ordering::Relaxed);
                                 let _ = stream.b2a_msg_recv_s.lock().await.send(data).await;
                             },
                             None => defered_orphan.log(sid),
                         };
                         retrigger(cid, p, &mut recv_protocols);
                     },
+                    Ok(ProtocolEvent::Ack { sid, received }) => {
+                        let _ = b2b_notify_send_of_recv_resume_s.send(ResumeEvent::Ack {
+                            cid,
+                            sid,
+                            received,
+                        });
+                        retrigger(cid, p, &mut recv_protocols);
+                    },
                     Ok(ProtocolEvent::Shutdown) => {
                         info!(?cid, "shutdown protocol");
                         if let Err(e) = b2b_close_send_protocol_s.send(cid).await {
@@ -548,10 +1015,14 @@ impl BParticipant {
                     },
                     Err(e) => {
                         info!(?e, ?cid, "protocol failed, shutting down channel");
-                        if let Err(e) = b2b_close_send_protocol_s.send(cid).await {
-                            debug!(?e, ?cid, "send_mgr was already closed simultaneously");
+                        let send_closed = b2b_lost_send_protocol_s.send(cid).await.is_err();
+                        if send_closed {
+                            debug!(?cid, "send_mgr was already closed simultaneously");
                         }
-                        if remove_c(&mut recv_protocols, &cid) {
+                        // otherwise send_mgr stops us, if the participant isn't resumed
+                        if remove_c(&mut recv_protocols, &cid)
+                            && (send_closed || self.resume_timeout.is_zero())
+                        {
                             break;
                         }
                     },
@@ -605,8 +1076,15 @@ impl BParticipant {
                         );
                         drop(lock);
                         let (send, recv) = protocol.split();
-                        b2b_add_send_protocol_s.send((cid, send)).unwrap();
-                        b2b_add_recv_protocol_s.send((cid, recv)).unwrap();
+                        let send = send.with_conditions(self.network_conditions);
+                        // a participant that wasn't resumed in time is already closed
+                        if b2b_add_send_protocol_s.send((cid, send)).is_err()
+                            || b2b_add_recv_protocol_s.send((cid, recv)).is_err()
+                        {
+                            debug!(?cid, "participant is already closed, dropping channel");
+                            let _ = b2s_create_channel_done_s.send(());
+                            return;
+                        }
                         if let Err(e) =
                             b2a_event_s.send(ParticipantEvent::ChannelCreated(remote_con_addr))
                         {
@@ -765,6 +1243,7 @@ impl BParticipant {
             promises,
             send_closed: Arc::clone(&send_closed),
             b2a_msg_recv_s: Mutex::new(b2a_msg_recv_s),
+            received: AtomicU64::new(0),
         });
         self.metrics.streams_opened(&self.remote_pid_string);
 
@@ -803,6 +1282,7 @@ impl BParticipant {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message::Message;
     use core::assert_matches::assert_matches;
     use network_protocol::{ProtocolMetricCache, ProtocolMetrics};
     use tokio::{
@@ -811,7 +1291,9 @@ mod tests {
         task::JoinHandle,
     };
 
-    fn mock_bparticipant() -> (
+    fn mock_bparticipant(
+        resume_timeout: Duration,
+    ) -> (
         Arc<Runtime>,
         mpsc::UnboundedSender<A2bStreamOpen>,
         mpsc::UnboundedReceiver<Stream>,
@@ -842,10 +1324,19 @@ mod tests {
             let sid = Sid::new(1000);
             let metrics = Arc::new(NetworkMetrics::new(&local_pid).unwrap());
 
-            BParticipant::new(local_pid, remote_pid, sid, Arc::clone(&metrics))
+            BParticipant::new(
+                local_pid,
+                remote_pid,
+                sid,
+                Arc::clone(&metrics),
+                None,
+                resume_timeout,
+                NetworkConditions::default(),
+            )
         });
 
-        let handle = runtime_clone.spawn(bparticipant.run(b2s_prio_statistic_s));
+        let (b2s_reconnect_s, _) = mpsc::unbounded_channel::<B2sReconnect>();
+        let handle = runtime_clone.spawn(bparticipant.run(b2s_prio_statistic_s, b2s_reconnect_s));
         (
             runtime_clone,
             a2b_open_stream_s,
@@ -891,7 +1382,7 @@ mod tests {
             b2s_prio_statistic_r,
             _b2a_bandwidth_stats_r,
             handle,
-        ) = mock_bparticipant();
+        ) = mock_bparticipant(Duration::ZERO);
 
         let _remote = runtime.block_on(mock_mpsc(0, &runtime, &mut s2b_create_channel_s));
         std::thread::sleep(Duration::from_millis(50));
@@ -937,7 +1428,7 @@ mod tests {
             b2s_prio_statistic_r,
             _b2a_bandwidth_stats_r,
             handle,
-        ) = mock_bparticipant();
+        ) = mock_bparticipant(Duration::ZERO);
 
         let remote = runtime.block_on(mock_mpsc(0, &runtime, &mut s2b_create_channel_s));
         std::thread::sleep(Duration::from_millis(50));
@@ -984,7 +1475,7 @@ mod tests {
             b2s_prio_statistic_r,
             _b2a_bandwidth_stats_r,
             handle,
-        ) = mock_bparticipant();
+        ) = mock_bparticipant(Duration::ZERO);
 
         let remote = runtime.block_on(mock_mpsc(0, &runtime, &mut s2b_create_channel_s));
         std::thread::sleep(Duration::from_millis(50));
@@ -1040,7 +1531,7 @@ mod tests {
             b2s_prio_statistic_r,
             _b2a_bandwidth_stats_r,
             handle,
-        ) = mock_bparticipant();
+        ) = mock_bparticipant(Duration::ZERO);
 
         let remote = runtime.block_on(mock_mpsc(0, &runtime, &mut s2b_create_channel_s));
         std::thread::sleep(Duration::from_millis(50));
@@ -1074,4 +1565,88 @@ mod tests {
         drop((a2b_open_stream_s, b2a_stream_opened_r, b2s_prio_statistic_r));
         drop(runtime);
     }
+
+    #[test]
+    fn resume_stream_on_new_channel() {
+        let (
+            runtime,
+            a2b_open_stream_s,
+            b2a_stream_opened_r,
+            _b2a_event_r,
+            mut s2b_create_channel_s,
+            s2b_shutdown_bparticipant_s,
+            b2s_prio_statistic_r,
+            _b2a_bandwidth_stats_r,
+            handle,
+        ) = mock_bparticipant(Duration::from_secs(10));
+
+        let remote = runtime.block_on(mock_mpsc(0, &runtime, &mut s2b_create_channel_s));
+        std::thread::sleep(Duration::from_millis(50));
+
+        let (rs, mut rr) = remote.split();
+        let (stream_sender, stream_receiver) = oneshot::channel();
+        let promises = Promises::ORDERED | Promises::GUARANTEED_DELIVERY;
+        a2b_open_stream_s
+            .send((7u8, promises, 0, stream_sender))
+            .unwrap();
+        let stream = runtime.block_on(stream_receiver).unwrap();
+        assert_matches!(
+            runtime.block_on(rr.recv()),
+            Ok(ProtocolEvent::OpenStream { sid, .. }) if sid == Sid::new(1000)
+        );
+        stream.send(1u32).unwrap();
+        assert_matches!(
+            runtime.block_on(rr.recv()),
+            Ok(ProtocolEvent::Message { .. })
+        );
+
+        // channel fails, messages are kept till the participant is resumed
+        drop((rs, rr));
+        std::thread::sleep(Duration::from_millis(50));
+        stream.send(2u32).unwrap();
+        std::thread::sleep(Duration::from_millis(50));
+
+        let remote = runtime.block_on(mock_mpsc(1, &runtime, &mut s2b_create_channel_s));
+        let (mut rs, mut rr) = remote.split();
+        assert_matches!(
+            runtime.block_on(rr.recv()),
+            Ok(ProtocolEvent::OpenStream { sid, .. }) if sid == Sid::new(1000)
+        );
+        assert_matches!(
+            runtime.block_on(rr.recv()),
+            Ok(ProtocolEvent::Ack { sid, received: 0 }) if sid == Sid::new(1000)
+        );
+        // the first message arrived before the channel failed
+        runtime
+            .block_on(rs.send(ProtocolEvent::Ack {
+                sid: Sid::new(1000),
+                received: 1,
+            }))
+            .unwrap();
+        let expected = Message::serialize(&2u32, stream.params()).data;
+        assert_matches!(
+            runtime.block_on(rr.recv()),
+            Ok(ProtocolEvent::Message { sid, data }) if sid == Sid::new(1000) && data == expected
+        );
+
+        let (s, r) = oneshot::channel();
+        runtime.block_on(async {
+            drop(s2b_create_channel_s);
+            s2b_shutdown_bparticipant_s
+                .send((Duration::from_secs(1), s))
+                .unwrap();
+            drop((rs, rr));
+            r.await.unwrap().unwrap();
+        });
+
+        runtime.block_on(handle).unwrap();
+
+        drop((
+            stream,
+            a2b_open_stream_s,
+            b2a_stream_opened_r,
+            b2s_prio_statistic_r,
+        ));
+        drop(runtime);
+    }
 }
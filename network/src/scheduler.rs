@@ -2,17 +2,19 @@ use crate::{
     api::{ConnectAddr, ListenAddr, NetworkConnectError, Participant},
     channel::Protocols,
     metrics::{NetworkMetrics, ProtocolInfo},
-    participant::{B2sPrioStatistic, BParticipant, S2bCreateChannel, S2bShutdownBparticipant},
+    participant::{
+        B2sPrioStatistic, B2sReconnect, BParticipant, S2bCreateChannel, S2bShutdownBparticipant,
+    },
 };
 use futures_util::StreamExt;
 use hashbrown::HashMap;
-use network_protocol::{Cid, Pid, ProtocolMetricCache, ProtocolMetrics};
+use network_protocol::{Cid, NetworkConditions, Pid, ProtocolMetricCache, ProtocolMetrics};
 #[cfg(feature = "metrics")]
 use prometheus::Registry;
 use rand::RngExt;
 use std::{
     sync::{
-        Arc,
+        Arc, RwLock,
         atomic::{AtomicBool, AtomicU64, Ordering},
     },
     time::Duration,
@@ -36,7 +38,6 @@ use tracing::*;
 #[derive(Debug)]
 struct ParticipantInfo {
     secret: u128,
-    #[expect(dead_code)]
     s2b_create_channel_s: mpsc::UnboundedSender<S2bCreateChannel>,
     s2b_shutdown_bparticipant_s: Option<oneshot::Sender<S2bShutdownBparticipant>>,
 }
@@ -55,6 +56,7 @@ struct ControlChannels {
     a2s_scheduler_shutdown_r: oneshot::Receiver<()>,
     a2s_disconnect_r: mpsc::UnboundedReceiver<A2sDisconnect>,
     b2s_prio_statistic_r: mpsc::UnboundedReceiver<B2sPrioStatistic>,
+    b2s_reconnect_r: mpsc::UnboundedReceiver<B2sReconnect>,
 }
 
 #[derive(Debug, Clone)]
@@ -62,6 +64,7 @@ struct ParticipantChannels {
     s2a_connected_s: mpsc::UnboundedSender<Participant>,
     a2s_disconnect_s: mpsc::UnboundedSender<A2sDisconnect>,
     b2s_prio_statistic_s: mpsc::UnboundedSender<B2sPrioStatistic>,
+    b2s_reconnect_s: mpsc::UnboundedSender<B2sReconnect>,
 }
 
 #[derive(Debug)]
@@ -76,11 +79,17 @@ pub struct Scheduler {
     channel_listener: Mutex<HashMap<ProtocolInfo, oneshot::Sender<()>>>,
     metrics: Arc<NetworkMetrics>,
     protocol_metrics: Arc<ProtocolMetrics>,
+    /// in milliseconds, shared with [`Network`](crate::api::Network)
+    resume_timeout: Arc<AtomicU64>,
+    /// shared with [`Network`](crate::api::Network)
+    network_conditions: Arc<RwLock<NetworkConditions>>,
 }
 
 impl Scheduler {
     pub fn new(
         local_pid: Pid,
+        resume_timeout: Arc<AtomicU64>,
+        network_conditions: Arc<RwLock<NetworkConditions>>,
         #[cfg(feature = "metrics")] registry: Option<&Registry>,
     ) -> (
         Self,
@@ -96,6 +105,7 @@ impl Scheduler {
         let (a2s_disconnect_s, a2s_disconnect_r) = mpsc::unbounded_channel::<A2sDisconnect>();
         let (b2s_prio_statistic_s, b2s_prio_statistic_r) =
             mpsc::unbounded_channel::<B2sPrioStatistic>();
+        let (b2s_reconnect_s, b2s_reconnect_r) = mpsc::unbounded_channel::<B2sReconnect>();
 
         let run_channels = Some(ControlChannels {
             a2s_listen_r,
@@ -103,12 +113,14 @@ impl Scheduler {
             a2s_scheduler_shutdown_r,
             a2s_disconnect_r,
             b2s_prio_statistic_r,
+            b2s_reconnect_r,
         });
 
         let participant_channels = ParticipantChannels {
             s2a_connected_s,
             a2s_disconnect_s,
             b2s_prio_statistic_s,
+            b2s_reconnect_s,
         };
 
         let metrics = Arc::new(NetworkMetrics::new(&local_pid).unwrap());
@@ -137,6 +149,8 @@ impl Scheduler {
                 channel_listener: Mutex::new(HashMap::new()),
                 metrics,
                 protocol_metrics,
+                resume_timeout,
+                network_conditions,
             },
             a2s_listen_s,
             a2s_connect_s,
@@ -156,6 +170,7 @@ impl Scheduler {
             self.connect_mgr(run_channels.a2s_connect_r),
             self.disconnect_mgr(run_channels.a2s_disconnect_r),
             self.prio_adj_mgr(run_channels.b2s_prio_statistic_r),
+            self.reconnect_mgr(run_channels.b2s_reconnect_r),
             self.scheduler_shutdown_mgr(run_channels.a2s_scheduler_shutdown_r),
         );
     }
@@ -190,6 +205,18 @@ impl Scheduler {
                         ListenAddr::Tcp(addr) => {
                             Protocols::with_tcp_listen(
                                 addr,
+                                None,
+                                cids,
+                                metrics,
+                                s2s_stop_listening_r,
+                                c2s_protocol_s,
+                            )
+                            .await
+                        },
+                        ListenAddr::TcpEncrypted(addr, ref static_key) => {
+                            Protocols::with_tcp_listen(
+                                addr,
+                                Some(Arc::clone(static_key)),
                                 cids,
                                 metrics,
                                 s2s_stop_listening_r,
@@ -209,6 +236,16 @@ impl Scheduler {
                             )
                             .await
                         },
+                        ListenAddr::Udp(addr) => {
+                            Protocols::with_udp_listen(
+                                addr,
+                                cids,
+                                metrics,
+                                s2s_stop_listening_r,
+                                c2s_protocol_s,
+                            )
+                            .await
+                        },
                         ListenAddr::Mpsc(addr) => {
                             Protocols::with_mpsc_listen(
                                 addr,
@@ -219,12 +256,12 @@ impl Scheduler {
                             )
                             .await
                         },
-                        _ => unimplemented!(),
                     };
                     let _ = s2a_listen_result_s.send(res);
 
                     while let Some((prot, con_addr, cid)) = c2s_protocol_r.recv().await {
-                        self.init_protocol(prot, con_addr, cid, None, true).await;
+                        self.init_protocol(prot, con_addr, cid, None, true, None)
+                            .await;
                     }
                 }
             })
@@ -239,28 +276,69 @@ impl Scheduler {
             let metrics =
                 ProtocolMetricCache::new(&cid.to_string(), Arc::clone(&self.protocol_metrics));
             self.metrics.connect_request(&addr);
-            let protocol = match addr.clone() {
-                ConnectAddr::Tcp(addr) => Protocols::with_tcp_connect(addr, metrics).await,
-                #[cfg(feature = "quic")]
-                ConnectAddr::Quic(addr, ref config, name) => {
-                    Protocols::with_quic_connect(addr, config.clone(), name, metrics).await
-                },
-                ConnectAddr::Mpsc(addr) => Protocols::with_mpsc_connect(addr, metrics).await,
-                _ => unimplemented!(),
-            };
-            let protocol = match protocol {
+            let protocol = match Self::connect_protocol(addr.clone(), metrics).await {
                 Ok(p) => p,
                 Err(e) => {
                     pid_sender.send(Err(e)).unwrap();
                     continue;
                 },
             };
-            self.init_protocol(protocol, addr, cid, Some(pid_sender), false)
+            self.init_protocol(protocol, addr, cid, Some(pid_sender), false, None)
                 .await;
         }
         trace!("Stop connect_mgr");
     }
 
+    async fn connect_protocol(
+        addr: ConnectAddr,
+        metrics: ProtocolMetricCache,
+    ) -> Result<Protocols, NetworkConnectError> {
+        match addr {
+            ConnectAddr::Tcp(addr) => Protocols::with_tcp_connect(addr, None, metrics).await,
+            ConnectAddr::TcpEncrypted(addr, remote_key) => {
+                Protocols::with_tcp_connect(addr, Some(remote_key), metrics).await
+            },
+            ConnectAddr::Udp(addr) => Protocols::with_udp_connect(addr, metrics).await,
+            #[cfg(feature = "quic")]
+            ConnectAddr::Quic(addr, config, name) => {
+                Protocols::with_quic_connect(addr, config, name, metrics).await
+            },
+            ConnectAddr::Mpsc(addr) => Protocols::with_mpsc_connect(addr, metrics).await,
+        }
+    }
+
+    /// Connects again to participants that lost all their channels, so they
+    /// can be resumed
+    async fn reconnect_mgr(&self, b2s_reconnect_r: mpsc::UnboundedReceiver<B2sReconnect>) {
+        trace!("Start reconnect_mgr");
+        let b2s_reconnect_r = UnboundedReceiverStream::new(b2s_reconnect_r);
+        b2s_reconnect_r
+            .for_each_concurrent(None, |(pid, addr)| async move {
+                if self.closed.load(Ordering::Relaxed) {
+                    return;
+                }
+                let cid = self.channel_ids.fetch_add(1, Ordering::Relaxed);
+                let metrics =
+                    ProtocolMetricCache::new(&cid.to_string(), Arc::clone(&self.protocol_metrics));
+                debug!(?pid, ?cid, "Reconnecting to resume participant");
+                let protocol = tokio::time::timeout(
+                    BParticipant::RECONNECT_INTERVAL,
+                    Self::connect_protocol(addr.clone(), metrics),
+                )
+                .await;
+                match protocol {
+                    Ok(Ok(protocol)) => {
+                        self.init_protocol(protocol, addr, cid, None, false, Some(pid))
+                            .await
+                    },
+                    Ok(Err(e)) => debug!(?pid, ?e, "Reconnect failed"),
+                    Err(_) => debug!(?pid, "Reconnect timed out"),
+                }
+            })
+            .await;
+        trace!("Stop reconnect_mgr");
+    }
+
     async fn disconnect_mgr(&self, a2s_disconnect_r: mpsc::UnboundedReceiver<A2sDisconnect>) {
         trace!("Start disconnect_mgr");
 
@@ -379,6 +457,7 @@ impl Scheduler {
         cid: Cid,
         s2a_return_pid_s: Option<oneshot::Sender<Result<Participant, NetworkConnectError>>>,
         send_handshake: bool,
+        resume_pid: Option<Pid>,
     ) {
         //channels are unknown till PID is known!
         /* When A connects to a NETWORK, we, the listener answers with a Handshake.
@@ -386,7 +465,10 @@ impl Scheduler {
           Contra: - DOS possibility because we answer first
                   - Speed, because otherwise the message can be send with the creation
         */
-        let participant_channels = self.participant_channels.lock().await.clone().unwrap();
+        let Some(participant_channels) = self.participant_channels.lock().await.clone() else {
+            debug!(?cid, "Network is shutting down, dropping channel");
+            return;
+        };
         // spawn is needed here, e.g. for TCP connect it would mean that only 1
         // participant can be in handshake phase ever! Someone could deadlock
         // the whole server easily for new clients UDP doesnt work at all, as
@@ -395,25 +477,68 @@ impl Scheduler {
         let metrics = Arc::clone(&self.metrics);
         let local_pid = self.local_pid;
         let local_secret = self.local_secret;
+        let resume_timeout = Duration::from_millis(self.resume_timeout.load(Ordering::Relaxed));
+        let network_conditions = *self
+            .network_conditions
+            .read()
+            .expect("network conditions lock poisoned");
         // this is necessary for UDP to work at all and to remove code duplication
         tokio::spawn(
             async move {
                 trace!(?cid, "Open channel and be ready for Handshake");
                 use network_protocol::InitProtocol;
                 let init_result = protocol
-                    .initialize(send_handshake, local_pid, local_secret)
+                    .initialize(
+                        send_handshake,
+                        resume_pid.is_some(),
+                        local_pid,
+                        local_secret,
+                    )
                     .instrument(info_span!("handshake", ?cid))
                     .await;
                 match init_result {
-                    Ok((pid, sid, secret)) => {
+                    Ok((pid, sid, secret, remote_resume)) => {
                         trace!(
                             ?cid,
                             ?pid,
                             "Detected that my channel is ready!, activating it :)"
                         );
+                        let resume = remote_resume || resume_pid.is_some();
+                        // The secret is all that's needed to take over a participant, so only
+                        // accept it over channels where no one else can read it
+                        let encrypted = protocol.encrypted();
+                        if resume && (resume_timeout.is_zero() || !encrypted) {
+                            info!(
+                                ?cid,
+                                ?pid,
+                                ?encrypted,
+                                "Resuming is disabled or the channel isn't encrypted, dropping"
+                            );
+                            return;
+                        }
+                        if resume_pid.is_some_and(|resume_pid| resume_pid != pid) {
+                            info!(
+                                ?cid,
+                                ?pid,
+                                "Reconnected to a different participant, dropping"
+                            );
+                            return;
+                        }
                         let mut participants = participants.lock().await;
+                        if !participants.contains_key(&pid) && resume {
+                            info!(?cid, ?pid, "Participant to resume doesn't exist, dropping");
+                            return;
+                        }
                         if !participants.contains_key(&pid) {
                             debug!(?cid, "New participant connected via a channel");
+                            // the connecting side reconnects, the listening side waits for it
+                            let resume_timeout = if encrypted {
+                                resume_timeout
+                            } else {
+                                Duration::ZERO
+                            };
+                            let reconnect_addr = (!send_handshake && !resume_timeout.is_zero())
+                                .then(|| con_addr.clone());
                             let (
                                 bparticipant,
                                 a2b_open_stream_s,
@@ -422,7 +547,15 @@ impl Scheduler {
                                 s2b_create_channel_s,
                                 s2b_shutdown_bparticipant_s,
                                 b2a_bandwidth_stats_r,
-                            ) = BParticipant::new(local_pid, pid, sid, Arc::clone(&metrics));
+                            ) = BParticipant::new(
+                                local_pid,
+                                pid,
+                                sid,
+                                Arc::clone(&metrics),
+                                reconnect_addr,
+                                resume_timeout,
+                                network_conditions,
+                            );
 
                             let participant = Participant::new(
                                 local_pid,
@@ -446,7 +579,10 @@ impl Scheduler {
                             let p = pid;
                             tokio::spawn(
                                 bparticipant
-                                    .run(participant_channels.b2s_prio_statistic_s)
+                                    .run(
+                                        participant_channels.b2s_prio_statistic_s,
+                                        participant_channels.b2s_reconnect_s,
+                                    )
                                     .instrument(info_span!("remote", ?p)),
                             );
                             //create a new channel within BParticipant and wait for it to run
@@ -493,6 +629,22 @@ impl Scheduler {
                                 }
                                 return;
                             }
+                            if resume {
+                                debug!(?cid, ?pid, "Resuming participant with a new channel");
+                                let (b2s_create_channel_done_s, b2s_create_channel_done_r) =
+                                    oneshot::channel();
+                                if pi
+                                    .s2b_create_channel_s
+                                    .send((cid, sid, protocol, con_addr, b2s_create_channel_done_s))
+                                    .is_err()
+                                {
+                                    debug!(?cid, ?pid, "Participant is already shutting down");
+                                    return;
+                                }
+                                drop(participants);
+                                let _ = b2s_create_channel_done_r.await;
+                                return;
+                            }
                             error!(
                                 ?cid,
                                 "Ufff i cant answer the pid_oneshot. as i need to create the SAME \
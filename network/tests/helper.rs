@@ -12,7 +12,9 @@ use std::{
 use tokio::runtime::Runtime;
 use tracing::*;
 use tracing_subscriber::EnvFilter;
-use veloren_network::{ConnectAddr, ListenAddr, Network, Participant, Pid, Promises, Stream};
+use veloren_network::{
+    ConnectAddr, ListenAddr, Network, Participant, Pid, Promises, StaticKey, Stream,
+};
 
 // sleep time when only internal rust calculations are done
 pub const SLEEP_INTERNAL: Duration = Duration::from_millis(3000);
@@ -90,6 +92,19 @@ pub fn tcp() -> (ListenAddr, ConnectAddr) {
     )
 }
 
+#[allow(dead_code)]
+pub fn tcp_encrypted() -> (ListenAddr, ConnectAddr) {
+    let (static_key, _) = StaticKey::generate();
+    let public_key = static_key.public_key();
+    match tcp() {
+        (ListenAddr::Tcp(listen), ConnectAddr::Tcp(addr)) => (
+            ListenAddr::TcpEncrypted(listen, Arc::new(static_key)),
+            ConnectAddr::TcpEncrypted(addr, public_key),
+        ),
+        _ => unreachable!(),
+    }
+}
+
 lazy_static! {
     static ref UDP_PORTS: AtomicU16 = AtomicU16::new(5000);
 }
@@ -5,7 +5,11 @@ use bindings::{
     veloren::plugin::{
         actions,
         information::Entity,
-        types::{self, GameMode, Health, JoinResult, PlayerId, Uid},
+        storage,
+        types::{
+            self, BlockDiff, ChatKind, ChatResult, EventKind, GameMode, Health, ItemStack,
+            JoinResult, PlayerId, TimerId, Uid,
+        },
     },
 };
 use core::sync::atomic::{AtomicBool, Ordering};
@@ -18,8 +22,13 @@ static COUNTER: AtomicBool = AtomicBool::new(false);
 impl Guest for Component {
     fn load(mode: GameMode) {
         actions::register_command("test");
+        actions::subscribe(EventKind::Death);
+        actions::subscribe(EventKind::Chat);
         match mode {
-            GameMode::Server => println!("Hello, server!"),
+            GameMode::Server => {
+                println!("Hello, server!");
+                let _ = actions::schedule_timer(600.0, true);
+            },
             GameMode::Client => println!("Hello, client!"),
             GameMode::SinglePlayer => println!("Hello, singleplayer!"),
         }
@@ -43,18 +52,53 @@ impl bindings::exports::veloren::plugin::server_events::Guest for Component {
         let entity: Result<Entity, types::Error> = Entity::find_entity(player);
         let health = entity
             .as_ref()
-            .map_err(|err| *err)
+            .map_err(|err| err.clone())
             .and_then(|entity| entity.health())
             .unwrap_or(Health {
                 base_max: 0.0,
                 maximum: 0.0,
                 current: 0.0,
             });
+        // count the invocations across server restarts
+        let calls = storage::get("calls")
+            .ok()
+            .flatten()
+            .and_then(|bytes| bytes.try_into().ok())
+            .map_or(0, u64::from_le_bytes)
+            + 1;
+        let _ = storage::set("calls", &calls.to_le_bytes());
         Ok(vec![format!(
-            "Player id {player:?} name {} with {health:?} command {command} args {command_args:?}",
+            "Player id {player:?} name {} with {health:?} command {command} args {command_args:?} \
+             (call {calls})",
             entity.and_then(|e| e.name()).unwrap_or_default(),
         )])
     }
+
+    fn death(entity: Uid, killer: Option<Uid>) {
+        println!("Entity {entity} was killed by {killer:?}");
+    }
+
+    fn health_change(_entity: Uid, _amount: f32, _by: Option<Uid>) {}
+
+    fn chat(_sender: Uid, _kind: ChatKind, message: String) -> ChatResult {
+        if message.contains("hello") {
+            ChatResult::Modify(message.replace("hello", "hello plugin"))
+        } else {
+            ChatResult::Allow
+        }
+    }
+
+    fn trade(_parties: (Uid, Uid), _first_offer: Vec<ItemStack>, _second_offer: Vec<ItemStack>) {}
+
+    fn block_change(_changes: Vec<BlockDiff>) {}
+
+    fn logout(_player: Uid) {}
+
+    fn tick(_dt: f64) {}
+
+    fn timer(id: TimerId) {
+        println!("Timer {id} expired, still running");
+    }
 }
 
 bindings::export!(Component with_types_in bindings);
@@ -118,6 +118,8 @@ impl NpcCtx<'_, '_> {
 #[derive(SystemData)]
 pub struct NpcSystemData<'a> {
     pub positions: ReadStorage<'a, comp::Pos>,
+    pub alignments: ReadStorage<'a, comp::Alignment>,
+    pub healths: ReadStorage<'a, comp::Health>,
     pub id_maps: Read<'a, IdMaps>,
     pub server_constants: ReadExpect<'a, ServerConstants>,
     pub weather_grid: ReadExpect<'a, WeatherGrid>,
@@ -22,6 +22,7 @@ pub struct Death {
 pub enum TrackedPopulation {
     Adventurers,
     Merchants,
+    Caravans,
     Guards,
     Captains,
     OtherTownNpcs,
@@ -64,6 +65,7 @@ impl TrackedPopulation {
                     common::rtsim::Profession::Pirate(true) => Self::PirateCaptains,
                     common::rtsim::Profession::Cultist => Self::Cultists,
                     common::rtsim::Profession::Captain => Self::Captains,
+                    common::rtsim::Profession::Caravan => Self::Caravans,
                 },
                 None => Self::OtherTownNpcs,
             },
@@ -7,6 +7,7 @@ pub mod quest;
 pub mod report;
 pub mod sentiment;
 pub mod site;
+pub mod trade;
 
 pub use self::{
     faction::{Faction, FactionId, Factions},
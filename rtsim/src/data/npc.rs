@@ -1,6 +1,6 @@
 use crate::{
     ai::Action,
-    data::{Reports, Sentiments, quest::Quest},
+    data::{Reports, Sentiments, quest::Quest, trade::Cargo},
     generate::name,
 };
 pub use common::rtsim::{NpcId, Profession};
@@ -16,6 +16,7 @@ use common::{
     },
     store::Id,
     terrain::CoordinateConversions,
+    trade::Good,
     util::Dir,
 };
 use hashbrown::{HashMap, HashSet};
@@ -68,6 +69,9 @@ pub struct Controller {
     pub look_dir: Option<Dir>,
     pub job: Option<Job>,
     pub quests_to_create: Vec<(QuestId, Quest)>,
+    /// Goods that were loaded (negative) or unloaded (positive) at sites by
+    /// this NPC.
+    pub goods_traded: Vec<(SiteId, Good, f32)>,
 
     /// Each pilot gets assigned to a route, and as the server ticks onward, the
     /// current leg of each pilot's assigned route increments. This gets
@@ -158,6 +162,24 @@ impl Controller {
         }
     }
 
+    /// Take on cargo at the site it's being carried from.
+    pub fn load_cargo(&mut self, cargo: Cargo) {
+        for (good, amount) in &cargo.goods {
+            self.goods_traded.push((cargo.from, *good, -*amount));
+        }
+        self.job = Some(Job::Caravan(cargo));
+    }
+
+    /// Hand over the cargo at the site it was being carried to.
+    pub fn unload_cargo(&mut self) {
+        if let Some(Job::Caravan(cargo)) = &self.job {
+            for (good, amount) in &cargo.goods {
+                self.goods_traded.push((cargo.to, *good, *amount));
+            }
+            self.job = None;
+        }
+    }
+
     pub fn send_msg(&mut self, to: impl Into<Actor>, msg: NpcMsg) {
         self.actions.push(NpcAction::Msg { to: to.into(), msg });
     }
@@ -336,6 +358,8 @@ pub enum Job {
     Hired(Actor, Time),
     /// NPC is helping to perform a quest
     Quest(QuestId),
+    /// NPC is carrying goods from one site to another.
+    Caravan(Cargo),
 }
 
 impl Clone for Npc {
@@ -1,3 +1,4 @@
+use super::ReportId;
 use common::{
     resources::Time,
     rtsim::{Actor, ItemResource, QuestId, SiteId},
@@ -7,6 +8,9 @@ use itertools::Either;
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicU8, AtomicU64, Ordering};
 
+/// How long (in seconds) resolved quests are remembered for.
+pub const RESOLVED_QUEST_LIFETIME: f64 = 60.0 * 30.0;
+
 /// The easiest way to think about quests is as a virtual Jira board (or,
 /// perhaps, a community jobs noticeboard).
 ///
@@ -15,9 +19,10 @@ use std::sync::atomic::{AtomicU8, AtomicU64, Ordering};
 /// the world. They are informal contracts, and it is up to the NPCs and players
 /// that interact with them to drive them forward.
 ///
-/// Quests that are resolved or that have been active for some time without
-/// activity may be garbage-collected, although the exact mechanism for this has
-/// not yet been defined.
+/// Resolved quests are garbage-collected after [`RESOLVED_QUEST_LIFETIME`], so
+/// the actors involved can still learn how they ended. Quests that have been
+/// active for some time without activity may be garbage-collected in the
+/// future, although the exact mechanism for this has not yet been defined.
 #[derive(Default, Serialize, Deserialize)]
 pub struct Quests {
     /// Because quests can be created in a multi-threaded context, we use an
@@ -66,6 +71,16 @@ impl Quests {
 
     pub fn get(&self, id: QuestId) -> Option<&Quest> { self.quests.get(&id) }
 
+    pub fn get_mut(&mut self, id: QuestId) -> Option<&mut Quest> { self.quests.get_mut(&id) }
+
+    /// Quests that haven't been resolved yet
+    pub fn unresolved(&self) -> impl Iterator<Item = (QuestId, &Quest)> + '_ {
+        self.quests
+            .iter()
+            .filter(|(_, q)| q.resolution().is_none())
+            .map(|(id, q)| (*id, q))
+    }
+
     pub fn related_to(&self, actor: impl Into<Actor>) -> impl Iterator<Item = QuestId> + '_ {
         match self.related_quests.get(&actor.into()) {
             Some(quests) => Either::Left(
@@ -78,6 +93,16 @@ impl Quests {
         }
     }
 
+    /// Like [`Quests::related_to`], but including quests that have already been
+    /// resolved.
+    pub fn all_related_to(&self, actor: impl Into<Actor>) -> impl Iterator<Item = QuestId> + '_ {
+        self.related_quests
+            .get(&actor.into())
+            .into_iter()
+            .flatten()
+            .copied()
+    }
+
     /// Find all of the actors that are related to another actor via a quest
     pub fn related_actors(
         &self,
@@ -100,6 +125,32 @@ impl Quests {
         related.into_iter()
     }
 
+    /// Forget quests that were resolved more than [`RESOLVED_QUEST_LIFETIME`]
+    /// ago.
+    pub fn cleanup(&mut self, now: Time) {
+        let related_quests = &mut self.related_quests;
+        self.quests.retain(|id, quest| {
+            if quest.resolution().is_none() {
+                return true;
+            }
+            // Resolution happens through a shared reference, so the time is only noted
+            // down here
+            let resolved_at = *quest.resolved_at.get_or_insert(now);
+            if now.0 - resolved_at.0 < RESOLVED_QUEST_LIFETIME {
+                return true;
+            }
+            quest.for_related_actors(|actor| {
+                if let Some(quests) = related_quests.get_mut(&actor) {
+                    quests.remove(id);
+                    if quests.is_empty() {
+                        related_quests.remove(&actor);
+                    }
+                }
+            });
+            false
+        });
+    }
+
     pub(super) fn prepare(&mut self) {
         // Populate quest lookup table
         for (quest_id, quest) in &self.quests {
@@ -138,6 +189,20 @@ pub struct Quest {
 
     outcome: QuestOutcome,
 
+    /// Progress towards the objective of kinds of quests that can't be checked
+    /// by the arbiter, see [`QuestProgress`].
+    #[serde(default)]
+    progress: QuestProgress,
+
+    /// Whether the quester gave up on the quest. The arbiter resolves
+    /// abandoned quests as failed.
+    #[serde(default)]
+    abandoned: bool,
+
+    /// Roughly when the quest was resolved, see [`Quests::cleanup`].
+    #[serde(default)]
+    resolved_at: Option<Time>,
+
     /// The only aspect of the quest that mutates over time. Resolving quests is
     /// monotonic: once resolved, they cannot be unresolved (to avoid the
     /// deposit being paid back twice, for example).
@@ -150,26 +215,67 @@ impl Quest {
     ///
     /// The escortee is considered to be the quest arbiter.
     pub fn escort(escortee: Actor, escorter: Actor, to: SiteId) -> Self {
-        Self {
-            arbiter: escortee,
-            kind: QuestKind::Escort {
-                escortee,
-                escorter,
-                to,
-            },
-            timeout: None,
-            outcome: QuestOutcome::default(),
-            res: QuestRes(AtomicU8::new(0)),
-        }
+        Self::new(escortee, QuestKind::Escort {
+            escortee,
+            escorter,
+            to,
+        })
     }
 
     /// Create a new slay quest that requires the slayer to kill a target.
     pub fn slay(arbiter: Actor, target: Actor, slayer: Actor) -> Self {
+        Self::new(arbiter, QuestKind::Slay { target, slayer })
+    }
+
+    /// Create a new delivery quest that requires a courier to bring an item to
+    /// a recipient, usually in another site.
+    ///
+    /// The recipient is considered to be the quest arbiter, so whoever sends
+    /// the item deposits the payment with the recipient.
+    pub fn deliver(recipient: Actor, courier: Actor, item: ItemResource, amount: u32) -> Self {
+        Self::new(recipient, QuestKind::Deliver {
+            recipient,
+            courier,
+            item,
+            amount,
+        })
+    }
+
+    /// Create a new gather quest that requires the gatherer to bring items
+    /// found in nature to the arbiter.
+    pub fn gather(arbiter: Actor, gatherer: Actor, item: ItemResource, amount: u32) -> Self {
+        Self::new(arbiter, QuestKind::Gather {
+            gatherer,
+            item,
+            amount,
+        })
+    }
+
+    /// Create a new quest that requires the clearer to defeat the hostile
+    /// creatures of a site.
+    pub fn clear_site(arbiter: Actor, site: SiteId, clearer: Actor) -> Self {
+        Self::new(arbiter, QuestKind::ClearSite { site, clearer })
+    }
+
+    /// Create a new bounty quest that requires the hunter to kill the culprit
+    /// of a crime the arbiter knows about.
+    pub fn bounty(arbiter: Actor, target: Actor, hunter: Actor, report: ReportId) -> Self {
+        Self::new(arbiter, QuestKind::Bounty {
+            target,
+            hunter,
+            report,
+        })
+    }
+
+    fn new(arbiter: Actor, kind: QuestKind) -> Self {
         Self {
             arbiter,
-            kind: QuestKind::Slay { target, slayer },
+            kind,
             timeout: None,
             outcome: QuestOutcome::default(),
+            progress: QuestProgress::default(),
+            abandoned: false,
+            resolved_at: None,
             res: QuestRes(AtomicU8::new(0)),
         }
     }
@@ -227,6 +333,34 @@ impl Quest {
 
     pub fn resolution(&self) -> Option<bool> { self.res.get() }
 
+    pub fn progress(&self) -> QuestProgress { self.progress }
+
+    pub fn deposit(&self) -> Option<(ItemResource, f32)> { self.outcome.deposit }
+
+    /// Give up on the quest. This doesn't resolve it, only the arbiter can do
+    /// that.
+    pub fn abandon(&mut self) { self.abandoned = true; }
+
+    pub fn is_abandoned(&self) -> bool { self.abandoned }
+
+    /// The actor that has to carry out the quest.
+    pub fn quester(&self) -> Actor {
+        match &self.kind {
+            QuestKind::Escort { escorter, .. } => *escorter,
+            QuestKind::Slay { slayer, .. } => *slayer,
+            QuestKind::Deliver { courier, .. } => *courier,
+            QuestKind::Gather { gatherer, .. } => *gatherer,
+            QuestKind::ClearSite { clearer, .. } => *clearer,
+            QuestKind::Bounty { hunter, .. } => *hunter,
+        }
+    }
+
+    /// Progress is monotonic, so this does nothing if the quest already
+    /// progressed further.
+    pub fn advance(&mut self, progress: QuestProgress) {
+        self.progress = self.progress.max(progress);
+    }
+
     pub fn get_related_actors(&self) -> HashSet<Actor> {
         let mut related = HashSet::default();
         self.for_related_actors(|actor| {
@@ -250,6 +384,18 @@ impl Quest {
                 f(*target);
                 f(*slayer);
             },
+            QuestKind::Deliver {
+                recipient, courier, ..
+            } => {
+                f(*recipient);
+                f(*courier);
+            },
+            QuestKind::Gather { gatherer, .. } => f(*gatherer),
+            QuestKind::ClearSite { clearer, .. } => f(*clearer),
+            QuestKind::Bounty { target, hunter, .. } => {
+                f(*target);
+                f(*hunter);
+            },
         }
     }
 }
@@ -308,4 +454,43 @@ pub enum QuestKind {
         target: Actor,
         slayer: Actor,
     },
+    Deliver {
+        recipient: Actor,
+        courier: Actor,
+        item: ItemResource,
+        amount: u32,
+    },
+    Gather {
+        gatherer: Actor,
+        item: ItemResource,
+        amount: u32,
+    },
+    /// Completed once the clearer saw the hostile creatures of the site and
+    /// defeated all of them, see [`QuestProgress`]
+    ClearSite {
+        site: SiteId,
+        clearer: Actor,
+    },
+    /// Completed once the hunter killed the target. The target is the killer
+    /// or thief of a [`crate::data::ReportKind::Death`] or
+    /// [`crate::data::ReportKind::Theft`] report.
+    Bounty {
+        target: Actor,
+        hunter: Actor,
+        report: ReportId,
+    },
+}
+
+/// Whether a quest's objective was achieved, for quests whose objective can't
+/// be checked when they're resolved: a site that was cleared gets populated
+/// again when it's loaded the next time, and a dead player respawns.
+///
+/// This is tracked by [`crate::rule::quest::QuestEvents`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum QuestProgress {
+    #[default]
+    NotStarted,
+    /// The quester found the hostile creatures of the site they have to clear
+    Underway,
+    Achieved,
 }
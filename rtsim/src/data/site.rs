@@ -1,15 +1,16 @@
-use crate::data::{ReportId, Reports};
+use crate::data::{ReportId, Reports, trade::Market};
 pub use common::rtsim::SiteId;
 use common::{
     rtsim::{FactionId, NpcId},
     store::Id,
+    trade::SitePrices,
 };
 use hashbrown::{HashMap, HashSet};
 use serde::{Deserialize, Serialize};
 use slotmap::DenseSlotMap;
 use std::ops::{Deref, DerefMut};
 use vek::*;
-use world::site::Site as WorldSite;
+use world::{IndexRef, site::Site as WorldSite};
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Site {
@@ -22,6 +23,10 @@ pub struct Site {
     /// being on a noticeboard or something).
     pub known_reports: HashSet<ReportId>,
 
+    /// The goods that caravans have brought to or taken from the site.
+    #[serde(default)]
+    pub market: Market,
+
     /// How many chunks this site is loaded in.
     #[serde(skip)]
     pub count_loaded_chunks: usize,
@@ -68,6 +73,14 @@ impl Site {
     }
 
     pub fn is_loaded(&self) -> bool { self.count_loaded_chunks > 0 }
+
+    /// The prices that merchants at this site trade at, taking into account
+    /// the goods that caravans have brought to or taken from the site.
+    pub fn prices(&self, index: IndexRef) -> Option<SitePrices> {
+        let mut prices = index.get_site_prices(self.world_site?.id())?;
+        self.market.adjust_prices(&mut prices);
+        Some(prices)
+    }
 }
 
 #[derive(Clone, Default, Serialize, Deserialize)]
@@ -0,0 +1,80 @@
+use common::{
+    rtsim::SiteId,
+    trade::{Good, SitePrices},
+};
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+
+/// The goods that caravans carry between sites. Abstract goods like territory
+/// or road security can't be loaded onto a cart.
+pub const TRADE_GOODS: [Good; 9] = [
+    Good::Flour,
+    Good::Meat,
+    Good::Food,
+    Good::Wood,
+    Good::Stone,
+    Good::Tools,
+    Good::Armor,
+    Good::Ingredients,
+    Good::Potions,
+];
+
+/// How many units of a good must pile up at a site for its price to halve (or,
+/// when taken away, to double).
+const PRICE_HALVING_SURPLUS: f32 = 100.0;
+/// Prices never drift further than this factor from what the site's economy
+/// would ask for on its own.
+const MAX_PRICE_FACTOR: f32 = 2.0;
+/// The time (in seconds) that it takes for the site's own production and
+/// consumption to even out half of a trade surplus or shortage.
+const SURPLUS_HALF_LIFE: f32 = 60.0 * 60.0 * 2.0;
+
+/// The goods that rtsim has moved in and out of a site, on top of what the
+/// site's economy was computed to produce and consume during worldgen.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Market {
+    /// How many units of each good the site has gained (or, when negative,
+    /// lost) through trade.
+    pub surplus: HashMap<Good, f32>,
+}
+
+impl Market {
+    pub fn deposit(&mut self, good: Good, amount: f32) {
+        *self.surplus.entry(good).or_default() += amount;
+    }
+
+    pub fn withdraw(&mut self, good: Good, amount: f32) { self.deposit(good, -amount); }
+
+    /// The factor that the price of a good is multiplied with: goods that pile
+    /// up become cheaper, goods that are taken away become dearer.
+    pub fn price_factor(&self, good: Good) -> f32 {
+        let surplus = self.surplus.get(&good).copied().unwrap_or(0.0);
+        0.5f32
+            .powf(surplus / PRICE_HALVING_SURPLUS)
+            .clamp(MAX_PRICE_FACTOR.recip(), MAX_PRICE_FACTOR)
+    }
+
+    pub fn adjust_prices(&self, prices: &mut SitePrices) {
+        for (good, price) in &mut prices.values {
+            *price *= self.price_factor(*good);
+        }
+    }
+
+    /// Let the site's own production and consumption even out the surplus
+    /// over time.
+    pub fn settle(&mut self, dt: f32) {
+        let decay = 0.5f32.powf(dt / SURPLUS_HALF_LIFE);
+        self.surplus.retain(|_, surplus| {
+            *surplus *= decay;
+            surplus.abs() > 0.01
+        });
+    }
+}
+
+/// The goods that a caravan is carrying from one site to another.
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub struct Cargo {
+    pub from: SiteId,
+    pub to: SiteId,
+    pub goods: Vec<(Good, f32)>,
+}
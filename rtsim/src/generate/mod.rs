@@ -43,6 +43,7 @@ pub fn wanted_population(world: &World, index: IndexRef) -> Population {
         pop.add(TrackedPopulation::OtherTownNpcs, others);
 
         pop.add(TrackedPopulation::Merchants, (town_pop / 6) + 1);
+        pop.add(TrackedPopulation::Caravans, town_pop / 8);
     }
 
     let pirate_hideouts = sites
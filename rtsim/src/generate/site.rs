@@ -78,6 +78,7 @@ impl Site {
             count_loaded_chunks: 0,
             population: Default::default(),
             known_reports: Default::default(),
+            market: Default::default(),
             nearby_sites_by_size: Vec::new(),
         }
     }
@@ -202,6 +202,8 @@ impl RtState {
         self.start_rule::<rule::architect::Architect>();
         self.start_rule::<rule::replenish_resources::ReplenishResources>();
         self.start_rule::<rule::report::ReportEvents>();
+        self.start_rule::<rule::quest::QuestEvents>();
+        self.start_rule::<rule::trade::Trade>();
         self.start_rule::<rule::sync_npcs::SyncNpcs>();
         self.start_rule::<rule::simulate_npcs::SimulateNpcs>();
         self.start_rule::<rule::npc_ai::NpcAi>();
@@ -8,7 +8,7 @@ use rand::{
     RngExt, rng,
     seq::{IndexedRandom, IteratorRandom},
 };
-use world::{CONFIG, IndexRef, World, sim::SimChunk, site::SiteKind};
+use world::{Config, IndexRef, World, sim::SimChunk, site::SiteKind};
 
 use crate::{
     Data, EventCtx, OnTick, RtState,
@@ -81,6 +81,10 @@ fn architect_tick(ctx: EventCtx<Architect, OnTick>) {
                     Body::Humanoid(comp::humanoid::Body::random()),
                     Role::Civilised(Some(Profession::Merchant)),
                 ),
+                TrackedPopulation::Caravans => (
+                    Body::Humanoid(comp::humanoid::Body::random()),
+                    Role::Civilised(Some(Profession::Caravan)),
+                ),
                 TrackedPopulation::Guards => (
                     Body::Humanoid(comp::humanoid::Body::random()),
                     Role::Civilised(Some(Profession::Guard)),
@@ -265,9 +269,12 @@ fn randomize_body(body: Body, rng: &mut impl RngExt) -> Body {
 fn role_personality(rng: &mut impl RngExt, role: &Role) -> Personality {
     match role {
         Role::Civilised(profession) => match profession {
-            Some(Profession::Guard | Profession::Merchant | Profession::Captain) => {
-                Personality::random_good(rng)
-            },
+            Some(
+                Profession::Guard
+                | Profession::Merchant
+                | Profession::Captain
+                | Profession::Caravan,
+            ) => Personality::random_good(rng),
             Some(Profession::Cultist | Profession::Pirate(_)) => Personality::random_evil(rng),
             None
             | Some(
@@ -520,25 +527,25 @@ fn spawn_npc(data: &mut Data, world: &World, index: IndexRef, death: &Death) ->
                 }
             },
             Role::Monster => {
-                let chunk_filter: fn(&SimChunk) -> bool = match body {
+                let chunk_filter: fn(&Config, &SimChunk) -> bool = match body {
                     Body::BipedLarge(body) => match body.species {
                         comp::biped_large::Species::Tursus
                         | comp::biped_large::Species::Gigasfrost
                         | comp::biped_large::Species::Wendigo => {
-                            |chunk| !chunk.is_underwater() && chunk.temp < CONFIG.snow_temp
+                            |config, chunk| !chunk.is_underwater() && chunk.temp < config.snow_temp
                         },
-                        comp::biped_large::Species::Gigasfire => |chunk| {
+                        comp::biped_large::Species::Gigasfire => |config, chunk| {
                             !chunk.is_underwater()
-                                && chunk.temp > CONFIG.desert_temp
-                                && chunk.humidity < CONFIG.desert_hum
+                                && chunk.temp > config.desert_temp
+                                && chunk.humidity < config.desert_hum
                         },
                         comp::biped_large::Species::Mountaintroll => {
-                            |chunk| !chunk.is_underwater() && chunk.alt > 500.0
+                            |_, chunk| !chunk.is_underwater() && chunk.alt > 500.0
                         },
-                        comp::biped_large::Species::Swamptroll => {
-                            |chunk| !chunk.is_underwater() && chunk.humidity > CONFIG.jungle_hum
+                        comp::biped_large::Species::Swamptroll => |config, chunk| {
+                            !chunk.is_underwater() && chunk.humidity > config.jungle_hum
                         },
-                        _ => |chunk| !chunk.is_underwater(),
+                        _ => |_, chunk| !chunk.is_underwater(),
                     },
                     Body::Arthropod(_)
                     | Body::Humanoid(_)
@@ -547,12 +554,14 @@ fn spawn_npc(data: &mut Data, world: &World, index: IndexRef, death: &Death) ->
                     | Body::QuadrupedMedium(_)
                     | Body::Golem(_)
                     | Body::Theropod(_)
-                    | Body::QuadrupedLow(_) => |chunk| !chunk.is_underwater(),
-                    Body::Dragon(_) | Body::BirdLarge(_) | Body::BirdMedium(_) => |_| true,
+                    | Body::QuadrupedLow(_) => |_, chunk| !chunk.is_underwater(),
+                    Body::Dragon(_) | Body::BirdLarge(_) | Body::BirdMedium(_) => |_, _| true,
                     Body::Crustacean(_) | Body::FishSmall(_) | Body::FishMedium(_) => {
-                        |chunk| chunk.is_underwater()
+                        |_, chunk| chunk.is_underwater()
+                    },
+                    Body::Object(_) | Body::Ship(_) | Body::Item(_) | Body::Plugin(_) => {
+                        |_, _| true
                     },
-                    Body::Object(_) | Body::Ship(_) | Body::Item(_) | Body::Plugin(_) => |_| true,
                 };
 
                 for _ in 0..RESPAWN_ATTEMPTS {
@@ -565,7 +574,7 @@ fn spawn_npc(data: &mut Data, world: &World, index: IndexRef, death: &Death) ->
                     // TODO: If we had access to `ChunkStates` here we could make sure
                     // these aren't getting respawned in loaded chunks.
                     if let Some(chunk) = world.sim().get(cpos)
-                        && chunk_filter(chunk)
+                        && chunk_filter(&world.sim().config, chunk)
                     {
                         let wpos = cpos.cpos_to_wpos_center();
                         let wpos = wpos.as_().with_z(world.sim().get_surface_alt_approx(wpos));
@@ -7,6 +7,7 @@ const NPC_SENTIMENT_TICK_SKIP: u64 = 30;
 const NPC_CLEANUP_TICK_SKIP: u64 = 100;
 const FACTION_CLEANUP_TICK_SKIP: u64 = 30;
 const SITE_CLEANUP_TICK_SKIP: u64 = 30;
+const QUEST_CLEANUP_TICK_SKIP: u64 = 30;
 
 /// A rule that cleans up data structures in rtsim: removing old reports,
 /// irrelevant sentiments, etc.
@@ -73,6 +74,11 @@ impl Rule for CleanUp {
 
             // Clean up old reports
             data.reports.cleanup(data.time_of_day);
+
+            // Clean up resolved quests
+            if ctx.event.tick.is_multiple_of(QUEST_CLEANUP_TICK_SKIP) {
+                data.quests.cleanup(ctx.event.time);
+            }
         });
 
         Ok(Self)
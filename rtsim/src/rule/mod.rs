@@ -2,10 +2,14 @@ pub mod architect;
 pub mod cleanup;
 pub mod migrate;
 pub mod npc_ai;
+pub mod quest;
 pub mod replenish_resources;
 pub mod report;
 pub mod simulate_npcs;
 pub mod sync_npcs;
+pub mod trade;
+
+#[cfg(test)] mod test_helpers;
 
 use super::RtState;
 use std::fmt;
@@ -0,0 +1,95 @@
+use super::*;
+use crate::data::trade::{Cargo, TRADE_GOODS};
+
+/// Caravans won't travel further than this (in blocks) to sell their goods.
+const MAX_TRADE_DISTANCE: f32 = 5000.0;
+/// How many units of each good a caravan carries.
+const CARGO_PER_GOOD: f32 = 20.0;
+/// How many different goods a caravan carries at once.
+const MAX_CARGO_GOODS: usize = 2;
+/// Goods are only worth carrying if they sell for at least this many times
+/// their price at home.
+const MIN_PROFIT: f32 = 1.25;
+
+/// Work out the most profitable cargo to carry from a site to another site
+/// nearby, if there's any worth carrying.
+fn plan_cargo(ctx: &NpcCtx, from: SiteId) -> Option<Cargo> {
+    let site = ctx.data.sites.get(from)?;
+    let prices = site.prices(ctx.index)?;
+
+    ctx.data
+        .sites
+        .iter()
+        .filter(|(to, to_site)| {
+            *to != from && to_site.wpos.as_::<f32>().distance(site.wpos.as_()) < MAX_TRADE_DISTANCE
+        })
+        .filter_map(|(to, to_site)| {
+            let to_prices = to_site.prices(ctx.index)?;
+            let mut goods = TRADE_GOODS
+                .iter()
+                .filter_map(|good| {
+                    let price = *prices.values.get(good)?;
+                    let profit = *to_prices.values.get(good)? / price.max(f32::EPSILON);
+                    (profit >= MIN_PROFIT).then_some((*good, profit))
+                })
+                .collect::<Vec<_>>();
+            goods.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+            goods.truncate(MAX_CARGO_GOODS);
+            let profit = goods.iter().map(|(_, profit)| profit).sum::<f32>();
+            Some((to, goods, profit))
+        })
+        .filter(|(_, goods, _)| !goods.is_empty())
+        .max_by(|(_, _, a), (_, _, b)| a.total_cmp(b))
+        .map(|(to, goods, _)| Cargo {
+            from,
+            to,
+            goods: goods
+                .into_iter()
+                .map(|(good, _)| (good, CARGO_PER_GOOD))
+                .collect(),
+        })
+}
+
+/// Wait around at a site until there's a cargo worth carrying to another site.
+pub fn caravan() -> impl Action<DefaultState> {
+    choose(|ctx: &mut NpcCtx, _, consider| {
+        if let Some(site) = ctx.npc.current_site
+            // Set off with a new load about every half an hour
+            && ctx.chance(1.0 / 1800.0)
+            && let Some(cargo) = plan_cargo(ctx, site)
+        {
+            let site_name = util::site_name(ctx, cargo.to).unwrap_or_default();
+            consider.important(just(move |ctx, _| {
+                ctx.controller.say(
+                    None,
+                    Content::localized("npc-speech-caravan-setting_off")
+                        .with_arg("site", site_name.as_str()),
+                );
+                ctx.controller.load_cargo(cargo.clone());
+            }));
+        } else if let Some(site) = ctx.npc.current_site.or(ctx.npc.home) {
+            consider.casual(villager(site));
+        } else {
+            consider.casual(idle());
+        }
+    })
+    .debug(|| "caravan")
+}
+
+/// Carry cargo along the roads to the site it's destined for and hand it over.
+pub fn deliver(cargo: Cargo) -> impl Action<DefaultState> {
+    let to = cargo.to;
+    travel_to_site(to, 0.5)
+        .then(just(move |ctx, _| {
+            let site_name = util::site_name(ctx, cargo.from).unwrap_or_default();
+            ctx.controller.say(
+                None,
+                Content::localized("npc-speech-caravan-arrived")
+                    .with_arg("site", site_name.as_str()),
+            );
+            ctx.controller.unload_cargo();
+        }))
+        // Robbed caravans have nothing left to deliver
+        .stop_if(|ctx: &mut NpcCtx| !matches!(ctx.npc.job, Some(Job::Caravan(_))))
+        .debug(move || format!("deliver cargo to {to:?}"))
+}
@@ -16,6 +16,19 @@ pub fn general<S: State>(tgt: Actor, session: DialogueSession) -> impl Action<S>
                         .boxed(),
                 ));
             },
+            Some(Job::Caravan(cargo)) => {
+                let to_name =
+                    util::site_name(ctx, cargo.to).unwrap_or_else(|| "<unknown>".to_string());
+                responses.push((
+                    Response::from(Content::localized("dialogue-question-caravan-cargo")),
+                    session
+                        .say_statement(
+                            Content::localized("npc-speech-caravan-cargo")
+                                .with_arg("site", to_name),
+                        )
+                        .boxed(),
+                ));
+            },
             Some(_) => {},
             None => {
                 responses.push((
@@ -116,20 +129,133 @@ pub fn general<S: State>(tgt: Actor, session: DialogueSession) -> impl Action<S>
                             )),
                             session
                                 .say_statement(Content::localized("npc-response-quest-slay-thanks"))
-                                .then(now(move |ctx, _| {
-                                    if let Ok(deposit) =
-                                        quest::resolve_take_deposit(ctx, quest_id, true)
-                                    {
-                                        session
-                                            .say_statement_with_gift(
-                                                Content::localized("npc-response-quest-reward"),
-                                                deposit,
-                                            )
-                                            .boxed()
-                                    } else {
-                                        finish().boxed()
-                                    }
-                                }))
+                                .then(quest::reward(session, quest_id))
+                                .boxed(),
+                        ));
+                    }
+                },
+                QuestKind::Deliver {
+                    courier,
+                    item,
+                    amount,
+                    ..
+                } if quest.arbiter == Actor::Npc(ctx.npc_id) && *courier == tgt => {
+                    // Handing over the parcel is checked by the server
+                    responses.push((
+                        Response {
+                            msg: Content::localized("dialogue-question-quest-deliver-hand_in"),
+                            given_item: Some((item.to_equivalent_item_def(), *amount)),
+                        },
+                        session
+                            .say_statement(Content::localized("npc-response-quest-deliver-thanks"))
+                            .then(quest::reward(session, quest_id))
+                            .boxed(),
+                    ));
+                },
+                QuestKind::Gather {
+                    gatherer,
+                    item,
+                    amount,
+                } if quest.arbiter == Actor::Npc(ctx.npc_id) && *gatherer == tgt => {
+                    responses.push((
+                        Response {
+                            msg: Content::localized("dialogue-question-quest-gather-hand_in")
+                                .with_arg("amount", *amount as u64)
+                                .with_arg("item", quest::item_name(*item)),
+                            given_item: Some((item.to_equivalent_item_def(), *amount)),
+                        },
+                        session
+                            .say_statement(Content::localized("npc-response-quest-gather-thanks"))
+                            .then(quest::reward(session, quest_id))
+                            .boxed(),
+                    ));
+                },
+                QuestKind::ClearSite { site, clearer }
+                    if quest.arbiter == Actor::Npc(ctx.npc_id) && *clearer == tgt =>
+                {
+                    let site_name =
+                        util::site_name(ctx, *site).unwrap_or_else(|| "<unknown>".to_string());
+                    if quest.progress() == QuestProgress::Achieved {
+                        responses.push((
+                            Response::from(
+                                Content::localized("dialogue-question-quest-clear_site-claim")
+                                    .with_arg("place", site_name),
+                            ),
+                            session
+                                .say_statement(Content::localized(
+                                    "npc-response-quest-clear_site-thanks",
+                                ))
+                                .then(quest::reward(session, quest_id))
+                                .boxed(),
+                        ));
+                    } else if let Some(site_wpos) =
+                        ctx.data.sites.get(*site).map(|site| site.wpos.as_())
+                    {
+                        responses.push((
+                            Response::from(
+                                Content::localized("dialogue-question-quest-clear_site-where")
+                                    .with_arg("place", site_name.clone()),
+                            ),
+                            session
+                                .give_marker(
+                                    Marker::at(site_wpos)
+                                        .with_id(quest_id)
+                                        .with_label(
+                                            Content::localized("hud-map-clear_site-label")
+                                                .with_arg("place", site_name.clone()),
+                                        )
+                                        .with_quest_flag(true),
+                                )
+                                .then(
+                                    session.say_statement(
+                                        Content::localized("npc-response-quest-clear_site-where")
+                                            .with_arg("place", site_name),
+                                    ),
+                                )
+                                .boxed(),
+                        ));
+                    }
+                },
+                QuestKind::Bounty { target, hunter, .. }
+                    if quest.arbiter == Actor::Npc(ctx.npc_id) && *hunter == tgt =>
+                {
+                    let target_name =
+                        util::actor_name(ctx, *target).unwrap_or_else(|| "<unknown>".to_string());
+                    if quest.progress() == QuestProgress::Achieved {
+                        responses.push((
+                            Response::from(
+                                Content::localized("dialogue-question-quest-bounty-claim")
+                                    .with_arg("name", target_name),
+                            ),
+                            session
+                                .say_statement(Content::localized(
+                                    "npc-response-quest-bounty-thanks",
+                                ))
+                                .then(quest::reward(session, quest_id))
+                                .boxed(),
+                        ));
+                    } else if let Some(target_pos) = util::locate_actor(ctx, *target) {
+                        responses.push((
+                            Response::from(
+                                Content::localized("dialogue-question-quest-bounty-where")
+                                    .with_arg("name", target_name.clone()),
+                            ),
+                            session
+                                .give_marker(
+                                    Marker::at(target_pos.xy())
+                                        .with_id(*target)
+                                        .with_label(
+                                            Content::localized("hud-map-character-label")
+                                                .with_arg("name", target_name),
+                                        )
+                                        .with_kind(MarkerKind::Character)
+                                        .with_quest_flag(true),
+                                )
+                                .then(
+                                    session.say_statement(Content::localized(
+                                        "npc-response-directions",
+                                    )),
+                                )
                                 .boxed(),
                         ));
                     }
@@ -232,6 +358,7 @@ fn about_self<S: State>(session: DialogueSession) -> impl Action<S> {
                 Profession::Cultist => "noun-role-cultist",
                 Profession::Herbalist => "noun-role-herbalist",
                 Profession::Captain => "noun-role-captain",
+                Profession::Caravan => "noun-role-caravan",
             })
             .map(|p| Content::localized("npc-info-role").with_arg("role", Content::localized(p)))
             .unwrap_or_else(|| Content::localized("noun-role-none"));
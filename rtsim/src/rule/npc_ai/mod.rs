@@ -26,6 +26,7 @@
 mod airship_ai;
 #[cfg(feature = "airship_log")]
 mod airship_logger;
+mod caravan;
 pub mod dialogue;
 pub mod movement;
 pub mod quest;
@@ -43,7 +44,7 @@ use crate::{
     data::{
         ReportKind, Sentiment, Sites,
         npc::{Brain, DialogueSession, Job, PathData, SimulationMode},
-        quest::{Quest, QuestKind},
+        quest::{Quest, QuestKind, QuestProgress},
     },
     event::OnTick,
 };
@@ -1535,10 +1536,12 @@ fn humanoid() -> impl Action<DefaultState> {
                         _ => ctx.controller.end_quest(),
                     }
                 },
+                Job::Caravan(cargo) => consider.important(caravan::deliver(cargo.clone())),
             };
         } else {
             let action = match ctx.npc.profession() {
-                Some(Profession::Adventurer(_) | Profession::Merchant) => adventure().l().l(),
+                Some(Profession::Adventurer(_) | Profession::Merchant) => adventure().l().l().l(),
+                Some(Profession::Caravan) => caravan::caravan().r().l().l(),
                 Some(Profession::Pirate(is_leader)) => pirate(is_leader).l().r(),
                 _ => {
                     if let Some(home) = ctx.npc.home {
@@ -1,5 +1,6 @@
 use super::*;
 use common::comp::{Item, item::ItemBase};
+use enum_map::EnumMap;
 
 /// Perform a deposit check, ensuring that the NPC has the given item and amount
 /// in their inventory. If they do, the provided action is performed to
@@ -67,30 +68,9 @@ pub fn resolve_take_deposit(
         .and_then(|q| q.resolve(ctx.npc_id, success))
     {
         // ...take the deposit back into our own inventory...
-        if let Some((item, amount)) = &outcome.deposit
-            && let Some(npc_entity) = ctx.system_data.id_maps.rtsim_entity(ctx.npc_id)
-            && let Some(mut inv) = ctx
-                .system_data
-                .inventories
-                .lock()
-                .unwrap()
-                .get_mut(npc_entity)
-        {
-            let item_def = item.to_equivalent_item_def();
+        if let Some((item, amount)) = &outcome.deposit {
             // Rounding down, to avoid potential precision exploits
-            let amount = amount.floor() as u32;
-
-            let mut item = Item::new_from_item_base(
-                ItemBase::Simple(item_def.clone()),
-                Vec::new(),
-                &ctx.system_data.ability_map,
-                &ctx.system_data.msm,
-            );
-            item.set_amount(amount)
-                .expect("Item cannot be stacked that far!");
-            let _ = inv.push(item);
-
-            Ok(Some((item_def, amount)))
+            Ok(create_items(ctx, *item, amount.floor() as u32))
         } else {
             Ok(None)
         }
@@ -99,6 +79,56 @@ pub fn resolve_take_deposit(
     }
 }
 
+/// Put new items into the NPC's inventory, such that they can be given away
+/// in a dialogue.
+fn create_items(ctx: &mut NpcCtx, item: ItemResource, amount: u32) -> Option<(Arc<ItemDef>, u32)> {
+    let npc_entity = ctx.system_data.id_maps.rtsim_entity(ctx.npc_id)?;
+    let mut inventories = ctx.system_data.inventories.lock().unwrap();
+    let mut inv = inventories.get_mut(npc_entity)?;
+
+    let item_def = item.to_equivalent_item_def();
+    let mut item = Item::new_from_item_base(
+        ItemBase::Simple(item_def.clone()),
+        Vec::new(),
+        &ctx.system_data.ability_map,
+        &ctx.system_data.msm,
+    );
+    item.set_amount(amount)
+        .expect("Item cannot be stacked that far!");
+    let _ = inv.push(item);
+
+    Some((item_def, amount))
+}
+
+/// The name of an item, as used in quest dialogue.
+pub fn item_name(item: ItemResource) -> Content {
+    let attr = match item {
+        ItemResource::Coin => "coin",
+        ItemResource::Parcel => "parcel",
+        ItemResource::Apple => "apple",
+        ItemResource::Carrot => "carrot",
+        ItemResource::Mushroom => "mushroom",
+        ItemResource::Flax => "flax",
+        ItemResource::Stones => "stones",
+        ItemResource::Wood => "wood",
+        ItemResource::IronOre => "iron_ore",
+    };
+    Content::localized_attr("npc-item_resource", attr)
+}
+
+/// Resolve a quest as successful and hand its deposit to the quester.
+pub fn reward<S: State>(session: DialogueSession, quest_id: QuestId) -> impl Action<S> {
+    now(move |ctx, _| {
+        if let Ok(deposit) = resolve_take_deposit(ctx, quest_id, true) {
+            session
+                .say_statement_with_gift(Content::localized("npc-response-quest-reward"), deposit)
+                .boxed()
+        } else {
+            finish().boxed()
+        }
+    })
+}
+
 /// Register and create a new quest, producing its ID.
 ///
 /// This is an action because quest creation can only happen at the end of an
@@ -275,6 +305,287 @@ pub fn quest_request<S: State>(session: DialogueSession) -> impl Action<S> {
             );
         }
 
+        // Delivery quest
+        const DELIVER_REWARD_ITEM: ItemResource = ItemResource::Coin;
+        // Only NPCs that are at home have parcels to send
+        if matches!(ctx.npc.role, Role::Civilised(_))
+            && ctx.npc.home.is_some()
+            && ctx.npc.home == ctx.npc.current_site
+            // Choose a site to send the parcel to
+            && let Some((dst_site_id, dst_site, dist)) = ctx.data
+                .sites
+                .iter()
+                .map(|(site_id, site)| (site_id, site, site.wpos.as_().distance(ctx.npc.wpos.xy())))
+                .filter(|(site_id, _, dist)| Some(*site_id) != ctx.npc.current_site && (1000.0..5_000.0).contains(dist))
+                .filter(|(_, site, _)| !site.population.is_empty())
+                .choose(&mut ctx.rng)
+            // ...and somebody living there to receive it
+            && let Some((recipient_id, recipient_name)) = dst_site
+                .population
+                .iter()
+                .filter_map(|npc_id| Some((*npc_id, ctx.data.npcs.get(*npc_id)?)))
+                .filter(|(_, npc)| matches!(npc.role, Role::Civilised(_)) && !npc.is_dead())
+                .filter_map(|(npc_id, npc)| Some((npc_id, npc.get_name()?)))
+                .choose(&mut ctx.rng)
+            // Delivery reward amount is proportional to distance
+            && let deliver_reward_amount = dist / 30.0
+            && let Some(dst_site_name) = util::site_name(ctx, dst_site_id)
+            && let time_limit = 1.0 + dist as f64 / 60.0
+            && let Some(accept_quest) = create_deposit(ctx, DELIVER_REWARD_ITEM, deliver_reward_amount, session
+                    .ask_yes_no_question(Content::localized("npc-response-quest-deliver-ask")
+                        .with_arg("name", recipient_name.clone())
+                        .with_arg("dst", dst_site_name.clone())
+                        .with_arg("coins", deliver_reward_amount as u64)
+                        .with_arg("mins", time_limit as u64)))
+        {
+            let dst_wpos = dst_site.wpos.as_();
+            quests.push(
+                accept_quest
+                    .and_then(move |yes| {
+                        now(move |ctx, _| {
+                            if yes {
+                                // The recipient pays the courier, with the coins we deposit
+                                let quest = Quest::deliver(
+                                    recipient_id.into(),
+                                    session.target,
+                                    ItemResource::Parcel,
+                                    1,
+                                )
+                                .with_deposit(DELIVER_REWARD_ITEM, deliver_reward_amount)
+                                .with_timeout(ctx.time.add_minutes(time_limit));
+                                let parcel = create_items(ctx, ItemResource::Parcel, 1);
+                                create_quest(quest.clone())
+                                    .and_then(move |quest_id| {
+                                        session.give_marker(
+                                            Marker::at(dst_wpos)
+                                                .with_id(quest_id)
+                                                .with_label(
+                                                    Content::localized("hud-map-deliver-label")
+                                                        .with_arg("name", recipient_name)
+                                                        .with_arg("place", dst_site_name),
+                                                )
+                                                .with_quest_flag(true),
+                                        )
+                                    })
+                                    .then(session.say_statement_with_gift(
+                                        Content::localized("npc-response-quest-deliver-start"),
+                                        parcel,
+                                    ))
+                                    .boxed()
+                            } else {
+                                session
+                                    .say_statement(Content::localized(
+                                        "npc-response-quest-rejected",
+                                    ))
+                                    .boxed()
+                            }
+                        })
+                    })
+                    .boxed(),
+            );
+        }
+
+        // Gathering quest
+        const GATHER_REWARD_ITEM: ItemResource = ItemResource::Coin;
+        // Resources are gathered around the site we're in
+        if let Some(site) = ctx.npc.current_site.and_then(|site| ctx.data.sites.get(site))
+            && let Some((item, availability)) = gatherable_items(ctx, site.wpos)
+                .choose(&mut ctx.rng)
+            && let amount = ctx.rng.random_range(10..=20u32)
+            // Resources that are hard to find are worth more
+            && let gather_reward_amount = amount as f32 * 4.0 / availability
+            && let time_limit = 30.0
+            && let Some(accept_quest) = create_deposit(ctx, GATHER_REWARD_ITEM, gather_reward_amount, session
+                    .ask_yes_no_question(Content::localized("npc-response-quest-gather-ask")
+                        .with_arg("amount", amount as u64)
+                        .with_arg("item", item_name(item))
+                        .with_arg("coins", gather_reward_amount as u64)
+                        .with_arg("mins", time_limit as u64)))
+        {
+            quests.push(
+                accept_quest
+                    .and_then(move |yes| {
+                        now(move |ctx, _| {
+                            if yes {
+                                let quest =
+                                    Quest::gather(ctx.npc_id.into(), session.target, item, amount)
+                                        .with_deposit(GATHER_REWARD_ITEM, gather_reward_amount)
+                                        .with_timeout(ctx.time.add_minutes(time_limit));
+                                create_quest(quest.clone())
+                                    .then(session.say_statement(Content::localized(
+                                        "npc-response-quest-gather-start",
+                                    )))
+                                    .boxed()
+                            } else {
+                                session
+                                    .say_statement(Content::localized(
+                                        "npc-response-quest-rejected",
+                                    ))
+                                    .boxed()
+                            }
+                        })
+                    })
+                    .boxed(),
+            );
+        }
+
+        // Clear hostile site quest
+        const CLEAR_SITE_REWARD_ITEM: ItemResource = ItemResource::Coin;
+        if let Some((camp_id, camp, _)) = ctx.data
+            .sites
+            .iter()
+            // Only camps of hostile creatures can be cleared
+            .filter(|(_, site)| site.world_site.is_some_and(|ws| matches!(
+                ctx.index.sites.get(ws).kind,
+                Some(SiteKind::Gnarling | SiteKind::Adlet | SiteKind::Haniwa)
+            )))
+            // Don't send several people to clear the same camp
+            .filter(|(site_id, _)| !ctx.data.quests.unresolved().any(|(_, quest)| {
+                matches!(quest.kind, QuestKind::ClearSite { site, .. } if site == *site_id)
+            }))
+            .map(|(site_id, site)| (site_id, site, site.wpos.as_().distance(ctx.npc.wpos.xy())))
+            .filter(|(_, _, dist)| *dist < 3000.0)
+            // Find the closest
+            .min_by_key(|(_, _, dist)| *dist as i64)
+            && let camp_wpos = camp.wpos.as_()
+            && let Some(camp_name) = util::site_name(ctx, camp_id)
+            && let clear_site_reward_amount = 300.0
+            && let Some(accept_quest) = create_deposit(
+                ctx,
+                CLEAR_SITE_REWARD_ITEM,
+                clear_site_reward_amount,
+                session.ask_yes_no_question(
+                    Content::localized("npc-response-quest-clear_site-ask")
+                        .with_arg("site", camp_name.clone())
+                        .with_arg("coins", clear_site_reward_amount as u64),
+                ),
+            )
+        {
+            quests.push(
+                accept_quest
+                    .and_then(move |yes| {
+                        now(move |ctx, _| {
+                            if yes {
+                                let quest =
+                                    Quest::clear_site(ctx.npc_id.into(), camp_id, session.target)
+                                        .with_deposit(
+                                            CLEAR_SITE_REWARD_ITEM,
+                                            clear_site_reward_amount,
+                                        )
+                                        .with_timeout(ctx.time.add_minutes(90.0));
+                                create_quest(quest.clone())
+                                    .and_then(move |quest_id| {
+                                        session.give_marker(
+                                            Marker::at(camp_wpos)
+                                                .with_id(quest_id)
+                                                .with_label(
+                                                    Content::localized("hud-map-clear_site-label")
+                                                        .with_arg("place", camp_name),
+                                                )
+                                                .with_quest_flag(true),
+                                        )
+                                    })
+                                    .then(session.say_statement(Content::localized(
+                                        "npc-response-quest-clear_site-start",
+                                    )))
+                                    .boxed()
+                            } else {
+                                session
+                                    .say_statement(Content::localized(
+                                        "npc-response-quest-rejected",
+                                    ))
+                                    .boxed()
+                            }
+                        })
+                    })
+                    .boxed(),
+            );
+        }
+
+        // Bounty quest
+        const BOUNTY_REWARD_ITEM: ItemResource = ItemResource::Coin;
+        if let Some(home) = ctx.npc.home
+            && let Some((report_id, target, bounty_reward_amount, crime)) = ctx
+                .known_reports
+                .iter()
+                .chain(ctx.data.sites.get(home).into_iter().flat_map(|site| site.known_reports.iter()))
+                .filter_map(|report_id| {
+                    let (target, reward, crime) = match ctx.data.reports.get(*report_id)?.kind {
+                        // Murders of people living here...
+                        ReportKind::Death { actor: Actor::Npc(victim), killer: Some(killer) }
+                            if ctx.data.npcs.get(victim).is_some_and(|victim| victim.home == Some(home)) => (killer, 300.0, "murder"),
+                        // ...and thefts from the site
+                        ReportKind::Theft { thief, site: Some(site), .. } if site == home => (thief, 100.0, "theft"),
+                        _ => return None,
+                    };
+                    Some((*report_id, target, reward, crime))
+                })
+                // Don't ask the culprit to hunt themselves down
+                .filter(|(_, target, _, _)| *target != session.target && *target != Actor::Npc(ctx.npc_id))
+                // Only put a bounty on people who are still around
+                .filter(|(_, target, _, _)| util::locate_actor(ctx, *target).is_some())
+                // Don't put several bounties on the same crime
+                .filter(|(report_id, _, _, _)| !ctx.data.quests.unresolved().any(|(_, quest)| {
+                    matches!(quest.kind, QuestKind::Bounty { report, .. } if report == *report_id)
+                }))
+                .max_by(|(_, _, a, _), (_, _, b, _)| a.total_cmp(b))
+            && let Some(target_name) = util::actor_name(ctx, target)
+            && let Some(accept_quest) = create_deposit(
+                ctx,
+                BOUNTY_REWARD_ITEM,
+                bounty_reward_amount,
+                session.ask_yes_no_question(
+                    Content::localized_attr("npc-response-quest-bounty-ask", crime)
+                        .with_arg("name", target_name.clone())
+                        .with_arg("coins", bounty_reward_amount as u64),
+                ),
+            )
+        {
+            quests.push(
+                accept_quest
+                    .and_then(move |yes| {
+                        now(move |ctx, _| {
+                            if yes {
+                                let quest = Quest::bounty(
+                                    ctx.npc_id.into(),
+                                    target,
+                                    session.target,
+                                    report_id,
+                                )
+                                .with_deposit(BOUNTY_REWARD_ITEM, bounty_reward_amount)
+                                .with_timeout(ctx.time.add_minutes(60.0));
+                                let marker = util::locate_actor(ctx, target).map(|target_pos| {
+                                    Marker::at(target_pos.xy())
+                                        .with_id(target)
+                                        .with_label(
+                                            Content::localized("hud-map-character-label")
+                                                .with_arg("name", target_name),
+                                        )
+                                        .with_kind(MarkerKind::Character)
+                                        .with_quest_flag(true)
+                                });
+                                create_quest(quest.clone())
+                                    .then(now(move |_, _| match marker {
+                                        Some(marker) => session.give_marker(marker).boxed(),
+                                        None => finish().boxed(),
+                                    }))
+                                    .then(session.say_statement(Content::localized(
+                                        "npc-response-quest-bounty-start",
+                                    )))
+                                    .boxed()
+                            } else {
+                                session
+                                    .say_statement(Content::localized(
+                                        "npc-response-quest-rejected",
+                                    ))
+                                    .boxed()
+                            }
+                        })
+                    })
+                    .boxed(),
+            );
+        }
+
         if quests.is_empty() {
             session
                 .say_statement(Content::localized("npc-response-quest-nothing"))
@@ -290,9 +601,9 @@ pub fn check_for_timeouts<S: State>(ctx: &mut NpcCtx) -> Option<impl Action<S> +
         let Some(quest) = ctx.data.quests.get(quest_id) else {
             continue;
         };
-        if let Some(timeout) = quest.timeout
-            // The quest has timed out...
-            && ctx.time > timeout
+        let timed_out = quest.timeout.is_some_and(|timeout| ctx.time > timeout);
+        // The quest has timed out or the quester gave up on it...
+        if (timed_out || quest.is_abandoned())
             // ...so resolve it
             && let Ok(Some(_)) = resolve_take_deposit(ctx, quest_id, false)
         {
@@ -303,7 +614,7 @@ pub fn check_for_timeouts<S: State>(ctx: &mut NpcCtx) -> Option<impl Action<S> +
 
             // If needs be, inform the quester that they failed
             match quest.kind {
-                QuestKind::Escort { escorter, .. } => {
+                QuestKind::Escort { escorter, .. } if timed_out => {
                     return Some(
                         goto_actor(escorter, 2.0)
                             .then(do_dialogue(escorter, move |session| {
@@ -313,13 +624,52 @@ pub fn check_for_timeouts<S: State>(ctx: &mut NpcCtx) -> Option<impl Action<S> +
                             .boxed(),
                     );
                 },
-                QuestKind::Slay { .. } => {},
+                QuestKind::Escort { .. }
+                | QuestKind::Slay { .. }
+                | QuestKind::Deliver { .. }
+                | QuestKind::Gather { .. }
+                | QuestKind::ClearSite { .. }
+                | QuestKind::Bounty { .. } => {},
             }
         }
     }
     None
 }
 
+/// Radius, in chunks, around a site in which its people gather resources
+const GATHER_RADIUS: i32 = 8;
+
+/// Items that can be gathered around `wpos`, along with the availability of
+/// the resource they are gathered from
+fn gatherable_items(
+    ctx: &NpcCtx,
+    wpos: Vec2<i32>,
+) -> impl Iterator<Item = (ItemResource, f32)> + use<> {
+    let center = wpos.wpos_to_cpos();
+    let mut total = EnumMap::<TerrainResource, f32>::default();
+    let mut chunks = 0.0;
+    for x in -GATHER_RADIUS..=GATHER_RADIUS {
+        for y in -GATHER_RADIUS..=GATHER_RADIUS {
+            if let Some(resources) = ctx.data.nature.chunk_resources(center + Vec2::new(x, y)) {
+                for (resource, availability) in resources.iter() {
+                    total[resource] += *availability;
+                }
+                chunks += 1.0;
+            }
+        }
+    }
+
+    total.into_iter().filter_map(move |(resource, total)| {
+        let availability = total / chunks;
+        // Don't ask for resources that have been mostly depleted
+        if availability > 0.5 {
+            Some((resource.gathered_item()?, availability))
+        } else {
+            None
+        }
+    })
+}
+
 pub fn escorted<S: State>(quest_id: QuestId, escorter: Actor, dst_site: SiteId) -> impl Action<S> {
     follow_actor(escorter, 5.0)
         .stop_if(move |ctx: &mut NpcCtx| {
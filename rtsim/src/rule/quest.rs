@@ -0,0 +1,317 @@
+use crate::{
+    RtState, Rule, RuleError,
+    ai::NpcSystemData,
+    data::{
+        Data,
+        quest::{QuestKind, QuestProgress},
+    },
+    event::{EventCtx, OnDeath, OnTick},
+};
+use common::{comp::Alignment, rtsim::Actor};
+use specs::Join;
+use vek::*;
+
+/// Checking whether a site was cleared looks at every entity, so don't do it
+/// every tick
+const CLEAR_SITE_TICK_SKIP: u64 = 30;
+
+/// Tracks the progress of quests whose objective can't be checked by their
+/// arbiter when they get resolved, see [`QuestProgress`].
+pub struct QuestEvents;
+
+impl Rule for QuestEvents {
+    fn start(rtstate: &mut RtState) -> Result<Self, RuleError> {
+        rtstate.bind::<Self, OnDeath>(on_death);
+        rtstate.bind::<Self, OnTick>(on_tick);
+
+        Ok(Self)
+    }
+}
+
+fn on_death(ctx: EventCtx<QuestEvents, OnDeath>) {
+    let Some(killer) = ctx.event.killer else {
+        return;
+    };
+    let data = &mut *ctx.state.data_mut();
+
+    let bounties = data
+        .quests
+        .related_to(ctx.event.actor)
+        .filter(|quest_id| {
+            data.quests.get(*quest_id).is_some_and(|quest| {
+                matches!(
+                    quest.kind,
+                    QuestKind::Bounty { target, hunter, .. }
+                        if target == ctx.event.actor && hunter == killer
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+    for quest_id in bounties {
+        if let Some(quest) = data.quests.get_mut(quest_id) {
+            quest.advance(QuestProgress::Achieved);
+        }
+    }
+}
+
+fn on_tick(ctx: EventCtx<QuestEvents, OnTick>) {
+    if !ctx.event.tick.is_multiple_of(CLEAR_SITE_TICK_SKIP) {
+        return;
+    }
+    let data = &mut *ctx.state.data_mut();
+    let system_data = &*ctx.system_data;
+
+    let updates = data
+        .quests
+        .unresolved()
+        .filter_map(|(quest_id, quest)| match quest.kind {
+            QuestKind::ClearSite { site, clearer }
+                if quest.progress() != QuestProgress::Achieved =>
+            {
+                Some((quest_id, site, clearer))
+            },
+            _ => None,
+        })
+        .filter_map(|(quest_id, site_id, clearer)| {
+            let site = data.sites.get(site_id)?;
+            // Creatures only exist while the site is loaded
+            if !site.is_loaded() {
+                return None;
+            }
+            let center = site.wpos.as_::<f32>();
+            let radius = ctx.index.sites.get(site.world_site?).radius();
+            // The clearer has to be there to see the site being cleared
+            if locate_actor(data, system_data, clearer)?
+                .xy()
+                .distance_squared(center)
+                > radius.powi(2)
+            {
+                return None;
+            }
+            let has_enemies = (
+                &system_data.positions,
+                &system_data.alignments,
+                &system_data.healths,
+            )
+                .join()
+                .any(|(pos, alignment, health)| {
+                    matches!(alignment, Alignment::Enemy)
+                        && !health.is_dead
+                        && pos.0.xy().distance_squared(center) < radius.powi(2)
+                });
+            Some((quest_id, has_enemies))
+        })
+        .collect::<Vec<_>>();
+
+    for (quest_id, has_enemies) in updates {
+        if let Some(quest) = data.quests.get_mut(quest_id) {
+            if has_enemies {
+                quest.advance(QuestProgress::Underway);
+            } else if quest.progress() == QuestProgress::Underway {
+                // A site without enemies only counts as cleared if they were seen before,
+                // otherwise they might not have been spawned yet
+                quest.advance(QuestProgress::Achieved);
+            }
+        }
+    }
+}
+
+fn locate_actor(data: &Data, system_data: &NpcSystemData, actor: Actor) -> Option<Vec3<f32>> {
+    match actor {
+        Actor::Npc(npc_id) => data.npcs.get(npc_id).map(|npc| npc.wpos),
+        Actor::Character(character_id) => system_data
+            .id_maps
+            .character_entity(character_id)
+            .and_then(|entity| system_data.positions.get(entity))
+            .map(|pos| pos.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data::{
+            ReportId,
+            quest::{Quest, RESOLVED_QUEST_LIFETIME},
+        },
+        rule::{cleanup::CleanUp, test_helpers::TestSim},
+    };
+    use common::{
+        character::CharacterId,
+        comp,
+        rtsim::{ItemResource, QuestId, Role},
+    };
+    use specs::{Builder, WorldExt};
+
+    fn civilian(sim: &mut TestSim, wpos: Vec3<f32>) -> Actor {
+        Actor::Npc(sim.spawn_npc(wpos, Role::Civilised(None)))
+    }
+
+    fn create_quest(sim: &mut TestSim, quest: Quest) -> QuestId {
+        let quests = &mut sim.data().quests;
+        let quest_id = quests.register();
+        quests.create(quest_id, quest);
+        quest_id
+    }
+
+    fn progress(sim: &mut TestSim, quest_id: QuestId) -> QuestProgress {
+        sim.data().quests.get(quest_id).unwrap().progress()
+    }
+
+    #[test]
+    fn deliver_and_gather_pay_out_once() {
+        let mut sim = TestSim::new(&[], |rtstate| rtstate.start_rule::<QuestEvents>());
+        let recipient = civilian(&mut sim, Vec3::zero());
+        let courier = Actor::Character(CharacterId(1));
+        let deliver = create_quest(
+            &mut sim,
+            Quest::deliver(recipient, courier, ItemResource::Parcel, 1)
+                .with_deposit(ItemResource::Coin, 50.0),
+        );
+        let gather = create_quest(
+            &mut sim,
+            Quest::gather(recipient, courier, ItemResource::Flax, 10)
+                .with_deposit(ItemResource::Coin, 20.0),
+        );
+        assert_eq!(
+            sim.data().quests.related_to(courier).count(),
+            2,
+            "both quests are active"
+        );
+
+        let quests = &sim.data().quests;
+        let deliver_quest = quests.get(deliver).unwrap();
+        // Only the arbiter can resolve quests...
+        assert!(deliver_quest.resolve(courier, true).is_none());
+        assert_eq!(deliver_quest.resolution(), None);
+        // ...and only once, so the deposit is only paid out once
+        let outcome = deliver_quest.resolve(recipient, true).unwrap();
+        assert_eq!(outcome.deposit, Some((ItemResource::Coin, 50.0)));
+        assert!(deliver_quest.resolve(recipient, false).is_none());
+        assert_eq!(deliver_quest.resolution(), Some(true));
+
+        let gather_quest = quests.get(gather).unwrap();
+        assert!(gather_quest.resolve(recipient, false).is_some());
+        assert_eq!(gather_quest.resolution(), Some(false));
+
+        assert_eq!(quests.related_to(courier).count(), 0);
+        assert_eq!(quests.all_related_to(courier).count(), 2);
+    }
+
+    #[test]
+    fn resolved_quests_are_forgotten() {
+        let mut sim = TestSim::new(&[], |rtstate| {
+            rtstate.start_rule::<QuestEvents>();
+            rtstate.start_rule::<CleanUp>();
+        });
+        let arbiter = civilian(&mut sim, Vec3::zero());
+        let gatherer = Actor::Character(CharacterId(1));
+        let resolved = create_quest(
+            &mut sim,
+            Quest::gather(arbiter, gatherer, ItemResource::Apple, 10),
+        );
+        let active = create_quest(
+            &mut sim,
+            Quest::gather(arbiter, gatherer, ItemResource::Wood, 10),
+        );
+        assert!(
+            sim.data()
+                .quests
+                .get(resolved)
+                .unwrap()
+                .resolve(arbiter, true)
+                .is_some()
+        );
+
+        // The quester can still learn how the quest ended for a while...
+        sim.tick(30, 1.0);
+        assert!(sim.data().quests.get(resolved).is_some());
+        // ...until it gets forgotten
+        sim.tick(30, RESOLVED_QUEST_LIFETIME as f32 / 30.0);
+        let quests = &sim.data().quests;
+        assert!(quests.get(resolved).is_none());
+        assert!(quests.get(active).is_some());
+        assert_eq!(quests.all_related_to(gatherer).collect::<Vec<_>>(), [
+            active
+        ]);
+    }
+
+    #[test]
+    fn bounty_is_achieved_when_the_hunter_kills_the_target() {
+        let mut sim = TestSim::new(&[], |rtstate| rtstate.start_rule::<QuestEvents>());
+        let arbiter = civilian(&mut sim, Vec3::zero());
+        let target = civilian(&mut sim, Vec3::zero());
+        let hunter = Actor::Character(CharacterId(1));
+        let bounty = create_quest(
+            &mut sim,
+            Quest::bounty(arbiter, target, hunter, ReportId::default()),
+        );
+
+        // Somebody else got to the target first
+        sim.emit(OnDeath {
+            actor: target,
+            wpos: None,
+            killer: Some(Actor::Character(CharacterId(2))),
+        });
+        assert_eq!(progress(&mut sim, bounty), QuestProgress::NotStarted);
+
+        sim.emit(OnDeath {
+            actor: target,
+            wpos: None,
+            killer: Some(hunter),
+        });
+        assert_eq!(progress(&mut sim, bounty), QuestProgress::Achieved);
+    }
+
+    #[test]
+    fn site_is_cleared_after_its_enemies_were_seen_and_defeated() {
+        let mut sim = TestSim::new(&[Vec2::new(16, 16)], |rtstate| {
+            rtstate.start_rule::<QuestEvents>()
+        });
+        let camp = sim.sites[0];
+        let arbiter = civilian(&mut sim, Vec3::zero());
+        let clearer = sim.spawn_npc(Vec3::new(500.0, 500.0, 0.0), Role::Civilised(None));
+        let quest_id = create_quest(
+            &mut sim,
+            Quest::clear_site(arbiter, camp, Actor::Npc(clearer)),
+        );
+        let body = comp::Body::Humanoid(comp::humanoid::Body::random());
+        let enemy = sim
+            .ecs
+            .create_entity()
+            .with(comp::Pos(Vec3::new(20.0, 20.0, 0.0)))
+            .with(comp::Alignment::Enemy)
+            .with(comp::Health::new(body))
+            .build();
+
+        // Creatures only exist while the site is loaded
+        sim.tick(30, 1.0);
+        assert_eq!(progress(&mut sim, quest_id), QuestProgress::NotStarted);
+        sim.data().sites[camp].count_loaded_chunks = 1;
+
+        // The clearer has to be there to see the enemies
+        sim.tick(30, 1.0);
+        assert_eq!(progress(&mut sim, quest_id), QuestProgress::NotStarted);
+        sim.data().npcs[clearer].wpos = Vec3::new(16.0, 16.0, 0.0);
+        sim.tick(30, 1.0);
+        assert_eq!(progress(&mut sim, quest_id), QuestProgress::Underway);
+
+        sim.ecs
+            .write_storage::<comp::Health>()
+            .get_mut(enemy)
+            .unwrap()
+            .is_dead = true;
+        sim.tick(30, 1.0);
+        assert_eq!(progress(&mut sim, quest_id), QuestProgress::Achieved);
+
+        // The site gets populated again, but the quest stays achieved
+        sim.ecs
+            .write_storage::<comp::Health>()
+            .get_mut(enemy)
+            .unwrap()
+            .is_dead = false;
+        sim.tick(30, 1.0);
+        assert_eq!(progress(&mut sim, quest_id), QuestProgress::Achieved);
+    }
+}
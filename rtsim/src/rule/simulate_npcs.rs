@@ -348,6 +348,13 @@ fn on_tick(ctx: EventCtx<SimulateNpcs, OnTick>) {
             data.quests.create(id, quest);
         }
 
+        // Load and unload goods at sites
+        for (site, good, amount) in core::mem::take(&mut npc.controller.goods_traded) {
+            if let Some(site) = data.sites.get_mut(site) {
+                site.market.deposit(good, amount);
+            }
+        }
+
         // Set job status
         npc.job = npc.controller.job.clone();
     }
@@ -0,0 +1,131 @@
+//! A small rtsim world to test rules in. The world map is empty, and only the
+//! rules under test are running.
+
+use crate::{
+    Data, Event, RtState,
+    ai::NpcSystemData,
+    data::{Npc, NpcId, SiteId},
+};
+use anymap2::SendSyncAnyMap;
+use common::{
+    comp::{self, gizmos::RtsimGizmos, item::MaterialStatManifest, tool::AbilityMap},
+    resources::{Time, TimeOfDay},
+    rtsim::{Role, WorldSettings},
+    shared_server_config::ServerConstants,
+    uid::IdMaps,
+    weather::WeatherGrid,
+};
+use specs::WorldExt;
+use std::sync::Mutex;
+use vek::*;
+use world::{IndexOwned, World, index::Index, site::Site as WorldSite};
+
+pub struct TestSim {
+    pub rtstate: RtState,
+    /// Entities of the loaded part of the world
+    pub ecs: specs::World,
+    /// The sites passed to [`TestSim::new`], in the same order
+    pub sites: Vec<SiteId>,
+    world: World,
+    index: IndexOwned,
+    time: Time,
+}
+
+impl TestSim {
+    /// Create a world with a site at each of `sites`, running the rules that
+    /// `start_rules` starts.
+    pub fn new(sites: &[Vec2<i32>], start_rules: impl FnOnce(&mut RtState)) -> Self {
+        let (world, _) = World::empty();
+        let mut index = Index::new(0);
+        let world_sites = sites
+            .iter()
+            .map(|origin| {
+                let mut site = WorldSite::default();
+                site.origin = *origin;
+                index.sites.insert(site)
+            })
+            .collect::<Vec<_>>();
+        let index = IndexOwned::new(index);
+
+        let mut data = Data::generate(&WorldSettings::default(), &world, index.as_index_ref());
+        data.prepare();
+        let sites = world_sites
+            .iter()
+            .map(|world_site| data.sites.world_site_map[world_site])
+            .collect();
+
+        let mut rtstate = RtState {
+            resources: SendSyncAnyMap::new(),
+            rules: SendSyncAnyMap::new(),
+            event_handlers: SendSyncAnyMap::new(),
+        }
+        .with_resource(data);
+        start_rules(&mut rtstate);
+
+        let mut ecs = specs::World::new();
+        ecs.register::<comp::Pos>();
+        ecs.register::<comp::Alignment>();
+        ecs.register::<comp::Health>();
+        ecs.register::<comp::Inventory>();
+        ecs.insert(IdMaps::default());
+        ecs.insert(ServerConstants {
+            day_cycle_coefficient: 24.0,
+        });
+        ecs.insert(WeatherGrid::new(Vec2::zero()));
+        ecs.insert(RtsimGizmos::default());
+        ecs.insert(AbilityMap::load().cloned());
+        ecs.insert(MaterialStatManifest::load().cloned());
+
+        Self {
+            rtstate,
+            ecs,
+            sites,
+            world,
+            index,
+            time: Time(0.0),
+        }
+    }
+
+    pub fn data(&mut self) -> &mut Data { self.rtstate.get_data_mut() }
+
+    pub fn spawn_npc(&mut self, wpos: Vec3<f32>, role: Role) -> NpcId {
+        self.data().spawn_npc(Npc::new(
+            0,
+            wpos,
+            comp::Body::Humanoid(comp::humanoid::Body::random()),
+            role,
+        ))
+    }
+
+    pub fn emit<E: for<'a> Event<SystemData<'a> = ()>>(&mut self, event: E) {
+        self.rtstate
+            .emit(event, &mut (), &self.world, self.index.as_index_ref());
+    }
+
+    /// Run `ticks` ticks that are `dt` seconds long each.
+    pub fn tick(&mut self, ticks: u64, dt: f32) {
+        for _ in 0..ticks {
+            self.time.0 += f64::from(dt);
+            let mut system_data = NpcSystemData {
+                positions: self.ecs.system_data(),
+                alignments: self.ecs.system_data(),
+                healths: self.ecs.system_data(),
+                id_maps: self.ecs.system_data(),
+                server_constants: self.ecs.system_data(),
+                weather_grid: self.ecs.system_data(),
+                rtsim_gizmos: self.ecs.system_data(),
+                ability_map: self.ecs.system_data(),
+                msm: self.ecs.system_data(),
+                inventories: Mutex::new(self.ecs.system_data()),
+            };
+            self.rtstate.tick(
+                &mut system_data,
+                &self.world,
+                self.index.as_index_ref(),
+                TimeOfDay(self.time.0),
+                self.time,
+                dt,
+            );
+        }
+    }
+}
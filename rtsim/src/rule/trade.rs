@@ -0,0 +1,251 @@
+use crate::{
+    RtState, Rule, RuleError,
+    data::{
+        Data, Factions, Npc, Sentiment,
+        npc::{Job, SimulationMode},
+        trade::Cargo,
+    },
+    event::{EventCtx, OnDeath, OnTick},
+};
+use common::rtsim::{Actor, NpcId, Profession};
+
+/// Markets don't change much from tick to tick, and caravans move slowly
+const TRADE_TICK_SKIP: u64 = 30;
+/// How close (in blocks) a robber needs to get to a caravan to rob it
+const ROBBERY_RANGE: f32 = 24.0;
+
+/// A rule that keeps track of the goods that caravans move between sites, and
+/// lets hostile NPCs rob them on the way.
+pub struct Trade;
+
+impl Rule for Trade {
+    fn start(rtstate: &mut RtState) -> Result<Self, RuleError> {
+        rtstate.bind(on_tick);
+        rtstate.bind(on_death);
+
+        Ok(Self)
+    }
+}
+
+/// Pirates, cultists and members of evil factions prey on caravans.
+fn is_robber(factions: &Factions, npc: &Npc) -> bool {
+    !npc.is_dead()
+        && npc.hired().is_none()
+        && (matches!(
+            npc.profession(),
+            Some(Profession::Pirate(_) | Profession::Cultist)
+        ) || npc
+            .faction
+            .and_then(|faction| factions.get(faction))
+            .is_some_and(|faction| !faction.good_or_evil))
+}
+
+/// The stolen goods end up at the robber's home.
+fn plunder(data: &mut Data, robber: NpcId, cargo: Cargo) {
+    if let Some(home) = data.npcs.get(robber).and_then(|robber| robber.home)
+        && let Some(site) = data.sites.get_mut(home)
+    {
+        for (good, amount) in cargo.goods {
+            site.market.deposit(good, amount);
+        }
+    }
+}
+
+fn on_tick(ctx: EventCtx<Trade, OnTick>) {
+    if !ctx.event.tick.is_multiple_of(TRADE_TICK_SKIP) {
+        return;
+    }
+
+    let data = &mut *ctx.state.data_mut();
+
+    for site in data.sites.values_mut() {
+        site.market.settle(ctx.event.dt * TRADE_TICK_SKIP as f32);
+    }
+
+    // Caravans are only robbed on the road while simulated: when loaded, they fight
+    // back (and players can protect them) like any other NPC.
+    let robberies = data
+        .npcs
+        .iter()
+        .filter(|(_, npc)| {
+            !npc.is_dead()
+                && matches!(npc.mode, SimulationMode::Simulated)
+                && npc.current_site.is_none()
+                && matches!(npc.job, Some(Job::Caravan(_)))
+        })
+        .filter_map(|(npc_id, npc)| {
+            let robber = data
+                .npcs
+                .nearby(Some(npc_id), npc.wpos, ROBBERY_RANGE)
+                .filter_map(|actor| actor.npc())
+                .find(|robber| {
+                    data.npcs.get(*robber).is_some_and(|robber| {
+                        // Robbers don't rob their own
+                        (robber.faction.is_none() || robber.faction != npc.faction)
+                            && is_robber(&data.factions, robber)
+                    })
+                })?;
+            Some((npc_id, robber))
+        })
+        .collect::<Vec<_>>();
+
+    for (npc_id, robber) in robberies {
+        let Some(npc) = data.npcs.get_mut(npc_id) else {
+            continue;
+        };
+        let Some(Job::Caravan(cargo)) = npc.job.take() else {
+            continue;
+        };
+        // The job is reapplied from the controller each tick
+        npc.controller.job = None;
+        npc.sentiments
+            .toward_mut(Actor::Npc(robber))
+            .change_by(-0.5, Sentiment::ENEMY);
+        plunder(data, robber, cargo);
+    }
+}
+
+fn on_death(ctx: EventCtx<Trade, OnDeath>) {
+    let data = &mut *ctx.state.data_mut();
+
+    // Whoever kills a caravan gets to keep its cargo
+    if let Actor::Npc(npc_id) = ctx.event.actor
+        && let Some(Actor::Npc(killer)) = ctx.event.killer
+        && let Some(npc) = data.npcs.get_mut(npc_id)
+        && let Some(Job::Caravan(cargo)) = npc.job.take()
+    {
+        npc.controller.job = None;
+        plunder(data, killer, cargo);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        event::OnSetup,
+        rule::{simulate_npcs::SimulateNpcs, sync_npcs::SyncNpcs, test_helpers::TestSim},
+    };
+    use common::{rtsim::Role, trade::Good};
+    use vek::*;
+
+    fn cargo(sim: &TestSim) -> Cargo {
+        Cargo {
+            from: sim.sites[0],
+            to: sim.sites[1],
+            goods: vec![(Good::Wood, 20.0), (Good::Tools, 10.0)],
+        }
+    }
+
+    fn surplus(sim: &mut TestSim, site: usize, good: Good) -> f32 {
+        let site = sim.sites[site];
+        sim.data().sites[site]
+            .market
+            .surplus
+            .get(&good)
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    fn caravan(sim: &mut TestSim) -> NpcId {
+        sim.spawn_npc(
+            Vec3::new(8.0, 8.0, 0.0),
+            Role::Civilised(Some(Profession::Caravan)),
+        )
+    }
+
+    #[test]
+    fn caravans_move_goods_between_sites() {
+        let mut sim = TestSim::new(&[Vec2::new(8, 8), Vec2::new(40, 40)], |rtstate| {
+            rtstate.start_rule::<SimulateNpcs>()
+        });
+        let caravan = caravan(&mut sim);
+        let cargo = cargo(&sim);
+
+        sim.data().npcs[caravan]
+            .controller
+            .load_cargo(cargo.clone());
+        sim.tick(1, 1.0);
+        assert!(sim.data().npcs[caravan].job == Some(Job::Caravan(cargo)));
+        assert_eq!(surplus(&mut sim, 0, Good::Wood), -20.0);
+        assert_eq!(surplus(&mut sim, 0, Good::Tools), -10.0);
+        assert_eq!(surplus(&mut sim, 1, Good::Wood), 0.0);
+
+        // Arriving at the destination
+        sim.data().npcs[caravan].controller.unload_cargo();
+        sim.tick(1, 1.0);
+        assert!(sim.data().npcs[caravan].job.is_none());
+        assert_eq!(surplus(&mut sim, 0, Good::Wood), -20.0);
+        assert_eq!(surplus(&mut sim, 1, Good::Wood), 20.0);
+        assert_eq!(surplus(&mut sim, 1, Good::Tools), 10.0);
+    }
+
+    #[test]
+    fn markets_settle_over_time() {
+        let mut sim = TestSim::new(&[Vec2::new(8, 8)], |rtstate| rtstate.start_rule::<Trade>());
+        let site = sim.sites[0];
+        sim.data().sites[site].market.deposit(Good::Wood, 100.0);
+        sim.data().sites[site].market.withdraw(Good::Stone, 100.0);
+
+        // Surpluses halve every two hours
+        sim.tick(TRADE_TICK_SKIP, 2.0 * 3600.0 / TRADE_TICK_SKIP as f32);
+        assert!((surplus(&mut sim, 0, Good::Wood) - 50.0).abs() < 0.01);
+        assert!((surplus(&mut sim, 0, Good::Stone) + 50.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn robbers_take_cargo_home() {
+        let mut sim = TestSim::new(
+            &[Vec2::new(8, 8), Vec2::new(40, 40), Vec2::new(60, 60)],
+            |rtstate| {
+                rtstate.start_rule::<Trade>();
+                rtstate.start_rule::<SyncNpcs>();
+                rtstate.start_rule::<SimulateNpcs>();
+            },
+        );
+        sim.emit(OnSetup);
+        let caravan = caravan(&mut sim);
+        let cargo = cargo(&sim);
+        sim.data().npcs[caravan].controller.load_cargo(cargo);
+        let robber = sim.spawn_npc(
+            Vec3::new(12.0, 12.0, 0.0),
+            Role::Civilised(Some(Profession::Cultist)),
+        );
+        let hideout = sim.sites[2];
+        sim.data().npcs[robber].home = Some(hideout);
+
+        sim.tick(TRADE_TICK_SKIP, 1.0);
+        assert!(sim.data().npcs[caravan].job.is_none());
+        assert_eq!(surplus(&mut sim, 2, Good::Wood), 20.0);
+        assert_eq!(surplus(&mut sim, 2, Good::Tools), 10.0);
+        // The goods never arrive
+        assert_eq!(surplus(&mut sim, 1, Good::Wood), 0.0);
+
+        // Without cargo, there's nothing left to rob
+        sim.tick(TRADE_TICK_SKIP, 1.0);
+        assert_eq!(surplus(&mut sim, 2, Good::Wood), 20.0);
+    }
+
+    #[test]
+    fn killers_take_cargo_home() {
+        let mut sim = TestSim::new(
+            &[Vec2::new(8, 8), Vec2::new(40, 40), Vec2::new(60, 60)],
+            |rtstate| rtstate.start_rule::<Trade>(),
+        );
+        let caravan = caravan(&mut sim);
+        let cargo = cargo(&sim);
+        sim.data().npcs[caravan].job = Some(Job::Caravan(cargo));
+        let killer = sim.spawn_npc(Vec3::new(12.0, 12.0, 0.0), Role::Civilised(None));
+        let home = sim.sites[2];
+        sim.data().npcs[killer].home = Some(home);
+
+        sim.emit(OnDeath {
+            actor: Actor::Npc(caravan),
+            wpos: None,
+            killer: Some(Actor::Npc(killer)),
+        });
+        assert!(sim.data().npcs[caravan].job.is_none());
+        assert_eq!(surplus(&mut sim, 2, Good::Wood), 20.0);
+        assert_eq!(surplus(&mut sim, 2, Good::Tools), 10.0);
+    }
+}
@@ -4,7 +4,8 @@
 
 use clap::{Parser, builder::ValueParser};
 use common::comp;
-use server::persistence::SqlLogMode;
+use serde::Serialize;
+use server::{client::Traffic, persistence::SqlLogMode};
 use std::{str::FromStr, sync::mpsc::Sender};
 use tracing::error;
 
@@ -47,6 +48,67 @@ pub enum Shutdown {
     Cancel,
 }
 
+#[derive(Clone, Debug, Parser)]
+pub enum Whitelist {
+    /// Adds a player to the whitelist
+    Add { username: String },
+    /// Removes a player from the whitelist
+    Remove { username: String },
+}
+
+#[derive(Clone, Debug, Parser)]
+pub enum TpDestination {
+    /// Teleport to a position
+    Pos { x: f32, y: f32, z: f32 },
+    /// Teleport to another online player
+    Player { username: String },
+}
+
+#[derive(Clone, Debug, Parser)]
+pub enum Motd {
+    /// Shows the message of the day
+    Show {
+        /// Locale of the message, the default locale if omitted
+        locale: Option<String>,
+    },
+    /// Sets the message of the day
+    Set { locale: String, msg: String },
+    /// Removes the message of the day
+    Clear { locale: String },
+}
+
+#[derive(Clone, Debug, Parser)]
+pub enum Character {
+    /// Writes a character to a RON file, or JSON if the file name ends with
+    /// `.json`
+    Export {
+        character_id: i64,
+        /// File name in the `character_exports` folder of the data dir
+        file: String,
+    },
+    /// Creates a new character for a player from an exported file
+    Import {
+        /// File name in the `character_exports` folder of the data dir
+        file: String,
+        /// UUID of the player who gets the character
+        player_uuid: String,
+    },
+}
+
+#[derive(Clone, Debug, Parser)]
+pub enum Backup {
+    /// Lists the backups of the database
+    List,
+    /// Backs up the database
+    Create,
+    /// Replaces the database with a backup, after backing up its current
+    /// state
+    Restore {
+        /// File name of the backup, as shown by `backup list`
+        name: String,
+    },
+}
+
 #[derive(Clone, Debug, Parser)]
 pub enum SharedCommand {
     /// Perform operations on the admin list
@@ -54,6 +116,11 @@ pub enum SharedCommand {
         #[command(subcommand)]
         command: Admin,
     },
+    /// Move characters between servers by exporting and importing them
+    Character {
+        #[command(subcommand)]
+        command: Character,
+    },
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -81,17 +148,122 @@ pub enum Message {
     DisconnectAllClients,
     /// returns active player names
     ListPlayers,
+    /// returns name, uuid, position and last ping of active players
+    ListPlayerDetails,
+    /// Shows how many bytes were sent to and received from the active
+    /// players, by message type
+    Traffic,
+    /// Backs up the database in the background
+    Backup,
     ListLogs,
     /// sends a msg to everyone on the server
     SendGlobalMsg {
         msg: String,
     },
+    /// Loads the whitelist, banlist, admins and server description from disk
+    /// again
+    ReloadSettings,
+    /// Loads all plugins from disk again
+    #[cfg(feature = "plugins")]
+    ReloadPlugins,
+    /// Kicks an online player
+    Kick {
+        username: String,
+        #[arg(default_value = "")]
+        reason: String,
+    },
+    /// Bans a player and kicks them if they are online
+    Ban {
+        username: String,
+        #[arg(default_value = "")]
+        reason: String,
+        /// Duration of the ban (e.g. "3d 12h"), permanent if omitted
+        #[arg(short, long)]
+        duration: Option<humantime::Duration>,
+        /// Replace an existing ban
+        #[arg(short, long)]
+        overwrite: bool,
+    },
+    /// Bans a player and the IP address they are connected from
+    BanIp {
+        username: String,
+        #[arg(default_value = "")]
+        reason: String,
+        /// Duration of the ban (e.g. "3d 12h"), permanent if omitted
+        #[arg(short, long)]
+        duration: Option<humantime::Duration>,
+        /// Replace an existing ban
+        #[arg(short, long)]
+        overwrite: bool,
+    },
+    /// Lifts the ban of a player
+    Unban {
+        username: String,
+        /// Only lift the IP ban
+        #[arg(long)]
+        ip: bool,
+    },
+    /// Perform operations on the whitelist
+    Whitelist {
+        #[command(subcommand)]
+        command: Whitelist,
+    },
+    /// Teleports an online player
+    Tp {
+        username: String,
+        #[command(subcommand)]
+        destination: TpDestination,
+    },
+    /// Prevents a player from chatting until the server restarts
+    Mute {
+        username: String,
+        /// Duration of the mute (e.g. "30m"), until unmuted if omitted
+        #[arg(short, long)]
+        duration: Option<humantime::Duration>,
+    },
+    /// Lifts the mute of a player
+    Unmute {
+        username: String,
+    },
+    /// Show or change the message of the day
+    Motd {
+        #[command(subcommand)]
+        command: Motd,
+    },
+    /// Runs an in-game chat command (without the leading `/`) as the console
+    /// admin, e.g. `run give_item common.items.food.apple 5`
+    Run {
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlayerDetails {
+    pub alias: String,
+    pub uuid: String,
+    /// `None` while the player is in the character selection
+    pub position: Option<[f32; 3]>,
+    /// Round trip time to the client in milliseconds, `None` until it was
+    /// measured
+    pub ping_ms: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PlayerTraffic {
+    pub alias: String,
+    pub traffic: Traffic,
 }
 
 #[derive(Debug, Clone)]
 pub enum MessageReturn {
     Players(Vec<String>),
+    PlayerDetails(Vec<PlayerDetails>),
+    Traffic(Vec<PlayerTraffic>),
     Logs(Vec<String>),
+    /// Outcome of a command that changes the server, as a human readable
+    /// message
+    Feedback(Result<String, String>),
 }
 
 #[derive(Parser)]
@@ -124,6 +296,11 @@ pub enum ArgvCommand {
     /// Load an area, run the server for some time, and then exit (useful for
     /// profiling).
     Bench(BenchParams),
+    /// Manage backups of the database, only while the server is stopped
+    Backup {
+        #[command(subcommand)]
+        command: Backup,
+    },
 }
 
 #[derive(Parser)]
@@ -19,29 +19,37 @@ mod tuilog;
 mod web;
 use crate::{
     cli::{
-        Admin, ArgvApp, ArgvCommand, BenchParams, Message, MessageReturn, SharedCommand, Shutdown,
+        Admin, ArgvApp, ArgvCommand, Backup, BenchParams, Character, Message, MessageReturn, Motd,
+        PlayerDetails, PlayerTraffic, SharedCommand, Shutdown, TpDestination, Whitelist,
     },
     settings::Settings,
     shutdown_coordinator::ShutdownCoordinator,
     tui_runner::Tui,
     tuilog::TuiLog,
 };
+use client_i18n::LocalizationHandle;
 use common::{
+    character::CharacterId,
     clock::Clock,
-    comp::{ChatType, Player},
+    comp::{ChatMsg, ChatType, Player, Pos},
     consts::MIN_RECOMMENDED_TOKIO_THREADS,
 };
 use common_base::span;
 use core::sync::atomic::{AtomicUsize, Ordering};
 use rand::distr::SampleString;
-use server::{Event, Input, Server, persistence::DatabaseSettings, settings::Protocol};
+use server::{
+    Event, Input, Server, client::Client, console::TeleportDestination,
+    persistence::DatabaseSettings, settings::Protocol,
+};
 use std::{
+    collections::HashMap,
     io,
     sync::{Arc, atomic::AtomicBool},
     time::{Duration, Instant},
 };
 use tokio::sync::Notify;
-use tracing::{info, trace};
+use tracing::{error, info, trace, warn};
+use vek::Vec3;
 
 lazy_static::lazy_static! {
     pub static ref LOG: TuiLog<'static> = TuiLog::default();
@@ -137,6 +145,7 @@ fn main() -> io::Result<()> {
     let database_settings = DatabaseSettings {
         db_dir: server_data_dir.join(PERSISTENCE_DB_DIR),
         sql_log_mode,
+        backend: server_settings.database.clone(),
     };
 
     let mut bench = None;
@@ -180,6 +189,75 @@ fn main() -> io::Result<()> {
                     },
                 };
             },
+            ArgvCommand::Shared(SharedCommand::Character { command }) => {
+                server::persistence::run_migrations(&database_settings);
+                let character_file_path = |file: &str| {
+                    server::persistence::character_file_path(&server_data_dir, file).ok_or_else(
+                        || {
+                            io::Error::new(
+                                io::ErrorKind::InvalidInput,
+                                format!(
+                                    "'{file}' is not a file name in the {} folder",
+                                    server::persistence::CHARACTER_EXPORTS_DIR
+                                ),
+                            )
+                        },
+                    )
+                };
+                let result = match command {
+                    Character::Export { character_id, file } => {
+                        let path = character_file_path(&file)?;
+                        server::persistence::export_character(
+                            &database_settings,
+                            CharacterId(character_id),
+                            &path,
+                        )
+                        .map(|alias| info!("Exported {alias} to {}", path.display()))
+                    },
+                    Character::Import { file, player_uuid } => {
+                        let path = character_file_path(&file)?;
+                        let player_uuid = player_uuid.parse().map_err(|e| {
+                            io::Error::new(io::ErrorKind::InvalidInput, format!("{e}"))
+                        })?;
+                        server::persistence::import_character(
+                            &database_settings,
+                            &path,
+                            player_uuid,
+                        )
+                        .map(|(character_id, alias)| {
+                            info!("Imported {alias} as character {}", character_id.0)
+                        })
+                    },
+                };
+                return result.map_err(|e| io::Error::other(e.to_string()));
+            },
+            ArgvCommand::Backup { command } => {
+                use server::persistence::backup;
+                let result = match command {
+                    Backup::List => backup::list_backups(&database_settings).map(|backups| {
+                        if backups.is_empty() {
+                            info!("There are no backups");
+                        }
+                        for backup in backups {
+                            info!(
+                                "{} ({:.1} MiB)",
+                                backup.name,
+                                backup.size as f64 / 1_048_576.0
+                            );
+                        }
+                    }),
+                    Backup::Create => backup::create_backup(&database_settings)
+                        .map(|path| info!("Created {}", path.display())),
+                    Backup::Restore { name } => backup::restore_backup(&database_settings, &name)
+                        .map(|previous| {
+                            info!(
+                                "Restored {name}, the previous database was backed up to {}",
+                                previous.display()
+                            )
+                        }),
+                };
+                return result.map_err(|e| io::Error::other(e.to_string()));
+            },
             ArgvCommand::Bench(params) => {
                 bench = Some(params);
                 // If we are trying to benchmark, don't limit the server view distance.
@@ -225,6 +303,7 @@ fn main() -> io::Result<()> {
     let metrics_shutdown = Arc::new(Notify::new());
     let metrics_shutdown_clone = Arc::clone(&metrics_shutdown);
     let web_chat_secret = settings.web_chat_secret.clone();
+    let web_admin_secret = settings.web_admin_secret.clone();
     let ui_api_secret = settings.ui_api_secret.clone().unwrap_or_else(|| {
         // when no secret is provided we generate one that we distribute via the /ui
         // endpoint
@@ -239,6 +318,7 @@ fn main() -> io::Result<()> {
             registry,
             chat,
             web_chat_secret,
+            web_admin_secret,
             ui_api_secret,
             web_ui_request_s,
             settings.web_address,
@@ -252,6 +332,10 @@ fn main() -> io::Result<()> {
         .into_iter()
         .map(|protocol| match protocol {
             Protocol::Tcp { address } => ("TCP", address),
+            Protocol::TcpEncrypted {
+                address,
+                key_file_path: _,
+            } => ("encrypted TCP", address),
             Protocol::Quic {
                 address,
                 cert_file_path: _,
@@ -299,6 +383,16 @@ fn server_loop(
     let mut clock = Clock::new(Duration::from_secs_f64(1.0 / TPS as f64));
     let mut shutdown_coordinator = ShutdownCoordinator::new(Arc::clone(&shutdown_signal));
     let mut bench_exit_time = None;
+    // Only used for the output of in-game commands, server only asset packs may
+    // not contain the translations
+    let localization = LocalizationHandle::load("en")
+        .inspect_err(|e| {
+            warn!(
+                ?e,
+                "Failed to load translations, command output won't be localized"
+            )
+        })
+        .ok();
 
     let mut tick_no = 0u64;
     'outer: loop {
@@ -369,6 +463,17 @@ fn server_loop(
                 }) => {
                     server.remove_admin(&username);
                 },
+                Message::Shared(SharedCommand::Character { command }) => {
+                    let result = match command {
+                        Character::Export { character_id, file } => {
+                            server.export_character(CharacterId(character_id), &file)
+                        },
+                        Character::Import { file, player_uuid } => {
+                            server.import_character(&file, &player_uuid)
+                        },
+                    };
+                    let _ = response.send(MessageReturn::Feedback(result));
+                },
                 #[cfg(feature = "worldgen")]
                 Message::LoadArea { view_distance } => {
                     server.create_centered_persister(view_distance);
@@ -389,6 +494,37 @@ fn server_loop(
                         .collect();
                     let _ = response.send(MessageReturn::Players(players));
                 },
+                Message::ListPlayerDetails => {
+                    let ecs = server.state().ecs();
+                    let players = (
+                        &ecs.read_storage::<Player>(),
+                        &ecs.read_storage::<Client>(),
+                        ecs.read_storage::<Pos>().maybe(),
+                    )
+                        .join()
+                        .map(|(player, client, pos)| PlayerDetails {
+                            alias: player.alias.clone(),
+                            uuid: player.uuid().to_string(),
+                            position: pos.map(|pos| pos.0.into_array()),
+                            ping_ms: client.rtt().map(|rtt| rtt.as_secs_f64() * 1000.0),
+                        })
+                        .collect();
+                    let _ = response.send(MessageReturn::PlayerDetails(players));
+                },
+                Message::Traffic => {
+                    let ecs = server.state().ecs();
+                    let players = (&ecs.read_storage::<Player>(), &ecs.read_storage::<Client>())
+                        .join()
+                        .map(|(player, client)| PlayerTraffic {
+                            alias: player.alias.clone(),
+                            traffic: client.traffic(),
+                        })
+                        .collect();
+                    let _ = response.send(MessageReturn::Traffic(players));
+                },
+                Message::Backup => {
+                    let _ = response.send(MessageReturn::Feedback(server.backup_database()));
+                },
                 Message::ListLogs => {
                     let log = LOG.inner.lock().unwrap();
                     let lines: Vec<_> = log
@@ -405,6 +541,118 @@ fn server_loop(
                     let msg = ChatType::Meta.into_plain_msg(msg);
                     server.state().send_chat(msg, false);
                 },
+                Message::ReloadSettings => {
+                    server.reload_editable_settings();
+                    let _ = response.send(MessageReturn::Feedback(Ok("Reloaded the editable \
+                                                                      settings"
+                        .to_owned())));
+                },
+                #[cfg(feature = "plugins")]
+                Message::ReloadPlugins => {
+                    server.reload_plugins();
+                },
+                Message::Kick { username, reason } => {
+                    let output = server.kick_player(&username, &reason);
+                    let _ = response.send(MessageReturn::Feedback(command_output(
+                        localization.as_ref(),
+                        &output,
+                    )));
+                },
+                Message::Ban {
+                    username,
+                    reason,
+                    duration,
+                    overwrite,
+                } => {
+                    let output =
+                        server.ban_player(&username, &reason, duration.map(Into::into), overwrite);
+                    let _ = response.send(MessageReturn::Feedback(command_output(
+                        localization.as_ref(),
+                        &output,
+                    )));
+                },
+                Message::BanIp {
+                    username,
+                    reason,
+                    duration,
+                    overwrite,
+                } => {
+                    let output = server.ban_player_ip(
+                        &username,
+                        &reason,
+                        duration.map(Into::into),
+                        overwrite,
+                    );
+                    let _ = response.send(MessageReturn::Feedback(command_output(
+                        localization.as_ref(),
+                        &output,
+                    )));
+                },
+                Message::Unban { username, ip } => {
+                    let output = server.unban_player(&username, ip);
+                    let _ = response.send(MessageReturn::Feedback(command_output(
+                        localization.as_ref(),
+                        &output,
+                    )));
+                },
+                Message::Whitelist { command } => {
+                    let output = match command {
+                        Whitelist::Add { username } => server.whitelist_add(&username),
+                        Whitelist::Remove { username } => server.whitelist_remove(&username),
+                    };
+                    let _ = response.send(MessageReturn::Feedback(command_output(
+                        localization.as_ref(),
+                        &output,
+                    )));
+                },
+                Message::Tp {
+                    username,
+                    destination,
+                } => {
+                    let destination = match &destination {
+                        TpDestination::Pos { x, y, z } => {
+                            TeleportDestination::Position(Vec3::new(*x, *y, *z))
+                        },
+                        TpDestination::Player { username } => TeleportDestination::Player(username),
+                    };
+                    let result = server
+                        .teleport_player(&username, destination)
+                        .and_then(|output| command_output(localization.as_ref(), &output));
+                    let _ = response.send(MessageReturn::Feedback(result));
+                },
+                Message::Mute { username, duration } => {
+                    let _ = response.send(MessageReturn::Feedback(
+                        server.mute_player(&username, duration.map(Into::into)),
+                    ));
+                },
+                Message::Unmute { username } => {
+                    let _ = response.send(MessageReturn::Feedback(server.unmute_player(&username)));
+                },
+                Message::Motd { command } => {
+                    let result = match command {
+                        Motd::Show { locale } => server.motd(locale.as_deref()),
+                        Motd::Set { locale, msg } => command_output(
+                            localization.as_ref(),
+                            &server.set_motd(&locale, Some(msg)),
+                        ),
+                        Motd::Clear { locale } => {
+                            command_output(localization.as_ref(), &server.set_motd(&locale, None))
+                        },
+                    };
+                    let _ = response.send(MessageReturn::Feedback(result));
+                },
+                Message::Run { command } => {
+                    let mut command = command.into_iter();
+                    let name = command.next().unwrap_or_default();
+                    let result = server
+                        .run_console_command(
+                            settings.console_role,
+                            name.trim_start_matches('/'),
+                            command.collect(),
+                        )
+                        .and_then(|output| command_output(localization.as_ref(), &output));
+                    let _ = response.send(MessageReturn::Feedback(result));
+                },
             }
             false
         };
@@ -419,7 +667,21 @@ fn server_loop(
                 if let Ok(msg_answ) = recv.try_recv() {
                     match msg_answ {
                         MessageReturn::Players(players) => info!("Players: {:?}", players),
+                        MessageReturn::PlayerDetails(players) => {
+                            for player in players {
+                                let ping = player
+                                    .ping_ms
+                                    .map_or("unknown".to_owned(), |ping| format!("{ping:.0}ms"));
+                                info!(
+                                    "{} ({}) at {:?}, ping {}",
+                                    player.alias, player.uuid, player.position, ping
+                                );
+                            }
+                        },
+                        MessageReturn::Traffic(players) => log_traffic(&players),
                         MessageReturn::Logs(_) => info!("skipp sending logs to tui"),
+                        MessageReturn::Feedback(Ok(msg)) => info!("{}", msg),
+                        MessageReturn::Feedback(Err(msg)) => error!("{}", msg),
                     };
                 }
             }
@@ -440,3 +702,90 @@ fn server_loop(
     }
     Ok(())
 }
+
+/// Logs the traffic by message type and by player, the biggest first
+fn log_traffic(players: &[PlayerTraffic]) {
+    fn by_size<'a>(
+        traffic: impl Iterator<Item = (&'a &'static str, &'a u64)>,
+    ) -> Vec<(&'static str, u64)> {
+        let mut sum = HashMap::<&'static str, u64>::new();
+        for (kind, bytes) in traffic {
+            *sum.entry(*kind).or_default() += bytes;
+        }
+        let mut sum: Vec<_> = sum.into_iter().collect();
+        sum.sort_unstable_by(|a, b| b.1.cmp(&a.1));
+        sum
+    }
+    fn human(bytes: u64) -> String {
+        match bytes {
+            0..1024 => format!("{bytes} B"),
+            1024..1_048_576 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+            _ => format!("{:.1} MiB", bytes as f64 / 1_048_576.0),
+        }
+    }
+
+    for (direction, kinds) in [
+        (
+            "Sent",
+            by_size(players.iter().flat_map(|p| &p.traffic.sent)),
+        ),
+        (
+            "Received",
+            by_size(players.iter().flat_map(|p| &p.traffic.received)),
+        ),
+    ] {
+        let total = kinds.iter().map(|(_, bytes)| bytes).sum();
+        info!("{direction} {} in total", human(total));
+        for (kind, bytes) in kinds {
+            info!("  {kind}: {}", human(bytes));
+        }
+    }
+    let mut players: Vec<_> = players
+        .iter()
+        .map(|p| (p, by_size(p.traffic.sent.iter())))
+        .collect();
+    players.sort_unstable_by_key(|(_, kinds)| {
+        core::cmp::Reverse(kinds.iter().map(|(_, bytes)| bytes).sum::<u64>())
+    });
+    for (player, kinds) in players {
+        let sent = kinds.iter().map(|(_, bytes)| bytes).sum();
+        let received = player.traffic.received.values().sum();
+        let biggest = kinds.first().map_or("nothing", |(kind, _)| *kind);
+        info!(
+            "{}: sent {} (mostly {biggest}), received {}",
+            player.alias,
+            human(sent),
+            human(received)
+        );
+    }
+}
+
+/// Renders the messages an in-game command sent back, fails if any of them is
+/// an error
+fn command_output(
+    localization: Option<&LocalizationHandle>,
+    output: &[ChatMsg],
+) -> Result<String, String> {
+    let localization = localization.map(LocalizationHandle::read);
+    let text = output
+        .iter()
+        .map(|msg| match &localization {
+            Some(localization) => localization.get_content(msg.content()),
+            None => msg
+                .content()
+                .as_plain()
+                .map_or_else(|| format!("{:?}", msg.content()), str::to_owned),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    if output
+        .iter()
+        .any(|msg| matches!(msg.chat_type, ChatType::CommandError))
+    {
+        Err(text)
+    } else if text.is_empty() {
+        Ok("The command didn't send any output".to_owned())
+    } else {
+        Ok(text)
+    }
+}
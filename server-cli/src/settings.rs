@@ -1,3 +1,4 @@
+use common::comp::AdminRole;
 use serde::{Deserialize, Serialize};
 use std::{
     fs,
@@ -34,10 +35,15 @@ pub struct Settings {
     /// SECRET API HEADER used to access the chat api, if disabled the API is
     /// unreachable
     pub web_chat_secret: Option<String>,
+    /// SECRET API HEADER used to access the /admin api, if disabled the API is
+    /// unreachable
+    pub web_admin_secret: Option<String>,
     /// public SECRET API HEADER used to access the /ui_api, if disabled the API
     /// is reachable localhost only (by /ui)
     pub ui_api_secret: Option<String>,
     pub shutdown_signals: Vec<ShutdownSignal>,
+    /// Role the console has when running in-game commands with `run`
+    pub console_role: AdminRole,
 }
 
 impl Default for Settings {
@@ -47,12 +53,14 @@ impl Default for Settings {
             update_shutdown_message: "The server is restarting for an update".to_owned(),
             web_address: SocketAddr::from((Ipv4Addr::LOCALHOST, 14005)),
             web_chat_secret: None,
+            web_admin_secret: None,
             ui_api_secret: None,
             shutdown_signals: if cfg!(not(target_os = "windows")) {
                 vec![ShutdownSignal::SIGUSR1]
             } else {
                 Vec::new()
             },
+            console_role: AdminRole::Admin,
         }
     }
 }
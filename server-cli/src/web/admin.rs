@@ -0,0 +1,258 @@
+//! JSON API for external tools (dashboards, bots) to administrate the server.
+//! Every request needs the `X-Secret-Token` header, the API is disabled when no
+//! `web_admin_secret` is configured.
+use crate::{
+    cli::{Message, MessageReturn, Shutdown, Whitelist},
+    web::{
+        auth::{IpAddresses, SecretToken, log_users, validate_secret},
+        ui::api::UiRequestSender,
+    },
+};
+use axum::{
+    Json, Router,
+    extract::State,
+    response::IntoResponse,
+    routing::{get, post},
+};
+use hyper::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub fn router(web_ui_request_s: UiRequestSender, secret_token: Option<String>) -> Router {
+    let token = SecretToken { secret_token };
+    let ip_addrs = IpAddresses::new("/admin");
+    Router::new()
+        .route("/players", get(players))
+        .route("/kick", post(kick))
+        .route("/ban", post(ban))
+        .route("/unban", post(unban))
+        .route("/whitelist/add", post(whitelist_add))
+        .route("/whitelist/remove", post(whitelist_remove))
+        .route("/broadcast", post(broadcast))
+        .route("/shutdown", post(shutdown))
+        .route("/shutdown/cancel", post(cancel_shutdown))
+        .route("/reload_settings", post(reload_settings))
+        .layer(axum::middleware::from_fn_with_state(ip_addrs, log_users))
+        .layer(axum::middleware::from_fn_with_state(token, validate_secret))
+        .with_state(web_ui_request_s)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Feedback {
+    Message(String),
+    Error(String),
+}
+
+/// Forwards `msg` to the server and waits for the answer
+async fn request(
+    web_ui_request_s: &UiRequestSender,
+    msg: Message,
+) -> Result<MessageReturn, StatusCode> {
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+    web_ui_request_s
+        .send((msg, sender))
+        .await
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)?;
+    receiver
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// Forwards a command that reports back with [`MessageReturn::Feedback`]
+async fn command(
+    web_ui_request_s: &UiRequestSender,
+    msg: Message,
+) -> Result<(StatusCode, Json<Feedback>), StatusCode> {
+    match request(web_ui_request_s, msg).await? {
+        MessageReturn::Feedback(Ok(msg)) => Ok((StatusCode::OK, Json(Feedback::Message(msg)))),
+        MessageReturn::Feedback(Err(err)) => {
+            Ok((StatusCode::UNPROCESSABLE_ENTITY, Json(Feedback::Error(err))))
+        },
+        _ => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+async fn players(
+    State(web_ui_request_s): State<UiRequestSender>,
+) -> Result<impl IntoResponse, StatusCode> {
+    match request(&web_ui_request_s, Message::ListPlayerDetails).await? {
+        MessageReturn::PlayerDetails(players) => Ok(Json(players)),
+        _ => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+#[derive(Deserialize)]
+struct KickBody {
+    username: String,
+    #[serde(default)]
+    reason: String,
+}
+
+async fn kick(
+    State(web_ui_request_s): State<UiRequestSender>,
+    Json(payload): Json<KickBody>,
+) -> Result<impl IntoResponse, StatusCode> {
+    command(&web_ui_request_s, Message::Kick {
+        username: payload.username,
+        reason: payload.reason,
+    })
+    .await
+}
+
+#[derive(Deserialize)]
+struct BanBody {
+    username: String,
+    #[serde(default)]
+    reason: String,
+    /// Permanent if omitted
+    duration_secs: Option<u64>,
+    /// Also ban the IP address of the player
+    #[serde(default)]
+    ip: bool,
+    /// Replace an existing ban
+    #[serde(default)]
+    overwrite: bool,
+}
+
+async fn ban(
+    State(web_ui_request_s): State<UiRequestSender>,
+    Json(payload): Json<BanBody>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let duration = payload
+        .duration_secs
+        .map(|secs| Duration::from_secs(secs).into());
+    let msg = if payload.ip {
+        Message::BanIp {
+            username: payload.username,
+            reason: payload.reason,
+            duration,
+            overwrite: payload.overwrite,
+        }
+    } else {
+        Message::Ban {
+            username: payload.username,
+            reason: payload.reason,
+            duration,
+            overwrite: payload.overwrite,
+        }
+    };
+    command(&web_ui_request_s, msg).await
+}
+
+#[derive(Deserialize)]
+struct UnbanBody {
+    username: String,
+    /// Only lift the IP ban
+    #[serde(default)]
+    ip: bool,
+}
+
+async fn unban(
+    State(web_ui_request_s): State<UiRequestSender>,
+    Json(payload): Json<UnbanBody>,
+) -> Result<impl IntoResponse, StatusCode> {
+    command(&web_ui_request_s, Message::Unban {
+        username: payload.username,
+        ip: payload.ip,
+    })
+    .await
+}
+
+#[derive(Deserialize)]
+struct UsernameBody {
+    username: String,
+}
+
+async fn whitelist_add(
+    State(web_ui_request_s): State<UiRequestSender>,
+    Json(payload): Json<UsernameBody>,
+) -> Result<impl IntoResponse, StatusCode> {
+    command(&web_ui_request_s, Message::Whitelist {
+        command: Whitelist::Add {
+            username: payload.username,
+        },
+    })
+    .await
+}
+
+async fn whitelist_remove(
+    State(web_ui_request_s): State<UiRequestSender>,
+    Json(payload): Json<UsernameBody>,
+) -> Result<impl IntoResponse, StatusCode> {
+    command(&web_ui_request_s, Message::Whitelist {
+        command: Whitelist::Remove {
+            username: payload.username,
+        },
+    })
+    .await
+}
+
+#[derive(Deserialize)]
+struct BroadcastBody {
+    msg: String,
+}
+
+async fn broadcast(
+    State(web_ui_request_s): State<UiRequestSender>,
+    Json(payload): Json<BroadcastBody>,
+) -> Result<impl IntoResponse, StatusCode> {
+    // The server doesn't answer this message, so don't wait for it
+    let (dummy_s, _) = tokio::sync::oneshot::channel();
+    web_ui_request_s
+        .send((Message::SendGlobalMsg { msg: payload.msg }, dummy_s))
+        .await
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+#[derive(Deserialize)]
+struct ShutdownBody {
+    seconds: u64,
+    #[serde(default = "default_shutdown_reason")]
+    reason: String,
+}
+
+fn default_shutdown_reason() -> String { "The server is shutting down".to_owned() }
+
+async fn shutdown(
+    State(web_ui_request_s): State<UiRequestSender>,
+    Json(payload): Json<ShutdownBody>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let (dummy_s, _) = tokio::sync::oneshot::channel();
+    web_ui_request_s
+        .send((
+            Message::Shutdown {
+                command: Shutdown::Graceful {
+                    seconds: payload.seconds,
+                    reason: payload.reason,
+                },
+            },
+            dummy_s,
+        ))
+        .await
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn cancel_shutdown(
+    State(web_ui_request_s): State<UiRequestSender>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let (dummy_s, _) = tokio::sync::oneshot::channel();
+    web_ui_request_s
+        .send((
+            Message::Shutdown {
+                command: Shutdown::Cancel,
+            },
+            dummy_s,
+        ))
+        .await
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn reload_settings(
+    State(web_ui_request_s): State<UiRequestSender>,
+) -> Result<impl IntoResponse, StatusCode> {
+    command(&web_ui_request_s, Message::ReloadSettings).await
+}
@@ -0,0 +1,82 @@
+//! Middleware shared by the APIs of the web server
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use hyper::StatusCode;
+use std::{
+    collections::HashSet,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+use tokio::sync::Mutex;
+
+pub const X_SECRET_TOKEN: &str = "X-Secret-Token";
+
+/// Keep Size small, so we dont have to Clone much for each request.
+#[derive(Clone)]
+pub struct SecretToken {
+    /// The endpoint is disabled when no token is configured
+    pub secret_token: Option<String>,
+}
+
+/// Requires the `X-Secret-Token` header to match the configured token
+pub async fn validate_secret(
+    State(token): State<SecretToken>,
+    req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    // check if this endpoint is disabled
+    let secret_token = token.secret_token.ok_or(StatusCode::METHOD_NOT_ALLOWED)?;
+
+    let session_cookie = req
+        .headers()
+        .get(X_SECRET_TOKEN)
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    if session_cookie.as_bytes() != secret_token.as_bytes() {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    Ok(next.run(req).await)
+}
+
+#[derive(Clone)]
+pub struct IpAddresses {
+    /// Shown in the log, e.g. `/chat`
+    endpoint: &'static str,
+    users: Arc<Mutex<HashSet<IpAddr>>>,
+}
+
+impl IpAddresses {
+    pub fn new(endpoint: &'static str) -> Self {
+        Self {
+            endpoint,
+            users: Arc::default(),
+        }
+    }
+}
+
+/// Logs each new IP address that accesses this API authenticated
+pub async fn log_users(
+    State(ip_addresses): State<IpAddresses>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let endpoint = ip_addresses.endpoint;
+    let mut ip_addresses = ip_addresses.users.lock().await;
+    let ip_addr = addr.ip();
+    if !ip_addresses.contains(&ip_addr) {
+        ip_addresses.insert(ip_addr);
+        let users_so_far = ip_addresses.len();
+        tracing::info!(
+            ?ip_addr,
+            ?users_so_far,
+            "Is accessing the {} endpoint",
+            endpoint
+        );
+    }
+    Ok(next.run(req).await)
+}
@@ -1,79 +1,46 @@
+use crate::{
+    cli::Message,
+    web::{
+        auth::{IpAddresses, SecretToken, log_users, validate_secret},
+        ui::api::UiRequestSender,
+    },
+};
 use axum::{
     Json, Router,
-    extract::{ConnectInfo, Query, Request, State},
-    middleware::Next,
-    response::{IntoResponse, Response},
-    routing::get,
+    extract::{Query, State},
+    response::{
+        IntoResponse,
+        sse::{Event, KeepAlive, Sse},
+    },
+    routing::{get, post},
 };
 use chrono::DateTime;
 use hyper::StatusCode;
 use serde::{Deserialize, Deserializer};
 use server::chat::ChatCache;
-use std::{
-    collections::HashSet,
-    net::{IpAddr, SocketAddr},
-    str::FromStr,
-    sync::Arc,
+use std::{convert::Infallible, str::FromStr};
+use tokio_stream::{
+    Stream, StreamExt,
+    wrappers::{BroadcastStream, errors::BroadcastStreamRecvError},
 };
-use tokio::sync::Mutex;
 
-/// Keep Size small, so we dont have to Clone much for each request.
-#[derive(Clone)]
-struct ChatToken {
+pub fn router(
+    cache: ChatCache,
+    web_ui_request_s: UiRequestSender,
     secret_token: Option<String>,
-}
-
-#[derive(Clone, Default)]
-struct IpAddresses {
-    users: Arc<Mutex<HashSet<IpAddr>>>,
-}
-
-async fn validate_secret(
-    State(token): State<ChatToken>,
-    req: Request,
-    next: Next,
-) -> Result<Response, StatusCode> {
-    // check if this endpoint is disabled
-    let secret_token = token.secret_token.ok_or(StatusCode::METHOD_NOT_ALLOWED)?;
-
-    pub const X_SECRET_TOKEN: &str = "X-Secret-Token";
-    let session_cookie = req
-        .headers()
-        .get(X_SECRET_TOKEN)
-        .ok_or(StatusCode::UNAUTHORIZED)?;
-
-    if session_cookie.as_bytes() != secret_token.as_bytes() {
-        return Err(StatusCode::UNAUTHORIZED);
-    }
-
-    Ok(next.run(req).await)
-}
-
-/// Logs each new IP address that accesses this API authenticated
-async fn log_users(
-    State(ip_addresses): State<IpAddresses>,
-    ConnectInfo(addr): ConnectInfo<SocketAddr>,
-    req: Request,
-    next: Next,
-) -> Result<Response, StatusCode> {
-    let mut ip_addresses = ip_addresses.users.lock().await;
-    let ip_addr = addr.ip();
-    if !ip_addresses.contains(&ip_addr) {
-        ip_addresses.insert(ip_addr);
-        let users_so_far = ip_addresses.len();
-        tracing::info!(?ip_addr, ?users_so_far, "Is accessing the /chat endpoint");
-    }
-    Ok(next.run(req).await)
-}
-
-pub fn router(cache: ChatCache, secret_token: Option<String>) -> Router {
-    let token = ChatToken { secret_token };
-    let ip_addrs = IpAddresses::default();
+) -> Router {
+    let token = SecretToken { secret_token };
+    let ip_addrs = IpAddresses::new("/chat");
+    let send = Router::new()
+        .route("/send", post(send))
+        .with_state(web_ui_request_s);
     Router::new()
         .route("/history", get(history))
+        .route("/stream", get(stream))
+        .with_state(cache)
+        .merge(send)
         .layer(axum::middleware::from_fn_with_state(ip_addrs, log_users))
         .layer(axum::middleware::from_fn_with_state(token, validate_secret))
-        .with_state(cache)
 }
 
 #[derive(Debug, Deserialize)]
@@ -120,3 +87,56 @@ async fn history(
     };
     Ok(Json(filtered))
 }
+
+/// Pushes every new message as a server-sent event with the message as JSON.
+/// If the client can't keep up, a `lagged` event with the number of skipped
+/// messages is sent instead, which can be fetched from `/history`.
+async fn stream(
+    State(cache): State<ChatCache>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let messages = BroadcastStream::new(cache.subscribe()).map(|msg| {
+        Ok(match msg {
+            Ok(msg) => Event::default()
+                .event("message")
+                .json_data(msg)
+                .unwrap_or_else(|e| {
+                    tracing::warn!(?e, "could not serialize chat message");
+                    Event::default().event("error")
+                }),
+            Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                Event::default().event("lagged").data(skipped.to_string())
+            },
+        })
+    });
+    Sse::new(messages).keep_alive(KeepAlive::default())
+}
+
+#[derive(Deserialize)]
+struct SendBody {
+    /// Shown in front of the message, e.g. the name of the user on the other
+    /// side of a bridge
+    #[serde(default)]
+    author: Option<String>,
+    msg: String,
+}
+
+/// Sends a message to the world chat
+async fn send(
+    State(web_ui_request_s): State<UiRequestSender>,
+    Json(payload): Json<SendBody>,
+) -> Result<impl IntoResponse, StatusCode> {
+    if payload.msg.is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let msg = match payload.author {
+        Some(author) => format!("[{author}] {}", payload.msg),
+        None => payload.msg,
+    };
+    // The server doesn't answer this message, so don't wait for it
+    let (dummy_s, _) = tokio::sync::oneshot::channel();
+    web_ui_request_s
+        .send((Message::SendGlobalMsg { msg }, dummy_s))
+        .await
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)?;
+    Ok(StatusCode::ACCEPTED)
+}
@@ -7,6 +7,8 @@ use prometheus::{Registry, TextEncoder};
 use server::chat::ChatCache;
 use std::{future::IntoFuture, net::SocketAddr};
 
+mod admin;
+mod auth;
 mod chat;
 mod ui;
 
@@ -14,6 +16,7 @@ pub async fn run<S, F, R>(
     registry: R,
     cache: ChatCache,
     chat_secret: Option<String>,
+    admin_secret: Option<String>,
     ui_secret: String,
     web_ui_request_s: UiRequestSender,
     addr: S,
@@ -29,7 +32,14 @@ where
         .with_state(registry.deref().clone());
 
     let app = Router::new()
-        .nest("/chat/v1", chat::router(cache, chat_secret))
+        .nest(
+            "/chat/v1",
+            chat::router(cache, web_ui_request_s.clone(), chat_secret),
+        )
+        .nest(
+            "/admin/v1",
+            admin::router(web_ui_request_s.clone(), admin_secret),
+        )
         .nest(
             "/ui_api/v1",
             ui::api::router(web_ui_request_s, ui_secret.clone()),
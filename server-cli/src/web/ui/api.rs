@@ -1,7 +1,10 @@
-use crate::cli::{Message, MessageReturn};
+use crate::{
+    cli::{Message, MessageReturn},
+    web::auth::{IpAddresses, X_SECRET_TOKEN, log_users},
+};
 use axum::{
     Json, Router,
-    extract::{ConnectInfo, Request, State},
+    extract::{Request, State},
     http::header::COOKIE,
     middleware::Next,
     response::{IntoResponse, Response},
@@ -9,12 +12,6 @@ use axum::{
 };
 use hyper::StatusCode;
 use serde::Deserialize;
-use std::{
-    collections::HashSet,
-    net::{IpAddr, SocketAddr},
-    sync::Arc,
-};
-use tokio::sync::Mutex;
 
 /// Keep Size small, so we dont have to Clone much for each request.
 #[derive(Clone)]
@@ -25,11 +22,6 @@ struct UiApiToken {
 pub(crate) type UiRequestSender =
     tokio::sync::mpsc::Sender<(Message, tokio::sync::oneshot::Sender<MessageReturn>)>;
 
-#[derive(Clone, Default)]
-struct IpAddresses {
-    users: Arc<Mutex<HashSet<IpAddr>>>,
-}
-
 async fn validate_secret(
     State(token): State<UiApiToken>,
     req: Request,
@@ -37,7 +29,6 @@ async fn validate_secret(
 ) -> Result<Response, StatusCode> {
     let session_cookie = req.headers().get(COOKIE).ok_or(StatusCode::UNAUTHORIZED)?;
 
-    pub const X_SECRET_TOKEN: &str = "X-Secret-Token";
     let expected = format!("{X_SECRET_TOKEN}={}", token.secret_token);
 
     if session_cookie.as_bytes() != expected.as_bytes() {
@@ -47,28 +38,11 @@ async fn validate_secret(
     Ok(next.run(req).await)
 }
 
-/// Logs each new IP address that accesses this API authenticated
-async fn log_users(
-    State(ip_addresses): State<IpAddresses>,
-    ConnectInfo(addr): ConnectInfo<SocketAddr>,
-    req: Request,
-    next: Next,
-) -> Result<Response, StatusCode> {
-    let mut ip_addresses = ip_addresses.users.lock().await;
-    let ip_addr = addr.ip();
-    if !ip_addresses.contains(&ip_addr) {
-        ip_addresses.insert(ip_addr);
-        let users_so_far = ip_addresses.len();
-        tracing::info!(?ip_addr, ?users_so_far, "Is accessing the /ui_api endpoint");
-    }
-    Ok(next.run(req).await)
-}
-
 //TODO: do security audit before we extend this api with more security relevant
 // functionality (e.g. account management)
 pub fn router(web_ui_request_s: UiRequestSender, secret_token: String) -> Router {
     let token = UiApiToken { secret_token };
-    let ip_addrs = IpAddresses::default();
+    let ip_addrs = IpAddresses::new("/ui_api");
     Router::new()
         .route("/players", get(players))
         .route("/logs", get(logs))
@@ -30,6 +30,8 @@ pub enum ActionErr {
     BannedWord,
     TooLong,
     SpamMuted(Duration),
+    /// Muted by a moderator, with the remaining time if the mute expires
+    Muted(Option<Duration>),
 }
 
 impl fmt::Display for ActionErr {
@@ -50,6 +52,12 @@ impl fmt::Display for ActionErr {
                 "You have sent too many messages and are muted for {} seconds.",
                 dur.as_secs_f32() as u64
             ),
+            ActionErr::Muted(None) => write!(f, "You have been muted by a moderator."),
+            ActionErr::Muted(Some(dur)) => write!(
+                f,
+                "You have been muted by a moderator for {} more seconds.",
+                dur.as_secs_f32() as u64
+            ),
         }
     }
 }
@@ -58,6 +66,10 @@ pub struct AutoMod {
     settings: ModerationSettings,
     censor: Arc<Censor>,
     players: HashMap<Uuid, PlayerState>,
+    /// Players muted by a moderator, until the given time or indefinitely if
+    /// `None`. Unlike automod mutes these also apply to private messages and
+    /// admins.
+    mutes: HashMap<Uuid, Option<Instant>>,
 }
 
 impl AutoMod {
@@ -79,11 +91,22 @@ impl AutoMod {
             settings: settings.clone(),
             censor,
             players: HashMap::default(),
+            mutes: HashMap::default(),
         }
     }
 
     pub fn enabled(&self) -> bool { self.settings.automod }
 
+    /// Mute a player for `duration`, or indefinitely if `None`. Replaces any
+    /// previous mute of the player.
+    pub fn mute(&mut self, player: Uuid, duration: Option<Duration>) {
+        let until = duration.and_then(|duration| Instant::now().checked_add(duration));
+        self.mutes.insert(player, until);
+    }
+
+    /// Returns whether the player was muted
+    pub fn unmute(&mut self, player: Uuid) -> bool { self.mutes.remove(&player).is_some() }
+
     fn player_mut(&mut self, player: Uuid) -> &mut PlayerState {
         self.players.entry(player).or_default()
     }
@@ -99,6 +122,12 @@ impl AutoMod {
         // TODO: Consider using grapheme cluster count instead of size in bytes
         if msg.len() > ChatMsg::MAX_BYTES_PLAYER_CHAT_MSG {
             Err(ActionErr::TooLong)
+        } else if let Some(until) = self.mutes.get(&player).copied()
+            && until.is_none_or(|until| until > now)
+        {
+            Err(ActionErr::Muted(
+                until.map(|until| until.saturating_duration_since(now)),
+            ))
         } else if !self.settings.automod
             // Is this a private chat message?
             || chat_type.is_private().unwrap_or(true)
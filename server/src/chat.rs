@@ -62,12 +62,14 @@ type MessagesStore = Arc<Mutex<VecDeque<ChatMessage>>>;
 #[derive(Clone)]
 pub struct ChatCache {
     pub messages: MessagesStore,
+    live: tokio::sync::broadcast::Sender<ChatMessage>,
 }
 
 /// Will internally run on tokio and take stress from main loop
 struct ChatForwarder {
     chat_r: tokio::sync::mpsc::Receiver<ChatMessage>,
     messages: MessagesStore,
+    live: tokio::sync::broadcast::Sender<ChatMessage>,
     keep_duration: chrono::Duration,
 }
 
@@ -219,6 +221,8 @@ impl ChatExporter {
 impl ChatForwarder {
     async fn run(mut self) {
         while let Some(msg) = self.chat_r.recv().await {
+            // Fails only if nobody is listening right now
+            let _ = self.live.send(msg.clone());
             let drop_older_than = msg.time.sub(self.keep_duration);
             let mut messages = self.messages.lock().await;
             while let Some(msg) = messages.front()
@@ -241,6 +245,7 @@ impl ChatCache {
     pub fn new(keep_duration: Duration, runtime: &tokio::runtime::Runtime) -> (Self, ChatExporter) {
         const BUFFER_SIZE: usize = 1_000;
         let (chat_s, chat_r) = tokio::sync::mpsc::channel(BUFFER_SIZE);
+        let (live, _) = tokio::sync::broadcast::channel(BUFFER_SIZE);
         let messages: Arc<Mutex<VecDeque<ChatMessage>>> = Default::default();
         let messages_clone = Arc::clone(&messages);
         let keep_duration = chrono::Duration::from_std(keep_duration).unwrap();
@@ -249,10 +254,16 @@ impl ChatCache {
             keep_duration,
             chat_r,
             messages: messages_clone,
+            live: live.clone(),
         };
 
         runtime.spawn(worker.run().instrument(info_span!("chat_forwarder")));
 
-        (Self { messages }, ChatExporter { chat_s })
+        (Self { messages, live }, ChatExporter { chat_s })
+    }
+
+    /// Receive every message exported from now on, as they arrive
+    pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<ChatMessage> {
+        self.live.subscribe()
     }
 }
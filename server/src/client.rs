@@ -1,8 +1,16 @@
-use common_net::msg::{ClientType, ServerGeneral, ServerMsg};
+use common_net::{
+    msg::{ClientType, MsgKind, PingMsg, ServerGeneral, ServerMsg},
+    sync::{PhysicsBaseline, PhysicsState, PhysicsSyncPackage},
+};
+use hashbrown::HashMap;
 use network::{ConnectAddr, Message, Participant, Stream, StreamError, StreamParams};
 use serde::{Serialize, de::DeserializeOwned};
 use specs::Component;
-use std::{net::SocketAddr, sync::atomic::AtomicBool};
+use std::{
+    net::SocketAddr,
+    sync::{Mutex, atomic::AtomicBool},
+    time::{Duration, Instant},
+};
 
 /// Client handles ALL network related information of everything that connects
 /// to the server Client DOES NOT handle game states
@@ -16,6 +24,10 @@ pub struct Client {
     pub current_ip_addrs: Vec<SocketAddr>,
     connected_from_addr: ConnectAddr,
     pub last_ping: f64,
+    /// When the server last sent a ping, and whether the client answered it
+    ping_sent: Instant,
+    ping_answered: bool,
+    rtt: Option<Duration>,
     pub login_msg_sent: AtomicBool,
     pub locale: Option<String>,
 
@@ -35,13 +47,32 @@ pub struct Client {
     character_screen_stream_params: StreamParams,
     in_game_stream_params: StreamParams,
     terrain_stream_params: StreamParams,
+
+    traffic: Mutex<TrafficStats>,
+    physics_baseline: Mutex<PhysicsBaseline>,
 }
 
 pub struct PreparedMsg {
     stream_id: u8,
+    kind: &'static str,
     message: Message,
 }
 
+/// Bytes of serialized and compressed messages, by [`MsgKind`]
+#[derive(Clone, Debug, Default)]
+pub struct Traffic {
+    pub sent: HashMap<&'static str, u64>,
+    pub received: HashMap<&'static str, u64>,
+}
+
+#[derive(Default)]
+struct TrafficStats {
+    /// Since the client connected
+    total: Traffic,
+    /// Since the last call of [`Client::take_unreported_traffic`]
+    unreported: Traffic,
+}
+
 impl Component for Client {
     type Storage = specs::DenseVecStorage<Self>;
 }
@@ -72,6 +103,9 @@ impl Client {
             current_ip_addrs: connected_from.socket_addr().into_iter().collect(),
             connected_from_addr: connected_from,
             last_ping,
+            ping_sent: Instant::now(),
+            ping_answered: true,
+            rtt: None,
             locale,
             login_msg_sent: AtomicBool::new(false),
             general_stream,
@@ -86,9 +120,57 @@ impl Client {
             character_screen_stream_params,
             in_game_stream_params,
             terrain_stream_params,
+            traffic: Mutex::new(TrafficStats::default()),
+            physics_baseline: Mutex::new(PhysicsBaseline::default()),
         }
     }
 
+    /// Round trip time of the last ping the client answered, `None` until the
+    /// first answer
+    pub fn rtt(&self) -> Option<Duration> { self.rtt }
+
+    /// Time since the last ping was sent, if the client answered it already
+    pub(crate) fn since_answered_ping(&self) -> Option<Duration> {
+        self.ping_answered.then(|| self.ping_sent.elapsed())
+    }
+
+    /// Pings the client, the round trip time is measured from the first ping
+    /// that is not answered yet
+    pub(crate) fn send_ping(&mut self) {
+        if self.ping_answered {
+            self.ping_sent = Instant::now();
+            self.ping_answered = false;
+        }
+        self.send_fallible(PingMsg::Ping);
+    }
+
+    /// Called when the client answers a ping. The round trip time includes the
+    /// time until the server handles the answer, so up to a tick.
+    pub(crate) fn pong_received(&mut self) {
+        if !self.ping_answered {
+            self.rtt = Some(self.ping_sent.elapsed());
+            self.ping_answered = true;
+        }
+    }
+
+    /// Everything sent to and received from this client so far
+    pub fn traffic(&self) -> Traffic { self.traffic.lock().unwrap().total.clone() }
+
+    /// Traffic since the last call, used to update the metrics
+    pub(crate) fn take_unreported_traffic(&self) -> Traffic {
+        core::mem::take(&mut self.traffic.lock().unwrap().unreported)
+    }
+
+    /// Delta encodes the physics of other entities against the latest
+    /// snapshot this client acknowledged
+    pub(crate) fn encode_physics_sync(&self, states: Vec<PhysicsState>) -> PhysicsSyncPackage {
+        self.physics_baseline.lock().unwrap().encode(states)
+    }
+
+    pub(crate) fn acknowledge_physics_sync(&self, seq: u32) {
+        self.physics_baseline.lock().unwrap().acknowledge(seq);
+    }
+
     pub(crate) fn connected_from_addr(&self) -> &ConnectAddr { &self.connected_from_addr }
 
     pub(crate) fn send<M: Into<ServerMsg>>(&self, msg: M) -> Result<(), StreamError> {
@@ -163,14 +245,23 @@ impl Client {
             4 => self.ping_stream.send_raw(&msg.message),
             5 => self.terrain_stream.send_raw(&msg.message),
             _ => unreachable!("invalid stream id"),
-        }
+        }?;
+        let size = msg.message.size() as u64;
+        let mut traffic = self.traffic.lock().unwrap();
+        *traffic.total.sent.entry(msg.kind).or_default() += size;
+        *traffic.unreported.sent.entry(msg.kind).or_default() += size;
+        Ok(())
     }
 
     pub(crate) fn prepare<M: Into<ServerMsg>>(&self, msg: M) -> PreparedMsg {
-        match msg.into() {
-            ServerMsg::Info(m) => PreparedMsg::new(0, &m, &self.register_stream_params),
-            ServerMsg::Init(m) => PreparedMsg::new(0, &m, &self.register_stream_params),
-            ServerMsg::RegisterAnswer(m) => PreparedMsg::new(0, &m, &self.register_stream_params),
+        let msg = msg.into();
+        let kind = msg.kind();
+        match msg {
+            ServerMsg::Info(m) => PreparedMsg::new(0, kind, &m, &self.register_stream_params),
+            ServerMsg::Init(m) => PreparedMsg::new(0, kind, &m, &self.register_stream_params),
+            ServerMsg::RegisterAnswer(m) => {
+                PreparedMsg::new(0, kind, &m, &self.register_stream_params)
+            },
             ServerMsg::General(g) => {
                 match g {
                     // Character Screen related
@@ -181,7 +272,7 @@ impl Client {
                     | ServerGeneral::CharacterEdited(_)
                     | ServerGeneral::CharacterSuccess
                     | ServerGeneral::SpectatorSuccess(_) => {
-                        PreparedMsg::new(1, &g, &self.character_screen_stream_params)
+                        PreparedMsg::new(1, kind, &g, &self.character_screen_stream_params)
                     },
                     // In-game related
                     ServerGeneral::GroupUpdate(_)
@@ -192,6 +283,9 @@ impl Client {
                     | ServerGeneral::InventoryUpdate(_, _)
                     | ServerGeneral::GroupInventoryUpdate(_, _)
                     | ServerGeneral::Dialogue(_, _)
+                    | ServerGeneral::QuestLog(_)
+                    | ServerGeneral::QuestUpdate(_)
+                    | ServerGeneral::QuestRemoved(_)
                     | ServerGeneral::SetViewDistance(_)
                     | ServerGeneral::Outcomes(_)
                     | ServerGeneral::Knockback(_)
@@ -204,13 +298,13 @@ impl Client {
                     | ServerGeneral::SpectatePosition(_)
                     | ServerGeneral::UpdateRecipes
                     | ServerGeneral::Gizmos(_) => {
-                        PreparedMsg::new(2, &g, &self.in_game_stream_params)
+                        PreparedMsg::new(2, kind, &g, &self.in_game_stream_params)
                     },
                     // Terrain
                     ServerGeneral::TerrainChunkUpdate { .. }
                     | ServerGeneral::LodZoneUpdate { .. }
                     | ServerGeneral::TerrainBlockUpdates(_) => {
-                        PreparedMsg::new(5, &g, &self.terrain_stream_params)
+                        PreparedMsg::new(5, kind, &g, &self.terrain_stream_params)
                     },
                     // Always possible
                     ServerGeneral::PlayerListUpdate(_)
@@ -220,17 +314,18 @@ impl Client {
                     | ServerGeneral::TimeOfDay(_, _, _, _)
                     | ServerGeneral::EntitySync(_)
                     | ServerGeneral::CompSync(_, _)
+                    | ServerGeneral::PhysicsSync(_)
                     | ServerGeneral::CreateEntity(_)
                     | ServerGeneral::DeleteEntity(_)
                     | ServerGeneral::Disconnect(_)
                     | ServerGeneral::Notification(_)
                     | ServerGeneral::SetPlayerRole(_)
                     | ServerGeneral::PluginData(_) => {
-                        PreparedMsg::new(3, &g, &self.general_stream_params)
+                        PreparedMsg::new(3, kind, &g, &self.general_stream_params)
                     },
                 }
             },
-            ServerMsg::Ping(m) => PreparedMsg::new(4, &m, &self.ping_stream_params),
+            ServerMsg::Ping(m) => PreparedMsg::new(4, kind, &m, &self.ping_stream_params),
         }
     }
 
@@ -248,30 +343,50 @@ impl Client {
         ) {
             unreachable!("You must not call this function without a terrain chunk update!")
         }
-        PreparedMsg::new(5, &terrain_chunk_update, params)
+        PreparedMsg::new(
+            5,
+            terrain_chunk_update.kind(),
+            &terrain_chunk_update,
+            params,
+        )
     }
 
-    pub(crate) fn recv<M: DeserializeOwned>(
+    pub(crate) fn recv<M: DeserializeOwned + MsgKind>(
         &mut self,
         stream_id: u8,
     ) -> Result<Option<M>, StreamError> {
         // TODO: are two systems using the same stream?? why is there contention here?
-        match stream_id {
-            0 => self.register_stream.try_recv(),
-            1 => self.character_screen_stream.try_recv(),
-            2 => self.in_game_stream.try_recv(),
-            3 => self.general_stream.try_recv(),
-            4 => self.ping_stream.try_recv(),
-            5 => self.terrain_stream.try_recv(),
+        let message = match stream_id {
+            0 => self.register_stream.try_recv_raw(),
+            1 => self.character_screen_stream.try_recv_raw(),
+            2 => self.in_game_stream.try_recv_raw(),
+            3 => self.general_stream.try_recv_raw(),
+            4 => self.ping_stream.try_recv_raw(),
+            5 => self.terrain_stream.try_recv_raw(),
             _ => unreachable!("invalid stream id"),
-        }
+        }?;
+        let Some(message) = message else {
+            return Ok(None);
+        };
+        let size = message.size() as u64;
+        let msg: M = message.deserialize()?;
+        let traffic = self.traffic.get_mut().unwrap();
+        *traffic.total.received.entry(msg.kind()).or_default() += size;
+        *traffic.unreported.received.entry(msg.kind()).or_default() += size;
+        Ok(Some(msg))
     }
 }
 
 impl PreparedMsg {
-    fn new<M: Serialize + ?Sized>(id: u8, msg: &M, stream_params: &StreamParams) -> PreparedMsg {
+    fn new<M: Serialize + ?Sized>(
+        id: u8,
+        kind: &'static str,
+        msg: &M,
+        stream_params: &StreamParams,
+    ) -> PreparedMsg {
         Self {
             stream_id: id,
+            kind,
             message: Message::serialize(&msg, stream_params.clone()),
         }
     }
@@ -6,8 +6,10 @@ use crate::weather::WeatherJob;
 use crate::{
     Server, Settings, StateExt,
     client::Client,
+    console::{CONSOLE_USERNAME, CONSOLE_UUID, ConsoleSession},
     location::Locations,
     login_provider::LoginProvider,
+    persistence::{character_loader::CharacterLoader, character_updater::CharacterUpdater},
     settings::{
         BanInfo, BanOperation, BanOperationError, EditableSetting, SettingError, WhitelistInfo,
         WhitelistRecord,
@@ -28,6 +30,7 @@ use common::{
     CachedSpatialGrid, Damage, DamageKind, Explosion, GroupTarget, LoadoutBuilder, RadiusEffect,
     assets,
     calendar::Calendar,
+    character::CharacterId,
     cmd::{
         AreaKind, BUFF_PACK, BUFF_PARSER, EntityTarget, KIT_MANIFEST_PATH, KitSpec,
         PRESET_MANIFEST_PATH, ServerChatCommand,
@@ -79,7 +82,13 @@ use humantime::Duration as HumanDuration;
 use rand::{RngExt, rng};
 use specs::{Builder, Entity as EcsEntity, Join, LendJoin, WorldExt, storage::StorageEntry};
 use std::{
-    fmt::Write, net::SocketAddr, num::NonZeroU32, ops::DerefMut, str::FromStr, sync::Arc,
+    fmt::Write,
+    net::SocketAddr,
+    num::NonZeroU32,
+    ops::DerefMut,
+    path::PathBuf,
+    str::FromStr,
+    sync::Arc,
     time::Duration,
 };
 use vek::*;
@@ -91,11 +100,29 @@ use common::comp::Alignment;
 use tracing::{error, info, warn};
 
 pub trait ChatCommandExt {
-    fn execute(&self, server: &mut Server, entity: EcsEntity, args: Vec<String>);
+    fn execute(&self, server: &mut Server, entity: EcsEntity, args: Vec<String>) {
+        self.execute_on(server, entity, entity, args)
+    }
+
+    /// Like [`Self::execute`], but the command acts on `target` like with
+    /// `/sudo`
+    fn execute_on(
+        &self,
+        server: &mut Server,
+        entity: EcsEntity,
+        target: EcsEntity,
+        args: Vec<String>,
+    );
 }
 impl ChatCommandExt for ServerChatCommand {
-    fn execute(&self, server: &mut Server, entity: EcsEntity, args: Vec<String>) {
-        if let Err(err) = do_command(server, entity, entity, args, self) {
+    fn execute_on(
+        &self,
+        server: &mut Server,
+        entity: EcsEntity,
+        target: EcsEntity,
+        args: Vec<String>,
+    ) {
+        if let Err(err) = do_command(server, entity, target, args, self) {
             server.notify_client(
                 entity,
                 ServerGeneral::server_msg(ChatType::CommandError, err),
@@ -167,6 +194,7 @@ fn do_command(
         ServerChatCommand::DropAll => handle_drop_all,
         ServerChatCommand::Dummy => handle_spawn_training_dummy,
         ServerChatCommand::Explosion => handle_explosion,
+        ServerChatCommand::ExportCharacter => handle_export_character,
         ServerChatCommand::Faction => handle_faction,
         ServerChatCommand::GiveItem => handle_give_item,
         ServerChatCommand::Gizmos => handle_gizmos,
@@ -179,6 +207,7 @@ fn do_command(
         ServerChatCommand::GroupLeave => handle_group_leave,
         ServerChatCommand::GroupPromote => handle_group_promote,
         ServerChatCommand::Health => handle_health,
+        ServerChatCommand::ImportCharacter => handle_import_character,
         ServerChatCommand::IntoNpc => handle_into_npc,
         ServerChatCommand::JoinFaction => handle_join_faction,
         ServerChatCommand::Jump => handle_jump,
@@ -312,6 +341,12 @@ fn socket_addr(server: &Server, entity: EcsEntity, descriptor: &str) -> CmdResul
 }
 
 fn real_role(server: &Server, uuid: Uuid, descriptor: &str) -> CmdResult<AdminRole> {
+    // The console isn't in the admin list, it has the role it was configured with
+    if uuid == CONSOLE_UUID
+        && let Some(session) = server.state.ecs().try_fetch::<ConsoleSession>()
+    {
+        return Ok(session.role);
+    }
     server
         .editable_settings()
         .admins
@@ -394,18 +429,10 @@ fn verify_above_role(
     reason: Content,
 ) -> CmdResult<()> {
     let client_temp = server.entity_admin_role(client);
-    let client_perm = server
-        .editable_settings()
-        .admins
-        .get(&client_uuid)
-        .map(|record| record.role);
+    let client_perm = real_role(server, client_uuid, "client").ok();
 
     let player_temp = server.entity_admin_role(player);
-    let player_perm = server
-        .editable_settings()
-        .admins
-        .get(&player_uuid)
-        .map(|record| record.role);
+    let player_perm = real_role(server, player_uuid, "player").ok();
 
     if client_perm > player_perm || client_perm == player_perm && client_temp > player_temp {
         Ok(())
@@ -463,6 +490,9 @@ fn uuid_to_username(
     fallback_entity: EcsEntity,
     uuid: Uuid,
 ) -> CmdResult<String> {
+    if uuid == CONSOLE_UUID {
+        return Ok(CONSOLE_USERNAME.to_owned());
+    }
     let make_err = || {
         Content::localized_with_args("command-uuid-username-unavailable", [(
             "uuid",
@@ -2598,6 +2628,7 @@ fn handle_spawn_campfire(
         .emit_now(CreateSpecialEntityEvent {
             pos: pos.0,
             entity: SpecialEntity::Waypoint,
+            anchor: None,
         });
 
     server.notify_client(
@@ -3837,6 +3868,52 @@ fn handle_explosion(
     Ok(())
 }
 
+fn character_file_path(server: &Server, file: &str) -> CmdResult<PathBuf> {
+    crate::persistence::character_file_path(&server.data_dir().path, file).ok_or_else(|| {
+        Content::localized_with_args("command-character_file-invalid", [("file", file)])
+    })
+}
+
+fn handle_export_character(
+    server: &mut Server,
+    client: EcsEntity,
+    _target: EcsEntity,
+    args: Vec<String>,
+    action: &ServerChatCommand,
+) -> CmdResult<()> {
+    let (Some(character_id), Some(file)) = parse_cmd_args!(args, i64, String) else {
+        return Err(action.help_content());
+    };
+    let path = character_file_path(server, &file)?;
+    // The result is reported once the character loader is done with it
+    server
+        .state
+        .ecs()
+        .read_resource::<CharacterLoader>()
+        .export_character(Some(client), CharacterId(character_id), path, file);
+    Ok(())
+}
+
+fn handle_import_character(
+    server: &mut Server,
+    client: EcsEntity,
+    _target: EcsEntity,
+    args: Vec<String>,
+    action: &ServerChatCommand,
+) -> CmdResult<()> {
+    let (Some(file), Some(player_uuid)) = parse_cmd_args!(args, String, Uuid) else {
+        return Err(action.help_content());
+    };
+    let path = character_file_path(server, &file)?;
+    // The result is reported once the character updater is done with it
+    server
+        .state
+        .ecs()
+        .write_resource::<CharacterUpdater>()
+        .import_character(Some(client), player_uuid.to_string(), path, file);
+    Ok(())
+}
+
 fn handle_set_waypoint(
     server: &mut Server,
     client: EcsEntity,
@@ -5094,7 +5171,11 @@ fn make_ban_info(server: &mut Server, client: EcsEntity, client_uuid: Uuid) -> C
     Ok(ban_info)
 }
 
-fn ban_end_date(
+/// A ban duration the end date of which overflows, so the ban is permanent,
+/// see [`ban_end_date`]
+pub(crate) const PERMANENT_BAN: &str = "1000000years";
+
+pub(crate) fn ban_end_date(
     now: chrono::DateTime<Utc>,
     parse_duration: Option<HumanDuration>,
 ) -> CmdResult<Option<chrono::DateTime<Utc>>> {
@@ -0,0 +1,447 @@
+//! Moderation actions performed from the server console.
+//!
+//! These run the equivalent chat commands on behalf of a virtual "Console"
+//! admin instead of a player, and return the messages the commands send back.
+//! Actions without a chat command report back with plain text instead.
+//!
+//! Any other chat command can be run through [`Server::run_console_command`].
+//!
+//! NOTE: Do *not* allow these to be called from anything that doesn't go
+//! through the CLI!
+
+use crate::{
+    Server,
+    automod::AutoMod,
+    cmd::{ChatCommandExt, PERMANENT_BAN},
+    login_provider::LoginProvider,
+    persistence::{self, character_loader::CharacterLoader, character_updater::CharacterUpdater},
+};
+use authc::Uuid;
+use common::{
+    character::CharacterId,
+    cmd::ServerChatCommand,
+    comp::{self, AdminRole, ChatMsg, ChatType},
+    resources::BattleMode,
+};
+use common_net::{
+    msg::{ServerGeneral, ServerMsg},
+    sync::WorldSyncExt,
+};
+use specs::{Builder, Entity as EcsEntity, Join, World, WorldExt};
+use std::{path::PathBuf, time::Duration};
+use tracing::warn;
+use vek::*;
+
+/// The name recorded for actions performed from the console
+pub const CONSOLE_USERNAME: &str = "Console";
+/// The UUID of the console admin, no real account can have it
+pub const CONSOLE_UUID: Uuid = Uuid::nil();
+
+/// Present while a chat command runs on behalf of the console, collects the
+/// messages the command sends back to it
+pub(crate) struct ConsoleSession {
+    pub entity: EcsEntity,
+    pub role: AdminRole,
+    pub output: Vec<ChatMsg>,
+}
+
+impl ConsoleSession {
+    /// Create the virtual console admin with `role`. It gets a `Uid` from the
+    /// allocator like any other entity, since commands look up the `Uid` of
+    /// the player running them.
+    fn start(ecs: &mut World, role: AdminRole) -> EcsEntity {
+        let entity = ecs
+            .create_entity_synced()
+            .with(comp::Player::new(
+                CONSOLE_USERNAME.to_owned(),
+                BattleMode::PvE,
+                CONSOLE_UUID,
+                None,
+            ))
+            .with(comp::Admin(role))
+            .build();
+        ecs.insert(ConsoleSession {
+            entity,
+            role,
+            output: Vec::new(),
+        });
+        entity
+    }
+
+    /// Keep a chat message sent to `entity` if it is the console admin
+    pub(crate) fn record(ecs: &World, entity: EcsEntity, msg: ServerMsg) {
+        if let Some(mut session) = ecs.try_fetch_mut::<ConsoleSession>()
+            && session.entity == entity
+            && let ServerMsg::General(ServerGeneral::ChatMsg(msg)) = msg
+        {
+            session.output.push(msg);
+        }
+    }
+
+    /// Delete the console admin, returns the messages sent to it
+    fn finish(ecs: &mut World) -> Vec<ChatMsg> {
+        let Some(session) = ecs.remove::<ConsoleSession>() else {
+            return Vec::new();
+        };
+        match ecs.uid_from_entity(session.entity) {
+            Some(uid) => ecs.delete_entity_and_clear_uid_mapping(uid),
+            None => warn!("The console entity has no uid"),
+        }
+        session.output
+    }
+}
+
+/// Where [`Server::teleport_player`] moves the player to
+pub enum TeleportDestination<'a> {
+    Position(Vec3<f32>),
+    Player(&'a str),
+}
+
+impl Server {
+    /// Run a chat command on behalf of a virtual "Console" admin with the
+    /// given role, returns the messages the command sent back.
+    ///
+    /// The console has no character, so commands acting on the player that
+    /// runs them fail unless they take another player as the target.
+    pub fn run_console_command(
+        &mut self,
+        role: AdminRole,
+        name: &str,
+        args: Vec<String>,
+    ) -> Result<Vec<ChatMsg>, String> {
+        let command = name
+            .parse::<ServerChatCommand>()
+            .map_err(|_| format!("Unknown command '/{name}'"))?;
+        Ok(self.run_as_console(role, |server, console| {
+            command.execute(server, console, args)
+        }))
+    }
+
+    /// Runs `f` with a virtual console entity that has `role`, returns the
+    /// messages sent to it
+    fn run_as_console(
+        &mut self,
+        role: AdminRole,
+        f: impl FnOnce(&mut Server, EcsEntity),
+    ) -> Vec<ChatMsg> {
+        let entity = ConsoleSession::start(self.state.ecs_mut(), role);
+        f(self, entity);
+        ConsoleSession::finish(self.state.ecs_mut())
+    }
+
+    /// Run a moderation command as a console admin
+    fn run_moderation_command(
+        &mut self,
+        command: ServerChatCommand,
+        args: Vec<String>,
+    ) -> Vec<ChatMsg> {
+        self.run_as_console(AdminRole::Admin, |server, console| {
+            command.execute(server, console, args)
+        })
+    }
+
+    fn console_username_to_uuid(&self, username: &str) -> Result<Uuid, String> {
+        self.state
+            .ecs()
+            .fetch::<LoginProvider>()
+            .username_to_uuid(username)
+            .map_err(|_| format!("Unable to determine UUID for username \"{username}\""))
+    }
+
+    fn online_player_by_alias(&self, alias: &str) -> Result<EcsEntity, String> {
+        let ecs = self.state.ecs();
+        (&ecs.entities(), &ecs.read_storage::<comp::Player>())
+            .join()
+            .find(|(_, player)| player.alias == alias)
+            .map(|(entity, _)| entity)
+            .ok_or_else(|| format!("Player \"{alias}\" is not online"))
+    }
+
+    pub fn kick_player(&mut self, username: &str, reason: &str) -> Vec<ChatMsg> {
+        self.run_moderation_command(ServerChatCommand::Kick, vec![
+            username.to_owned(),
+            reason.to_owned(),
+        ])
+    }
+
+    /// Ban a player for `duration`, or permanently if `None`, and kick them if
+    /// they are online
+    pub fn ban_player(
+        &mut self,
+        username: &str,
+        reason: &str,
+        duration: Option<Duration>,
+        overwrite: bool,
+    ) -> Vec<ChatMsg> {
+        self.run_moderation_command(
+            ServerChatCommand::Ban,
+            ban_args(username, reason, duration, overwrite),
+        )
+    }
+
+    /// Ban a player and the IP address they were last connected from. If the
+    /// address isn't known it will be banned the next time they try to log in.
+    pub fn ban_player_ip(
+        &mut self,
+        username: &str,
+        reason: &str,
+        duration: Option<Duration>,
+        overwrite: bool,
+    ) -> Vec<ChatMsg> {
+        self.run_moderation_command(
+            ServerChatCommand::BanIp,
+            ban_args(username, reason, duration, overwrite),
+        )
+    }
+
+    /// Lift the ban of a player, with `ip` only their IP ban is lifted
+    pub fn unban_player(&mut self, username: &str, ip: bool) -> Vec<ChatMsg> {
+        let command = if ip {
+            ServerChatCommand::UnbanIp
+        } else {
+            ServerChatCommand::Unban
+        };
+        self.run_moderation_command(command, vec![username.to_owned()])
+    }
+
+    pub fn whitelist_add(&mut self, username: &str) -> Vec<ChatMsg> {
+        self.run_moderation_command(ServerChatCommand::Whitelist, vec![
+            "add".to_owned(),
+            username.to_owned(),
+        ])
+    }
+
+    pub fn whitelist_remove(&mut self, username: &str) -> Vec<ChatMsg> {
+        self.run_moderation_command(ServerChatCommand::Whitelist, vec![
+            "remove".to_owned(),
+            username.to_owned(),
+        ])
+    }
+
+    pub fn teleport_player(
+        &mut self,
+        username: &str,
+        destination: TeleportDestination,
+    ) -> Result<Vec<ChatMsg>, String> {
+        let entity = self.online_player_by_alias(username)?;
+        let (command, args) = match destination {
+            TeleportDestination::Position(pos) => (ServerChatCommand::Goto, vec![
+                pos.x.to_string(),
+                pos.y.to_string(),
+                pos.z.to_string(),
+            ]),
+            TeleportDestination::Player(other) => (ServerChatCommand::Tp, vec![other.to_owned()]),
+        };
+        let mut output = self.run_as_console(AdminRole::Admin, |server, console| {
+            command.execute_on(server, console, entity, args)
+        });
+        // Teleporting only reports errors
+        if output.is_empty() {
+            output.push(ChatType::CommandInfo.into_plain_msg(format!("Teleported {username}")));
+        }
+        Ok(output)
+    }
+
+    /// Prevent a player from sending chat messages for `duration`, or until
+    /// they are unmuted if `None`. Mutes are not persisted across restarts.
+    pub fn mute_player(
+        &self,
+        username: &str,
+        duration: Option<Duration>,
+    ) -> Result<String, String> {
+        let uuid = self.console_username_to_uuid(username)?;
+        self.state
+            .ecs()
+            .write_resource::<AutoMod>()
+            .mute(uuid, duration);
+        Ok(match duration {
+            Some(duration) => format!(
+                "Muted {username} for {}",
+                humantime::format_duration(duration)
+            ),
+            None => format!("Muted {username}"),
+        })
+    }
+
+    pub fn unmute_player(&self, username: &str) -> Result<String, String> {
+        let uuid = self.console_username_to_uuid(username)?;
+        if self.state.ecs().write_resource::<AutoMod>().unmute(uuid) {
+            Ok(format!("Unmuted {username}"))
+        } else {
+            Err(format!("{username} is not muted"))
+        }
+    }
+
+    /// The message of the day in `locale`, or the default locale if `None`
+    pub fn motd(&self, locale: Option<&str>) -> Result<String, String> {
+        self.editable_settings()
+            .server_description
+            .get(locale)
+            .map(|description| description.motd.clone())
+            .ok_or_else(|| "No message of the day is set for this locale".to_owned())
+    }
+
+    /// Set the message of the day in `locale`, `None` clears it
+    pub fn set_motd(&mut self, locale: &str, motd: Option<String>) -> Vec<ChatMsg> {
+        self.run_moderation_command(
+            ServerChatCommand::SetMotd,
+            std::iter::once(locale.to_owned()).chain(motd).collect(),
+        )
+    }
+
+    /// Back up the database in the background, the result is logged
+    pub fn backup_database(&mut self) -> Result<String, String> {
+        let settings = self.settings().database_backups.clone();
+        self.database_backups
+            .start(&settings)
+            .map(|()| "Started a database backup, see the log for the result".to_owned())
+            .map_err(|e| e.to_string())
+    }
+
+    /// Write a character in the background to `file` in the character exports
+    /// folder of the data dir, from where it can be imported on any server.
+    /// The result is logged.
+    pub fn export_character(
+        &self,
+        character_id: CharacterId,
+        file: &str,
+    ) -> Result<String, String> {
+        let path = self.character_file_path(file)?;
+        self.state
+            .ecs()
+            .read_resource::<CharacterLoader>()
+            .export_character(None, character_id, path, file.to_owned());
+        Ok(format!(
+            "Exporting character {} to {file}, see the log for the result",
+            character_id.0
+        ))
+    }
+
+    /// Create a new character for the player with `player_uuid` in the
+    /// background, from a `file` written by [`Self::export_character`]. The
+    /// result is logged.
+    pub fn import_character(&self, file: &str, player_uuid: &str) -> Result<String, String> {
+        let path = self.character_file_path(file)?;
+        let player_uuid =
+            Uuid::parse_str(player_uuid).map_err(|e| format!("Invalid player uuid: {e}"))?;
+        self.state
+            .ecs()
+            .write_resource::<CharacterUpdater>()
+            .import_character(None, player_uuid.to_string(), path, file.to_owned());
+        Ok(format!("Importing {file}, see the log for the result"))
+    }
+
+    fn character_file_path(&self, file: &str) -> Result<PathBuf, String> {
+        persistence::character_file_path(&self.data_dir().path, file).ok_or_else(|| {
+            format!(
+                "'{file}' is not a file name, characters can only be exported to and imported \
+                 from the {} folder",
+                persistence::CHARACTER_EXPORTS_DIR
+            )
+        })
+    }
+}
+
+/// The arguments of the `/ban` and `/ban_ip` commands.
+///
+/// The duration is always given, otherwise a reason like "1h" would be parsed
+/// as the duration.
+fn ban_args(
+    username: &str,
+    reason: &str,
+    duration: Option<Duration>,
+    overwrite: bool,
+) -> Vec<String> {
+    let duration = match duration {
+        Some(duration) => humantime::format_duration(duration).to_string(),
+        None => PERMANENT_BAN.to_owned(),
+    };
+    vec![
+        username.to_owned(),
+        overwrite.to_string(),
+        duration,
+        reason.to_owned(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cmd::ban_end_date;
+    use common::{
+        comp::Content,
+        parse_cmd_args,
+        uid::{IdMaps, Uid},
+    };
+    use humantime::Duration as HumanDuration;
+
+    #[test]
+    fn character_files_stay_in_the_exports_folder() {
+        let data_dir = std::path::Path::new("data");
+        assert_eq!(
+            persistence::character_file_path(data_dir, "alice.ron"),
+            Some(
+                data_dir
+                    .join(persistence::CHARACTER_EXPORTS_DIR)
+                    .join("alice.ron")
+            )
+        );
+        for file in [
+            "",
+            ".",
+            "..",
+            "../alice.ron",
+            "exports/alice.ron",
+            "/etc/passwd",
+        ] {
+            assert_eq!(persistence::character_file_path(data_dir, file), None);
+        }
+    }
+
+    #[test]
+    fn ban_args_keep_the_reason() {
+        for (duration, permanent) in [(None, true), (Some(Duration::from_secs(3600)), false)] {
+            let args = ban_args("alice", "1h", duration, true);
+            let (Some(username), Some(overwrite), Some(duration), Some(reason)) =
+                parse_cmd_args!(args, String, bool, HumanDuration, String)
+            else {
+                panic!("ban arguments don't parse");
+            };
+            assert_eq!(username, "alice");
+            assert!(overwrite);
+            assert_eq!(reason, "1h");
+            let end_date = ban_end_date(chrono::Utc::now(), Some(duration)).unwrap();
+            assert_eq!(end_date.is_none(), permanent);
+        }
+    }
+
+    #[test]
+    fn console_session_collects_output_and_deletes_the_console() {
+        let mut ecs = World::new();
+        ecs.insert(IdMaps::new());
+        ecs.register::<Uid>();
+        ecs.register::<comp::Player>();
+        ecs.register::<comp::Admin>();
+        let other = ecs.create_entity_synced().build();
+
+        let console = ConsoleSession::start(&mut ecs, AdminRole::Moderator);
+        let uid = ecs
+            .uid_from_entity(console)
+            .expect("the console needs a uid");
+        assert_eq!(ecs.entity_from_uid(uid), Some(console));
+
+        let msg = |text: &str| {
+            ServerGeneral::server_msg(ChatType::CommandInfo, Content::Plain(text.to_owned())).into()
+        };
+        ConsoleSession::record(&ecs, console, msg("to the console"));
+        ConsoleSession::record(&ecs, other, msg("to someone else"));
+
+        let output = ConsoleSession::finish(&mut ecs);
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].content().as_plain(), Some("to the console"));
+        assert!(!ecs.is_alive(console));
+        assert_eq!(ecs.entity_from_uid(uid), None);
+        assert!(ecs.is_alive(other));
+        assert!(ConsoleSession::finish(&mut ecs).is_empty());
+    }
+}
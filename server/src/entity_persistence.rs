@@ -0,0 +1,564 @@
+//! Entities that are neither generated with the world nor simulated by rtsim,
+//! like dropped items, placed campfires and NPCs and ships spawned by
+//! commands. They are saved with the chunk they're in when it unloads, next to
+//! the persisted terrain, and spawned again once the chunk loads.
+//!
+//! The saved data doesn't depend on the layout of ECS components: items and
+//! skills are saved like exported characters, by item definition and skill
+//! group.
+//!
+//! Entities with an [`Anchor`] aren't saved: those generated with a chunk are
+//! generated again. Pets aren't saved either: those of players are saved with
+//! their owner's character, and the [`Uid`] of other owners doesn't survive a
+//! restart. Objects other than placed campfires are short-lived or generated,
+//! so NPCs riding them are saved on their own.
+//!
+//! [`Uid`]: common::uid::Uid
+
+use crate::persistence::portable::{
+    ItemData, SkillGroupData, item_from_data, item_to_data, skill_set_from_data, skill_set_to_data,
+};
+use common::{
+    comp::{
+        self, Agent, Alignment, Anchor, Body, Content, Health, Inventory, Ori, PickupItem, Pos,
+        Presence, Projectile, Scale, SkillSet, Stats, WaypointArea,
+        inventory::loadout_builder::LoadoutBuilder,
+    },
+    event::{
+        CreateItemDropEvent, CreateNpcEvent, CreateShipEvent, CreateSpecialEntityEvent, EmitExt,
+        NpcBuilder,
+    },
+    generation::SpecialEntity,
+    link::Is,
+    mounting::{Mount, Rider, Volume, VolumeRider, VolumeRiders},
+    resources::ProgramTime,
+    rtsim::RtSimEntity,
+    terrain::TerrainGrid,
+    uid::IdMaps,
+};
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+use specs::{Entity as EcsEntity, ReadExpect, ReadStorage, SystemData, shred};
+use tracing::warn;
+use vek::*;
+
+#[derive(Serialize, Deserialize)]
+pub enum PersistedEntity {
+    /// A stack of dropped items
+    Item {
+        pos: Vec3<f32>,
+        ori: Quaternion<f32>,
+        items: Vec<ItemData>,
+        should_merge: bool,
+    },
+    /// A campfire that was placed, generated ones are anchored to their chunk
+    Campfire { pos: Vec3<f32> },
+    Npc {
+        pos: Vec3<f32>,
+        ori: Quaternion<f32>,
+        npc: NpcData,
+    },
+    Ship {
+        pos: Vec3<f32>,
+        ori: Quaternion<f32>,
+        body: comp::ship::Body,
+        driver: Option<NpcData>,
+    },
+}
+
+/// The state of an NPC that outlives a restart. Buffs, the state of its agent
+/// and the like start over.
+#[derive(Serialize, Deserialize)]
+pub struct NpcData {
+    name: Content,
+    body: Body,
+    alignment: AlignmentData,
+    scale: f32,
+    /// Fraction of the maximum health
+    health: f32,
+    skill_groups: Vec<SkillGroupData>,
+    /// The equipped items by the persistence key of their slot
+    loadout: Vec<(String, ItemData)>,
+    /// The items in the inventory, including the overflow
+    items: Vec<ItemData>,
+    has_agent: bool,
+    rider: Option<Box<NpcData>>,
+}
+
+/// [`Alignment`] of NPCs that are saved, see the module documentation about
+/// pets
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+enum AlignmentData {
+    Wild,
+    Enemy,
+    Npc,
+    Tame,
+    Passive,
+}
+
+impl AlignmentData {
+    fn from_alignment(alignment: Alignment) -> Option<Self> {
+        match alignment {
+            Alignment::Wild => Some(Self::Wild),
+            Alignment::Enemy => Some(Self::Enemy),
+            Alignment::Npc => Some(Self::Npc),
+            Alignment::Tame => Some(Self::Tame),
+            Alignment::Owned(_) => None,
+            Alignment::Passive => Some(Self::Passive),
+        }
+    }
+
+    fn into_alignment(self) -> Alignment {
+        match self {
+            Self::Wild => Alignment::Wild,
+            Self::Enemy => Alignment::Enemy,
+            Self::Npc => Alignment::Npc,
+            Self::Tame => Alignment::Tame,
+            Self::Passive => Alignment::Passive,
+        }
+    }
+}
+
+/// Items that can't be loaded anymore, e.g. because their definition was
+/// removed, are lost
+fn load_item(item: ItemData) -> Option<comp::Item> {
+    item_from_data(item)
+        .inspect_err(|error| warn!(?error, "Failed to load a saved item"))
+        .ok()
+}
+
+impl PersistedEntity {
+    /// Spawns the entity again, through the same events that created it
+    pub fn restore<E>(self, emitters: &mut E, program_time: ProgramTime)
+    where
+        E: EmitExt<CreateItemDropEvent>
+            + EmitExt<CreateNpcEvent>
+            + EmitExt<CreateShipEvent>
+            + EmitExt<CreateSpecialEntityEvent>,
+    {
+        match self {
+            Self::Item {
+                pos,
+                ori,
+                items,
+                should_merge,
+            } => {
+                // The items of the stack are dropped one by one, those that can merge do so
+                // again
+                for item in items.into_iter().filter_map(load_item) {
+                    emitters.emit(CreateItemDropEvent {
+                        pos: Pos(pos),
+                        vel: comp::Vel(Vec3::zero()),
+                        ori: Ori::new(ori),
+                        item: PickupItem::new(item, program_time, should_merge),
+                        loot_owner: None,
+                    });
+                }
+            },
+            Self::Campfire { pos } => emitters.emit(CreateSpecialEntityEvent {
+                pos,
+                entity: SpecialEntity::Waypoint,
+                anchor: None,
+            }),
+            Self::Npc { pos, ori, npc } => emitters.emit(CreateNpcEvent {
+                pos: Pos(pos),
+                ori: Ori::new(ori),
+                npc: npc.into_builder(pos),
+            }),
+            Self::Ship {
+                pos,
+                ori,
+                body,
+                driver,
+            } => emitters.emit(CreateShipEvent {
+                pos: Pos(pos),
+                ori: Ori::new(ori),
+                ship: body,
+                rtsim_entity: None,
+                driver: driver.map(|driver| driver.into_builder(pos)),
+            }),
+        }
+    }
+}
+
+impl NpcData {
+    fn into_builder(self, pos: Vec3<f32>) -> NpcBuilder {
+        let body = self.body;
+        let mut health = Health::new(body);
+        health.set_fraction(self.health);
+        let skill_set = match skill_set_from_data(self.skill_groups) {
+            Ok((skill_set, error)) => {
+                if let Some(error) = error {
+                    warn!(?error, "The skills of a saved NPC were reset");
+                }
+                skill_set
+            },
+            Err(error) => {
+                warn!(?error, "Failed to load the skills of a saved NPC");
+                SkillSet::default()
+            },
+        };
+
+        let mut loadout = LoadoutBuilder::empty().build();
+        for (key, item) in self.loadout {
+            if let Some(item) = load_item(item)
+                && loadout
+                    .set_item_at_slot_using_persistence_key(&key, item)
+                    .is_err()
+            {
+                warn!(?key, "Failed to equip a saved item");
+            }
+        }
+        let mut inventory = Inventory::with_loadout(loadout, body);
+        if let Err(error) = inventory.push_all(self.items.into_iter().filter_map(load_item)) {
+            inventory.persistence_push_overflow_items(error.returned_items());
+        }
+
+        let alignment = self.alignment.into_alignment();
+        NpcBuilder::new(Stats::new(self.name, body), body, alignment)
+            .with_skill_set(skill_set)
+            .with_health(health)
+            .with_inventory(inventory)
+            .with_scale(Scale(self.scale))
+            .with_agent(
+                self.has_agent
+                    .then(|| Agent::from_body(&body).with_patrol_origin(pos)),
+            )
+            .with_rider(self.rider.map(|rider| rider.into_builder(pos)))
+    }
+}
+
+#[derive(SystemData)]
+struct Data<'a> {
+    id_maps: ReadExpect<'a, IdMaps>,
+    positions: ReadStorage<'a, Pos>,
+    orientations: ReadStorage<'a, Ori>,
+    presences: ReadStorage<'a, Presence>,
+    anchors: ReadStorage<'a, Anchor>,
+    rtsim_entities: ReadStorage<'a, RtSimEntity>,
+    bodies: ReadStorage<'a, Body>,
+    waypoint_areas: ReadStorage<'a, WaypointArea>,
+    projectiles: ReadStorage<'a, Projectile>,
+    stats: ReadStorage<'a, Stats>,
+    healths: ReadStorage<'a, Health>,
+    scales: ReadStorage<'a, Scale>,
+    alignments: ReadStorage<'a, Alignment>,
+    skill_sets: ReadStorage<'a, SkillSet>,
+    agents: ReadStorage<'a, Agent>,
+    is_riders: ReadStorage<'a, Is<Rider>>,
+    is_mounts: ReadStorage<'a, Is<Mount>>,
+    is_volume_riders: ReadStorage<'a, Is<VolumeRider>>,
+    volume_riders: ReadStorage<'a, VolumeRiders>,
+    items: ReadStorage<'a, PickupItem>,
+    inventories: ReadStorage<'a, Inventory>,
+}
+
+impl Data<'_> {
+    /// Entities in a chunk that unloads that are saved, e.g. no players
+    fn is_persisted(&self, entity: EcsEntity) -> bool {
+        !self.presences.contains(entity)
+            && !self.anchors.contains(entity)
+            && !self.rtsim_entities.contains(entity)
+            && !self.projectiles.contains(entity)
+    }
+
+    fn is_persisted_npc(&self, entity: EcsEntity) -> bool {
+        self.is_persisted(entity)
+            && self.bodies.get(entity).is_some_and(|body| {
+                !matches!(
+                    body,
+                    Body::Object(_) | Body::Item(_) | Body::Ship(_) | Body::Plugin(_)
+                )
+            })
+            && self
+                .alignments
+                .get(entity)
+                .is_some_and(|alignment| AlignmentData::from_alignment(*alignment).is_some())
+    }
+
+    fn is_persisted_ship(&self, entity: EcsEntity) -> bool {
+        self.is_persisted(entity) && matches!(self.bodies.get(entity), Some(Body::Ship(_)))
+    }
+
+    /// Riders and the drivers of ships are saved with their mount
+    fn is_saved_with_mount(&self, entity: EcsEntity) -> bool {
+        let mount = self
+            .is_riders
+            .get(entity)
+            .map(|is_rider| is_rider.mount)
+            .or_else(|| {
+                let is_volume_rider = self.is_volume_riders.get(entity)?;
+                match is_volume_rider.pos.kind {
+                    Volume::Entity(mount) if is_volume_rider.is_steering_entity() => Some(mount),
+                    _ => None,
+                }
+            });
+        mount
+            .and_then(|mount| self.id_maps.uid_entity(mount))
+            .is_some_and(|mount| self.is_persisted_npc(mount) || self.is_persisted_ship(mount))
+    }
+
+    /// The entity steering a ship, or riding it when it has no helm
+    fn driver(&self, ship: EcsEntity) -> Option<EcsEntity> {
+        let driver = self
+            .is_mounts
+            .get(ship)
+            .map(|is_mount| is_mount.rider)
+            .or_else(|| {
+                self.volume_riders.get(ship)?.iter_riders().find(|rider| {
+                    self.id_maps
+                        .uid_entity(*rider)
+                        .and_then(|rider| self.is_volume_riders.get(rider))
+                        .is_some_and(|is_volume_rider| is_volume_rider.is_steering_entity())
+                })
+            })?;
+        self.id_maps.uid_entity(driver)
+    }
+
+    fn take_npc(&self, entity: EcsEntity) -> Option<NpcData> {
+        if !self.is_persisted_npc(entity) {
+            return None;
+        }
+        let inventory = self.inventories.get(entity)?;
+        let rider = self
+            .is_mounts
+            .get(entity)
+            .and_then(|is_mount| self.id_maps.uid_entity(is_mount.rider))
+            .and_then(|rider| self.take_npc(rider))
+            .map(Box::new);
+        Some(NpcData {
+            name: self.stats.get(entity)?.name.clone(),
+            body: *self.bodies.get(entity)?,
+            alignment: AlignmentData::from_alignment(*self.alignments.get(entity)?)?,
+            scale: self.scales.get(entity).map_or(1.0, |scale| scale.0),
+            health: self.healths.get(entity)?.fraction(),
+            skill_groups: skill_set_to_data(self.skill_sets.get(entity)?),
+            loadout: inventory
+                .loadout_items_with_persistence_key()
+                .filter_map(|(key, item)| Some((key.to_owned(), item_to_data(item?))))
+                .collect(),
+            items: inventory
+                .slots()
+                .flatten()
+                .chain(inventory.overflow_items())
+                .map(item_to_data)
+                .collect(),
+            has_agent: self.agents.contains(entity),
+            rider,
+        })
+    }
+
+    fn take(&self, entity: EcsEntity) -> Option<(Vec2<i32>, PersistedEntity)> {
+        if !self.is_persisted(entity) || self.is_saved_with_mount(entity) {
+            return None;
+        }
+        let pos = self.positions.get(entity)?.0;
+        let ori = self
+            .orientations
+            .get(entity)
+            .copied()
+            .unwrap_or_default()
+            .to_quat();
+        let persisted = if let Some(item) = self.items.get(entity) {
+            PersistedEntity::Item {
+                pos,
+                ori,
+                items: item.items().iter().map(item_to_data).collect(),
+                should_merge: item.should_merge(),
+            }
+        } else if self.waypoint_areas.contains(entity)
+            && self.bodies.get(entity).is_some_and(Body::is_campfire)
+        {
+            PersistedEntity::Campfire { pos }
+        } else if let Some(Body::Ship(body)) = self.bodies.get(entity) {
+            PersistedEntity::Ship {
+                pos,
+                ori,
+                body: *body,
+                driver: self.driver(entity).and_then(|driver| self.take_npc(driver)),
+            }
+        } else {
+            PersistedEntity::Npc {
+                pos,
+                ori,
+                npc: self.take_npc(entity)?,
+            }
+        };
+        let key = TerrainGrid::chunk_key(pos.xy().map(|e| e.floor() as i32));
+        Some((key, persisted))
+    }
+}
+
+/// Takes the state of the persisted ones of `entities` out of the ECS, grouped
+/// by the chunk they're in. The entities have to be deleted afterwards.
+pub fn take_entities(
+    ecs: &specs::World,
+    entities: &[EcsEntity],
+) -> HashMap<Vec2<i32>, Vec<PersistedEntity>> {
+    let data = ecs.system_data::<Data>();
+    let mut chunks = HashMap::<_, Vec<_>>::new();
+    for (key, entity) in entities.iter().filter_map(|entity| data.take(*entity)) {
+        chunks.entry(key).or_default().push(entity);
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{
+        comp::{Item, humanoid, skillset::SkillGroupKind},
+        uid::Uid,
+    };
+    use specs::{Builder, WorldExt};
+
+    #[derive(Default)]
+    struct Emitted {
+        items: Vec<CreateItemDropEvent>,
+        npcs: Vec<CreateNpcEvent>,
+        ships: Vec<CreateShipEvent>,
+        special_entities: Vec<CreateSpecialEntityEvent>,
+    }
+
+    macro_rules! impl_emit {
+        ($($event:ty => $field:ident),* $(,)?) => {
+            $(impl EmitExt<$event> for Emitted {
+                fn emit(&mut self, event: $event) { self.$field.push(event); }
+
+                fn emit_many(&mut self, events: impl IntoIterator<Item = $event>) {
+                    self.$field.extend(events);
+                }
+            })*
+        };
+    }
+
+    impl_emit! {
+        CreateItemDropEvent => items,
+        CreateNpcEvent => npcs,
+        CreateShipEvent => ships,
+        CreateSpecialEntityEvent => special_entities,
+    }
+
+    fn mock_world() -> specs::World {
+        let mut world = specs::World::new();
+        world.insert(IdMaps::new());
+        world.register::<Uid>();
+        world.register::<Pos>();
+        world.register::<Ori>();
+        world.register::<Presence>();
+        world.register::<Anchor>();
+        world.register::<RtSimEntity>();
+        world.register::<Body>();
+        world.register::<WaypointArea>();
+        world.register::<Projectile>();
+        world.register::<Stats>();
+        world.register::<Health>();
+        world.register::<Scale>();
+        world.register::<Alignment>();
+        world.register::<SkillSet>();
+        world.register::<Agent>();
+        world.register::<Is<Rider>>();
+        world.register::<Is<Mount>>();
+        world.register::<Is<VolumeRider>>();
+        world.register::<VolumeRiders>();
+        world.register::<PickupItem>();
+        world.register::<Inventory>();
+        world
+    }
+
+    fn create_npc(world: &mut specs::World, body: Body, alignment: Alignment) -> EcsEntity {
+        let mut inventory =
+            Inventory::with_loadout(LoadoutBuilder::empty().defaults().build(), body);
+        inventory
+            .push(Item::new_from_asset_expect("common.items.food.cheese"))
+            .unwrap();
+        let mut skill_set = SkillSet::default();
+        skill_set.add_experience(SkillGroupKind::General, 1000);
+        let mut health = Health::new(body);
+        health.set_fraction(0.5);
+        world
+            .create_entity()
+            .with(Pos(Vec3::new(1.0, 2.0, 3.0)))
+            .with(body)
+            .with(Stats::new(Content::Plain("Tester".to_string()), body))
+            .with(health)
+            .with(alignment)
+            .with(skill_set)
+            .with(inventory)
+            .build()
+    }
+
+    #[test]
+    fn take_and_restore() {
+        let mut world = mock_world();
+        let body = Body::Humanoid(humanoid::Body::random());
+        let npc = create_npc(&mut world, body, Alignment::Npc);
+        let owner = world.write_resource::<IdMaps>().allocate(npc);
+        let pet = create_npc(&mut world, body, Alignment::Owned(owner));
+        let item = world
+            .create_entity()
+            .with(Pos(Vec3::new(4.0, 5.0, 6.0)))
+            .with(PickupItem::new(
+                Item::new_from_asset_expect("common.items.food.cheese"),
+                ProgramTime(0.0),
+                true,
+            ))
+            .build();
+
+        let mut chunks = take_entities(&world, &[npc, pet, item]);
+        let entities = chunks.remove(&Vec2::zero()).unwrap();
+        assert!(chunks.is_empty());
+        // The pet isn't saved
+        assert_eq!(entities.len(), 2);
+
+        let contents = ron::to_string(&entities).unwrap();
+        let mut emitted = Emitted::default();
+        for entity in ron::from_str::<Vec<PersistedEntity>>(&contents).unwrap() {
+            entity.restore(&mut emitted, ProgramTime(0.0));
+        }
+        assert!(emitted.ships.is_empty() && emitted.special_entities.is_empty());
+
+        let [item] = &emitted.items[..] else {
+            panic!("Expected one item drop");
+        };
+        assert_eq!(item.pos.0, Vec3::new(4.0, 5.0, 6.0));
+        assert_eq!(
+            item.item.item().persistence_item_id(),
+            "common.items.food.cheese"
+        );
+
+        let [CreateNpcEvent { pos, npc, .. }] = &emitted.npcs[..] else {
+            panic!("Expected one NPC");
+        };
+        assert_eq!(pos.0, Vec3::new(1.0, 2.0, 3.0));
+        assert_eq!(npc.body, body);
+        assert_eq!(npc.alignment, Alignment::Npc);
+        assert!((npc.health.as_ref().unwrap().fraction() - 0.5).abs() < 0.01);
+        // The pet wasn't taken, so it still has the same state as the NPC had
+        assert_eq!(
+            npc.skill_set.earned_sp(SkillGroupKind::General),
+            world
+                .read_storage::<SkillSet>()
+                .get(pet)
+                .unwrap()
+                .earned_sp(SkillGroupKind::General)
+        );
+        let items = |inventory: &Inventory| {
+            (
+                inventory
+                    .loadout_items_with_persistence_key()
+                    .filter_map(|(key, item)| Some((key.to_owned(), item?.persistence_item_id())))
+                    .collect::<Vec<_>>(),
+                inventory
+                    .slots()
+                    .flatten()
+                    .map(Item::persistence_item_id)
+                    .collect::<Vec<_>>(),
+            )
+        };
+        assert_eq!(
+            items(&npc.inventory),
+            items(world.read_storage::<Inventory>().get(pet).unwrap())
+        );
+    }
+}
@@ -529,6 +529,7 @@ pub fn handle_create_special_entity(server: &mut Server, ev: CreateSpecialEntity
                     dir: None,
                 })
                 .with(WaypointArea::default())
+                .maybe_with(ev.anchor)
                 .with(comp::Immovable)
                 .with(comp::EnteredAuras::default())
                 .with(comp::Auras::new(vec![
@@ -563,12 +564,14 @@ pub fn handle_create_special_entity(server: &mut Server, ev: CreateSpecialEntity
             server
                 .state
                 .create_teleporter(comp::Pos(ev.pos), portal)
+                .maybe_with(ev.anchor)
                 .build();
         },
         SpecialEntity::ArenaTotem { range } => {
             server
                 .state
                 .create_object(Pos(ev.pos), comp::object::Body::GnarlingTotemGreen)
+                .maybe_with(ev.anchor)
                 .with(comp::Immovable)
                 .with(comp::EnteredAuras::default())
                 .with(comp::Auras::new(vec![
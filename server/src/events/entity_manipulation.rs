@@ -66,6 +66,8 @@ use common::{
     vol::ReadVol,
 };
 use common_net::{msg::ServerGeneral, sync::WorldSyncExt, synced_components::Heads};
+#[cfg(feature = "plugins")]
+use common_state::plugin::PluginEvent;
 use common_state::{AreasContainer, BlockChange, NoDurabilityArea, ScheduledBlockChange};
 use hashbrown::HashSet;
 use rand::RngExt;
@@ -238,6 +240,8 @@ pub struct HealthChangeEventData<'a> {
     agents: WriteStorage<'a, Agent>,
     healths: WriteStorage<'a, Health>,
     heads: WriteStorage<'a, Heads>,
+    #[cfg(feature = "plugins")]
+    plugin_events: Read<'a, EventBus<PluginEvent>>,
 }
 
 impl ServerEvent for HealthChangeEvent {
@@ -245,6 +249,8 @@ impl ServerEvent for HealthChangeEvent {
 
     fn handle(events: impl ExactSizeIterator<Item = Self>, mut data: Self::SystemData<'_>) {
         let mut emitters = data.events.get_emitters();
+        #[cfg(feature = "plugins")]
+        let mut plugin_events = data.plugin_events.emitter();
         let mut rng = rand::rng();
         for ev in events {
             if let Some((mut health, inventory, pos, uid, heads)) = (
@@ -308,6 +314,17 @@ impl ServerEvent for HealthChangeEvent {
                     }
                 }
 
+                #[cfg(feature = "plugins")]
+                if let Some(uid) = uid
+                    && changed
+                {
+                    plugin_events.emit(PluginEvent::HealthChange {
+                        entity: *uid,
+                        amount: ev.change.amount,
+                        by: ev.change.damage_by().map(|by| by.uid()),
+                    });
+                }
+
                 if let (Some(pos), Some(uid)) = (pos, uid)
                     && changed
                 {
@@ -590,6 +607,8 @@ pub struct DestroyEventData<'a> {
     buffs: ReadStorage<'a, comp::Buffs>,
     orientations: ReadStorage<'a, comp::Ori>,
     combos: ReadStorage<'a, comp::Combo>,
+    #[cfg(feature = "plugins")]
+    plugin_events: Read<'a, EventBus<PluginEvent>>,
 }
 
 /// Handle an entity dying. If it is a player, it will send a message to all
@@ -625,6 +644,14 @@ impl ServerEvent for DestroyEvent {
                 }
             }
 
+            #[cfg(feature = "plugins")]
+            if let Some(uid) = data.uids.get(ev.entity) {
+                data.plugin_events.emit_now(PluginEvent::Death {
+                    entity: *uid,
+                    killer: ev.cause.damage_by().map(|by| by.uid()),
+                });
+            }
+
             // Remove components that should not persist across death
             data.melees.remove(ev.entity);
             data.beams.remove(ev.entity);
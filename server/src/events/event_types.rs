@@ -1,17 +1,17 @@
 pub use common::event::{
-    ArcingEvent, AuraEvent, BonkEvent, BuffEvent, ChangeAbilityEvent, ChangeBodyEvent,
-    ChangeStanceEvent, ChatEvent, ClientDisconnectEvent, ClientDisconnectWithoutPersistenceEvent,
-    ComboChangeEvent, CommandEvent, CreateAuraEntityEvent, CreateItemDropEvent, CreateNpcEvent,
-    CreateNpcGroupEvent, CreateObjectEvent, CreateShipEvent, CreateSpecialEntityEvent,
-    CreateSpriteEvent, DeleteCharacterEvent, DeleteEvent, DestroyEvent, DialogueEvent, DownedEvent,
-    EnergyChangeEvent, EntityAttackedHookEvent, EventBus, ExitIngameEvent, ExplosionEvent,
-    GroupManipEvent, HealthChangeEvent, HelpDownedEvent, InitializeCharacterEvent,
-    InitializeSpectatorEvent, InitiateInviteEvent, InventoryManipEvent, InviteResponseEvent,
-    KillEvent, KnockbackEvent, LandOnGroundEvent, MakeAdminEvent, MineBlockEvent, MountEvent,
-    NpcInteractEvent, ParryHookEvent, PoiseChangeEvent, PossessEvent, ProcessTradeActionEvent,
-    RegrowHeadEvent, RemoveLightEmitterEvent, RequestSiteInfoEvent, RespawnEvent,
-    SetBattleModeEvent, SetLanternEvent, SetPetStayEvent, ShockwaveEvent, ShootEvent, SoundEvent,
-    StartInteractionEvent, StartTeleportingEvent, SummonBeamPillarsEvent, TamePetEvent,
+    AbandonQuestEvent, ArcingEvent, AuraEvent, BonkEvent, BuffEvent, ChangeAbilityEvent,
+    ChangeBodyEvent, ChangeStanceEvent, ChatEvent, ClientDisconnectEvent,
+    ClientDisconnectWithoutPersistenceEvent, ComboChangeEvent, CommandEvent, CreateAuraEntityEvent,
+    CreateItemDropEvent, CreateNpcEvent, CreateNpcGroupEvent, CreateObjectEvent, CreateShipEvent,
+    CreateSpecialEntityEvent, CreateSpriteEvent, DeleteCharacterEvent, DeleteEvent, DestroyEvent,
+    DialogueEvent, DownedEvent, EnergyChangeEvent, EntityAttackedHookEvent, EventBus,
+    ExitIngameEvent, ExplosionEvent, GroupManipEvent, HealthChangeEvent, HelpDownedEvent,
+    InitializeCharacterEvent, InitializeSpectatorEvent, InitiateInviteEvent, InventoryManipEvent,
+    InviteResponseEvent, KillEvent, KnockbackEvent, LandOnGroundEvent, MakeAdminEvent,
+    MineBlockEvent, MountEvent, NpcInteractEvent, ParryHookEvent, PoiseChangeEvent, PossessEvent,
+    ProcessTradeActionEvent, RegrowHeadEvent, RemoveLightEmitterEvent, RequestSiteInfoEvent,
+    RespawnEvent, SetBattleModeEvent, SetLanternEvent, SetPetStayEvent, ShockwaveEvent, ShootEvent,
+    SoundEvent, StartInteractionEvent, StartTeleportingEvent, SummonBeamPillarsEvent, TamePetEvent,
     TeleportToEvent, TeleportToPositionEvent, ThrowEvent, ToggleSpriteLightEvent, TransformEvent,
     UpdateCharacterDataEvent, UpdateMapMarkerEvent,
 };
@@ -54,6 +54,7 @@ macro_rules! server_events {
             SetLanternEvent
             NpcInteractEvent
             DialogueEvent
+            AbandonQuestEvent
             InviteResponseEvent
             InitiateInviteEvent
             ProcessTradeActionEvent
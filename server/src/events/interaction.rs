@@ -1,6 +1,9 @@
 use std::{f32::consts::PI, ops::Mul};
 
-use common::{comp::loot_owner::ONWERSHIP_TIMEOUT_FAST, rtsim::DialogueKind};
+use common::{
+    comp::loot_owner::ONWERSHIP_TIMEOUT_FAST,
+    rtsim::{Actor, DialogueKind},
+};
 use common_state::{BlockChange, ScheduledBlockChange};
 use specs::{DispatcherBuilder, Join, ReadExpect, ReadStorage, WriteExpect, WriteStorage};
 use tracing::error;
@@ -18,9 +21,9 @@ use common::{
     },
     consts::{MAX_INTERACT_RANGE, MAX_NPCINTERACT_RANGE, SOUND_TRAVEL_DIST_PER_VOLUME},
     event::{
-        CreateItemDropEvent, CreateSpriteEvent, DialogueEvent, EventBus, MineBlockEvent,
-        NpcInteractEvent, SetLanternEvent, SetPetStayEvent, SoundEvent, TamePetEvent,
-        ToggleSpriteLightEvent,
+        AbandonQuestEvent, CreateItemDropEvent, CreateSpriteEvent, DialogueEvent, EventBus,
+        MineBlockEvent, NpcInteractEvent, SetLanternEvent, SetPetStayEvent, SoundEvent,
+        TamePetEvent, ToggleSpriteLightEvent,
     },
     link::Is,
     mounting::Mount,
@@ -32,7 +35,7 @@ use common::{
     vol::ReadVol,
 };
 
-use crate::{Server, ServerGeneral, Time, client::Client};
+use crate::{Server, ServerGeneral, Time, client::Client, rtsim::RtSim};
 
 use crate::pet::tame_pet;
 use hashbrown::{HashMap, HashSet};
@@ -44,6 +47,7 @@ pub(super) fn register_event_systems(builder: &mut DispatcherBuilder) {
     event_dispatch::<SetLanternEvent>(builder, &[]);
     event_dispatch::<NpcInteractEvent>(builder, &[]);
     event_dispatch::<DialogueEvent>(builder, &[]);
+    event_dispatch::<AbandonQuestEvent>(builder, &[]);
     event_dispatch::<SetPetStayEvent>(builder, &[]);
     event_dispatch::<MineBlockEvent>(builder, &[]);
     event_dispatch::<SoundEvent>(builder, &[]);
@@ -221,6 +225,28 @@ impl ServerEvent for DialogueEvent {
     }
 }
 
+impl ServerEvent for AbandonQuestEvent {
+    type SystemData<'a> = (WriteExpect<'a, RtSim>, ReadStorage<'a, comp::Presence>);
+
+    fn handle(
+        events: impl ExactSizeIterator<Item = Self>,
+        (rtsim, presences): Self::SystemData<'_>,
+    ) {
+        let mut data = rtsim.state().data_mut();
+        for ev in events {
+            // Only the character that has to carry out the quest can give up on it
+            if let Some(character_id) = presences
+                .get(ev.entity)
+                .and_then(|presence| presence.kind.character_id())
+                && let Some(quest) = data.quests.get_mut(ev.quest_id)
+                && quest.quester() == Actor::Character(character_id)
+            {
+                quest.abandon();
+            }
+        }
+    }
+}
+
 impl ServerEvent for SetPetStayEvent {
     type SystemData<'a> = (
         WriteStorage<'a, comp::Agent>,
@@ -2,6 +2,8 @@ use super::{
     ServerEvent, event_dispatch,
     group_manip::{self, update_map_markers},
 };
+#[cfg(feature = "worldgen")]
+use crate::rtsim::{RtSim, site_prices};
 use crate::{Settings, client::Client};
 use common::{
     comp::{
@@ -234,6 +236,8 @@ pub struct InviteResponseData<'a> {
     trades: Write<'a, Trades>,
     #[cfg(feature = "worldgen")]
     index: ReadExpect<'a, IndexOwned>,
+    #[cfg(feature = "worldgen")]
+    rtsim: Option<Read<'a, RtSim>>,
     id_maps: Read<'a, IdMaps>,
     invites: WriteStorage<'a, Invite>,
     pending_invites: WriteStorage<'a, PendingInvites>,
@@ -329,15 +333,19 @@ pub fn handle_invite_accept(data: &mut InviteResponseData, entity: Entity) {
                         .agents
                         .get(inviter)
                         .and_then(|a| {
-                            a.behavior
-                                .trade_site()
-                                .and_then(|id| data.index.get_site_prices(id))
+                            a.behavior.trade_site().and_then(|id| {
+                                site_prices(data.rtsim.as_deref(), data.index.as_index_ref(), id)
+                            })
                         })
                         .or_else(|| {
                             data.agents.get(entity).and_then(|a| {
-                                a.behavior
-                                    .trade_site()
-                                    .and_then(|id| data.index.get_site_prices(id))
+                                a.behavior.trade_site().and_then(|id| {
+                                    site_prices(
+                                        data.rtsim.as_deref(),
+                                        data.index.as_index_ref(),
+                                        id,
+                                    )
+                                })
                             })
                         });
                     #[cfg(not(feature = "worldgen"))]
@@ -197,7 +197,13 @@ impl Server {
             this.process_command(ev.0, ev.1, ev.2);
         });
         self.handle_serial_events(|this, ev: ChatEvent| {
-            this.state.send_chat(ev.msg, ev.from_client);
+            #[cfg(feature = "plugins")]
+            let Some(msg) = this.plugin_chat_hook(ev.msg) else {
+                return;
+            };
+            #[cfg(not(feature = "plugins"))]
+            let msg = ev.msg;
+            this.state.send_chat(msg, ev.from_client);
         });
         self.handle_serial_events(handle_mount);
         self.handle_serial_events(handle_tame_pet);
@@ -217,6 +223,12 @@ impl Server {
         self.handle_all_serial_events(&mut frontend_events);
         drop(guard);
 
+        #[cfg(feature = "plugins")]
+        {
+            self.dispatch_plugin_events();
+            self.apply_plugin_actions();
+        }
+
         self.state.maintain_ecs();
 
         #[cfg(debug_assertions)]
@@ -13,6 +13,8 @@ use common::{
 use common_base::span;
 use common_net::msg::{PlayerListUpdate, ServerGeneral};
 use common_state::State;
+#[cfg(feature = "plugins")]
+use common_state::plugin::PluginEvent;
 use hashbrown::HashSet;
 use specs::{Builder, Entity as EcsEntity, Join, WorldExt};
 use tracing::{Instrument, debug, error, trace, warn};
@@ -42,6 +44,8 @@ pub fn handle_character_delete(server: &mut Server, ev: DeleteCharacterEvent) {
 
 pub fn handle_exit_ingame(server: &mut Server, entity: EcsEntity, skip_persistence: bool) {
     span!(_guard, "handle_exit_ingame");
+    #[cfg(feature = "plugins")]
+    notify_plugins_logout(server, entity);
     let state = server.state_mut();
 
     // Sync the player's character data to the database. This must be done before
@@ -275,6 +279,8 @@ pub fn handle_client_disconnect(
         disconnected_event = Some(Event::ClientDisconnected { entity });
     }
 
+    #[cfg(feature = "plugins")]
+    notify_plugins_logout(server, entity);
     let state = server.state_mut();
 
     // Tell other clients to remove from player list
@@ -309,6 +315,23 @@ pub fn handle_client_disconnect(
     disconnected_event
 }
 
+/// Tell plugins a character leaves the game, while its components are still
+/// around to be inspected
+#[cfg(feature = "plugins")]
+fn notify_plugins_logout(server: &Server, entity: EcsEntity) {
+    let ecs = server.state().ecs();
+    let in_game = ecs
+        .read_storage::<Presence>()
+        .get(entity)
+        .is_some_and(|presence| presence.kind.character_id().is_some());
+    let uid = ecs.read_storage::<Uid>().get(entity).copied();
+    if let (true, Some(player)) = (in_game, uid) {
+        server.with_plugins(|plugin_mgr, ecs_world| {
+            plugin_mgr.game_event(ecs_world, &PluginEvent::Logout { player })
+        });
+    }
+}
+
 /// When a player logs out, their data is queued for persistence in the next
 /// tick of the persistence batch update unless the character logging out is
 /// dead and has hardcore enabled, in which case the character is deleted
@@ -1,4 +1,6 @@
 use crate::Server;
+#[cfg(feature = "worldgen")]
+use crate::rtsim::{RtSim, site_prices};
 use common::{
     comp::{
         self, CharacterState, Health,
@@ -15,6 +17,8 @@ use common_net::{
     msg::ServerGeneral,
     sync::{Uid, WorldSyncExt},
 };
+#[cfg(feature = "plugins")]
+use common_state::plugin::PluginEvent;
 use hashbrown::{HashMap, hash_map::Entry};
 use specs::{Entity as EcsEntity, world::WorldExt};
 use std::{cmp::Ordering, num::NonZeroU32};
@@ -35,6 +39,7 @@ pub fn notify_agent_simple(
 #[cfg(feature = "worldgen")]
 fn notify_agent_prices(
     mut agents: specs::WriteStorage<Agent>,
+    rtsim: Option<&RtSim>,
     index: &IndexOwned,
     entity: EcsEntity,
     event: AgentEvent,
@@ -45,7 +50,7 @@ fn notify_agent_prices(
         // Prefer using this Agent's price data, but use the counterparty's price
         // data if we don't have price data
         let prices = site_id
-            .and_then(|site_id| index.get_site_prices(site_id))
+            .and_then(|site_id| site_prices(rtsim, index.as_index_ref(), site_id))
             .unwrap_or(boxval.2);
         // Box<(tid, pend, _, inventories)>) = event {
         agent
@@ -110,8 +115,16 @@ pub(super) fn handle_process_trade_action(
             if let Entry::Occupied(entry) = trades.trades.entry(trade_id) {
                 let parties = entry.get().parties;
                 if entry.get().should_commit() {
+                    #[cfg(feature = "plugins")]
+                    let offers = offered_items(server.state.ecs(), entry.get());
                     let result = commit_trade(server.state.ecs(), entry.get());
                     entry.remove();
+                    #[cfg(feature = "plugins")]
+                    if let TradeResult::Completed = result {
+                        server
+                            .state
+                            .emit_event_now(PluginEvent::Trade { parties, offers });
+                    }
                     for party in parties.iter() {
                         if let Some(e) = server.state.ecs().entity_from_uid(*party) {
                             server.notify_client(e, ServerGeneral::FinishedTrade(result.clone()));
@@ -128,6 +141,8 @@ pub(super) fn handle_process_trade_action(
                     let mut inventories: [Option<ReducedInventory>; 2] = [None, None];
                     #[cfg(feature = "worldgen")]
                     let mut prices = None;
+                    #[cfg(feature = "worldgen")]
+                    let rtsim = server.state.ecs().try_fetch::<RtSim>();
                     #[cfg(not(feature = "worldgen"))]
                     let prices = None;
                     let agents = server.state.ecs().read_storage::<Agent>();
@@ -150,7 +165,13 @@ pub(super) fn handle_process_trade_action(
                                     agents
                                         .get(e)
                                         .and_then(|a| a.behavior.trade_site())
-                                        .and_then(|id| server.index.get_site_prices(id))
+                                        .and_then(|id| {
+                                            site_prices(
+                                                rtsim.as_deref(),
+                                                server.index.as_index_ref(),
+                                                id,
+                                            )
+                                        })
                                 });
                             }
                         }
@@ -169,6 +190,7 @@ pub(super) fn handle_process_trade_action(
                             #[cfg(feature = "worldgen")]
                             notify_agent_prices(
                                 server.state.ecs().write_storage::<Agent>(),
+                                rtsim.as_deref(),
                                 &server.index,
                                 e,
                                 AgentEvent::UpdatePendingTrade(Box::new((
@@ -217,6 +239,28 @@ pub(crate) fn cancel_trades_for(state: &mut common_state::State, entity: EcsEnti
     }
 }
 
+/// The item ids and amounts each party offered, used to inform plugins about
+/// completed trades
+#[cfg(feature = "plugins")]
+fn offered_items(ecs: &specs::World, trade: &PendingTrade) -> [Vec<(String, u32)>; 2] {
+    let inventories = ecs.read_storage::<Inventory>();
+    std::array::from_fn(|who| {
+        ecs.entity_from_uid(trade.parties[who])
+            .and_then(|entity| inventories.get(entity))
+            .map(|inventory| {
+                trade.offers[who]
+                    .iter()
+                    .filter_map(|(slot, amount)| {
+                        inventory
+                            .get(*slot)
+                            .map(|item| (item.persistence_item_id(), *amount))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    })
+}
+
 /// Commit a trade that both parties have agreed to, modifying their respective
 /// inventories
 fn commit_trade(ecs: &specs::World, trade: &PendingTrade) -> TradeResult {
@@ -14,7 +14,10 @@ mod chunk_serialize;
 pub mod client;
 pub mod cmd;
 pub mod connection_handler;
+pub mod console;
 mod data_dir;
+#[cfg(feature = "persistent_world")]
+pub mod entity_persistence;
 pub mod error;
 pub mod events;
 pub mod input;
@@ -24,6 +27,7 @@ pub mod login_provider;
 pub mod metrics;
 pub mod persistence;
 mod pet;
+#[cfg(feature = "plugins")] mod plugin;
 pub mod presence;
 pub mod rtsim;
 pub mod settings;
@@ -100,9 +104,10 @@ use common_net::{
 use common_state::{AreasContainer, BlockDiff, BuildArea, State};
 use common_systems::add_local_systems;
 use metrics::{EcsSystemMetrics, PhysicsMetrics, TickMetrics};
-use network::{ListenAddr, Network, Pid};
+use network::{ListenAddr, Network, Pid, StaticKey};
 use persistence::{
-    character_loader::{CharacterLoader, CharacterUpdaterMessage},
+    backup::BackupScheduler,
+    character_loader::{CharacterFileResponseKind, CharacterLoader, CharacterUpdaterMessage},
     character_updater::CharacterUpdater,
 };
 use prometheus::Registry;
@@ -134,10 +139,7 @@ use std::sync::RwLock;
 use crate::settings::Protocol;
 
 #[cfg(feature = "plugins")]
-use {
-    common::uid::IdMaps,
-    common_state::plugin::{PluginMgr, memory_manager::EcsWorld},
-};
+use common_state::plugin::{PluginEvent, PluginMgr};
 
 use crate::{chat::ChatCache, persistence::character_loader::CharacterScreenResponseKind};
 use common::comp::Anchor;
@@ -249,9 +251,13 @@ pub struct Server {
     metrics_registry: Arc<Registry>,
     chat_cache: ChatCache,
     database_settings: Arc<RwLock<DatabaseSettings>>,
+    database_backups: BackupScheduler,
     disconnect_all_clients_requested: bool,
 
     event_dispatcher: SendDispatcher<'static>,
+
+    #[cfg(all(feature = "plugins", feature = "hot-reloading"))]
+    plugin_watcher: Option<common::assets::ReloadWatcher>,
 }
 
 impl Server {
@@ -292,6 +298,7 @@ impl Server {
         let physics_metrics = PhysicsMetrics::new(&registry).unwrap();
         let server_event_metrics = metrics::ServerEventMetrics::new(&registry).unwrap();
         let query_server_metrics = metrics::QueryServerMetrics::new(&registry).unwrap();
+        let network_traffic_metrics = metrics::NetworkTrafficMetrics::new(&registry).unwrap();
 
         let battlemode_buffer = BattleModeBuffer::default();
 
@@ -299,7 +306,16 @@ impl Server {
 
         // Load plugins before generating the world.
         #[cfg(feature = "plugins")]
-        let plugin_mgr = PluginMgr::from_asset_or_default();
+        let plugin_mgr = {
+            let mut plugin_mgr = PluginMgr::from_asset_or_default();
+            match persistence::plugin_storage::DatabasePluginStorage::new(Arc::clone(
+                &database_settings,
+            )) {
+                Ok(storage) => plugin_mgr.set_storage(Arc::new(storage)),
+                Err(e) => error!(?e, "Couldn't load the plugin storage, plugins can't use it"),
+            }
+            plugin_mgr
+        };
 
         debug!("Generating world, seed: {}", settings.world_seed);
         #[cfg(feature = "worldgen")]
@@ -377,6 +393,8 @@ impl Server {
         state
             .ecs_mut()
             .insert(EventBus::<chunk_serialize::ChunkSendEntry>::default());
+        #[cfg(feature = "plugins")]
+        state.ecs_mut().insert(EventBus::<PluginEvent>::default());
         state.ecs_mut().insert(Locations::default());
         state.ecs_mut().insert(LoginProvider::new(
             settings.auth_server_address.clone(),
@@ -399,6 +417,7 @@ impl Server {
         state.ecs_mut().insert(physics_metrics);
         state.ecs_mut().insert(server_event_metrics);
         state.ecs_mut().insert(query_server_metrics);
+        state.ecs_mut().insert(network_traffic_metrics);
         if settings.experimental_terrain_persistence {
             #[cfg(feature = "persistent_world")]
             {
@@ -550,6 +569,8 @@ impl Server {
         state.ecs_mut().insert(DeletedEntities::default());
 
         let network = Network::new_with_registry(Pid::new(), &runtime, &registry);
+        network.set_resume_timeout(settings.session_resume_timeout);
+        network.set_network_conditions(settings.simulated_network_conditions);
         let (chat_cache, chat_tracker) = ChatCache::new(Duration::from_secs(60), &runtime);
         state.ecs_mut().insert(chat_tracker);
 
@@ -559,6 +580,46 @@ impl Server {
                 Protocol::Tcp { address } => {
                     runtime.block_on(network.listen(ListenAddr::Tcp(*address)))?;
                 },
+                Protocol::TcpEncrypted {
+                    address,
+                    key_file_path,
+                } => {
+                    use std::fs;
+
+                    match || -> Result<_, Box<dyn std::error::Error>> {
+                        if key_file_path.exists() {
+                            let pkcs8 = fs::read(key_file_path)?;
+                            Ok(StaticKey::from_pkcs8(&pkcs8).map_err(|e| e.to_string())?)
+                        } else {
+                            let (static_key, pkcs8) = StaticKey::generate();
+                            if let Some(parent) = key_file_path.parent() {
+                                fs::create_dir_all(parent)?;
+                            }
+                            fs::write(key_file_path, pkcs8)?;
+                            info!(?key_file_path, "Generated a new static key");
+                            Ok(static_key)
+                        }
+                    }() {
+                        Ok(static_key) => {
+                            // Clients have to pin this key to encrypt the connection
+                            info!(
+                                public_key = %static_key.public_key(),
+                                "Listening for encrypted TCP on {}", *address
+                            );
+                            let static_key = Arc::new(static_key);
+                            runtime.block_on(
+                                network.listen(ListenAddr::TcpEncrypted(*address, static_key)),
+                            )?;
+                        },
+                        Err(e) => {
+                            error!(
+                                ?e,
+                                "Failed to load the static key, running without encrypted TCP {}",
+                                *address
+                            );
+                        },
+                    }
+                },
                 Protocol::Quic {
                     address,
                     cert_file_path,
@@ -633,7 +694,7 @@ impl Server {
         }
 
         if let Some(addr) = settings.query_address {
-            use veloren_query_server::proto::ServerInfo;
+            use veloren_query_server::proto::{ExtendedServerInfo, ServerInfo};
 
             const QUERY_SERVER_RATELIMIT: u16 = 120;
 
@@ -645,8 +706,22 @@ impl Server {
                     player_cap: settings.max_players,
                     battlemode: settings.gameplay.battle_mode.into(),
                 });
+            let (query_extended_info_tx, query_extended_info_rx) =
+                tokio::sync::watch::channel(ExtendedServerInfo::new(
+                    settings.world_seed,
+                    settings.day_length,
+                    "",
+                    &settings.server_tags,
+                ));
             let mut query_server =
-                QueryServer::new(addr, query_server_info_rx, QUERY_SERVER_RATELIMIT);
+                QueryServer::new(addr, query_server_info_rx, QUERY_SERVER_RATELIMIT)
+                    .with_extended_info(query_extended_info_rx);
+            if settings.query_player_list {
+                let (query_player_list_tx, query_player_list_rx) =
+                    tokio::sync::watch::channel(Vec::new());
+                query_server = query_server.with_player_list(query_player_list_rx);
+                state.ecs_mut().insert(query_player_list_tx);
+            }
             let query_server_metrics =
                 Arc::new(Mutex::new(veloren_query_server::server::Metrics::default()));
             let query_server_metrics2 = Arc::clone(&query_server_metrics);
@@ -655,6 +730,7 @@ impl Server {
                 error!(?err, "Query server stopped unexpectedly");
             });
             state.ecs_mut().insert(query_server_info_tx);
+            state.ecs_mut().insert(query_extended_info_tx);
             state.ecs_mut().insert(query_server_metrics);
         }
 
@@ -683,6 +759,9 @@ impl Server {
             weather::init(&mut state);
         }
 
+        #[cfg(all(feature = "plugins", feature = "hot-reloading"))]
+        let plugin_watcher = plugin::plugin_watcher();
+
         let this = Self {
             state,
             world,
@@ -692,10 +771,14 @@ impl Server {
 
             metrics_registry: registry,
             chat_cache,
+            database_backups: BackupScheduler::new(Arc::clone(&database_settings)),
             database_settings,
             disconnect_all_clients_requested: false,
 
             event_dispatcher: Self::create_event_dispatcher(pools),
+
+            #[cfg(all(feature = "plugins", feature = "hot-reloading"))]
+            plugin_watcher,
         };
 
         debug!(?settings, "created veloren server with");
@@ -825,6 +908,12 @@ impl Server {
         let before_state_tick = Instant::now();
 
         fn on_block_update(ecs: &specs::World, changes: Vec<BlockDiff>) {
+            #[cfg(feature = "plugins")]
+            if !changes.is_empty() {
+                ecs.read_resource::<EventBus<PluginEvent>>()
+                    .emit_now(PluginEvent::BlockChange(changes.clone()));
+            }
+
             // When a resource block updates, inform rtsim
             if changes
                 .iter()
@@ -860,6 +949,15 @@ impl Server {
         // Handle entity links (such as mounting)
         self.state.maintain_links();
 
+        // Run the periodic logic of plugins, the actions they request are applied
+        // together with the other plugin actions while handling events
+        #[cfg(feature = "plugins")]
+        {
+            #[cfg(feature = "hot-reloading")]
+            self.reload_changed_plugins();
+            self.tick_plugins(dt);
+        }
+
         // Handle game events
         frontend_events.append(&mut self.handle_events());
 
@@ -992,6 +1090,17 @@ impl Server {
                 .collect::<Vec<_>>()
         };
 
+        // Save the entities that aren't generated again, so they return when their
+        // chunk loads
+        #[cfg(feature = "persistent_world")]
+        if let Some(mut terrain_persistence) =
+            self.state.ecs().try_fetch_mut::<TerrainPersistence>()
+        {
+            for (key, entities) in entity_persistence::take_entities(self.state.ecs(), &to_delete) {
+                terrain_persistence.save_entities(key, entities);
+            }
+        }
+
         #[cfg(feature = "worldgen")]
         {
             let mut rtsim = self.state.ecs().write_resource::<rtsim::RtSim>();
@@ -1037,6 +1146,52 @@ impl Server {
                 CharacterUpdaterMessage::DatabaseBatchCompletion(batch_id) => {
                     character_updater.process_batch_completion(batch_id);
                 },
+                CharacterUpdaterMessage::CharacterFileResponse(response) => {
+                    let Some(target_entity) = response.target_entity else {
+                        // Requested from the console, which doesn't wait for the result
+                        response.log();
+                        return;
+                    };
+                    let (chat_type, content) = match response.response_kind {
+                        CharacterFileResponseKind::Export {
+                            result: Ok(alias), ..
+                        } => (
+                            ChatType::CommandInfo,
+                            Content::localized_with_args("command-export_character-success", [
+                                ("alias", alias),
+                                ("file", response.file),
+                            ]),
+                        ),
+                        CharacterFileResponseKind::Export {
+                            character_id,
+                            result: Err(error),
+                        } => (
+                            ChatType::CommandError,
+                            Content::localized_with_args("command-export_character-failed", [
+                                ("id", character_id.0.to_string()),
+                                ("error", error.to_string()),
+                            ]),
+                        ),
+                        CharacterFileResponseKind::Import(Ok((character_id, alias))) => (
+                            ChatType::CommandInfo,
+                            Content::localized_with_args("command-import_character-success", [
+                                ("alias", alias),
+                                ("id", character_id.0.to_string()),
+                            ]),
+                        ),
+                        CharacterFileResponseKind::Import(Err(error)) => (
+                            ChatType::CommandError,
+                            Content::localized_with_args("command-import_character-failed", [
+                                ("file", response.file),
+                                ("error", error.to_string()),
+                            ]),
+                        ),
+                    };
+                    self.notify_client(
+                        target_entity,
+                        ServerGeneral::server_msg(chat_type, content),
+                    );
+                },
                 CharacterUpdaterMessage::CharacterScreenResponse(response) => {
                     match response.response_kind {
                         CharacterScreenResponseKind::CharacterList(result) => match result {
@@ -1144,6 +1299,14 @@ impl Server {
         drop(character_loader);
         drop(character_updater);
 
+        self.database_backups.maintain(
+            &self
+                .state
+                .ecs()
+                .read_resource::<Settings>()
+                .database_backups,
+        );
+
         {
             // Check for new chunks; cancel and regenerate all chunks if the asset has been
             // reloaded. Note that all of these assignments are no-ops, so the
@@ -1345,6 +1508,8 @@ impl Server {
     {
         if let Some(client) = self.state.ecs().read_storage::<Client>().get(entity) {
             client.send_fallible(msg);
+        } else {
+            console::ConsoleSession::record(self.state.ecs(), entity, msg.into());
         }
     }
 
@@ -1357,15 +1522,7 @@ impl Server {
         } else {
             #[cfg(feature = "plugins")]
             {
-                let mut plugin_manager = self.state.ecs().write_resource::<PluginMgr>();
-                let ecs_world = EcsWorld {
-                    entities: &self.state.ecs().entities(),
-                    health: self.state.ecs().read_component().into(),
-                    uid: self.state.ecs().read_component().into(),
-                    id_maps: &self.state.ecs().read_resource::<IdMaps>().into(),
-                    player: self.state.ecs().read_component().into(),
-                };
-                let uid = if let Some(uid) = ecs_world.uid.get(entity).copied() {
+                let uid = if let Some(uid) = self.state.ecs().uid_from_entity(entity) {
                     uid
                 } else {
                     self.notify_client(
@@ -1379,7 +1536,10 @@ impl Server {
                     );
                     return;
                 };
-                match plugin_manager.command_event(&ecs_world, &name, args.as_slice(), uid) {
+                let result = self.with_plugins(|plugin_manager, ecs_world| {
+                    plugin_manager.command_event(ecs_world, &name, args.as_slice(), uid)
+                });
+                match result {
                     Err(common_state::plugin::CommandResults::UnknownCommand) => self
                         .notify_client(
                             entity,
@@ -1500,6 +1660,16 @@ impl Server {
         };
     }
 
+    /// Load the editable settings (whitelist, banlist, admins, ...) from disk
+    /// again, to pick up changes made to the files while the server is running.
+    ///
+    /// NOTE: Roles of online players only change when they log in again.
+    pub fn reload_editable_settings(&self) {
+        let editable_settings = EditableSettings::load(&self.data_dir().path);
+        *self.editable_settings_mut() = editable_settings;
+        info!("Reloaded editable settings");
+    }
+
     /// Useful for testing without a client
     /// view_distance: distance in chunks that are persisted, this acts like the
     /// player view distance so it is actually a bit farther due to a buffer
@@ -1752,6 +1922,11 @@ impl Drop for Server {
             .try_fetch_mut::<TerrainPersistence>()
             .map(|mut terrain_persistence| {
                 info!("Unloading terrain persistence...");
+                let ecs = self.state.ecs();
+                let entities = (&ecs.entities()).join().collect::<Vec<_>>();
+                for (key, entities) in entity_persistence::take_entities(ecs, &entities) {
+                    terrain_persistence.save_entities(key, entities);
+                }
                 terrain_persistence.unload_all()
             });
 
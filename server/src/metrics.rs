@@ -1,3 +1,4 @@
+use crate::client::Traffic;
 use prometheus::{
     Gauge, GaugeVec, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge,
     IntGaugeVec, Opts, Registry,
@@ -70,6 +71,12 @@ pub struct ServerEventMetrics {
     pub event_count: IntCounterVec,
 }
 
+/// Bytes sent to and received from all clients, by message type
+pub struct NetworkTrafficMetrics {
+    pub sent_bytes: IntCounterVec,
+    pub received_bytes: IntCounterVec,
+}
+
 pub struct QueryServerMetrics {
     pub received_packets: IntCounter,
     pub dropped_packets: IntCounter,
@@ -77,6 +84,8 @@ pub struct QueryServerMetrics {
     pub proccessing_errors: IntCounter,
     pub info_requests: IntCounter,
     pub init_requests: IntCounter,
+    pub player_list_requests: IntCounter,
+    pub extended_info_requests: IntCounter,
     pub sent_responses: IntCounter,
     pub failed_responses: IntCounter,
     pub timed_out_responses: IntCounter,
@@ -441,6 +450,41 @@ impl ServerEventMetrics {
     }
 }
 
+impl NetworkTrafficMetrics {
+    pub fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
+        let sent_bytes = IntCounterVec::new(
+            Opts::new(
+                "network_sent_bytes",
+                "bytes of messages sent to clients, after compression",
+            ),
+            &["msg"],
+        )?;
+        let received_bytes = IntCounterVec::new(
+            Opts::new(
+                "network_received_bytes",
+                "bytes of messages received from clients, after compression",
+            ),
+            &["msg"],
+        )?;
+        registry.register(Box::new(sent_bytes.clone()))?;
+        registry.register(Box::new(received_bytes.clone()))?;
+
+        Ok(Self {
+            sent_bytes,
+            received_bytes,
+        })
+    }
+
+    pub fn apply(&self, traffic: &Traffic) {
+        for (msg, bytes) in &traffic.sent {
+            self.sent_bytes.with_label_values(&[msg]).inc_by(*bytes);
+        }
+        for (msg, bytes) in &traffic.received {
+            self.received_bytes.with_label_values(&[msg]).inc_by(*bytes);
+        }
+    }
+}
+
 impl QueryServerMetrics {
     pub fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
         let received_packets = IntCounter::with_opts(Opts::new(
@@ -467,6 +511,14 @@ impl QueryServerMetrics {
             "query_server::ping_requests",
             "Amount of init requests received by the query server",
         ))?;
+        let player_list_requests = IntCounter::with_opts(Opts::new(
+            "query_server::player_list_requests",
+            "Amount of player list requests received by the query server",
+        ))?;
+        let extended_info_requests = IntCounter::with_opts(Opts::new(
+            "query_server::extended_info_requests",
+            "Amount of extended server info requests received by the query server",
+        ))?;
         let sent_responses = IntCounter::with_opts(Opts::new(
             "query_server::sent_responses",
             "Amount of responses sent by the query server",
@@ -490,6 +542,8 @@ impl QueryServerMetrics {
         registry.register(Box::new(proccessing_errors.clone()))?;
         registry.register(Box::new(info_requests.clone()))?;
         registry.register(Box::new(init_requests.clone()))?;
+        registry.register(Box::new(player_list_requests.clone()))?;
+        registry.register(Box::new(extended_info_requests.clone()))?;
         registry.register(Box::new(sent_responses.clone()))?;
         registry.register(Box::new(failed_responses.clone()))?;
         registry.register(Box::new(timed_out_responses.clone()))?;
@@ -502,6 +556,8 @@ impl QueryServerMetrics {
             proccessing_errors,
             info_requests,
             init_requests,
+            player_list_requests,
+            extended_info_requests,
             sent_responses,
             failed_responses,
             timed_out_responses,
@@ -518,6 +574,8 @@ impl QueryServerMetrics {
             proccessing_errors,
             info_requests,
             init_requests,
+            player_list_requests,
+            extended_info_requests,
             sent_responses,
             failed_responses,
             timed_out_responses,
@@ -530,6 +588,10 @@ impl QueryServerMetrics {
         self.proccessing_errors.inc_by(proccessing_errors as u64);
         self.info_requests.inc_by(info_requests as u64);
         self.init_requests.inc_by(init_requests as u64);
+        self.player_list_requests
+            .inc_by(player_list_requests as u64);
+        self.extended_info_requests
+            .inc_by(extended_info_requests as u64);
         self.sent_responses.inc_by(sent_responses as u64);
         self.failed_responses.inc_by(failed_responses as u64);
         self.timed_out_responses.inc_by(timed_out_responses as u64);
@@ -0,0 +1,8 @@
+-- Key/value storage of server plugins, namespaced by the plugin name
+CREATE TABLE plugin_storage
+(
+    plugin TEXT NOT NULL,
+    key TEXT NOT NULL,
+    value BLOB NOT NULL,
+    PRIMARY KEY (plugin, key)
+) WITHOUT ROWID;
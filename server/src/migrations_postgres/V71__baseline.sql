@@ -0,0 +1,94 @@
+-- Schema of SQLite databases after V71, PostgreSQL databases start here.
+-- Every later migration has to be added to both backends with the same version.
+--
+-- Foreign keys are deferrable, so character updates can defer them like
+-- SQLite's defer_foreign_keys pragma does. The position of an item in its
+-- container is a deferrable unique constraint for the same reason.
+CREATE TABLE entity
+(
+    entity_id BIGINT NOT NULL PRIMARY KEY
+);
+
+-- Servers sharing the database take new entity IDs in blocks of 65536 from
+-- this sequence, see `get_new_entity_ids`. Block 0 is left to the fixed IDs
+-- inserted below.
+CREATE SEQUENCE entity_id_block_seq INCREMENT BY 65536 START WITH 65536;
+
+CREATE TABLE item
+(
+    item_id                  BIGINT NOT NULL PRIMARY KEY
+        REFERENCES entity (entity_id) DEFERRABLE,
+    parent_container_item_id BIGINT NOT NULL
+        REFERENCES item (item_id) DEFERRABLE,
+    item_definition_id       TEXT   NOT NULL,
+    stack_size               BIGINT NOT NULL,
+    position                 TEXT   NOT NULL,
+    properties               TEXT   NOT NULL DEFAULT '{}',
+    CONSTRAINT idx_parent_container_item_id_position
+        UNIQUE (parent_container_item_id, position) DEFERRABLE
+);
+
+CREATE INDEX idx_item_definition_id ON item (item_definition_id);
+
+CREATE TABLE body
+(
+    body_id   BIGINT NOT NULL PRIMARY KEY
+        REFERENCES entity (entity_id) DEFERRABLE,
+    variant   TEXT   NOT NULL,
+    body_data TEXT   NOT NULL
+);
+
+CREATE TABLE character
+(
+    character_id BIGINT NOT NULL PRIMARY KEY
+        REFERENCES body (body_id) DEFERRABLE
+        REFERENCES item (item_id) DEFERRABLE,
+    player_uuid  TEXT   NOT NULL,
+    alias        TEXT   NOT NULL,
+    waypoint     TEXT,
+    hardcore     BIGINT NOT NULL DEFAULT 0
+);
+
+CREATE INDEX idx_player_uuid ON character (player_uuid);
+
+CREATE TABLE pet
+(
+    pet_id       BIGINT NOT NULL PRIMARY KEY
+        REFERENCES entity (entity_id) DEFERRABLE,
+    character_id BIGINT NOT NULL
+        REFERENCES character (character_id) DEFERRABLE,
+    name         TEXT   NOT NULL
+);
+
+CREATE TABLE skill_group
+(
+    entity_id        BIGINT NOT NULL
+        REFERENCES entity (entity_id) DEFERRABLE,
+    skill_group_kind TEXT   NOT NULL,
+    earned_exp       BIGINT NOT NULL,
+    spent_exp        BIGINT NOT NULL,
+    skills           TEXT   NOT NULL,
+    hash_val         BYTEA  NOT NULL,
+    PRIMARY KEY (entity_id, skill_group_kind)
+);
+
+CREATE TABLE ability_set
+(
+    entity_id    BIGINT NOT NULL PRIMARY KEY
+        REFERENCES character (character_id) DEFERRABLE,
+    ability_sets TEXT   NOT NULL
+);
+
+-- Key/value storage of server plugins, namespaced by the plugin name
+CREATE TABLE plugin_storage
+(
+    plugin TEXT  NOT NULL,
+    key    TEXT  NOT NULL,
+    value  BYTEA NOT NULL,
+    PRIMARY KEY (plugin, key)
+);
+
+-- The world pseudo-container all character pseudo-containers are stored in
+INSERT INTO entity (entity_id) VALUES (1);
+INSERT INTO item (item_id, parent_container_item_id, item_definition_id, stack_size, position, properties)
+VALUES (1, 1, 'veloren.core.pseudo_containers.world', 1, 'world', '{}');
@@ -0,0 +1,330 @@
+//! Snapshots of the SQLite database, taken while the server runs
+//!
+//! Copying `db.sqlite` while the server writes to it can produce a torn copy,
+//! and misses everything still in the write-ahead log. Backups are created
+//! with SQLite's online backup API instead, which copies a consistent state
+//! of the database. They are saved as `backups/db-<time>.sqlite` in the saves
+//! directory and can be restored while the server is stopped.
+
+use super::{
+    ConnectionMode, DatabaseBackend, DatabaseSettings, error::PersistenceError,
+    establish_sqlite_connection,
+};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+use tracing::{error, info};
+
+const BACKUP_DIR: &str = "backups";
+const BACKUP_PREFIX: &str = "db-";
+const BACKUP_EXTENSION: &str = ".sqlite";
+/// Down to milliseconds, so backups created shortly after one another (like the
+/// one taken before restoring) get different names
+const TIME_FORMAT: &str = "%Y%m%d-%H%M%S-%3f";
+/// Backups created by older versions only had second resolution
+const LEGACY_TIME_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// When the server backs up its database and how many backups are kept
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackupSettings {
+    /// Time between periodic backups, `None` to only create them on demand
+    pub interval: Option<Duration>,
+    /// Number of backups kept, the oldest ones are deleted after a new one
+    /// was created
+    pub keep: usize,
+    /// Backups older than this are deleted as well, even if there are fewer
+    /// than `keep`
+    pub max_age: Option<Duration>,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            interval: Some(Duration::from_secs(6 * 60 * 60)),
+            keep: 8,
+            max_age: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BackupInfo {
+    /// File name of the backup, used to restore it
+    pub name: String,
+    pub created: DateTime<Utc>,
+    /// In bytes
+    pub size: u64,
+}
+
+fn backup_dir(settings: &DatabaseSettings) -> PathBuf { settings.db_dir.join(BACKUP_DIR) }
+
+fn backup_name(created: DateTime<Utc>) -> String {
+    format!(
+        "{BACKUP_PREFIX}{}{BACKUP_EXTENSION}",
+        created.format(TIME_FORMAT)
+    )
+}
+
+fn parse_backup_name(name: &str) -> Option<DateTime<Utc>> {
+    let time = name
+        .strip_prefix(BACKUP_PREFIX)?
+        .strip_suffix(BACKUP_EXTENSION)?;
+    NaiveDateTime::parse_from_str(time, TIME_FORMAT)
+        .or_else(|_| NaiveDateTime::parse_from_str(time, LEGACY_TIME_FORMAT))
+        .ok()
+        .map(|time| time.and_utc())
+}
+
+fn check_backend(settings: &DatabaseSettings) -> Result<(), PersistenceError> {
+    match settings.backend {
+        DatabaseBackend::Sqlite => Ok(()),
+        DatabaseBackend::Postgres { .. } => Err(PersistenceError::OtherError(
+            "Backups are only supported by SQLite, use pg_dump for PostgreSQL".to_owned(),
+        )),
+    }
+}
+
+/// Copies all pages in one step. With a write-ahead log this doesn't block
+/// the server from writing, while copying a few pages at a time would start
+/// over whenever the server writes in between.
+fn copy_database(
+    from: &rusqlite::Connection,
+    to: &mut rusqlite::Connection,
+) -> Result<(), PersistenceError> {
+    rusqlite::backup::Backup::new(from, to)?.run_to_completion(-1, Duration::ZERO, None)?;
+    Ok(())
+}
+
+/// Backups of the database, the oldest first
+pub fn list_backups(settings: &DatabaseSettings) -> Result<Vec<BackupInfo>, PersistenceError> {
+    check_backend(settings)?;
+    let dir = match fs::read_dir(backup_dir(settings)) {
+        Ok(dir) => dir,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(PersistenceError::OtherError(format!(
+                "Failed to read the backup directory: {err}"
+            )));
+        },
+    };
+    let mut backups = dir
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let created = parse_backup_name(&name)?;
+            let size = entry.metadata().ok()?.len();
+            Some(BackupInfo {
+                name,
+                created,
+                size,
+            })
+        })
+        .collect::<Vec<_>>();
+    backups.sort_by_key(|backup| backup.created);
+    Ok(backups)
+}
+
+/// Creates a backup of the database, returns its path. Can be used while the
+/// server is running.
+pub fn create_backup(settings: &DatabaseSettings) -> Result<PathBuf, PersistenceError> {
+    check_backend(settings)?;
+    let dir = backup_dir(settings);
+    fs::create_dir_all(&dir).map_err(|err| {
+        PersistenceError::OtherError(format!("Failed to create the backup directory: {err}"))
+    })?;
+    let path = dir.join(backup_name(Utc::now()));
+    // Never replace an existing backup, it might be the only copy of that state
+    if path.try_exists().unwrap_or(true) {
+        return Err(PersistenceError::OtherError(format!(
+            "A backup called {} already exists",
+            path.display()
+        )));
+    }
+    // Written to a different file first, so an interrupted backup is never
+    // mistaken for a complete one
+    let partial_path = path.with_extension("partial");
+
+    let from = establish_sqlite_connection(settings, ConnectionMode::ReadOnly)?;
+    let mut to = rusqlite::Connection::open(&partial_path)?;
+    copy_database(&from, &mut to)?;
+    drop(to);
+    fs::rename(&partial_path, &path).map_err(|err| {
+        PersistenceError::OtherError(format!("Failed to move the finished backup: {err}"))
+    })?;
+
+    Ok(path)
+}
+
+/// Deletes the backups `settings` doesn't keep, returns how many
+pub fn prune_backups(
+    database_settings: &DatabaseSettings,
+    settings: &BackupSettings,
+) -> Result<usize, PersistenceError> {
+    let backups = list_backups(database_settings)?;
+    let now = Utc::now();
+    let excess = backups.len().saturating_sub(settings.keep);
+    let mut deleted = 0;
+    for (i, backup) in backups.iter().enumerate() {
+        let expired = settings.max_age.is_some_and(|max_age| {
+            (now - backup.created)
+                .to_std()
+                .is_ok_and(|age| age > max_age)
+        });
+        if i < excess || expired {
+            let path = backup_dir(database_settings).join(&backup.name);
+            fs::remove_file(&path).map_err(|err| {
+                PersistenceError::OtherError(format!("Failed to delete {}: {err}", path.display()))
+            })?;
+            deleted += 1;
+        }
+    }
+    Ok(deleted)
+}
+
+/// Replaces the database with the backup called `name`. The server must not
+/// be running. The current state of the database is backed up first, so this
+/// can be undone.
+pub fn restore_backup(
+    settings: &DatabaseSettings,
+    name: &str,
+) -> Result<PathBuf, PersistenceError> {
+    if !list_backups(settings)?
+        .iter()
+        .any(|backup| backup.name == name)
+    {
+        return Err(PersistenceError::OtherError(format!(
+            "There is no backup called {name}"
+        )));
+    }
+    // Fails instead of replacing the backup to restore if it was created in the
+    // same millisecond
+    let previous = create_backup(settings)?;
+
+    let from = rusqlite::Connection::open_with_flags(
+        backup_dir(settings).join(name),
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY,
+    )?;
+    let mut to = establish_sqlite_connection(settings, ConnectionMode::ReadWrite)?;
+    copy_database(&from, &mut to)?;
+
+    Ok(previous)
+}
+
+/// Creates periodic and requested backups on a separate thread, one at a time
+pub struct BackupScheduler {
+    database_settings: Arc<RwLock<DatabaseSettings>>,
+    last_backup: Option<DateTime<Utc>>,
+    running: Arc<AtomicBool>,
+}
+
+impl BackupScheduler {
+    pub fn new(database_settings: Arc<RwLock<DatabaseSettings>>) -> Self {
+        // Continue the schedule of the last run of the server, otherwise servers
+        // that restart more often than the interval would never back up
+        let last_backup = list_backups(&database_settings.read().unwrap())
+            .ok()
+            .and_then(|backups| backups.last().map(|backup| backup.created));
+        Self {
+            database_settings,
+            last_backup,
+            running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Starts a backup if the interval of `settings` passed since the last one
+    pub fn maintain(&mut self, settings: &BackupSettings) {
+        let Some(interval) = settings.interval else {
+            return;
+        };
+        if self.database_settings.read().unwrap().backend != DatabaseBackend::Sqlite {
+            return;
+        }
+        let due = self.last_backup.is_none_or(|last_backup| {
+            (Utc::now() - last_backup)
+                .to_std()
+                .is_ok_and(|elapsed| elapsed >= interval)
+        });
+        if due && !self.running.load(Ordering::Acquire) {
+            // Failures are logged, and retried after the next interval
+            let _ = self.start(settings);
+        }
+    }
+
+    /// Starts a backup in the background, its result is only logged. Old
+    /// backups are deleted afterwards according to `settings`.
+    pub fn start(&mut self, settings: &BackupSettings) -> Result<(), PersistenceError> {
+        let database_settings = self.database_settings.read().unwrap().clone();
+        check_backend(&database_settings)?;
+        if self.running.swap(true, Ordering::AcqRel) {
+            return Err(PersistenceError::OtherError(
+                "A backup is already running".to_owned(),
+            ));
+        }
+        self.last_backup = Some(Utc::now());
+
+        let settings = settings.clone();
+        let running = Arc::clone(&self.running);
+        let spawned = std::thread::Builder::new()
+            .name("database_backup".into())
+            .spawn(move || {
+                match create_backup(&database_settings) {
+                    Ok(path) => {
+                        info!(?path, "Created a database backup");
+                        match prune_backups(&database_settings, &settings) {
+                            Ok(0) => {},
+                            Ok(deleted) => info!("Deleted {deleted} old database backups"),
+                            Err(error) => error!(?error, "Failed to delete old database backups"),
+                        }
+                    },
+                    Err(error) => error!(?error, "Failed to back up the database"),
+                }
+                running.store(false, Ordering::Release);
+            });
+        if let Err(err) = spawned {
+            self.running.store(false, Ordering::Release);
+            return Err(PersistenceError::OtherError(format!(
+                "Failed to spawn the backup thread: {err}"
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn backup_names_round_trip() {
+        let created = Utc.with_ymd_and_hms(2024, 5, 17, 8, 30, 5).unwrap()
+            + chrono::Duration::milliseconds(42);
+        let name = backup_name(created);
+        assert_eq!(name, "db-20240517-083005-042.sqlite");
+        assert_eq!(parse_backup_name(&name), Some(created));
+        assert_eq!(parse_backup_name("db-20240517-083005-042.partial"), None);
+        assert_eq!(
+            parse_backup_name("db-20240517-083005.sqlite"),
+            Some(Utc.with_ymd_and_hms(2024, 5, 17, 8, 30, 5).unwrap())
+        );
+        assert_eq!(parse_backup_name("db.sqlite"), None);
+    }
+
+    #[test]
+    fn backups_in_the_same_second_get_different_names() {
+        let created = Utc.with_ymd_and_hms(2024, 5, 17, 8, 30, 5).unwrap();
+        assert_ne!(
+            backup_name(created),
+            backup_name(created + chrono::Duration::milliseconds(1))
+        );
+    }
+}
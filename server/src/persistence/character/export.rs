@@ -0,0 +1,455 @@
+//! Portable files of single characters, to move them between servers or to
+//! restore them from a backup.
+//!
+//! A file holds the decoded components of a character: its body, skills,
+//! abilities and the items it carries by item definition, with the components
+//! of modular items nested in them. Importing a file turns it into the rows the
+//! character would be saved as and loads them with the same conversions as
+//! loading a character, so unknown item definitions and so on are rejected
+//! before anything is written.
+use super::{
+    EntityId, create_character, load_character_data,
+    portable::{ItemData, SkillGroupData, item_to_data, skill_set_from_data, skill_set_to_data},
+    update_pets,
+};
+use crate::persistence::{
+    PersistedComponents, VelorenConnection,
+    backend::{Database, params},
+    character::conversions::{convert_inventory_from_database_items, convert_stats_from_database},
+    error::PersistenceError,
+    models::Item,
+};
+use common::{
+    character::CharacterId,
+    comp::{
+        self, ActiveAbilities, BASE_ABILITY_LIMIT, ability::AuxiliaryAbility,
+        inventory::slot::InvSlotId, item::tool::ToolKind,
+    },
+    resources::Time,
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::{fs, path::Path};
+use tracing::warn;
+use vek::{Vec2, Vec3};
+
+/// Increased on incompatible changes of [`CharacterFile`]
+const VERSION: u32 = 2;
+
+/// Added to the ids of the rows of an imported character, which makes them
+/// larger than any id in the database. [`create_character`] then replaces them
+/// with new ones, like ids from failed transactions.
+const IMPORT_ID_OFFSET: EntityId = 1 << 62;
+
+// Ids of the pseudo containers of an imported character, the ids of its items
+// follow them
+const INVENTORY_ID: EntityId = IMPORT_ID_OFFSET + 1;
+const LOADOUT_ID: EntityId = IMPORT_ID_OFFSET + 2;
+const OVERFLOW_ITEMS_ID: EntityId = IMPORT_ID_OFFSET + 3;
+const RECIPE_BOOK_ID: EntityId = IMPORT_ID_OFFSET + 4;
+const FIRST_ITEM_ID: EntityId = IMPORT_ID_OFFSET + 5;
+
+/// Read before the rest of a file, so files of other versions are rejected
+/// with a clear error
+#[derive(Deserialize)]
+struct FileVersion {
+    version: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CharacterFile {
+    version: u32,
+    alias: String,
+    hardcore: bool,
+    body: comp::Body,
+    waypoint: Option<Vec3<f32>>,
+    map_marker: Option<Vec2<i32>>,
+    skill_groups: Vec<SkillGroupData>,
+    ability_sets: Vec<AbilitySetData>,
+    items: Vec<StoredItem>,
+    pets: Vec<comp::Body>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AbilitySetData {
+    mainhand: Option<ToolKind>,
+    offhand: Option<ToolKind>,
+    abilities: Vec<AuxiliaryAbility>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredItem {
+    slot: ItemSlot,
+    item: ItemData,
+}
+
+#[derive(Serialize, Deserialize)]
+enum ItemSlot {
+    Inventory(InvSlotId),
+    /// The persistence key of the loadout slot
+    Loadout(String),
+    Overflow,
+    RecipeBook,
+}
+
+impl CharacterFile {
+    fn read(path: &Path) -> Result<Self, PersistenceError> {
+        let contents = fs::read_to_string(path).map_err(|err| {
+            PersistenceError::OtherError(format!("Failed to read {}: {err}", path.display()))
+        })?;
+        Self::parse(&contents, is_json(path))
+    }
+
+    fn parse(contents: &str, json: bool) -> Result<Self, PersistenceError> {
+        let FileVersion { version } = deserialize(contents, json)?;
+        if version != VERSION {
+            return Err(PersistenceError::ConversionError(format!(
+                "Unsupported character file version {version}, expected {VERSION}"
+            )));
+        }
+        deserialize(contents, json)
+    }
+
+    fn write(&self, path: &Path) -> Result<(), PersistenceError> {
+        fs::write(path, self.serialize(is_json(path))?).map_err(|err| {
+            PersistenceError::OtherError(format!("Failed to write {}: {err}", path.display()))
+        })
+    }
+
+    fn serialize(&self, json: bool) -> Result<String, PersistenceError> {
+        if json {
+            Ok(serde_json::to_string_pretty(self)?)
+        } else {
+            ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).map_err(|err| {
+                PersistenceError::ConversionError(format!("Failed to serialize character: {err}"))
+            })
+        }
+    }
+}
+
+fn deserialize<T: DeserializeOwned>(contents: &str, json: bool) -> Result<T, PersistenceError> {
+    if json {
+        Ok(serde_json::from_str(contents)?)
+    } else {
+        ron::from_str(contents).map_err(|err| {
+            PersistenceError::ConversionError(format!("Invalid character file: {err}"))
+        })
+    }
+}
+
+/// Files ending with `.json` are JSON, all others RON
+fn is_json(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("json"))
+}
+
+/// Writes a character from the database to a file at `path`, returns its
+/// alias
+pub fn export_character(
+    connection: &mut VelorenConnection,
+    char_id: CharacterId,
+    path: &Path,
+) -> Result<String, PersistenceError> {
+    let file = character_to_file(connection, char_id)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|err| {
+            PersistenceError::OtherError(format!("Failed to create {}: {err}", dir.display()))
+        })?;
+    }
+    file.write(path)?;
+    Ok(file.alias)
+}
+
+/// Creates a new character for the player with `player_uuid` from the file at
+/// `path`. The character limit of the player applies.
+pub fn import_character(
+    path: &Path,
+    player_uuid: &str,
+    connection: &mut VelorenConnection,
+) -> Result<(CharacterId, String), PersistenceError> {
+    let file = CharacterFile::read(path)?;
+    let alias = file.alias.clone();
+    let mut components = file_to_components(file)?;
+    let pets = std::mem::take(&mut components.pets);
+
+    let mut transaction = connection.transaction()?;
+    let (char_id, _) = create_character(player_uuid, &alias, components, &mut transaction)?;
+    update_pets(char_id, pets, &mut transaction)?;
+    transaction.commit()?;
+
+    Ok((char_id, alias))
+}
+
+fn character_to_file(
+    connection: &mut impl Database,
+    char_id: CharacterId,
+) -> Result<CharacterFile, PersistenceError> {
+    let row = connection.query_row(
+        "
+        SELECT  player_uuid,
+                alias
+        FROM    character
+        WHERE   character_id = ?1",
+        params![char_id.0],
+    )?;
+    let player_uuid: String = row.get(0)?;
+    let alias: String = row.get(1)?;
+
+    let (components, metadata) = load_character_data(player_uuid, char_id, connection)?;
+    if let Some(error) = metadata.skill_set_persistence_load_error {
+        warn!(
+            ?error,
+            "Failed to load the skills of character {}, exporting it without them", char_id.0
+        );
+    }
+    Ok(components_to_file(alias, &components))
+}
+
+fn components_to_file(alias: String, components: &PersistedComponents) -> CharacterFile {
+    let inventory = &components.inventory;
+    let stored = |slot, item: &comp::Item| StoredItem {
+        slot,
+        item: item_to_data(item),
+    };
+    let items = inventory
+        .slots_with_id()
+        .filter_map(|(slot, item)| Some(stored(ItemSlot::Inventory(slot), item.as_ref()?)))
+        .chain(
+            inventory
+                .loadout_items_with_persistence_key()
+                .filter_map(|(key, item)| Some(stored(ItemSlot::Loadout(key.to_owned()), item?))),
+        )
+        .chain(
+            inventory
+                .overflow_items()
+                .map(|item| stored(ItemSlot::Overflow, item)),
+        )
+        .chain(
+            inventory
+                .persistence_recipes_iter_with_index()
+                .map(|(_, item)| stored(ItemSlot::RecipeBook, item)),
+        )
+        .collect();
+
+    // Both are sorted, so exporting a character twice gives the same file
+    let skill_groups = skill_set_to_data(&components.skill_set);
+    let mut ability_sets = components
+        .active_abilities
+        .auxiliary_sets
+        .iter()
+        .map(|(&(mainhand, offhand), abilities)| AbilitySetData {
+            mainhand,
+            offhand,
+            abilities: abilities.clone(),
+        })
+        .collect::<Vec<_>>();
+    ability_sets.sort_by_key(|ability_set| (ability_set.mainhand, ability_set.offhand));
+
+    CharacterFile {
+        version: VERSION,
+        alias,
+        hardcore: components.hardcore.is_some(),
+        body: components.body,
+        waypoint: components.waypoint.map(|waypoint| waypoint.get_pos()),
+        map_marker: components.map_marker.map(|map_marker| map_marker.0),
+        skill_groups,
+        ability_sets,
+        items,
+        pets: components.pets.iter().map(|(_, body, _)| *body).collect(),
+    }
+}
+
+/// Validates the contents of a file by converting them into components
+fn file_to_components(file: CharacterFile) -> Result<PersistedComponents, PersistenceError> {
+    // The rows of each pseudo container, the loader expects them in separate
+    // lists
+    let mut rows: [Vec<Item>; 4] = Default::default();
+    let mut next_id = FIRST_ITEM_ID;
+    for StoredItem { slot, item } in file.items {
+        let (container, position) = match slot {
+            ItemSlot::Inventory(slot) => (INVENTORY_ID, serde_json::to_string(&slot)?),
+            ItemSlot::Loadout(key) => (LOADOUT_ID, key),
+            // These positions only have to be unique
+            ItemSlot::Overflow => (OVERFLOW_ITEMS_ID, next_id.to_string()),
+            ItemSlot::RecipeBook => (RECIPE_BOOK_ID, next_id.to_string()),
+        };
+        item_to_rows(
+            item,
+            container,
+            position,
+            &mut next_id,
+            &mut rows[(container - INVENTORY_ID) as usize],
+        )?;
+    }
+    let [
+        inventory_items,
+        loadout_items,
+        overflow_items,
+        recipe_book_items,
+    ] = rows;
+    let inventory = convert_inventory_from_database_items(
+        INVENTORY_ID,
+        &inventory_items,
+        LOADOUT_ID,
+        &loadout_items,
+        OVERFLOW_ITEMS_ID,
+        &overflow_items,
+        &recipe_book_items,
+    )?;
+
+    let (skill_set, skill_set_error) = skill_set_from_data(file.skill_groups)?;
+    if let Some(error) = skill_set_error {
+        warn!(
+            ?error,
+            "The skills of the imported character {} were reset", file.alias
+        );
+    }
+
+    let active_abilities = ActiveAbilities::from_auxiliary(
+        file.ability_sets
+            .into_iter()
+            .map(|ability_set| {
+                (
+                    (ability_set.mainhand, ability_set.offhand),
+                    ability_set.abilities,
+                )
+            })
+            .collect(),
+        Some(BASE_ABILITY_LIMIT),
+    );
+
+    let pets = file
+        .pets
+        .into_iter()
+        .map(|body| (comp::Pet::default(), body, comp::Stats::empty(body)))
+        .collect();
+
+    Ok(PersistedComponents {
+        body: file.body,
+        hardcore: file.hardcore.then_some(comp::Hardcore),
+        stats: convert_stats_from_database(file.alias, file.body),
+        skill_set,
+        inventory,
+        waypoint: file.waypoint.map(|pos| comp::Waypoint::new(pos, Time(0.0))),
+        pets,
+        active_abilities,
+        map_marker: file.map_marker.map(comp::MapMarker),
+    })
+}
+
+/// Adds the rows of an item and its components to `rows`, parents before
+/// their children
+fn item_to_rows(
+    item: ItemData,
+    parent: EntityId,
+    position: String,
+    next_id: &mut EntityId,
+    rows: &mut Vec<Item>,
+) -> Result<(), PersistenceError> {
+    let item_id = *next_id;
+    *next_id += 1;
+    rows.push(Item {
+        item_id,
+        parent_container_item_id: parent,
+        item_definition_id: item.definition,
+        stack_size: i64::from(item.amount),
+        position,
+        properties: serde_json::to_string(&item.properties)?,
+    });
+    for (i, component) in item.components.into_iter().enumerate() {
+        item_to_rows(component, item_id, format!("component_{i}"), next_id, rows)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CharacterFile, components_to_file, file_to_components};
+    use crate::persistence::{PersistedComponents, error::PersistenceError};
+    use common::{
+        comp::{
+            self, BASE_ABILITY_LIMIT, Content, Inventory, Item, MapMarker, SkillSet, Stats,
+            Waypoint, inventory::loadout_builder::LoadoutBuilder, skillset::SkillGroupKind,
+        },
+        resources::Time,
+    };
+    use vek::{Vec2, Vec3};
+
+    fn components() -> PersistedComponents {
+        let body = comp::Body::Humanoid(comp::humanoid::Body::random());
+        let pet = comp::Body::QuadrupedSmall(comp::quadruped_small::Body::random());
+        let mut inventory =
+            Inventory::with_loadout_humanoid(LoadoutBuilder::empty().defaults().build());
+        let mut potions = Item::new_from_asset_expect("common.items.consumable.potion_minor");
+        potions.set_amount(3).unwrap();
+        inventory.push(potions).unwrap();
+        inventory
+            .push(Item::new_from_asset_expect("common.items.food.cheese"))
+            .unwrap();
+        let mut skill_set = SkillSet::default();
+        skill_set.add_experience(SkillGroupKind::General, 1000);
+        PersistedComponents {
+            body,
+            hardcore: Some(comp::Hardcore),
+            stats: Stats::new(Content::Plain("Tester".to_string()), body),
+            skill_set,
+            inventory,
+            waypoint: Some(Waypoint::new(Vec3::new(1.0, 2.0, 3.0), Time(0.0))),
+            pets: vec![(comp::Pet::default(), pet, Stats::empty(pet))],
+            active_abilities: comp::ActiveAbilities::default_limited(BASE_ABILITY_LIMIT),
+            map_marker: Some(MapMarker(Vec2::new(4, 5))),
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        let components = components();
+        for json in [false, true] {
+            let contents = components_to_file("Tester".to_string(), &components)
+                .serialize(json)
+                .unwrap();
+            let imported =
+                file_to_components(CharacterFile::parse(&contents, json).unwrap()).unwrap();
+            assert_eq!(imported.body, components.body);
+            assert!(imported.hardcore.is_some());
+            assert_eq!(
+                imported.waypoint.map(|waypoint| waypoint.get_pos()),
+                Some(Vec3::new(1.0, 2.0, 3.0))
+            );
+            assert_eq!(
+                imported.map_marker.map(|map_marker| map_marker.0),
+                Some(Vec2::new(4, 5))
+            );
+            assert_eq!(imported.pets.len(), 1);
+            assert_eq!(imported.pets[0].1, components.pets[0].1);
+            // Everything else, like the items, has to be exported the same way again
+            assert_eq!(
+                components_to_file("Tester".to_string(), &imported)
+                    .serialize(json)
+                    .unwrap(),
+                contents
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_item_definitions_are_rejected() {
+        let mut file = components_to_file("Tester".to_string(), &components());
+        file.items[0].item.definition = "common.items.not_an_item".to_string();
+        assert!(matches!(
+            file_to_components(file),
+            Err(PersistenceError::AssetError(_))
+        ));
+    }
+
+    #[test]
+    fn other_versions_are_rejected() {
+        assert!(matches!(
+            CharacterFile::parse("(version: 1, alias: \"Tester\")", false),
+            Err(PersistenceError::ConversionError(_))
+        ));
+        assert!(matches!(
+            CharacterFile::parse("{\"version\": 1, \"alias\": \"Tester\"}", true),
+            Err(PersistenceError::ConversionError(_))
+        ));
+    }
+}
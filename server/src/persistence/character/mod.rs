@@ -11,6 +11,7 @@ use crate::{
     comp::{self, Inventory},
     persistence::{
         EditableComponents, PersistedComponents,
+        backend::{Database, Dialect, Transaction, params},
         character::conversions::{
             convert_active_abilities_from_database, convert_active_abilities_to_database,
             convert_body_from_database, convert_body_to_database_json,
@@ -23,7 +24,6 @@ use crate::{
         },
         character_loader::{CharacterCreationResult, CharacterDataResult, CharacterListResult},
         character_updater::PetPersistenceData,
-        error::PersistenceError::DatabaseError,
     },
 };
 use common::{
@@ -33,18 +33,20 @@ use common::{
     npc::NPC_NAMES,
 };
 use core::ops::Range;
-use rusqlite::{Connection, ToSql, Transaction, types::Value};
-use std::{num::NonZeroU64, rc::Rc};
+use std::num::NonZeroU64;
 use tracing::{debug, error, trace, warn};
 
 /// Private module for very tightly coupled database conversion methods.  In
 /// general, these have many invariants that need to be maintained when they're
 /// called--do not assume it's safe to make these public!
 mod conversions;
+mod export;
+pub(crate) mod portable;
 
 pub(crate) type EntityId = i64;
 
 pub(crate) use conversions::convert_waypoint_from_database_json as parse_waypoint;
+pub use export::{export_character, import_character};
 
 const CHARACTER_PSEUDO_CONTAINER_DEF_ID: &str = "veloren.core.pseudo_containers.character";
 const INVENTORY_PSEUDO_CONTAINER_DEF_ID: &str = "veloren.core.pseudo_containers.inventory";
@@ -73,8 +75,11 @@ struct CharacterContainers {
 ///
 /// For items with components, the parent item must sorted so that its
 /// components are after the parent item.
-pub fn load_items(connection: &Connection, root: i64) -> Result<Vec<Item>, PersistenceError> {
-    let mut stmt = connection.prepare_cached(
+pub fn load_items(
+    connection: &mut impl Database,
+    root: i64,
+) -> Result<Vec<Item>, PersistenceError> {
+    let rows = connection.query(
         "
         WITH RECURSIVE
         items_tree (
@@ -105,10 +110,12 @@ pub fn load_items(connection: &Connection, root: i64) -> Result<Vec<Item>, Persi
         )
         SELECT  *
         FROM    items_tree",
+        params![root],
     )?;
 
-    let items = stmt
-        .query_map([root], |row| {
+    let items = rows
+        .iter()
+        .map(|row| {
             Ok(Item {
                 item_id: row.get(0)?,
                 parent_container_item_id: row.get(1)?,
@@ -117,8 +124,8 @@ pub fn load_items(connection: &Connection, root: i64) -> Result<Vec<Item>, Persi
                 position: row.get(4)?,
                 properties: row.get(5)?,
             })
-        })?
-        .filter_map(Result::ok)
+        })
+        .filter_map(Result::<_, PersistenceError>::ok)
         .collect::<Vec<Item>>();
 
     Ok(items)
@@ -131,7 +138,7 @@ pub fn load_items(connection: &Connection, root: i64) -> Result<Vec<Item>, Persi
 pub fn load_character_data(
     requesting_player_uuid: String,
     char_id: CharacterId,
-    connection: &Connection,
+    connection: &mut impl Database,
 ) -> CharacterDataResult {
     let character_containers = get_pseudo_containers(connection, char_id)?;
     let inventory_items = load_items(connection, character_containers.inventory_container_id)?;
@@ -140,7 +147,7 @@ pub fn load_character_data(
         load_items(connection, character_containers.overflow_items_container_id)?;
     let recipe_book_items = load_items(connection, character_containers.recipe_book_container_id)?;
 
-    let mut stmt = connection.prepare_cached(
+    let row = connection.query_row(
         "
         SELECT  c.character_id,
                 c.alias,
@@ -152,28 +159,22 @@ pub fn load_character_data(
         JOIN    body b ON (c.character_id = b.body_id)
         WHERE   c.player_uuid = ?1
         AND     c.character_id = ?2",
+        params![&requesting_player_uuid, char_id.0],
     )?;
 
-    let (body_data, character_data) = stmt.query_row(
-        [requesting_player_uuid.clone(), char_id.0.to_string()],
-        |row| {
-            let character_data = Character {
-                character_id: row.get(0)?,
-                player_uuid: requesting_player_uuid,
-                alias: row.get(1)?,
-                waypoint: row.get(2)?,
-                hardcore: row.get(3)?,
-            };
-
-            let body_data = Body {
-                body_id: row.get(0)?,
-                variant: row.get(4)?,
-                body_data: row.get(5)?,
-            };
+    let character_data = Character {
+        character_id: row.get(0)?,
+        player_uuid: requesting_player_uuid,
+        alias: row.get(1)?,
+        waypoint: row.get(2)?,
+        hardcore: row.get(3)?,
+    };
 
-            Ok((body_data, character_data))
-        },
-    )?;
+    let body_data = Body {
+        body_id: row.get(0)?,
+        variant: row.get(4)?,
+        body_data: row.get(5)?,
+    };
 
     let (char_waypoint, char_map_marker) = match character_data
         .waypoint
@@ -192,8 +193,9 @@ pub fn load_character_data(
         None => (None, None),
     };
 
-    let mut stmt = connection.prepare_cached(
-        "
+    let skill_group_data = connection
+        .query(
+            "
         SELECT  skill_group_kind,
                 earned_exp,
                 spent_exp,
@@ -201,10 +203,10 @@ pub fn load_character_data(
                 hash_val
         FROM    skill_group
         WHERE   entity_id = ?1",
-    )?;
-
-    let skill_group_data = stmt
-        .query_map([char_id.0], |row| {
+            params![char_id.0],
+        )?
+        .iter()
+        .map(|row| {
             Ok(SkillGroup {
                 entity_id: char_id.0,
                 skill_group_kind: row.get(0)?,
@@ -213,12 +215,13 @@ pub fn load_character_data(
                 skills: row.get(3)?,
                 hash_val: row.get(4)?,
             })
-        })?
-        .filter_map(Result::ok)
+        })
+        .filter_map(Result::<_, PersistenceError>::ok)
         .collect::<Vec<SkillGroup>>();
 
-    #[rustfmt::skip]
-    let mut stmt = connection.prepare_cached("
+    let db_pets = connection
+        .query(
+            "
         SELECT  p.pet_id,
                 p.name,
                 b.variant,
@@ -226,18 +229,18 @@ pub fn load_character_data(
         FROM    pet p
         JOIN    body b ON (p.pet_id = b.body_id)
         WHERE   p.character_id = ?1",
-    )?;
-
-    let db_pets = stmt
-        .query_map([char_id.0], |row| {
+            params![char_id.0],
+        )?
+        .iter()
+        .map(|row| {
             Ok(Pet {
                 database_id: row.get(0)?,
                 name: row.get(1)?,
                 body_variant: row.get(2)?,
                 body_data: row.get(3)?,
             })
-        })?
-        .filter_map(Result::ok)
+        })
+        .filter_map(Result::<_, PersistenceError>::ok)
         .collect::<Vec<Pet>>();
 
     // Re-construct the pet components for the player's pets, including
@@ -271,19 +274,18 @@ pub fn load_character_data(
         })
         .collect::<Vec<(comp::Pet, comp::Body, comp::Stats)>>();
 
-    let mut stmt = connection.prepare_cached(
+    let row = connection.query_row(
         "
             SELECT  ability_sets
             FROM    ability_set
             WHERE   entity_id = ?1",
+        params![char_id.0],
     )?;
 
-    let ability_set_data = stmt.query_row([char_id.0], |row| {
-        Ok(AbilitySets {
-            entity_id: char_id.0,
-            ability_sets: row.get(0)?,
-        })
-    })?;
+    let ability_set_data = AbilitySets {
+        entity_id: char_id.0,
+        ability_sets: row.get(0)?,
+    };
 
     let (skill_set, skill_set_persistence_load_error) =
         convert_skill_set_from_database(&skill_group_data);
@@ -322,9 +324,13 @@ pub fn load_character_data(
 /// In the event that a join fails, for a character (i.e. they lack an entry for
 /// stats, body, etc...) the character is skipped, and no entry will be
 /// returned.
-pub fn load_character_list(player_uuid_: &str, connection: &Connection) -> CharacterListResult {
-    let mut stmt = connection.prepare_cached(
-        "
+pub fn load_character_list(
+    player_uuid_: &str,
+    connection: &mut impl Database,
+) -> CharacterListResult {
+    let characters = connection
+        .query(
+            "
             SELECT  character_id,
                     alias,
                     waypoint,
@@ -332,10 +338,10 @@ pub fn load_character_list(player_uuid_: &str, connection: &Connection) -> Chara
             FROM    character
             WHERE   player_uuid = ?1
             ORDER BY character_id",
-    )?;
-
-    let characters = stmt
-        .query_map([player_uuid_], |row| {
+            params![player_uuid_],
+        )?
+        .iter()
+        .map(|row| {
             Ok(Character {
                 character_id: row.get(0)?,
                 alias: row.get(1)?,
@@ -343,32 +349,28 @@ pub fn load_character_list(player_uuid_: &str, connection: &Connection) -> Chara
                 waypoint: row.get(2)?,
                 hardcore: row.get(3)?,
             })
-        })?
-        .map(|x| x.unwrap())
-        .collect::<Vec<Character>>();
-    drop(stmt);
+        })
+        .collect::<Result<Vec<Character>, PersistenceError>>()?;
 
     characters
         .iter()
         .map(|character_data| {
             let char = convert_character_from_database(character_data);
 
-            let mut stmt = connection.prepare_cached(
+            let row = connection.query_row(
                 "
                 SELECT  body_id,
                         variant,
                         body_data
                 FROM    body
                 WHERE   body_id = ?1",
+                params![character_data.character_id],
             )?;
-            let db_body = stmt.query_row([char.id.map(|c| c.0)], |row| {
-                Ok(Body {
-                    body_id: row.get(0)?,
-                    variant: row.get(1)?,
-                    body_data: row.get(2)?,
-                })
-            })?;
-            drop(stmt);
+            let db_body = Body {
+                body_id: row.get(0)?,
+                variant: row.get(1)?,
+                body_data: row.get(2)?,
+            };
 
             let char_body = convert_body_from_database(&db_body.variant, &db_body.body_data)?;
 
@@ -481,8 +483,9 @@ pub fn create_character(
         },
     ];
 
-    let mut stmt = transaction.prepare_cached(
-        "
+    for pseudo_container in pseudo_containers {
+        transaction.execute(
+            "
         INSERT INTO item (item_id,
                           parent_container_item_id,
                           item_definition_id,
@@ -490,37 +493,28 @@ pub fn create_character(
                           position,
                           properties)
         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-    )?;
-
-    for pseudo_container in pseudo_containers {
-        stmt.execute([
-            &pseudo_container.item_id as &dyn ToSql,
-            &pseudo_container.parent_container_item_id,
-            &pseudo_container.item_definition_id,
-            &pseudo_container.stack_size,
-            &pseudo_container.position,
-            &pseudo_container.properties,
-        ])?;
+            params![
+                pseudo_container.item_id,
+                pseudo_container.parent_container_item_id,
+                &pseudo_container.item_definition_id,
+                pseudo_container.stack_size,
+                &pseudo_container.position,
+                &pseudo_container.properties,
+            ],
+        )?;
     }
-    drop(stmt);
 
-    let mut stmt = transaction.prepare_cached(
+    let (body_variant, body_json) = convert_body_to_database_json(&body)?;
+    transaction.execute(
         "
         INSERT INTO body (body_id,
                           variant,
                           body_data)
         VALUES (?1, ?2, ?3)",
+        params![character_id, &body_variant.to_string(), &body_json],
     )?;
 
-    let (body_variant, body_json) = convert_body_to_database_json(&body)?;
-    stmt.execute([
-        &character_id as &dyn ToSql,
-        &body_variant.to_string(),
-        &body_json,
-    ])?;
-    drop(stmt);
-
-    let mut stmt = transaction.prepare_cached(
+    transaction.execute(
         "
         INSERT INTO character (character_id,
                                player_uuid,
@@ -528,22 +522,21 @@ pub fn create_character(
                                waypoint,
                                hardcore)
         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            character_id,
+            uuid,
+            character_alias,
+            &convert_waypoint_to_database_json(waypoint, map_marker),
+            convert_hardcore_to_database(hardcore),
+        ],
     )?;
 
-    stmt.execute([
-        &character_id as &dyn ToSql,
-        &uuid,
-        &character_alias,
-        &convert_waypoint_to_database_json(waypoint, map_marker),
-        &convert_hardcore_to_database(hardcore),
-    ])?;
-    drop(stmt);
-
     let db_skill_groups =
         convert_skill_groups_to_database(CharacterId(character_id), skill_set.skill_groups());
 
-    let mut stmt = transaction.prepare_cached(
-        "
+    for skill_group in db_skill_groups {
+        transaction.execute(
+            "
         INSERT INTO skill_group (entity_id,
                                  skill_group_kind,
                                  earned_exp,
@@ -551,36 +544,28 @@ pub fn create_character(
                                  skills,
                                  hash_val)
         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-    )?;
-
-    for skill_group in db_skill_groups {
-        stmt.execute([
-            &character_id as &dyn ToSql,
-            &skill_group.skill_group_kind,
-            &skill_group.earned_exp,
-            &skill_group.spent_exp,
-            &skill_group.skills,
-            &skill_group.hash_val,
-        ])?;
+            params![
+                character_id,
+                &skill_group.skill_group_kind,
+                skill_group.earned_exp,
+                skill_group.spent_exp,
+                &skill_group.skills,
+                &skill_group.hash_val,
+            ],
+        )?;
     }
-    drop(stmt);
 
     let ability_sets =
         convert_active_abilities_to_database(CharacterId(character_id), &active_abilities);
 
-    let mut stmt = transaction.prepare_cached(
+    transaction.execute(
         "
         INSERT INTO ability_set (entity_id,
                                  ability_sets)
         VALUES (?1, ?2)",
+        params![character_id, &ability_sets.ability_sets],
     )?;
 
-    stmt.execute([
-        &character_id as &dyn ToSql,
-        &ability_sets.ability_sets as &dyn ToSql,
-    ])?;
-    drop(stmt);
-
     // Insert default inventory and loadout item records
     let mut inserts = Vec::new();
 
@@ -597,8 +582,9 @@ pub fn create_character(
         next_id
     })?;
 
-    let mut stmt = transaction.prepare_cached(
-        "
+    for item in inserts {
+        transaction.execute(
+            "
         INSERT INTO item (item_id,
                           parent_container_item_id,
                           item_definition_id,
@@ -606,19 +592,16 @@ pub fn create_character(
                           position,
                           properties)
         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-    )?;
-
-    for item in inserts {
-        stmt.execute([
-            &item.model.item_id as &dyn ToSql,
-            &item.model.parent_container_item_id,
-            &item.model.item_definition_id,
-            &item.model.stack_size,
-            &item.model.position,
-            &item.model.properties,
-        ])?;
+            params![
+                item.model.item_id,
+                item.model.parent_container_item_id,
+                &item.model.item_definition_id,
+                item.model.stack_size,
+                &item.model.position,
+                &item.model.properties,
+            ],
+        )?;
     }
-    drop(stmt);
 
     load_character_list(uuid, transaction).map(|list| (CharacterId(character_id), list))
 }
@@ -655,23 +638,17 @@ pub fn edit_character(
         }
     }
 
-    let mut stmt = transaction
-        .prepare_cached("UPDATE body SET variant = ?1, body_data = ?2 WHERE body_id = ?3")?;
-
     let (body_variant, body_data) = convert_body_to_database_json(&body)?;
-    stmt.execute([
-        &body_variant.to_string(),
-        &body_data,
-        &character_id.0 as &dyn ToSql,
-    ])?;
-    drop(stmt);
+    transaction.execute(
+        "UPDATE body SET variant = ?1, body_data = ?2 WHERE body_id = ?3",
+        params![&body_variant.to_string(), &body_data, character_id.0],
+    )?;
 
     if let Some(character_alias) = character_alias {
-        let mut stmt = transaction
-            .prepare_cached("UPDATE character SET alias = ?1 WHERE character_id = ?2")?;
-
-        stmt.execute([&character_alias, &character_id.0 as &dyn ToSql])?;
-        drop(stmt);
+        transaction.execute(
+            "UPDATE character SET alias = ?1 WHERE character_id = ?2",
+            params![character_alias, character_id.0],
+        )?;
     }
 
     char_list.map(|list| (character_id, list))
@@ -685,19 +662,16 @@ pub fn delete_character(
 ) -> Result<(), PersistenceError> {
     debug!(?requesting_player_uuid, ?char_id, "Deleting character");
 
-    let mut stmt = transaction.prepare_cached(
-        "
+    let result: i64 = transaction
+        .query_row(
+            "
         SELECT  COUNT(1)
         FROM    character
         WHERE   character_id = ?1
         AND     player_uuid = ?2",
-    )?;
-
-    let result = stmt.query_row([&char_id.0 as &dyn ToSql, &requesting_player_uuid], |row| {
-        let y: i64 = row.get(0)?;
-        Ok(y)
-    })?;
-    drop(stmt);
+            params![char_id.0, requesting_player_uuid],
+        )?
+        .get(0)?;
 
     if result != 1 {
         // The character does not exist, or does not belong to the requesting player so
@@ -706,61 +680,50 @@ pub fn delete_character(
     }
 
     // Delete skill groups
-    let mut stmt = transaction.prepare_cached(
+    transaction.execute(
         "
         DELETE
         FROM    skill_group
         WHERE   entity_id = ?1",
+        params![char_id.0],
     )?;
 
-    stmt.execute([&char_id.0])?;
-    drop(stmt);
-
-    let pet_ids = get_pet_ids(char_id, transaction)?
-        .iter()
-        .map(|x| Value::from(*x))
-        .collect::<Vec<Value>>();
+    let pet_ids = get_pet_ids(char_id, transaction)?;
     if !pet_ids.is_empty() {
-        delete_pets(transaction, char_id, Rc::new(pet_ids))?;
+        delete_pets(transaction, char_id, &pet_ids)?;
     }
 
     // Delete ability sets
-    let mut stmt = transaction.prepare_cached(
+    transaction.execute(
         "
         DELETE
         FROM    ability_set
         WHERE   entity_id = ?1",
+        params![char_id.0],
     )?;
 
-    stmt.execute([&char_id.0])?;
-    drop(stmt);
-
     // Delete character
-    let mut stmt = transaction.prepare_cached(
+    transaction.execute(
         "
         DELETE
         FROM    character
         WHERE   character_id = ?1",
+        params![char_id.0],
     )?;
 
-    stmt.execute([&char_id.0])?;
-    drop(stmt);
-
     // Delete body
-    let mut stmt = transaction.prepare_cached(
+    transaction.execute(
         "
         DELETE
         FROM    body
         WHERE   body_id = ?1",
+        params![char_id.0],
     )?;
 
-    stmt.execute([&char_id.0])?;
-    drop(stmt);
-
     // Delete all items, recursively walking all containers starting from the
     // "character" pseudo-container that is the root for all items owned by
     // a character.
-    let mut stmt = transaction.prepare_cached(
+    let deleted_item_count = transaction.execute(
         "
         WITH RECURSIVE
         parents AS (
@@ -776,11 +739,9 @@ pub fn delete_character(
         DELETE
         FROM    item
         WHERE   EXISTS (SELECT 1 FROM parents WHERE parents.item_id = item.item_id)",
+        params![char_id.0],
     )?;
 
-    let deleted_item_count = stmt.execute([&char_id.0])?;
-    drop(stmt);
-
     if deleted_item_count < 3 {
         return Err(PersistenceError::OtherError(format!(
             "Error deleting from item table for char_id {} (expected at least 3 deletions, found \
@@ -798,16 +759,15 @@ pub fn check_character_limit(
     uuid: &str,
     transaction: &mut Transaction,
 ) -> Result<(), PersistenceError> {
-    let mut stmt = transaction.prepare_cached(
-        "
+    let character_count: i64 = transaction
+        .query_row(
+            "
         SELECT  COUNT(1)
         FROM    character
         WHERE   player_uuid = ?1",
-    )?;
-
-    #[expect(clippy::needless_question_mark)]
-    let character_count: i64 = stmt.query_row([&uuid], |row| Ok(row.get(0)?))?;
-    drop(stmt);
+            params![uuid],
+        )?
+        .get(0)?;
 
     if character_count < MAX_CHARACTERS_PER_PLAYER as i64 {
         Ok(())
@@ -816,6 +776,10 @@ pub fn check_character_limit(
     }
 }
 
+/// Entity IDs a PostgreSQL transaction takes from `entity_id_block_seq` at
+/// once, far more than a character needs
+const ENTITY_ID_BLOCK_SIZE: i64 = 1 << 16;
+
 /// NOTE: This relies heavily on serializability to work correctly.
 ///
 /// The count function takes the starting entity id, and returns the desired
@@ -826,28 +790,42 @@ fn get_new_entity_ids(
     transaction: &mut Transaction,
     mut max: impl FnMut(i64) -> i64,
 ) -> Result<Range<EntityId>, PersistenceError> {
-    // The sqlite_sequence table is used here to avoid reusing entity IDs for
-    // deleted entities. This table always contains the highest used ID for
-    // each AUTOINCREMENT column in a SQLite database.
-    let mut stmt = transaction.prepare_cached(
-        "
-        SELECT  seq + 1 AS entity_id
-        FROM    sqlite_sequence
-        WHERE   name = 'entity'",
-    )?;
-
-    #[expect(clippy::needless_question_mark)]
-    let next_entity_id = stmt.query_row([], |row| Ok(row.get(0)?))?;
+    let next_entity_id = match transaction.dialect() {
+        // The sqlite_sequence table is used here to avoid reusing entity IDs for
+        // deleted entities. This table always contains the highest used ID for
+        // each AUTOINCREMENT column in a SQLite database.
+        Dialect::Sqlite => transaction.query_row(
+            "
+            SELECT  seq + 1 AS entity_id
+            FROM    sqlite_sequence
+            WHERE   name = 'entity'",
+            &[],
+        )?,
+        // A sequence never hands out the same value twice, even to concurrent
+        // transactions of other servers sharing the database, and doesn't
+        // block them. The IDs have to be contiguous, so each value stands for
+        // a whole block of them.
+        Dialect::Postgres => {
+            transaction.query_row("SELECT nextval('entity_id_block_seq') AS entity_id", &[])?
+        },
+    }
+    .get(0)?;
     let max_entity_id = max(next_entity_id);
+    if matches!(transaction.dialect(), Dialect::Postgres)
+        && max_entity_id - next_entity_id > ENTITY_ID_BLOCK_SIZE
+    {
+        return Err(PersistenceError::OtherError(format!(
+            "Needed {} new entity IDs, only {ENTITY_ID_BLOCK_SIZE} are reserved at once",
+            max_entity_id - next_entity_id
+        )));
+    }
 
     // Create a new range of IDs and insert them into the entity table
     let new_ids: Range<EntityId> = next_entity_id..max_entity_id;
 
-    let mut stmt = transaction.prepare_cached("INSERT INTO entity (entity_id) VALUES (?1)")?;
-
     // SQLite has no bulk insert
     for i in new_ids.clone() {
-        stmt.execute([i])?;
+        transaction.execute("INSERT INTO entity (entity_id) VALUES (?1)", params![i])?;
     }
 
     trace!(
@@ -864,7 +842,7 @@ fn get_new_entity_ids(
 
 /// Fetches the pseudo_container IDs for a character
 fn get_pseudo_containers(
-    connection: &Connection,
+    connection: &mut impl Database,
     character_id: CharacterId,
 ) -> Result<CharacterContainers, PersistenceError> {
     let character_containers = CharacterContainers {
@@ -894,39 +872,30 @@ fn get_pseudo_containers(
 }
 
 fn get_pseudo_container_id(
-    connection: &Connection,
+    connection: &mut impl Database,
     character_id: CharacterId,
     pseudo_container_position: &str,
 ) -> Result<EntityId, PersistenceError> {
-    let mut stmt = connection.prepare_cached(
-        "
+    let res = connection
+        .query_row(
+            "
         SELECT  item_id
         FROM    item
         WHERE   parent_container_item_id = ?1
         AND     position = ?2",
-    )?;
-
-    #[expect(clippy::needless_question_mark)]
-    let res = stmt.query_row(
-        [
-            character_id.0.to_string(),
-            pseudo_container_position.to_string(),
-        ],
-        |row| Ok(row.get(0)?),
-    );
-
-    match res {
-        Ok(id) => Ok(id),
-        Err(e) => {
-            error!(
-                ?e,
-                ?character_id,
-                ?pseudo_container_position,
-                "Failed to retrieve pseudo container ID"
-            );
-            Err(DatabaseError(e))
-        },
+            params![character_id.0, pseudo_container_position],
+        )
+        .and_then(|row| row.get(0));
+
+    if let Err(e) = &res {
+        error!(
+            ?e,
+            ?character_id,
+            ?pseudo_container_position,
+            "Failed to retrieve pseudo container ID"
+        );
     }
+    res
 }
 
 /// Stores new pets in the database, and removes pets from the database that the
@@ -941,22 +910,19 @@ fn update_pets(
 
     let db_pets = get_pet_ids(char_id, transaction)?;
     if !db_pets.is_empty() {
-        let dead_pet_ids = Rc::new(
-            db_pets
-                .iter()
-                .filter(|pet_id| {
-                    !pets.iter().any(|(pet, _, _)| {
-                        pet.get_database_id()
-                            .load()
-                            .is_some_and(|x| x.get() == **pet_id as u64)
-                    })
+        let dead_pet_ids = db_pets
+            .into_iter()
+            .filter(|pet_id| {
+                !pets.iter().any(|(pet, _, _)| {
+                    pet.get_database_id()
+                        .load()
+                        .is_some_and(|x| x.get() == *pet_id as u64)
                 })
-                .map(|x| Value::from(*x))
-                .collect::<Vec<Value>>(),
-        );
+            })
+            .collect::<Vec<i64>>();
 
         if !dead_pet_ids.is_empty() {
-            delete_pets(transaction, char_id, dead_pet_ids)?;
+            delete_pets(transaction, char_id, &dead_pet_ids)?;
         }
     }
 
@@ -968,37 +934,30 @@ fn update_pets(
 
         let (body_variant, body_json) = convert_body_to_database_json(body)?;
 
-        #[rustfmt::skip]
-        let mut stmt = transaction.prepare_cached("
+        transaction.execute(
+            "
             INSERT
             INTO    body (
                     body_id,
                     variant,
                     body_data)
-            VALUES  (?1, ?2, ?3)"
+            VALUES  (?1, ?2, ?3)",
+            params![pet_entity_id, &body_variant.to_string(), &body_json],
         )?;
 
-        stmt.execute([
-            &pet_entity_id as &dyn ToSql,
-            &body_variant.to_string(),
-            &body_json,
-        ])?;
-
-        #[rustfmt::skip]
-        let mut stmt = transaction.prepare_cached("
+        // TODO: use pet names here, when such feature will be implemented
+        let pet_name = "";
+        transaction.execute(
+            "
             INSERT
             INTO    pet (
                     pet_id,
                     character_id,
                     name)
             VALUES  (?1, ?2, ?3)",
+            params![pet_entity_id, char_id.0, pet_name],
         )?;
 
-        // TODO: use pet names here, when such feature will be implemented
-        let pet_name = "";
-        stmt.execute([&pet_entity_id as &dyn ToSql, &char_id.0, &pet_name])?;
-        drop(stmt);
-
         pet.get_database_id()
             .store(NonZeroU64::new(pet_entity_id as u64));
     }
@@ -1010,49 +969,56 @@ fn get_pet_ids(
     char_id: CharacterId,
     transaction: &mut Transaction,
 ) -> Result<Vec<i64>, PersistenceError> {
-    #[rustfmt::skip]
-        let mut stmt = transaction.prepare_cached("
+    let db_pets = transaction
+        .query(
+            "
         SELECT  pet_id
         FROM    pet
         WHERE   character_id = ?1
-    ")?;
-
-    #[expect(clippy::needless_question_mark)]
-    let db_pets = stmt
-        .query_map([&char_id.0], |row| Ok(row.get(0)?))?
-        .map(|x| x.unwrap())
-        .collect::<Vec<i64>>();
-    drop(stmt);
+    ",
+            params![char_id.0],
+        )?
+        .iter()
+        .map(|row| row.get(0))
+        .collect::<Result<Vec<i64>, _>>()?;
     Ok(db_pets)
 }
 
 fn delete_pets(
     transaction: &mut Transaction,
     char_id: CharacterId,
-    pet_ids: Rc<Vec<Value>>,
+    pet_ids: &[i64],
 ) -> Result<(), PersistenceError> {
-    #[rustfmt::skip]
-    let mut stmt = transaction.prepare_cached("
+    let (delete_pets, delete_bodies) = match transaction.dialect() {
+        Dialect::Sqlite => (
+            "
             DELETE
             FROM    pet
-            WHERE   pet_id IN rarray(?1)"
-    )?;
+            WHERE   pet_id IN rarray(?1)",
+            "
+            DELETE
+            FROM    body
+            WHERE   body_id IN rarray(?1)",
+        ),
+        Dialect::Postgres => (
+            "
+            DELETE
+            FROM    pet
+            WHERE   pet_id = ANY(?1)",
+            "
+            DELETE
+            FROM    body
+            WHERE   body_id = ANY(?1)",
+        ),
+    };
 
-    let delete_count = stmt.execute([&pet_ids])?;
-    drop(stmt);
+    let delete_count = transaction.execute(delete_pets, params![pet_ids])?;
     debug!(
         "Deleted {} pets for character id {}",
         delete_count, char_id.0
     );
 
-    #[rustfmt::skip]
-    let mut stmt = transaction.prepare_cached("
-            DELETE
-            FROM    body
-            WHERE   body_id IN rarray(?1)"
-    )?;
-
-    let delete_count = stmt.execute([&pet_ids])?;
+    let delete_count = transaction.execute(delete_bodies, params![pet_ids])?;
     debug!(
         "Deleted {} pet bodies for character id {}",
         delete_count, char_id.0
@@ -1093,39 +1059,51 @@ pub fn update(
 
     // Next, delete any slots we aren't upserting.
     trace!("Deleting items for character_id {}", char_id.0);
-    let mut existing_item_ids: Vec<_> = vec![
-        Value::from(pseudo_containers.inventory_container_id),
-        Value::from(pseudo_containers.loadout_container_id),
-        Value::from(pseudo_containers.overflow_items_container_id),
-        Value::from(pseudo_containers.recipe_book_container_id),
+    let mut existing_item_ids: Vec<i64> = vec![
+        pseudo_containers.inventory_container_id,
+        pseudo_containers.loadout_container_id,
+        pseudo_containers.overflow_items_container_id,
+        pseudo_containers.recipe_book_container_id,
     ];
     for it in load_items(transaction, pseudo_containers.inventory_container_id)? {
-        existing_item_ids.push(Value::from(it.item_id));
+        existing_item_ids.push(it.item_id);
     }
     for it in load_items(transaction, pseudo_containers.loadout_container_id)? {
-        existing_item_ids.push(Value::from(it.item_id));
+        existing_item_ids.push(it.item_id);
     }
     for it in load_items(transaction, pseudo_containers.overflow_items_container_id)? {
-        existing_item_ids.push(Value::from(it.item_id));
+        existing_item_ids.push(it.item_id);
     }
     for it in load_items(transaction, pseudo_containers.recipe_book_container_id)? {
-        existing_item_ids.push(Value::from(it.item_id));
+        existing_item_ids.push(it.item_id);
     }
 
     let non_upserted_items = upserts
         .iter()
-        .map(|item_pair| Value::from(item_pair.model.item_id))
-        .collect::<Vec<Value>>();
+        .map(|item_pair| item_pair.model.item_id)
+        .collect::<Vec<i64>>();
 
-    let mut stmt = transaction.prepare_cached(
-        "
+    let delete_items = match transaction.dialect() {
+        Dialect::Sqlite => {
+            "
         DELETE
         FROM    item
         WHERE   parent_container_item_id
         IN      rarray(?1)
-        AND     item_id NOT IN rarray(?2)",
-    )?;
-    let delete_count = stmt.execute([Rc::new(existing_item_ids), Rc::new(non_upserted_items)])?;
+        AND     item_id NOT IN rarray(?2)"
+        },
+        Dialect::Postgres => {
+            "
+        DELETE
+        FROM    item
+        WHERE   parent_container_item_id = ANY(?1)
+        AND     item_id <> ALL(?2)"
+        },
+    };
+    let delete_count = transaction.execute(delete_items, params![
+        &existing_item_ids,
+        &non_upserted_items
+    ])?;
     trace!("Deleted {} items", delete_count);
 
     // Upsert items
@@ -1153,10 +1131,14 @@ pub fn update(
         // The `defer_foreign_keys` pragma treats the foreign key
         // constraints as deferred for the next transaction (it turns itself
         // off at the commit boundary). https://sqlite.org/foreignkeys.html#fk_deferred
-        transaction.pragma_update(None, "defer_foreign_keys", "ON")?;
-
-        let mut stmt = transaction.prepare_cached(
-            "
+        //
+        // On PostgreSQL the constraints are declared as deferrable, which
+        // includes the unique position of items in their container that
+        // `REPLACE` resolves on SQLite by deleting the previous item.
+        let upsert_item = match transaction.dialect() {
+            Dialect::Sqlite => {
+                transaction.execute("PRAGMA defer_foreign_keys = ON", &[])?;
+                "
             REPLACE
             INTO    item (item_id,
                           parent_container_item_id,
@@ -1164,15 +1146,44 @@ pub fn update(
                           stack_size,
                           position,
                           properties)
-            VALUES  (?1, ?2, ?3, ?4, ?5, ?6)",
-        )?;
+            VALUES  (?1, ?2, ?3, ?4, ?5, ?6)"
+            },
+            Dialect::Postgres => {
+                transaction.execute("SET CONSTRAINTS ALL DEFERRED", &[])?;
+                "
+            INSERT
+            INTO    item (item_id,
+                          parent_container_item_id,
+                          item_definition_id,
+                          stack_size,
+                          position,
+                          properties)
+            VALUES  (?1, ?2, ?3, ?4, ?5, ?6)
+            ON CONFLICT (item_id) DO UPDATE
+            SET     parent_container_item_id = EXCLUDED.parent_container_item_id,
+                    item_definition_id = EXCLUDED.item_definition_id,
+                    stack_size = EXCLUDED.stack_size,
+                    position = EXCLUDED.position,
+                    properties = EXCLUDED.properties"
+            },
+        };
 
         for item in upserted_items.iter() {
-            stmt.execute([
-                &item.item_id as &dyn ToSql,
-                &item.parent_container_item_id,
+            // IDs are only filtered out as left over from a rolled back
+            // transaction if they are above the ones just taken, but the
+            // PostgreSQL sequence isn't rolled back. Such IDs were never
+            // handed out again, so they are claimed here instead.
+            if matches!(transaction.dialect(), Dialect::Postgres) {
+                transaction.execute(
+                    "INSERT INTO entity (entity_id) VALUES (?1) ON CONFLICT DO NOTHING",
+                    params![item.item_id],
+                )?;
+            }
+            transaction.execute(upsert_item, params![
+                item.item_id,
+                item.parent_container_item_id,
                 &item.item_definition_id,
-                &item.stack_size,
+                item.stack_size,
                 &item.position,
                 &item.properties,
             ])?;
@@ -1181,8 +1192,9 @@ pub fn update(
 
     let db_skill_groups = convert_skill_groups_to_database(char_id, char_skill_set.skill_groups());
 
-    let mut stmt = transaction.prepare_cached(
-        "
+    let upsert_skill_group = match transaction.dialect() {
+        Dialect::Sqlite => {
+            "
         REPLACE
         INTO    skill_group (entity_id,
                              skill_group_kind,
@@ -1190,15 +1202,32 @@ pub fn update(
                              spent_exp,
                              skills,
                              hash_val)
-        VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-    )?;
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)"
+        },
+        Dialect::Postgres => {
+            "
+        INSERT
+        INTO    skill_group (entity_id,
+                             skill_group_kind,
+                             earned_exp,
+                             spent_exp,
+                             skills,
+                             hash_val)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        ON CONFLICT (entity_id, skill_group_kind) DO UPDATE
+        SET     earned_exp = EXCLUDED.earned_exp,
+                spent_exp = EXCLUDED.spent_exp,
+                skills = EXCLUDED.skills,
+                hash_val = EXCLUDED.hash_val"
+        },
+    };
 
     for skill_group in db_skill_groups {
-        stmt.execute([
-            &skill_group.entity_id as &dyn ToSql,
+        transaction.execute(upsert_skill_group, params![
+            skill_group.entity_id,
             &skill_group.skill_group_kind,
-            &skill_group.earned_exp,
-            &skill_group.spent_exp,
+            skill_group.earned_exp,
+            skill_group.spent_exp,
             &skill_group.skills,
             &skill_group.hash_val,
         ])?;
@@ -1206,16 +1235,15 @@ pub fn update(
 
     let db_waypoint = convert_waypoint_to_database_json(char_waypoint, map_marker);
 
-    let mut stmt = transaction.prepare_cached(
+    let waypoint_count = transaction.execute(
         "
         UPDATE  character
         SET     waypoint = ?1
         WHERE   character_id = ?2
     ",
+        params![&db_waypoint, char_id.0],
     )?;
 
-    let waypoint_count = stmt.execute([&db_waypoint as &dyn ToSql, &char_id.0])?;
-
     if waypoint_count != 1 {
         return Err(PersistenceError::OtherError(format!(
             "Error updating character table for char_id {}",
@@ -1225,19 +1253,15 @@ pub fn update(
 
     let ability_sets = convert_active_abilities_to_database(char_id, &active_abilities);
 
-    let mut stmt = transaction.prepare_cached(
+    let ability_sets_count = transaction.execute(
         "
         UPDATE  ability_set
         SET     ability_sets = ?1
         WHERE   entity_id = ?2
     ",
+        params![&ability_sets.ability_sets, char_id.0],
     )?;
 
-    let ability_sets_count = stmt.execute([
-        &ability_sets.ability_sets as &dyn ToSql,
-        &char_id.0 as &dyn ToSql,
-    ])?;
-
     if ability_sets_count != 1 {
         return Err(PersistenceError::OtherError(format!(
             "Error updating ability_set table for char_id {}",
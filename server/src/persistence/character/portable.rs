@@ -0,0 +1,132 @@
+//! Representations of items and skills that depend neither on the database
+//! nor on the layout of ECS components, for data that is kept outside of the
+//! database, like exported characters and the entities saved with the terrain.
+use super::conversions::{
+    ABILITY_MAP, MATERIAL_STATS_MANIFEST, convert_skill_groups_to_database,
+    convert_skill_set_from_database,
+};
+use crate::persistence::{
+    error::PersistenceError,
+    json_models::{
+        DatabaseItemProperties, apply_db_item_properties, item_properties_to_db_model,
+        try_skill_group_to_db_string,
+    },
+};
+use common::{
+    character::CharacterId,
+    comp::{
+        self,
+        skillset::{self, SkillGroupKind, SkillSet, SkillsPersistenceError, skills::Skill},
+    },
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+pub struct SkillGroupData {
+    pub kind: SkillGroupKind,
+    pub earned_exp: u32,
+    /// In the order they were unlocked in
+    pub skills: Vec<Skill>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ItemData {
+    /// See [`comp::Item::persistence_item_id`]
+    pub definition: String,
+    pub amount: u32,
+    pub properties: DatabaseItemProperties,
+    /// The components of modular items
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<ItemData>,
+}
+
+pub fn item_to_data(item: &comp::Item) -> ItemData {
+    ItemData {
+        definition: item.persistence_item_id(),
+        // Like when saving, the amount is only kept for stackable items
+        amount: if item.is_stackable() {
+            item.amount()
+        } else {
+            1
+        },
+        properties: item_properties_to_db_model(item),
+        components: item.components().iter().map(item_to_data).collect(),
+    }
+}
+
+/// Loads an item like it would be loaded from the database, failing on unknown
+/// item definitions
+#[cfg_attr(not(feature = "persistent_world"), expect(dead_code))]
+pub fn item_from_data(data: ItemData) -> Result<comp::Item, PersistenceError> {
+    let mut item = comp::Item::new_from_asset(&data.definition).map_err(|err| {
+        PersistenceError::AssetError(format!(
+            "Error loading item asset: {} - {}",
+            data.definition, err
+        ))
+    })?;
+    apply_db_item_properties(&mut item, &data.properties);
+    if data.amount == 1 || item.is_stackable() {
+        item.set_amount(data.amount).map_err(|_| {
+            PersistenceError::ConversionError(format!(
+                "Invalid amount {} of {}",
+                data.amount, data.definition
+            ))
+        })?;
+    }
+    for component in data.components {
+        item.persistence_access_add_component(item_from_data(component)?);
+    }
+    // Components were added, see `persistence_update_all_item_states`
+    item.update_item_state(&ABILITY_MAP, &MATERIAL_STATS_MANIFEST);
+    Ok(item)
+}
+
+/// The skill groups of `skill_set`, sorted so that saving the same skill set
+/// twice gives the same data
+pub fn skill_set_to_data(skill_set: &SkillSet) -> Vec<SkillGroupData> {
+    let mut skill_groups = skill_set
+        .skill_groups()
+        .map(|skill_group| SkillGroupData {
+            kind: skill_group.skill_group_kind,
+            earned_exp: skill_group.earned_exp,
+            skills: skill_group.ordered_skills.clone(),
+        })
+        .collect::<Vec<_>>();
+    skill_groups.sort_by_key(|skill_group| skill_group.kind);
+    skill_groups
+}
+
+/// Loads a skill set like it would be loaded from the database, which checks
+/// that the skill points suffice for the skills.
+///
+/// Like [`SkillSet::load_from_database`], skills that can't be unlocked are
+/// reset and the error is returned next to the skill set.
+pub fn skill_set_from_data(
+    skill_groups: Vec<SkillGroupData>,
+) -> Result<(SkillSet, Option<SkillsPersistenceError>), PersistenceError> {
+    let skill_groups = skill_groups
+        .into_iter()
+        .map(|skill_group| {
+            // Unsupported skill groups would panic when saving them
+            if try_skill_group_to_db_string(skill_group.kind).is_none() {
+                return Err(PersistenceError::ConversionError(format!(
+                    "Unsupported skill group {:?}",
+                    skill_group.kind
+                )));
+            }
+            let mut new_skill_group = skillset::SkillGroup {
+                skill_group_kind: skill_group.kind,
+                available_exp: 0,
+                earned_exp: 0,
+                available_sp: 0,
+                earned_sp: 0,
+                ordered_skills: skill_group.skills,
+            };
+            new_skill_group.add_experience(skill_group.earned_exp);
+            Ok(new_skill_group)
+        })
+        .collect::<Result<Vec<_>, PersistenceError>>()?;
+    Ok(convert_skill_set_from_database(
+        &convert_skill_groups_to_database(CharacterId(0), skill_groups.iter()),
+    ))
+}
@@ -1,6 +1,6 @@
 use crate::persistence::{
-    ConnectionMode, DatabaseSettings, PersistedComponents,
-    character::{load_character_data, load_character_list},
+    ConnectionMode, DatabaseSettings, PersistedComponents, VelorenConnection,
+    character::{export_character, load_character_data, load_character_list},
     error::PersistenceError,
     establish_connection,
 };
@@ -9,9 +9,11 @@ use common::{
     event::UpdateCharacterMetadata,
 };
 use crossbeam_channel::{self, TryIter};
-use rusqlite::Connection;
-use std::sync::{Arc, RwLock};
-use tracing::{debug, error};
+use std::{
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
+use tracing::{debug, error, info, warn};
 
 pub(crate) type CharacterListResult = Result<Vec<CharacterItem>, PersistenceError>;
 pub(crate) type CharacterCreationResult =
@@ -19,6 +21,8 @@ pub(crate) type CharacterCreationResult =
 pub(crate) type CharacterEditResult = Result<(CharacterId, Vec<CharacterItem>), PersistenceError>;
 pub(crate) type CharacterDataResult =
     Result<(PersistedComponents, UpdateCharacterMetadata), PersistenceError>;
+pub(crate) type CharacterExportResult = Result<String, PersistenceError>;
+pub(crate) type CharacterImportResult = Result<(CharacterId, String), PersistenceError>;
 type CharacterLoaderRequest = (specs::Entity, CharacterLoaderRequestKind);
 
 /// Available database operations when modifying a player's character list
@@ -32,12 +36,69 @@ enum CharacterLoaderRequestKind {
     },
 }
 
+/// Requests to the character loader thread
+enum CharacterLoaderAction {
+    CharacterScreen(CharacterLoaderRequest),
+    ExportCharacter {
+        target_entity: Option<specs::Entity>,
+        character_id: CharacterId,
+        path: PathBuf,
+        file: String,
+    },
+}
+
 #[derive(Debug)]
 pub enum CharacterUpdaterMessage {
     CharacterScreenResponse(CharacterScreenResponse),
+    CharacterFileResponse(CharacterFileResponse),
     DatabaseBatchCompletion(u64),
 }
 
+/// An event emitted in response to exporting or importing a character with a
+/// command, reported back to the client that issued it
+#[derive(Debug)]
+pub struct CharacterFileResponse {
+    /// `None` for requests from the console, which are only logged
+    pub target_entity: Option<specs::Entity>,
+    /// The name of the file as given to the command
+    pub file: String,
+    pub response_kind: CharacterFileResponseKind,
+}
+
+#[derive(Debug)]
+pub enum CharacterFileResponseKind {
+    Export {
+        character_id: CharacterId,
+        result: CharacterExportResult,
+    },
+    Import(CharacterImportResult),
+}
+
+impl CharacterFileResponse {
+    /// Logs the result, for requests that no client is waiting for
+    pub fn log(&self) {
+        match &self.response_kind {
+            CharacterFileResponseKind::Export {
+                result: Ok(alias), ..
+            } => info!("Exported {} to {}", alias, self.file),
+            CharacterFileResponseKind::Export {
+                character_id,
+                result: Err(e),
+            } => warn!(
+                ?e,
+                "Failed to export character {} to {}", character_id.0, self.file
+            ),
+            CharacterFileResponseKind::Import(Ok((character_id, alias))) => info!(
+                "Imported {} from {} as character {}",
+                alias, self.file, character_id.0
+            ),
+            CharacterFileResponseKind::Import(Err(e)) => {
+                warn!(?e, "Failed to import a character from {}", self.file)
+            },
+        }
+    }
+}
+
 /// An event emitted from CharacterUpdater in response to a request made from
 /// the character selection/editing screen
 #[derive(Debug)]
@@ -79,12 +140,12 @@ pub enum CharacterScreenResponseKind {
 /// [`CharacterUpdaterMessage`]
 pub struct CharacterLoader {
     update_rx: crossbeam_channel::Receiver<CharacterUpdaterMessage>,
-    update_tx: crossbeam_channel::Sender<CharacterLoaderRequest>,
+    update_tx: crossbeam_channel::Sender<CharacterLoaderAction>,
 }
 
 impl CharacterLoader {
     pub fn new(settings: Arc<RwLock<DatabaseSettings>>) -> Result<Self, PersistenceError> {
-        let (update_tx, internal_rx) = crossbeam_channel::unbounded::<CharacterLoaderRequest>();
+        let (update_tx, internal_rx) = crossbeam_channel::unbounded::<CharacterLoaderAction>();
         let (internal_tx, update_rx) = crossbeam_channel::unbounded::<CharacterUpdaterMessage>();
 
         let builder = std::thread::Builder::new().name("persistence_loader".into());
@@ -98,10 +159,10 @@ impl CharacterLoader {
                 let mut conn =
                     establish_connection(&settings.read().unwrap(), ConnectionMode::ReadOnly);
 
-                for request in internal_rx {
+                for action in internal_rx {
                     conn.update_log_mode(&settings);
 
-                    let response = CharacterLoader::process_request(request, &conn);
+                    let response = CharacterLoader::process_action(action, &mut conn);
                     debug!("Processing complete");
                     if let Err(e) = internal_tx.send(response) {
                         error!(?e, "Could not send character loader response");
@@ -122,7 +183,7 @@ impl CharacterLoader {
     // CharacterScreenResponse::is_err()
     fn process_request(
         request: CharacterLoaderRequest,
-        connection: &Connection,
+        connection: &mut VelorenConnection,
     ) -> CharacterUpdaterMessage {
         let (entity, kind) = request;
         CharacterUpdaterMessage::CharacterScreenResponse(CharacterScreenResponse {
@@ -153,16 +214,41 @@ impl CharacterLoader {
         })
     }
 
+    fn process_action(
+        action: CharacterLoaderAction,
+        connection: &mut VelorenConnection,
+    ) -> CharacterUpdaterMessage {
+        match action {
+            CharacterLoaderAction::CharacterScreen(request) => {
+                CharacterLoader::process_request(request, connection)
+            },
+            CharacterLoaderAction::ExportCharacter {
+                target_entity,
+                character_id,
+                path,
+                file,
+            } => {
+                debug!(?character_id, ?path, "Exporting character");
+                CharacterUpdaterMessage::CharacterFileResponse(CharacterFileResponse {
+                    target_entity,
+                    file,
+                    response_kind: CharacterFileResponseKind::Export {
+                        character_id,
+                        result: export_character(connection, character_id, &path),
+                    },
+                })
+            },
+        }
+    }
+
     /// Loads a list of characters belonging to the player identified by
     /// `player_uuid`
     pub fn load_character_list(&self, entity: specs::Entity, player_uuid: String) {
         debug!(?player_uuid, "Requesting character list");
-        if let Err(e) = self
-            .update_tx
-            .send((entity, CharacterLoaderRequestKind::LoadCharacterList {
-                player_uuid,
-            }))
-        {
+        if let Err(e) = self.update_tx.send(CharacterLoaderAction::CharacterScreen((
+            entity,
+            CharacterLoaderRequestKind::LoadCharacterList { player_uuid },
+        ))) {
             error!(?e, "Could not send character list load request");
         }
     }
@@ -175,17 +261,37 @@ impl CharacterLoader {
         character_id: CharacterId,
     ) {
         debug!(?character_id, ?player_uuid, "Requesting character data");
-        if let Err(e) =
-            self.update_tx
-                .send((entity, CharacterLoaderRequestKind::LoadCharacterData {
-                    player_uuid,
-                    character_id,
-                }))
-        {
+        if let Err(e) = self.update_tx.send(CharacterLoaderAction::CharacterScreen((
+            entity,
+            CharacterLoaderRequestKind::LoadCharacterData {
+                player_uuid,
+                character_id,
+            },
+        ))) {
             error!(?e, "Could not send character data load request");
         }
     }
 
+    /// Writes a character to the file at `path`, `file` is the name it is
+    /// reported back with. Without a `target_entity`, the result is logged.
+    pub fn export_character(
+        &self,
+        target_entity: Option<specs::Entity>,
+        character_id: CharacterId,
+        path: PathBuf,
+        file: String,
+    ) {
+        debug!(?character_id, ?path, "Requesting character export");
+        if let Err(e) = self.update_tx.send(CharacterLoaderAction::ExportCharacter {
+            target_entity,
+            character_id,
+            path,
+            file,
+        }) {
+            error!(?e, "Could not send character export request");
+        }
+    }
+
     /// Returns a non-blocking iterator over CharacterUpdaterMessage messages
     pub fn messages(&self) -> TryIter<'_, CharacterUpdaterMessage> { self.update_rx.try_iter() }
 }
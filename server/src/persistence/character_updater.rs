@@ -3,17 +3,19 @@ use common::{character::CharacterId, event::PermanentChange};
 
 use crate::persistence::{
     ConnectionMode, DatabaseSettings, EditableComponents, PersistedComponents, VelorenConnection,
+    character::import_character,
     character_loader::{
-        CharacterScreenResponse, CharacterScreenResponseKind, CharacterUpdaterMessage,
+        CharacterFileResponse, CharacterFileResponseKind, CharacterScreenResponse,
+        CharacterScreenResponseKind, CharacterUpdaterMessage,
     },
     error::PersistenceError,
     establish_connection,
 };
 use crossbeam_channel::TryIter;
-use rusqlite::DropBehavior;
 use specs::Entity;
 use std::{
     collections::HashMap,
+    path::PathBuf,
     sync::{
         Arc, RwLock,
         atomic::{AtomicBool, Ordering},
@@ -53,6 +55,12 @@ enum CharacterUpdaterAction {
         editable_components: EditableComponents,
         trusted_change: Option<PermanentChange>,
     },
+    ImportCharacter {
+        target_entity: Option<Entity>,
+        player_uuid: String,
+        path: PathBuf,
+        file: String,
+    },
     DisconnectedSuccess,
 }
 
@@ -211,6 +219,26 @@ impl CharacterUpdater {
                                 ),
                             }
                         },
+                        CharacterUpdaterAction::ImportCharacter {
+                            target_entity,
+                            player_uuid,
+                            path,
+                            file,
+                        } => {
+                            conn.update_log_mode(&settings);
+
+                            let result = import_character(&path, &player_uuid, &mut conn);
+                            let response = CharacterUpdaterMessage::CharacterFileResponse(
+                                CharacterFileResponse {
+                                    target_entity,
+                                    file,
+                                    response_kind: CharacterFileResponseKind::Import(result),
+                                },
+                            );
+                            if let Err(e) = response_tx.send(response) {
+                                error!(?e, "Could not send character import response");
+                            }
+                        },
                         CharacterUpdaterAction::DisconnectedSuccess => {
                             info!(
                                 "CharacterUpdater received DisconnectedSuccess event, resuming \
@@ -336,6 +364,31 @@ impl CharacterUpdater {
         }
     }
 
+    /// Creates a new character for the player with `requesting_player_uuid`
+    /// from the file at `path`, `file` is the name it is reported back with.
+    /// Without a `target_entity`, the result is logged.
+    pub fn import_character(
+        &mut self,
+        target_entity: Option<Entity>,
+        requesting_player_uuid: String,
+        path: PathBuf,
+        file: String,
+    ) {
+        if let Err(e) =
+            self.update_tx
+                .as_ref()
+                .unwrap()
+                .send(CharacterUpdaterAction::ImportCharacter {
+                    target_entity,
+                    player_uuid: requesting_player_uuid,
+                    path,
+                    file,
+                })
+        {
+            error!(?e, "Could not send character import request");
+        }
+    }
+
     fn next_pending_database_event_id(&mut self) -> u64 {
         self.last_pending_database_event_id += 1;
         self.last_pending_database_event_id
@@ -418,8 +471,7 @@ fn execute_batch_update(
     updates: impl Iterator<Item = DatabaseActionKind>,
     connection: &mut VelorenConnection,
 ) -> Result<(), PersistenceError> {
-    let mut transaction = connection.connection.transaction()?;
-    transaction.set_drop_behavior(DropBehavior::Rollback);
+    let mut transaction = connection.transaction()?;
     trace!("Transaction started for character batch update");
     updates.into_iter().try_for_each(|event| match event {
         DatabaseActionKind::UpdateCharacter(box (
@@ -463,7 +515,7 @@ fn execute_character_create(
     persisted_components: PersistedComponents,
     connection: &mut VelorenConnection,
 ) -> Result<CharacterUpdaterMessage, PersistenceError> {
-    let mut transaction = connection.connection.transaction()?;
+    let mut transaction = connection.transaction()?;
 
     let response = CharacterScreenResponse {
         target_entity: entity,
@@ -493,7 +545,7 @@ fn execute_character_edit(
     trusted_change: Option<PermanentChange>,
     connection: &mut VelorenConnection,
 ) -> Result<CharacterUpdaterMessage, PersistenceError> {
-    let mut transaction = connection.connection.transaction()?;
+    let mut transaction = connection.transaction()?;
 
     let response = CharacterScreenResponse {
         target_entity: entity,
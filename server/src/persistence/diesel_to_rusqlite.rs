@@ -1,4 +1,5 @@
-use crate::persistence::{VelorenConnection, error::PersistenceError};
+use crate::persistence::error::PersistenceError;
+use rusqlite::Connection;
 use tracing::{debug, info};
 
 /// Performs a one-time migration from diesel to refinery migrations. Copies
@@ -6,11 +7,8 @@ use tracing::{debug, info};
 /// and drops __diesel_schema_migrations.
 // At some point in the future, when it is deemed no longer necessary to
 // support migrations from pre-rusqlite databases this method should be deleted.
-pub(crate) fn migrate_from_diesel(
-    connection: &mut VelorenConnection,
-) -> Result<(), PersistenceError> {
+pub(crate) fn migrate_from_diesel(connection: &mut Connection) -> Result<(), PersistenceError> {
     let transaction = connection
-        .connection
         .transaction()
         .expect("failed to start transaction");
     #[rustfmt::skip]
@@ -1,4 +1,4 @@
-//! Consolidates rusqlite and validation errors under a common error type
+//! Consolidates database and validation errors under a common error type
 
 extern crate rusqlite;
 
@@ -14,6 +14,10 @@ pub enum PersistenceError {
     DatabaseConnectionError(rusqlite::Error),
     // An error occurred when performing a database action
     DatabaseError(rusqlite::Error),
+    #[cfg(feature = "postgres")]
+    PostgresError(postgres::Error),
+    // A query that should return a row didn't return any
+    RowNotFound,
     // Unable to load body or stats for a character
     CharacterDataError,
     SerializationError(serde_json::Error),
@@ -28,6 +32,9 @@ impl fmt::Display for PersistenceError {
             Self::CharacterLimitReached => String::from("Character limit exceeded"),
             Self::DatabaseError(error) => error.to_string(),
             Self::DatabaseConnectionError(error) => error.to_string(),
+            #[cfg(feature = "postgres")]
+            Self::PostgresError(error) => error.to_string(),
+            Self::RowNotFound => String::from("Query returned no rows"),
             Self::CharacterDataError => String::from("Error while loading character data"),
             Self::SerializationError(error) => error.to_string(),
             Self::ConversionError(error) => error.to_string(),
@@ -40,6 +47,11 @@ impl From<rusqlite::Error> for PersistenceError {
     fn from(error: rusqlite::Error) -> PersistenceError { PersistenceError::DatabaseError(error) }
 }
 
+#[cfg(feature = "postgres")]
+impl From<postgres::Error> for PersistenceError {
+    fn from(error: postgres::Error) -> PersistenceError { PersistenceError::PostgresError(error) }
+}
+
 impl From<serde_json::Error> for PersistenceError {
     fn from(error: serde_json::Error) -> PersistenceError {
         PersistenceError::SerializationError(error)
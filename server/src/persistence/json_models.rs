@@ -69,8 +69,23 @@ pub struct CharacterPosition {
 }
 
 pub fn skill_group_to_db_string(skill_group: comp::skillset::SkillGroupKind) -> String {
+    try_skill_group_to_db_string(skill_group)
+        .unwrap_or_else(|| {
+            panic!(
+                "Tried to add unsupported skill group to database: {:?}",
+                skill_group
+            )
+        })
+        .to_string()
+}
+
+/// Like [`skill_group_to_db_string`], but for skill groups that didn't come
+/// from a character and may not be supported
+pub fn try_skill_group_to_db_string(
+    skill_group: comp::skillset::SkillGroupKind,
+) -> Option<&'static str> {
     use comp::{item::tool::ToolKind, skillset::SkillGroupKind::*};
-    let skill_group_string = match skill_group {
+    Some(match skill_group {
         General => "General",
         Weapon(ToolKind::Sword) => "Weapon Sword",
         Weapon(ToolKind::Axe) => "Weapon Axe",
@@ -89,12 +104,8 @@ pub fn skill_group_to_db_string(skill_group: comp::skillset::SkillGroupKind) ->
         | Weapon(ToolKind::Throwable)
         | Weapon(ToolKind::Empty)
         | Weapon(ToolKind::Natural)
-        | Weapon(ToolKind::Shovel) => panic!(
-            "Tried to add unsupported skill group to database: {:?}",
-            skill_group
-        ),
-    };
-    skill_group_string.to_string()
+        | Weapon(ToolKind::Shovel) => return None,
+    })
 }
 
 pub fn db_string_to_skill_group(skill_group_string: &str) -> comp::skillset::SkillGroupKind {
@@ -4,6 +4,8 @@
 // migration happens.
 // nya~
 
+pub mod backend;
+pub mod backup;
 pub(in crate::persistence) mod character;
 pub mod character_loader;
 pub mod character_updater;
@@ -11,24 +13,35 @@ mod diesel_to_rusqlite;
 pub mod error;
 mod json_models;
 mod models;
+#[cfg(feature = "plugins")]
+pub mod plugin_storage;
 
-use crate::persistence::character_updater::PetPersistenceData;
-use common::comp;
+use crate::persistence::{
+    backend::{Database, Dialect, Param, Row, Transaction},
+    character_updater::PetPersistenceData,
+    error::PersistenceError,
+};
+use authc::Uuid;
+use common::{character::CharacterId, comp};
 use refinery::Report;
 use rusqlite::{
-    Connection, OpenFlags,
+    OpenFlags,
     trace::{TraceEvent, TraceEventCodes},
 };
 use std::{
     fs,
-    ops::Deref,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{Arc, RwLock},
 };
-use tracing::info;
+use tracing::{info, warn};
+
+pub use backend::DatabaseBackend;
 
 // re-export waypoint parser for use to look up location names in character list
 pub(crate) use character::parse_waypoint;
+// re-export the representations of items and skills for the entities saved with
+// the terrain
+pub(crate) use character::portable;
 
 /// A struct of the components that are persisted to the DB for each character
 #[derive(Debug)]
@@ -49,19 +62,30 @@ pub type EditableComponents = (comp::Body,);
 // See: https://docs.rs/refinery/0.5.0/refinery/macro.embed_migrations.html
 // This macro is called at build-time, and produces the necessary migration info
 // for the `run_migrations` call below.
+//
+// PostgreSQL databases start out with the schema SQLite databases have after
+// the migration with the same version as the baseline in
+// `./src/migrations_postgres`. Every later migration has to be added to both
+// directories with the same version.
 mod embedded {
     use refinery::embed_migrations;
     embed_migrations!("./src/migrations");
 }
 
+#[cfg(feature = "postgres")]
+mod embedded_postgres {
+    use refinery::embed_migrations;
+    embed_migrations!("./src/migrations_postgres");
+}
+
 /// A database connection blessed by Veloren.
 pub(crate) struct VelorenConnection {
-    connection: Connection,
+    connection: backend::Connection,
     sql_log_mode: SqlLogMode,
 }
 
 impl VelorenConnection {
-    fn new(connection: Connection) -> Self {
+    fn new(connection: backend::Connection) -> Self {
         Self {
             connection,
             sql_log_mode: SqlLogMode::Disabled,
@@ -77,23 +101,48 @@ impl VelorenConnection {
             return;
         }
 
-        set_log_mode(&mut self.connection, settings.sql_log_mode);
-        self.sql_log_mode = settings.sql_log_mode;
+        self.set_log_mode(settings.sql_log_mode);
 
         info!(
             "SQL log mode for connection changed to {:?}",
             settings.sql_log_mode
         );
     }
+
+    fn set_log_mode(&mut self, sql_log_mode: SqlLogMode) {
+        self.sql_log_mode = sql_log_mode;
+        match &mut self.connection {
+            backend::Connection::Sqlite(connection) => set_log_mode(connection, sql_log_mode),
+            #[cfg(feature = "postgres")]
+            backend::Connection::Postgres(_) => {
+                if sql_log_mode != SqlLogMode::Disabled {
+                    warn!(
+                        "SQL logging is only supported by SQLite, use the log_statement setting \
+                         of PostgreSQL instead"
+                    );
+                }
+            },
+        }
+    }
+
+    pub fn transaction(&mut self) -> Result<Transaction<'_>, PersistenceError> {
+        self.connection.transaction()
+    }
 }
 
-impl Deref for VelorenConnection {
-    type Target = Connection;
+impl Database for VelorenConnection {
+    fn dialect(&self) -> Dialect { self.connection.dialect() }
 
-    fn deref(&self) -> &Connection { &self.connection }
+    fn query(&mut self, sql: &str, params: &[Param]) -> Result<Vec<Row>, PersistenceError> {
+        self.connection.query(sql, params)
+    }
+
+    fn execute(&mut self, sql: &str, params: &[Param]) -> Result<usize, PersistenceError> {
+        self.connection.execute(sql, params)
+    }
 }
 
-fn set_log_mode(connection: &mut Connection, sql_log_mode: SqlLogMode) {
+fn set_log_mode(connection: &mut rusqlite::Connection, sql_log_mode: SqlLogMode) {
     match sql_log_mode {
         SqlLogMode::Trace => {
             connection.trace_v2(
@@ -115,8 +164,11 @@ fn set_log_mode(connection: &mut Connection, sql_log_mode: SqlLogMode) {
 
 #[derive(Clone)]
 pub struct DatabaseSettings {
+    /// Location of the SQLite database, also used with other backends for the
+    /// files that are saved next to it
     pub db_dir: PathBuf,
     pub sql_log_mode: SqlLogMode,
+    pub backend: DatabaseBackend,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -169,26 +221,51 @@ impl ToString for SqlLogMode {
 pub fn run_migrations(settings: &DatabaseSettings) {
     let mut conn = establish_connection(settings, ConnectionMode::ReadWrite);
 
-    diesel_to_rusqlite::migrate_from_diesel(&mut conn)
-        .expect("One-time migration from Diesel to Refinery failed");
-
     // If migrations fail to run, the server cannot start since the database will
     // not be in the required state.
-    let report: Report = embedded::migrations::runner()
-        .set_abort_divergent(false)
-        .run(&mut conn.connection)
-        .expect("Database migrations failed, server startup aborted");
+    let report: Report = match &mut conn.connection {
+        backend::Connection::Sqlite(connection) => {
+            diesel_to_rusqlite::migrate_from_diesel(connection)
+                .expect("One-time migration from Diesel to Refinery failed");
+
+            embedded::migrations::runner()
+                .set_abort_divergent(false)
+                .run(connection)
+        },
+        #[cfg(feature = "postgres")]
+        backend::Connection::Postgres(client) => {
+            // Other servers sharing the database may start at the same time
+            client
+                .batch_execute(&format!("SELECT pg_advisory_lock({MIGRATIONS_LOCK_ID})"))
+                .expect("Failed to lock the database for migrations");
+            let report = embedded_postgres::migrations::runner().run(client);
+            client
+                .batch_execute(&format!("SELECT pg_advisory_unlock({MIGRATIONS_LOCK_ID})"))
+                .expect("Failed to unlock the database after migrations");
+            report
+        },
+    }
+    .expect("Database migrations failed, server startup aborted");
 
     let applied_migrations = report.applied_migrations().len();
     info!("Applied {} database migrations", applied_migrations);
 }
 
+/// Key of the advisory lock held while running the migrations on PostgreSQL
+#[cfg(feature = "postgres")]
+const MIGRATIONS_LOCK_ID: i64 = 0x7665_6c6f_7265_6e;
+
 /// Runs after the migrations. In some cases, it can reclaim a significant
 /// amount of space (reported 30%)
 pub fn vacuum_database(settings: &DatabaseSettings) {
-    let conn = establish_connection(settings, ConnectionMode::ReadWrite);
+    if settings.backend != DatabaseBackend::Sqlite {
+        // PostgreSQL's autovacuum takes care of this
+        return;
+    }
 
-    conn.execute("VACUUM main", [])
+    let mut conn = establish_connection(settings, ConnectionMode::ReadWrite);
+
+    conn.execute("VACUUM main", &[])
         .expect("Database vacuuming failed, server startup aborted");
 
     info!("Database vacuumed");
@@ -210,8 +287,62 @@ pub(crate) fn establish_connection(
     settings: &DatabaseSettings,
     connection_mode: ConnectionMode,
 ) -> VelorenConnection {
-    fs::create_dir_all(&settings.db_dir)
-        .unwrap_or_else(|_| panic!("Failed to create saves directory: {:?}", &settings.db_dir));
+    try_establish_connection(settings, connection_mode)
+        .unwrap_or_else(|err| panic!("Error connecting to the database, Error: {}", err))
+}
+
+/// Like [`establish_connection`], but returns an error instead of panicking
+/// when the database can't be opened
+pub(crate) fn try_establish_connection(
+    settings: &DatabaseSettings,
+    connection_mode: ConnectionMode,
+) -> Result<VelorenConnection, PersistenceError> {
+    let mut veloren_connection = match &settings.backend {
+        DatabaseBackend::Sqlite => VelorenConnection::new(backend::Connection::Sqlite(
+            establish_sqlite_connection(settings, connection_mode)?,
+        )),
+        DatabaseBackend::Postgres { url } => {
+            VelorenConnection::new(establish_postgres_connection(url, connection_mode)?)
+        },
+    };
+    veloren_connection.set_log_mode(settings.sql_log_mode);
+    Ok(veloren_connection)
+}
+
+#[cfg(feature = "postgres")]
+fn establish_postgres_connection(
+    url: &str,
+    connection_mode: ConnectionMode,
+) -> Result<backend::Connection, PersistenceError> {
+    let mut client = postgres::Client::connect(url, postgres::NoTls)?;
+
+    if connection_mode == ConnectionMode::ReadOnly {
+        client.batch_execute("SET default_transaction_read_only = on")?;
+    }
+
+    Ok(backend::Connection::Postgres(client))
+}
+
+#[cfg(not(feature = "postgres"))]
+fn establish_postgres_connection(
+    _: &str,
+    _: ConnectionMode,
+) -> Result<backend::Connection, PersistenceError> {
+    Err(PersistenceError::OtherError(
+        "The server was built without PostgreSQL support, enable the postgres feature".to_owned(),
+    ))
+}
+
+fn establish_sqlite_connection(
+    settings: &DatabaseSettings,
+    connection_mode: ConnectionMode,
+) -> Result<rusqlite::Connection, PersistenceError> {
+    fs::create_dir_all(&settings.db_dir).map_err(|err| {
+        PersistenceError::OtherError(format!(
+            "Failed to create saves directory {:?}: {err}",
+            &settings.db_dir
+        ))
+    })?;
 
     let open_flags = OpenFlags::SQLITE_OPEN_PRIVATE_CACHE
         | OpenFlags::SQLITE_OPEN_NO_MUTEX
@@ -222,37 +353,55 @@ pub(crate) fn establish_connection(
             ConnectionMode::ReadOnly => OpenFlags::SQLITE_OPEN_READ_ONLY,
         };
 
-    let connection = Connection::open_with_flags(settings.db_dir.join("db.sqlite"), open_flags)
-        .unwrap_or_else(|err| {
-            panic!(
-                "Error connecting to {}, Error: {:?}",
-                settings.db_dir.join("db.sqlite").display(),
-                err
-            )
-        });
+    let connection =
+        rusqlite::Connection::open_with_flags(settings.db_dir.join("db.sqlite"), open_flags)
+            .map_err(PersistenceError::DatabaseConnectionError)?;
 
-    let mut veloren_connection = VelorenConnection::new(connection);
-
-    let connection = &mut veloren_connection.connection;
-
-    set_log_mode(connection, settings.sql_log_mode);
-    veloren_connection.sql_log_mode = settings.sql_log_mode;
-
-    rusqlite::vtab::array::load_module(connection).expect("Failed to load sqlite array module");
+    rusqlite::vtab::array::load_module(&connection)?;
 
     connection.set_prepared_statement_cache_capacity(100);
 
     // Use Write-Ahead-Logging for improved concurrency: https://sqlite.org/wal.html
     // Set a busy timeout (in ms): https://sqlite.org/c3ref/busy_timeout.html
-    connection
-        .pragma_update(None, "foreign_keys", "ON")
-        .expect("Failed to set foreign_keys PRAGMA");
-    connection
-        .pragma_update(None, "journal_mode", "WAL")
-        .expect("Failed to set journal_mode PRAGMA");
-    connection
-        .pragma_update(None, "busy_timeout", "250")
-        .expect("Failed to set busy_timeout PRAGMA");
-
-    veloren_connection
+    connection.pragma_update(None, "foreign_keys", "ON")?;
+    connection.pragma_update(None, "journal_mode", "WAL")?;
+    connection.pragma_update(None, "busy_timeout", "250")?;
+
+    Ok(connection)
+}
+
+/// Exported characters are only written to and read from this folder in the
+/// data dir, so admins can't access arbitrary files of the server
+pub const CHARACTER_EXPORTS_DIR: &str = "character_exports";
+
+/// Path of the exported character `file` in [`CHARACTER_EXPORTS_DIR`], or
+/// `None` if `file` isn't a plain file name
+pub fn character_file_path(data_dir: &Path, file: &str) -> Option<PathBuf> {
+    let path = Path::new(file);
+    (path.file_name() == Some(path.as_os_str()))
+        .then(|| data_dir.join(CHARACTER_EXPORTS_DIR).join(path))
+}
+
+/// Writes a character from the database to a file at `path`, returns its
+/// alias. Only for use while the server isn't running, which does this on
+/// the character loader thread instead.
+pub fn export_character(
+    settings: &DatabaseSettings,
+    char_id: CharacterId,
+    path: &Path,
+) -> Result<String, PersistenceError> {
+    let mut connection = try_establish_connection(settings, ConnectionMode::ReadOnly)?;
+    character::export_character(&mut connection, char_id, path)
+}
+
+/// Creates a new character for the player with `player_uuid` from the file at
+/// `path`. Only for use while the server isn't running, which does this on the
+/// character updater thread instead.
+pub fn import_character(
+    settings: &DatabaseSettings,
+    path: &Path,
+    player_uuid: Uuid,
+) -> Result<(CharacterId, String), PersistenceError> {
+    let mut connection = try_establish_connection(settings, ConnectionMode::ReadWrite)?;
+    character::import_character(path, &player_uuid.to_string(), &mut connection)
 }
@@ -0,0 +1,320 @@
+//! Database backend of the persistent key/value storage of plugins
+
+use super::{
+    ConnectionMode, DatabaseSettings, VelorenConnection,
+    backend::{Database, Dialect, params},
+    error::PersistenceError,
+    try_establish_connection,
+};
+use common_state::plugin::storage::{PluginStorage, StorageOp};
+use hashbrown::HashMap;
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex, RwLock},
+};
+use tracing::error;
+
+/// The most keys a single plugin can store
+const MAX_KEYS: usize = 10_000;
+/// The most bytes (keys and values) a single plugin can store
+const MAX_BYTES: usize = 16 * 1024 * 1024;
+
+/// The stored values of a single plugin
+#[derive(Default)]
+struct Namespace {
+    values: BTreeMap<String, Vec<u8>>,
+    /// The size of all keys and values
+    bytes: usize,
+}
+
+impl Namespace {
+    fn insert(&mut self, key: String, value: Vec<u8>) {
+        self.remove(&key);
+        self.bytes += key.len() + value.len();
+        self.values.insert(key, value);
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(old) = self.values.remove(key) {
+            self.bytes -= key.len() + old.len();
+        }
+    }
+
+    /// The number of keys and bytes the namespace would have after applying
+    /// `ops`
+    fn usage_after(&self, ops: &[StorageOp]) -> (usize, usize) {
+        let mut changed = HashMap::<&str, Option<usize>>::new();
+        for op in ops {
+            match op {
+                StorageOp::Set { key, value } => {
+                    changed.insert(key, Some(key.len() + value.len()));
+                },
+                StorageOp::Remove { key } => {
+                    changed.insert(key, None);
+                },
+            }
+        }
+
+        let (mut keys, mut bytes) = (self.values.len(), self.bytes);
+        for (key, size) in changed {
+            if let Some(old) = self.values.get(key) {
+                keys -= 1;
+                bytes -= key.len() + old.len();
+            }
+            if let Some(size) = size {
+                keys += 1;
+                bytes += size;
+            }
+        }
+        (keys, bytes)
+    }
+}
+
+/// Stores the values of all plugins in the `plugin_storage` table, namespaced
+/// by the plugin name.
+///
+/// Plugins read the storage synchronously while they run, so all values are
+/// loaded when the server starts and kept in memory, which the per-plugin
+/// limits keep small. Commits are written to the database in a transaction
+/// before they are applied to memory, so memory never holds values the
+/// database doesn't have.
+pub struct DatabasePluginStorage {
+    namespaces: Mutex<HashMap<String, Namespace>>,
+    connection: Mutex<VelorenConnection>,
+    settings: Arc<RwLock<DatabaseSettings>>,
+}
+
+impl DatabasePluginStorage {
+    pub fn new(settings: Arc<RwLock<DatabaseSettings>>) -> Result<Self, PersistenceError> {
+        // Unwrap here is safe as there is no code that can panic when the write lock is
+        // taken that could cause the RwLock to become poisoned.
+        let connection =
+            try_establish_connection(&settings.read().unwrap(), ConnectionMode::ReadWrite)?;
+        Self::with_connection(connection, settings)
+    }
+
+    fn with_connection(
+        mut connection: VelorenConnection,
+        settings: Arc<RwLock<DatabaseSettings>>,
+    ) -> Result<Self, PersistenceError> {
+        let namespaces = load_namespaces(&mut connection)?;
+        Ok(Self {
+            namespaces: Mutex::new(namespaces),
+            connection: Mutex::new(connection),
+            settings,
+        })
+    }
+}
+
+fn load_namespaces(
+    connection: &mut VelorenConnection,
+) -> Result<HashMap<String, Namespace>, PersistenceError> {
+    let mut namespaces = HashMap::<String, Namespace>::new();
+    for row in connection.query(
+        "
+        SELECT  plugin,
+                key,
+                value
+        FROM    plugin_storage",
+        params![],
+    )? {
+        namespaces
+            .entry(row.get(0)?)
+            .or_default()
+            .insert(row.get(1)?, row.get(2)?);
+    }
+    Ok(namespaces)
+}
+
+fn write_commit(
+    connection: &mut VelorenConnection,
+    namespace: &str,
+    ops: &[StorageOp],
+) -> Result<(), PersistenceError> {
+    let mut transaction = connection.transaction()?;
+    let set = match transaction.dialect() {
+        Dialect::Sqlite => {
+            "
+            REPLACE
+            INTO    plugin_storage (plugin, key, value)
+            VALUES  (?1, ?2, ?3)"
+        },
+        Dialect::Postgres => {
+            "
+            INSERT
+            INTO    plugin_storage (plugin, key, value)
+            VALUES  (?1, ?2, ?3)
+            ON CONFLICT (plugin, key) DO UPDATE
+            SET     value = EXCLUDED.value"
+        },
+    };
+    for op in ops {
+        match op {
+            StorageOp::Set { key, value } => {
+                transaction.execute(set, params![namespace, key, value])?;
+            },
+            StorageOp::Remove { key } => {
+                transaction.execute(
+                    "
+                    DELETE
+                    FROM    plugin_storage
+                    WHERE   plugin = ?1
+                    AND     key = ?2",
+                    params![namespace, key],
+                )?;
+            },
+        }
+    }
+    transaction.commit()
+}
+
+impl PluginStorage for DatabasePluginStorage {
+    fn get(&self, namespace: &str, key: &str) -> Result<Option<Vec<u8>>, String> {
+        Ok(self
+            .namespaces
+            .lock()
+            .unwrap()
+            .get(namespace)
+            .and_then(|namespace| namespace.values.get(key).cloned()))
+    }
+
+    fn keys(&self, namespace: &str, prefix: &str) -> Result<Vec<String>, String> {
+        Ok(self
+            .namespaces
+            .lock()
+            .unwrap()
+            .get(namespace)
+            .map(|namespace| {
+                namespace
+                    .values
+                    .range::<str, _>(prefix..)
+                    .map(|(key, _)| key)
+                    .take_while(|key| key.starts_with(prefix))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    fn commit(&self, namespace: &str, ops: Vec<StorageOp>) -> Result<(), String> {
+        let mut namespaces = self.namespaces.lock().unwrap();
+        let values = namespaces.entry_ref(namespace).or_default();
+
+        // Plugins that are already over a limit (e.g. because it was lowered) can
+        // still free up space
+        let (keys, bytes) = values.usage_after(&ops);
+        if keys > MAX_KEYS && keys > values.values.len() {
+            return Err(format!("Plugins can't store more than {MAX_KEYS} keys"));
+        }
+        if bytes > MAX_BYTES && bytes > values.bytes {
+            return Err(format!("Plugins can't store more than {MAX_BYTES} bytes"));
+        }
+
+        // The transaction is rolled back on errors, memory is only changed once the
+        // database has the values
+        let mut connection = self.connection.lock().unwrap();
+        connection.update_log_mode(&self.settings);
+        write_commit(&mut connection, namespace, &ops).map_err(|e| {
+            error!(?e, "Couldn't write the plugin storage to the database");
+            e.to_string()
+        })?;
+
+        for op in ops {
+            match op {
+                StorageOp::Set { key, value } => values.insert(key, value),
+                StorageOp::Remove { key } => values.remove(&key),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::{DatabaseBackend, SqlLogMode, backend::Connection, embedded};
+
+    fn storage() -> DatabasePluginStorage {
+        let mut connection = rusqlite::Connection::open_in_memory().unwrap();
+        embedded::migrations::runner().run(&mut connection).unwrap();
+        let settings = DatabaseSettings {
+            db_dir: Default::default(),
+            sql_log_mode: SqlLogMode::Disabled,
+            backend: DatabaseBackend::Sqlite,
+        };
+        DatabasePluginStorage::with_connection(
+            VelorenConnection::new(Connection::Sqlite(connection)),
+            Arc::new(RwLock::new(settings)),
+        )
+        .unwrap()
+    }
+
+    fn set(key: &str, len: usize) -> StorageOp {
+        StorageOp::Set {
+            key: key.to_owned(),
+            value: vec![0; len],
+        }
+    }
+
+    #[test]
+    fn usage_counts_replaced_and_removed_values() {
+        let mut namespace = Namespace::default();
+        namespace.insert("a".to_owned(), vec![0; 10]);
+        namespace.insert("b".to_owned(), vec![0; 20]);
+        assert_eq!((namespace.values.len(), namespace.bytes), (2, 32));
+
+        let ops = [set("a", 5), set("c", 3), set("c", 7), StorageOp::Remove {
+            key: "b".to_owned(),
+        }];
+        assert_eq!(namespace.usage_after(&ops), (2, 14));
+
+        for op in ops {
+            match op {
+                StorageOp::Set { key, value } => namespace.insert(key, value),
+                StorageOp::Remove { key } => namespace.remove(&key),
+            }
+        }
+        assert_eq!((namespace.values.len(), namespace.bytes), (2, 14));
+    }
+
+    #[test]
+    fn commits_are_written_to_the_database() {
+        let storage = storage();
+        storage
+            .commit("plugin", vec![set("a", 3), set("b", 4)])
+            .unwrap();
+        storage
+            .commit("plugin", vec![StorageOp::Remove {
+                key: "a".to_owned(),
+            }])
+            .unwrap();
+
+        let namespaces = load_namespaces(&mut storage.connection.lock().unwrap()).unwrap();
+        let values = &namespaces["plugin"].values;
+        assert_eq!(values.keys().collect::<Vec<_>>(), ["b"]);
+        assert_eq!(values["b"], vec![0; 4]);
+    }
+
+    #[test]
+    fn failed_commit_leaves_values_unchanged() {
+        let storage = storage();
+        storage.commit("plugin", vec![set("a", 3)]).unwrap();
+
+        storage
+            .connection
+            .lock()
+            .unwrap()
+            .execute("DROP TABLE plugin_storage", params![])
+            .unwrap();
+        assert!(
+            storage
+                .commit("plugin", vec![set("a", 5), set("b", 4)])
+                .is_err()
+        );
+
+        assert_eq!(storage.get("plugin", "a").unwrap(), Some(vec![0; 3]));
+        assert_eq!(storage.get("plugin", "b").unwrap(), None);
+        let namespaces = storage.namespaces.lock().unwrap();
+        assert_eq!(namespaces["plugin"].bytes, 4);
+    }
+}
@@ -0,0 +1,283 @@
+//! Server side glue for plugins: dispatching game events to them and applying
+//! the world mutations they requested.
+//!
+//! Plugins only see a read only view of the ECS while they run, the actions
+//! they request are validated by the plugin runtime and collected in the
+//! [`PluginMgr`] until the server applies them here.
+
+use crate::Server;
+#[cfg(feature = "hot-reloading")]
+use common::assets::{AssetExt, PluginArchives, ReloadWatcher};
+use common::{
+    LoadoutBuilder,
+    comp::{
+        self, Alignment, ChatType, Content, Inventory, Item,
+        buff::{Buff, BuffChange, BuffSource, DestInfo},
+        inventory::item::{MaterialStatManifest, tool::AbilityMap},
+    },
+    event::{BuffEvent, CreateNpcEvent, EventBus, NpcBuilder, TeleportToPositionEvent},
+    resources::{GameMode, Time},
+    uid::{IdMaps, Uid},
+};
+use common_net::{msg::ServerGeneral, sync::WorldSyncExt};
+use common_state::plugin::{
+    PluginAction, PluginEvent, PluginMgr, memory_manager::EcsWorld, module::ChatKind,
+};
+use specs::{Entity as EcsEntity, WorldExt};
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+/// The asset reloader reloads [`PluginArchives`] whenever a plugin archive is
+/// added, changed or removed
+#[cfg(feature = "hot-reloading")]
+pub(crate) fn plugin_watcher() -> Option<ReloadWatcher> {
+    PluginArchives::load(PluginMgr::ASSETS_ID)
+        .inspect_err(|e| warn!(?e, "Failed to watch the plugin archives"))
+        .ok()
+        .map(|archives| archives.reload_watcher())
+}
+
+impl Server {
+    /// Calls `f` with the plugin manager and the view of the ECS plugins get
+    pub(crate) fn with_plugins<R>(&self, f: impl FnOnce(&mut PluginMgr, &EcsWorld) -> R) -> R {
+        let ecs = self.state.ecs();
+        let mut plugin_mgr = ecs.write_resource::<PluginMgr>();
+        let ecs_world = EcsWorld {
+            entities: &ecs.entities(),
+            health: ecs.read_component().into(),
+            uid: ecs.read_component().into(),
+            id_maps: &ecs.read_resource::<IdMaps>().into(),
+            player: ecs.read_component().into(),
+            pos: ecs.read_component().into(),
+            inventory: ecs.read_component().into(),
+            stats: ecs.read_component().into(),
+            buffs: ecs.read_component().into(),
+            group: ecs.read_component().into(),
+            body: ecs.read_component().into(),
+            group_manager: &ecs.read_resource::<comp::group::GroupManager>().into(),
+            time: &ecs.read_resource::<Time>().into(),
+        };
+        f(&mut plugin_mgr, &ecs_world)
+    }
+
+    /// Load all plugins from disk again, see [`PluginMgr::reload`]
+    pub fn reload_plugins(&mut self) {
+        // The old plugins may still have requested actions
+        self.apply_plugin_actions();
+        let game_mode = *self.state.ecs().read_resource::<GameMode>();
+        match self.with_plugins(|plugin_mgr, ecs_world| plugin_mgr.reload(ecs_world, game_mode)) {
+            Ok(plugins) => info!(?plugins, "Reloaded plugins"),
+            Err(e) => error!(?e, "Failed to reload plugins, keeping the current ones"),
+        }
+    }
+
+    #[cfg(feature = "hot-reloading")]
+    pub(crate) fn reload_changed_plugins(&mut self) {
+        if self
+            .plugin_watcher
+            .as_mut()
+            .is_some_and(ReloadWatcher::reloaded)
+        {
+            self.reload_plugins();
+        }
+    }
+
+    /// Call the `tick` export and due timers of plugins
+    pub(crate) fn tick_plugins(&self, dt: Duration) {
+        self.with_plugins(|plugin_mgr, ecs_world| plugin_mgr.tick(ecs_world, dt.as_secs_f64()));
+    }
+
+    /// Pass the game events collected while handling server events to the
+    /// plugins
+    pub(crate) fn dispatch_plugin_events(&mut self) {
+        let events: Vec<_> = self
+            .state
+            .ecs_mut()
+            .write_resource::<EventBus<PluginEvent>>()
+            .recv_all_mut()
+            .collect();
+        if events.is_empty() {
+            return;
+        }
+        self.with_plugins(|plugin_mgr, ecs_world| {
+            for event in events {
+                plugin_mgr.game_event(ecs_world, &event);
+            }
+        });
+    }
+
+    /// Let plugins veto or modify a player chat message, returns the message
+    /// to deliver if any
+    pub(crate) fn plugin_chat_hook(
+        &self,
+        mut msg: comp::UnresolvedChatMsg,
+    ) -> Option<comp::UnresolvedChatMsg> {
+        let kind = match msg.chat_type {
+            comp::ChatType::Say(_) => ChatKind::Say,
+            comp::ChatType::Tell(_, _) => ChatKind::Tell,
+            comp::ChatType::Group(_, _) => ChatKind::Group,
+            comp::ChatType::Faction(_, _) => ChatKind::Faction,
+            comp::ChatType::Region(_) => ChatKind::Region,
+            comp::ChatType::World(_) => ChatKind::World,
+            _ => return Some(msg),
+        };
+        let (Some(sender), Some(text)) = (msg.uid(), msg.content().as_plain()) else {
+            return Some(msg);
+        };
+        let text = text.to_owned();
+        match self.with_plugins(|plugin_mgr, ecs_world| {
+            plugin_mgr.chat_event(ecs_world, sender, kind, text)
+        }) {
+            Ok(text) => {
+                msg.set_content(Content::Plain(text));
+                Some(msg)
+            },
+            Err(reason) => {
+                if let (Some(reason), Some(entity)) =
+                    (reason, self.state.ecs().entity_from_uid(sender))
+                {
+                    self.notify_client(
+                        entity,
+                        ServerGeneral::server_msg(ChatType::CommandError, Content::Plain(reason)),
+                    );
+                }
+                None
+            },
+        }
+    }
+
+    /// Apply all actions plugins requested since the last call
+    pub(crate) fn apply_plugin_actions(&mut self) {
+        let actions = self
+            .state
+            .ecs()
+            .write_resource::<PluginMgr>()
+            .take_actions();
+        for action in actions {
+            match action {
+                PluginAction::SendMessage { target, text } => {
+                    if let Some(entity) = self.plugin_target(target) {
+                        self.notify_client(
+                            entity,
+                            ServerGeneral::server_msg(ChatType::CommandInfo, Content::Plain(text)),
+                        );
+                    }
+                },
+                PluginAction::Teleport { target, position } => {
+                    if let Some(entity) = self.plugin_target(target) {
+                        self.state
+                            .emit_event_now(TeleportToPositionEvent { entity, position });
+                    }
+                },
+                PluginAction::GiveItem {
+                    target,
+                    item,
+                    amount,
+                } => {
+                    if let Some(entity) = self.plugin_target(target) {
+                        self.plugin_give_item(entity, &item, amount);
+                    }
+                },
+                PluginAction::ApplyBuff { target, kind, data } => {
+                    if let Some(entity) = self.plugin_target(target) {
+                        let ecs = self.state.ecs();
+                        let time = *ecs.read_resource::<Time>();
+                        let stats = ecs.read_storage::<comp::Stats>();
+                        let masses = ecs.read_storage::<comp::Mass>();
+                        let buff = Buff::new(
+                            kind,
+                            data,
+                            vec![],
+                            BuffSource::Command,
+                            time,
+                            DestInfo {
+                                stats: stats.get(entity),
+                                mass: masses.get(entity),
+                            },
+                            None,
+                        );
+                        drop((stats, masses));
+                        self.state.emit_event_now(BuffEvent {
+                            entity,
+                            buff_change: BuffChange::Add(buff),
+                        });
+                    }
+                },
+                PluginAction::SpawnNpc {
+                    body,
+                    name,
+                    position,
+                } => {
+                    let loadout = LoadoutBuilder::from_default(&body).build();
+                    let npc = NpcBuilder::new(
+                        comp::Stats::new(Content::Plain(name), body),
+                        body,
+                        Alignment::Wild,
+                    )
+                    .with_health(comp::Health::new(body))
+                    .with_inventory(Inventory::with_loadout(loadout, body))
+                    .with_agent(comp::Agent::from_body(&body).with_patrol_origin(position))
+                    .with_scale(body.scale());
+                    self.state.emit_event_now(CreateNpcEvent {
+                        pos: comp::Pos(position),
+                        ori: comp::Ori::default(),
+                        npc,
+                    });
+                },
+            }
+        }
+    }
+
+    fn plugin_target(&self, uid: Uid) -> Option<EcsEntity> {
+        let entity = self.state.ecs().entity_from_uid(uid);
+        if entity.is_none() {
+            warn!(?uid, "Plugin action target no longer exists");
+        }
+        entity
+    }
+
+    fn plugin_give_item(&self, entity: EcsEntity, item: &str, amount: u32) {
+        let Ok(mut item) = Item::new_from_asset(item) else {
+            return;
+        };
+        let ecs = self.state.ecs();
+        {
+            let mut inventories = ecs.write_storage::<Inventory>();
+            let Some(mut inventory) = inventories.get_mut(entity) else {
+                return;
+            };
+            // NOTE: Items that don't fit into the inventory are dropped silently, like
+            // /give_item does.
+            if item.set_amount(amount).is_ok() {
+                if inventory.push(item).is_err() {
+                    warn!(?entity, "Inventory full, couldn't give plugin item");
+                }
+            } else {
+                let ability_map = ecs.read_resource::<AbilityMap>();
+                let msm = ecs.read_resource::<MaterialStatManifest>();
+                // Plugins control the amount, don't loop more often than there are slots to
+                // fill
+                let free_slots = inventory.free_slots();
+                if amount as usize > free_slots {
+                    warn!(?entity, "Inventory full, couldn't give plugin item");
+                }
+                for _ in 0..(amount as usize).min(free_slots) {
+                    if inventory.push(item.duplicate(&ability_map, &msm)).is_err() {
+                        warn!(?entity, "Inventory full, couldn't give plugin item");
+                        break;
+                    }
+                }
+            }
+        }
+
+        let mut inventory_update = ecs.write_storage::<comp::InventoryUpdate>();
+        if let Some(update) = inventory_update.get_mut(entity) {
+            update.push(comp::InventoryUpdateEvent::Given);
+        } else {
+            let _ = inventory_update.insert(
+                entity,
+                comp::InventoryUpdate::new(comp::InventoryUpdateEvent::Given),
+            );
+        }
+    }
+}
@@ -1,4 +1,5 @@
 pub mod event;
+pub mod quest_log;
 pub mod rule;
 pub mod tick;
 
@@ -8,6 +9,7 @@ use common::{
     mounting::VolumePos,
     rtsim::{Actor, NpcId, RtSimEntity, TerrainResource, WorldSettings},
     terrain::{CoordinateConversions, SpriteKind},
+    trade::{self, SitePrices},
 };
 use common_ecs::{System, dispatch};
 use common_state::BlockDiff;
@@ -356,6 +358,26 @@ impl RtSim {
     }
 }
 
+/// Get the prices that merchants of a site trade at. When rtsim is running,
+/// these take into account the goods that caravans have brought to or taken
+/// from the site.
+pub fn site_prices(
+    rtsim: Option<&RtSim>,
+    index: IndexRef,
+    site_id: trade::SiteId,
+) -> Option<SitePrices> {
+    let Some(rtsim) = rtsim else {
+        return index.get_site_prices(site_id);
+    };
+    let data = rtsim.state.data();
+    index
+        .sites
+        .recreate_id(site_id)
+        .and_then(|world_site| data.sites.world_site_map.get(&world_site))
+        .and_then(|site| data.sites.get(*site))
+        .map_or_else(|| index.get_site_prices(site_id), |site| site.prices(index))
+}
+
 fn save_thread(file_path: PathBuf, rx: Receiver<Data>) {
     if let Some(dir) = file_path.parent() {
         let _ = fs::create_dir_all(dir);
@@ -380,4 +402,5 @@ pub struct LoadedChunkState {
 
 pub fn add_server_systems(dispatch_builder: &mut DispatcherBuilder) {
     dispatch::<tick::Sys>(dispatch_builder, &[&common_systems::phys::Sys::sys_name()]);
+    dispatch::<quest_log::Sys>(dispatch_builder, &[&tick::Sys::sys_name()]);
 }
@@ -0,0 +1,545 @@
+//! Keeps the quest logs of players up to date with the quests they accepted
+//! from rtsim NPCs.
+
+use super::*;
+use crate::{ServerGeneral, client::Client};
+use common::{
+    character::CharacterId,
+    comp::{self, Content, Presence},
+    map::{Marker, MarkerKind},
+    resources::Time,
+    rtsim::{QuestId, QuestInfo, QuestStatus, SiteId},
+    uid::IdMaps,
+};
+use common_ecs::{Job, Origin, Phase};
+use hashbrown::HashMap;
+use rtsim::{
+    data::quest::{Quest, QuestKind, QuestProgress},
+    rule::npc_ai::quest::item_name,
+};
+use specs::{Entities, Entity as EcsEntity, Join, Read, ReadExpect, ReadStorage};
+
+/// Quest logs only need to be roughly in sync, and the markers of creatures
+/// move all the time
+const SYNC_INTERVAL: f64 = 1.0;
+
+/// The quest logs last sent to each client
+#[derive(Default)]
+pub struct Sys {
+    last_sync: Option<Time>,
+    logs: HashMap<EcsEntity, (CharacterId, HashMap<QuestId, QuestInfo>)>,
+}
+
+impl<'a> System<'a> for Sys {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, Time>,
+        ReadExpect<'a, RtSim>,
+        ReadExpect<'a, world::IndexOwned>,
+        Read<'a, IdMaps>,
+        ReadStorage<'a, Client>,
+        ReadStorage<'a, Presence>,
+        ReadStorage<'a, comp::Pos>,
+        ReadStorage<'a, comp::Stats>,
+    );
+
+    const NAME: &'static str = "rtsim::quest_log";
+    const ORIGIN: Origin = Origin::Server;
+    const PHASE: Phase = Phase::Create;
+
+    fn run(
+        job: &mut Job<Self>,
+        (
+            entities,
+            time,
+            rtsim,
+            index,
+            id_maps,
+            clients,
+            presences,
+            positions,
+            stats,
+        ): Self::SystemData,
+    ) {
+        if job
+            .own
+            .last_sync
+            .is_some_and(|last_sync| time.0 - last_sync.0 < SYNC_INTERVAL)
+        {
+            return;
+        }
+        job.own.last_sync = Some(*time);
+
+        let data = rtsim.state().data();
+        let ctx = InfoCtx {
+            data: &*data,
+            index: &index,
+            id_maps: &id_maps,
+            positions: &positions,
+            stats: &stats,
+        };
+
+        let logs = &mut job.own.logs;
+        logs.retain(|entity, (character_id, _)| {
+            presences.get(*entity).and_then(|p| p.kind.character_id()) == Some(*character_id)
+        });
+
+        for (entity, client, presence) in (&entities, &clients, &presences).join() {
+            let Some(character_id) = presence.kind.character_id() else {
+                continue;
+            };
+            let sent = logs.get(&entity).map(|(_, sent)| sent);
+            let quests = ctx.quest_log(character_id, sent);
+            for msg in sync_messages(sent, &quests) {
+                client.send_fallible(msg);
+            }
+            logs.insert(entity, (character_id, quests));
+        }
+    }
+}
+
+/// The messages that bring the quest log of a client up to date, given the
+/// quests that were last sent to it, if any.
+fn sync_messages(
+    sent: Option<&HashMap<QuestId, QuestInfo>>,
+    quests: &HashMap<QuestId, QuestInfo>,
+) -> Vec<ServerGeneral> {
+    match sent {
+        Some(sent) => quests
+            .iter()
+            .filter(|(quest_id, info)| sent.get(*quest_id) != Some(*info))
+            .map(|(_, info)| ServerGeneral::QuestUpdate(info.clone()))
+            // Rtsim forgets quests a while after they were resolved
+            .chain(
+                sent.keys()
+                    .filter(|quest_id| !quests.contains_key(*quest_id))
+                    .map(|quest_id| ServerGeneral::QuestRemoved(*quest_id)),
+            )
+            .collect(),
+        None => {
+            let mut log = quests.values().cloned().collect::<Vec<_>>();
+            // Quest ids are handed out in order, so this is the order they were
+            // accepted in
+            log.sort_by_key(|quest| quest.id.0);
+            vec![ServerGeneral::QuestLog(log)]
+        },
+    }
+}
+
+struct InfoCtx<'a, 'b> {
+    data: &'a Data,
+    index: &'a world::IndexOwned,
+    id_maps: &'a IdMaps,
+    positions: &'a ReadStorage<'b, comp::Pos>,
+    stats: &'a ReadStorage<'b, comp::Stats>,
+}
+
+impl InfoCtx<'_, '_> {
+    /// The quests that the character has to carry out, reusing the info that
+    /// was `sent` for quests that can't change anymore
+    fn quest_log(
+        &self,
+        character_id: CharacterId,
+        sent: Option<&HashMap<QuestId, QuestInfo>>,
+    ) -> HashMap<QuestId, QuestInfo> {
+        let actor = Actor::Character(character_id);
+        self.data
+            .quests
+            .all_related_to(actor)
+            .filter_map(|quest_id| {
+                let quest = self.data.quests.get(quest_id)?;
+                if quest.quester() != actor {
+                    return None;
+                }
+                // Resolved quests don't change anymore, no need to look at them again
+                let info = match sent.and_then(|sent| sent.get(&quest_id)) {
+                    Some(info) if quest.resolution().is_some() && !info.status.is_active() => {
+                        info.clone()
+                    },
+                    _ => self.info(quest_id, quest),
+                };
+                Some((quest_id, info))
+            })
+            .collect()
+    }
+
+    fn info(&self, id: QuestId, quest: &Quest) -> QuestInfo {
+        let status = match quest.resolution() {
+            Some(true) => QuestStatus::Completed,
+            Some(false) => QuestStatus::Failed,
+            None if quest.is_abandoned() => QuestStatus::Failed,
+            None => match quest.progress() {
+                QuestProgress::NotStarted => QuestStatus::Accepted,
+                QuestProgress::Underway => QuestStatus::Underway,
+                QuestProgress::Achieved => QuestStatus::Achieved,
+            },
+        };
+
+        let (objective, target_site, marker) = match &quest.kind {
+            QuestKind::Escort { escortee, to, .. } => {
+                let name = self.actor_name(*escortee);
+                let place = self.site_name(*to);
+                let marker = self.data.sites.get(*to).map(|site| {
+                    Marker::at(site.wpos.as_())
+                        .with_id(id)
+                        .with_label(
+                            Content::localized("hud-map-escort-label")
+                                .with_arg("name", name.clone())
+                                .with_arg("place", place.clone().unwrap_or_default()),
+                        )
+                        .with_quest_flag(true)
+                });
+                (
+                    Content::localized("hud-quest-objective-escort")
+                        .with_arg("name", name)
+                        .with_arg("place", place.clone().unwrap_or_default()),
+                    place,
+                    marker,
+                )
+            },
+            QuestKind::Slay { target, .. } => {
+                let npc = match target {
+                    Actor::Npc(npc_id) => self.data.npcs.get(*npc_id),
+                    Actor::Character(_) => None,
+                };
+                let body = npc.map_or_else(
+                    || Content::localized("hud-quest-unknown"),
+                    |npc| npc.body.localize_npc(),
+                );
+                let marker = npc.filter(|npc| !npc.is_dead()).map(|npc| {
+                    Marker::at(npc.wpos.xy())
+                        .with_id(*target)
+                        .with_label(
+                            Content::localized("hud-map-creature-label")
+                                .with_arg("body", body.clone()),
+                        )
+                        .with_quest_flag(true)
+                });
+                (
+                    Content::localized("hud-quest-objective-slay").with_arg("body", body),
+                    None,
+                    marker,
+                )
+            },
+            QuestKind::Deliver {
+                recipient,
+                item,
+                amount,
+                ..
+            } => {
+                let name = self.actor_name(*recipient);
+                let home = match recipient {
+                    Actor::Npc(npc_id) => self.data.npcs.get(*npc_id).and_then(|npc| npc.home),
+                    Actor::Character(_) => None,
+                };
+                let place = home.and_then(|home| self.site_name(home));
+                let marker = home.and_then(|home| self.data.sites.get(home)).map(|site| {
+                    Marker::at(site.wpos.as_())
+                        .with_id(id)
+                        .with_label(
+                            Content::localized("hud-map-deliver-label")
+                                .with_arg("name", name.clone())
+                                .with_arg("place", place.clone().unwrap_or_default()),
+                        )
+                        .with_quest_flag(true)
+                });
+                (
+                    Content::localized("hud-quest-objective-deliver")
+                        .with_arg("amount", u64::from(*amount))
+                        .with_arg("item", item_name(*item))
+                        .with_arg("name", name),
+                    place,
+                    marker,
+                )
+            },
+            QuestKind::Gather { item, amount, .. } => (
+                Content::localized("hud-quest-objective-gather")
+                    .with_arg("amount", u64::from(*amount))
+                    .with_arg("item", item_name(*item)),
+                None,
+                None,
+            ),
+            QuestKind::ClearSite { site, .. } => {
+                let place = self.site_name(*site);
+                let marker = self.data.sites.get(*site).map(|site| {
+                    Marker::at(site.wpos.as_())
+                        .with_id(id)
+                        .with_label(
+                            Content::localized("hud-map-clear_site-label")
+                                .with_arg("place", place.clone().unwrap_or_default()),
+                        )
+                        .with_quest_flag(true)
+                });
+                (
+                    Content::localized("hud-quest-objective-clear_site")
+                        .with_arg("place", place.clone().unwrap_or_default()),
+                    place,
+                    marker,
+                )
+            },
+            QuestKind::Bounty { target, .. } => {
+                let name = self.actor_name(*target);
+                let marker = self.locate(*target).map(|wpos| {
+                    Marker::at(wpos)
+                        .with_id(*target)
+                        .with_label(
+                            Content::localized("hud-map-character-label")
+                                .with_arg("name", name.clone()),
+                        )
+                        .with_kind(MarkerKind::Character)
+                        .with_quest_flag(true)
+                });
+                (
+                    Content::localized("hud-quest-objective-bounty").with_arg("name", name),
+                    None,
+                    marker,
+                )
+            },
+        };
+
+        QuestInfo {
+            id,
+            status,
+            objective,
+            giver: match quest.arbiter {
+                Actor::Npc(npc_id) => self.data.npcs.get(npc_id).and_then(|npc| npc.get_name()),
+                Actor::Character(_) => None,
+            },
+            target_site,
+            // Markers of finished quests would only clutter the map
+            marker: marker.filter(|_| status.is_active()),
+            reward: quest
+                .deposit()
+                .map(|(item, amount)| (item.to_equivalent_item_def(), amount.round() as u32)),
+            deadline: quest.timeout,
+        }
+    }
+
+    fn site_name(&self, site: SiteId) -> Option<String> {
+        let world_site = self.data.sites.get(site)?.world_site?;
+        self.index
+            .sites
+            .get(world_site)
+            .name()
+            .map(|name| name.to_string())
+    }
+
+    fn actor_name(&self, actor: Actor) -> Content {
+        match actor {
+            Actor::Npc(npc_id) => self
+                .data
+                .npcs
+                .get(npc_id)
+                .and_then(|npc| npc.get_name())
+                .map(Content::Plain),
+            Actor::Character(character_id) => self
+                .id_maps
+                .character_entity(character_id)
+                .and_then(|entity| self.stats.get(entity))
+                .map(|stats| stats.name.clone()),
+        }
+        .unwrap_or_else(|| Content::localized("hud-quest-unknown"))
+    }
+
+    /// Where the actor is, if it's alive
+    fn locate(&self, actor: Actor) -> Option<Vec2<f32>> {
+        match actor {
+            Actor::Npc(npc_id) => self
+                .data
+                .npcs
+                .get(npc_id)
+                .filter(|npc| !npc.is_dead())
+                .map(|npc| npc.wpos.xy()),
+            Actor::Character(character_id) => self
+                .id_maps
+                .character_entity(character_id)
+                .and_then(|entity| self.positions.get(entity))
+                .map(|pos| pos.0.xy()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::rtsim::{ItemResource, Role};
+    use rtsim::data::{Npc, quest::RESOLVED_QUEST_LIFETIME};
+    use specs::WorldExt;
+
+    struct TestWorld {
+        data: Data,
+        index: world::IndexOwned,
+        ecs: specs::World,
+        id_maps: IdMaps,
+    }
+
+    impl TestWorld {
+        fn new() -> Self {
+            let (world, index) = World::empty();
+            let data = Data::generate(&WorldSettings::default(), &world, index.as_index_ref());
+            let mut ecs = specs::World::new();
+            ecs.register::<comp::Pos>();
+            ecs.register::<comp::Stats>();
+            Self {
+                data,
+                index,
+                ecs,
+                id_maps: IdMaps::default(),
+            }
+        }
+
+        fn quest_log(
+            &self,
+            character_id: CharacterId,
+            sent: Option<&HashMap<QuestId, QuestInfo>>,
+        ) -> HashMap<QuestId, QuestInfo> {
+            InfoCtx {
+                data: &self.data,
+                index: &self.index,
+                id_maps: &self.id_maps,
+                positions: &self.ecs.read_storage(),
+                stats: &self.ecs.read_storage(),
+            }
+            .quest_log(character_id, sent)
+        }
+    }
+
+    /// Syncs the quest log of the character like [`Sys`] does
+    fn sync(
+        world: &TestWorld,
+        character_id: CharacterId,
+        sent: &mut Option<HashMap<QuestId, QuestInfo>>,
+    ) -> Vec<ServerGeneral> {
+        let quests = world.quest_log(character_id, sent.as_ref());
+        let msgs = sync_messages(sent.as_ref(), &quests);
+        *sent = Some(quests);
+        msgs
+    }
+
+    fn gather_quest(world: &mut TestWorld, gatherer: CharacterId) -> (Actor, QuestId) {
+        let giver = Actor::Npc(world.data.spawn_npc(Npc::new(
+            0,
+            Vec3::zero(),
+            comp::Body::Humanoid(comp::humanoid::Body::random()),
+            Role::Civilised(None),
+        )));
+        let quest_id = world.data.quests.register();
+        world.data.quests.create(
+            quest_id,
+            Quest::gather(giver, Actor::Character(gatherer), ItemResource::Flax, 10)
+                .with_deposit(ItemResource::Coin, 20.0),
+        );
+        (giver, quest_id)
+    }
+
+    #[test]
+    fn log_is_sent_once_then_updated() {
+        let mut world = TestWorld::new();
+        let gatherer = CharacterId(1);
+        let (giver, quest_id) = gather_quest(&mut world, gatherer);
+        // Quests of other characters don't show up
+        gather_quest(&mut world, CharacterId(2));
+
+        let mut sent = None;
+        let msgs = sync(&world, gatherer, &mut sent);
+        assert!(
+            matches!(
+                msgs.as_slice(),
+                [ServerGeneral::QuestLog(log)]
+                    if log.len() == 1
+                        && log[0].id == quest_id
+                        && log[0].status == QuestStatus::Accepted
+                        && log[0].reward.as_ref().is_some_and(|(_, amount)| *amount == 20)
+            ),
+            "the whole log is sent on the first sync"
+        );
+        assert!(
+            sync(&world, gatherer, &mut sent).is_empty(),
+            "nothing changed"
+        );
+
+        world
+            .data
+            .quests
+            .get_mut(quest_id)
+            .unwrap()
+            .advance(QuestProgress::Achieved);
+        let msgs = sync(&world, gatherer, &mut sent);
+        assert!(matches!(
+            msgs.as_slice(),
+            [ServerGeneral::QuestUpdate(info)] if info.status == QuestStatus::Achieved
+        ));
+
+        let quest = world.data.quests.get(quest_id).unwrap();
+        assert!(quest.resolve(giver, true).is_some());
+        let msgs = sync(&world, gatherer, &mut sent);
+        assert!(matches!(
+            msgs.as_slice(),
+            [ServerGeneral::QuestUpdate(info)] if info.status == QuestStatus::Completed
+        ));
+        assert!(sync(&world, gatherer, &mut sent).is_empty());
+    }
+
+    #[test]
+    fn abandoned_quests_fail() {
+        let mut world = TestWorld::new();
+        let gatherer = CharacterId(1);
+        let (_, quest_id) = gather_quest(&mut world, gatherer);
+
+        let mut sent = None;
+        sync(&world, gatherer, &mut sent);
+        world.data.quests.get_mut(quest_id).unwrap().abandon();
+        let msgs = sync(&world, gatherer, &mut sent);
+        assert!(matches!(
+            msgs.as_slice(),
+            [ServerGeneral::QuestUpdate(info)] if info.status == QuestStatus::Failed
+        ));
+    }
+
+    #[test]
+    fn forgotten_quests_are_removed() {
+        let mut world = TestWorld::new();
+        let gatherer = CharacterId(1);
+        let (giver, resolved) = gather_quest(&mut world, gatherer);
+        let (_, active) = gather_quest(&mut world, gatherer);
+
+        let mut sent = None;
+        let msgs = sync(&world, gatherer, &mut sent);
+        assert!(
+            matches!(
+                msgs.as_slice(),
+                [ServerGeneral::QuestLog(log)]
+                    if log.iter().map(|quest| quest.id).eq([resolved, active])
+            ),
+            "the log is in the order the quests were accepted in"
+        );
+
+        let quests = &mut world.data.quests;
+        assert!(
+            quests
+                .get(resolved)
+                .unwrap()
+                .resolve(giver, false)
+                .is_some()
+        );
+        quests.cleanup(Time(0.0));
+        sync(&world, gatherer, &mut sent);
+
+        world
+            .data
+            .quests
+            .cleanup(Time(RESOLVED_QUEST_LIFETIME - 1.0));
+        assert!(
+            sync(&world, gatherer, &mut sent).is_empty(),
+            "resolved quests stay in the log for a while"
+        );
+
+        world.data.quests.cleanup(Time(RESOLVED_QUEST_LIFETIME));
+        let msgs = sync(&world, gatherer, &mut sent);
+        assert!(matches!(
+            msgs.as_slice(),
+            [ServerGeneral::QuestRemoved(quest_id)] if *quest_id == resolved
+        ));
+        assert_eq!(sent.unwrap().into_keys().collect::<Vec<_>>(), vec![active]);
+    }
+}
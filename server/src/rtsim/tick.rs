@@ -177,6 +177,7 @@ fn humanoid_config(profession: &Profession) -> &'static str {
         Profession::Herbalist => "common.entity.village.herbalist",
         Profession::Captain => "common.entity.village.captain",
         Profession::Merchant => "common.entity.village.merchant",
+        Profession::Caravan => "common.entity.village.caravan",
         Profession::Guard => "common.entity.village.guard",
         Profession::Adventurer(rank) => match rank {
             0 => "common.entity.world.traveler0",
@@ -458,6 +459,8 @@ impl<'a> System<'a> for Sys {
         ReadExpect<'a, world::IndexOwned>,
         ReadExpect<'a, SlowJobPool>,
         ReadStorage<'a, comp::Pos>,
+        ReadStorage<'a, comp::Alignment>,
+        ReadStorage<'a, comp::Health>,
         ReadStorage<'a, RtSimEntity>,
         WriteStorage<'a, comp::Agent>,
         ReadStorage<'a, Presence>,
@@ -490,6 +493,8 @@ impl<'a> System<'a> for Sys {
             index,
             slow_jobs,
             positions,
+            alignments,
+            healths,
             rtsim_entities,
             mut agents,
             presences,
@@ -535,6 +540,8 @@ impl<'a> System<'a> for Sys {
         rtsim.state.tick(
             &mut NpcSystemData {
                 positions: positions.clone(),
+                alignments,
+                healths,
                 id_maps,
                 server_constants,
                 weather_grid,
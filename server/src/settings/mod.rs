@@ -23,6 +23,7 @@ use common::{
     rtsim::WorldSettings,
 };
 use core::time::Duration;
+use network::NetworkConditions;
 use portpicker::pick_unused_port;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -35,6 +36,7 @@ use tracing::{error, warn};
 use world::sim::{DEFAULT_WORLD_SEED, FileOpts};
 
 use self::server_physics::ServerPhysicsForceList;
+use crate::persistence::{DatabaseBackend, backup::BackupSettings};
 
 const CONFIG_DIR: &str = "server_config";
 const SETTINGS_FILENAME: &str = "settings.ron";
@@ -96,6 +98,13 @@ pub enum Protocol {
     Tcp {
         address: SocketAddr,
     },
+    /// Like `Tcp`, but clients that know the public key of `key_file_path`
+    /// can encrypt the connection. The key is generated if the file doesn't
+    /// exist.
+    TcpEncrypted {
+        address: SocketAddr,
+        key_file_path: PathBuf,
+    },
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -180,6 +189,11 @@ pub struct Settings {
     pub gameserver_protocols: Vec<Protocol>,
     pub auth_server_address: Option<String>,
     pub query_address: Option<SocketAddr>,
+    /// Whether the query server answers requests for the names of online
+    /// players. Off by default, since anyone can query them.
+    pub query_player_list: bool,
+    /// Tags describing the server, shown by server browsers
+    pub server_tags: Vec<String>,
     pub max_players: u16,
     pub world_seed: u32,
     pub server_name: String,
@@ -191,8 +205,21 @@ pub struct Settings {
     pub max_view_distance: Option<u32>,
     pub max_player_group_size: u32,
     pub client_timeout: Duration,
+    /// How long a client that lost its connection can reconnect and continue
+    /// its session. Zero disables this. Only clients with an encrypted
+    /// connection can reconnect.
+    pub session_resume_timeout: Duration,
+    /// Delays, drops or throttles data sent to clients connected via TCP or
+    /// in singleplayer. Only meant for testing, e.g. to reproduce
+    /// rubber-banding.
+    pub simulated_network_conditions: NetworkConditions,
     pub max_player_for_kill_broadcast: Option<usize>,
     pub calendar_mode: CalendarMode,
+    /// Where characters are saved. SQLite in the saves directory by default,
+    /// a PostgreSQL database can be shared by multiple servers.
+    pub database: DatabaseBackend,
+    /// Periodic backups of the SQLite database and how long they are kept
+    pub database_backups: BackupSettings,
 
     /// Experimental feature. No guaranteed forwards-compatibility, may be
     /// removed at *any time* with no migration.
@@ -221,6 +248,8 @@ impl Default for Settings {
             ],
             auth_server_address: Some("https://auth.veloren.net".into()),
             query_address: Some(SocketAddr::from((Ipv4Addr::UNSPECIFIED, 14006))),
+            query_player_list: false,
+            server_tags: Vec::new(),
             world_seed: DEFAULT_WORLD_SEED,
             server_name: "Veloren Server".into(),
             max_players: 100,
@@ -230,7 +259,11 @@ impl Default for Settings {
             max_player_group_size: 6,
             calendar_mode: CalendarMode::Auto,
             client_timeout: Duration::from_secs(40),
+            session_resume_timeout: Duration::from_secs(15),
+            simulated_network_conditions: NetworkConditions::default(),
             max_player_for_kill_broadcast: None,
+            database: DatabaseBackend::default(),
+            database_backups: BackupSettings::default(),
             experimental_terrain_persistence: false,
             gameplay: GameplaySettings::default(),
             moderation: ModerationSettings::default(),
@@ -18,6 +18,7 @@ use common::{
 };
 use common_ecs::{Job, Origin, Phase, System};
 use common_net::{msg::ServerGeneral, sync::CompSyncPackage};
+use hashbrown::HashMap;
 use itertools::Either;
 use specs::{Entities, Join, LendJoin, Read, ReadExpect, ReadStorage, Write, WriteStorage};
 use vek::*;
@@ -134,7 +135,7 @@ impl<'a> System<'a> for Sys {
         use rayon::iter::{IntoParallelIterator, ParallelIterator};
         job.cpu_stats.measure(common_ecs::ParMode::Rayon);
         common_base::prof_span!(guard, "regions");
-        regions_and_deleted_entities.into_par_iter().for_each_init(
+        let physics_by_region = regions_and_deleted_entities.into_par_iter().map_init(
             || {
                 common_base::prof_span!(guard, "entity sync rayon job");
                 guard
@@ -243,8 +244,10 @@ impl<'a> System<'a> for Sys {
                     entity_comp_sync = Either::Right(msg);
                 }
 
+                let mut physics_by_client = Vec::with_capacity(subscribers.len());
                 for (client, _, client_entity, client_pos) in &mut subscribers {
                     let mut comp_sync_package = CompSyncPackage::new();
+                    let mut physics = Vec::new();
 
                     for (_, entity, &uid, (&pos, last_pos), vel, ori, collider) in (
                         region.entities(),
@@ -257,6 +260,7 @@ impl<'a> System<'a> for Sys {
                     )
                         .join()
                     {
+                        let is_voxel = matches!(collider, Some(Collider::Voxel { .. }));
                         // Decide how regularly to send physics updates.
                         let send_now = if client_entity == &entity {
                             should_sync_client_physics(
@@ -267,7 +271,7 @@ impl<'a> System<'a> for Sys {
                                 is_rider,
                                 &editable_settings,
                             )
-                        } else if matches!(collider, Some(Collider::Voxel { .. })) {
+                        } else if is_voxel {
                             // Things with a voxel collider (airships, etc.) need to have very
                             // stable physics so we always send updated
                             // for these where we can.
@@ -296,15 +300,25 @@ impl<'a> System<'a> for Sys {
                             }
                         };
 
-                        add_physics_components(
-                            send_now,
-                            &mut comp_sync_package,
-                            uid,
-                            pos,
-                            last_pos,
-                            ori,
-                            vel,
-                        );
+                        if client_entity == &entity || is_voxel {
+                            // Not quantized, the client's own entity and voxel colliders need
+                            // precise physics
+                            add_physics_components(
+                                send_now,
+                                &mut comp_sync_package,
+                                uid,
+                                pos,
+                                last_pos,
+                                ori,
+                                vel,
+                            );
+                        } else if send_now
+                            || last_pos.is_none()
+                            || vel.is_some_and(|(_, last_vel)| last_vel.is_none())
+                            || ori.is_some_and(|(_, last_ori)| last_ori.is_none())
+                        {
+                            physics.push((uid, pos, vel.map(|(v, _)| *v), ori.map(|(o, _)| *o)));
+                        }
                     }
 
                     // TODO: force update counter only needs to be sent once per frame (and only if
@@ -316,12 +330,34 @@ impl<'a> System<'a> for Sys {
                         comp_sync_package,
                         force_updates.get(*client_entity).map_or(0, |f| f.counter()),
                     ));
+                    physics_by_client.push((*client_entity, physics));
                 }
+                physics_by_client
             },
         );
+        let physics_by_region = physics_by_region.collect::<Vec<_>>();
         drop(guard);
         job.cpu_stats.measure(common_ecs::ParMode::Single);
 
+        // Send the delta compressed physics of all subscribed regions in one
+        // message, so each client has a single baseline
+        let mut physics_by_client = HashMap::<_, Vec<_>>::new();
+        for (client_entity, physics) in physics_by_region.into_iter().flatten() {
+            physics_by_client
+                .entry(client_entity)
+                .or_default()
+                .extend(physics);
+        }
+        for (client_entity, physics) in physics_by_client {
+            if !physics.is_empty()
+                && let Some(client) = clients.get(client_entity)
+            {
+                client.send_fallible(ServerGeneral::PhysicsSync(
+                    client.encode_physics_sync(physics),
+                ));
+            }
+        }
+
         // Sync components that are only synced for the client's own entity.
         for (entity, client, &uid, (maybe_pos, last_pos), vel, ori) in (
             &entities,
@@ -1,11 +1,15 @@
 use crate::{
     HwStats, Tick, TickStart,
     chunk_generator::ChunkGenerator,
-    metrics::{EcsSystemMetrics, JobMetrics, PhysicsMetrics, QueryServerMetrics, TickMetrics},
+    client::Client,
+    metrics::{
+        EcsSystemMetrics, JobMetrics, NetworkTrafficMetrics, PhysicsMetrics, QueryServerMetrics,
+        TickMetrics,
+    },
 };
 use common::{resources::TimeOfDay, slowjob::SlowJobPool, terrain::TerrainGrid};
 use common_ecs::{Job, Origin, Phase, SysMetrics, System};
-use specs::{Entities, Join, Read, ReadExpect};
+use specs::{Entities, Join, Read, ReadExpect, ReadStorage};
 use std::{
     sync::{Arc, Mutex},
     time::Instant,
@@ -33,6 +37,8 @@ impl<'a> System<'a> for Sys {
         ReadExpect<'a, JobMetrics>,
         Option<Read<'a, Arc<Mutex<RawQueryServerMetrics>>>>,
         ReadExpect<'a, QueryServerMetrics>,
+        ReadExpect<'a, NetworkTrafficMetrics>,
+        ReadStorage<'a, Client>,
     );
 
     const NAME: &'static str = "metrics";
@@ -58,6 +64,8 @@ impl<'a> System<'a> for Sys {
             export_jobs,
             raw_query_server,
             export_query_server,
+            export_network_traffic,
+            clients,
         ): Self::SystemData,
     ) {
         const NANOSEC_PER_SEC: f64 = std::time::Duration::from_secs(1).as_nanos() as f64;
@@ -133,6 +141,10 @@ impl<'a> System<'a> for Sys {
             .entity_entity_collisions_count
             .inc_by(phys_metrics.entity_entity_collisions);
 
+        for client in clients.join() {
+            export_network_traffic.apply(&client.take_unreported_traffic());
+        }
+
         //detailed job metrics
         for (name, jobs) in slowjobpool.take_metrics() {
             let queried = export_jobs.job_queried_hst.with_label_values(&[&name]);
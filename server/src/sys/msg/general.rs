@@ -78,6 +78,7 @@ impl Sys {
                     common::comp::DisconnectReason::ClientRequested,
                 ));
             },
+            ClientGeneral::AckPhysicsSync(seq) => client.acknowledge_physics_sync(seq),
             ClientGeneral::RequestPlugins(plugins) => {
                 tracing::info!("Plugin request {plugins:x?}, {}", player.is_some());
 
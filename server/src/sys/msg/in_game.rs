@@ -51,6 +51,7 @@ event_emitters! {
         update_map_marker: event::UpdateMapMarkerEvent,
         client_disconnect: event::ClientDisconnectEvent,
         set_battle_mode: event::SetBattleModeEvent,
+        abandon_quest: event::AbandonQuestEvent,
     }
 }
 
@@ -262,6 +263,9 @@ impl Sys {
                     battle_mode,
                 });
             },
+            ClientGeneral::AbandonQuest(quest_id) => {
+                emitters.emit(event::AbandonQuestEvent { entity, quest_id });
+            },
             ClientGeneral::RequestCharacterList
             | ClientGeneral::CreateCharacter { .. }
             | ClientGeneral::EditCharacter { .. }
@@ -273,6 +277,7 @@ impl Sys {
             | ClientGeneral::ChatMsg(_)
             | ClientGeneral::Command(..)
             | ClientGeneral::Terminate
+            | ClientGeneral::AckPhysicsSync(_)
             | ClientGeneral::RequestPlugins(_) => {
                 debug!("Kicking possibly misbehaving client due to invalid client in game request");
                 emitters.emit(event::ClientDisconnectEvent(
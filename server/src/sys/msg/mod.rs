@@ -12,6 +12,7 @@ use crate::{
     sys::{loot, pets},
 };
 use common_ecs::{System, dispatch};
+use common_net::msg::MsgKind;
 use serde::de::DeserializeOwned;
 use specs::DispatcherBuilder;
 
@@ -38,7 +39,7 @@ pub(crate) fn try_recv_all<M, F>(
     mut f: F,
 ) -> Result<u64, crate::error::Error>
 where
-    M: DeserializeOwned,
+    M: DeserializeOwned + MsgKind,
     F: FnMut(&Client, M) -> Result<(), crate::error::Error>,
 {
     let mut cnt = 0u64;
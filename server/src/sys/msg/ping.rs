@@ -7,8 +7,12 @@ use common_ecs::{Job, Origin, Phase, System};
 use common_net::msg::PingMsg;
 use rayon::prelude::*;
 use specs::{Entities, ParJoin, Read, WriteStorage};
+use std::time::Duration;
 use tracing::{debug, info};
 
+/// How often the round trip time to clients is measured
+const RTT_PING_INTERVAL: Duration = Duration::from_secs(5);
+
 impl Sys {
     fn handle_ping_msg(client: &Client, msg: PingMsg) -> Result<(), crate::error::Error> {
         match msg {
@@ -42,7 +46,14 @@ impl<'a> System<'a> for Sys {
         (&entities, &mut clients).par_join().for_each_init(
             || client_disconnect.emitter(),
             |client_disconnect_emitter, (entity, client)| {
-                let res = super::try_recv_all(client, 4, Self::handle_ping_msg);
+                let mut pong_received = false;
+                let res = super::try_recv_all(client, 4, |client, msg| {
+                    pong_received |= msg == PingMsg::Pong;
+                    Self::handle_ping_msg(client, msg)
+                });
+                if pong_received {
+                    client.pong_received();
+                }
 
                 match res {
                     Err(e) => {
@@ -70,7 +81,13 @@ impl<'a> System<'a> for Sys {
                             > settings.client_timeout.as_secs() as f64 * 0.5
                         {
                             // Try pinging the client if the timeout is nearing.
-                            client.send_fallible(PingMsg::Ping);
+                            client.send_ping();
+                        } else if client
+                            .since_answered_ping()
+                            .is_some_and(|since| since >= RTT_PING_INTERVAL)
+                        {
+                            // Measure the round trip time regularly.
+                            client.send_ping();
                         }
                     },
                 }
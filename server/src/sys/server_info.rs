@@ -5,9 +5,9 @@ use common::{
 use common_ecs::{Origin, Phase, System};
 use specs::{Join, Read, ReadStorage};
 use tracing::warn;
-use veloren_query_server::proto::ServerInfo;
+use veloren_query_server::proto::{ExtendedServerInfo, ServerInfo};
 
-use crate::{Settings, Tick, client::Client};
+use crate::{EditableSettings, Settings, Tick, client::Client};
 
 // Update the server stats every 60 ticks
 const INFO_SEND_INTERVAL: u64 = 60;
@@ -19,7 +19,10 @@ impl<'a> System<'a> for Sys {
     type SystemData = (
         Read<'a, Tick>,
         Read<'a, Settings>,
+        Read<'a, EditableSettings>,
         Option<Read<'a, tokio::sync::watch::Sender<ServerInfo>>>,
+        Option<Read<'a, tokio::sync::watch::Sender<ExtendedServerInfo>>>,
+        Option<Read<'a, tokio::sync::watch::Sender<Vec<String>>>>,
         ReadStorage<'a, Player>,
         ReadStorage<'a, Client>,
     );
@@ -30,18 +33,31 @@ impl<'a> System<'a> for Sys {
 
     fn run(
         _job: &mut common_ecs::Job<Self>,
-        (tick, settings, sender, players, clients): Self::SystemData,
+        (
+            tick,
+            settings,
+            editable_settings,
+            sender,
+            extended_info_sender,
+            player_list_sender,
+            players,
+            clients,
+        ): Self::SystemData,
     ) {
-        if let Some(sender) = sender.as_ref()
-            && tick.0 % INFO_SEND_INTERVAL == 0
-        {
-            let count = (&players, &clients)
+        if tick.0 % INFO_SEND_INTERVAL != 0 {
+            return;
+        }
+
+        // Hide silent spectators from the player count and list
+        let visible_players = || {
+            (&players, &clients)
                 .join()
-                // Hide silent spectators from the player count
                 .filter(|(_, client)| client.client_type.emit_login_events())
-                .count()
-                .try_into()
-                .unwrap_or(u16::MAX);
+                .map(|(player, _)| player)
+        };
+
+        if let Some(sender) = sender.as_ref() {
+            let count = visible_players().count().try_into().unwrap_or(u16::MAX);
             if let Err(e) = sender.send(ServerInfo {
                 git_hash: *GIT_HASH,
                 git_timestamp: *GIT_TIMESTAMP,
@@ -52,5 +68,34 @@ impl<'a> System<'a> for Sys {
                 warn!(?e, "Failed to send server info to the query server");
             }
         }
+
+        if let Some(sender) = extended_info_sender.as_ref() {
+            let motd = editable_settings
+                .server_description
+                .get(None)
+                .map_or("", |description| description.motd.as_str());
+            if let Err(e) = sender.send(ExtendedServerInfo::new(
+                settings.world_seed,
+                settings.day_length,
+                motd,
+                &settings.server_tags,
+            )) {
+                warn!(
+                    ?e,
+                    "Failed to send extended server info to the query server"
+                );
+            }
+        }
+
+        if let Some(sender) = player_list_sender.as_ref() {
+            let mut names: Vec<_> = visible_players()
+                .map(|player| player.alias.clone())
+                .collect();
+            // Keep the order stable between updates for pagination
+            names.sort_unstable();
+            if let Err(e) = sender.send(names) {
+                warn!(?e, "Failed to send the player list to the query server");
+            }
+        }
     }
 }
@@ -2,6 +2,8 @@
 use crate::TerrainPersistence;
 #[cfg(not(feature = "worldgen"))]
 use crate::test_world::{IndexOwned, World};
+#[cfg(feature = "persistent_world")]
+use common::resources::ProgramTime;
 use tracing::error;
 #[cfg(feature = "worldgen")]
 use world::{IndexOwned, World};
@@ -20,8 +22,8 @@ use common::{
         biped_small, bird_medium,
     },
     event::{
-        CreateNpcEvent, CreateNpcGroupEvent, CreateSpecialEntityEvent, EmitExt, EventBus,
-        NpcBuilder,
+        CreateItemDropEvent, CreateNpcEvent, CreateNpcGroupEvent, CreateShipEvent,
+        CreateSpecialEntityEvent, EmitExt, EventBus, NpcBuilder,
     },
     event_emitters,
     generation::{EntityInfo, EntitySpawn, SpecialEntity},
@@ -63,6 +65,8 @@ event_emitters! {
         create_npc: CreateNpcEvent,
         create_npc_group: CreateNpcGroupEvent,
         create_waypoint: CreateSpecialEntityEvent,
+        create_item_drop: CreateItemDropEvent,
+        create_ship: CreateShipEvent,
     }
 }
 
@@ -84,6 +88,8 @@ pub struct Data<'a> {
     rtsim: RtSimData<'a>,
     #[cfg(feature = "persistent_world")]
     terrain_persistence: TerrainPersistenceData<'a>,
+    #[cfg(feature = "persistent_world")]
+    program_time: Read<'a, ProgramTime>,
     positions: WriteStorage<'a, Pos>,
     presences: ReadStorage<'a, Presence>,
     clients: ReadStorage<'a, Client>,
@@ -194,7 +200,11 @@ impl<'a> System<'a> for Sys {
                         let data = SpawnEntityData::from_entity_info(*entity);
                         match data {
                             SpawnEntityData::Special(pos, entity) => {
-                                emitters.emit(CreateSpecialEntityEvent { pos, entity });
+                                emitters.emit(CreateSpecialEntityEvent {
+                                    pos,
+                                    entity,
+                                    anchor: Some(comp::Anchor::Chunk(key)),
+                                });
                             },
                             SpawnEntityData::Npc(data) => {
                                 let (npc_builder, pos) = data.to_npc_builder();
@@ -233,6 +243,14 @@ impl<'a> System<'a> for Sys {
                     },
                 }
             }
+
+            // Spawn the entities that were saved when the chunk unloaded
+            #[cfg(feature = "persistent_world")]
+            if let Some(terrain_persistence) = data.terrain_persistence.as_mut() {
+                for entity in terrain_persistence.take_entities(key) {
+                    entity.restore(&mut emitters, *data.program_time);
+                }
+            }
         }
 
         // TODO: Consider putting this in another system since this forces us to take
@@ -1,3 +1,4 @@
+use crate::entity_persistence::PersistedEntity;
 use atomicwrites::{AtomicFile, OverwriteBehavior};
 use bincode::{
     config::legacy,
@@ -15,12 +16,14 @@ use std::{
     any::{Any, type_name},
     fs::File,
     io::{self, Read as _, Write as _},
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 use tracing::{debug, error, info, warn};
 use vek::*;
 
 const MAX_BLOCK_CACHE: usize = 64_000_000;
+/// Files of entities with another version are moved aside instead of loaded
+const ENTITIES_VERSION: u32 = 1;
 
 pub struct TerrainPersistence {
     path: PathBuf,
@@ -101,6 +104,85 @@ impl TerrainPersistence {
         path
     }
 
+    fn entities_path_for(&self, key: Vec2<i32>) -> PathBuf {
+        let mut path = self.path.clone();
+        path.push(format!("entities_{}_{}.ron", key.x, key.y));
+        path
+    }
+
+    /// Removes the entities that were saved in the chunk at `key` and returns
+    /// them, so they can be spawned again.
+    pub fn take_entities(&mut self, key: Vec2<i32>) -> Vec<PersistedEntity> {
+        let path = self.entities_path_for(key);
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Vec::new(),
+            Err(err) => {
+                error!("Failed to open entities of chunk {:?}: {:?}", key, err);
+                return Vec::new();
+            },
+        };
+        // The version is read first, so files of other versions are rejected with a
+        // clear error
+        let entities = ron::from_str::<EntitiesVersion>(&contents)
+            .map_err(|err| err.to_string())
+            .and_then(|EntitiesVersion { version }| {
+                if version == ENTITIES_VERSION {
+                    ron::from_str::<EntitiesFile>(&contents)
+                        .map(|file| file.entities)
+                        .map_err(|err| err.to_string())
+                } else {
+                    Err(format!("unknown version {}", version))
+                }
+            });
+
+        match entities {
+            Ok(entities) => {
+                if let Err(error) = std::fs::remove_file(&path) {
+                    error!(?error, ?path, "Failed to remove file of loaded entities");
+                }
+                entities
+            },
+            Err(err) => {
+                let backup_path = backup_path_for(&path);
+                error!(
+                    "Failed to load entities of chunk {:?} ({}), moving possibly corrupt (or too \
+                     new) data to {:?} for you to repair.",
+                    key, err, backup_path
+                );
+                if let Err(err) = std::fs::rename(path, backup_path) {
+                    error!("Failed to rename invalid entities file: {:?}", err);
+                }
+                Vec::new()
+            },
+        }
+    }
+
+    /// Saves entities that were removed because the chunk at `key` unloaded,
+    /// in addition to those that are already saved there.
+    pub fn save_entities(&mut self, key: Vec2<i32>, mut entities: Vec<PersistedEntity>) {
+        entities.extend(self.take_entities(key));
+        let file = EntitiesFile {
+            version: ENTITIES_VERSION,
+            entities,
+        };
+        let contents = match ron::to_string(&file) {
+            Err(err) => {
+                error!("Failed to serialize entities of chunk {:?}: {:?}", key, err);
+                return;
+            },
+            Ok(contents) => contents,
+        };
+
+        let atomic_file = AtomicFile::new(
+            self.entities_path_for(key),
+            OverwriteBehavior::AllowOverwrite,
+        );
+        if let Err(err) = atomic_file.write(|file| file.write_all(contents.as_bytes())) {
+            error!("Failed to write entities to file: {:?}", err);
+        }
+    }
+
     fn load_chunk(&mut self, key: Vec2<i32>) -> &mut LoadedChunk {
         let path = self.path_for(key);
         self.chunks.entry(key).or_insert_with(|| {
@@ -129,14 +211,7 @@ impl TerrainPersistence {
                     let chunk = match Chunk::deserialize_from(io::Cursor::new(bytes)) {
                         Some(chunk) => chunk,
                         None => {
-                            // Find an untaken name for a backup
-                            let mut backup_path = path.clone();
-                            backup_path.set_extension("dat_backup_0");
-                            let mut i = 1;
-                            while backup_path.exists() {
-                                backup_path.set_extension(format!("dat_backup_{}", i));
-                                i += 1;
-                            }
+                            let backup_path = backup_path_for(&path);
 
                             error!(
                                 "Failed to load chunk {:?}, moving possibly corrupt (or too new) \
@@ -231,6 +306,31 @@ impl Drop for TerrainPersistence {
     fn drop(&mut self) { self.unload_all(); }
 }
 
+/// Find an untaken name for a backup of the file at `path`
+fn backup_path_for(path: &Path) -> PathBuf {
+    let mut backup_path = path.to_path_buf();
+    backup_path.set_extension("dat_backup_0");
+    let mut i = 1;
+    while backup_path.exists() {
+        backup_path.set_extension(format!("dat_backup_{}", i));
+        i += 1;
+    }
+    backup_path
+}
+
+#[derive(Deserialize)]
+struct EntitiesVersion {
+    version: u32,
+}
+
+/// The entities saved in a chunk, stored as RON so that the format can evolve
+/// without invalidating saved entities
+#[derive(Serialize, Deserialize)]
+struct EntitiesFile {
+    version: u32,
+    entities: Vec<PersistedEntity>,
+}
+
 #[derive(Default, Serialize, Deserialize, Clone)]
 pub struct Chunk {
     blocks: HashMap<Vec3<i32>, Block>,
@@ -49,7 +49,7 @@ impl WeatherSim {
                             for x in 0..CHUNKS_PER_CELL {
                                 let chunk_pos = p * CHUNKS_PER_CELL + Vec2::new(x, y);
                                 if let Some(chunk) = world.sim().get(chunk_pos.as_()) {
-                                    let env = chunk.get_environment();
+                                    let env = chunk.get_environment(&world.sim().config);
                                     humid_sum += env.humid;
                                 }
                             }
@@ -95,6 +95,8 @@ pub enum GameInput {
     Chat,
     #[strum(serialize = "gameinput-social")]
     Social,
+    #[strum(serialize = "gameinput-questlog")]
+    QuestLog,
     #[strum(serialize = "gameinput-escape")]
     Escape,
     #[strum(serialize = "gameinput-controls")]
@@ -55,7 +55,7 @@ use map::Map;
 use minimap::{MiniMap, VoxelMinimap};
 use popup::Popup;
 use prompt_dialog::PromptDialog;
-use quest::Quest;
+use quest::{Quest, QuestLog};
 use serde::{Deserialize, Serialize};
 use settings_window::{SettingsTab, SettingsWindow};
 use skillbar::Skillbar;
@@ -334,6 +334,7 @@ widget_ids! {
         small_window,
         social_window,
         quest_window,
+        quest_log_window,
         tutorial_window,
         crafting_window,
         settings_window,
@@ -764,6 +765,8 @@ pub enum Event {
     MapMarkerEvent(MapMarkerChange),
     Dialogue(EcsEntity, rtsim::Dialogue),
     SetBattleMode(BattleMode),
+    TrackQuest(rtsim::QuestId, bool),
+    AbandonQuest(rtsim::QuestId),
 }
 
 // TODO: Are these the possible layouts we want?
@@ -935,6 +938,7 @@ pub struct Show {
     diary: bool,
     group: bool,
     quest: bool,
+    quest_log: bool,
     group_menu: bool,
     esc_menu: bool,
     open_windows: Windows,
@@ -986,6 +990,7 @@ impl Show {
             self.crafting_fields.salvage = false;
             self.social = false;
             self.quest = false;
+            self.quest_log = false;
             self.diary = false;
             self.want_grab = !self.any_window_requires_cursor();
         }
@@ -1012,6 +1017,15 @@ impl Show {
         }
     }
 
+    fn quest_log(&mut self, open: bool) {
+        if !self.esc_menu {
+            self.quest_log = open;
+            self.diary = false;
+            self.map = false;
+            self.want_grab = !self.any_window_requires_cursor();
+        }
+    }
+
     fn crafting(&mut self, open: bool) {
         if !self.esc_menu {
             if !self.crafting && open {
@@ -1049,6 +1063,7 @@ impl Show {
         if !self.esc_menu {
             self.social = false;
             self.quest = false;
+            self.quest_log = false;
             self.crafting = false;
             self.crafting_fields.salvage = false;
             self.bag = false;
@@ -1069,6 +1084,7 @@ impl Show {
             self.bag = false;
             self.social = false;
             self.quest = false;
+            self.quest_log = false;
             self.crafting = false;
             self.crafting_fields.salvage = false;
             self.diary = false;
@@ -1082,6 +1098,8 @@ impl Show {
 
     fn toggle_social(&mut self) { self.social(!self.social); }
 
+    fn toggle_quest_log(&mut self) { self.quest_log(!self.quest_log); }
+
     fn toggle_crafting(&mut self) { self.crafting(!self.crafting) }
 
     fn toggle_diary(&mut self) { self.diary(!self.diary) }
@@ -1119,6 +1137,7 @@ impl Show {
             || self.diary
             || self.intro
             || self.quest
+            || self.quest_log
             || !matches!(self.open_windows, Windows::None)
     }
 
@@ -1131,6 +1150,7 @@ impl Show {
             self.map = false;
             self.social = false;
             self.quest = false;
+            self.quest_log = false;
             self.diary = false;
             self.crafting = false;
             self.open_windows = Windows::None;
@@ -1425,6 +1445,7 @@ impl Hud {
                 group: false,
                 // Change this before implementation!
                 quest: false,
+                quest_log: false,
                 group_menu: false,
                 chat_tab_settings_index: None,
                 settings_tab: SettingsTab::Interface,
@@ -3820,6 +3841,37 @@ impl Hud {
             false
         };
 
+        if self.show.quest_log {
+            for event in QuestLog::new(
+                client,
+                &self.imgs,
+                &self.fonts,
+                i18n,
+                &self.item_imgs,
+                self.pulse,
+            )
+            .set(self.ids.quest_log_window, ui_widgets)
+            {
+                match event {
+                    quest::LogEvent::Track(quest_id, track) => {
+                        events.push(Event::TrackQuest(quest_id, track));
+                    },
+                    quest::LogEvent::Abandon(quest_id) => {
+                        events.push(Event::AbandonQuest(quest_id));
+                    },
+                    quest::LogEvent::Close => {
+                        self.show.quest_log(false);
+                        if !self.show.bag {
+                            self.show.want_grab = true;
+                            self.force_ungrab = false;
+                        } else {
+                            self.force_ungrab = true
+                        };
+                    },
+                }
+            }
+        }
+
         Tutorial::new(
             &self.show,
             client,
@@ -5022,6 +5074,10 @@ impl Hud {
                         self.show.toggle_social();
                         true
                     },
+                    GameInput::QuestLog if state => {
+                        self.show.toggle_quest_log();
+                        true
+                    },
                     GameInput::Crafting if state => {
                         global_state.profile.tutorial.event_open_crafting();
                         self.show.toggle_crafting();
@@ -5184,6 +5240,13 @@ impl Hud {
                     < em.recv_pos.distance(em.marker.wpos) + EXTRA_DISTANCE
             });
         }
+        // Tracked quests keep their markers up to date, so don't show them twice
+        self.extra_markers.retain(|em| {
+            !client
+                .tracked_quests()
+                .filter_map(|quest| quest.marker.as_ref())
+                .any(|marker| marker.is_same(&em.marker))
+        });
 
         // conrod eats tabs. Un-eat a tabstop so tab completion can work
         if self.ui.ui.global_input().events().any(|event| {
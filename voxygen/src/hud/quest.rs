@@ -387,3 +387,286 @@ impl Widget for Quest<'_> {
         event
     }
 }
+
+widget_ids! {
+    pub struct LogIds {
+        bg,
+        title,
+        close,
+        list,
+        scrollbar,
+        empty_txt,
+        frames[],
+        objectives[],
+        details[],
+        reward_icons[],
+        reward_amounts[],
+        track_btns[],
+        abandon_btns[],
+    }
+}
+
+pub struct LogState {
+    ids: LogIds,
+}
+
+/// The quests the character accepted, with their objective and reward
+#[derive(WidgetCommon)]
+pub struct QuestLog<'a> {
+    client: &'a Client,
+    imgs: &'a Imgs,
+    fonts: &'a Fonts,
+    localized_strings: &'a Localization,
+    item_imgs: &'a ItemImgs,
+    pulse: f32,
+
+    #[conrod(common_builder)]
+    common: widget::CommonBuilder,
+}
+
+impl<'a> QuestLog<'a> {
+    pub fn new(
+        client: &'a Client,
+        imgs: &'a Imgs,
+        fonts: &'a Fonts,
+        localized_strings: &'a Localization,
+        item_imgs: &'a ItemImgs,
+        pulse: f32,
+    ) -> Self {
+        Self {
+            client,
+            imgs,
+            fonts,
+            localized_strings,
+            item_imgs,
+            pulse,
+            common: widget::CommonBuilder::default(),
+        }
+    }
+
+    /// Status, giver, destination and time left of a quest
+    fn details(&self, quest: &rtsim::QuestInfo) -> String {
+        let i18n = self.localized_strings;
+        let mut details = vec![i18n.get_msg(match quest.status {
+            rtsim::QuestStatus::Accepted => "hud-quest-status-accepted",
+            rtsim::QuestStatus::Underway => "hud-quest-status-underway",
+            rtsim::QuestStatus::Achieved => "hud-quest-status-achieved",
+            rtsim::QuestStatus::Completed => "hud-quest-status-completed",
+            rtsim::QuestStatus::Failed => "hud-quest-status-failed",
+        })];
+        if let Some(giver) = &quest.giver {
+            details.push(i18n.get_msg_ctx("hud-quest-giver", &i18n::fluent_args! {
+                "name" => giver,
+            }));
+        }
+        if let Some(site) = &quest.target_site {
+            details.push(i18n.get_msg_ctx("hud-quest-site", &i18n::fluent_args! {
+                "place" => site,
+            }));
+        }
+        if let Some(deadline) = quest.deadline
+            && quest.status.is_active()
+        {
+            let minutes = ((deadline.0 - self.client.state().get_time()) / 60.0)
+                .ceil()
+                .max(0.0) as u64;
+            details.push(i18n.get_msg_ctx("hud-quest-deadline", &i18n::fluent_args! {
+                "minutes" => minutes,
+            }));
+        }
+        details.join(" · ")
+    }
+}
+
+pub enum LogEvent {
+    Track(rtsim::QuestId, bool),
+    Abandon(rtsim::QuestId),
+    Close,
+}
+
+impl Widget for QuestLog<'_> {
+    type Event = Vec<LogEvent>;
+    type State = LogState;
+    type Style = ();
+
+    fn init_state(&self, id_gen: widget::id::Generator) -> Self::State {
+        LogState {
+            ids: LogIds::new(id_gen),
+        }
+    }
+
+    fn style(&self) -> Self::Style {}
+
+    fn update(self, args: widget::UpdateArgs<Self>) -> Self::Event {
+        let widget::UpdateArgs { state, ui, .. } = args;
+        let i18n = self.localized_strings;
+        let mut events = Vec::new();
+
+        const BACKGROUND: Color = Color::Rgba(0.0, 0.0, 0.0, 0.85);
+        const ENTRY_HEIGHT: f64 = 112.0;
+
+        Rectangle::fill_with([380.0, 480.0], BACKGROUND)
+            .mid_right_with_margin_on(ui.window, 20.0)
+            .set(state.ids.bg, ui);
+        Text::new(&i18n.get_msg("hud-quest-log-title"))
+            .mid_top_with_margin_on(state.ids.bg, 8.0)
+            .font_id(self.fonts.cyri.conrod_id)
+            .font_size(self.fonts.cyri.scale(20))
+            .color(TEXT_COLOR)
+            .set(state.ids.title, ui);
+        if Button::image(self.imgs.close_btn)
+            .w_h(24.0, 25.0)
+            .hover_image(self.imgs.close_btn_hover)
+            .press_image(self.imgs.close_btn_press)
+            .top_right_with_margins_on(state.ids.bg, 2.0, 4.0)
+            .set(state.ids.close, ui)
+            .was_clicked()
+        {
+            events.push(LogEvent::Close);
+        }
+
+        Rectangle::fill_with([372.0, 436.0], color::TRANSPARENT)
+            .mid_bottom_with_margin_on(state.ids.bg, 4.0)
+            .scroll_kids_vertically()
+            .set(state.ids.list, ui);
+        Scrollbar::y_axis(state.ids.list)
+            .thickness(5.0)
+            .auto_hide(true)
+            .rgba(1.0, 1.0, 1.0, 0.2)
+            .set(state.ids.scrollbar, ui);
+
+        // Quests that still have to be done first, the most recent ones on top
+        let quests = self
+            .client
+            .quests()
+            .iter()
+            .rev()
+            .filter(|quest| quest.status.is_active())
+            .chain(
+                self.client
+                    .quests()
+                    .iter()
+                    .rev()
+                    .filter(|quest| !quest.status.is_active()),
+            )
+            .collect::<Vec<_>>();
+
+        if quests.is_empty() {
+            Text::new(&i18n.get_msg("hud-quest-log-empty"))
+                .mid_top_with_margin_on(state.ids.list, 16.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(14))
+                .color(TEXT_COLOR)
+                .set(state.ids.empty_txt, ui);
+            return events;
+        }
+
+        let n = quests.len();
+        if state.ids.frames.len() < n {
+            state.update(|s| {
+                let id_gen = &mut ui.widget_id_generator();
+                s.ids.frames.resize(n, id_gen);
+                s.ids.objectives.resize(n, id_gen);
+                s.ids.details.resize(n, id_gen);
+                s.ids.reward_icons.resize(n, id_gen);
+                s.ids.reward_amounts.resize(n, id_gen);
+                s.ids.track_btns.resize(n, id_gen);
+                s.ids.abandon_btns.resize(n, id_gen);
+            });
+        }
+
+        for (i, quest) in quests.into_iter().enumerate() {
+            let active = quest.status.is_active();
+            let text_color = if active {
+                TEXT_COLOR
+            } else {
+                Color::Rgba(1.0, 1.0, 1.0, 0.5)
+            };
+
+            let frame =
+                Rectangle::fill_with([364.0, ENTRY_HEIGHT], Color::Rgba(1.0, 1.0, 1.0, 0.05))
+                    .parent(state.ids.list);
+            let frame = if i == 0 {
+                frame.top_left_with_margins_on(state.ids.list, 0.0, 0.0)
+            } else {
+                frame.down_from(state.ids.frames[i - 1], 4.0)
+            };
+            frame.set(state.ids.frames[i], ui);
+
+            Text::new(&i18n.get_content(&quest.objective))
+                .top_left_with_margins_on(state.ids.frames[i], 6.0, 8.0)
+                .w(348.0)
+                .wrap_by_word()
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(14))
+                .color(text_color)
+                .set(state.ids.objectives[i], ui);
+            Text::new(&self.details(quest))
+                .down_from(state.ids.objectives[i], 4.0)
+                .w(348.0)
+                .wrap_by_word()
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(12))
+                .color(Color::Rgba(0.8, 0.8, 0.8, if active { 1.0 } else { 0.5 }))
+                .set(state.ids.details[i], ui);
+
+            if let Some((item, amount)) = &quest.reward {
+                Image::new(animate_by_pulse(
+                    &self
+                        .item_imgs
+                        .img_ids_or_not_found_img(ItemKey::from(&**item)),
+                    self.pulse,
+                ))
+                .bottom_left_with_margins_on(state.ids.frames[i], 6.0, 8.0)
+                .w_h(20.0, 20.0)
+                .set(state.ids.reward_icons[i], ui);
+                Text::new(&format!("x{amount}"))
+                    .right_from(state.ids.reward_icons[i], 4.0)
+                    .font_id(self.fonts.cyri.conrod_id)
+                    .font_size(self.fonts.cyri.scale(12))
+                    .color(text_color)
+                    .set(state.ids.reward_amounts[i], ui);
+            }
+
+            if !active {
+                continue;
+            }
+
+            let tracked = self.client.is_quest_tracked(quest.id);
+            if Button::image(self.imgs.button)
+                .w_h(90.0, 24.0)
+                .hover_image(self.imgs.button_hover)
+                .press_image(self.imgs.button_press)
+                .bottom_right_with_margins_on(state.ids.frames[i], 6.0, 8.0)
+                .label(&i18n.get_msg(if tracked {
+                    "hud-quest-untrack"
+                } else {
+                    "hud-quest-track"
+                }))
+                .label_font_id(self.fonts.cyri.conrod_id)
+                .label_font_size(self.fonts.cyri.scale(12))
+                .label_color(TEXT_COLOR)
+                .set(state.ids.track_btns[i], ui)
+                .was_clicked()
+            {
+                events.push(LogEvent::Track(quest.id, !tracked));
+            }
+            if Button::image(self.imgs.button)
+                .w_h(90.0, 24.0)
+                .hover_image(self.imgs.button_hover)
+                .press_image(self.imgs.button_press)
+                .left_from(state.ids.track_btns[i], 6.0)
+                .label(&i18n.get_msg("hud-quest-abandon"))
+                .label_font_id(self.fonts.cyri.conrod_id)
+                .label_font_size(self.fonts.cyri.scale(12))
+                .label_color(TEXT_COLOR)
+                .set(state.ids.abandon_btns[i], ui)
+                .was_clicked()
+            {
+                events.push(LogEvent::Abandon(quest.id));
+            }
+        }
+
+        events
+    }
+}
@@ -1,5 +1,5 @@
 use client::{
-    Client, ClientInitStage, ServerInfo,
+    Client, ClientInitStage, NetworkConditions, ServerInfo,
     addr::ConnectionArgs,
     error::{Error as ClientError, NetworkConnectError, NetworkError},
 };
@@ -53,6 +53,7 @@ impl ClientInit {
         locale: Option<String>,
         config_dir: &Path,
         client_type: ClientType,
+        network_conditions: NetworkConditions,
     ) -> Self {
         let (tx, rx) = unbounded();
         let (trust_tx, trust_rx) = unbounded();
@@ -94,6 +95,7 @@ impl ClientInit {
                     crate::ecs::sys::add_local_systems,
                     config_dir.clone(),
                     client_type,
+                    network_conditions,
                 )
                 .await
                 {
@@ -13,7 +13,7 @@ use crate::{
 };
 use chrono::{DateTime, Local, Utc};
 use client::{
-    Client, ClientInitStage, ServerInfo,
+    Client, ClientInitStage, NetworkConditions, ServerInfo,
     addr::ConnectionArgs,
     error::{InitProtocolError, NetworkConnectError, NetworkError},
 };
@@ -30,7 +30,7 @@ use server::ServerInitStage;
 use specs::WorldExt;
 use std::{cell::RefCell, path::Path, rc::Rc, sync::Arc};
 use tokio::runtime;
-use tracing::error;
+use tracing::{error, warn};
 use ui::{Event as MainMenuEvent, MainMenuUi};
 
 pub use ui::rand_bg_image_spec;
@@ -137,6 +137,10 @@ impl PlayState for MainMenuState {
                             &global_state.i18n,
                             &global_state.config_dir,
                             global_state.args.client_type.0,
+                            global_state
+                                .settings
+                                .networking
+                                .simulated_network_conditions,
                         );
                     },
                     Ok(Err(e)) => {
@@ -411,6 +415,14 @@ impl PlayState for MainMenuState {
                     let use_srv = net_settings.use_srv;
                     let use_quic = net_settings.use_quic;
                     let validate_tls = net_settings.validate_tls;
+                    let server_key = net_settings
+                        .server_keys
+                        .get(&server_address)
+                        .and_then(|key| {
+                            key.parse::<client::StaticPublicKey>()
+                                .inspect_err(|e| warn!(?e, ?key, "Invalid server key"))
+                                .ok()
+                        });
                     net_settings.username.clone_from(&username);
                     net_settings.default_server.clone_from(&server_address);
                     if !server_address.is_empty() && !net_settings.servers.contains(&server_address)
@@ -427,6 +439,7 @@ impl PlayState for MainMenuState {
                             prefer_ipv6: false,
                             validate_tls,
                             use_quic,
+                            server_key,
                         }
                     } else if use_quic {
                         ConnectionArgs::Quic {
@@ -438,6 +451,7 @@ impl PlayState for MainMenuState {
                         ConnectionArgs::Tcp {
                             hostname: server_address,
                             prefer_ipv6: false,
+                            server_key,
                         }
                     };
                     attempt_login(
@@ -455,6 +469,10 @@ impl PlayState for MainMenuState {
                         &global_state.i18n,
                         &global_state.config_dir,
                         global_state.args.client_type.0,
+                        global_state
+                            .settings
+                            .networking
+                            .simulated_network_conditions,
                     );
                 },
                 MainMenuEvent::CancelLoginAttempt => {
@@ -724,6 +742,7 @@ fn attempt_login(
     localized_strings: &LocalizationHandle,
     config_dir: &Path,
     client_type: ClientType,
+    network_conditions: NetworkConditions,
 ) {
     let localization = localized_strings.read();
     if let Err(err) = comp::Player::alias_validate(&username) {
@@ -758,6 +777,7 @@ fn attempt_login(
             locale,
             config_dir,
             client_type,
+            network_conditions,
         ));
     }
 }
@@ -2247,6 +2247,12 @@ impl PlayState for SessionState {
                     HudEvent::Dialogue(target, dialogue) => {
                         self.client.borrow_mut().perform_dialogue(target, dialogue);
                     },
+                    HudEvent::TrackQuest(quest_id, track) => {
+                        self.client.borrow_mut().track_quest(quest_id, track);
+                    },
+                    HudEvent::AbandonQuest(quest_id) => {
+                        self.client.borrow_mut().abandon_quest(quest_id);
+                    },
                     HudEvent::SetBattleMode(mode) => {
                         self.client.borrow_mut().set_battle_mode(mode);
                     },
@@ -164,6 +164,7 @@ impl ControlSettings {
             GameInput::Inventory => char("I"),
             GameInput::Trade => char("T"),
             GameInput::Social => char("O"),
+            GameInput::QuestLog => char("U"),
             GameInput::Crafting => char("C"),
             GameInput::Diary => char("P"),
             GameInput::Settings => Key::Named(NamedKey::F10),
@@ -452,6 +452,7 @@ impl ControllerSettings {
             GameInput::Inventory => Some(Button::Simple(GilButton::DPadRight)),
             GameInput::Trade => Some(Button::Simple(GilButton::Unknown)),
             GameInput::Social => Some(Button::Simple(GilButton::DPadLeft)),
+            GameInput::QuestLog => Some(Button::Simple(GilButton::Unknown)),
             GameInput::Crafting => Some(Button::Simple(GilButton::Unknown)),
             GameInput::Diary => Some(Button::Simple(GilButton::Unknown)),
             GameInput::Settings => Some(Button::Simple(GilButton::Unknown)),
@@ -716,6 +717,11 @@ impl ControllerSettings {
                 mod1: Button::Simple(GilButton::Unknown),
                 mod2: Button::Simple(GilButton::Unknown),
             }),
+            GameInput::QuestLog => Some(LayerEntry {
+                button: Button::Simple(GilButton::Unknown),
+                mod1: Button::Simple(GilButton::Unknown),
+                mod2: Button::Simple(GilButton::Unknown),
+            }),
             GameInput::Diary => Some(LayerEntry {
                 button: Button::Simple(GilButton::Unknown),
                 mod1: Button::Simple(GilButton::Unknown),
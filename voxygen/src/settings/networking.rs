@@ -1,4 +1,5 @@
-use hashbrown::HashSet;
+use client::NetworkConditions;
+use hashbrown::{HashMap, HashSet};
 use serde::{Deserialize, Serialize};
 
 /// `NetworkingSettings` stores server and networking settings.
@@ -9,12 +10,18 @@ pub struct NetworkingSettings {
     pub servers: Vec<String>,
     pub default_server: String,
     pub trusted_auth_servers: HashSet<String>,
+    /// Public keys of servers by address, in hex as published by the server.
+    /// TCP connections to these servers are encrypted.
+    pub server_keys: HashMap<String, String>,
     pub use_srv: bool,
     pub use_quic: bool,
     pub validate_tls: bool,
     pub player_physics_behavior: bool,
     pub lossy_terrain_compression: bool,
     pub enable_discord_integration: bool,
+    /// Delays, drops or throttles data sent to the server, only meant for
+    /// testing
+    pub simulated_network_conditions: NetworkConditions,
 }
 
 impl Default for NetworkingSettings {
@@ -27,12 +34,14 @@ impl Default for NetworkingSettings {
                 .iter()
                 .map(|s| s.to_string())
                 .collect(),
+            server_keys: HashMap::new(),
             use_srv: true,
             use_quic: false,
             validate_tls: true,
             player_physics_behavior: false,
             lossy_terrain_compression: false,
             enable_discord_integration: true,
+            simulated_network_conditions: NetworkConditions::default(),
         }
     }
 }
@@ -4,7 +4,7 @@ use i18n::LocalizationHandle;
 use rand::seq::IteratorRandom;
 use server::{
     Error as ServerError, Event, Input, Server, ServerInitStage,
-    persistence::{DatabaseSettings, SqlLogMode},
+    persistence::{DatabaseBackend, DatabaseSettings, SqlLogMode},
     settings::server_description::ServerDescription,
 };
 
@@ -127,6 +127,7 @@ impl SingleplayerState {
                                                      * so SQL logging can't be enabled for
                                                      * singleplayer without changing this line
                                                      * manually */
+                backend: DatabaseBackend::Sqlite,
             };
 
             let paused = Arc::new(AtomicBool::new(false));
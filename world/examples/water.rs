@@ -16,7 +16,7 @@ use tracing_subscriber::{
 };
 use vek::*;
 use veloren_world::{
-    CONFIG, ColumnSample, World,
+    ColumnSample, World,
     sim::{self, DEFAULT_WORLD_SEED, WorldOpts, get_horizon_map, sample_pos, sample_wpos},
     util::Sampler,
 };
@@ -114,8 +114,8 @@ fn main() {
                 min: Vec2::zero(),
                 max: map_size_lg.chunks().map(|e| e as i32),
             },
-            CONFIG.sea_level,
-            CONFIG.sea_level + sampler.max_height,
+            sampler.config.sea_level,
+            sampler.config.sea_level + sampler.max_height,
             |posi| {
                 let sample = sampler.get(uniform_idx_as_vec2(map_size_lg, posi)).unwrap();
                 if is_basement {
@@ -140,10 +140,10 @@ fn main() {
     let mut win =
         minifb::Window::new("World Viewer", W, H, minifb::WindowOptions::default()).unwrap();
 
-    let mut focus = Vec3::new(0.0, 0.0, CONFIG.sea_level as f64);
+    let mut focus = Vec3::new(0.0, 0.0, sampler.config.sea_level as f64);
     // Altitude is divided by gain and clamped to [0, 1]; thus, decreasing gain
     // makes smaller differences in altitude appear larger.
-    let mut gain = /*CONFIG.mountain_scale*/sampler.max_height;
+    let mut gain = /*sampler.config.mountain_scale*/sampler.max_height;
     // The Z component during normal calculations is multiplied by gain; thus,
     let mut fov = 1.0;
     let mut scale =
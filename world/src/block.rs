@@ -1,5 +1,5 @@
 use crate::{
-    CONFIG, IndexRef,
+    IndexRef,
     column::{ColumnGen, ColumnSample},
     util::{FastNoise, RandomField, Sampler, SmallCache},
 };
@@ -145,7 +145,10 @@ impl<'a> BlockGen<'a> {
             let over_water = alt < water_level;
             // Water
             if over_water && (wposf.z as f32 - water_level).abs() < ice_depth {
-                Some(Block::new(BlockKind::Ice, CONFIG.ice_color))
+                Some(Block::new(
+                    BlockKind::Ice,
+                    self.column_gen.sim.config.ice_color,
+                ))
             } else if (wposf.z as f32) < water_level {
                 // Ocean
                 Some(water)
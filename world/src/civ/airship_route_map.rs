@@ -1,5 +1,5 @@
 use crate::{
-    CONFIG, Index, IndexRef,
+    Index, IndexRef,
     civ::airship_travel::{
         AirshipDockPlatform, AirshipRoute, AirshipSpawningLocation, Airships, DockNode,
     },
@@ -290,8 +290,8 @@ fn basic_world_pixmap(image_size: &MapSizeLg, index: &Index, sampler: &WorldSim)
             min: Vec2::zero(),
             max: image_size.chunks().map(|e| e as i32),
         },
-        CONFIG.sea_level,
-        CONFIG.sea_level + sampler.max_height,
+        sampler.config.sea_level,
+        sampler.config.sea_level + sampler.max_height,
         |posi| {
             let sample = sampler.get(uniform_idx_as_vec2(*image_size, posi)).unwrap();
 
@@ -9,7 +9,6 @@ pub mod airship_route_map;
 use crate::{
     Index, IndexRef, Land,
     civ::airship_travel::Airships,
-    config::CONFIG,
     sim::WorldSim,
     site::{self, Site as WorldSite, SiteKind, SitesGenMeta, namegen::NameGen},
     util::{DHashMap, NEIGHBORS, attempt, seed_expan},
@@ -501,6 +500,7 @@ impl Civs {
             };
 
             // Flatten ground
+            let sea_level = ctx.sim.config.sea_level;
             if let Some(center_alt) = ctx.sim.get_alt_approx(wpos) {
                 for offs in Spiral2d::new().take(radius.pow(2) as usize) {
                     let pos = site.center + offs;
@@ -520,7 +520,7 @@ impl Civs {
                             // to worry about the case where water_alt is already set to a correct
                             // value higher than alt, since this chunk should have been filtered
                             // out in that case).
-                            chunk.water_alt = CONFIG.sea_level.max(chunk.water_alt + diff);
+                            chunk.water_alt = sea_level.max(chunk.water_alt + diff);
                             chunk.alt += diff;
                             chunk.basement += diff;
                             chunk.rockiness = 0.0;
@@ -919,7 +919,7 @@ impl Civs {
             }
             to_floodfill.push(exploring);
             // Should always be a chunk on the map
-            let biome = ctx.sim.chunks[exploring].get_biome();
+            let biome = ctx.sim.chunks[exploring].get_biome(&ctx.sim.config);
             let mut filled = Vec::new();
 
             while let Some(filling) = to_floodfill.pop() {
@@ -929,7 +929,7 @@ impl Civs {
                     if explored[neighbour] {
                         continue;
                     }
-                    let n_biome = ctx.sim.chunks[neighbour].get_biome();
+                    let n_biome = ctx.sim.chunks[neighbour].get_biome(&ctx.sim.config);
                     if n_biome == biome {
                         to_floodfill.push(neighbour);
                     } else {
@@ -1206,7 +1206,7 @@ impl Civs {
                 (
                     posi,
                     uniform_idx_as_vec2(map_size_lg, posi),
-                    (chunk.alt - CONFIG.sea_level) as u32,
+                    (chunk.alt - ctx.sim.config.sea_level) as u32,
                 )
             })
             .collect::<Vec<(usize, Vec2<i32>, u32)>>();
@@ -1665,6 +1665,7 @@ fn find_site_loc(
 }
 
 fn town_attributes_of_site(loc: Vec2<i32>, sim: &WorldSim) -> Option<TownSiteAttributes> {
+    let config = &sim.config;
     sim.get(loc).map(|chunk| {
         const RESOURCE_RADIUS: i32 = 1;
         let mut river_chunks = 0;
@@ -1692,11 +1693,11 @@ fn town_attributes_of_site(loc: Vec2<i32>, sim: &WorldSim) -> Option<TownSiteAtt
                         if c.tree_density > 0.7 {
                             tree_chunks += 1;
                         }
-                        if c.rockiness < 0.3 && c.temp > CONFIG.snow_temp {
+                        if c.rockiness < 0.3 && c.temp > config.snow_temp {
                             if c.surface_veg > 0.5 {
                                 farmable_chunks += 1;
                             } else {
-                                match c.get_biome() {
+                                match c.get_biome(config) {
                                     common::terrain::BiomeKind::Savannah => {
                                         farmable_needs_irrigation_chunks += 1
                                     },
@@ -1721,18 +1722,18 @@ fn town_attributes_of_site(loc: Vec2<i32>, sim: &WorldSim) -> Option<TownSiteAtt
         let has_river = river_chunks > 1;
         let has_lake = lake_chunks > 1;
         let vegetation_implies_potable_water = chunk.tree_density > 0.4
-            && !matches!(chunk.get_biome(), common::terrain::BiomeKind::Swamp);
+            && !matches!(chunk.get_biome(config), common::terrain::BiomeKind::Swamp);
         let has_many_rocks = chunk.rockiness > 1.2;
-        let warm_or_firewood = chunk.temp > CONFIG.snow_temp || tree_chunks > 2;
+        let warm_or_firewood = chunk.temp > config.snow_temp || tree_chunks > 2;
         let has_potable_water =
             { has_river || (has_lake && chunk.alt > 100.0) || vegetation_implies_potable_water };
         let has_building_materials = tree_chunks > 0
             || rock_chunks > 0
-            || chunk.temp > CONFIG.tropical_temp && (has_river || has_lake);
+            || chunk.temp > config.tropical_temp && (has_river || has_lake);
         let water_rich = lake_chunks + river_chunks > 2;
         let can_grow_rice = water_rich
-            && chunk.humidity + 1.0 > CONFIG.jungle_hum
-            && chunk.temp + 1.0 > CONFIG.tropical_temp;
+            && chunk.humidity + 1.0 > config.jungle_hum
+            && chunk.temp + 1.0 > config.tropical_temp;
         let farming_score = if can_grow_rice {
             farmable_chunks * 2
         } else {
@@ -1825,6 +1826,7 @@ impl fmt::Display for Site {
 
 impl SiteKind {
     pub fn is_suitable_loc(&self, loc: Vec2<i32>, sim: &WorldSim) -> bool {
+        let config = &sim.config;
         let on_land = || -> bool {
             if let Some(chunk) = sim.get(loc) {
                 !chunk.river.is_ocean()
@@ -1832,7 +1834,7 @@ impl SiteKind {
                     && !chunk.river.is_river()
                     && !chunk.is_underwater()
                     && !matches!(
-                        chunk.get_biome(),
+                        chunk.get_biome(config),
                         common::terrain::BiomeKind::Lake | common::terrain::BiomeKind::Ocean
                     )
             } else {
@@ -1866,7 +1868,7 @@ impl SiteKind {
                 },
                 SiteKind::Adlet => chunk.temp < -0.2 && chunk.cliff_height > 25.0,
                 SiteKind::DwarvenMine => {
-                    matches!(chunk.get_biome(), BiomeKind::Forest | BiomeKind::Desert)
+                    matches!(chunk.get_biome(config), BiomeKind::Forest | BiomeKind::Desert)
                         && !chunk.near_cliffs()
                         && !chunk.river.near_water()
                         && on_flat_terrain()
@@ -1884,7 +1886,7 @@ impl SiteKind {
                 },
                 SiteKind::Citadel => true,
                 SiteKind::CliffTown => {
-                    chunk.temp >= CONFIG.desert_temp
+                    chunk.temp >= config.desert_temp
                         && chunk.cliff_height > 40.0
                         && chunk.rockiness > 1.2
                         && suitable_for_town()
@@ -1893,24 +1895,24 @@ impl SiteKind {
                     chunk.alt > 1400.0
                 },
                 SiteKind::SavannahTown => {
-                    matches!(chunk.get_biome(), BiomeKind::Savannah)
+                    matches!(chunk.get_biome(config), BiomeKind::Savannah)
                         && !chunk.near_cliffs()
                         && !chunk.river.near_water()
                         && suitable_for_town()
                 },
                 SiteKind::CoastalTown => {
-                    (2.0..3.5).contains(&(chunk.water_alt - CONFIG.sea_level))
+                    (2.0..3.5).contains(&(chunk.water_alt - config.sea_level))
                         && suitable_for_town()
                 },
                 SiteKind::PirateHideout => {
-                    (0.5..3.5).contains(&(chunk.water_alt - CONFIG.sea_level))
+                    (0.5..3.5).contains(&(chunk.water_alt - config.sea_level))
                 },
                 SiteKind::Sahagin => {
-                    matches!(chunk.get_biome(), BiomeKind::Ocean)
-                    && (40.0..45.0).contains(&(CONFIG.sea_level - chunk.alt))
+                    matches!(chunk.get_biome(config), BiomeKind::Ocean)
+                    && (40.0..45.0).contains(&(config.sea_level - chunk.alt))
                 },
                 SiteKind::JungleRuin => {
-                    matches!(chunk.get_biome(), BiomeKind::Jungle)
+                    matches!(chunk.get_biome(config), BiomeKind::Jungle)
                 },
                 SiteKind::RockCircle => !chunk.near_cliffs() && !chunk.river.near_water(),
                 SiteKind::TrollCave => {
@@ -1928,13 +1930,13 @@ impl SiteKind {
                         && !chunk.river.near_water()
                 },
                 SiteKind::ChapelSite => {
-                    matches!(chunk.get_biome(), BiomeKind::Ocean)
-                        && CONFIG.sea_level < chunk.alt + 1.0
+                    matches!(chunk.get_biome(config), BiomeKind::Ocean)
+                        && config.sea_level < chunk.alt + 1.0
                 },
                 SiteKind::Terracotta => {
                     (0.9..1.0).contains(&chunk.temp)
                         && on_land()
-                        && (chunk.water_alt - CONFIG.sea_level) > 50.0
+                        && (chunk.water_alt - config.sea_level) > 50.0
                         && on_flat_terrain()
                         && !chunk.river.near_water()
                         && !chunk.near_cliffs()
@@ -1942,7 +1944,7 @@ impl SiteKind {
                 SiteKind::Myrmidon => {
                     (0.9..1.0).contains(&chunk.temp)
                         && on_land()
-                        && (chunk.water_alt - CONFIG.sea_level) > 50.0
+                        && (chunk.water_alt - config.sea_level) > 50.0
                         && on_flat_terrain()
                         && !chunk.river.near_water()
                         && !chunk.near_cliffs()
@@ -1,5 +1,5 @@
 use crate::{
-    CONFIG, IndexRef,
+    IndexRef,
     all::ForestKind,
     sim::{Path, RiverKind, SimChunk, WorldSim, local_cells},
     site::SpawnRules,
@@ -73,6 +73,7 @@ impl<'a> Sampler<'a> for ColumnGen<'a> {
         let chunk_pos = wpos.wpos_to_cpos();
 
         let sim = &self.sim;
+        let config = &sim.config;
 
         // let turb = Vec2::new(
         //     sim.gen_ctx.turb_x_nz.get((wposf.div(48.0)).into_array()) as f32,
@@ -375,7 +376,7 @@ impl<'a> Sampler<'a> for ColumnGen<'a> {
             })
             .collect::<Vec<_>>();
 
-        debug_assert!(sim_chunk.water_alt >= CONFIG.sea_level);
+        debug_assert!(sim_chunk.water_alt >= config.sea_level);
 
         /// A type that makes managing surface altitude weighting much simpler.
         #[derive(Default)]
@@ -467,7 +468,7 @@ impl<'a> Sampler<'a> for ColumnGen<'a> {
         }
 
         // Use this to temporarily alter the sea level
-        let base_sea_level = CONFIG.sea_level - 1.0 + 0.01;
+        let base_sea_level = config.sea_level - 1.0 + 0.01;
 
         // What's going on here?
         //
@@ -988,14 +989,14 @@ impl<'a> Sampler<'a> for ColumnGen<'a> {
             Lerp::lerp(
                 dead_tundra,
                 sand,
-                temp.sub(CONFIG.snow_temp)
-                    .div(CONFIG.desert_temp.sub(CONFIG.snow_temp))
+                temp.sub(config.snow_temp)
+                    .div(config.desert_temp.sub(config.snow_temp))
                     .mul(0.5),
             ),
             dirt,
             humidity
-                .sub(CONFIG.desert_hum)
-                .div(CONFIG.forest_hum.sub(CONFIG.desert_hum))
+                .sub(config.desert_hum)
+                .div(config.forest_hum.sub(config.desert_hum))
                 .mul(1.0),
         );
 
@@ -1012,33 +1013,33 @@ impl<'a> Sampler<'a> for ColumnGen<'a> {
                             tundra,
                             // snow_temp to temperate_temp
                             dirt,
-                            temp.sub(CONFIG.snow_temp)
-                                .div(CONFIG.temperate_temp.sub(CONFIG.snow_temp))
+                            temp.sub(config.snow_temp)
+                                .div(config.temperate_temp.sub(config.snow_temp))
                                 /*.sub((marble - 0.5) * 0.05)
                                 .mul(256.0)*/
                                 .mul(1.0),
                         ),
                         // temperate_temp to tropical_temp
                         grass,
-                        temp.sub(CONFIG.temperate_temp)
-                            .div(CONFIG.tropical_temp.sub(CONFIG.temperate_temp))
+                        temp.sub(config.temperate_temp)
+                            .div(config.tropical_temp.sub(config.temperate_temp))
                             .mul(4.0),
                     ),
                     // tropical_temp to desert_temp
                     moss,
-                    temp.sub(CONFIG.tropical_temp)
-                        .div(CONFIG.desert_temp.sub(CONFIG.tropical_temp))
+                    temp.sub(config.tropical_temp)
+                        .div(config.desert_temp.sub(config.tropical_temp))
                         .mul(1.0),
                 ),
                 // above desert_temp
                 sand,
-                temp.sub(CONFIG.desert_temp)
-                    .div(1.0 - CONFIG.desert_temp)
+                temp.sub(config.desert_temp)
+                    .div(1.0 - config.desert_temp)
                     .mul(4.0),
             ),
             humidity
-                .sub(CONFIG.desert_hum)
-                .div(CONFIG.forest_hum.sub(CONFIG.desert_hum))
+                .sub(config.desert_hum)
+                .div(config.forest_hum.sub(config.desert_hum))
                 .mul(1.25),
         );
         // From forest to jungle humidity, we go from snow to dark grass to grass to
@@ -1051,25 +1052,25 @@ impl<'a> Sampler<'a> for ColumnGen<'a> {
                         snow_moss,
                         // temperate_temp to tropical_temp
                         grass,
-                        temp.sub(CONFIG.temperate_temp)
-                            .div(CONFIG.tropical_temp.sub(CONFIG.temperate_temp))
+                        temp.sub(config.temperate_temp)
+                            .div(config.tropical_temp.sub(config.temperate_temp))
                             .mul(4.0),
                     ),
                     // tropical_temp to desert_temp
                     tropical,
-                    temp.sub(CONFIG.tropical_temp)
-                        .div(CONFIG.desert_temp.sub(CONFIG.tropical_temp))
+                    temp.sub(config.tropical_temp)
+                        .div(config.desert_temp.sub(config.tropical_temp))
                         .mul(1.0),
                 ),
                 // above desert_temp
                 sand,
-                temp.sub(CONFIG.desert_temp)
-                    .div(1.0 - CONFIG.desert_temp)
+                temp.sub(config.desert_temp)
+                    .div(1.0 - config.desert_temp)
                     .mul(4.0),
             ),
             humidity
-                .sub(CONFIG.forest_hum)
-                .div(CONFIG.jungle_hum.sub(CONFIG.forest_hum))
+                .sub(config.forest_hum)
+                .div(config.jungle_hum.sub(config.forest_hum))
                 .mul(1.0),
         );
         // From jungle humidity upwards, we go from snow to grass to rainforest to
@@ -1082,34 +1083,34 @@ impl<'a> Sampler<'a> for ColumnGen<'a> {
                         snow_moss,
                         // temperate_temp to tropical_temp
                         rainforest,
-                        temp.sub(CONFIG.temperate_temp)
-                            .div(CONFIG.tropical_temp.sub(CONFIG.temperate_temp))
+                        temp.sub(config.temperate_temp)
+                            .div(config.tropical_temp.sub(config.temperate_temp))
                             .mul(4.0),
                     ),
                     // tropical_temp to desert_temp
                     tropical,
-                    temp.sub(CONFIG.tropical_temp)
-                        .div(CONFIG.desert_temp.sub(CONFIG.tropical_temp))
+                    temp.sub(config.tropical_temp)
+                        .div(config.desert_temp.sub(config.tropical_temp))
                         .mul(4.0),
                 ),
                 // above desert_temp
                 sand,
-                temp.sub(CONFIG.desert_temp)
-                    .div(1.0 - CONFIG.desert_temp)
+                temp.sub(config.desert_temp)
+                    .div(1.0 - config.desert_temp)
                     .mul(4.0),
             ),
-            humidity.sub(CONFIG.jungle_hum).mul(1.0),
+            humidity.sub(config.jungle_hum).mul(1.0),
         );
 
         // Snow covering
         let thematic_snow = calendar.is_some_and(|c| c.is_event(CalendarEvent::Christmas));
         let snow_factor = temp
             .sub(if thematic_snow {
-                CONFIG.tropical_temp
+                config.tropical_temp
             } else {
-                CONFIG.snow_temp
+                config.snow_temp
             })
-            .max(-humidity.sub(CONFIG.desert_hum))
+            .max(-humidity.sub(config.desert_hum))
             .mul(4.0)
             .max(-0.25)
             // 'Simulate' avalanches moving snow from areas with high gradients to areas with high flux
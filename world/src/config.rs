@@ -1,8 +1,14 @@
 use common::assets::{BoxedError, FileAsset, load_ron};
-use serde::Deserialize;
-use std::borrow::Cow;
+use serde::{Deserialize, Serialize};
+use std::{borrow::Cow, path::Path};
 use vek::*;
 
+/// Climate and terrain parameters of a world. Servers can change them with a
+/// RON file referenced by [`GenOpts::config`](crate::sim::GenOpts::config),
+/// e.g. to generate arctic-only worlds or archipelagos. Fields missing from
+/// the file keep their default.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
 pub struct Config {
     pub sea_level: f32,
     pub mountain_scale: f32,
@@ -57,26 +63,34 @@ pub struct Config {
     pub ice_color: Rgb<u8>,
 }
 
-pub const CONFIG: Config = Config {
-    sea_level: 140.0,
-    mountain_scale: 2048.0,
-    // temperature
-    snow_temp: -0.8,
-    temperate_temp: -0.4,
-    tropical_temp: 0.4,
-    desert_temp: 0.8,
-    // humidity
-    desert_hum: 0.15,
-    forest_hum: 0.5,
-    jungle_hum: 0.75,
-    // water
-    rainfall_chunk_rate: 1.0 / (512.0 * 32.0 * 32.0),
-    river_roughness: 0.06125,
-    river_max_width: 2.0,
-    river_min_height: 0.25,
-    river_width_to_depth: 8.0,
-    ice_color: Rgb::new(140, 175, 255),
-};
+impl Config {
+    pub const DEFAULT: Self = Self {
+        sea_level: 140.0,
+        mountain_scale: 2048.0,
+        // temperature
+        snow_temp: -0.8,
+        temperate_temp: -0.4,
+        tropical_temp: 0.4,
+        desert_temp: 0.8,
+        // humidity
+        desert_hum: 0.15,
+        forest_hum: 0.5,
+        jungle_hum: 0.75,
+        // water
+        rainfall_chunk_rate: 1.0 / (512.0 * 32.0 * 32.0),
+        river_roughness: 0.06125,
+        river_max_width: 2.0,
+        river_min_height: 0.25,
+        river_width_to_depth: 8.0,
+        ice_color: Rgb::new(140, 175, 255),
+    };
+
+    pub fn load(path: &Path) -> Result<Self, BoxedError> { load_ron(&std::fs::read(path)?) }
+}
+
+impl Default for Config {
+    fn default() -> Self { Self::DEFAULT }
+}
 
 #[derive(Deserialize)]
 pub struct Features {
@@ -100,3 +114,50 @@ impl FileAsset for Features {
 
     fn from_bytes(bytes: Cow<[u8]>) -> Result<Self, BoxedError> { load_ron(&bytes) }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs, path::PathBuf};
+
+    fn config_file(name: &str, ron: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("veloren-world-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        fs::write(&path, ron).unwrap();
+        path
+    }
+
+    #[test]
+    fn ron_round_trip() {
+        let config = Config {
+            sea_level: 80.0,
+            snow_temp: 0.2,
+            jungle_hum: 0.9,
+            ice_color: Rgb::new(1, 2, 3),
+            ..Config::DEFAULT
+        };
+        let path = config_file("round_trip.ron", &ron::to_string(&config).unwrap());
+        assert_eq!(Config::load(&path).unwrap(), config);
+    }
+
+    #[test]
+    fn missing_fields_are_default() {
+        let path = config_file("partial.ron", "(sea_level: 80.0, desert_temp: 0.5)");
+        assert_eq!(Config::load(&path).unwrap(), Config {
+            sea_level: 80.0,
+            desert_temp: 0.5,
+            ..Config::DEFAULT
+        });
+
+        let path = config_file("empty.ron", "()");
+        assert_eq!(Config::load(&path).unwrap(), Config::DEFAULT);
+    }
+
+    #[test]
+    fn invalid_files_are_errors() {
+        let path = config_file("invalid.ron", "(sea_level: \"deep\")");
+        assert!(Config::load(&path).is_err());
+        assert!(Config::load(&path.with_file_name("missing.ron")).is_err());
+    }
+}
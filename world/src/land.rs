@@ -2,6 +2,7 @@ use crate::{
     ColumnSample, IndexRef,
     all::ForestKind,
     column::ColumnGen,
+    config::Config,
     sim::{self, SimChunk},
     util::Sampler,
 };
@@ -21,6 +22,9 @@ impl<'a> Land<'a> {
 
     pub fn from_sim(sim: &'a sim::WorldSim) -> Self { Self { sim: Some(sim) } }
 
+    /// The climate and terrain parameters of the world.
+    pub fn config(&self) -> &'a Config { self.sim.map_or(&Config::DEFAULT, |sim| &sim.config) }
+
     pub fn get_interpolated<T>(&self, wpos: Vec2<i32>, f: impl FnMut(&SimChunk) -> T) -> T
     where
         T: Copy + Default + std::ops::Add<Output = T> + std::ops::Mul<f32, Output = T>,
@@ -14,7 +14,6 @@ pub use self::{
 use crate::{
     Canvas, CanvasInfo,
     column::ColumnSample,
-    config::CONFIG,
     sim,
     util::{FastNoise, RandomPerm, Sampler},
 };
@@ -290,7 +289,7 @@ pub fn apply_caverns_to<R: Rng>(canvas: &mut Canvas, dynamic_rng: &mut R) {
         );
 
         let cavern_avg_alt =
-            CONFIG.sea_level.min(alt * 0.25) - height_range.end - surface_clearance;
+            info.chunks().config.sea_level.min(alt * 0.25) - height_range.end - surface_clearance;
 
         let cavern = canvern_nz_at(wpos2d);
         let cavern_height = cavern * cavern_avg_height;
@@ -1,6 +1,7 @@
 use crate::{
-    CONFIG, Canvas,
+    Canvas,
     column::ColumnSample,
+    config::Config,
     sim::SimChunk,
     util::{RandomField, close},
 };
@@ -46,7 +47,7 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
         kind: SpriteKind,
         water_mode: WaterMode,
         permit: fn(BlockKind) -> bool,
-        f: fn(&SimChunk, &ColumnSample) -> (f32, Option<(f32, f32, f32)>),
+        f: fn(&Config, &SimChunk, &ColumnSample) -> (f32, Option<(f32, f32, f32)>),
     }
 
     // TODO: Add back all sprites we had before
@@ -57,11 +58,11 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: BlueFlower,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, CONFIG.temperate_temp, 0.7).min(close(
+                    close(col.temp, config.temperate_temp, 0.7).min(close(
                         col.humidity,
-                        CONFIG.jungle_hum,
+                        config.jungle_hum,
                         0.4,
                     )) * col.tree_density
                         * MUSH_FACT
@@ -74,9 +75,9 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: PinkFlower,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, 0.0, 0.7).min(close(col.humidity, CONFIG.jungle_hum, 0.4))
+                    close(col.temp, 0.0, 0.7).min(close(col.humidity, config.jungle_hum, 0.4))
                         * col.tree_density
                         * MUSH_FACT
                         * 350.0,
@@ -88,13 +89,13 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: PurpleFlower,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass | BlockKind::Snow),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, CONFIG.temperate_temp, 0.7)
-                        .max(close(col.temp, CONFIG.snow_temp, 0.7))
-                        .min(close(col.humidity, CONFIG.jungle_hum, 0.4).max(close(
+                    close(col.temp, config.temperate_temp, 0.7)
+                        .max(close(col.temp, config.snow_temp, 0.7))
+                        .min(close(col.humidity, config.jungle_hum, 0.4).max(close(
                             col.humidity,
-                            CONFIG.forest_hum,
+                            config.forest_hum,
                             0.5,
                         )))
                         * col.tree_density
@@ -108,13 +109,13 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: RedFlower,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass | BlockKind::Snow),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, CONFIG.tropical_temp, 0.7)
-                        .max(close(col.temp, CONFIG.snow_temp, 0.7))
-                        .min(close(col.humidity, CONFIG.jungle_hum, 0.4).max(close(
+                    close(col.temp, config.tropical_temp, 0.7)
+                        .max(close(col.temp, config.snow_temp, 0.7))
+                        .min(close(col.humidity, config.jungle_hum, 0.4).max(close(
                             col.humidity,
-                            CONFIG.forest_hum,
+                            config.forest_hum,
                             0.5,
                         )))
                         * col.tree_density
@@ -128,9 +129,9 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: WhiteFlower,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, 0.0, 0.7).min(close(col.humidity, CONFIG.jungle_hum, 0.4))
+                    close(col.temp, 0.0, 0.7).min(close(col.humidity, config.jungle_hum, 0.4))
                         * col.tree_density
                         * MUSH_FACT
                         * 350.0,
@@ -142,13 +143,13 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: YellowFlower,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass | BlockKind::Snow),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
                     close(col.temp, 0.0, 0.7)
-                        .max(close(col.temp, CONFIG.snow_temp, 0.7))
-                        .min(close(col.humidity, CONFIG.jungle_hum, 0.4).max(close(
+                        .max(close(col.temp, config.snow_temp, 0.7))
+                        .min(close(col.humidity, config.jungle_hum, 0.4).max(close(
                             col.humidity,
-                            CONFIG.forest_hum,
+                            config.forest_hum,
                             0.5,
                         )))
                         * col.tree_density
@@ -162,11 +163,11 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: Cotton,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Earth | BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, CONFIG.tropical_temp, 0.7).min(close(
+                    close(col.temp, config.tropical_temp, 0.7).min(close(
                         col.humidity,
-                        CONFIG.jungle_hum,
+                        config.jungle_hum,
                         0.4,
                     )) * col.tree_density
                     * MUSH_FACT
@@ -183,9 +184,9 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: Sunflower,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, 0.0, 0.7).min(close(col.humidity, CONFIG.jungle_hum, 0.4))
+                    close(col.temp, 0.0, 0.7).min(close(col.humidity, config.jungle_hum, 0.4))
                         * col.tree_density
                         * MUSH_FACT
                         * 350.0,
@@ -197,11 +198,11 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: WildFlax,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, CONFIG.temperate_temp, 0.7).min(close(
+                    close(col.temp, config.temperate_temp, 0.7).min(close(
                         col.humidity,
-                        CONFIG.forest_hum,
+                        config.forest_hum,
                         0.4,
                     )) * col.tree_density
                         * MUSH_FACT
@@ -218,9 +219,9 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: LingonBerry,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, 0.3, 0.4).min(close(col.humidity, CONFIG.jungle_hum, 0.5))
+                    close(col.temp, 0.3, 0.4).min(close(col.humidity, config.jungle_hum, 0.5))
                         * MUSH_FACT
                         * 2.5,
                     None,
@@ -231,9 +232,9 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: LeafyPlant,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, 0.3, 0.4).min(close(col.humidity, CONFIG.jungle_hum, 0.3))
+                    close(col.temp, 0.3, 0.4).min(close(col.humidity, config.jungle_hum, 0.3))
                         * GRASS_FACT
                         * 4.0,
                     None,
@@ -244,9 +245,9 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: JungleLeafyPlant,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, 0.3, 0.4).min(close(col.humidity, CONFIG.jungle_hum, 0.4))
+                    close(col.temp, 0.3, 0.4).min(close(col.humidity, config.jungle_hum, 0.4))
                         * GRASS_FACT
                         * 32.0,
                     Some((0.15, 64.0, 0.2)),
@@ -257,9 +258,9 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: Fern,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, 0.3, 0.4).min(close(col.humidity, CONFIG.forest_hum, 0.5))
+                    close(col.temp, 0.3, 0.4).min(close(col.humidity, config.forest_hum, 0.5))
                         * GRASS_FACT
                         * 0.25,
                     Some((0.0, 64.0, 0.2)),
@@ -270,9 +271,9 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: JungleFern,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, 0.3, 0.4).min(close(col.humidity, CONFIG.jungle_hum, 0.4))
+                    close(col.temp, 0.3, 0.4).min(close(col.humidity, config.jungle_hum, 0.4))
                         * col.tree_density
                         * MUSH_FACT
                         * 200.0,
@@ -284,11 +285,11 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: Blueberry,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, CONFIG.temperate_temp, 0.5).min(close(
+                    close(col.temp, config.temperate_temp, 0.5).min(close(
                         col.humidity,
-                        CONFIG.forest_hum,
+                        config.forest_hum,
                         0.5,
                     )) * MUSH_FACT
                         * 0.3,
@@ -301,13 +302,13 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
             f: if calendar.is_some_and(|calendar| calendar.is_event(CalendarEvent::Halloween)) {
-                |_, _| (0.1, Some((0.0003, 128.0, 0.1)))
+                |_, _, _| (0.1, Some((0.0003, 128.0, 0.1)))
             } else {
-                |_, col| {
+                |config, _, col| {
                     (
-                        close(col.temp, CONFIG.temperate_temp, 0.5).min(close(
+                        close(col.temp, config.temperate_temp, 0.5).min(close(
                             col.humidity,
-                            CONFIG.forest_hum,
+                            config.forest_hum,
                             0.5,
                         )) * MUSH_FACT
                             * 500.0,
@@ -322,7 +323,7 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: Twigs,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |_, _, col| {
                 (
                     (col.tree_density * 1.25 - 0.25).powf(0.5).max(0.0) * TREE_FACT * 5.0,
                     None,
@@ -334,7 +335,7 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: Wood,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |_, _, col| {
                 (
                     (col.tree_density * 1.25 - 0.25).powf(0.5).max(0.0) * TREE_FACT,
                     None,
@@ -345,11 +346,11 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: Hardwood,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    ((close(col.temp, CONFIG.tropical_temp + 0.1, 0.3).min(close(
+                    ((close(col.temp, config.tropical_temp + 0.1, 0.3).min(close(
                         col.humidity,
-                        CONFIG.jungle_hum,
+                        config.jungle_hum,
                         0.4,
                     )) > 0.0) as i32) as f32
                         * (col.tree_density * 1.25 - 0.25).powf(0.5).max(0.0)
@@ -372,7 +373,7 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: Frostwood,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Snow | BlockKind::Ice),
-            f: |_, col| {
+            f: |_, _, col| {
                 (
                     (col.tree_density * 1.25 - 0.25).powf(0.5).max(0.0) * TREE_FACT * 0.5,
                     None,
@@ -393,7 +394,7 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
                         | BlockKind::Ice
                 )
             },
-            f: |chunk, _| ((chunk.rockiness - 0.5).max(0.025) * 1.0e-3, None),
+            f: |_, chunk, _| ((chunk.rockiness - 0.5).max(0.025) * 1.0e-3, None),
         },
         ScatterConfig {
             kind: Copper,
@@ -404,7 +405,7 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
                     BlockKind::Earth | BlockKind::Grass | BlockKind::Rock | BlockKind::Sand
                 )
             },
-            f: |chunk, _| ((chunk.rockiness - 0.5).max(0.0) * 0.85e-3, None),
+            f: |_, chunk, _| ((chunk.rockiness - 0.5).max(0.0) * 0.85e-3, None),
         },
         ScatterConfig {
             kind: Tin,
@@ -415,16 +416,16 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
                     BlockKind::Earth | BlockKind::Grass | BlockKind::Rock | BlockKind::Sand
                 )
             },
-            f: |chunk, _| ((chunk.rockiness - 0.5).max(0.0) * 0.85e-3, None),
+            f: |_, chunk, _| ((chunk.rockiness - 0.5).max(0.0) * 0.85e-3, None),
         },
         // Don't spawn Mushrooms in snowy regions
         ScatterConfig {
             kind: Mushroom,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, 0.3, 0.4).min(close(col.humidity, CONFIG.forest_hum, 0.35))
+                    close(col.temp, 0.3, 0.4).min(close(col.humidity, config.forest_hum, 0.35))
                         * MUSH_FACT,
                     None,
                 )
@@ -435,9 +436,9 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: ShortGrass,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, 0.2, 0.75).min(close(col.humidity, CONFIG.jungle_hum, 0.4))
+                    close(col.temp, 0.2, 0.75).min(close(col.humidity, config.jungle_hum, 0.4))
                         * GRASS_FACT
                         * 150.0,
                     Some((0.3, 64.0, 0.3)),
@@ -448,11 +449,11 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: ShortGrass,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Snow),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, CONFIG.snow_temp - 0.2, 0.4).min(close(
+                    close(col.temp, config.snow_temp - 0.2, 0.4).min(close(
                         col.humidity,
-                        CONFIG.forest_hum,
+                        config.forest_hum,
                         0.5,
                     )) * GRASS_FACT
                         * 50.0,
@@ -464,9 +465,9 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: MediumGrass,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, 0.2, 0.6).min(close(col.humidity, CONFIG.jungle_hum, 0.4))
+                    close(col.temp, 0.2, 0.6).min(close(col.humidity, config.jungle_hum, 0.4))
                         * GRASS_FACT
                         * 120.0,
                     Some((0.3, 64.0, 0.3)),
@@ -477,9 +478,9 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: LongGrass,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, 0.3, 0.35).min(close(col.humidity, CONFIG.jungle_hum, 0.3))
+                    close(col.temp, 0.3, 0.35).min(close(col.humidity, config.jungle_hum, 0.3))
                         * GRASS_FACT
                         * 150.0,
                     Some((0.1, 48.0, 0.3)),
@@ -490,11 +491,11 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: LongGrass,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Snow),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, CONFIG.snow_temp - 0.2, 0.4).min(close(
+                    close(col.temp, config.snow_temp - 0.2, 0.4).min(close(
                         col.humidity,
-                        CONFIG.forest_hum,
+                        config.forest_hum,
                         0.5,
                     )) * GRASS_FACT
                         * 25.0,
@@ -506,9 +507,9 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: JungleRedGrass,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, 0.3, 0.4).min(close(col.humidity, CONFIG.jungle_hum, 0.4))
+                    close(col.temp, 0.3, 0.4).min(close(col.humidity, config.jungle_hum, 0.4))
                         * col.tree_density
                         * MUSH_FACT
                         * 350.0,
@@ -519,9 +520,9 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
         // Jungle Sprites
         // (LongGrass, Ground, |c, col| {
         //     (
-        //         close(col.temp, CONFIG.tropical_temp, 0.4).min(close(
+        //         close(col.temp, config.tropical_temp, 0.4).min(close(
         //             col.humidity,
-        //             CONFIG.jungle_hum,
+        //             config.jungle_hum,
         //             0.6,
         //         )) * 0.08,
         //         Some((0.0, 60.0, 5.0)),
@@ -529,7 +530,7 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
         // }),
         /*(WheatGreen, Ground, |c, col| {
             (
-                close(col.temp, 0.4, 0.2).min(close(col.humidity, CONFIG.forest_hum, 0.1))
+                close(col.temp, 0.4, 0.2).min(close(col.humidity, config.forest_hum, 0.1))
                     * MUSH_FACT
                     * 0.001,
                 None,
@@ -539,11 +540,11 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: TaigaGrass,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, CONFIG.snow_temp - 0.2, 0.4).min(close(
+                    close(col.temp, config.snow_temp - 0.2, 0.4).min(close(
                         col.humidity,
-                        CONFIG.forest_hum,
+                        config.forest_hum,
                         0.5,
                     )) * GRASS_FACT
                         * 100.0,
@@ -555,11 +556,11 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: Moonbell,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, CONFIG.snow_temp - 0.2, 0.4).min(close(
+                    close(col.temp, config.snow_temp - 0.2, 0.4).min(close(
                         col.humidity,
-                        CONFIG.forest_hum,
+                        config.forest_hum,
                         0.5,
                     )) * 0.003,
                     Some((0.0, 48.0, 0.2)),
@@ -571,7 +572,7 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: SavannaGrass,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |_, _, col| {
                 (
                     {
                         let savanna = close(col.temp, 1.0, 0.4) * close(col.humidity, 0.2, 0.25);
@@ -586,7 +587,7 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: TallSavannaGrass,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |_, _, col| {
                 (
                     {
                         let savanna = close(col.temp, 1.0, 0.4) * close(col.humidity, 0.2, 0.25);
@@ -601,7 +602,7 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: RedSavannaGrass,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |_, _, col| {
                 (
                     {
                         let savanna = close(col.temp, 1.0, 0.4) * close(col.humidity, 0.2, 0.25);
@@ -616,7 +617,7 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: SavannaBush,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |_, _, col| {
                 (
                     {
                         let savanna = close(col.temp, 1.0, 0.4) * close(col.humidity, 0.2, 0.25);
@@ -632,10 +633,10 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: DeadBush,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass | BlockKind::Snow),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
                     close(col.temp, 1.0, 0.95)
-                        .max(close(col.temp, CONFIG.snow_temp, 0.95))
+                        .max(close(col.temp, config.snow_temp, 0.95))
                         .min(close(col.humidity, 0.0, 0.45))
                         * MUSH_FACT
                         * 7.5,
@@ -647,9 +648,9 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: Pyrebloom,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, CONFIG.desert_temp, 0.25).min(close(col.humidity, 0.0, 0.2))
+                    close(col.temp, config.desert_temp, 0.25).min(close(col.humidity, 0.0, 0.2))
                         * MUSH_FACT
                         * 0.1,
                     None,
@@ -660,9 +661,9 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: LargeCactus,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, CONFIG.desert_temp, 0.25).min(close(col.humidity, 0.0, 0.2))
+                    close(col.temp, config.desert_temp, 0.25).min(close(col.humidity, 0.0, 0.2))
                         * MUSH_FACT
                         * 1.5,
                     None,
@@ -673,9 +674,9 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: BarrelCactus,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, CONFIG.desert_temp, 0.25).min(close(col.humidity, 0.0, 0.2))
+                    close(col.temp, config.desert_temp, 0.25).min(close(col.humidity, 0.0, 0.2))
                         * MUSH_FACT
                         * 2.0,
                     None,
@@ -686,9 +687,9 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: TallCactus,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, CONFIG.desert_temp, 0.25).min(close(col.humidity, 0.0, 0.2))
+                    close(col.temp, config.desert_temp, 0.25).min(close(col.humidity, 0.0, 0.2))
                         * MUSH_FACT
                         * 1.5,
                     None,
@@ -699,9 +700,9 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: RoundCactus,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, CONFIG.desert_temp, 0.25).min(close(col.humidity, 0.0, 0.2))
+                    close(col.temp, config.desert_temp, 0.25).min(close(col.humidity, 0.0, 0.2))
                         * MUSH_FACT
                         * 2.0,
                     None,
@@ -712,9 +713,9 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: ShortCactus,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, CONFIG.desert_temp, 0.25).min(close(col.humidity, 0.0, 0.2))
+                    close(col.temp, config.desert_temp, 0.25).min(close(col.humidity, 0.0, 0.2))
                         * MUSH_FACT
                         * 2.0,
                     None,
@@ -725,9 +726,9 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: MedFlatCactus,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, CONFIG.desert_temp, 0.25).min(close(col.humidity, 0.0, 0.2))
+                    close(col.temp, config.desert_temp, 0.25).min(close(col.humidity, 0.0, 0.2))
                         * MUSH_FACT
                         * 2.0,
                     None,
@@ -738,9 +739,9 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: ShortFlatCactus,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, CONFIG.desert_temp, 0.25).min(close(col.humidity, 0.0, 0.2))
+                    close(col.temp, config.desert_temp, 0.25).min(close(col.humidity, 0.0, 0.2))
                         * MUSH_FACT
                         * 2.0,
                     None,
@@ -752,7 +753,7 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: ChestBuried,
             water_mode: Underwater,
             permit: |b| matches!(b, BlockKind::Earth | BlockKind::Sand),
-            f: |_, col| {
+            f: |_, _, col| {
                 (
                     MUSH_FACT
                         * 1.0e-6
@@ -770,7 +771,7 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: Mud,
             water_mode: Underwater,
             permit: |b| matches!(b, BlockKind::Earth | BlockKind::Sand),
-            f: |_, col| {
+            f: |_, _, col| {
                 (
                     MUSH_FACT
                         * 1.0e-3
@@ -788,7 +789,7 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: GrassBlue,
             water_mode: Underwater,
             permit: |b| matches!(b, BlockKind::Grass | BlockKind::Sand),
-            f: |_, col| {
+            f: |_, _, col| {
                 (
                     MUSH_FACT
                         * 250.0
@@ -806,12 +807,12 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: Seagrass,
             water_mode: Underwater,
             permit: |b| matches!(b, BlockKind::Grass | BlockKind::Sand),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, CONFIG.temperate_temp, 0.8)
+                    close(col.temp, config.temperate_temp, 0.8)
                         * MUSH_FACT
                         * 300.0
-                        * if col.water_level <= CONFIG.sea_level
+                        * if col.water_level <= config.sea_level
                             && col.alt < col.water_level - DEPTH_WATER_NORM + 18.0
                         {
                             1.0
@@ -827,11 +828,11 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: Seagrass,
             water_mode: Underwater,
             permit: |b| matches!(b, BlockKind::Grass | BlockKind::Sand),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
                     MUSH_FACT
                         * 600.0
-                        * if col.water_level <= CONFIG.sea_level
+                        * if col.water_level <= config.sea_level
                             && (col.water_level - col.alt) < 3.0
                         {
                             1.0
@@ -847,12 +848,12 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: SeaweedTemperate,
             water_mode: Underwater,
             permit: |b| matches!(b, BlockKind::Grass | BlockKind::Sand),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, CONFIG.temperate_temp, 0.8)
+                    close(col.temp, config.temperate_temp, 0.8)
                         * MUSH_FACT
                         * 50.0
-                        * if col.water_level <= CONFIG.sea_level
+                        * if col.water_level <= config.sea_level
                             && col.alt < col.water_level - DEPTH_WATER_NORM + 11.0
                         {
                             1.0
@@ -868,12 +869,12 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: SeaweedTropical,
             water_mode: Underwater,
             permit: |b| matches!(b, BlockKind::Grass | BlockKind::Sand),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
                     close(col.temp, 1.0, 0.95)
                         * MUSH_FACT
                         * 50.0
-                        * if col.water_level <= CONFIG.sea_level
+                        * if col.water_level <= config.sea_level
                             && col.alt < col.water_level - DEPTH_WATER_NORM + 11.0
                         {
                             1.0
@@ -889,11 +890,11 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: SeaGrapes,
             water_mode: Underwater,
             permit: |b| matches!(b, BlockKind::Earth | BlockKind::Sand),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
                     MUSH_FACT
                         * 250.0
-                        * if col.water_level <= CONFIG.sea_level
+                        * if col.water_level <= config.sea_level
                             && col.alt < col.water_level - DEPTH_WATER_NORM + 10.0
                         {
                             1.0
@@ -909,11 +910,11 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: WavyAlgae,
             water_mode: Underwater,
             permit: |b| matches!(b, BlockKind::Earth | BlockKind::Sand),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
                     MUSH_FACT
                         * 250.0
-                        * if col.water_level <= CONFIG.sea_level
+                        * if col.water_level <= config.sea_level
                             && col.alt < col.water_level - DEPTH_WATER_NORM + 10.0
                         {
                             1.0
@@ -929,12 +930,12 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: MermaidsFan,
             water_mode: Underwater,
             permit: |b| matches!(b, BlockKind::Earth | BlockKind::Sand),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
                     close(col.temp, 1.0, 0.95)
                         * MUSH_FACT
                         * 500.0
-                        * if col.water_level <= CONFIG.sea_level
+                        * if col.water_level <= config.sea_level
                             && col.alt < col.water_level - DEPTH_WATER_NORM + 10.0
                         {
                             1.0
@@ -950,12 +951,12 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: SeaAnemone,
             water_mode: Underwater,
             permit: |b| matches!(b, BlockKind::Earth | BlockKind::Sand),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, CONFIG.temperate_temp, 0.8)
+                    close(col.temp, config.temperate_temp, 0.8)
                         * MUSH_FACT
                         * 125.0
-                        * if col.water_level <= CONFIG.sea_level
+                        * if col.water_level <= config.sea_level
                             && col.alt < col.water_level - DEPTH_WATER_NORM - 9.0
                         {
                             1.0
@@ -971,12 +972,12 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: GiantKelp,
             water_mode: Underwater,
             permit: |b| matches!(b, BlockKind::Earth | BlockKind::Sand),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, CONFIG.temperate_temp, 0.8)
+                    close(col.temp, config.temperate_temp, 0.8)
                         * MUSH_FACT
                         * 220.0
-                        * if col.water_level <= CONFIG.sea_level
+                        * if col.water_level <= config.sea_level
                             && col.alt < col.water_level - DEPTH_WATER_NORM - 9.0
                         {
                             1.0
@@ -992,12 +993,12 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: BullKelp,
             water_mode: Underwater,
             permit: |b| matches!(b, BlockKind::Earth | BlockKind::Sand),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, CONFIG.temperate_temp, 0.7)
+                    close(col.temp, config.temperate_temp, 0.7)
                         * MUSH_FACT
                         * 300.0
-                        * if col.water_level <= CONFIG.sea_level
+                        * if col.water_level <= config.sea_level
                             && col.alt < col.water_level - DEPTH_WATER_NORM + 3.0
                         {
                             1.0
@@ -1013,12 +1014,12 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: StonyCoral,
             water_mode: Underwater,
             permit: |b| matches!(b, BlockKind::Earth | BlockKind::Sand),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
                     close(col.temp, 1.0, 0.9)
                         * MUSH_FACT
                         * 160.0
-                        * if col.water_level <= CONFIG.sea_level
+                        * if col.water_level <= config.sea_level
                             && col.alt < col.water_level - DEPTH_WATER_NORM + 10.0
                         {
                             1.0
@@ -1034,12 +1035,12 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: SoftCoral,
             water_mode: Underwater,
             permit: |b| matches!(b, BlockKind::Earth | BlockKind::Sand),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
                     close(col.temp, 1.0, 0.9)
                         * MUSH_FACT
                         * 120.0
-                        * if col.water_level <= CONFIG.sea_level
+                        * if col.water_level <= config.sea_level
                             && col.alt < col.water_level - DEPTH_WATER_NORM + 10.0
                         {
                             1.0
@@ -1055,11 +1056,11 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: Seashells,
             water_mode: Underwater,
             permit: |b| matches!(b, BlockKind::Earth | BlockKind::Sand),
-            f: |c, col| {
+            f: |config, c, col| {
                 (
                     (c.rockiness - 0.5).max(0.0)
                         * 1.0e-3
-                        * if col.water_level <= CONFIG.sea_level
+                        * if col.water_level <= config.sea_level
                             && col.alt < col.water_level - DEPTH_WATER_NORM + 20.0
                         {
                             1.0
@@ -1074,7 +1075,7 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: Stones,
             water_mode: Underwater,
             permit: |b| matches!(b, BlockKind::Earth | BlockKind::Sand),
-            f: |c, col| {
+            f: |_, c, col| {
                 (
                     (c.rockiness - 0.5).max(0.0)
                         * 1.0e-3
@@ -1092,12 +1093,12 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: LillyPads,
             water_mode: Floating,
             permit: |_| true,
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, 0.2, 0.6).min(close(col.humidity, CONFIG.jungle_hum, 0.4))
+                    close(col.temp, 0.2, 0.6).min(close(col.humidity, config.jungle_hum, 0.4))
                         * GRASS_FACT
                         * 100.0
-                        * ((col.alt - CONFIG.sea_level) / 12.0).clamped(0.0, 1.0)
+                        * ((col.alt - config.sea_level) / 12.0).clamped(0.0, 1.0)
                         * col
                             .water_dist
                             .map_or(0.0, |d| 1.0 / (1.0 + (d.abs() * 0.4).powi(2))),
@@ -1109,12 +1110,12 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: Reed,
             water_mode: Underwater,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.temp, 0.2, 0.6).min(close(col.humidity, CONFIG.jungle_hum, 0.4))
+                    close(col.temp, 0.2, 0.6).min(close(col.humidity, config.jungle_hum, 0.4))
                         * GRASS_FACT
                         * 100.0
-                        * ((col.alt - CONFIG.sea_level) / 12.0).clamped(0.0, 1.0)
+                        * ((col.alt - config.sea_level) / 12.0).clamped(0.0, 1.0)
                         * col
                             .water_dist
                             .map_or(0.0, |d| 1.0 / (1.0 + (d.abs() * 0.40).powi(2))),
@@ -1126,14 +1127,14 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: Reed,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
-                    close(col.humidity, CONFIG.jungle_hum, 0.9)
+                    close(col.humidity, config.jungle_hum, 0.9)
                         * col
                             .water_dist
                             .map(|wd| Lerp::lerp(0.2, 0.0, (wd / 8.0).clamped(0.0, 1.0)))
                             .unwrap_or(0.0)
-                        * ((col.alt - CONFIG.sea_level) / 12.0).clamped(0.0, 1.0),
+                        * ((col.alt - config.sea_level) / 12.0).clamped(0.0, 1.0),
                     Some((0.2, 128.0, 0.5)),
                 )
             },
@@ -1142,15 +1143,15 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
             kind: Bamboo,
             water_mode: Ground,
             permit: |b| matches!(b, BlockKind::Grass),
-            f: |_, col| {
+            f: |config, _, col| {
                 (
                     0.014
-                        * close(col.humidity, CONFIG.jungle_hum, 0.9)
+                        * close(col.humidity, config.jungle_hum, 0.9)
                         * col
                             .water_dist
                             .map(|wd| Lerp::lerp(0.2, 0.0, (wd / 8.0).clamped(0.0, 1.0)))
                             .unwrap_or(0.0)
-                        * ((col.alt - CONFIG.sea_level) / 12.0).clamped(0.0, 1.0),
+                        * ((col.alt - config.sea_level) / 12.0).clamped(0.0, 1.0),
                     Some((0.2, 128.0, 0.5)),
                 )
             },
@@ -1177,7 +1178,7 @@ pub fn apply_scatter_to(canvas: &mut Canvas, _rng: &mut impl Rng, calendar: Opti
                     return None;
                 }
                 let snow_covered = matches!(block_kind, BlockKind::Snow | BlockKind::Ice);
-                let (density, patch) = f(canvas.chunk(), col);
+                let (density, patch) = f(&canvas.chunks().config, canvas.chunk(), col);
                 let density = patch
                     .map(|(base_density_prop, wavelen, threshold)| {
                         if canvas
@@ -1,4 +1,4 @@
-use crate::{CONFIG, IndexRef, column::ColumnSample, sim::SimChunk, util::close};
+use crate::{IndexRef, column::ColumnSample, config::Config, sim::SimChunk, util::close};
 use common::{
     assets::{AssetExt, Ron},
     calendar::{Calendar, CalendarEvent},
@@ -140,7 +140,7 @@ impl Pack {
     }
 }
 
-pub type DensityFn = fn(&SimChunk, &ColumnSample) -> f32;
+pub type DensityFn = fn(&Config, &SimChunk, &ColumnSample) -> f32;
 
 pub fn spawn_manifest() -> Vec<(&'static str, DensityFn)> {
     const BASE_DENSITY: f32 = 1.0e-5; // Base wildlife density
@@ -150,39 +150,39 @@ pub fn spawn_manifest() -> Vec<(&'static str, DensityFn)> {
     vec![
         // **Tundra**
         // Rock animals
-        ("world.wildlife.spawn.tundra.rock", |c, col| {
-            close(c.temp, CONFIG.snow_temp, 0.15) * BASE_DENSITY * col.rock_density * 1.0
+        ("world.wildlife.spawn.tundra.rock", |config, c, col| {
+            close(c.temp, config.snow_temp, 0.15) * BASE_DENSITY * col.rock_density * 1.0
         }),
         // Core animals
-        ("world.wildlife.spawn.tundra.core", |c, _col| {
-            close(c.temp, CONFIG.snow_temp, 0.15) * BASE_DENSITY * 0.5
+        ("world.wildlife.spawn.tundra.core", |config, c, _col| {
+            close(c.temp, config.snow_temp, 0.15) * BASE_DENSITY * 0.5
         }),
         // Core animals events
         (
             "world.wildlife.spawn.calendar.christmas.tundra.core",
-            |c, _col| close(c.temp, CONFIG.snow_temp, 0.15) * BASE_DENSITY * 0.5,
+            |config, c, _col| close(c.temp, config.snow_temp, 0.15) * BASE_DENSITY * 0.5,
         ),
         (
             "world.wildlife.spawn.calendar.halloween.tundra.core",
-            |c, _col| close(c.temp, CONFIG.snow_temp, 0.15) * BASE_DENSITY * 1.0,
+            |config, c, _col| close(c.temp, config.snow_temp, 0.15) * BASE_DENSITY * 1.0,
         ),
         (
             "world.wildlife.spawn.calendar.april_fools.tundra.core",
-            |c, _col| close(c.temp, CONFIG.snow_temp, 0.15) * BASE_DENSITY * 0.5,
+            |config, c, _col| close(c.temp, config.snow_temp, 0.15) * BASE_DENSITY * 0.5,
         ),
         (
             "world.wildlife.spawn.calendar.easter.tundra.core",
-            |c, _col| close(c.temp, CONFIG.snow_temp, 0.15) * BASE_DENSITY * 0.5,
+            |config, c, _col| close(c.temp, config.snow_temp, 0.15) * BASE_DENSITY * 0.5,
         ),
         // Snowy animals
-        ("world.wildlife.spawn.tundra.snow", |c, col| {
-            close(c.temp, CONFIG.snow_temp, 0.3) * BASE_DENSITY * col.snow_cover as i32 as f32 * 1.0
+        ("world.wildlife.spawn.tundra.snow", |config, c, col| {
+            close(c.temp, config.snow_temp, 0.3) * BASE_DENSITY * col.snow_cover as i32 as f32 * 1.0
         }),
         // Snowy animals event
         (
             "world.wildlife.spawn.calendar.christmas.tundra.snow",
-            |c, col| {
-                close(c.temp, CONFIG.snow_temp, 0.3)
+            |config, c, col| {
+                close(c.temp, config.snow_temp, 0.3)
                     * BASE_DENSITY
                     * col.snow_cover as i32 as f32
                     * 1.0
@@ -190,8 +190,8 @@ pub fn spawn_manifest() -> Vec<(&'static str, DensityFn)> {
         ),
         (
             "world.wildlife.spawn.calendar.halloween.tundra.snow",
-            |c, col| {
-                close(c.temp, CONFIG.snow_temp, 0.3)
+            |config, c, col| {
+                close(c.temp, config.snow_temp, 0.3)
                     * BASE_DENSITY
                     * col.snow_cover as i32 as f32
                     * 1.5
@@ -199,8 +199,8 @@ pub fn spawn_manifest() -> Vec<(&'static str, DensityFn)> {
         ),
         (
             "world.wildlife.spawn.calendar.april_fools.tundra.snow",
-            |c, col| {
-                close(c.temp, CONFIG.snow_temp, 0.3)
+            |config, c, col| {
+                close(c.temp, config.snow_temp, 0.3)
                     * BASE_DENSITY
                     * col.snow_cover as i32 as f32
                     * 1.0
@@ -208,23 +208,23 @@ pub fn spawn_manifest() -> Vec<(&'static str, DensityFn)> {
         ),
         (
             "world.wildlife.spawn.calendar.easter.tundra.snow",
-            |c, col| {
-                close(c.temp, CONFIG.snow_temp, 0.3)
+            |config, c, col| {
+                close(c.temp, config.snow_temp, 0.3)
                     * BASE_DENSITY
                     * col.snow_cover as i32 as f32
                     * 1.0
             },
         ),
         // Forest animals
-        ("world.wildlife.spawn.tundra.forest", |c, col| {
-            close(c.temp, CONFIG.snow_temp, 0.3) * col.tree_density * BASE_DENSITY * 1.4
+        ("world.wildlife.spawn.tundra.forest", |config, c, col| {
+            close(c.temp, config.snow_temp, 0.3) * col.tree_density * BASE_DENSITY * 1.4
         }),
         // River wildlife
-        ("world.wildlife.spawn.tundra.river", |c, col| {
-            close(col.temp, CONFIG.snow_temp, 0.3)
+        ("world.wildlife.spawn.tundra.river", |config, c, col| {
+            close(col.temp, config.snow_temp, 0.3)
                 * if col.water_dist.map(|d| d < 1.0).unwrap_or(false)
-                    && !matches!(col.chunk.get_biome(), BiomeKind::Ocean)
-                    && c.alt > CONFIG.sea_level + 20.0
+                    && !matches!(col.chunk.get_biome(config), BiomeKind::Ocean)
+                    && c.alt > config.sea_level + 20.0
                 {
                     0.001
                 } else {
@@ -234,72 +234,83 @@ pub fn spawn_manifest() -> Vec<(&'static str, DensityFn)> {
         // Forest animals event
         (
             "world.wildlife.spawn.calendar.christmas.tundra.forest",
-            |c, col| close(c.temp, CONFIG.snow_temp, 0.3) * col.tree_density * BASE_DENSITY * 1.4,
+            |config, c, col| {
+                close(c.temp, config.snow_temp, 0.3) * col.tree_density * BASE_DENSITY * 1.4
+            },
         ),
         (
             "world.wildlife.spawn.calendar.halloween.tundra.forest",
-            |c, col| close(c.temp, CONFIG.snow_temp, 0.3) * col.tree_density * BASE_DENSITY * 2.0,
+            |config, c, col| {
+                close(c.temp, config.snow_temp, 0.3) * col.tree_density * BASE_DENSITY * 2.0
+            },
         ),
         (
             "world.wildlife.spawn.calendar.april_fools.tundra.forest",
-            |c, col| close(c.temp, CONFIG.snow_temp, 0.3) * col.tree_density * BASE_DENSITY * 1.4,
+            |config, c, col| {
+                close(c.temp, config.snow_temp, 0.3) * col.tree_density * BASE_DENSITY * 1.4
+            },
         ),
         (
             "world.wildlife.spawn.calendar.easter.tundra.forest",
-            |c, col| close(c.temp, CONFIG.snow_temp, 0.3) * col.tree_density * BASE_DENSITY * 1.4,
+            |config, c, col| {
+                close(c.temp, config.snow_temp, 0.3) * col.tree_density * BASE_DENSITY * 1.4
+            },
         ),
         // **Taiga**
         // Forest core animals
-        ("world.wildlife.spawn.taiga.core_forest", |c, col| {
-            close(c.temp, CONFIG.snow_temp + 0.2, 0.2) * col.tree_density * BASE_DENSITY * 0.4
-        }),
+        (
+            "world.wildlife.spawn.taiga.core_forest",
+            |config, c, col| {
+                close(c.temp, config.snow_temp + 0.2, 0.2) * col.tree_density * BASE_DENSITY * 0.4
+            },
+        ),
         // Forest core animals event
         (
             "world.wildlife.spawn.calendar.christmas.taiga.core_forest",
-            |c, col| {
-                close(c.temp, CONFIG.snow_temp + 0.2, 0.2) * col.tree_density * BASE_DENSITY * 0.4
+            |config, c, col| {
+                close(c.temp, config.snow_temp + 0.2, 0.2) * col.tree_density * BASE_DENSITY * 0.4
             },
         ),
         (
             "world.wildlife.spawn.calendar.halloween.taiga.core",
-            |c, col| {
-                close(c.temp, CONFIG.snow_temp + 0.2, 0.2) * col.tree_density * BASE_DENSITY * 0.8
+            |config, c, col| {
+                close(c.temp, config.snow_temp + 0.2, 0.2) * col.tree_density * BASE_DENSITY * 0.8
             },
         ),
         (
             "world.wildlife.spawn.calendar.april_fools.taiga.core",
-            |c, col| {
-                close(c.temp, CONFIG.snow_temp + 0.2, 0.2) * col.tree_density * BASE_DENSITY * 0.4
+            |config, c, col| {
+                close(c.temp, config.snow_temp + 0.2, 0.2) * col.tree_density * BASE_DENSITY * 0.4
             },
         ),
         (
             "world.wildlife.spawn.calendar.easter.taiga.core",
-            |c, col| {
-                close(c.temp, CONFIG.snow_temp + 0.2, 0.2) * col.tree_density * BASE_DENSITY * 0.4
+            |config, c, col| {
+                close(c.temp, config.snow_temp + 0.2, 0.2) * col.tree_density * BASE_DENSITY * 0.4
             },
         ),
         // Core animals
-        ("world.wildlife.spawn.taiga.core", |c, _col| {
-            close(c.temp, CONFIG.snow_temp + 0.2, 0.2) * BASE_DENSITY * 1.0
+        ("world.wildlife.spawn.taiga.core", |config, c, _col| {
+            close(c.temp, config.snow_temp + 0.2, 0.2) * BASE_DENSITY * 1.0
         }),
         // Forest area animals
-        ("world.wildlife.spawn.taiga.forest", |c, col| {
-            close(c.temp, CONFIG.snow_temp + 0.2, 0.6) * col.tree_density * BASE_DENSITY * 0.9
+        ("world.wildlife.spawn.taiga.forest", |config, c, col| {
+            close(c.temp, config.snow_temp + 0.2, 0.6) * col.tree_density * BASE_DENSITY * 0.9
         }),
         // Area animals
-        ("world.wildlife.spawn.taiga.area", |c, _col| {
-            close(c.temp, CONFIG.snow_temp + 0.2, 0.6) * BASE_DENSITY * 5.0
+        ("world.wildlife.spawn.taiga.area", |config, c, _col| {
+            close(c.temp, config.snow_temp + 0.2, 0.6) * BASE_DENSITY * 5.0
         }),
         // Water animals
-        ("world.wildlife.spawn.taiga.water", |c, col| {
-            close(c.temp, CONFIG.snow_temp, 0.15) * col.tree_density * BASE_DENSITY * 5.0
+        ("world.wildlife.spawn.taiga.water", |config, c, col| {
+            close(c.temp, config.snow_temp, 0.15) * col.tree_density * BASE_DENSITY * 5.0
         }),
         // River wildlife
-        ("world.wildlife.spawn.taiga.river", |c, col| {
-            close(col.temp, CONFIG.snow_temp + 0.2, 0.6)
+        ("world.wildlife.spawn.taiga.river", |config, c, col| {
+            close(col.temp, config.snow_temp + 0.2, 0.6)
                 * if col.water_dist.map(|d| d < 1.0).unwrap_or(false)
-                    && !matches!(col.chunk.get_biome(), BiomeKind::Ocean)
-                    && c.alt > CONFIG.sea_level + 20.0
+                    && !matches!(col.chunk.get_biome(config), BiomeKind::Ocean)
+                    && c.alt > config.sea_level + 20.0
                 {
                     0.001
                 } else {
@@ -308,22 +319,25 @@ pub fn spawn_manifest() -> Vec<(&'static str, DensityFn)> {
         }),
         // **Temperate**
         // Area rare
-        ("world.wildlife.spawn.temperate.rare", |c, _col| {
-            close(c.temp, CONFIG.temperate_temp, 0.8) * BASE_DENSITY * 0.08
+        ("world.wildlife.spawn.temperate.rare", |config, c, _col| {
+            close(c.temp, config.temperate_temp, 0.8) * BASE_DENSITY * 0.08
         }),
         // Plains
-        ("world.wildlife.spawn.temperate.plains", |c, _col| {
-            close(c.temp, CONFIG.temperate_temp, 0.8)
-                * close(c.tree_density, 0.0, 0.1)
-                * BASE_DENSITY
-                * 5.0
-        }),
+        (
+            "world.wildlife.spawn.temperate.plains",
+            |config, c, _col| {
+                close(c.temp, config.temperate_temp, 0.8)
+                    * close(c.tree_density, 0.0, 0.1)
+                    * BASE_DENSITY
+                    * 5.0
+            },
+        ),
         // River wildlife
-        ("world.wildlife.spawn.temperate.river", |c, col| {
-            close(col.temp, CONFIG.temperate_temp, 0.6)
+        ("world.wildlife.spawn.temperate.river", |config, c, col| {
+            close(col.temp, config.temperate_temp, 0.6)
                 * if col.water_dist.map(|d| d < 1.0).unwrap_or(false)
-                    && !matches!(col.chunk.get_biome(), BiomeKind::Ocean)
-                    && c.alt > CONFIG.sea_level + 20.0
+                    && !matches!(col.chunk.get_biome(config), BiomeKind::Ocean)
+                    && c.alt > config.sea_level + 20.0
                 {
                     0.001
                 } else {
@@ -331,49 +345,52 @@ pub fn spawn_manifest() -> Vec<(&'static str, DensityFn)> {
                 }
         }),
         // Forest animals
-        ("world.wildlife.spawn.temperate.wood", |c, col| {
-            close(c.temp, CONFIG.temperate_temp + 0.1, 0.5) * col.tree_density * BASE_DENSITY * 5.0
+        ("world.wildlife.spawn.temperate.wood", |config, c, col| {
+            close(c.temp, config.temperate_temp + 0.1, 0.5) * col.tree_density * BASE_DENSITY * 5.0
         }),
         // Rainforest animals
-        ("world.wildlife.spawn.temperate.rainforest", |c, _col| {
-            close(c.temp, CONFIG.temperate_temp + 0.1, 0.6)
-                * close(c.humidity, CONFIG.forest_hum, 0.6)
-                * BASE_DENSITY
-                * 5.0
-        }),
+        (
+            "world.wildlife.spawn.temperate.rainforest",
+            |config, c, _col| {
+                close(c.temp, config.temperate_temp + 0.1, 0.6)
+                    * close(c.humidity, config.forest_hum, 0.6)
+                    * BASE_DENSITY
+                    * 5.0
+            },
+        ),
         // Temperate Rainforest animals event
         (
             "world.wildlife.spawn.calendar.halloween.temperate.rainforest",
-            |c, _col| {
-                close(c.temp, CONFIG.temperate_temp + 0.1, 0.6)
-                    * close(c.humidity, CONFIG.forest_hum, 0.6)
+            |config, c, _col| {
+                close(c.temp, config.temperate_temp + 0.1, 0.6)
+                    * close(c.humidity, config.forest_hum, 0.6)
                     * BASE_DENSITY
                     * 5.0
             },
         ),
         (
             "world.wildlife.spawn.calendar.april_fools.temperate.rainforest",
-            |c, _col| {
-                close(c.temp, CONFIG.temperate_temp + 0.1, 0.6)
-                    * close(c.humidity, CONFIG.forest_hum, 0.6)
+            |config, c, _col| {
+                close(c.temp, config.temperate_temp + 0.1, 0.6)
+                    * close(c.humidity, config.forest_hum, 0.6)
                     * BASE_DENSITY
                     * 4.0
             },
         ),
         (
             "world.wildlife.spawn.calendar.easter.temperate.rainforest",
-            |c, _col| {
-                close(c.temp, CONFIG.temperate_temp + 0.1, 0.6)
-                    * close(c.humidity, CONFIG.forest_hum, 0.6)
+            |config, c, _col| {
+                close(c.temp, config.temperate_temp + 0.1, 0.6)
+                    * close(c.humidity, config.forest_hum, 0.6)
                     * BASE_DENSITY
                     * 4.0
             },
         ),
         // Ocean animals
-        ("world.wildlife.spawn.temperate.ocean", |_c, col| {
-            close(col.temp, CONFIG.temperate_temp, 1.0) / 10.0
+        ("world.wildlife.spawn.temperate.ocean", |config, _c, col| {
+            close(col.temp, config.temperate_temp, 1.0) / 10.0
                 * if col.water_dist.map(|d| d < 1.0).unwrap_or(false)
-                    && matches!(col.chunk.get_biome(), BiomeKind::Ocean)
+                    && matches!(col.chunk.get_biome(config), BiomeKind::Ocean)
                 {
                     0.001
                 } else {
@@ -381,11 +398,11 @@ pub fn spawn_manifest() -> Vec<(&'static str, DensityFn)> {
                 }
         }),
         // Ocean beach animals
-        ("world.wildlife.spawn.temperate.beach", |c, col| {
-            close(col.temp, CONFIG.temperate_temp, 1.0) / 10.0
+        ("world.wildlife.spawn.temperate.beach", |config, c, col| {
+            close(col.temp, config.temperate_temp, 1.0) / 10.0
                 * if col.water_dist.map(|d| d < 30.0).unwrap_or(false)
-                    && !matches!(col.chunk.get_biome(), BiomeKind::Ocean)
-                    && c.alt < CONFIG.sea_level + 2.0
+                    && !matches!(col.chunk.get_biome(config), BiomeKind::Ocean)
+                    && c.alt < config.sea_level + 2.0
                 {
                     0.001
                 } else {
@@ -394,54 +411,60 @@ pub fn spawn_manifest() -> Vec<(&'static str, DensityFn)> {
         }),
         // **Jungle**
         // Rainforest animals
-        ("world.wildlife.spawn.jungle.rainforest", |c, _col| {
-            close(c.temp, CONFIG.tropical_temp + 0.2, 0.2)
-                * close(c.humidity, CONFIG.jungle_hum, 0.2)
-                * BASE_DENSITY
-                * 2.8
-        }),
+        (
+            "world.wildlife.spawn.jungle.rainforest",
+            |config, c, _col| {
+                close(c.temp, config.tropical_temp + 0.2, 0.2)
+                    * close(c.humidity, config.jungle_hum, 0.2)
+                    * BASE_DENSITY
+                    * 2.8
+            },
+        ),
         // Rainforest area animals
-        ("world.wildlife.spawn.jungle.rainforest_area", |c, _col| {
-            close(c.temp, CONFIG.tropical_temp + 0.2, 0.3)
-                * close(c.humidity, CONFIG.jungle_hum, 0.2)
-                * BASE_DENSITY
-                * 8.0
-        }),
+        (
+            "world.wildlife.spawn.jungle.rainforest_area",
+            |config, c, _col| {
+                close(c.temp, config.tropical_temp + 0.2, 0.3)
+                    * close(c.humidity, config.jungle_hum, 0.2)
+                    * BASE_DENSITY
+                    * 8.0
+            },
+        ),
         // Jungle animals event
         (
             "world.wildlife.spawn.calendar.halloween.jungle.area",
-            |c, _col| {
-                close(c.temp, CONFIG.tropical_temp + 0.2, 0.3)
-                    * close(c.humidity, CONFIG.jungle_hum, 0.2)
+            |config, c, _col| {
+                close(c.temp, config.tropical_temp + 0.2, 0.3)
+                    * close(c.humidity, config.jungle_hum, 0.2)
                     * BASE_DENSITY
                     * 10.0
             },
         ),
         (
             "world.wildlife.spawn.calendar.april_fools.jungle.area",
-            |c, _col| {
-                close(c.temp, CONFIG.tropical_temp + 0.2, 0.3)
-                    * close(c.humidity, CONFIG.jungle_hum, 0.2)
+            |config, c, _col| {
+                close(c.temp, config.tropical_temp + 0.2, 0.3)
+                    * close(c.humidity, config.jungle_hum, 0.2)
                     * BASE_DENSITY
                     * 8.0
             },
         ),
         (
             "world.wildlife.spawn.calendar.easter.jungle.area",
-            |c, _col| {
-                close(c.temp, CONFIG.tropical_temp + 0.2, 0.3)
-                    * close(c.humidity, CONFIG.jungle_hum, 0.2)
+            |config, c, _col| {
+                close(c.temp, config.tropical_temp + 0.2, 0.3)
+                    * close(c.humidity, config.jungle_hum, 0.2)
                     * BASE_DENSITY
                     * 8.0
             },
         ),
         // **Tropical**
         // River animals
-        ("world.wildlife.spawn.tropical.river", |c, col| {
-            close(col.temp, CONFIG.tropical_temp, 0.5)
+        ("world.wildlife.spawn.tropical.river", |config, c, col| {
+            close(col.temp, config.tropical_temp, 0.5)
                 * if col.water_dist.map(|d| d < 1.0).unwrap_or(false)
-                    && !matches!(col.chunk.get_biome(), BiomeKind::Ocean)
-                    && c.alt > CONFIG.sea_level + 20.0
+                    && !matches!(col.chunk.get_biome(config), BiomeKind::Ocean)
+                    && c.alt > config.sea_level + 20.0
                 {
                     0.001
                 } else {
@@ -449,10 +472,10 @@ pub fn spawn_manifest() -> Vec<(&'static str, DensityFn)> {
                 }
         }),
         // Ocean animals
-        ("world.wildlife.spawn.tropical.ocean", |_c, col| {
-            close(col.temp, CONFIG.tropical_temp, 0.1) / 10.0
+        ("world.wildlife.spawn.tropical.ocean", |config, _c, col| {
+            close(col.temp, config.tropical_temp, 0.1) / 10.0
                 * if col.water_dist.map(|d| d < 1.0).unwrap_or(false)
-                    && matches!(col.chunk.get_biome(), BiomeKind::Ocean)
+                    && matches!(col.chunk.get_biome(config), BiomeKind::Ocean)
                 {
                     0.001
                 } else {
@@ -460,11 +483,11 @@ pub fn spawn_manifest() -> Vec<(&'static str, DensityFn)> {
                 }
         }),
         // Ocean beach animals
-        ("world.wildlife.spawn.tropical.beach", |c, col| {
-            close(col.temp, CONFIG.tropical_temp, 1.0) / 10.0
+        ("world.wildlife.spawn.tropical.beach", |config, c, col| {
+            close(col.temp, config.tropical_temp, 1.0) / 10.0
                 * if col.water_dist.map(|d| d < 30.0).unwrap_or(false)
-                    && !matches!(col.chunk.get_biome(), BiomeKind::Ocean)
-                    && c.alt < CONFIG.sea_level + 2.0
+                    && !matches!(col.chunk.get_biome(config), BiomeKind::Ocean)
+                    && c.alt < config.sea_level + 2.0
                 {
                     0.001
                 } else {
@@ -472,65 +495,71 @@ pub fn spawn_manifest() -> Vec<(&'static str, DensityFn)> {
                 }
         }),
         // Arctic ocean animals
-        ("world.wildlife.spawn.arctic.ocean", |_c, col| {
-            close(col.temp, CONFIG.snow_temp, 0.25) / 10.0
-                * if matches!(col.chunk.get_biome(), BiomeKind::Ocean) {
+        ("world.wildlife.spawn.arctic.ocean", |config, _c, col| {
+            close(col.temp, config.snow_temp, 0.25) / 10.0
+                * if matches!(col.chunk.get_biome(config), BiomeKind::Ocean) {
                     0.001
                 } else {
                     0.0
                 }
         }),
         // Rainforest area animals
-        ("world.wildlife.spawn.tropical.rainforest", |c, _col| {
-            close(c.temp, CONFIG.tropical_temp + 0.1, 0.4)
-                * close(c.humidity, CONFIG.jungle_hum, 0.4)
-                * BASE_DENSITY
-                * 2.0
-        }),
+        (
+            "world.wildlife.spawn.tropical.rainforest",
+            |config, c, _col| {
+                close(c.temp, config.tropical_temp + 0.1, 0.4)
+                    * close(c.humidity, config.jungle_hum, 0.4)
+                    * BASE_DENSITY
+                    * 2.0
+            },
+        ),
         // Tropical Rainforest animals event
         (
             "world.wildlife.spawn.calendar.halloween.tropical.rainforest",
-            |c, _col| {
-                close(c.temp, CONFIG.tropical_temp + 0.1, 0.4)
-                    * close(c.humidity, CONFIG.jungle_hum, 0.4)
+            |config, c, _col| {
+                close(c.temp, config.tropical_temp + 0.1, 0.4)
+                    * close(c.humidity, config.jungle_hum, 0.4)
                     * BASE_DENSITY
                     * 3.5
             },
         ),
         (
             "world.wildlife.spawn.calendar.april_fools.tropical.rainforest",
-            |c, _col| {
-                close(c.temp, CONFIG.tropical_temp + 0.1, 0.4)
-                    * close(c.humidity, CONFIG.jungle_hum, 0.4)
+            |config, c, _col| {
+                close(c.temp, config.tropical_temp + 0.1, 0.4)
+                    * close(c.humidity, config.jungle_hum, 0.4)
                     * BASE_DENSITY
                     * 2.0
             },
         ),
         // Rock animals
-        ("world.wildlife.spawn.tropical.rock", |c, col| {
-            close(c.temp, CONFIG.tropical_temp + 0.1, 0.5) * col.rock_density * BASE_DENSITY * 5.0
+        ("world.wildlife.spawn.tropical.rock", |config, c, col| {
+            close(c.temp, config.tropical_temp + 0.1, 0.5) * col.rock_density * BASE_DENSITY * 5.0
         }),
         // **Desert**
         // Area animals
-        ("world.wildlife.spawn.desert.area", |c, _col| {
-            close(c.temp, CONFIG.desert_temp + 0.1, 0.4)
-                * close(c.humidity, CONFIG.desert_hum, 0.4)
+        ("world.wildlife.spawn.desert.area", |config, c, _col| {
+            close(c.temp, config.desert_temp + 0.1, 0.4)
+                * close(c.humidity, config.desert_hum, 0.4)
                 * BASE_DENSITY
                 * 0.8
         }),
         // Wasteland animals
-        ("world.wildlife.spawn.desert.wasteland", |c, _col| {
-            close(c.temp, CONFIG.desert_temp + 0.2, 0.3)
-                * close(c.humidity, CONFIG.desert_hum, 0.5)
-                * BASE_DENSITY
-                * 1.3
-        }),
+        (
+            "world.wildlife.spawn.desert.wasteland",
+            |config, c, _col| {
+                close(c.temp, config.desert_temp + 0.2, 0.3)
+                    * close(c.humidity, config.desert_hum, 0.5)
+                    * BASE_DENSITY
+                    * 1.3
+            },
+        ),
         // River animals
-        ("world.wildlife.spawn.desert.river", |c, col| {
-            close(col.temp, CONFIG.desert_temp + 0.2, 0.3)
+        ("world.wildlife.spawn.desert.river", |config, c, col| {
+            close(col.temp, config.desert_temp + 0.2, 0.3)
                 * if col.water_dist.map(|d| d < 1.0).unwrap_or(false)
-                    && !matches!(col.chunk.get_biome(), BiomeKind::Ocean)
-                    && c.alt > CONFIG.sea_level + 20.0
+                    && !matches!(col.chunk.get_biome(config), BiomeKind::Ocean)
+                    && c.alt > config.sea_level + 20.0
                 {
                     0.001
                 } else {
@@ -538,12 +567,12 @@ pub fn spawn_manifest() -> Vec<(&'static str, DensityFn)> {
                 }
         }),
         // Hot area desert
-        ("world.wildlife.spawn.desert.hot", |c, _col| {
-            close(c.temp, CONFIG.desert_temp + 0.2, 0.3) * BASE_DENSITY * 3.8
+        ("world.wildlife.spawn.desert.hot", |config, c, _col| {
+            close(c.temp, config.desert_temp + 0.2, 0.3) * BASE_DENSITY * 3.8
         }),
         // Rock animals
-        ("world.wildlife.spawn.desert.rock", |c, col| {
-            close(c.temp, CONFIG.desert_temp + 0.2, 0.05) * col.rock_density * BASE_DENSITY * 4.0
+        ("world.wildlife.spawn.desert.rock", |config, c, col| {
+            close(c.temp, config.desert_temp + 0.2, 0.05) * col.rock_density * BASE_DENSITY * 4.0
         }),
     ]
 }
@@ -555,6 +584,7 @@ pub fn apply_wildlife_supplement<'a, R: Rng>(
     mut get_column: impl FnMut(Vec2<i32>) -> Option<&'a ColumnSample<'a>>,
     vol: &(impl RectSizedVol<Vox = Block> + ReadVol + WriteVol),
     index: IndexRef,
+    config: &Config,
     chunk: &SimChunk,
     supplement: &mut ChunkSupplement,
     time: Option<&(TimeOfDay, Calendar)>,
@@ -587,7 +617,8 @@ pub fn apply_wildlife_supplement<'a, R: Rng>(
             let entity_group = scatter
                 .iter()
                 .filter_map(|(entry, get_density)| {
-                    let density = get_density(chunk, col_sample) * wildlife_density_modifier;
+                    let density =
+                        get_density(config, chunk, col_sample) * wildlife_density_modifier;
                     (density > 0.0)
                         .then(|| {
                             entry
@@ -27,7 +27,7 @@ pub mod util;
 // Reexports
 pub use crate::{
     canvas::{Canvas, CanvasInfo},
-    config::{CONFIG, Features},
+    config::{Config, Features},
     land::Land,
     layer::PathLocals,
 };
@@ -276,8 +276,9 @@ impl World {
                                 })
                             {
                                 let weight = 1.0 / (distance * std::f32::consts::TAU + 1.0);
-                                let chunk_difficulty = 20.0
-                                    / (20.0 + chunk.get_biome().difficulty().pow(4) as f32 / 5.0);
+                                let biome = chunk.get_biome(&self.sim().config);
+                                let chunk_difficulty =
+                                    20.0 / (20.0 + biome.difficulty().pow(4) as f32 / 5.0);
                                 // let chunk_difficulty = 1.0 / chunk.get_biome().difficulty() as
                                 // f32;
 
@@ -386,7 +387,7 @@ impl World {
         };
         let meta = TerrainChunkMeta::new(
             sim_chunk.get_location_name(&index.sites, &self.civs.pois, chunk_center_wpos2d),
-            sim_chunk.get_biome(),
+            sim_chunk.get_biome(&self.sim.config),
             sim_chunk.alt,
             sim_chunk.tree_density,
             sim_chunk.river.is_river(),
@@ -556,6 +557,7 @@ impl World {
             sample_get,
             &chunk,
             index,
+            &self.sim.config,
             sim_chunk,
             &mut supplement,
             time.as_ref(),
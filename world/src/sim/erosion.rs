@@ -1,5 +1,5 @@
 use super::{diffusion, downhill, uphill};
-use crate::{config::CONFIG, util::RandomField};
+use crate::{config::Config, util::RandomField};
 use common::{
     terrain::{
         MapSizeLg, NEIGHBOR_DELTA, TerrainChunkSize, neighbors, uniform_idx_as_vec2,
@@ -218,6 +218,7 @@ impl RiverData {
 /// that we draw rivers at all.
 pub fn get_rivers<F: fmt::Debug + Float + Into<f64>, G: Float + Into<f64>>(
     map_size_lg: MapSizeLg,
+    config: &Config,
     continent_scale_hack: f64,
     newh: &[u32],
     water_alt: &[F],
@@ -266,7 +267,7 @@ pub fn get_rivers<F: fmt::Debug + Float + Into<f64>, G: Float + Into<f64>>(
         // TODO: consider having different rainfall rates (and including this
         // information in the computation of drainage).
         let volumetric_flow_rate =
-            chunk_drainage * chunk_area_factor * CONFIG.rainfall_chunk_rate as f64;
+            chunk_drainage * chunk_area_factor * config.rainfall_chunk_rate as f64;
         let downhill_drainage = drainage[downhill_idx].into();
 
         // We know the drainage to the downhill node is just chunk_drainage - 1.0 (the
@@ -443,8 +444,8 @@ pub fn get_rivers<F: fmt::Debug + Float + Into<f64>, G: Float + Into<f64>>(
         let slope_sqrt = slope.sqrt();
         // Now, we compute a quantity that is proportional to the velocity of the chunk,
         // derived from the Manning formula, equal to
-        // volumetric_flow_rate / slope_sqrt * CONFIG.river_roughness.
-        let almost_velocity = volumetric_flow_rate / slope_sqrt * CONFIG.river_roughness as f64;
+        // volumetric_flow_rate / slope_sqrt * config.river_roughness.
+        let almost_velocity = volumetric_flow_rate / slope_sqrt * config.river_roughness as f64;
         // From this, we can figure out the width of the chunk if we know the height.
         // For now, we hardcode the height to 0.5, but it should almost
         // certainly be much more complicated than this.
@@ -470,16 +471,16 @@ pub fn get_rivers<F: fmt::Debug + Float + Into<f64>, G: Float + Into<f64>>(
         //
         // NOTE: Derived from a paper on estimating river width.
         let mut width = 5.0
-            * (CONFIG.river_width_to_depth as f64
-                * (CONFIG.river_width_to_depth as f64 + 2.0).powf(2.0 / 3.0))
+            * (config.river_width_to_depth as f64
+                * (config.river_width_to_depth as f64 + 2.0).powf(2.0 / 3.0))
             .powf(3.0 / 8.0)
             * volumetric_flow_rate.powf(3.0 / 8.0)
             * slope.powf(-3.0 / 16.0)
-            * (CONFIG.river_roughness as f64).powf(3.0 / 8.0);
+            * (config.river_roughness as f64).powf(3.0 / 8.0);
         width = width.max(0.0);
 
         let mut height = if width == 0.0 {
-            CONFIG.river_min_height as f64
+            config.river_min_height as f64
         } else {
             (almost_velocity / width).powf(3.0 / 5.0)
         };
@@ -490,9 +491,9 @@ pub fn get_rivers<F: fmt::Debug + Float + Into<f64>, G: Float + Into<f64>>(
 
         // Now, we can check whether this is "really" a river.
         // Currently, we just check that width and height are at least 0.5 and
-        // CONFIG.river_min_height.
+        // config.river_min_height.
         let river = &rivers[chunk_idx];
-        let is_river = river.is_river() || width >= 0.5 && height >= CONFIG.river_min_height as f64;
+        let is_river = river.is_river() || width >= 0.5 && height >= config.river_min_height as f64;
         let downhill_river = &mut rivers[downhill_idx];
 
         if is_river {
@@ -513,7 +514,7 @@ pub fn get_rivers<F: fmt::Debug + Float + Into<f64>, G: Float + Into<f64>>(
             // problem by making the river deeper when it hits the max width,
             // until it consumes all the available energy in this part of the
             // river.
-            let max_width = TerrainChunkSize::RECT_SIZE.x as f64 * CONFIG.river_max_width as f64;
+            let max_width = TerrainChunkSize::RECT_SIZE.x as f64 * config.river_max_width as f64;
             if width > max_width {
                 width = max_width;
                 height = (almost_velocity / width).powf(3.0 / 5.0);
@@ -521,7 +522,7 @@ pub fn get_rivers<F: fmt::Debug + Float + Into<f64>, G: Float + Into<f64>>(
         }
         // Now we can compute the river's approximate velocity magnitude as well, as
         let velocity_magnitude =
-            1.0 / CONFIG.river_roughness as f64 * height.powf(2.0 / 3.0) * slope_sqrt;
+            1.0 / config.river_roughness as f64 * height.powf(2.0 / 3.0) * slope_sqrt;
 
         // Set up the river's cross-sectional area.
         let cross_section = Vec2::new(width as f32, height as f32);
@@ -549,6 +550,7 @@ pub fn get_rivers<F: fmt::Debug + Float + Into<f64>, G: Float + Into<f64>>(
 /// TODO: See if allocating in advance is worthwhile.
 fn get_max_slope(
     map_size_lg: MapSizeLg,
+    config: &Config,
     h: &[Alt],
     rock_strength_nz: &(impl NoiseFn<f64, 3> + Sync),
     height_scale: impl Fn(usize) -> Alt + Sync,
@@ -590,7 +592,7 @@ fn get_max_slope(
                 1.0 * logit(rock_strength.clamp(1e-7, 1.0f64 - 1e-7))
                     + 1.0
                         * log_odds(
-                            (wposz / CONFIG.mountain_scale as f64)
+                            (wposz / config.mountain_scale as f64)
                                 .abs()
                                 .clamp(dmin, dmax),
                         ),
@@ -699,6 +701,8 @@ type M32 = std::simd::Mask<i32, 1>;
 fn erode(
     // Underlying map dimensions.
     map_size_lg: MapSizeLg,
+    // Climate and terrain parameters of the world
+    config: &Config,
     // Height above sea level of topsoil
     h: &mut [Alt],
     // Height above sea level of bedrock
@@ -845,9 +849,10 @@ fn erode(
         || {
             threadpool.join(
                 || {
-                    let max_slope = get_max_slope(map_size_lg, h, rock_strength_nz, |posi| {
-                        height_scale(n_f(posi))
-                    });
+                    let max_slope =
+                        get_max_slope(map_size_lg, config, h, rock_strength_nz, |posi| {
+                            height_scale(n_f(posi))
+                        });
                     debug!("Got max slopes...");
                     max_slope
                 },
@@ -2523,6 +2528,7 @@ pub fn get_multi_rec<F: fmt::Debug + Float + Sync + Into<Compute>>(
 #[expect(clippy::too_many_arguments)]
 pub fn do_erosion(
     map_size_lg: MapSizeLg,
+    config: &Config,
     _max_uplift: f32,
     n_steps: usize,
     seed: &RandomField,
@@ -2657,6 +2663,7 @@ pub fn do_erosion(
 
         erode(
             map_size_lg,
+            config,
             &mut h,
             &mut b,
             &mut wh,
@@ -0,0 +1,312 @@
+//! Hand-designed maps, whose altitude comes from a grayscale image instead of
+//! noise. Erosion, rivers, civilisations and sites are generated on top of
+//! them like on any other map.
+
+use super::GenOpts;
+use common::terrain::{MapSizeLg, uniform_idx_as_vec2};
+use image::DynamicImage;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{
+    iter,
+    ops::{Add, Mul},
+    path::PathBuf,
+    time::SystemTime,
+};
+use vek::*;
+
+/// Depth below the sea level of chunks in the water mask, in units of
+/// [`Config::mountain_scale`](crate::config::Config::mountain_scale)
+const WATER_DEPTH: f32 = 0.02;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct HeightmapOpts {
+    /// Grayscale image of the altitude, black is the lowest and white the
+    /// highest point. PNG and TIFF images of any bit depth work, north is up
+    /// like on the map in the game.
+    pub path: PathBuf,
+    /// Size of the map the image is scaled to. Lower the erosion quality to
+    /// keep closer to the image.
+    #[serde(default)]
+    pub opts: GenOpts,
+    /// Gray value between 0 and 1 of the sea level, everything darker is below
+    /// it
+    #[serde(default = "default_sea_level")]
+    pub sea_level: f32,
+    /// Altitude of white, where 1 is the height of the highest mountains of
+    /// generated maps
+    #[serde(default = "default_height")]
+    pub height: f32,
+    /// Image where white marks water, which is lowered below the sea level.
+    /// Water that doesn't reach the edge of the map becomes lakes.
+    #[serde(default)]
+    pub water_mask: Option<PathBuf>,
+    /// Color image of the climate, red is the temperature and green the
+    /// humidity. Both are still affected by altitude and rivers.
+    #[serde(default)]
+    pub biome_mask: Option<PathBuf>,
+    /// Name of the world file in `./maps` the generated world is saved to,
+    /// the name of the image by default. Later starts load it instead of
+    /// eroding the image again, unless one of the images changed since.
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+fn default_sea_level() -> f32 { 0.25 }
+
+fn default_height() -> f32 { 1.0 }
+
+/// A heightmap scaled to the size of the map, one value per chunk
+pub(super) struct Heightmap {
+    /// Altitude relative to the sea level, in units of
+    /// [`Config::mountain_scale`](crate::config::Config::mountain_scale)
+    pub alt: Vec<f32>,
+    /// Uniform temperature and humidity, between 0 and 1
+    pub climate: Option<Vec<(f32, f32)>>,
+}
+
+impl HeightmapOpts {
+    pub(super) fn load(&self, map_size_lg: MapSizeLg) -> Result<Heightmap, image::ImageError> {
+        let open = |path: &Option<PathBuf>| path.as_ref().map(image::open).transpose();
+        Ok(self.from_images(
+            map_size_lg,
+            &image::open(&self.path)?,
+            open(&self.water_mask)?.as_ref(),
+            open(&self.biome_mask)?.as_ref(),
+        ))
+    }
+
+    /// Name of the world file the generated world is saved to
+    pub(super) fn map_name(&self) -> Option<String> {
+        self.name.clone().or_else(|| {
+            self.path
+                .file_stem()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+    }
+
+    /// Whether one of the images was modified after `time`, so that a world
+    /// saved at that time is outdated. Missing images keep the saved world.
+    pub(super) fn modified_since(&self, time: SystemTime) -> bool {
+        iter::once(&self.path)
+            .chain(&self.water_mask)
+            .chain(&self.biome_mask)
+            .any(|path| {
+                std::fs::metadata(path)
+                    .and_then(|metadata| metadata.modified())
+                    .is_ok_and(|modified| modified > time)
+            })
+    }
+
+    fn from_images(
+        &self,
+        map_size_lg: MapSizeLg,
+        image: &DynamicImage,
+        water_mask: Option<&DynamicImage>,
+        biome_mask: Option<&DynamicImage>,
+    ) -> Heightmap {
+        let image = image.to_luma32f();
+        // 8 and 16 bit images are between 0 and 1, but float images can hold
+        // anything, e.g. meters
+        let (min, max) = image
+            .as_raw()
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), &x| {
+                (min.min(x), max.max(x))
+            });
+        let range = (max - min).max(f32::EPSILON);
+        let sea_level = self.sea_level.clamp(0.0, 1.0 - f32::EPSILON);
+        let mut alt = resample(map_size_lg, image.dimensions(), |x, y| {
+            let gray = (image.get_pixel(x, y).0[0] - min) / range;
+            (gray - sea_level) / (1.0 - sea_level) * self.height
+        });
+
+        if let Some(water_mask) = water_mask {
+            let water = resample_mask(water_mask, map_size_lg);
+            alt.par_iter_mut()
+                .zip(water)
+                .filter(|(_, water)| water.r > 0.5)
+                .for_each(|(alt, _)| *alt = alt.min(-WATER_DEPTH));
+        }
+
+        let climate = biome_mask.map(|biome_mask| {
+            resample_mask(biome_mask, map_size_lg)
+                .into_iter()
+                .map(|color| (color.r.clamp(0.0, 1.0), color.g.clamp(0.0, 1.0)))
+                .collect()
+        });
+
+        Heightmap { alt, climate }
+    }
+}
+
+fn resample_mask(image: &DynamicImage, map_size_lg: MapSizeLg) -> Vec<Rgb<f32>> {
+    let image = image.to_rgb32f();
+    resample(map_size_lg, image.dimensions(), |x, y| {
+        Rgb::from(image.get_pixel(x, y).0)
+    })
+}
+
+/// Bilinearly samples an image of size `(width, height)` at the center of each
+/// chunk, so it covers the whole map
+fn resample<T>(
+    map_size_lg: MapSizeLg,
+    (width, height): (u32, u32),
+    pixel: impl Fn(u32, u32) -> T + Sync,
+) -> Vec<T>
+where
+    T: Copy + Send + Add<Output = T> + Mul<f32, Output = T>,
+{
+    let chunks = map_size_lg.chunks().map(f32::from);
+    let at = |x: f32, y: f32| {
+        pixel(
+            x.clamp(0.0, (width - 1) as f32) as u32,
+            y.clamp(0.0, (height - 1) as f32) as u32,
+        )
+    };
+    (0..map_size_lg.chunks_len())
+        .into_par_iter()
+        .map(|posi| {
+            let pos = uniform_idx_as_vec2(map_size_lg, posi).map(|e| e as f32);
+            // Rows of images go down, but the y axis of the world goes north
+            let x = (pos.x + 0.5) / chunks.x * width as f32 - 0.5;
+            let y = (1.0 - (pos.y + 0.5) / chunks.y) * height as f32 - 0.5;
+            let (x0, y0) = (x.floor(), y.floor());
+            let (fx, fy) = (x - x0, y - y0);
+            let top = at(x0, y0) * (1.0 - fx) + at(x0 + 1.0, y0) * fx;
+            let bottom = at(x0, y0 + 1.0) * (1.0 - fx) + at(x0 + 1.0, y0 + 1.0) * fx;
+            top * (1.0 - fy) + bottom * fy
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma, Rgb32FImage, RgbImage};
+
+    fn opts(sea_level: f32, height: f32) -> HeightmapOpts {
+        HeightmapOpts {
+            path: PathBuf::from("heightmap.png"),
+            opts: GenOpts::default(),
+            sea_level,
+            height,
+            water_mask: None,
+            biome_mask: None,
+            name: None,
+        }
+    }
+
+    fn map_size_lg(x: u32, y: u32) -> MapSizeLg { MapSizeLg::new(Vec2::new(x, y)).unwrap() }
+
+    fn assert_close(actual: &[f32], expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len());
+        for (actual, expected) in actual.iter().zip(expected) {
+            assert!(
+                (actual - expected).abs() < 1e-4,
+                "{actual:?} != {expected:?}"
+            );
+        }
+    }
+
+    /// North-west, north-east, south-west and south-east
+    fn image(pixels: [u8; 4]) -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_raw(2, 2, pixels.to_vec()).unwrap())
+    }
+
+    #[test]
+    fn sea_level_mapping() {
+        let heightmap =
+            opts(0.2, 2.0).from_images(map_size_lg(1, 1), &image([0, 255, 51, 102]), None, None);
+        // Chunks start in the south-west, the top of the image is north
+        assert_close(&heightmap.alt, &[0.0, 0.5, -0.5, 2.0]);
+        assert!(heightmap.climate.is_none());
+    }
+
+    #[test]
+    fn gray_is_stretched_to_full_range() {
+        let heightmap =
+            opts(0.0, 1.0).from_images(map_size_lg(1, 1), &image([100, 150, 100, 150]), None, None);
+        assert_close(&heightmap.alt, &[0.0, 1.0, 0.0, 1.0]);
+
+        // Float images can hold any values, e.g. meters
+        let meters = DynamicImage::ImageLuma32F(
+            image::ImageBuffer::from_raw(2, 1, vec![-50.0, 1000.0]).unwrap(),
+        );
+        let heightmap = opts(0.0, 1.0).from_images(map_size_lg(1, 0), &meters, None, None);
+        assert_close(&heightmap.alt, &[0.0, 1.0]);
+    }
+
+    #[test]
+    fn scaling() {
+        let gradient = DynamicImage::ImageLuma16(
+            image::ImageBuffer::from_raw(2, 1, vec![0, u16::MAX]).unwrap(),
+        );
+        // Scaled up, chunks interpolate between the pixels around their centers
+        let heightmap = opts(0.0, 1.0).from_images(map_size_lg(2, 1), &gradient, None, None);
+        let row = [0.0, 0.25, 0.75, 1.0];
+        assert_close(&heightmap.alt, &[row, row].concat());
+
+        // Scaled down, chunks sample the center of the pixels they cover
+        let gradient =
+            DynamicImage::ImageLuma8(GrayImage::from_fn(4, 4, |x, _| Luma([x as u8 * 85])));
+        let heightmap = opts(0.0, 1.0).from_images(map_size_lg(1, 1), &gradient, None, None);
+        let row = [1.0 / 6.0, 5.0 / 6.0];
+        assert_close(&heightmap.alt, &[row, row].concat());
+    }
+
+    #[test]
+    fn water_mask() {
+        let water_mask = image([255, 0, 255, 0]);
+        let heightmap = opts(0.2, 1.0).from_images(
+            map_size_lg(1, 1),
+            &image([0, 255, 255, 102]),
+            Some(&water_mask),
+            None,
+        );
+        // Water is lowered below the sea level, but never raised
+        assert_close(&heightmap.alt, &[-WATER_DEPTH, 0.25, -0.25, 1.0]);
+    }
+
+    #[test]
+    fn biome_mask() {
+        let biome_mask = DynamicImage::ImageRgb8(RgbImage::from_fn(2, 2, |x, y| {
+            image::Rgb([
+                if y == 0 { 255 } else { 0 },
+                if x == 0 { 0 } else { 255 },
+                255,
+            ])
+        }));
+        let heightmap = opts(0.25, 1.0).from_images(
+            map_size_lg(1, 1),
+            &image([0, 0, 0, 255]),
+            None,
+            Some(&biome_mask),
+        );
+        assert_eq!(
+            heightmap.climate.as_deref(),
+            Some(&[(0.0, 0.0), (0.0, 1.0), (1.0, 0.0), (1.0, 1.0)][..])
+        );
+
+        // Float masks are clamped
+        let biome_mask =
+            DynamicImage::ImageRgb32F(Rgb32FImage::from_pixel(1, 1, image::Rgb([2.0, -1.0, 0.0])));
+        let heightmap = opts(0.25, 1.0).from_images(
+            map_size_lg(0, 0),
+            &image([0, 0, 0, 255]),
+            None,
+            Some(&biome_mask),
+        );
+        assert_eq!(heightmap.climate, Some(vec![(1.0, 0.0)]));
+    }
+
+    #[test]
+    fn map_name() {
+        assert_eq!(opts(0.25, 1.0).map_name().as_deref(), Some("heightmap"));
+        let opts = HeightmapOpts {
+            name: Some("island".to_owned()),
+            ..opts(0.25, 1.0)
+        };
+        assert_eq!(opts.map_name().as_deref(), Some("island"));
+    }
+}
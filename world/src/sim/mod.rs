@@ -1,19 +1,21 @@
 mod diffusion;
 mod erosion;
+mod heightmap;
 mod location;
 mod map;
 mod util;
 mod way;
 
 // Reexports
-use self::erosion::Compute;
 pub use self::{
     diffusion::diffusion,
+    heightmap::HeightmapOpts,
     location::Location,
     map::{sample_pos, sample_wpos},
     util::get_horizon_map,
     way::{Path, Way},
 };
+use self::{erosion::Compute, heightmap::Heightmap};
 pub(crate) use self::{
     erosion::{
         Alt, RiverData, RiverKind, do_erosion, fill_sinks, get_lakes, get_multi_drainage,
@@ -26,11 +28,12 @@ pub(crate) use self::{
 };
 
 use crate::{
-    CONFIG, IndexRef,
+    IndexRef,
     all::{Environment, ForestKind, TreeAttr},
     block::BlockGen,
     civ::{Place, PointOfInterest},
     column::ColumnGen,
+    config::Config,
     site::Site,
     util::{
         CARDINALS, DHashSet, FastNoise, FastNoise2d, LOCALITY, NEIGHBORS, RandomField, Sampler,
@@ -150,6 +153,9 @@ pub struct GenOpts {
     pub scale: f64,
     pub map_kind: MapKind,
     pub erosion_quality: f32,
+    /// RON file with the climate and terrain parameters of the world, see
+    /// [`Config`]. Loaded maps use the parameters they were saved with.
+    pub config: Option<PathBuf>,
 }
 
 impl Default for GenOpts {
@@ -160,6 +166,22 @@ impl Default for GenOpts {
             scale: 2.0,
             map_kind: MapKind::Square,
             erosion_quality: 1.0,
+            config: None,
+        }
+    }
+}
+
+impl GenOpts {
+    fn load_config(&self) -> Config {
+        let Some(path) = &self.config else {
+            return Config::DEFAULT;
+        };
+        match Config::load(path) {
+            Ok(config) => config,
+            Err(e) => {
+                warn!(?e, ?path, "Couldn't load world config. Using default...");
+                Config::DEFAULT
+            },
         }
     }
 }
@@ -195,6 +217,10 @@ pub enum FileOpts {
     /// NOTE: Could stand to merge this with `Load` and construct an enum that
     /// can handle either a PathBuf or an asset specifier, at some point.
     LoadAsset(String),
+    /// If set, generate the world map from a heightmap image instead of noise
+    /// (generates it from noise if the image can't be loaded) and save the
+    /// world file, which is loaded until the image changes.
+    Heightmap(HeightmapOpts),
 }
 
 impl Default for FileOpts {
@@ -231,9 +257,10 @@ impl FileOpts {
 
     fn gen_opts(&self) -> Option<GenOpts> {
         match self {
-            Self::Generate(opts) | Self::Save(_, opts) | Self::LoadOrGenerate { opts, .. } => {
-                Some(opts.clone())
-            },
+            Self::Generate(opts)
+            | Self::Save(_, opts)
+            | Self::LoadOrGenerate { opts, .. }
+            | Self::Heightmap(HeightmapOpts { opts, .. }) => Some(opts.clone()),
             _ => None,
         }
     }
@@ -241,16 +268,17 @@ impl FileOpts {
     // TODO: this should return Option so that caller can choose fallback
     fn map_size(&self) -> MapSizeLg {
         match self {
-            Self::Generate(opts) | Self::Save(_, opts) | Self::LoadOrGenerate { opts, .. } => {
-                MapSizeLg::new(Vec2 {
-                    x: opts.x_lg,
-                    y: opts.y_lg,
-                })
-                .unwrap_or_else(|e| {
-                    warn!("World size does not satisfy invariants: {:?}", e);
-                    DEFAULT_WORLD_CHUNKS_LG
-                })
-            },
+            Self::Generate(opts)
+            | Self::Save(_, opts)
+            | Self::LoadOrGenerate { opts, .. }
+            | Self::Heightmap(HeightmapOpts { opts, .. }) => MapSizeLg::new(Vec2 {
+                x: opts.x_lg,
+                y: opts.y_lg,
+            })
+            .unwrap_or_else(|e| {
+                warn!("World size does not satisfy invariants: {:?}", e);
+                DEFAULT_WORLD_CHUNKS_LG
+            }),
             _ => DEFAULT_WORLD_CHUNKS_LG,
         }
     }
@@ -365,13 +393,16 @@ impl FileOpts {
                     x_lg, y_lg, scale, ..
                 } = opts;
                 let map = match map {
-                    WorldFile::Veloren0_7_0(map) => map,
                     WorldFile::Veloren0_5_0(_) => {
                         panic!("World file v0.5.0 isn't supported with LoadOrGenerate.")
                     },
+                    map => map.into_modern(),
                 };
 
-                if map.continent_scale_hack != *scale || map.map_size_lg != Vec2::new(*x_lg, *y_lg)
+                if let Ok(map) = &map
+                    && (map.continent_scale_hack != *scale
+                        || map.map_size_lg != Vec2::new(*x_lg, *y_lg)
+                        || map.config != opts.load_config())
                 {
                     if *overwrite {
                         warn!(
@@ -390,7 +421,50 @@ impl FileOpts {
                     return None;
                 }
 
-                map.into_modern()
+                map
+            },
+            Self::Heightmap(heightmap) => {
+                let path = self.map_path()?;
+
+                // The map is generated on the first start
+                let file = File::open(&path).ok()?;
+                if let Ok(modified) = file.metadata().and_then(|metadata| metadata.modified())
+                    && heightmap.modified_since(modified)
+                {
+                    info!(
+                        ?path,
+                        "Heightmap changed since the map was saved. Generating..."
+                    );
+                    return None;
+                }
+
+                let mut reader = BufReader::new(file);
+                let map: WorldFile = match decode_from_std_read(&mut reader, legacy()) {
+                    Ok(map) => map,
+                    Err(e) => {
+                        warn!(?e, ?path, "Couldn't parse map. Generating...");
+                        return None;
+                    },
+                };
+
+                let GenOpts {
+                    x_lg, y_lg, scale, ..
+                } = &heightmap.opts;
+                let map = map.into_modern();
+                if let Ok(map) = &map
+                    && (map.continent_scale_hack != *scale
+                        || map.map_size_lg != Vec2::new(*x_lg, *y_lg)
+                        || map.config != heightmap.opts.load_config())
+                {
+                    warn!(
+                        "{}\n{}",
+                        "Specified options don't correspond to these in loaded map.",
+                        "Map will be regenerated and overwritten."
+                    );
+                    return None;
+                }
+
+                map
             },
             Self::Generate { .. } | Self::Save { .. } => return None,
         };
@@ -408,15 +482,32 @@ impl FileOpts {
         }
     }
 
+    fn load_heightmap(&self, map_size_lg: MapSizeLg) -> Option<Heightmap> {
+        let Self::Heightmap(opts) = self else {
+            return None;
+        };
+        match opts.load(map_size_lg) {
+            Ok(heightmap) => Some(heightmap),
+            Err(e) => {
+                warn!(?e, path = ?opts.path, "Couldn't load heightmap. Generating...");
+                None
+            },
+        }
+    }
+
     fn map_path(&self) -> Option<PathBuf> {
+        const MAP_DIR: &str = "./maps";
         // TODO: Work out a nice bincode file extension.
         match self {
             Self::Save(path, _) => Some(PathBuf::from(&path)),
             Self::LoadOrGenerate { name, .. } => {
-                const MAP_DIR: &str = "./maps";
                 let file_name = format!("{}.bin", name);
                 Some(std::path::Path::new(MAP_DIR).join(file_name))
             },
+            Self::Heightmap(heightmap) => {
+                let file_name = format!("{}.bin", heightmap.map_name()?);
+                Some(std::path::Path::new(MAP_DIR).join(file_name))
+            },
             _ => None,
         }
     }
@@ -509,6 +600,25 @@ pub struct WorldMap_0_7_0 {
     pub basement: Box<[Alt]>,
 }
 
+/// Version of the world map intended for use in Veloren 0.18.0.
+#[derive(Serialize, Deserialize)]
+#[repr(C)]
+pub struct WorldMap_0_18_0 {
+    /// Saved map size.
+    pub map_size_lg: Vec2<u32>,
+    /// Saved continent_scale hack, to try to better approximate the correct
+    /// seed according to varying map size.
+    ///
+    /// TODO: Remove when generating new maps becomes more principled.
+    pub continent_scale_hack: f64,
+    /// Saved climate and terrain parameters the map was eroded with.
+    pub config: Config,
+    /// Saved altitude height map.
+    pub alt: Box<[Alt]>,
+    /// Saved basement height map.
+    pub basement: Box<[Alt]>,
+}
+
 /// Errors when converting a map to the most recent type (currently,
 /// shared by the various map types, but at some point we might switch to
 /// version-specific errors if it feels worthwhile).
@@ -548,6 +658,7 @@ pub enum WorldFileError {
 pub enum WorldFile {
     Veloren0_5_0(WorldMap_0_5_0) = 0,
     Veloren0_7_0(WorldMap_0_7_0) = 1,
+    Veloren0_18_0(WorldMap_0_18_0) = 2,
 }
 
 impl FileAsset for WorldFile {
@@ -558,7 +669,7 @@ impl FileAsset for WorldFile {
 
 /// Data for the most recent map type.  Update this when you add a new map
 /// version.
-pub type ModernMap = WorldMap_0_7_0;
+pub type ModernMap = WorldMap_0_18_0;
 
 /// The default world map.
 ///
@@ -636,6 +747,23 @@ impl WorldMap_0_5_0 {
 }
 
 impl WorldMap_0_7_0 {
+    #[inline]
+    pub fn into_modern(self) -> Result<ModernMap, WorldFileError> {
+        // Maps from before the config could be changed were all generated with the
+        // default one.
+        let map = WorldMap_0_18_0 {
+            map_size_lg: self.map_size_lg,
+            continent_scale_hack: self.continent_scale_hack,
+            config: Config::DEFAULT,
+            alt: self.alt,
+            basement: self.basement,
+        };
+
+        map.into_modern()
+    }
+}
+
+impl WorldMap_0_18_0 {
     #[inline]
     pub fn into_modern(self) -> Result<ModernMap, WorldFileError> {
         if self.alt.len() != self.basement.len()
@@ -654,7 +782,7 @@ impl WorldFile {
     /// for serialization. Whenever a new map is updated, just change the
     /// variant we construct here to make sure we're using the latest map
     /// version.
-    pub fn new(map: ModernMap) -> Self { WorldFile::Veloren0_7_0(map) }
+    pub fn new(map: ModernMap) -> Self { WorldFile::Veloren0_18_0(map) }
 
     #[inline]
     /// Turns a WorldFile into the latest version.  Whenever a new map version
@@ -663,6 +791,7 @@ impl WorldFile {
         match self {
             WorldFile::Veloren0_5_0(map) => map.into_modern(),
             WorldFile::Veloren0_7_0(map) => map.into_modern(),
+            WorldFile::Veloren0_18_0(map) => map.into_modern(),
         }
     }
 }
@@ -691,6 +820,8 @@ pub struct WorldSim {
     pub rng: ChaChaRng,
 
     pub(crate) calendar: Option<Calendar>,
+    /// Climate and terrain parameters the world was generated with
+    pub config: Config,
 }
 
 impl WorldSim {
@@ -753,6 +884,7 @@ impl WorldSim {
             gen_ctx,
             rng: rand_chacha::ChaCha20Rng::from_seed([0; 32]),
             calendar: None,
+            config: Config::DEFAULT,
         }
     }
 
@@ -768,9 +900,14 @@ impl WorldSim {
 
         // Parse out the contents of various map formats into the values we need.
         let (parsed_world_file, map_size_lg, gen_opts) = world_file.load_content();
-        // Currently only used with LoadOrGenerate to know if we need to
-        // overwrite world file
+        // Currently only used with LoadOrGenerate and Heightmap to know if we
+        // need to overwrite world file
         let fresh = parsed_world_file.is_none();
+        let heightmap = world_file.load_heightmap(map_size_lg);
+        // Loaded maps were eroded with the config they were saved with
+        let config = parsed_world_file
+            .as_ref()
+            .map_or_else(|| gen_opts.load_config(), |map| map.config);
 
         let mut rng = ChaChaRng::from_seed(seed_expan::rng_state(seed));
         let continent_scale = gen_opts.scale
@@ -910,10 +1047,10 @@ impl WorldSim {
                 uniform_noise(map_size_lg, |_, wposf| {
                     match gen_opts.map_kind {
                         MapKind::Square => {
-                            // "Base" of the chunk, to be multiplied by CONFIG.mountain_scale
+                            // "Base" of the chunk, to be multiplied by config.mountain_scale
                             // (multiplied value is from -0.35 *
-                            // (CONFIG.mountain_scale * 1.05) to
-                            // 0.35 * (CONFIG.mountain_scale * 0.95), but value here is from -0.3675
+                            // (config.mountain_scale * 1.05) to
+                            // 0.35 * (config.mountain_scale * 0.95), but value here is from -0.3675
                             // to 0.3325).
                             Some(
                                 (gen_ctx
@@ -1009,13 +1146,18 @@ impl WorldSim {
         );
 
         // We ignore sea level because we actually want to be relative to sea level here
-        // and want things in CONFIG.mountain_scale units, but otherwise this is
+        // and want things in config.mountain_scale units, but otherwise this is
         // a correct altitude calculation.  Note that this is using the
         // "unadjusted" temperature.
         //
         // No NaNs in these uniform vectors, since the original noise value always
         // returns Some.
         let (alt_old, _) = uniform_noise(map_size_lg, |posi, wposf| {
+            // Hand-designed maps replace the noise, but are eroded just the same
+            if let Some(heightmap) = &heightmap {
+                return Some(heightmap.alt[posi]);
+            }
+
             // This is the extension upwards from the base added to some extra noise from -1
             // to 1.
             //
@@ -1036,7 +1178,7 @@ impl WorldSim {
             // get [-0.445, 0.565].
             let alt_main = {
                 // Extension upwards from the base.  A positive number from 0 to 1 curved to be
-                // maximal at 0.  Also to be multiplied by CONFIG.mountain_scale.
+                // maximal at 0.  Also to be multiplied by config.mountain_scale.
                 let alt_main = (gen_ctx
                     .alt_nz
                     .get((wposf.div(2_000.0)).into_array())
@@ -1066,7 +1208,7 @@ impl WorldSim {
 
             // Now we can compute the final altitude using chaos.
             // We multiply by chaos clamped to [0.1, 1.32] to get a value between [0.03,
-            // 2.232] for alt_pre, then multiply by CONFIG.mountain_scale and
+            // 2.232] for alt_pre, then multiply by config.mountain_scale and
             // add to the base and sea level to get an adjusted value, then
             // multiply the whole thing by map_edge_factor (TODO: compute final
             // bounds).
@@ -1079,11 +1221,11 @@ impl WorldSim {
                 ((alt_base[posi].1 + alt_main.mul((chaos[posi].1 as f64).powf(1.2)))
                     .mul(map_edge_factor(map_size_lg, posi) as f64)
                     .add(
-                        (CONFIG.sea_level as f64)
-                            .div(CONFIG.mountain_scale as f64)
+                        (config.sea_level as f64)
+                            .div(config.mountain_scale as f64)
                             .mul(map_edge_factor(map_size_lg, posi) as f64),
                     )
-                    .sub((CONFIG.sea_level as f64).div(CONFIG.mountain_scale as f64)))
+                    .sub((config.sea_level as f64).div(config.mountain_scale as f64)))
                     as f32,
             )
         });
@@ -1106,7 +1248,7 @@ impl WorldSim {
             1.0
         };
         let old_height = |posi: usize| {
-            alt_old[posi].1 * CONFIG.mountain_scale * height_scale(n_func(posi)) as f32
+            alt_old[posi].1 * config.mountain_scale * height_scale(n_func(posi)) as f32
         };
 
         // NOTE: Needed if you wish to use the distance to the point defining the Worley
@@ -1230,7 +1372,7 @@ impl WorldSim {
             let wposf3 = Vec3::new(
                 wposf.x,
                 wposf.y,
-                uheight * CONFIG.mountain_scale as f64 * rock_strength_div_factor,
+                uheight * config.mountain_scale as f64 * rock_strength_div_factor,
             );
             let rock_strength = gen_ctx
                 .rock_strength_nz
@@ -1283,7 +1425,7 @@ impl WorldSim {
             let wposf3 = Vec3::new(
                 wposf.x,
                 wposf.y,
-                uheight * CONFIG.mountain_scale as f64 * rock_strength_div_factor,
+                uheight * config.mountain_scale as f64 * rock_strength_div_factor,
             );
             let rock_strength = gen_ctx
                 .rock_strength_nz
@@ -1332,7 +1474,7 @@ impl WorldSim {
             if is_ocean_fn(posi) {
                 old_height(posi)
             } else {
-                (old_height(posi) as f64 / CONFIG.mountain_scale as f64) as f32 - 0.5
+                (old_height(posi) as f64 / config.mountain_scale as f64) as f32 - 0.5
             }
         };
 
@@ -1369,6 +1511,7 @@ impl WorldSim {
         } else {
             let (alt, basement) = do_erosion(
                 map_size_lg,
+                &config,
                 max_erosion_per_delta_t as f32,
                 n_steps,
                 river_seed,
@@ -1398,6 +1541,7 @@ impl WorldSim {
             // Quick "small scale" erosion cycle in order to lower extreme angles.
             do_erosion(
                 map_size_lg,
+                &config,
                 1.0f32,
                 n_small_steps,
                 river_seed,
@@ -1426,6 +1570,7 @@ impl WorldSim {
         let map = WorldFile::new(ModernMap {
             continent_scale_hack: gen_opts.scale,
             map_size_lg: map_size_lg.vec(),
+            config,
             alt,
             basement,
         });
@@ -1438,6 +1583,7 @@ impl WorldSim {
         let ModernMap {
             continent_scale_hack: _,
             map_size_lg: _,
+            config: _,
             alt,
             basement,
         } = map.into_modern().unwrap();
@@ -1448,6 +1594,7 @@ impl WorldSim {
         } else {
             do_erosion(
                 map_size_lg,
+                &config,
                 1.0f32,
                 n_post_load_steps,
                 river_seed,
@@ -1555,6 +1702,7 @@ impl WorldSim {
 
         let rivers = get_rivers(
             map_size_lg,
+            &config,
             gen_opts.scale,
             &water_alt_pos,
             &water_alt,
@@ -1677,6 +1825,21 @@ impl WorldSim {
                 },
             );
 
+        // The climate of hand-designed maps replaces the base temperature and
+        // humidity
+        let (mut temp_base, mut humid_base) = (temp_base, humid_base);
+        if let Some(climate) = heightmap.and_then(|heightmap| heightmap.climate) {
+            for (posi, (temp, humid)) in climate.into_iter().enumerate() {
+                // NaN wherever pure_water() returned true, these aren't used
+                if !temp_base[posi].1.is_nan() {
+                    temp_base[posi].0 = temp;
+                }
+                if !humid_base[posi].1.is_nan() {
+                    humid_base[posi].0 = humid;
+                }
+            }
+        }
+
         let gen_cdf = GenCdf {
             humid_base,
             temp_base,
@@ -1693,7 +1856,7 @@ impl WorldSim {
 
         let chunks = (0..map_size_lg.chunks_len())
             .into_par_iter()
-            .map(|i| SimChunk::generate(map_size_lg, i, &gen_ctx, &gen_cdf))
+            .map(|i| SimChunk::generate(map_size_lg, i, &gen_ctx, &gen_cdf, &config))
             .collect::<Vec<_>>();
 
         let mut this = Self {
@@ -1705,6 +1868,7 @@ impl WorldSim {
             gen_ctx,
             rng,
             calendar,
+            config,
         };
 
         this.generate_cliffs();
@@ -1733,7 +1897,7 @@ impl WorldSim {
     }
 
     pub fn generate_oob_chunk(&self) -> TerrainChunk {
-        TerrainChunk::water(CONFIG.sea_level as i32)
+        TerrainChunk::water(self.config.sea_level as i32)
     }
 
     pub fn approx_chunk_terrain_normal(&self, chunk_pos: Vec2<i32>) -> Option<Vec3<f32>> {
@@ -1762,7 +1926,10 @@ impl WorldSim {
         prof_span!("WorldSim::get_map");
         let mut map_config = MapConfig::orthographic(
             self.map_size_lg(),
-            core::ops::RangeInclusive::new(CONFIG.sea_level, CONFIG.sea_level + self.max_height),
+            core::ops::RangeInclusive::new(
+                self.config.sea_level,
+                self.config.sea_level + self.max_height,
+            ),
         );
         // Build a horizon map.
         let scale_angle = |angle: Alt| {
@@ -1791,7 +1958,7 @@ impl WorldSim {
                                 calendar,
                             )
                         )?;
-                        // sample.water_level = CONFIG.sea_level.max(sample.water_level);
+                        // sample.water_level = config.sea_level.max(sample.water_level);
 
                         Some(sample)
                     },
@@ -1811,15 +1978,15 @@ impl WorldSim {
                 min: Vec2::zero(),
                 max: self.map_size_lg().chunks().map(|e| e as i32),
             },
-            CONFIG.sea_level,
-            CONFIG.sea_level + self.max_height,
+            self.config.sea_level,
+            self.config.sea_level + self.max_height,
             |posi| {
                 /* let chunk = &self.chunks[posi];
                 chunk.alt.max(chunk.water_alt) as Alt */
                 let sample = samples_data[posi].as_ref();
                 sample
                     .map(|s| s.alt.max(s.water_level))
-                    .unwrap_or(CONFIG.sea_level)
+                    .unwrap_or(self.config.sea_level)
             },
             |a| scale_angle(a.into()),
             |h| scale_height(h.into()),
@@ -2095,7 +2262,7 @@ impl WorldSim {
         self.get_interpolated(wpos, |chunk| chunk.alt)
             .zip(self.get_interpolated(wpos, |chunk| chunk.water_alt))
             .map(|(alt, water_alt)| alt.max(water_alt))
-            .unwrap_or(CONFIG.sea_level)
+            .unwrap_or(self.config.sea_level)
     }
 
     pub fn get_alt_approx(&self, wpos: Vec2<i32>) -> Option<f32> {
@@ -2422,7 +2589,7 @@ impl WorldSim {
         } else {
             return Lottery::from(vec![(1.0, None)]);
         };
-        let env = chunk.get_environment();
+        let env = chunk.get_environment(&self.config);
         Lottery::from(
             ForestKind::iter()
                 .enumerate()
@@ -2528,7 +2695,13 @@ pub struct NearestWaysData<M, F: FnOnce() -> Vec2<f32>> {
 }
 
 impl SimChunk {
-    fn generate(map_size_lg: MapSizeLg, posi: usize, gen_ctx: &GenCtx, gen_cdf: &GenCdf) -> Self {
+    fn generate(
+        map_size_lg: MapSizeLg,
+        posi: usize,
+        gen_ctx: &GenCtx,
+        gen_cdf: &GenCdf,
+        config: &Config,
+    ) -> Self {
         let pos = uniform_idx_as_vec2(map_size_lg, posi);
         let wposf = (pos * TerrainChunkSize::RECT_SIZE.map(|e| e as i32)).map(|e| e as f64);
 
@@ -2579,14 +2752,14 @@ impl SimChunk {
         // Moisture evaporates more in hot places
         let humidity = humidity
             * (1.0
-                - (temp - CONFIG.tropical_temp)
+                - (temp - config.tropical_temp)
                     .max(0.0)
-                    .div(1.0 - CONFIG.tropical_temp))
+                    .div(1.0 - config.tropical_temp))
             .max(0.0);
 
-        let mut alt = CONFIG.sea_level.add(alt_pre);
-        let basement = CONFIG.sea_level.add(basement_pre);
-        let water_alt = CONFIG.sea_level.add(water_alt_pre);
+        let mut alt = config.sea_level.add(alt_pre);
+        let basement = config.sea_level.add(basement_pre);
+        let water_alt = config.sea_level.add(water_alt_pre);
         let (downhill, _gradient) = if downhill_pre == -2 {
             (None, 0.0)
         } else if downhill_pre < 0 {
@@ -2619,7 +2792,7 @@ impl SimChunk {
         let river_slope = river.velocity.z / river_xy;
         match river.river_kind {
             Some(RiverKind::River { cross_section }) => {
-                if cross_section.x >= 0.5 && cross_section.y >= CONFIG.river_min_height {
+                if cross_section.x >= 0.5 && cross_section.y >= config.river_min_height {
                     /* println!(
                         "Big area! Pos area: {:?}, River data: {:?}, slope: {:?}",
                         wposf, river, river_slope
@@ -2706,7 +2879,7 @@ impl SimChunk {
                 const SOIL_SCALE: f32 = 16.0;
                 let soil = soil_nz * SOIL_SCALE * tree_density.sqrt() * humidity.sqrt();
 
-                let warp_factor = ((alt - CONFIG.sea_level) / 16.0).clamped(0.0, 1.0);
+                let warp_factor = ((alt - config.sea_level) / 16.0).clamped(0.0, 1.0);
 
                 let warp = (dune + soil) * warp_factor;
 
@@ -2773,18 +2946,18 @@ impl SimChunk {
 
     pub fn get_base_z(&self) -> f32 { self.alt - self.chaos * 50.0 - 16.0 }
 
-    pub fn get_biome(&self) -> BiomeKind {
+    pub fn get_biome(&self, config: &Config) -> BiomeKind {
         let savannah_hum_temp = [0.05..0.55, 0.3..1.6];
         let taiga_hum_temp = [0.2..1.4, -0.7..-0.3];
         if self.river.is_ocean() {
             BiomeKind::Ocean
         } else if self.river.is_lake() {
             BiomeKind::Lake
-        } else if self.temp < CONFIG.snow_temp {
+        } else if self.temp < config.snow_temp {
             BiomeKind::Snowland
         } else if self.alt > 500.0 && self.chaos > 0.3 && self.tree_density < 0.6 {
             BiomeKind::Mountain
-        } else if self.temp > CONFIG.desert_temp && self.humidity < CONFIG.desert_hum {
+        } else if self.temp > config.desert_temp && self.humidity < config.desert_hum {
             BiomeKind::Desert
         } else if self.tree_density > 0.65 && self.humidity > 0.65 && self.temp > 0.45 {
             BiomeKind::Jungle
@@ -2808,13 +2981,13 @@ impl SimChunk {
 
     pub fn near_cliffs(&self) -> bool { self.cliff_height > 0.0 }
 
-    pub fn get_environment(&self) -> Environment {
+    pub fn get_environment(&self, config: &Config) -> Environment {
         Environment {
             humid: self.humidity,
             temp: self.temp,
             near_water: if self.river.is_lake()
                 || self.river.near_river()
-                || self.alt < CONFIG.sea_level + 6.0
+                || self.alt < config.sea_level + 6.0
             // Close to sea in altitude
             {
                 1.0
@@ -2841,3 +3014,33 @@ impl SimChunk {
             .or_else(|| self.poi.map(|poi| civs_pois[poi].name.clone()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_map_keeps_its_config() {
+        let config = Config {
+            sea_level: 80.0,
+            mountain_scale: 1024.0,
+            ..Config::DEFAULT
+        };
+        let path = std::env::temp_dir()
+            .join(format!("veloren-world-maps-{}", std::process::id()))
+            .join("custom_config.bin");
+        FileOpts::Save(path.clone(), GenOpts::default()).save(&WorldFile::new(ModernMap {
+            map_size_lg: Vec2::new(1, 1),
+            continent_scale_hack: 2.0,
+            config,
+            alt: vec![1.0, 2.0, 3.0, 4.0].into_boxed_slice(),
+            basement: vec![0.0; 4].into_boxed_slice(),
+        }));
+
+        // Loaded maps use the config they were saved with, not the default one
+        let map = FileOpts::Load(path).try_load_map().unwrap();
+        assert_eq!(map.config, config);
+        assert_eq!(map.map_size_lg, Vec2::new(1, 1));
+        assert_eq!(&*map.alt, &[1.0, 2.0, 3.0, 4.0]);
+    }
+}
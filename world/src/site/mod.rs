@@ -17,7 +17,6 @@ pub use self::{
 };
 use crate::{
     Canvas, IndexRef, Land,
-    config::CONFIG,
     sim::Path,
     util::{CARDINALS, DHashSet, Grid, SQUARE_4, SQUARE_9, attempt},
 };
@@ -3274,7 +3273,7 @@ fn wpos_is_hazard(land: &Land, wpos: Vec2<i32>) -> Option<HazardKind> {
 fn temp_at_wpos(land: &Land, wpos: Vec2<i32>) -> f32 {
     land.get_chunk_wpos(wpos)
         .map(|c| c.temp)
-        .unwrap_or(CONFIG.temperate_temp)
+        .unwrap_or(land.config().temperate_temp)
 }
 
 pub fn aabr_tiles(aabr: Aabr<i32>) -> impl Iterator<Item = Vec2<i32>> {
@@ -979,7 +979,7 @@ impl Bridge {
             kind: bridge,
             biome: land
                 .get_chunk_wpos(center.xy())
-                .map_or(BiomeKind::Void, |chunk| chunk.get_biome()),
+                .map_or(BiomeKind::Void, |chunk| chunk.get_biome(land.config())),
             surface_color,
         }
     }
@@ -63,9 +63,9 @@ impl Structure for TrollCave {
             .fill(Fill::Prefab(Box::new(model), model_pos, rng_val));
         let temp = self.temp;
         // npcs
-        let troll = if temp >= CONFIG.tropical_temp {
+        let troll = if temp >= land.config().tropical_temp {
             "common.entity.wild.aggressive.swamp_troll"
-        } else if temp <= (CONFIG.snow_temp) {
+        } else if temp <= land.config().snow_temp {
             "common.entity.wild.aggressive.mountain_troll"
         } else {
             "common.entity.wild.aggressive.cave_troll"